@@ -0,0 +1,899 @@
+use raylib::prelude::*;
+use crate::assets::{AssetEvent, AssetManager};
+use crate::asteroids::AsteroidBelt;
+use crate::audio::{AudioState, SoundSystem};
+use crate::autopilot::Autopilot;
+use crate::benchmark::{Benchmark, BENCH_FRAME_TIME};
+use crate::camera::{Camera, CameraMode};
+use crate::camera_path::CameraPath;
+use crate::clock::{FixedTimestep, SimulationClock};
+use crate::color::DEFAULT_GAMMA;
+use crate::console::{self, Command, Console, SpawnKind};
+use crate::controls_screen::ControlsScreen;
+use crate::corona::SunCorona;
+use crate::daylight::DaylightView;
+use crate::debug_draw::{bounding_radius, DebugDraw};
+use crate::docking::Docking;
+use crate::eclipses::EclipseForecast;
+use crate::field_of_view::FieldOfView;
+use crate::flight_model::{FlightConfig, FlightModel};
+use crate::frame_graph::FrameGraph;
+#[cfg(feature = "gpu")]
+use crate::gpu_shading::GpuShading;
+use crate::hangar::{Hangar, HangarConfig};
+use crate::input_map::{Action, InputMap};
+use crate::input_state::InputState;
+use crate::labels::Labels;
+use crate::lensing::GravitationalLens;
+use crate::lens_flare::LensFlare;
+use crate::light::Light;
+use crate::map_view::MapView;
+use crate::minimap::Minimap;
+use crate::nebula::Nebula;
+use crate::obj::{LoadOptions, Obj};
+use crate::parking_orbit::ParkingOrbit;
+use crate::particles::ParticleEmitter;
+use crate::photo_mode::PhotoMode;
+use crate::procedural::generate_system;
+use crate::quality::{QualityConfig, QualityGovernor};
+use crate::render_stats::RenderStats;
+use crate::renderer::Renderer;
+use crate::replay::{Replay, ReplayPlayer};
+use crate::ring_particles::RingParticles;
+use crate::route::Route;
+use crate::sas::Sas;
+use crate::scene::{BodyRef, Scene};
+use crate::scene_world::{BodyMeshes, SceneWorld};
+#[cfg(feature = "scripting")]
+use crate::scripting::ScriptHost;
+use crate::secondary_view::SecondaryView;
+use crate::shaders::set_shader_quality;
+use crate::ship::{Ship, SHIP_RADIUS};
+use crate::ship_systems::ShipSystems;
+use crate::solar_activity::SolarActivity;
+use crate::spatial_index::SpatialIndex;
+use crate::starfield::{Starfield, StarfieldConfig};
+use crate::stats_overlay::StatsOverlay;
+use crate::terrain::SurfaceTerrain;
+use crate::tour::{Tour, TourConfig};
+use crate::trajectory::Trajectory;
+use crate::uniforms::Uniforms;
+use crate::volcanism::VolcanicActivity;
+use crate::warp::WarpSystem;
+use crate::weapons::Weapons;
+use std::sync::Arc;
+use std::time::Duration;
+
+// El paso fijo de la simulación, el dibujo del frame y el HUD, cada uno en su archivo
+mod overlay;
+mod render;
+mod step;
+
+pub use overlay::draw_hud;
+pub use render::render;
+use step::step;
+
+/// Escalas de render que alterna F11: el framebuffer por software puede tener otra resolución
+/// que la ventana y se escala al dibujarlo
+const RENDER_SCALES: [f32; 4] = [0.5, 0.75, 1.0, 2.0];
+
+/// Radio del sol en unidades del mundo (mayor que el radio 2 original para verse mejor)
+const SUN_RADIUS: f32 = 3.0;
+
+/// Rocas del cinturón de asteroides de cada escena
+const ASTEROID_COUNT: usize = 150;
+
+/// Lado del minimapa en píxeles
+const MINIMAP_SIZE: i32 = 170;
+
+/// Distancia máxima a la superficie de un cuerpo para insertarse en su órbita
+const ORBIT_CAPTURE_DISTANCE: f32 = 15.0;
+
+/// Near plane de la cámara
+const NEAR: f32 = 0.1;
+
+/// Far plane mínimo: el real es dinámico (se recalcula cada frame según la escena y la cámara)
+/// Con profundidad logarítmica no hay pérdida de precisión al alejarlo
+const MIN_FAR: f32 = 300.0;
+
+const CONTROLS_FILE: &str = "assets/controls.cfg";
+const CAMERA_PATH_FILE: &str = "camera_path.txt";
+const SKYBOX_DIR: &str = "assets/skybox";
+
+/// Opciones de arranque (`cargo run -- --preset real --pluto` o `cargo run -- --seed 42`)
+#[derive(Clone, Debug, Default)]
+pub struct AppOptions {
+    pub preset: Option<String>, // Escena predefinida en lugar del archivo de escena
+    pub pluto: bool,            // Incluir Plutón en el preset `real`
+    pub seed: Option<u64>,      // Semilla de un sistema generado proceduralmente (tiene prioridad sobre el preset)
+    pub record: Option<String>, // Archivo donde grabar la repetición de la partida al salir
+    pub replay: Option<String>, // Repetición a reproducir desde el arranque
+    pub bench: Option<usize>,   // Frames a medir en modo benchmark
+    pub outputs: Vec<String>,   // Destinos extra de cada frame además de la ventana (`--output`)
+    pub sync_upload: bool,      // Codificar la textura de la ventana en el hilo principal, sin retraso
+    pub gpu: bool,              // Color base de los planetas en la GPU (característica `gpu`)
+}
+
+/// Estado interpolado con el que se dibujó el último frame; el HUD lo lee para coincidir con
+/// la imagen
+struct DrawState {
+    elapsed_time: f32,
+    clock: SimulationClock,
+    camera: Camera,
+    ship: Ship,
+    view_uniforms: Uniforms,
+    inset_title: Option<String>, // Título de la vista secundaria, si se dibujó
+    trajectory: Trajectory,
+    orbital_angles: Vec<f32>,    // Ángulos de las órbitas del paso, a restaurar al acabar el frame
+}
+
+/// Todo el estado del juego entre frames: la escena y su simulación, la nave y la cámara, las
+/// herramientas de la interfaz y el render
+/// Cada frame se lee la entrada con `capture_input`, se avanza con `update`, se dibuja con
+/// `render` y `draw_hud` y se cierra con `finish_frame`
+pub struct AppState<'a> {
+    pub renderer: Renderer,
+    window_width: i32,
+    window_height: i32,
+    render_scale_idx: usize,         // Escala de `RENDER_SCALES` elegida con F11
+    quality_governor: QualityGovernor,
+    resize_pending: bool,            // Cambió la escala o la calidad: redimensionar al dibujar
+    gamma: f32,                      // Gamma de salida del framebuffer
+    dithering: bool,                 // Tramado del color al pasar a 8 bits
+    sound: SoundSystem<'a>,
+    starfield: Starfield,
+    assets: AssetManager,
+    ship: Ship,
+    camera: Camera,
+    field_of_view: FieldOfView,
+    scene: Scene,
+    system_seed: Option<u64>,
+    new_system_seed: u64,
+    #[cfg(feature = "scripting")]
+    scripts: Option<ScriptHost>,
+    disk_meshes: Vec<Obj>,
+    gravitational_lens: GravitationalLens,
+    sun_corona: SunCorona,
+    nebula: Option<Nebula>,
+    body_meshes: BodyMeshes,
+    lens_flare: LensFlare,
+    solar_activity: SolarActivity,
+    cme_mesh: Obj,
+    eclipse_forecast: EclipseForecast,
+    screen_shake: f32,               // Fuerza actual del temblor (0 a 1), decae tras pasar el frente
+    engine_exhaust: ParticleEmitter,
+    asteroid_belt: Option<AsteroidBelt>,
+    impact_debris: ParticleEmitter,
+    volcanic_activity: VolcanicActivity,
+    volcanic_ejecta: Vec<ParticleEmitter>,
+    weapons: Weapons,
+    minimap: Minimap,
+    labels: Labels,
+    debug_draw: DebugDraw,
+    stats_overlay: StatsOverlay,
+    frame_graph: FrameGraph,
+    hangar: Hangar,
+    ship_load_options: LoadOptions,
+    ship_model: Arc<Obj>,
+    ship_bounding_radius: f32,
+    last_ship_position: Vector3,
+    elapsed_time: f32,
+    body_index: SpatialIndex<BodyRef>,
+    clock: SimulationClock,
+    warp_system: WarpSystem,
+    autopilot: Autopilot,
+    ship_systems: ShipSystems,
+    flight_model: FlightModel,
+    manual_flight: bool,             // Los controles mueven la nave (no el warp, el piloto automático...)
+    input_map: InputMap,
+    controls_screen: ControlsScreen,
+    console: Console,
+    console_warp: Option<Vector3>,   // Destino del warp pedido en la consola (se hace en el siguiente paso)
+    pending_commands: Vec<Command>,  // Órdenes de la consola y de los scripts, se atienden al empezar el frame
+    script_events: Vec<(&'static str, String)>, // Sucesos del paso para `on_event` de los scripts
+    photo_mode: PhotoMode,
+    map_view: MapView,
+    secondary_view: SecondaryView,
+    parking_orbit: Option<ParkingOrbit>,
+    docking: Docking,
+    route: Route,
+    sas: Sas,
+    daylight: DaylightView,
+    surface_terrain: SurfaceTerrain,
+    ring_particles: RingParticles,
+    scene_world: SceneWorld,
+    camera_path: CameraPath,
+    path_record_start: f32,
+    path_playback_start: Option<f32>,
+    tour: Tour,
+    next_warp_moon: usize,           // Próxima luna a la que se teletransporta F8
+    record_path: Option<String>,     // Archivo de `--record`
+    recording: Option<Replay>,
+    replay_player: Option<ReplayPlayer>,
+    benchmark: Option<Benchmark>,
+    timestep: FixedTimestep,
+    pending_input: InputState,       // Pulsaciones que aún no ha visto ningún paso
+    previous_camera: Camera,
+    previous_ship: (Vector3, Vector3),
+    ship_velocity: Vector3,
+    last_frame_time: Duration,
+    ship_thrusting: bool,            // Para el resplandor de las toberas
+    draw_state: Option<DrawState>,
+}
+
+impl<'a> AppState<'a> {
+    /// Estado inicial para una ventana de `window_size` píxeles: carga la escena, la configuración
+    /// y la repetición de `options`, y pide en segundo plano la nave y el cielo
+    pub fn new(options: &AppOptions, window_size: (i32, i32), sound: SoundSystem<'a>) -> Self {
+        let mut options = options.clone();
+        // Una repetición fija las opciones iniciales con las que se grabó, para repetir el mismo vuelo
+        let replay = options.replay.as_deref().and_then(|path| match Replay::load(path) {
+            Ok(replay) => {
+                println!("Reproduciendo {} ({} frames, {:.1} s)", path, replay.frames.len(), replay.duration());
+                Some(replay)
+            }
+            Err(e) => {
+                eprintln!("Error al cargar {}: {}", path, e);
+                None
+            }
+        });
+        if let Some(replay) = &replay {
+            options.preset = replay.preset.clone();
+            options.pluto = replay.pluto;
+            options.seed = replay.seed;
+        }
+        let (window_width, window_height) = window_size;
+
+        // Calidad adaptativa: si los frames tardan más de lo que permiten los FPS objetivo se bajan
+        // las octavas de los shaders, los segmentos de las esferas, las estrellas y la resolución, y
+        // se recuperan cuando sobra margen. Los límites están en el archivo de configuración
+        let quality_file = "assets/quality.cfg";
+        let quality_config = QualityConfig::load(quality_file).unwrap_or_else(|e| {
+            eprintln!("Error al cargar {}: {}. Usando la calidad por defecto.", quality_file, e);
+            QualityConfig::default()
+        });
+        let mut quality_governor = QualityGovernor::new(quality_config);
+        // El benchmark mide siempre con la calidad máxima permitida
+        quality_governor.config.adaptive &= options.bench.is_none();
+        let quality = quality_governor.settings();
+        set_shader_quality(quality.shader_quality);
+        let render_scale_idx = 2;
+        let (render_width, render_height) = scaled_size(window_size, RENDER_SCALES[render_scale_idx] * quality.render_scale);
+
+        // Fondo de estrellas (semilla fija para que sea consistente entre ejecuciones)
+        let starfield = Starfield::new(starfield_config(quality.star_fraction), render_width as i32, render_height as i32);
+
+        // Recursos pesados (modelos y cielo) en hilos de fondo: la ventana abre sin esperarlos y,
+        // mientras tanto, la nave es una esfera provisional. Se recargan solos si cambian en el disco
+        let mut assets = AssetManager::new(2, Obj::generate_sphere(SHIP_RADIUS, 16));
+
+        // Cielo con imágenes reales si hay alguna en assets/skybox: cubemap (px, nx, py, ny, pz, nz .png)
+        // o panorama equirectangular (sky.png). Sin imágenes se usa el campo de estrellas procedural
+        assets.request_skybox(SKYBOX_DIR);
+
+        // Inicializar la nave
+        let mut ship = Ship::new();
+
+        // Camera setup
+        // Posición inicial donde la nave estará visible delante de la cámara; la distancia entre el
+        // ojo y el target (20) es también la distancia inicial del modo órbita, que luego cambia la rueda
+        let camera_initial_position = Vector3::new(0.0, 20.0, 60.0); // Posición inicial de la cámara
+        let camera_initial_target = Vector3::new(0.0, 20.0, 40.0); // Target inicial (nave estará aquí)
+        let camera_up = Vector3::new(0.0, 1.0, 0.0);
+        let mut camera = Camera::new(camera_initial_position, camera_initial_target, camera_up);
+
+        // Projection setup - Aumentar far plane para ver todo el sistema
+        // Campo de visión: 60° por defecto, ajustable con +/- del teclado numérico, prismáticos con el
+        // botón derecho y un golpe de apertura durante el warp
+        let field_of_view = FieldOfView::new();
+
+        // Light setup
+        let mut renderer = Renderer::new(Light::new(Vector3::new(5.0, 5.0, 5.0)));
+        if options.gpu {
+            enable_gpu_shading(&mut renderer);
+        }
+
+        // Cargar el sistema solar desde el archivo de escena (nombres, órbitas, lunas)
+        // Si falla, se usa el sistema por defecto definido en código
+        let scene_file = "assets/scenes/solar_system.scene";
+        let preset = options.preset.as_deref().and_then(|name| {
+            let scene = Scene::preset(name, options.pluto);
+            if scene.is_none() {
+                eprintln!("Escena predefinida desconocida '{}' (opciones: {}).", name, Scene::PRESET_NAMES.join(", "));
+            }
+            scene
+        });
+        // Con --seed se genera un sistema aleatorio; Y genera otro con una semilla nueva
+        let system_seed = options.seed;
+        // Semilla del primer sistema que genera Y sin --seed (la repetición guarda la suya)
+        let new_system_seed = replay.as_ref().map_or_else(fresh_seed, |replay| replay.new_system_seed);
+        if let Some(seed) = system_seed {
+            println!("Sistema generado con la semilla {}", seed);
+        }
+        let scene = system_seed.map(generate_system).or(preset).unwrap_or_else(|| {
+            Scene::load(scene_file).unwrap_or_else(|e| {
+                eprintln!("Error al cargar {}: {}. Usando el sistema por defecto.", scene_file, e);
+                Scene::solar_system()
+            })
+        });
+
+        // Scripts de la escena: corren en cada paso de la simulación y piden órdenes como la consola
+        #[cfg(feature = "scripting")]
+        let scripts = load_scripts(&scene, scene_file);
+        #[cfg(not(feature = "scripting"))]
+        if !scene.scripts.is_empty() {
+            eprintln!("Los scripts de la escena necesitan compilar con `cargo run --features scripting`; se ignoran");
+        }
+
+        // La luz que reciben los planetas toma el color de la estrella (blanca para una G como el sol)
+        renderer.light.color = scene.star.light_color();
+        println!("Estrella: clase {} ({:.0} K)", scene.star.spectral_class().letter(), scene.star.temperature());
+
+        // Agujeros negros: horizonte negro, disco de acreción translúcido y lente gravitacional
+        let disk_meshes = generate_disk_meshes(&scene);
+        let gravitational_lens = GravitationalLens::new();
+        let sun_corona = SunCorona::new();
+
+        // Nebulosa de fondo si la escena la define (se precalcula al cargar la escena)
+        let nebula = scene.nebula.clone().map(Nebula::new);
+
+        // Mallas de los cuerpos (esferas con sus niveles de detalle, estaciones, rocas), compartidas
+        // por las entidades de cualquier escena. Se dibujan indexadas: cada vértice único pasa una
+        // sola vez por el vertex shader. El sol es mayor que el radio 2 original para verse mejor
+        let body_meshes = BodyMeshes::new(SUN_RADIUS);
+        let lens_flare = LensFlare::new();
+
+        // Eyecciones de masa coronal: erupciones aleatorias (Insert lanza una hacia la nave)
+        // Al alcanzar la nave la pantalla tiembla y la imagen sufre interferencias
+        let solar_activity = SolarActivity::for_star(&scene.star, scene.extent() * 1.1);
+        let cme_mesh = Obj::generate_spherical_cap(solar_activity.half_angle, 12, 32);
+        let eclipse_forecast = EclipseForecast::new(SUN_RADIUS);
+        let screen_shake = 0.0f32;
+        let engine_exhaust = ParticleEmitter::engine_exhaust();
+
+        // Cinturón de asteroides: de vez en cuando una roca cae sobre un planeta y le deja un cráter
+        // (I desvía una hacia el planeta más cercano a la nave)
+        let asteroid_belt = AsteroidBelt::for_scene(&scene, ASTEROID_COUNT, system_seed.unwrap_or(0));
+        let impact_debris = ParticleEmitter::explosion();
+
+        // Volcanes: los planetas volcánicos entran en erupción de vez en cuando; la lava alrededor de
+        // la boca se aviva y la eyecta incandescente sigue arcos balísticos hasta caer de nuevo
+        // (un emisor por planeta, cada uno con la gravedad de su planeta)
+        let volcanic_activity = VolcanicActivity::default();
+        let volcanic_ejecta: Vec<ParticleEmitter> = Vec::new();
+
+        // Cañón de la nave: Espacio dispara proyectiles que destruyen los asteroides
+        let weapons = Weapons::new();
+
+        // Minimapa en la esquina superior derecha (M para mostrar/ocultar)
+        let minimap = Minimap::new(window_width - MINIMAP_SIZE - 10, 10, MINIMAP_SIZE);
+
+        // Etiquetas con el nombre de cada cuerpo (L para mostrar/ocultar)
+        // Se desvanecen al acercarse a la distancia máxima
+        let labels = Labels::new(120.0);
+
+        // Gizmos de depuración: G ejes, B esferas envolventes, V velocidad de la nave, N normales
+        let debug_draw = DebugDraw::new();
+
+        // Estadísticas del pipeline (7): contadores por etapa y tiempo de cada objeto
+        let stats_overlay = StatsOverlay::new();
+
+        // Gráfica de tiempos de frame en la esquina inferior derecha (8)
+        let frame_graph = FrameGraph::new();
+
+        // Hangar: las naves de `assets/ships.cfg` (Av Pág pasa a la siguiente y recarga su modelo)
+        let hangar_file = "assets/ships.cfg";
+        let hangar = Hangar::new(HangarConfig::load(hangar_file).unwrap_or_else(|e| {
+            eprintln!("Error al cargar {}: {}. Usando solo la nave original.", hangar_file, e);
+            HangarConfig::default()
+        }));
+
+        // Pedir el modelo 3D de la nave activa
+        // Se centra en su centroide y se escala a SHIP_RADIUS, sea cual sea la escala del archivo, y se
+        // precalcula su oclusión ambiental por vértice (uniones y huecos del casco más oscuros)
+        let ship_load_options = LoadOptions {
+            normalize_radius: Some(SHIP_RADIUS),
+            ambient_occlusion: true,
+            ..LoadOptions::default()
+        };
+        assets.request_mesh(&hangar.active().path, &ship_load_options);
+        let ship_model = assets.mesh_or_placeholder(&hangar.active().path);
+        let ship_bounding_radius = bounding_radius(&ship_model.vertices);
+        ship.scale = hangar.active().scale;
+
+        // Velocidad medida de la nave (posición entre frames), para el gizmo de velocidad
+        let last_ship_position = ship.position;
+
+        let elapsed_time = 0.0f32;
+
+        // Cuerpos de la escena por posición: se reconstruye en cada paso de la simulación
+        let body_index = scene.body_index(elapsed_time);
+
+        // Reloj de la simulación (horas simuladas): marca el giro de los planetas y la hora local
+        // , y . lo frenan y aceleran
+        let clock = SimulationClock::default();
+        let warp_system = WarpSystem::new();
+
+        // Piloto automático: Shift + 1-5 lleva la nave al planeta, Shift + 6 atraca en la estación
+        // más cercana; cualquier tecla lo cancela
+        let autopilot = Autopilot::new();
+
+        // Combustible (lo gastan los motores, se repone cerca de una estación) y carga de warp
+        let ship_systems = ShipSystems::new();
+
+        // Modelo de vuelo manual: Tab alterna entre arcade y newtoniano
+        // Las constantes se ajustan en el archivo de configuración (si falta, valores por defecto)
+        let flight_config_file = "assets/flight.cfg";
+        let flight_config = match &replay {
+            Some(replay) => replay.flight,
+            None => FlightConfig::load(flight_config_file).unwrap_or_else(|e| {
+                eprintln!("Error al cargar {}: {}. Usando el vuelo por defecto.", flight_config_file, e);
+                FlightConfig::default()
+            }),
+        };
+        let flight_model = FlightModel::new(flight_config);
+        let manual_flight = false;
+        // La inercia de los controles de la cámara viene del mismo archivo (y se graba en las repeticiones)
+        camera.rotation_acceleration = flight_config.rotation_acceleration;
+        camera.rotation_damping = flight_config.rotation_damping;
+        camera.pan_acceleration = flight_config.pan_acceleration;
+        camera.pan_damping = flight_config.pan_damping;
+
+        // Controles: cada acción tiene sus teclas en el archivo de configuración (si falta, la
+        // distribución por defecto); Retroceso abre la pantalla para reasignarlas en el juego
+        let input_map = InputMap::load(CONTROLS_FILE).unwrap_or_else(|e| {
+            eprintln!("Error al cargar {}: {}. Usando los controles por defecto.", CONTROLS_FILE, e);
+            InputMap::default()
+        });
+        let controls_screen = ControlsScreen::new();
+        let console = Console::new();
+        let console_warp: Option<Vector3> = None;
+        let pending_commands: Vec<Command> = Vec::new();
+        let script_events: Vec<(&str, String)> = Vec::new();
+        let photo_mode = PhotoMode::new();
+        let map_view = MapView::new();
+        let secondary_view = SecondaryView::new();
+
+        // Órbita de estacionamiento: O inserta la nave en órbita del cuerpo más cercano o la rompe
+        let parking_orbit: Option<ParkingOrbit> = None;
+
+        // Atraque: H junto al puerto de una estación (y casi en reposo respecto a él) inicia la
+        // aproximación; atracada, U reposta y H desatraca
+        let docking = Docking::new();
+
+        // Ruta: X añade el cuerpo bajo la mira (o un punto delante de la nave), Z quita el último
+        // punto (Shift + Z borra la ruta) e Intro la recorre con el piloto automático
+        let route = Route::new();
+        let sas = Sas::new();
+        let daylight = DaylightView::new();
+        let surface_terrain = SurfaceTerrain::new();
+        let ring_particles = RingParticles::new();
+        // Entidades de los cuerpos de la escena: se crean aquí y al generar otro sistema, y cada
+        // frame sus sistemas les copian el estado de la simulación
+        let scene_world = SceneWorld::new(&scene, &body_meshes, &ring_particles);
+
+        // Trayectoria cinemática: `PathRecord` graba keyframes, `PathPlay` la reproduce y
+        // `PathSave`/`PathLoad` la guardan y la cargan
+        let camera_path = CameraPath::new();
+        let path_record_start = 0.0;
+        let path_playback_start: Option<f32> = None;
+        // Visita guiada (`Tour`): vuela de cuerpo en cuerpo con la ficha de cada uno
+        let tour_file = "assets/tour.cfg";
+        let tour = Tour::new(TourConfig::load(tour_file).unwrap_or_else(|e| {
+            eprintln!("Error al cargar {}: {}. Usando la visita por defecto.", tour_file, e);
+            TourConfig::default()
+        }));
+        let next_warp_moon = 0;
+
+        // Repeticiones: --record graba el paso de tiempo y la entrada de cada frame (se guarda al
+        // salir) y --replay los reproduce en lugar de la ventana hasta terminar
+        let recording = options
+            .record
+            .as_ref()
+            .map(|_| Replay::new(options.preset.clone(), options.pluto, options.seed, new_system_seed, flight_config));
+        let replay_player = replay.map(ReplayPlayer::new);
+
+        // Benchmark: --bench N dibuja N frames con la cámara guionizada y muestra las estadísticas
+        // del pipeline al terminar (la entrada del jugador se ignora)
+        let benchmark = options.bench.map(Benchmark::new);
+        if benchmark.is_some() {
+            renderer.profiling = true;
+            camera.set_mode(CameraMode::FreeFly);
+        }
+
+        // Paso fijo: la simulación avanza en pasos de 1/120 s sea cual sea la tasa de frames y se
+        // dibuja interpolando entre el estado del paso anterior y el actual (cámara, nave, órbitas
+        // de los planetas y el tiempo de lo que se calcula a partir de él)
+        let timestep = FixedTimestep::default();
+        let pending_input = InputState::new();
+        let previous_camera = camera.clone();
+        let previous_ship = (ship.position, ship.rotation);
+        let ship_velocity = Vector3::zero();
+        let last_frame_time = Duration::ZERO;
+        let ship_thrusting = false;
+
+        AppState {
+            renderer,
+            window_width,
+            window_height,
+            render_scale_idx,
+            quality_governor,
+            resize_pending: false,
+            gamma: DEFAULT_GAMMA,
+            dithering: true,
+            sound,
+            starfield,
+            assets,
+            ship,
+            camera,
+            field_of_view,
+            scene,
+            system_seed,
+            new_system_seed,
+            #[cfg(feature = "scripting")]
+            scripts,
+            disk_meshes,
+            gravitational_lens,
+            sun_corona,
+            nebula,
+            body_meshes,
+            lens_flare,
+            solar_activity,
+            cme_mesh,
+            eclipse_forecast,
+            screen_shake,
+            engine_exhaust,
+            asteroid_belt,
+            impact_debris,
+            volcanic_activity,
+            volcanic_ejecta,
+            weapons,
+            minimap,
+            labels,
+            debug_draw,
+            stats_overlay,
+            frame_graph,
+            hangar,
+            ship_load_options,
+            ship_model,
+            ship_bounding_radius,
+            last_ship_position,
+            elapsed_time,
+            body_index,
+            clock,
+            warp_system,
+            autopilot,
+            ship_systems,
+            flight_model,
+            manual_flight,
+            input_map,
+            controls_screen,
+            console,
+            console_warp,
+            pending_commands,
+            script_events,
+            photo_mode,
+            map_view,
+            secondary_view,
+            parking_orbit,
+            docking,
+            route,
+            sas,
+            daylight,
+            surface_terrain,
+            ring_particles,
+            scene_world,
+            camera_path,
+            path_record_start,
+            path_playback_start,
+            tour,
+            next_warp_moon,
+            record_path: options.record,
+            recording,
+            replay_player,
+            benchmark,
+            timestep,
+            pending_input,
+            previous_camera,
+            previous_ship,
+            ship_velocity,
+            last_frame_time,
+            ship_thrusting,
+            draw_state: None,
+        }
+    }
+
+    /// Tamaño del framebuffer con la escala de render y la calidad actuales
+    pub fn render_size(&self) -> (u32, u32) {
+        let scale = RENDER_SCALES[self.render_scale_idx] * self.quality_governor.settings().render_scale;
+        scaled_size((self.window_width, self.window_height), scale)
+    }
+
+    /// Desplazamiento de la imagen en la ventana por el temblor de pantalla del último frame
+    pub fn shake_offset(&self) -> Vector2 {
+        let time = self.draw_state.as_ref().map_or(self.elapsed_time, |state| state.elapsed_time);
+        Vector2::new(
+            (time * 53.0).sin() * 10.0 * self.screen_shake,
+            (time * 71.0).cos() * 10.0 * self.screen_shake,
+        )
+    }
+
+    /// Si el bucle puede esperar al límite de FPS: no durante el warp (la simulación no depende
+    /// de los FPS) ni en el benchmark
+    pub fn limits_fps(&self) -> bool {
+        !self.warp_system.is_warping && self.benchmark.is_none()
+    }
+
+    /// Guarda la repetición de `--record`, si se estaba grabando
+    pub fn save_recording(&self) {
+        if let (Some(recording), Some(path)) = (&self.recording, &self.record_path) {
+            match recording.save(path) {
+                Ok(()) => println!("Repetición guardada en {} ({} frames, {:.1} s)", path, recording.frames.len(), recording.duration()),
+                Err(e) => eprintln!("Error al guardar {}: {}", path, e),
+            }
+        }
+    }
+}
+
+/// Entrada y duración del frame: las de la repetición en curso, ninguna en el benchmark o, si
+/// no, las de la ventana (la pantalla de controles y la consola se quedan el teclado mientras
+/// están abiertas)
+pub fn capture_input(app: &mut AppState, window: &mut RaylibHandle) -> (InputState, f32) {
+    let replay_frame = app.replay_player.as_mut().and_then(ReplayPlayer::next_frame);
+    if app.replay_player.is_some() && replay_frame.is_none() {
+        println!("Reproducción terminada: los controles vuelven al jugador");
+        app.replay_player = None;
+    }
+
+    // Get delta time from Raylib (o de la repetición)
+    let delta_time = match (&replay_frame, &app.benchmark) {
+        (Some(frame), _) => frame.delta_time,
+        (None, Some(_)) => BENCH_FRAME_TIME,
+        (None, None) => window.get_frame_time(),
+    };
+
+    // Toda la lógica del frame lee esta instantánea de la entrada, no la ventana
+    let frame_input = match replay_frame {
+        Some(frame) => frame.input,
+        None if app.benchmark.is_some() => InputState::new(),
+        None => {
+            // Pantalla de controles: mientras está abierta ninguna acción del juego responde
+            if !app.console.is_open() && app.controls_screen.update(window, &mut app.input_map) {
+                match app.input_map.save(CONTROLS_FILE) {
+                    Ok(()) => println!("Controles guardados en {}", CONTROLS_FILE),
+                    Err(e) => eprintln!("Error al guardar {}: {}", CONTROLS_FILE, e),
+                }
+            }
+            // Consola de desarrollo: igual, el teclado escribe en ella mientras está abierta
+            if !app.controls_screen.is_open() {
+                app.pending_commands.extend(app.console.update(window, &mut app.input_map));
+            }
+            InputState::capture(window, &app.input_map)
+        }
+    };
+    (frame_input, delta_time)
+}
+
+/// Avanza el juego un frame de `delta_time` segundos con la entrada `frame_input`: el modo foto y
+/// el mapa, las órdenes de la consola, los pasos fijos de la simulación y el sonido
+pub fn update(app: &mut AppState, frame_input: &InputState, delta_time: f32) {
+    // Recursos terminados en segundo plano: la nave activa (la provisional hasta que está) y el cielo
+    for event in app.assets.update() {
+        match event {
+            AssetEvent::Loaded(key) if key == SKYBOX_DIR => println!("Skybox cargado desde {}", key),
+            AssetEvent::Failed(key, e) if key == SKYBOX_DIR => println!("Sin skybox de imágenes ({}). Usando estrellas procedurales.", e),
+            AssetEvent::Loaded(key) => println!("Cargado {}", key),
+            AssetEvent::Reloaded(key) => println!("Recargado {} (cambió en el disco)", key),
+            AssetEvent::Failed(key, e) => eprintln!("Error al cargar {}: {}. Usando el modelo provisional.", key, e),
+        }
+    }
+    let active_ship_model = app.assets.mesh_or_placeholder(&app.hangar.active().path);
+    if !Arc::ptr_eq(&active_ship_model, &app.ship_model) {
+        app.ship_bounding_radius = bounding_radius(&active_ship_model.vertices);
+        app.ship_model = active_ship_model;
+    }
+
+    // Modo foto: la simulación se congela y la entrada de este frame mueve su cámara libre
+    // (no llega a la simulación ni a la grabación). No está disponible en repeticiones ni benchmark
+    if frame_input.is_pressed(Action::PhotoMode) && app.replay_player.is_none() && app.benchmark.is_none() {
+        if app.photo_mode.is_active() {
+            app.photo_mode.exit();
+            println!("Modo foto desactivado");
+        } else {
+            app.map_view.exit();
+            app.photo_mode.enter(&app.camera, app.field_of_view.current());
+            println!("Modo foto: simulación en pausa");
+        }
+        app.sound.click();
+    } else if app.photo_mode.is_active() {
+        app.photo_mode.update(frame_input, delta_time);
+    }
+
+    // Vista de mapa: la simulación sigue, pero la entrada de vuelo desplaza el mapa, así que a
+    // la simulación (y a la grabación) le llega una entrada vacía. Tampoco en repeticiones
+    let map_toggle = frame_input.is_pressed(Action::MapView) && app.replay_player.is_none() && app.benchmark.is_none();
+    if map_toggle && !app.photo_mode.is_active() {
+        app.map_view.toggle(app.scene.extent());
+        println!("Vista de mapa {}", if app.map_view.is_active() { "activada" } else { "desactivada" });
+        app.sound.click();
+    } else if app.map_view.is_active() && !app.photo_mode.is_active() {
+        app.map_view.update(frame_input, app.scene.extent(), delta_time);
+    }
+    let no_input = InputState::new();
+    let frame_input = if app.map_view.is_active() { &no_input } else { frame_input };
+    if !app.photo_mode.is_active() {
+        if let Some(recording) = &mut app.recording {
+            recording.record(delta_time, frame_input);
+        }
+        app.pending_input.accumulate(frame_input);
+    }
+
+    // Órdenes de la consola de desarrollo y de los scripts. Las de la consola cambian el estado
+    // fuera de la entrada grabada, así que una repetición no las reproduce (y en repeticiones y
+    // benchmark no hay consola); las de los scripts salen de la simulación y sí se repiten
+    let mut console_quality = false;
+    for command in app.pending_commands.drain(..) {
+        let reply = match command {
+            Command::Warp(_) if app.warp_system.is_warping || app.docking.is_input_locked() => "Ahora no se puede hacer warp".to_string(),
+            Command::Warp(target) => {
+                app.console_warp = Some(target);
+                format!("Warp a ({:.1}, {:.1}, {:.1})", target.x, target.y, target.z)
+            }
+            Command::TimeScale(rate) => {
+                app.clock.set_rate(rate);
+                format!("Reloj: {:.2} horas simuladas por segundo", app.clock.hours_per_second)
+            }
+            Command::Spawn { kind: SpawnKind::Asteroid, count } => match &mut app.asteroid_belt {
+                Some(belt) => {
+                    belt.spawn_many(count, app.elapsed_time);
+                    format!("{} asteroides nuevos ({} en el cinturón)", count, belt.asteroids().len())
+                }
+                None => "Este sistema no tiene cinturón de asteroides".to_string(),
+            },
+            Command::Track(name) => match app.scene.find_body(&name) {
+                Some(BodyRef::Planet(idx)) => {
+                    app.camera.track_planet(Some(idx));
+                    format!("Siguiendo a {}", app.scene.planets[idx].name)
+                }
+                Some(BodyRef::Moon(idx)) => {
+                    app.camera.track_moon(Some(idx));
+                    format!("Siguiendo a {}", app.scene.moons[idx].name)
+                }
+                Some(_) => "Solo se puede seguir a planetas y lunas".to_string(),
+                None => format!("No hay ningún cuerpo llamado '{}'", name),
+            },
+            Command::Quality(level) => {
+                app.quality_governor.set_level(level);
+                console_quality = true;
+                format!("Calidad del render: nivel {} (calidad adaptativa desactivada)", level)
+            }
+            Command::Help => console::HELP.to_string(),
+        };
+        app.console.print(reply);
+    }
+
+    // Calidad adaptativa según el tiempo de trabajo del frame anterior, o la elegida en la
+    // consola (no en modo foto: las capturas supersampleadas no son frames normales)
+    if console_quality || (!app.photo_mode.is_active() && app.quality_governor.update(app.last_frame_time)) {
+        let quality = app.quality_governor.settings();
+        set_shader_quality(quality.shader_quality);
+        app.resize_pending = true;
+        println!("Calidad del render: nivel {} de {}", app.quality_governor.level(), app.quality_governor.config.max_level);
+    }
+
+    for _ in 0..app.timestep.advance(if app.photo_mode.is_active() { 0.0 } else { delta_time }) {
+        step(app);
+    }
+
+    // Sonido del frame: el motor sigue al empuje y el retumbar crece a menos de cinco radios
+    // de la superficie del sol
+    let ship_distance_to_sun = (app.ship.position.x * app.ship.position.x + app.ship.position.y * app.ship.position.y + app.ship.position.z * app.ship.position.z).sqrt();
+    app.sound.update(&AudioState {
+        throttle: app.ship_systems.thruster_output,
+        warping: app.warp_system.is_warping,
+        sun_proximity: 1.0 - ((ship_distance_to_sun - SUN_RADIUS) / (SUN_RADIUS * 5.0)).clamp(0.0, 1.0),
+    });
+
+    // La cámara del benchmark sigue su guion (sin interpolar: ya es continua)
+    if let Some(benchmark) = &app.benchmark {
+        let (eye, target) = benchmark.camera_pose(app.scene.extent());
+        app.camera.look_from(eye, target);
+        app.previous_camera = app.camera.clone();
+    }
+}
+
+
+/// Cierra el frame que tardó `frame_time` en trabajarse: lo anota en la gráfica, la calidad
+/// adaptativa y el benchmark, y devuelve las órbitas al estado del último paso
+/// Devuelve false cuando el benchmark ha terminado
+pub fn finish_frame(app: &mut AppState, frame_time: Duration, frame_stats: &RenderStats) -> bool {
+    if let Some(state) = app.draw_state.take() {
+        for (planet, angle) in app.scene.planets.iter_mut().zip(state.orbital_angles) {
+            planet.orbital_angle = angle;
+        }
+    }
+    app.last_frame_time = frame_time;
+    app.frame_graph.record(frame_time);
+    if let Some(benchmark) = &mut app.benchmark {
+        benchmark.record(frame_time, frame_stats);
+        if benchmark.is_finished() {
+            println!("{}", benchmark.report());
+            return false;
+        }
+    }
+    true
+}
+
+/// Tamaño de la ventana escalado por `scale` (al menos un píxel por lado)
+fn scaled_size(window_size: (i32, i32), scale: f32) -> (u32, u32) {
+    (
+        ((window_size.0 as f32 * scale) as u32).max(1),
+        ((window_size.1 as f32 * scale) as u32).max(1),
+    )
+}
+
+/// Estación más cercana a `point` según el índice de cuerpos del paso
+fn nearest_station(body_index: &SpatialIndex<BodyRef>, point: Vector3) -> Option<BodyRef> {
+    body_index
+        .nearest(point, |body| matches!(body, BodyRef::Station(_)))
+        .map(|(entry, _)| entry.item)
+}
+
+/// `--gpu`: pasa el color base de los planetas a la GPU; si no hay una compatible (o el
+/// ejecutable se compiló sin la característica `gpu`) se avisa y todo sigue en la CPU
+#[cfg(feature = "gpu")]
+fn enable_gpu_shading(renderer: &mut Renderer) {
+    match GpuShading::new() {
+        Ok(gpu) => {
+            println!("Fragment stage de los planetas en la GPU: {}", gpu.adapter_name());
+            renderer.set_gpu_shading(Some(gpu));
+        }
+        Err(e) => eprintln!("--gpu: {}; se sigue sombreando en la CPU", e),
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+fn enable_gpu_shading(_renderer: &mut Renderer) {
+    eprintln!("--gpu necesita compilar con `cargo run --features gpu`; se sigue sombreando en la CPU");
+}
+
+/// Scripts de la escena (`[script]`), con rutas relativas al archivo de escena; None si no tiene
+/// ninguno o no se pudo cargar ninguno
+#[cfg(feature = "scripting")]
+fn load_scripts(scene: &Scene, scene_file: &str) -> Option<ScriptHost> {
+    if scene.scripts.is_empty() {
+        return None;
+    }
+    let folder = std::path::Path::new(scene_file).parent().unwrap_or(std::path::Path::new(""));
+    let mut host = ScriptHost::new();
+    for script in &scene.scripts {
+        match host.load(&folder.join(script)) {
+            Ok(()) => println!("Script cargado: {}", script),
+            Err(e) => eprintln!("Error al cargar el script {}", e),
+        }
+    }
+    for message in host.take_messages() {
+        println!("{}", message);
+    }
+    (!host.is_empty()).then_some(host)
+}
+
+/// Malla del disco de acreción de cada agujero negro, en unidades del mundo
+fn generate_disk_meshes(scene: &Scene) -> Vec<Obj> {
+    scene.black_holes.iter()
+        .map(|hole| Obj::generate_rings(hole.disk_inner, hole.disk_outer, 12, 64))
+        .collect()
+}
+
+/// Campo de estrellas por defecto con solo `fraction` de sus estrellas (calidad adaptativa)
+fn starfield_config(fraction: f32) -> StarfieldConfig {
+    let config = StarfieldConfig::default();
+    StarfieldConfig { count: (config.count as f32 * fraction) as usize, ..config }
+}
+
+/// Semilla nueva a partir del reloj del sistema, para regenerar sin `--seed`
+fn fresh_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+}
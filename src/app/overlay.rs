@@ -0,0 +1,220 @@
+use raylib::prelude::*;
+use crate::clock::format_time_of_day;
+use crate::eclipses::Observer;
+use crate::ephemeris::Ephemeris;
+use crate::framebuffer::Framebuffer;
+use crate::hud;
+use crate::labels::LabelTarget;
+use crate::render_stats::RenderStats;
+use crate::route::Route;
+use crate::scene::{BodyRef, Station};
+use crate::screen_texture::ScreenTexture;
+use crate::units::{format_days, format_distance, orbit_seconds_to_days};
+use super::{nearest_station, AppState, DrawState, MINIMAP_SIZE, SKYBOX_DIR, SUN_RADIUS};
+
+/// Dibuja el HUD sobre la imagen ya presentada en la ventana, con el estado del último `render`
+pub fn draw_hud(d: &mut RaylibDrawHandle, app: &AppState, framebuffer: &Framebuffer, screen_texture: &ScreenTexture, frame_stats: &RenderStats) {
+    let Some(DrawState { elapsed_time, clock, camera, ship, view_uniforms, inset_title, trajectory, .. }) = &app.draw_state else {
+        return;
+    };
+    let elapsed_time = *elapsed_time;
+    let sun_translation = Vector3::zero(); // Centro del sistema
+
+    // HUD del juego (el modo foto puede ocultarlo para encuadrar)
+    if !app.photo_mode.is_active() || app.photo_mode.show_hud {
+        // Nombres sobre los cuerpos visibles (usa la profundidad del frame para la oclusión)
+        let mut label_targets = vec![LabelTarget { name: "Sol", position: sun_translation, radius: SUN_RADIUS }];
+        for planet in &app.scene.planets {
+            label_targets.push(LabelTarget { name: &planet.name, position: planet.position(), radius: planet.scale });
+        }
+        for (moon_idx, moon) in app.scene.moons.iter().enumerate() {
+            label_targets.push(LabelTarget {
+                name: &moon.name,
+                position: app.scene.moon_position(moon_idx, elapsed_time),
+                radius: moon.scale,
+            });
+        }
+        for (station_idx, station) in app.scene.stations.iter().enumerate() {
+            label_targets.push(LabelTarget {
+                name: &station.name,
+                position: app.scene.station_position(station_idx, elapsed_time),
+                radius: station.scale * Station::OUTER_RADIUS,
+            });
+        }
+        for hole in &app.scene.black_holes {
+            label_targets.push(LabelTarget { name: &hole.name, position: hole.position, radius: hole.radius });
+        }
+        let screen_scale = app.window_width as f32 / framebuffer.width as f32;
+        app.labels.draw(d, framebuffer, view_uniforms, &label_targets, screen_scale);
+
+        // Marco y título de la vista secundaria
+        if let Some(title) = inset_title {
+            app.secondary_view.draw(d, framebuffer.width, framebuffer.height, screen_scale, title);
+        }
+
+        // Marcadores de progrado, retrógrado y objetivo del SAS
+        let relative_velocity = app.sas.relative_velocity(&app.scene, elapsed_time, app.flight_model.velocity);
+        if !app.map_view.is_active() {
+            let target_position = app.sas.target.and_then(|body| app.scene.body_position(body, elapsed_time));
+            app.sas.draw_markers(d, view_uniforms, camera.view_eye(), relative_velocity, target_position, screen_scale);
+        }
+
+        // Distancias de los tramos de la ruta y resumen bajo las efemérides
+        if !app.route.is_empty() {
+            app.route.draw_annotations(d, view_uniforms, &app.scene, elapsed_time, ship.position, screen_scale);
+            let total = app.route.total_distance(&app.scene, elapsed_time, ship.position);
+            let text = match app.route.current_waypoint() {
+                Some(waypoint) => format!(
+                    "Ruta: punto {} de {} -> {} ({} restantes, Intro detiene)",
+                    app.route.current_index().unwrap_or(0) + 1,
+                    app.route.waypoints.len(),
+                    Route::name(waypoint, &app.scene),
+                    format_distance(total),
+                ),
+                None => format!("Ruta: {} puntos, {} (Intro para recorrerla)", app.route.waypoints.len(), format_distance(total)),
+            };
+            d.draw_text(&text, 10, 50, 14, Color::new(80, 230, 255, 255));
+        }
+
+        // Marco de la cabina en primera persona
+        if camera.is_cockpit_view() {
+            hud::draw_cockpit_overlay(d, app.window_width, app.window_height, camera.pitch);
+        }
+
+        // Minimapa: vista cenital del sistema con la nave
+        app.minimap.draw(d, &app.scene, elapsed_time, ship.position, camera.forward());
+
+        // Progreso del piloto automático
+        if app.autopilot.is_active() {
+            hud::draw_autopilot_hud(
+                d,
+                app.window_width,
+                app.window_height,
+                app.autopilot.target.and_then(|target| app.scene.body_name(target)).unwrap_or("punto de ruta"),
+                app.autopilot.progress(),
+                app.autopilot.remaining_distance(),
+            );
+        }
+
+        // Ficha de la parada de la visita guiada
+        app.tour.draw(d, &app.scene, elapsed_time, app.window_height);
+
+        // Hora local bajo la nave en el planeta más cercano (amanecer 06:00, atardecer 18:00)
+        let distance_to_ship = |p: Vector3| {
+            let (dx, dy, dz) = (p.x - ship.position.x, p.y - ship.position.y, p.z - ship.position.z);
+            dx * dx + dy * dy + dz * dz
+        };
+        let nearest_planet = app.scene
+            .planets
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| distance_to_ship(a.position()).total_cmp(&distance_to_ship(b.position())));
+        if let Some((planet_idx, planet)) = nearest_planet {
+            let local_time = planet.local_time_of_day(clock.hours(), ship.position);
+            let text = format!(
+                "{} ({} del sol): {} hora local (día de {:.0} h) - reloj x{:.2} h/s",
+                planet.name,
+                format_distance(planet.orbital_radius),
+                format_time_of_day(local_time),
+                planet.rotation_period,
+                clock.hours_per_second,
+            );
+            d.draw_text(&text, 10, 10, 16, Color::new(220, 230, 255, 255));
+
+            // Efemérides: velocidad orbital y próxima conjunción con el planeta vecino
+            let ephemeris = Ephemeris::new(&app.scene, elapsed_time);
+            let body = BodyRef::Planet(planet_idx);
+            let neighbor = if planet_idx + 1 < app.scene.planets.len() { planet_idx + 1 } else { planet_idx.saturating_sub(1) };
+            if let Some(velocity) = ephemeris.velocity(body, elapsed_time).filter(|_| neighbor != planet_idx) {
+                let speed = (velocity.x * velocity.x + velocity.y * velocity.y + velocity.z * velocity.z).sqrt();
+                let conjunction = match ephemeris.next_conjunction(body, BodyRef::Planet(neighbor), elapsed_time) {
+                    Some(time) => format!("en {}", format_days(orbit_seconds_to_days(time - elapsed_time))),
+                    None => String::from("no prevista"),
+                };
+                let text = format!(
+                    "Velocidad orbital {:.1} u/s - conjunción con {} {}",
+                    speed,
+                    app.scene.planets[neighbor].name,
+                    conjunction,
+                );
+                d.draw_text(&text, 10, 30, 14, Color::new(170, 185, 210, 255));
+            }
+        }
+        if let Some(text) = app.daylight.readout(&app.scene, clock.hours(), ship.position, clock.hours_per_second) {
+            d.draw_text(&text, 10, 48, 14, Color::new(255, 210, 150, 255));
+        }
+
+        // Recursos cargándose en segundo plano y los que no se pudieron cargar (el cielo que
+        // falta no es un error: se usan las estrellas procedurales)
+        let (done, total) = app.assets.progress();
+        if let Some(first) = app.assets.loading().first() {
+            let text = format!("Cargando recursos {}/{}: {}", done, total, first);
+            d.draw_text(&text, 10, app.window_height - 190, 14, Color::new(170, 185, 210, 255));
+        }
+        for (line, (key, message)) in app.assets.failures().into_iter().filter(|(key, _)| *key != SKYBOX_DIR).enumerate() {
+            let text = format!("No se pudo cargar {} ({}): se usa el modelo provisional", key, message);
+            d.draw_text(&text, 10, app.window_height - 210 - line as i32 * 18, 14, Color::new(255, 120, 90, 255));
+        }
+
+        // Atraque: opciones en la estación o aviso de que se puede atracar
+        let docking_station = app.docking.station().or_else(|| nearest_station(&app.body_index, ship.position));
+        if let Some(station) = docking_station.filter(|_| !app.autopilot.is_active()) {
+            hud::draw_docking_hud(d, app.window_width, app.window_height, app.scene.body_name(station).unwrap_or("estación"), &app.docking, &app.ship_systems);
+        }
+
+        // Combustible, empuje y carga de warp
+        hud::draw_ship_systems_hud(d, app.window_height, &app.ship_systems, &app.flight_model, elapsed_time);
+        app.sas.draw_status(d, &app.scene, relative_velocity, 10, app.window_height - 164);
+        if let Some((body, seconds)) = trajectory.impact {
+            let name = body.and_then(|body| app.scene.body_name(body)).unwrap_or("el sol");
+            let text = format!("TRAYECTORIA DE IMPACTO CON {} EN {:.1} s", name.to_uppercase(), seconds);
+            let x = app.window_width / 2 - measure_text(&text, 14) / 2;
+            d.draw_text(&text, x, app.window_height / 2 + 40, 14, Color::new(255, 90, 60, 255));
+        }
+
+        // Aviso de tormenta solar mientras dura el temblor
+        if app.screen_shake > 0.05 {
+            hud::draw_solar_storm_hud(d, app.window_width, app.screen_shake, elapsed_time);
+        }
+
+        // Próximo eclipse (o el que está en curso)
+        if let Some(eclipse) = app.eclipse_forecast.featured(elapsed_time) {
+            hud::draw_eclipse_hud(d, app.window_width, &eclipse.headline(&app.scene, elapsed_time), eclipse.observer == Observer::Ship);
+        }
+
+        // Gizmos de depuración activos
+        if app.debug_draw.any_enabled() {
+            let text = format!("Depuración: {}", app.debug_draw.enabled_names().join(", "));
+            d.draw_text(&text, 10, app.window_height - 24, 16, Color::YELLOW);
+        }
+
+        // Crosshair centrado
+        let center_x = app.window_width / 2;
+        let center_y = app.window_height / 2;
+        let crosshair_size = 10;
+        d.draw_line(center_x - crosshair_size, center_y, center_x + crosshair_size, center_y, Color::WHITE);
+        d.draw_line(center_x, center_y - crosshair_size, center_x, center_y + crosshair_size, Color::WHITE);
+
+        // Repetición en curso o grabación
+        if let Some(player) = &app.replay_player {
+            d.draw_text(&format!("REPRODUCCIÓN {:3.0}%", player.progress() * 100.0), 10, 70, 14, Color::new(120, 200, 255, 255));
+        } else if app.recording.is_some() {
+            d.draw_text("GRABANDO", 10, 70, 14, Color::new(255, 80, 60, 255));
+        }
+
+        // Estadísticas del pipeline del frame, bajo el minimapa
+        app.stats_overlay.draw(d, frame_stats, screen_texture, app.window_width, MINIMAP_SIZE + 20);
+        app.frame_graph.draw(d, app.window_width - 10, app.window_height - 10);
+    }
+    if !app.photo_mode.is_active() && !app.map_view.is_active() {
+        app.field_of_view.draw(d, app.window_width, app.window_height);
+    }
+
+    // Paneles del modo foto y de la vista de mapa
+    app.photo_mode.draw(d, &app.input_map, app.window_width, app.window_height);
+    app.map_view.draw(d, app.window_width, app.window_height);
+
+    // Consola y pantalla de controles por encima de todo el HUD
+    app.console.draw(d, &app.input_map, app.window_width);
+    app.controls_screen.draw(d, &app.input_map, app.window_width, app.window_height);
+}
@@ -0,0 +1,425 @@
+use raylib::prelude::*;
+use crate::camera::{lerp_angle, Camera};
+use crate::color::srgb_to_linear_rgb;
+use crate::corona::SunCorona;
+use crate::ecs::{orbit_system, render_system, spin_system, Pass, Rotation, Transform};
+use crate::framebuffer::Framebuffer;
+use crate::lensing::GravitationalLens;
+use crate::matrix::{create_model_matrix, create_viewport_matrix, is_orthographic, Projection};
+use crate::nebula::Nebula;
+use crate::obj::Obj;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::scene_world::SceneWorld;
+use crate::secondary_view::InsetKind;
+use crate::shaders::{shader_quality, AccretionDiskShader, CmeShader, ShipShader};
+use crate::ship::SHIP_RADIUS;
+use crate::skybox::Skybox;
+use crate::solar_activity::SolarActivity;
+use crate::starfield::Starfield;
+use crate::trajectory::Trajectory;
+use crate::uniforms::Uniforms;
+use super::{starfield_config, AppState, DrawState, MIN_FAR, NEAR, SKYBOX_DIR, SUN_RADIUS};
+
+/// Dibuja el frame en `framebuffer` con el estado interpolado entre los dos últimos pasos: la
+/// escena, los efectos de pantalla, la vista secundaria y la captura del modo foto
+pub fn render(app: &mut AppState, framebuffer: &mut Framebuffer) {
+    // Estado interpolado para dibujar: a `alpha` del camino entre el paso anterior y el
+    // actual, es decir, `lag` segundos antes del último paso. Se calcula aparte en lugar de
+    // modificar el estado para que la simulación (y las repeticiones) no se vean afectadas;
+    // los ángulos de las órbitas se restauran en `finish_frame`
+    let alpha = app.timestep.alpha();
+    let lag = (1.0 - alpha) * app.timestep.step;
+    let elapsed_time = app.elapsed_time - lag;
+    let clock = app.clock.rewound(lag);
+    let mut camera = app.camera.interpolated(&app.previous_camera, alpha);
+    if app.photo_mode.is_active() {
+        app.photo_mode.apply(&mut camera);
+    } else if app.map_view.is_active() {
+        app.map_view.apply(&mut camera, app.scene.extent());
+    }
+    let lerp = |a: Vector3, b: Vector3| {
+        Vector3::new(a.x + (b.x - a.x) * alpha, a.y + (b.y - a.y) * alpha, a.z + (b.z - a.z) * alpha)
+    };
+    let mut ship = app.ship;
+    ship.position = lerp(app.previous_ship.0, app.ship.position);
+    // Ángulos de Euler: cada uno por el camino corto, el yaw salta de PI a -PI al dar la vuelta
+    let (from, to) = (app.previous_ship.1, app.ship.rotation);
+    ship.rotation = Vector3::new(lerp_angle(from.x, to.x, alpha), lerp_angle(from.y, to.y, alpha), lerp_angle(from.z, to.z, alpha));
+    let orbital_angles: Vec<f32> = app.scene.planets.iter().map(|planet| planet.orbital_angle).collect();
+    for planet in &mut app.scene.planets {
+        planet.orbital_angle -= planet.orbital_speed * lag;
+    }
+
+    // Los sistemas del mundo copian a las entidades el estado interpolado de este frame
+    app.scene_world.planet_system(&app.scene, elapsed_time, clock.hours());
+    app.scene_world.planetshine_system(&app.scene, elapsed_time);
+    // Relieve del planeta rocoso al que se ha acercado la nave
+    app.scene_world.terrain_system(&app.scene, &app.surface_terrain, ship.position, clock.hours(), elapsed_time);
+    // Anillos de cerca: rocas alrededor de la cámara en lugar del disco plano
+    app.scene_world.ring_system(&app.scene, &app.ring_particles, camera.view_eye(), clock.hours());
+    app.scene_world.asteroid_system(app.asteroid_belt.as_ref());
+    app.scene_world.projectile_system(&app.weapons);
+    // La nave rota exactamente igual que la cámara (ship.rotation = camera.yaw/pitch); las
+    // toberas quedan detrás según su rumbo (como la estela del motor) y brillan al empujar
+    let ship_spec = app.hangar.active();
+    let (pitch, yaw) = (ship.rotation.x, ship.rotation.y);
+    let nozzle_offset = ship.scale * SHIP_RADIUS * ship_spec.engine_offset;
+    let nozzle = Vector3::new(
+        ship.position.x - yaw.cos() * pitch.cos() * nozzle_offset,
+        ship.position.y - pitch.sin() * nozzle_offset,
+        ship.position.z - yaw.sin() * pitch.cos() * nozzle_offset,
+    );
+    let ship_shader = ShipShader::new(nozzle, ship.scale * SHIP_RADIUS * ship_spec.engine_size, if app.ship_thrusting { 1.0 } else { 0.0 });
+    let ship_transform = Transform { position: ship.position, rotation: Rotation::Euler(ship.rotation), scale: ship.scale };
+    app.scene_world.ship_system(ship_transform, &app.ship_model, app.ship_bounding_radius * ship.scale, ship_shader);
+    orbit_system(&mut app.scene_world.world, elapsed_time);
+    spin_system(&mut app.scene_world.world, elapsed_time, clock.hours());
+    // Mallas del nivel de detalle de la calidad actual
+    let sphere_lod = app.quality_governor.settings().sphere_lod;
+
+    // Captura del modo foto: este frame se renderiza a `supersampling` veces la ventana en cada
+    // eje y se guarda entero; el frame siguiente vuelve al tamaño de la escala de render
+    let photo_capture = app.photo_mode.take_capture_request();
+    let size = if photo_capture {
+        (app.window_width as u32 * app.photo_mode.supersampling, app.window_height as u32 * app.photo_mode.supersampling)
+    } else {
+        app.render_size()
+    };
+    if app.resize_pending || (framebuffer.width, framebuffer.height) != size {
+        resize_render_target(framebuffer, &mut app.starfield, size, app.quality_governor.settings().star_fraction);
+        app.resize_pending = false;
+    }
+    if framebuffer.gamma() != app.gamma {
+        framebuffer.set_gamma(app.gamma);
+    }
+    if framebuffer.dithering() != app.dithering {
+        framebuffer.set_dithering(app.dithering);
+    }
+    framebuffer.set_exposure(if app.photo_mode.is_active() { app.photo_mode.exposure() } else { 1.0 });
+    let fov_y = if app.photo_mode.is_active() { app.photo_mode.fov } else { app.field_of_view.current() };
+
+    framebuffer.clear();
+
+    // Dibujar estrellas en el skybox (titilan con el tiempo) y la Vía Láctea
+    // Usan una profundidad muy lejana para que estén detrás de todo
+    // Con un skybox de imágenes el fondo se rellena después de la geometría opaca
+    let skybox = app.assets.skybox(SKYBOX_DIR);
+    app.renderer.begin_object("Fondo");
+    if skybox.is_none() {
+        app.starfield.render(framebuffer, elapsed_time);
+    }
+
+    // Far plane dinámico: lo bastante lejos para abarcar todo el sistema desde la cámara
+    let far = app.scene.far_plane(camera.view_eye(), MIN_FAR);
+
+    let aspect = app.window_width as f32 / app.window_height as f32;
+    let projection = if app.map_view.is_active() {
+        app.map_view.projection(aspect, far)
+    } else {
+        Projection::Perspective { fov_y, aspect, near: NEAR, far }
+    };
+    let show_ship = !app.photo_mode.is_active() || app.photo_mode.show_ship;
+    let main_view = SceneView::new(&camera, &projection, framebuffer.width, framebuffer.height, show_ship && !camera.is_cockpit_view());
+    let scene_frame = SceneFrame {
+        scene: &app.scene,
+        elapsed_time,
+        sphere_lod,
+        sun_radius: SUN_RADIUS,
+        disk_meshes: &app.disk_meshes,
+        solar_activity: &app.solar_activity,
+        cme_mesh: &app.cme_mesh,
+        skybox: skybox.as_deref(),
+        nebula: app.nebula.as_ref(),
+        gravitational_lens: &app.gravitational_lens,
+        sun_corona: &app.sun_corona,
+    };
+    scene_frame.render(&app.renderer, &mut app.scene_world, framebuffer, &main_view);
+    let view_uniforms = main_view.uniforms(elapsed_time);
+    let sun_translation = Vector3::zero(); // Centro del sistema
+    let orthographic = projection.is_orthographic();
+
+    // Niebla de distancia: los cuerpos muy lejanos se desvanecen hacia el fondo
+    // (no en el mapa: todo el sistema está a la misma distancia de la cámara y se vería con niebla)
+    app.renderer.begin_object("Efectos");
+    if !orthographic {
+        framebuffer.fog_pass(far, far * 0.5, far * 0.95);
+    }
+
+    // Bloom: el sol, las luces de la estación, los circuitos, la lava y las toberas desbordan
+    // su silueta con un halo
+    if shader_quality().bloom() {
+        framebuffer.bloom_pass(1.5, 2);
+    }
+
+    // Rayos crepusculares: los planetas que pasan por delante del sol proyectan haces oscuros
+    if shader_quality().god_rays() && !orthographic {
+        let ray_color = srgb_to_linear_rgb(app.scene.star.palette().warm);
+        app.sun_corona.render_god_rays(framebuffer, &view_uniforms, sun_translation, SUN_RADIUS, ray_color);
+    }
+
+    // Partículas (mezcla aditiva, probadas contra la profundidad de lo opaco)
+    if show_ship {
+        app.engine_exhaust.render(framebuffer, &view_uniforms);
+    }
+    app.impact_debris.render(framebuffer, &view_uniforms);
+    for ejecta in &app.volcanic_ejecta {
+        ejecta.render(framebuffer, &view_uniforms);
+    }
+
+    // Trayectoria prevista con la gravedad si la nave deja de empujar (vuelo newtoniano manual)
+    let trajectory = if app.manual_flight && app.flight_model.feels_gravity() && app.flight_model.config.trajectory_seconds > 0.0 {
+        let config = &app.flight_model.config;
+        Trajectory::predict(&app.scene, SUN_RADIUS, elapsed_time, ship.position, app.flight_model.velocity, config.gravity, config.trajectory_seconds)
+    } else {
+        Trajectory::default()
+    };
+    trajectory.render(&app.renderer, framebuffer, &view_uniforms, Vector3::new(0.35, 1.0, 0.55));
+
+    // Terminador y altura del sol sobre el planeta seleccionado
+    app.daylight.render(&app.renderer, framebuffer, &view_uniforms, &app.scene, clock.hours());
+
+    // Ruta planificada (después de la niebla, como los gizmos)
+    if !app.route.is_empty() {
+        app.route.render(&app.renderer, framebuffer, &view_uniforms, &app.scene, elapsed_time, ship.position);
+    }
+
+    // Órbitas y marcadores del mapa
+    if app.map_view.is_active() {
+        app.map_view.render_overlay(&app.renderer, framebuffer, &view_uniforms, &app.scene, ship.position);
+    }
+
+    // Gizmos de depuración (después de la niebla para que no se desvanezcan)
+    if app.debug_draw.any_enabled() {
+        let debug_objects = app.scene_world.world.debug_objects(sphere_lod);
+        app.debug_draw.draw(&app.renderer, framebuffer, &view_uniforms, &debug_objects, ship.position, app.ship_velocity);
+    }
+
+    // Destello de lente del sol: se compone al final para probar oclusión con toda la escena
+    // El radio incluye un margen por las prominencias del vertex shader del sol
+    if !orthographic {
+        app.lens_flare.render(framebuffer, &view_uniforms, sun_translation, SUN_RADIUS * 1.1);
+    }
+
+    // Efecto de túnel de warp: estiramiento radial + aberración cromática en post-proceso
+    // La intensidad sube hasta la mitad del warp y vuelve a cero al llegar
+    if app.warp_system.is_warping && !orthographic {
+        let warp_strength = (1.0 - (app.warp_system.warp_progress - 0.5).abs() * 2.0).max(0.0);
+        framebuffer.warp_tunnel_pass(warp_strength);
+    }
+
+    // Interferencias mientras el frente de plasma atraviesa la nave
+    framebuffer.static_pass(app.screen_shake, elapsed_time);
+
+    // Vista secundaria (retrovisor o cámara del planeta seguido, o si no del más cercano): la
+    // escena se vuelve a dibujar desde otra cámara en su propio framebuffer y se compone sobre
+    // la imagen. No en el modo foto ni en el mapa
+    let inset = match app.secondary_view.kind() {
+        _ if app.photo_mode.is_active() || app.map_view.is_active() => None,
+        Some(InsetKind::RearView) => {
+            let (pitch, yaw) = (ship.rotation.x, ship.rotation.y);
+            let heading = Vector3::new(yaw.cos() * pitch.cos(), pitch.sin(), yaw.sin() * pitch.cos());
+            let lift = ship.scale * SHIP_RADIUS * 0.6;
+            Some((app.secondary_view.rear_camera(ship.position, heading, lift), String::from("Retrovisor")))
+        }
+        Some(InsetKind::PlanetCam) => {
+            let distance_to_ship = |position: Vector3| {
+                let (dx, dy, dz) = (position.x - ship.position.x, position.y - ship.position.y, position.z - ship.position.z);
+                dx * dx + dy * dy + dz * dz
+            };
+            camera
+                .get_tracking_planet()
+                .or_else(|| {
+                    (0..app.scene.planets.len())
+                        .min_by(|&a, &b| distance_to_ship(app.scene.planets[a].position()).total_cmp(&distance_to_ship(app.scene.planets[b].position())))
+                })
+                .map(|idx| {
+                    let planet = &app.scene.planets[idx];
+                    (app.secondary_view.planet_camera(planet.position(), planet.scale, elapsed_time), planet.name.clone())
+                })
+        }
+        None => None,
+    };
+    if let Some((inset_camera, _)) = &inset {
+        let (x, y) = app.secondary_view.prepare(framebuffer.width, framebuffer.height);
+        let inset_far = app.scene.far_plane(inset_camera.view_eye(), MIN_FAR);
+        let show_ship = app.secondary_view.kind() != Some(InsetKind::RearView);
+        let (width, height) = (app.secondary_view.framebuffer.width, app.secondary_view.framebuffer.height);
+        let inset_view = SceneView::new(inset_camera, &app.secondary_view.projection(inset_far), width, height, show_ship);
+        app.renderer.begin_object("Vista secundaria");
+        scene_frame.render(&app.renderer, &mut app.scene_world, &mut app.secondary_view.framebuffer, &inset_view);
+        app.secondary_view.framebuffer.fog_pass(inset_far, inset_far * 0.5, inset_far * 0.95);
+        framebuffer.composite(&app.secondary_view.framebuffer, x, y);
+    }
+
+    if photo_capture {
+        let path = app.photo_mode.capture_path();
+        match framebuffer.export(&path) {
+            Ok(()) => {
+                println!("Captura guardada en {} ({}x{})", path, framebuffer.width, framebuffer.height);
+                app.photo_mode.notify(format!("Guardada en {}", path));
+            }
+            Err(e) => {
+                eprintln!("Error al guardar {}: {}", path, e);
+                app.photo_mode.notify(String::from("Error al guardar la captura"));
+            }
+        }
+    }
+
+    app.renderer.end_object();
+    app.draw_state = Some(DrawState {
+        elapsed_time,
+        clock,
+        camera,
+        ship,
+        view_uniforms,
+        inset_title: inset.map(|(_, title)| title),
+        trajectory,
+        orbital_angles,
+    });
+}
+/// Cambiar la resolución del framebuffer y regenerar las estrellas para ella (la textura de la
+/// ventana se recrea al presentarlo)
+fn resize_render_target(framebuffer: &mut Framebuffer, starfield: &mut Starfield, size: (u32, u32), star_fraction: f32) {
+    if (framebuffer.width, framebuffer.height) != size {
+        framebuffer.resize(size.0, size.1);
+    }
+    *starfield = Starfield::new(starfield_config(star_fraction), size.0 as i32, size.1 as i32);
+}
+/// Lo que se dibuja además de las entidades de `SceneWorld` en un frame, ya interpolado: el
+/// fondo, los discos de acreción y las eyecciones. Se construye una vez por frame y se dibuja
+/// con `render` desde la vista principal y desde cada vista secundaria
+#[derive(Clone, Copy)]
+struct SceneFrame<'a> {
+    scene: &'a Scene,
+    elapsed_time: f32,
+    sphere_lod: usize, // Nivel de detalle de las mallas de los cuerpos (calidad adaptativa)
+    sun_radius: f32,
+    disk_meshes: &'a [Obj],
+    solar_activity: &'a SolarActivity,
+    cme_mesh: &'a Obj,
+    skybox: Option<&'a Skybox>,
+    nebula: Option<&'a Nebula>,
+    gravitational_lens: &'a GravitationalLens,
+    sun_corona: &'a SunCorona,
+}
+/// Cámara, proyección y destino de una vista del sistema (la principal o una secundaria)
+#[derive(Clone, Copy)]
+struct SceneView {
+    view_matrix: Matrix,
+    projection_matrix: Matrix,
+    viewport_matrix: Matrix, // Del tamaño del framebuffer en el que se dibuja la vista
+    eye: Vector3,
+    show_ship: bool,         // false en cabina (la cámara está dentro de la nave) o si el modo foto la oculta
+}
+impl SceneView {
+    /// Vista desde `camera` con `projection`, para un framebuffer de `width` x `height`
+    fn new(camera: &Camera, projection: &Projection, width: u32, height: u32, show_ship: bool) -> Self {
+        SceneView {
+            view_matrix: camera.get_view_matrix(),
+            projection_matrix: projection.matrix(),
+            viewport_matrix: create_viewport_matrix(0.0, 0.0, width as f32, height as f32),
+            eye: camera.view_eye(),
+            show_ship,
+        }
+    }
+
+    /// Uniforms de la vista sin matriz de modelo, para lo que solo proyecta puntos del mundo
+    /// (partículas, etiquetas, rutas, gizmos)
+    fn uniforms(&self, time: f32) -> Uniforms {
+        Uniforms::new(Matrix::identity(), self.view_matrix, self.projection_matrix, self.viewport_matrix, time, self.eye, Vector3::zero())
+    }
+}
+impl SceneFrame<'_> {
+    /// Dibuja las entidades de `world` (ya colocadas por sus sistemas) y el fondo en
+    /// `framebuffer` desde `view`: la pasada opaca y la translúcida, sin los efectos de pantalla
+    fn render(&self, renderer: &Renderer, world: &mut SceneWorld, framebuffer: &mut Framebuffer, view: &SceneView) {
+        let SceneFrame {
+            scene,
+            elapsed_time,
+            sphere_lod,
+            disk_meshes,
+            solar_activity,
+            cme_mesh,
+            skybox,
+            nebula,
+            gravitational_lens,
+            sun_corona,
+            sun_radius,
+            ..
+        } = *self;
+        let SceneView { view_matrix, projection_matrix, viewport_matrix, eye, .. } = *view;
+        let view_uniforms = view.uniforms(elapsed_time);
+
+        // Los cuerpos son entidades que los sistemas ya colocaron este frame; solo la nave
+        // depende de la vista
+        world.set_ship_visible(view.show_ship);
+        render_system(&mut world.world, renderer, framebuffer, &view_uniforms, Pass::Opaque, sphere_lod);
+
+        // Skybox de imágenes: solo en los píxeles donde no se dibujó geometría
+        renderer.begin_object("Fondo");
+        // Va antes de los anillos porque la pasada translúcida no escribe profundidad
+        // En el mapa no hay fondo en perspectiva: todos los rayos de la proyección ortográfica son paralelos
+        let orthographic = is_orthographic(&projection_matrix);
+        if let Some(skybox) = &skybox
+            && !orthographic
+        {
+            skybox.render(framebuffer, &view_uniforms);
+        }
+
+        // Nebulosa: brillo aditivo sobre el fondo (las estrellas siguen viéndose a través del gas)
+        if let Some(nebula) = &nebula
+            && !orthographic
+        {
+            nebula.render(framebuffer, &view_uniforms);
+        }
+
+        // Corona del sol: billboard aditivo a la profundidad de su centro, detrás de la malla
+        if !orthographic {
+            let corona_color = srgb_to_linear_rgb(scene.star.palette().warm);
+            sun_corona.render_corona(framebuffer, &view_uniforms, Vector3::zero(), sun_radius, corona_color, scene.star.corona_strength);
+        }
+
+        // Lente gravitacional: curva las estrellas y los cuerpos que quedan detrás de cada agujero
+        // negro. Antes de la pasada translúcida para que el disco de acreción no se distorsione
+        for hole in scene.black_holes.iter().filter(|_| !orthographic) {
+            gravitational_lens.render(framebuffer, &view_uniforms, hole.position, hole.radius);
+        }
+
+        // Pasada translúcida: anillos y auroras mezclados sobre todo lo opaco ya dibujado
+        render_system(&mut world.world, renderer, framebuffer, &view_uniforms, Pass::Translucent, sphere_lod);
+
+        // Discos de acreción, inclinados respecto a la eclíptica; el giro lo anima el shader
+        renderer.begin_object("Agujeros negros");
+        for (hole, disk_mesh) in scene.black_holes.iter().zip(disk_meshes) {
+            let disk_uniforms = Uniforms::new(
+                create_model_matrix(hole.position, 1.0, hole.disk_rotation()),
+                view_matrix,
+                projection_matrix,
+                viewport_matrix,
+                elapsed_time,
+                eye,
+                Vector3::zero(), // Emisivo: no depende del sol
+            );
+            let disk_shader = AccretionDiskShader::new(hole.disk_inner, hole.disk_outer, hole.disk_temperature, hole.disk_speed);
+            renderer.draw_indexed_mesh(framebuffer, &disk_uniforms, &disk_mesh.vertices, &disk_mesh.indices, &disk_shader);
+        }
+
+        // Frentes de plasma de las eyecciones activas, con el color de la estrella
+        renderer.begin_object("Eyecciones");
+        let plasma_color = srgb_to_linear_rgb(scene.star.palette().warm);
+        for ejection in solar_activity.ejections() {
+            let cme_uniforms = Uniforms::new(
+                ejection.model_matrix(elapsed_time),
+                view_matrix,
+                projection_matrix,
+                viewport_matrix,
+                elapsed_time,
+                eye,
+                Vector3::zero(), // Emisivo: no depende del sol
+            );
+            let cme_shader = CmeShader::new(plasma_color, ejection.half_angle, ejection.intensity(elapsed_time));
+            renderer.draw_indexed_mesh(framebuffer, &cme_uniforms, &cme_mesh.vertices, &cme_mesh.indices, &cme_shader);
+        }
+    }
+}
@@ -0,0 +1,1125 @@
+use raylib::prelude::*;
+use crate::asteroids::{AsteroidBelt, AsteroidEvent};
+use crate::camera::{Camera, CameraMode};
+use crate::camera_path::CameraPath;
+use crate::docking::DockingState;
+use crate::eclipses::{EclipseEvent, EclipseKind, Observer};
+use crate::ephemeris::Ephemeris;
+use crate::flight_model::FlightMode;
+use crate::input_map::Action;
+use crate::nebula::Nebula;
+use crate::parking_orbit::ParkingOrbit;
+use crate::particles::{Attractor, ParticleEmitter};
+use crate::procedural::generate_system;
+use crate::route::{Route, Waypoint};
+use crate::sas::SasMode;
+use crate::scene::BodyRef;
+use crate::scene_world::SceneWorld;
+#[cfg(feature = "scripting")]
+use crate::scripting::ScriptContext;
+use crate::secondary_view::InsetKind;
+use crate::shaders::{set_shader_quality, shader_quality};
+use crate::ship::SHIP_RADIUS;
+use crate::solar_activity::{SolarActivity, SolarEvent};
+use crate::spatial_index::SpatialIndex;
+use crate::trajectory::{gravity_at, gravity_sources};
+use crate::volcanism::VolcanicEvent;
+use crate::weapons::WeaponEvent;
+use super::{generate_disk_meshes, nearest_station, AppState, ASTEROID_COUNT, CAMERA_PATH_FILE, ORBIT_CAPTURE_DISTANCE, RENDER_SCALES, SUN_RADIUS};
+use std::f32::consts::PI;
+
+/// Un paso fijo de la simulación con las pulsaciones acumuladas desde el anterior
+pub(super) fn step(app: &mut AppState) {
+    let delta_time = app.timestep.step;
+    // Solo el primer paso del frame ve las pulsaciones; los demás, las teclas mantenidas
+    let input = app.pending_input.clone();
+    app.pending_input = app.pending_input.held();
+    app.script_events.clear();
+    app.previous_camera = app.camera.clone();
+    app.previous_ship = (app.ship.position, app.ship.rotation);
+    app.elapsed_time += delta_time;
+    app.clock.advance(delta_time);
+
+    // Cualquier entrada del usuario cancela el piloto automático
+    // (salvo durante el atraque: los controles están bloqueados)
+    if app.autopilot.is_active() && !app.docking.is_input_locked() && input.any_pressed {
+        app.autopilot.cancel();
+        println!("Piloto automático cancelado");
+    }
+
+    // Procesar entrada de la cámara (la nave seguirá a la cámara)
+    // Deshabilitar input durante el warp para evitar interferencias
+    // También durante la reproducción de una trayectoria y con el piloto automático
+    app.manual_flight = !app.warp_system.is_warping && app.path_playback_start.is_none() && !app.tour.is_active() && !app.autopilot.is_active() && app.parking_orbit.is_none() && !app.docking.is_input_locked();
+    if app.manual_flight {
+        // Los controles solo dan la dirección del empuje (`pan_command`, ya suavizada); el
+        // modelo de vuelo integra la velocidad y el combustible paga el empuje que usa (sin
+        // él, la nave sigue a la deriva)
+        let eye_before = app.camera.eye;
+        app.camera.process_input(&input, delta_time);
+        // El SAS gira la proa y, en newtoniano, puede pedir empuje para igualar velocidades
+        let relative_velocity = app.sas.relative_velocity(&app.scene, app.elapsed_time, app.flight_model.velocity);
+        let sas_command = app.sas.update(&mut app.camera, &input, relative_velocity, delta_time);
+        let requested = Vector3::new(app.camera.eye.x - eye_before.x, app.camera.eye.y - eye_before.y, app.camera.eye.z - eye_before.z);
+        let command = match sas_command {
+            Some(command) if app.flight_model.mode == FlightMode::Newtonian => command,
+            _ => app.camera.pan_command(),
+        };
+        if app.camera.is_tracking() || app.camera.mode == CameraMode::Orbit {
+            // La posición la decide el seguimiento o la órbita
+            app.flight_model.stop();
+        } else if app.flight_model.feels_gravity() {
+            let sources = gravity_sources(&app.scene, SUN_RADIUS, app.elapsed_time, 0.0);
+            app.flight_model.accelerate(gravity_at(&sources, app.ship.position, app.flight_model.config.gravity), delta_time);
+        }
+        let applied = app.flight_model.update(command, app.camera.forward(), app.ship_systems.has_fuel(), delta_time);
+        app.ship_systems.thrust(app.flight_model.output(), delta_time);
+        let correction = Vector3::new(applied.x - requested.x, applied.y - requested.y, applied.z - requested.z);
+        app.camera.eye = Vector3::new(app.camera.eye.x + correction.x, app.camera.eye.y + correction.y, app.camera.eye.z + correction.z);
+        app.camera.target = Vector3::new(app.camera.target.x + correction.x, app.camera.target.y + correction.y, app.camera.target.z + correction.z);
+    } else {
+        // Warp, trayectoria, piloto automático, órbita o atraque mueven la nave por su cuenta
+        app.flight_model.stop();
+        app.camera.stop_motion();
+        app.ship_systems.thrust(0.0, delta_time);
+    }
+    app.ship_systems.update(delta_time);
+
+    if input.is_pressed(Action::FlightModel) {
+        app.flight_model.toggle();
+        println!("Modelo de vuelo: {}", app.flight_model.mode.name());
+    }
+
+    if Action::INTERFACE.iter().any(|&action| input.is_pressed(action)) {
+        app.sound.click();
+    }
+    if input.is_pressed(Action::VolumeDown) || input.is_pressed(Action::VolumeUp) {
+        app.sound.adjust_master(if input.is_pressed(Action::VolumeUp) { 0.1 } else { -0.1 });
+        println!("Volumen: {:.0}%", app.sound.config.master * 100.0);
+    }
+
+    if input.is_pressed(Action::Minimap) {
+        app.minimap.toggle();
+    }
+    if input.is_pressed(Action::SecondaryView) {
+        match app.secondary_view.cycle() {
+            Some(InsetKind::RearView) => println!("Vista secundaria: retrovisor"),
+            Some(InsetKind::PlanetCam) => println!("Vista secundaria: cámara del planeta"),
+            None => println!("Vista secundaria oculta"),
+        }
+    }
+    if input.is_pressed(Action::Labels) {
+        app.labels.toggle();
+    }
+    // Día y noche: el planeta bajo la mira o, si no hay ninguno, el más cercano a la nave
+    if input.is_pressed(Action::Daylight) {
+        let selected = match body_under_crosshair(&app.body_index, &app.camera) {
+            Some(BodyRef::Planet(idx)) => Some(idx),
+            _ => app.scene
+                .planets
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    let distance = |p: Vector3| (p.x - app.ship.position.x).powi(2) + (p.y - app.ship.position.y).powi(2) + (p.z - app.ship.position.z).powi(2);
+                    distance(a.position()).total_cmp(&distance(b.position()))
+                })
+                .map(|(idx, _)| idx),
+        };
+        match app.daylight.toggle(selected).and_then(|idx| app.scene.planets.get(idx)) {
+            Some(planet) => println!("Día y noche: {}", planet.name),
+            None => println!("Día y noche oculto"),
+        }
+    }
+    if input.is_pressed(Action::DebugAxes) {
+        app.debug_draw.axes = !app.debug_draw.axes;
+    }
+    if input.is_pressed(Action::DebugBounds) {
+        app.debug_draw.bounding_spheres = !app.debug_draw.bounding_spheres;
+    }
+    if input.is_pressed(Action::DebugVelocity) {
+        app.debug_draw.velocity = !app.debug_draw.velocity;
+    }
+    if input.is_pressed(Action::DebugNormals) {
+        app.debug_draw.normals = !app.debug_draw.normals;
+    }
+    if input.is_pressed(Action::DebugStats) {
+        app.stats_overlay.toggle();
+        app.renderer.profiling = app.stats_overlay.visible || app.benchmark.is_some();
+    }
+    if input.is_pressed(Action::FrameGraph) {
+        app.frame_graph.toggle();
+    }
+    // Elegir la calidad de los shaders a mano deja fija la calidad adaptativa
+    if input.is_pressed(Action::ShaderQuality) {
+        let quality = shader_quality().next();
+        set_shader_quality(quality);
+        app.quality_governor.config.adaptive = false;
+        println!("Calidad de los shaders: {} (calidad adaptativa desactivada)", quality.name());
+    }
+
+    if input.is_pressed(Action::RenderScale) {
+        app.render_scale_idx = (app.render_scale_idx + 1) % RENDER_SCALES.len();
+        app.resize_pending = true;
+        let scale = RENDER_SCALES[app.render_scale_idx] * app.quality_governor.settings().render_scale;
+        let (render_width, render_height) = app.render_size();
+        println!("Escala de render: {}x ({}x{})", scale, render_width, render_height);
+    }
+
+    // Nuevo sistema procedural: la siguiente semilla (o una del reloj si no había ninguna)
+    if input.is_pressed(Action::NewSystem) {
+        let seed = app.system_seed.map_or(app.new_system_seed, |seed| seed.wrapping_add(1));
+        app.system_seed = Some(seed);
+        app.scene = generate_system(seed);
+        app.scene_world = SceneWorld::new(&app.scene, &app.body_meshes, &app.ring_particles);
+        app.disk_meshes = generate_disk_meshes(&app.scene);
+        app.nebula = app.scene.nebula.clone().map(Nebula::new);
+        app.renderer.light.color = app.scene.star.light_color();
+        app.solar_activity = SolarActivity::for_star(&app.scene.star, app.scene.extent() * 1.1);
+        app.solar_activity.reset(app.elapsed_time);
+        app.asteroid_belt = AsteroidBelt::for_scene(&app.scene, ASTEROID_COUNT, seed);
+        if let Some(belt) = &mut app.asteroid_belt {
+            belt.reset(app.elapsed_time);
+        }
+        app.weapons.clear();
+        app.volcanic_activity.reset();
+        app.eclipse_forecast.reset();
+        app.volcanic_ejecta.clear();
+        app.docking.abort();
+        app.route.clear();
+        // Los índices de planetas y lunas anteriores ya no son válidos
+        app.sas.target = None;
+        app.daylight.planet = None;
+        app.autopilot.cancel();
+        app.parking_orbit = None;
+        app.camera.track_planet(None);
+        app.tour.stop();
+        app.next_warp_moon = 0;
+        // Los scripts eran de la escena del archivo
+        #[cfg(feature = "scripting")]
+        {
+            app.scripts = None;
+        }
+        println!(
+            "Sistema generado con la semilla {} (estrella {} de {:.0} K, {} planetas, {} lunas)",
+            seed,
+            app.scene.star.spectral_class().letter(),
+            app.scene.star.temperature(),
+            app.scene.planets.len(),
+            app.scene.moons.len(),
+        );
+    }
+    app.body_index = app.scene.body_index(app.elapsed_time);
+
+    // Actividad solar: los sucesos se atienden aquí (avisos y temblor de pantalla)
+    if input.is_pressed(Action::CoronalMassEjection) {
+        app.solar_activity.trigger(app.elapsed_time, app.ship.position);
+        println!("Eyección de masa coronal lanzada hacia la nave");
+    }
+    for event in app.solar_activity.update(app.elapsed_time, app.ship.position) {
+        match event {
+            SolarEvent::Eruption { direction, .. } => {
+                println!("¡Erupción solar! Eyección de masa coronal hacia ({:.2}, {:.2}, {:.2})", direction.x, direction.y, direction.z);
+            }
+            SolarEvent::ShockArrived { strength, .. } => {
+                app.screen_shake = app.screen_shake.max(strength);
+                println!("El frente de plasma alcanza la nave (fuerza {:.0}%)", strength * 100.0);
+            }
+            SolarEvent::ShockPassed { .. } => println!("El frente de plasma ha pasado"),
+            SolarEvent::Dissipated { .. } => {}
+        }
+    }
+    let exposure = app.solar_activity.exposure(app.ship.position, app.elapsed_time);
+    app.screen_shake = (app.screen_shake - delta_time * 1.5).max(exposure).max(0.0);
+
+    // Cada frente que atraviesa un planeta desata una tormenta que se apaga en unos 20 s
+    app.scene_world.aurora_system(&app.scene, &app.solar_activity, app.elapsed_time, delta_time);
+
+    // Eclipses: se avisa en la consola de los que ve la nave y de los totales
+    for event in app.eclipse_forecast.update(app.elapsed_time, &app.scene, app.ship.position) {
+        match event {
+            EclipseEvent::Started(eclipse) if eclipse.observer == Observer::Ship || eclipse.kind == EclipseKind::Total => {
+                println!("{}", eclipse.headline(&app.scene, app.elapsed_time));
+                app.script_events.push(("eclipse", eclipse.headline(&app.scene, app.elapsed_time)));
+            }
+            EclipseEvent::Ended(eclipse) if eclipse.observer == Observer::Ship => println!("El eclipse ha terminado"),
+            _ => {}
+        }
+    }
+
+    // Ritmo del reloj de la simulación: , lo divide entre 2 y . lo duplica
+    if input.is_pressed(Action::SlowerTime) {
+        app.clock.scale_rate(0.5);
+        println!("Reloj: {:.2} horas simuladas por segundo", app.clock.hours_per_second);
+    }
+    if input.is_pressed(Action::FasterTime) {
+        app.clock.scale_rate(2.0);
+        println!("Reloj: {:.2} horas simuladas por segundo", app.clock.hours_per_second);
+    }
+
+    // Gamma de salida: [ y ] la ajustan en pasos de 0.1 (2.2 ≈ sRGB)
+    if input.is_pressed(Action::GammaDown) {
+        app.gamma = (app.gamma - 0.1).max(1.0);
+        println!("Gamma: {:.1}", app.gamma);
+    }
+    if input.is_pressed(Action::GammaUp) {
+        app.gamma = (app.gamma + 0.1).min(3.0);
+        println!("Gamma: {:.1}", app.gamma);
+    }
+    // Tramado ordenado al pasar a 8 bits (evita las bandas en los degradados)
+    if input.is_pressed(Action::Dithering) {
+        app.dithering = !app.dithering;
+        println!("Tramado del color: {}", if app.dithering { "activado" } else { "desactivado" });
+    }
+
+    // Grabación y reproducción de trayectorias de cámara
+    if input.is_pressed(Action::PathRecord) && app.path_playback_start.is_none() {
+        if app.camera_path.keyframes.is_empty() {
+            app.path_record_start = app.elapsed_time;
+        }
+        app.camera_path.add_keyframe(app.camera.view_eye(), app.camera.view_target(), app.elapsed_time - app.path_record_start);
+        println!("Keyframe {} grabado", app.camera_path.keyframes.len());
+    }
+    if input.is_pressed(Action::PathClear) && app.path_playback_start.is_none() {
+        app.camera_path.clear();
+        println!("Trayectoria borrada");
+    }
+    if input.is_pressed(Action::PathPlay) {
+        if app.path_playback_start.is_some() {
+            app.path_playback_start = None;
+        } else if app.camera_path.is_playable() {
+            app.path_playback_start = Some(app.elapsed_time);
+        }
+    }
+    if input.is_pressed(Action::PathSave) {
+        match app.camera_path.save(CAMERA_PATH_FILE) {
+            Ok(()) => println!("Trayectoria guardada en {}", CAMERA_PATH_FILE),
+            Err(e) => eprintln!("Error al guardar {}: {}", CAMERA_PATH_FILE, e),
+        }
+    }
+    if input.is_pressed(Action::PathLoad) && app.path_playback_start.is_none() {
+        match CameraPath::load(CAMERA_PATH_FILE) {
+            Ok(loaded) => {
+                println!("Trayectoria cargada: {} keyframes", loaded.keyframes.len());
+                app.camera_path = loaded;
+            }
+            Err(e) => eprintln!("Error al cargar {}: {}", CAMERA_PATH_FILE, e),
+        }
+    }
+
+    // Visita guiada: se lleva la cámara como una trayectoria, así que para la reproducción,
+    // el seguimiento y el piloto automático
+    if input.is_pressed(Action::Tour) && !app.docking.is_input_locked() {
+        if app.tour.is_active() {
+            app.tour.stop();
+            println!("Visita guiada detenida");
+        } else if !app.warp_system.is_warping && app.tour.start(&app.scene, app.elapsed_time, app.camera.view_eye(), app.camera.view_target()) {
+            app.path_playback_start = None;
+            app.autopilot.cancel();
+            app.parking_orbit = None;
+            app.camera.track_planet(None);
+            println!("Visita guiada: {} paradas", app.scene.bodies().count());
+        }
+    }
+
+    // Seguimiento de planetas: 1-5 fijan la cámara en un planeta, / sigue a las lunas y 0 vuelve al modo libre
+    // Con Shift, 1-5 activan el piloto automático hacia ese planeta
+    if !app.warp_system.is_warping && !app.docking.is_input_locked() {
+        let shift_down = input.is_down(Action::Modifier);
+        for (planet_idx, action) in Action::TRACK_PLANET.iter().enumerate() {
+            if !input.is_pressed(*action) || planet_idx >= app.scene.planets.len() {
+                continue;
+            }
+            if shift_down {
+                let planet = &app.scene.planets[planet_idx];
+                app.parking_orbit = None;
+                app.autopilot.engage(
+                    BodyRef::Planet(planet_idx),
+                    app.ship.position,
+                    app.camera.forward(),
+                    planet.position(),
+                    planet.scale,
+                );
+                // La nave vuela con la cámara detrás (o en cabina)
+                app.camera.track_planet(None);
+                if app.camera.mode != CameraMode::Chase && app.camera.mode != CameraMode::Cockpit {
+                    app.camera.set_mode(CameraMode::Chase);
+                }
+                println!("Piloto automático hacia {}", planet.name);
+            } else {
+                app.camera.track_planet(Some(planet_idx));
+            }
+        }
+        if shift_down && input.is_pressed(Action::DockAutopilot) {
+            let station = nearest_station(&app.body_index, app.ship.position);
+            if let Some((body, (port, axis))) = station.and_then(|body| Some((body, app.scene.docking_point(body, app.elapsed_time)?))) {
+                app.parking_orbit = None;
+                app.autopilot.engage_dock(body, app.ship.position, app.camera.forward(), port, axis);
+                app.docking.begin(body, app.autopilot.docking_distance);
+                app.camera.track_planet(None);
+                if app.camera.mode != CameraMode::Chase && app.camera.mode != CameraMode::Cockpit {
+                    app.camera.set_mode(CameraMode::Chase);
+                }
+                println!("Piloto automático: atraque en {}", app.scene.body_name(body).unwrap_or("estación"));
+            }
+        }
+        // Las lunas se recorren una a una; tras la última se vuelve al modo libre
+        if input.is_pressed(Action::TrackMoon) {
+            let next = app.camera.get_tracking_moon().map_or(0, |idx| idx + 1);
+            if next < app.scene.moons.len() {
+                app.camera.track_moon(Some(next));
+                println!("Siguiendo a {}", app.scene.moons[next].name);
+            } else {
+                app.camera.track_planet(None);
+            }
+        }
+        if input.is_pressed(Action::FreeCamera) {
+            app.camera.track_planet(None);
+            if app.camera.mode == CameraMode::Orbit {
+                app.camera.set_mode(CameraMode::Chase);
+            }
+        }
+    }
+
+    // La nave ya no procesa input directamente, sigue a la cámara
+    // ship.process_input(&window, delta_time); // Deshabilitado - la nave sigue a la cámara
+    app.ship.update(delta_time);
+
+    // ======================================
+    // EJEMPLO: Rotar el modelo 3D directamente por código
+    // ======================================
+    // Para rotar el modelo, descomenta las siguientes líneas:
+    //
+    // // 1. Habilitar rotación directa (desactiva la sincronización con la cámara)
+    // ship.set_direct_rotation(true);
+    //
+    // // 2. ROTAR EL MODELO - Métodos más directos:
+    //
+    // // Rotar agregando rotación (más común):
+    // ship.rotate_pitch(0.1);        // Rota 0.1 radianes en X
+    // ship.rotate_yaw(0.2);          // Rota 0.2 radianes en Y
+    // ship.rotate_roll(0.05);        // Rota 0.05 radianes en Z
+    //
+    // // O rotar todos los ejes a la vez:
+    // ship.rotate(0.1, 0.2, 0.05);   // Rota en todos los ejes
+    //
+    // // Rotar usando grados (más intuitivo):
+    // ship.rotate_pitch_degrees(45.0);  // Rota 45 grados en X
+    // ship.rotate_yaw_degrees(90.0);    // Rota 90 grados en Y
+    // ship.rotate_roll_degrees(180.0);  // Rota 180 grados en Z
+    //
+    // // O rotar todos los ejes usando grados:
+    // ship.rotate_degrees(45.0, 90.0, 0.0);
+    //
+    // // Rotación continua (útil para animaciones):
+    // ship.rotate_continuous(0.0, 1.0, 0.0, delta_time); // Rota 1 rad/seg en Y
+    // ship.rotate_continuous_degrees(0.0, 90.0, 0.0, delta_time); // Rota 90°/seg en Y
+    //
+    // // Establecer rotación absoluta:
+    // ship.set_rotation(0.5, 1.0, 0.0);              // En radianes
+    // ship.set_rotation_degrees(45.0, 90.0, 0.0);    // En grados
+    //
+    // // Resetear rotación:
+    // ship.reset_rotation(); // Vuelve a (0, 0, 0)
+    //
+    // // Para volver a la sincronización con la cámara:
+    // ship.set_direct_rotation(false);
+    // ======================================
+
+    // Manejar teletransporte (warp) con las acciones `Action::WARP` - ahora sobre la nave
+    // Warp1 = Vista general, Warp2 = Sol, Warp3-Warp7 = Planetas 1-5, Warp8 = las lunas por turnos
+    if !app.warp_system.is_warping && !app.docking.is_input_locked() {
+        // El warp de la consola no gasta carga
+        let mut warp_target = app.console_warp.take();
+        for (waypoint_idx, action) in Action::WARP.iter().enumerate() {
+            if input.is_pressed(*action) {
+                if !app.ship_systems.try_warp() {
+                    println!("Carga de warp insuficiente ({:.0}%)", app.ship_systems.warp_charge * 100.0);
+                    break;
+                }
+
+                // Calcular waypoint basado en las posiciones actuales de los planetas
+                warp_target = Some(match waypoint_idx {
+                    0 => {
+                        // Vista general del sistema
+                        Vector3::new(0.0, 40.0, 60.0)
+                    },
+                    1 => {
+                        // Cerca del Sol
+                        Vector3::new(0.0, 8.0, 12.0)
+                    },
+                    i if i >= 2 && i <= 6 => {
+                        // Waypoints 2-6: Cada planeta
+                        let planet_idx = i - 2;
+                        if planet_idx < app.scene.planets.len() {
+                            let planet = &app.scene.planets[planet_idx];
+                            let orbit_x = planet.orbital_radius * planet.orbital_angle.cos();
+                            let orbit_z = planet.orbital_radius * planet.orbital_angle.sin();
+                            Vector3::new(orbit_x, 5.0, orbit_z)
+                        } else {
+                            Vector3::new(0.0, 25.0, 50.0)
+                        }
+                    },
+                    7 if !app.scene.moons.is_empty() => {
+                        // Waypoint 7: recorrer las lunas en orden con cada pulsación
+                        let moon_idx = app.next_warp_moon % app.scene.moons.len();
+                        app.next_warp_moon = moon_idx + 1;
+                        let moon_pos = app.scene.moon_position(moon_idx, app.elapsed_time);
+                        Vector3::new(moon_pos.x, moon_pos.y + 2.0, moon_pos.z)
+                    },
+                    _ => Vector3::new(0.0, 40.0, 60.0),
+                });
+                break;
+            }
+        }
+
+        // Warp al mejor sitio para ver el eclipse del aviso, mirando hacia el sol
+        if warp_target.is_none() && input.is_pressed(Action::EclipseWarp) {
+            let arrival = app.elapsed_time + app.warp_system.warp_duration;
+            let spot = app.eclipse_forecast.featured(app.elapsed_time).and_then(|eclipse| {
+                let at = if eclipse.is_active(arrival) { arrival } else { eclipse.peak.max(arrival) };
+                Some((eclipse, app.eclipse_forecast.viewing_spot(eclipse, &app.scene, app.elapsed_time, at)?))
+            });
+            match spot {
+                None => println!("No hay ningún eclipse previsto"),
+                Some(_) if !app.ship_systems.try_warp() => {
+                    println!("Carga de warp insuficiente ({:.0}%)", app.ship_systems.warp_charge * 100.0);
+                }
+                Some((eclipse, spot)) => {
+                    println!("Warp para ver: {}", eclipse.headline(&app.scene, app.elapsed_time));
+                    let distance = (spot.x * spot.x + spot.y * spot.y + spot.z * spot.z).sqrt().max(0.0001);
+                    app.camera.yaw = (-spot.z).atan2(-spot.x);
+                    app.camera.pitch = (-spot.y / distance).clamp(-1.0, 1.0).asin().clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
+                    warp_target = Some(spot);
+                }
+            }
+        }
+
+        if let Some(target_pos) = warp_target {
+            // Calcular posición objetivo de la cámara basándose en la posición objetivo de la nave
+            // Mantener el offset relativo entre la cámara y la nave
+            let ship_offset_forward = 20.0; // Distancia fija delante de la cámara
+            let ship_offset_down = -2.0; // Ligeramente abajo
+        
+            // Calcular dirección forward de la cámara actual para mantener la orientación
+            let cos_yaw = app.camera.yaw.cos();
+            let sin_yaw = app.camera.yaw.sin();
+            let cos_pitch = app.camera.pitch.cos();
+            let sin_pitch = app.camera.pitch.sin();
+        
+            let camera_forward = Vector3::new(
+                cos_yaw * cos_pitch,
+                sin_pitch,
+                sin_yaw * cos_pitch,
+            );
+        
+            let _camera_right = Vector3::new(
+                -sin_yaw,
+                0.0,
+                cos_yaw,
+            );
+        
+            let camera_up_dir = Vector3::new(
+                -cos_yaw * sin_pitch,
+                cos_pitch,
+                -sin_yaw * sin_pitch,
+            );
+        
+            // La cámara debe estar detrás de la nave (en dirección opuesta a forward)
+            let target_camera_pos = Vector3::new(
+                target_pos.x - camera_forward.x * ship_offset_forward - camera_up_dir.x * ship_offset_down,
+                target_pos.y - camera_forward.y * ship_offset_forward - camera_up_dir.y * ship_offset_down,
+                target_pos.z - camera_forward.z * ship_offset_forward - camera_up_dir.z * ship_offset_down,
+            );
+        
+            // El warp rompe la órbita y desactiva el piloto automático
+            app.parking_orbit = None;
+            app.autopilot.cancel();
+            app.warp_system.start_warp(
+                app.elapsed_time,
+                app.ship.position,
+                target_pos,
+                app.camera.eye,
+                target_camera_pos,
+            );
+        }
+    }
+
+    // Actualizar sistema de warping sobre la nave y la cámara
+    if app.warp_system.update(app.elapsed_time) {
+        app.script_events.push(("warp_end", String::new()));
+    }
+    let warp_intensity = if app.warp_system.is_warping { (app.warp_system.warp_progress * PI).sin() } else { 0.0 };
+    app.field_of_view.update(&input, warp_intensity, delta_time);
+
+    // Planificación de la ruta
+    if !app.warp_system.is_warping && !app.docking.is_input_locked() {
+        let shift_down = input.is_down(Action::Modifier);
+        if input.is_pressed(Action::RouteAdd) {
+            // El cuerpo bajo la mira; si no hay ninguno, un punto 30 unidades delante de la nave
+            let forward = app.camera.forward();
+            let waypoint = match body_under_crosshair(&app.body_index, &app.camera) {
+                Some(body) => Waypoint::Body(body),
+                None => Waypoint::Point(Vector3::new(
+                    app.ship.position.x + forward.x * 30.0,
+                    app.ship.position.y + forward.y * 30.0,
+                    app.ship.position.z + forward.z * 30.0,
+                )),
+            };
+            app.route.add(waypoint);
+            println!("Punto de ruta {}: {}", app.route.waypoints.len(), Route::name(waypoint, &app.scene));
+        }
+        if input.is_pressed(Action::RouteRemove) {
+            if shift_down {
+                app.route.clear();
+                println!("Ruta borrada");
+            } else if let Some(waypoint) = app.route.remove_last() {
+                println!("Punto de ruta quitado: {}", Route::name(waypoint, &app.scene));
+            }
+        }
+        if input.is_pressed(Action::RouteRun) {
+            if app.route.is_running() {
+                app.route.stop();
+                app.autopilot.cancel();
+                println!("Ruta detenida");
+            } else if app.route.start().is_some() && app.route.engage(&mut app.autopilot, &app.scene, app.elapsed_time, app.ship.position, app.camera.forward()) {
+                app.parking_orbit = None;
+                app.camera.track_planet(None);
+                if app.camera.mode != CameraMode::Chase && app.camera.mode != CameraMode::Cockpit {
+                    app.camera.set_mode(CameraMode::Chase);
+                }
+                println!("Recorriendo la ruta ({:.0} unidades)", app.route.total_distance(&app.scene, app.elapsed_time, app.ship.position));
+            } else {
+                app.route.stop();
+            }
+        }
+    }
+
+    // Asistente de estabilidad: objetivo bajo la mira (o ninguno: velocidades respecto al sol)
+    // y un modo activo como mucho (pulsar el del modo activo lo apaga)
+    if input.is_pressed(Action::SasTarget) {
+        app.sas.target = body_under_crosshair(&app.body_index, &app.camera);
+        match app.sas.target.and_then(|body| app.scene.body_name(body)) {
+            Some(name) => println!("Objetivo del SAS: {}", name),
+            None => println!("Objetivo del SAS: ninguno (velocidades respecto al sol)"),
+        }
+    }
+    let sas_modes = [
+        (Action::SasKillRotation, SasMode::KillRotation),
+        (Action::SasPrograde, SasMode::Prograde),
+        (Action::SasRetrograde, SasMode::Retrograde),
+        (Action::SasMatchVelocity, SasMode::MatchVelocity),
+    ];
+    for (action, mode) in sas_modes {
+        if input.is_pressed(action) {
+            match app.sas.toggle(mode) {
+                Some(mode) => println!("SAS: {}", mode.name()),
+                None => println!("SAS apagado"),
+            }
+        }
+    }
+
+    // Atraque manual con H: solo cerca del puerto y casi en reposo respecto a él
+    // Atracada, H desatraca y U empieza/detiene el repostaje
+    if input.is_pressed(Action::Dock) && !app.warp_system.is_warping {
+        if app.docking.is_docked() {
+            app.docking.undock();
+            println!("Desatracando");
+        } else if app.docking.can_dock() {
+            let station = nearest_station(&app.body_index, app.ship.position);
+            if let Some((body, (port, axis))) = station.and_then(|body| Some((body, app.scene.docking_point(body, app.elapsed_time)?))) {
+                app.parking_orbit = None;
+                app.autopilot.engage_dock(body, app.ship.position, app.camera.forward(), port, axis);
+                app.docking.begin(body, app.autopilot.docking_distance);
+                app.camera.track_planet(None);
+                if app.camera.mode != CameraMode::Chase && app.camera.mode != CameraMode::Cockpit {
+                    app.camera.set_mode(CameraMode::Chase);
+                }
+                println!("Secuencia de atraque en {}", app.scene.body_name(body).unwrap_or("estación"));
+            }
+        } else if !app.docking.is_input_locked() {
+            println!(
+                "No se puede atracar: puerto a {:.1} (máx. {:.1}), velocidad relativa {:.1} (máx. {:.1})",
+                app.docking.port_distance(),
+                app.docking.capture_range,
+                app.docking.relative_speed(),
+                app.docking.max_relative_speed,
+            );
+        }
+    }
+    if input.is_pressed(Action::Refuel) && app.docking.is_docked() {
+        app.docking.refueling = !app.docking.refueling;
+    }
+
+    // Piloto automático: mueve la nave y coloca la cámara según su rumbo
+    // Después, las ramas de abajo vuelven a pegar la nave a la cámara en la misma posición
+    // Al atracar, el destino es el puerto de atraque y no la superficie del cuerpo
+    // En una ruta el destino puede ser un punto fijo del espacio
+    if app.autopilot.is_active() {
+        let destination = match app.autopilot.target {
+            Some(target) if app.autopilot.is_docking() => app.scene.docking_point(target, app.elapsed_time).map(|(port, _)| (port, 0.0)),
+            Some(target) => app.scene.body_position(target, app.elapsed_time).zip(app.scene.body_radius(target)),
+            None => app.autopilot.target_point.map(|point| (point, 0.0)),
+        };
+        match destination {
+            Some((body_position, body_radius)) => {
+                let previous_position = app.ship.position;
+                app.ship.position = app.autopilot.update(app.ship.position, body_position, body_radius, delta_time);
+                place_camera_behind_ship(&mut app.camera, app.ship.position, app.autopilot.heading);
+                let travelled = Vector3::new(
+                    app.ship.position.x - previous_position.x,
+                    app.ship.position.y - previous_position.y,
+                    app.ship.position.z - previous_position.z,
+                );
+                let travelled = (travelled.x * travelled.x + travelled.y * travelled.y + travelled.z * travelled.z).sqrt();
+                // La aproximación del atraque la guía la estación (no gasta combustible), así que
+                // una nave sin combustible siempre puede volver a repostar
+                if !app.docking.is_input_locked() && app.ship_systems.burn(travelled) < 1.0 {
+                    app.autopilot.cancel();
+                    println!("Piloto automático: sin combustible, la nave va a la deriva");
+                } else if !app.autopilot.is_active() {
+                    println!("Piloto automático: destino alcanzado");
+                }
+            }
+            None => app.autopilot.cancel(),
+        }
+    }
+
+    // Ruta: al llegar a un punto de paso el piloto automático sigue hacia el siguiente
+    // Si se cancela antes de llegar (cualquier tecla, sin combustible) la ruta se detiene
+    if app.route.is_running() && !app.autopilot.is_active() {
+        if app.autopilot.remaining_distance() > 0.0 {
+            app.route.stop();
+            println!("Ruta interrumpida");
+        } else if app.route.advance().is_none() {
+            println!("Ruta completada");
+        } else if !app.route.engage(&mut app.autopilot, &app.scene, app.elapsed_time, app.ship.position, app.camera.forward()) {
+            app.route.stop();
+        }
+    }
+
+    // Fin de la aproximación: atracada si el piloto automático llegó al puerto, si no se aborta
+    if matches!(app.docking.state, DockingState::Approaching { .. }) && !app.autopilot.is_active() {
+        if app.autopilot.remaining_distance() <= 0.0 {
+            app.docking.complete(app.autopilot.heading);
+            println!("Atracada en {}", app.docking.station().and_then(|body| app.scene.body_name(body)).unwrap_or("estación"));
+        } else {
+            app.docking.abort();
+        }
+    }
+
+    // Hangar: cambiar de nave sin reiniciar (el modelo se carga en segundo plano la primera
+    // vez; las siguientes sale de la caché)
+    if input.is_pressed(Action::NextShip) {
+        let spec = app.hangar.cycle();
+        app.assets.request_mesh(&spec.path, &app.ship_load_options);
+        app.ship.scale = spec.scale;
+        println!("Nave: {}", spec.name);
+    }
+
+    // Insertarse en órbita (o romperla) con O
+    if input.is_pressed(Action::ParkingOrbit) && !app.warp_system.is_warping && !app.docking.is_input_locked() {
+        if app.parking_orbit.take().is_some() {
+            println!("Órbita abandonada: vuelo libre");
+        } else if let Some((entry, surface_distance)) = app.body_index.nearest(app.ship.position, |_| true) {
+            let body = entry.item;
+            if surface_distance <= ORBIT_CAPTURE_DISTANCE {
+                let body_position = app.scene.body_position(body, app.elapsed_time).unwrap_or(app.ship.position);
+                let body_radius = app.scene.body_radius(body).unwrap_or(1.0);
+                app.parking_orbit = Some(ParkingOrbit::insert(body, body_position, body_radius, app.ship.position));
+                app.camera.track_planet(None);
+                if app.camera.mode != CameraMode::Chase && app.camera.mode != CameraMode::Cockpit {
+                    app.camera.set_mode(CameraMode::Chase);
+                }
+                println!("En órbita de {}", app.scene.body_name(body).unwrap_or("?"));
+            } else {
+                println!("Demasiado lejos para entrar en órbita ({:.1} > {:.1})", surface_distance, ORBIT_CAPTURE_DISTANCE);
+            }
+        }
+    }
+
+    // En órbita la nave sigue al cuerpo padre y avanza por su órbita
+    if let Some(orbit) = app.parking_orbit.as_mut() {
+        match app.scene.body_position(orbit.parent, app.elapsed_time) {
+            Some(parent_position) => {
+                orbit.update(delta_time);
+                app.ship.position = orbit.position(parent_position);
+                place_camera_behind_ship(&mut app.camera, app.ship.position, orbit.heading());
+            }
+            None => app.parking_orbit = None,
+        }
+    }
+
+    // Atracada o separándose, la nave va emparentada al puerto y acompaña a la estación
+    // En vuelo libre se mide la distancia y velocidad relativa al puerto más cercano
+    match app.docking.station() {
+        Some(station) => match app.scene.docking_point(station, app.elapsed_time) {
+            Some((port, axis)) => {
+                if let Some(position) = app.docking.update(port, axis, delta_time) {
+                    app.ship.position = position;
+                    place_camera_behind_ship(&mut app.camera, app.ship.position, app.docking.heading);
+                }
+            }
+            None => app.docking.abort(),
+        },
+        None => {
+            let station = nearest_station(&app.body_index, app.ship.position);
+            let port = station.and_then(|body| app.scene.docking_point(body, app.elapsed_time)).map(|(port, _)| port);
+            app.docking.track(app.ship.position, port, delta_time);
+        }
+    }
+
+    if app.warp_system.is_warping {
+        // Durante el warp, mover tanto la nave como la cámara
+        app.ship.position = app.warp_system.get_current_ship_position();
+        app.camera.eye = app.warp_system.get_current_camera_position();
+    
+        // Calcular dirección forward de la cámara basada en yaw y pitch
+        let cos_yaw = app.camera.yaw.cos();
+        let sin_yaw = app.camera.yaw.sin();
+        let cos_pitch = app.camera.pitch.cos();
+        let sin_pitch = app.camera.pitch.sin();
+    
+        let _camera_forward = Vector3::new(
+            cos_yaw * cos_pitch,
+            sin_pitch,
+            sin_yaw * cos_pitch,
+        );
+    
+        // Actualizar target de la cámara para que mire hacia la nave
+        app.camera.target = Vector3::new(
+            app.ship.position.x,
+            app.ship.position.y,
+            app.ship.position.z,
+        );
+    
+        // La nave rota exactamente igual que la cámara (solo si no usa rotación directa)
+        if !app.ship.use_direct_rotation {
+            app.ship.rotation.y = app.camera.yaw;
+            app.ship.rotation.x = app.camera.pitch;
+            app.ship.rotation.z = 0.0;
+        }
+    } else if app.camera.is_cockpit_view() {
+        // Cabina: la nave está exactamente en la posición de la cámara y gira con ella
+        app.ship.position = app.camera.eye;
+        if !app.ship.use_direct_rotation {
+            app.ship.rotation.y = app.camera.yaw;
+            app.ship.rotation.x = app.camera.pitch;
+            app.ship.rotation.z = 0.0;
+        }
+    } else if app.camera.is_tracking() || app.camera.mode != CameraMode::Chase {
+        // Siguiendo un cuerpo, en vuelo libre o en órbita: la nave se queda estacionada
+        // donde estaba y la cámara se actualiza por su cuenta
+    } else {
+        // Cuando no hay warp, comportamiento normal: la nave sigue a la cámara
+        // La nave está completamente ligada al movimiento de la cámara
+        // Se mueve y rota junto con la cámara, no está relacionada con los planetas
+        // Calcular dirección forward de la cámara basada en yaw y pitch
+        let cos_yaw = app.camera.yaw.cos();
+        let sin_yaw = app.camera.yaw.sin();
+        let cos_pitch = app.camera.pitch.cos();
+        let sin_pitch = app.camera.pitch.sin();
+    
+        // Calcular dirección forward de la cámara (hacia donde mira)
+        let camera_forward = Vector3::new(
+            cos_yaw * cos_pitch,
+            sin_pitch,
+            sin_yaw * cos_pitch,
+        );
+    
+        // Calcular dirección right de la cámara (perpendicular a forward)
+        let camera_right = Vector3::new(
+            -sin_yaw,
+            0.0,
+            cos_yaw,
+        );
+    
+        // Calcular dirección up de la cámara
+        let camera_up_dir = Vector3::new(
+            -cos_yaw * sin_pitch,
+            cos_pitch,
+            -sin_yaw * sin_pitch,
+        );
+    
+        // Cámara libre: La nave siempre está fija en la perspectiva de la cámara
+        // La nave está "pegada" a la cámara en una posición relativa fija
+        // Desde la perspectiva de la cámara, la nave siempre está en el mismo lugar en la pantalla
+        let ship_offset_forward = 20.0; // Distancia fija delante de la cámara (zoom fijo)
+        let ship_offset_down = -2.0; // Ligeramente abajo
+        let ship_offset_right = 0.0; // Centrada horizontalmente
+    
+        // Posicionar la nave en una posición relativa fija respecto a la cámara
+        // Esta posición es constante desde la perspectiva de la cámara
+        // Cuando la cámara se mueve o rota, la nave se mueve y rota con ella instantáneamente
+        app.ship.position = Vector3::new(
+            app.camera.eye.x + camera_forward.x * ship_offset_forward + camera_right.x * ship_offset_right + camera_up_dir.x * ship_offset_down,
+            app.camera.eye.y + camera_forward.y * ship_offset_forward + camera_right.y * ship_offset_right + camera_up_dir.y * ship_offset_down,
+            app.camera.eye.z + camera_forward.z * ship_offset_forward + camera_right.z * ship_offset_right + camera_up_dir.z * ship_offset_down,
+        );
+    
+        // La nave rota exactamente igual que la cámara (solo si no usa rotación directa)
+        // Cuando rotas la cámara, la nave rota igual, manteniéndose en la misma posición relativa
+        if !app.ship.use_direct_rotation {
+            app.ship.rotation.y = app.camera.yaw; // Yaw de la cámara - rota con A/D
+            app.ship.rotation.x = app.camera.pitch; // Pitch de la cámara - rota con W/S
+            app.ship.rotation.z = 0.0; // Sin roll por ahora
+        }
+    
+        // Vista tercera persona: La cámara siempre mira hacia la nave
+        // Esto asegura que la nave esté siempre visible y en la misma posición en la pantalla
+        app.camera.target = Vector3::new(
+            app.ship.position.x,
+            app.ship.position.y,
+            app.ship.position.z,
+        );
+    }
+
+    // Repostar mientras está atracada (U)
+    if app.docking.is_docked() && app.docking.refueling && app.ship_systems.refuel(delta_time) {
+        app.docking.refueling = false;
+        println!("Depósito lleno");
+    }
+
+    // Estela del motor: al acelerar (flecha arriba) o durante el warp la nave emite partículas
+    let ship_flying = app.camera.mode == CameraMode::Chase || app.camera.mode == CameraMode::Cockpit;
+    let thrusting = app.warp_system.is_warping
+        || app.autopilot.is_active()
+        || app.parking_orbit.is_some()
+        || (input.is_down(Action::Forward)
+            && app.ship_systems.has_fuel()
+            && ship_flying
+            && !app.camera.is_tracking()
+            && app.path_playback_start.is_none()
+            && !app.tour.is_active());
+    app.ship_thrusting = thrusting;
+    if thrusting {
+        let ship_forward = app.camera.forward();
+        let nozzle_offset = app.ship.scale * SHIP_RADIUS * app.hangar.active().engine_offset; // Distancia del centro de la nave a la tobera
+        let nozzle = Vector3::new(
+            app.ship.position.x - ship_forward.x * nozzle_offset,
+            app.ship.position.y - ship_forward.y * nozzle_offset,
+            app.ship.position.z - ship_forward.z * nozzle_offset,
+        );
+        let exhaust_direction = Vector3::new(-ship_forward.x, -ship_forward.y, -ship_forward.z);
+        app.engine_exhaust.emit(nozzle, exhaust_direction, Vector3::zero(), delta_time);
+    }
+    app.engine_exhaust.update(delta_time);
+
+    // Actualizar parámetros de la cámara
+    app.camera.up = Vector3::new(0.0, 1.0, 0.0);
+    app.camera.update_transition(delta_time);
+
+
+    // Update orbital positions and rotations
+    for planet in &mut app.scene.planets {
+        planet.orbital_angle += planet.orbital_speed * delta_time;
+        if planet.orbital_angle >= 2.0 * PI {
+            planet.orbital_angle -= 2.0 * PI;
+        }
+    }
+
+    // Asteroides (después de las órbitas: las rocas que caen persiguen la posición actual)
+    if let Some(belt) = &mut app.asteroid_belt {
+        if input.is_pressed(Action::DeflectAsteroid) {
+            let distance_to_ship = |position: Vector3| {
+                let (dx, dy, dz) = (position.x - app.ship.position.x, position.y - app.ship.position.y, position.z - app.ship.position.z);
+                dx * dx + dy * dy + dz * dz
+            };
+            let nearest = (0..app.scene.planets.len())
+                .min_by(|&a, &b| distance_to_ship(app.scene.planets[a].position()).total_cmp(&distance_to_ship(app.scene.planets[b].position())));
+            if let Some(AsteroidEvent::Deflected { planet, .. }) = nearest.and_then(|planet| belt.deflect_toward(planet, &app.scene)) {
+                println!("Asteroide desviado hacia {}", app.scene.planets[planet].name);
+            }
+        }
+        for event in belt.update(app.elapsed_time, delta_time, &app.scene) {
+            match event {
+                AsteroidEvent::Deflected { planet, .. } => {
+                    println!("Un asteroide abandona el cinturón rumbo a {}", app.scene.planets[planet].name);
+                }
+                AsteroidEvent::Impact { planet, position, size, .. } => {
+                    let target = &mut app.scene.planets[planet];
+                    target.add_crater(position, size, app.clock.hours(), app.elapsed_time);
+                    app.impact_debris.burst(position, 120, Vector3::zero());
+                    println!("¡Impacto en {}! ({} cráteres)", target.name, target.craters.len());
+                    app.script_events.push(("impact", target.name.clone()));
+                }
+            }
+        }
+    }
+    app.impact_debris.update(delta_time);
+
+    // Erupciones volcánicas: chorro inicial al empezar y eyecta continua mientras duran
+    app.volcanic_ejecta.resize_with(app.scene.planets.len(), ParticleEmitter::volcanic_ejecta);
+    for event in app.volcanic_activity.update(app.elapsed_time, &app.scene) {
+        match event {
+            VolcanicEvent::Eruption { planet, center } => {
+                let ephemeris = Ephemeris::new(&app.scene, app.elapsed_time);
+                let planet_velocity = ephemeris.velocity(BodyRef::Planet(planet), app.elapsed_time).unwrap_or(Vector3::zero());
+                let target = &mut app.scene.planets[planet];
+                target.add_eruption(center, app.elapsed_time, app.volcanic_activity.duration);
+                let (vent, normal) = target.surface_point(center, app.clock.hours());
+                app.volcanic_ejecta[planet].fountain(vent, normal, 80, planet_velocity);
+                println!("¡Erupción en {}!", target.name);
+            }
+        }
+    }
+    let ephemeris = Ephemeris::new(&app.scene, app.elapsed_time);
+    for (idx, (planet, ejecta)) in app.scene.planets.iter().zip(app.volcanic_ejecta.iter_mut()).enumerate() {
+        if planet.eruptions.is_empty() && ejecta.particles.is_empty() {
+            continue;
+        }
+        ejecta.attractor = Some(Attractor { center: planet.position(), gravity: 3.0 * planet.scale, radius: planet.scale });
+        let planet_velocity = ephemeris.velocity(BodyRef::Planet(idx), app.elapsed_time).unwrap_or(Vector3::zero());
+        for eruption in &planet.eruptions {
+            let intensity = eruption.intensity(app.elapsed_time);
+            if intensity > 0.0 {
+                // El ritmo de emisión sigue a la intensidad de la erupción
+                let (vent, normal) = planet.surface_point(eruption.center, app.clock.hours());
+                ejecta.emit(vent, normal, planet_velocity, delta_time * intensity);
+            }
+        }
+        ejecta.update(delta_time);
+    }
+
+    // Seguir el cuerpo rastreado (después de actualizar las órbitas, antes de la matriz de vista)
+    // El modo órbita necesita un planeta seleccionado: por defecto el primero
+    if app.camera.mode == CameraMode::Orbit && !app.camera.is_tracking() && !app.scene.planets.is_empty() {
+        app.camera.track_planet(Some(0));
+    }
+    let mut tracked_body = app.camera
+        .get_tracking_planet()
+        .and_then(|planet_idx| app.scene.planets.get(planet_idx))
+        .map(|planet| (planet.position(), planet.scale));
+    if let Some(moon_idx) = app.camera.get_tracking_moon().filter(|&idx| idx < app.scene.moons.len()) {
+        tracked_body = Some((app.scene.moon_position(moon_idx, app.elapsed_time), app.scene.moons[moon_idx].scale));
+    }
+    if let Some((body_position, body_radius)) = tracked_body {
+        if app.camera.mode == CameraMode::Orbit {
+            app.camera.orbit_around(body_position, body_radius);
+        } else {
+            app.camera.follow_tracked_body(body_position, body_radius);
+        }
+    }
+
+    // Reproducción de la trayectoria: tiene prioridad sobre cualquier modo de cámara
+    if let Some(start) = app.path_playback_start {
+        let playback_time = app.elapsed_time - start;
+        if let Some((eye, target)) = app.camera_path.sample(playback_time) {
+            app.camera.look_from(eye, target);
+        }
+        if playback_time >= app.camera_path.duration() {
+            app.path_playback_start = None;
+        }
+    }
+    if app.tour.is_active() {
+        if let Some((eye, target)) = app.tour.update(&app.scene, app.elapsed_time) {
+            app.camera.look_from(eye, target);
+        }
+        if !app.tour.is_active() {
+            println!("Visita guiada terminada");
+        }
+    }
+
+    // Colisión de la cámara de persecución: no puede quedar dentro del sol, planetas ni lunas
+    if app.camera.mode == CameraMode::Chase && !app.camera.is_tracking() && app.path_playback_start.is_none() && !app.tour.is_active() {
+        let mut obstacles = vec![(Vector3::zero(), SUN_RADIUS * 1.1)];
+        obstacles.extend(
+            app.body_index
+                .along_segment(app.ship.position, app.camera.eye, Camera::COLLISION_MARGIN)
+                .into_iter()
+                .map(|entry| (entry.center, entry.radius)),
+        );
+        app.camera.resolve_collisions(app.ship.position, &obstacles);
+    } else {
+        app.camera.clear_collision();
+    }
+
+    app.ship_velocity = if delta_time > 0.0 {
+        Vector3::new(
+            (app.ship.position.x - app.last_ship_position.x) / delta_time,
+            (app.ship.position.y - app.last_ship_position.y) / delta_time,
+            (app.ship.position.z - app.last_ship_position.z) / delta_time,
+        )
+    } else {
+        Vector3::zero()
+    };
+    app.last_ship_position = app.ship.position;
+
+    // Disparo: los proyectiles salen del morro de la nave y heredan su velocidad
+    if input.is_down(Action::Fire) && !app.warp_system.is_warping && !app.docking.is_input_locked() {
+        let ship_forward = app.camera.forward();
+        let muzzle_offset = app.ship.scale * SHIP_RADIUS;
+        let muzzle = Vector3::new(
+            app.ship.position.x + ship_forward.x * muzzle_offset,
+            app.ship.position.y + ship_forward.y * muzzle_offset,
+            app.ship.position.z + ship_forward.z * muzzle_offset,
+        );
+        app.weapons.fire(app.elapsed_time, muzzle, ship_forward, app.ship_velocity);
+    }
+    for event in app.weapons.update(delta_time, app.asteroid_belt.as_mut()) {
+        match event {
+            WeaponEvent::AsteroidDestroyed { position, size, .. } => {
+                app.impact_debris.burst(position, (size * 400.0) as usize + 40, Vector3::zero());
+                println!("Asteroide destruido");
+            }
+        }
+    }
+
+    // Scripts de la escena: ven el estado de este paso y sus órdenes se atienden en el
+    // siguiente frame, como las de la consola
+    #[cfg(feature = "scripting")]
+    if let Some(host) = &mut app.scripts {
+        let context = ScriptContext {
+            time: app.elapsed_time,
+            hours: app.clock.hours(),
+            ship: app.ship.position,
+            camera: app.camera.eye,
+            bodies: app.scene
+                .bodies()
+                .filter_map(|body| Some((app.scene.body_name(body)?.to_string(), app.scene.body_position(body, app.elapsed_time)?)))
+                .collect(),
+        };
+        app.pending_commands.extend(host.update(context, delta_time, &app.script_events));
+        for message in host.take_messages() {
+            println!("{}", message);
+            app.console.print(message);
+        }
+    }
+}
+
+
+/// Cuerpo cuyo borde queda más cerca de la mira (a menos de ~3°)
+fn body_under_crosshair(body_index: &SpatialIndex<BodyRef>, camera: &Camera) -> Option<BodyRef> {
+    body_index
+        .within_cone(camera.view_eye(), camera.forward(), 0.05)
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(entry, _)| entry.item)
+}
+
+/// Orientar la cámara según el rumbo de la nave y colocarla donde la deja el modo actual:
+/// en la nave en cabina, o detrás y encima (el inverso del offset de persecución) en los demás
+fn place_camera_behind_ship(camera: &mut Camera, ship_position: Vector3, heading: Vector3) {
+    camera.yaw = heading.z.atan2(heading.x);
+    camera.pitch = heading.y.clamp(-1.0, 1.0).asin().clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1);
+    let forward = camera.forward();
+    if camera.mode == CameraMode::Cockpit {
+        camera.eye = ship_position;
+        camera.target = Vector3::new(
+            ship_position.x + forward.x,
+            ship_position.y + forward.y,
+            ship_position.z + forward.z,
+        );
+    } else {
+        // 20 unidades detrás y 2 arriba de la nave
+        let camera_up_dir = Vector3::new(
+            -camera.yaw.cos() * camera.pitch.sin(),
+            camera.pitch.cos(),
+            -camera.yaw.sin() * camera.pitch.sin(),
+        );
+        camera.eye = Vector3::new(
+            ship_position.x - forward.x * 20.0 + camera_up_dir.x * 2.0,
+            ship_position.y - forward.y * 20.0 + camera_up_dir.y * 2.0,
+            ship_position.z - forward.z * 20.0 + camera_up_dir.z * 2.0,
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use crate::obj::{LoadOptions, Obj};
+use crate::skybox::Skybox;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Qué se carga y cómo
+#[derive(Clone, Debug)]
+enum AssetKind {
+    Mesh(LoadOptions),
+    Skybox, // Carpeta con un cubemap (`Skybox::FACE_NAMES`) o un panorama `sky.png`
+}
+
+/// Recurso ya cargado
+#[derive(Clone)]
+enum Asset {
+    Mesh(Arc<Obj>),
+    Skybox(Arc<Skybox>),
+}
+
+/// Estado de un recurso pedido
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetState {
+    Loading,
+    Ready,
+    Failed(String),
+}
+
+/// Sucesos de `AssetManager::update`, para avisar en la consola o en la interfaz
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetEvent {
+    Loaded(String),
+    Reloaded(String), // Recargado porque el archivo cambió en el disco
+    Failed(String, String),
+}
+
+struct Entry {
+    kind: AssetKind,
+    asset: Option<Asset>,         // Última versión cargada (se sigue usando mientras se recarga)
+    state: AssetState,
+    pending: bool,                // Hay una carga en los hilos
+    modified: Option<SystemTime>, // Fecha de los archivos cuando se pidió la última carga
+}
+
+struct LoadJob {
+    key: String,
+    kind: AssetKind,
+}
+
+struct LoadResult {
+    key: String,
+    result: Result<Asset, String>,
+}
+
+/// Gestor de recursos: carga modelos OBJ y cielos de imágenes en hilos de fondo para que el
+/// arranque no espere al disco. Mientras un recurso se carga (o si falla) se usa uno provisional:
+/// para las mallas, `placeholder`; el cielo simplemente no está. Lo cargado queda en caché por
+/// ruta, así que pedirlo otra vez no vuelve a leer el archivo, y cada `poll_interval` se mira la
+/// fecha de modificación de los archivos: si cambió, se recarga en segundo plano y la versión
+/// anterior se sigue dibujando hasta que la nueva está lista
+pub struct AssetManager {
+    entries: HashMap<String, Entry>,
+    order: Vec<String>,            // Rutas en el orden en que se pidieron (progreso)
+    jobs: Option<Sender<LoadJob>>, // Se suelta al destruirlo para que los hilos terminen
+    results: Receiver<LoadResult>,
+    workers: Vec<JoinHandle<()>>,
+    placeholder: Arc<Obj>,
+    pub poll_interval: Duration,   // Cada cuánto se buscan archivos cambiados
+    last_poll: Instant,
+}
+
+impl AssetManager {
+    /// Gestor con `workers` hilos de carga y `placeholder` como malla provisional
+    pub fn new(workers: usize, placeholder: Obj) -> Self {
+        let (jobs, incoming) = mpsc::channel::<LoadJob>();
+        let (done, results) = mpsc::channel();
+        let incoming = Arc::new(Mutex::new(incoming));
+        let workers = (0..workers.max(1))
+            .map(|index| {
+                let (incoming, done) = (Arc::clone(&incoming), done.clone());
+                thread::Builder::new()
+                    .name(format!("asset-loader-{}", index))
+                    .spawn(move || loop {
+                        // El cerrojo solo se toma para recibir: las cargas van en paralelo
+                        let job = match incoming.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => break,
+                        };
+                        let Ok(job) = job else {
+                            break;
+                        };
+                        let result = load(&job.key, &job.kind);
+                        if done.send(LoadResult { key: job.key, result }).is_err() {
+                            break;
+                        }
+                    })
+                    .expect("no se pudo crear un hilo de carga de recursos")
+            })
+            .collect();
+
+        AssetManager {
+            entries: HashMap::new(),
+            order: Vec::new(),
+            jobs: Some(jobs),
+            results,
+            workers,
+            placeholder: Arc::new(placeholder),
+            poll_interval: Duration::from_secs(1),
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Pedir el modelo OBJ de `path` (si ya está pedido, no hace nada)
+    pub fn request_mesh(&mut self, path: &str, options: &LoadOptions) {
+        self.request(path, AssetKind::Mesh(options.clone()));
+    }
+
+    /// Pedir el cielo de imágenes de la carpeta `dir`
+    pub fn request_skybox(&mut self, dir: &str) {
+        self.request(dir, AssetKind::Skybox);
+    }
+
+    fn request(&mut self, key: &str, kind: AssetKind) {
+        if self.entries.contains_key(key) {
+            return;
+        }
+        let entry = Entry { kind, asset: None, state: AssetState::Loading, pending: false, modified: None };
+        self.entries.insert(String::from(key), entry);
+        self.order.push(String::from(key));
+        self.submit(key);
+    }
+
+    fn submit(&mut self, key: &str) {
+        let Some(entry) = self.entries.get_mut(key) else {
+            return;
+        };
+        entry.modified = last_modified(key, &entry.kind);
+        entry.pending = true;
+        if let Some(jobs) = &self.jobs {
+            let job = LoadJob { key: String::from(key), kind: entry.kind.clone() };
+            if jobs.send(job).is_err() {
+                entry.pending = false;
+                entry.state = AssetState::Failed(String::from("los hilos de carga se cerraron"));
+            }
+        }
+    }
+
+    /// Recoger las cargas terminadas y, cada `poll_interval`, recargar lo que cambió en el disco;
+    /// una vez por frame
+    pub fn update(&mut self) -> Vec<AssetEvent> {
+        let mut events = Vec::new();
+        while let Ok(LoadResult { key, result }) = self.results.try_recv() {
+            let Some(entry) = self.entries.get_mut(&key) else {
+                continue;
+            };
+            entry.pending = false;
+            match result {
+                Ok(asset) => {
+                    let reloaded = entry.asset.is_some();
+                    entry.asset = Some(asset);
+                    entry.state = AssetState::Ready;
+                    events.push(if reloaded { AssetEvent::Reloaded(key) } else { AssetEvent::Loaded(key) });
+                }
+                Err(message) => {
+                    // Una recarga fallida (archivo a medio guardar) conserva la versión anterior
+                    entry.state = if entry.asset.is_some() { AssetState::Ready } else { AssetState::Failed(message.clone()) };
+                    events.push(AssetEvent::Failed(key, message));
+                }
+            }
+        }
+
+        if self.last_poll.elapsed() >= self.poll_interval {
+            self.last_poll = Instant::now();
+            self.poll_changes();
+        }
+        events
+    }
+
+    /// Volver a cargar los recursos cuyos archivos cambiaron desde la última carga
+    pub fn poll_changes(&mut self) {
+        let changed: Vec<String> = self
+            .order
+            .iter()
+            .filter(|key| {
+                let entry = &self.entries[key.as_str()];
+                !entry.pending && last_modified(key, &entry.kind) != entry.modified
+            })
+            .cloned()
+            .collect();
+        for key in changed {
+            self.submit(&key);
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn state(&self, key: &str) -> Option<&AssetState> {
+        self.entries.get(key).map(|entry| &entry.state)
+    }
+
+    /// Malla de `path` si ya está cargada
+    pub fn mesh(&self, path: &str) -> Option<Arc<Obj>> {
+        match self.entries.get(path)?.asset.as_ref()? {
+            Asset::Mesh(mesh) => Some(Arc::clone(mesh)),
+            _ => None,
+        }
+    }
+
+    /// Malla de `path`, o la provisional mientras se carga o si no se pudo cargar
+    pub fn mesh_or_placeholder(&self, path: &str) -> Arc<Obj> {
+        self.mesh(path).unwrap_or_else(|| Arc::clone(&self.placeholder))
+    }
+
+    /// Cielo de la carpeta `dir` si ya está cargado
+    pub fn skybox(&self, dir: &str) -> Option<Arc<Skybox>> {
+        match self.entries.get(dir)?.asset.as_ref()? {
+            Asset::Skybox(skybox) => Some(Arc::clone(skybox)),
+            _ => None,
+        }
+    }
+
+    /// Recursos terminados (cargados o fallidos) y pedidos en total
+    pub fn progress(&self) -> (usize, usize) {
+        let done = self.entries.values().filter(|entry| entry.state != AssetState::Loading).count();
+        (done, self.entries.len())
+    }
+
+    /// Recursos que no se pudieron cargar y el motivo, en el orden en que se pidieron
+    pub fn failures(&self) -> Vec<(&str, &str)> {
+        self.order
+            .iter()
+            .filter_map(|key| match &self.entries[key.as_str()].state {
+                AssetState::Failed(message) => Some((key.as_str(), message.as_str())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Rutas que aún se están cargando por primera vez, en el orden en que se pidieron
+    pub fn loading(&self) -> Vec<&str> {
+        self.order
+            .iter()
+            .filter(|key| self.entries[key.as_str()].state == AssetState::Loading)
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+impl Drop for AssetManager {
+    fn drop(&mut self) {
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Carga en un hilo de fondo
+fn load(key: &str, kind: &AssetKind) -> Result<Asset, String> {
+    match kind {
+        AssetKind::Mesh(options) => Obj::load_with_options(key, options)
+            .map(|mesh| Asset::Mesh(Arc::new(mesh)))
+            .map_err(|e| format!("{:?}", e)),
+        AssetKind::Skybox => Skybox::load_cubemap_dir(key, "png")
+            .or_else(|_| Skybox::load_equirectangular(&format!("{}/sky.png", key)))
+            .map(|skybox| Asset::Skybox(Arc::new(skybox)))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Fecha de modificación más reciente de los archivos de un recurso (None si no hay ninguno)
+fn last_modified(key: &str, kind: &AssetKind) -> Option<SystemTime> {
+    let files: Vec<String> = match kind {
+        AssetKind::Mesh(_) => vec![String::from(key)],
+        AssetKind::Skybox => Skybox::FACE_NAMES
+            .iter()
+            .map(|face| format!("{}/{}.png", key, face))
+            .chain([format!("{}/sky.png", key)])
+            .collect(),
+    };
+    files.iter().filter_map(|file| fs::metadata(file).and_then(|metadata| metadata.modified()).ok()).max()
+}
@@ -0,0 +1,308 @@
+use raylib::prelude::*;
+use crate::ecs::{Orbit, Spin};
+use crate::scene::Scene;
+use crate::spatial_index::SpatialIndex;
+use std::f32::consts::PI;
+
+/// Estado de un asteroide del cinturón
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AsteroidState {
+    /// En su órbita circular alrededor del sol
+    Orbiting,
+    /// Desviado de su órbita: vuela en línea recta hacia el planeta `target`
+    Falling { target: usize },
+}
+
+/// Roca del cinturón de asteroides
+#[derive(Clone, Debug)]
+pub struct Asteroid {
+    pub id: u32,
+    pub orbital_radius: f32,  // Radio de la órbita alrededor del sol
+    pub orbital_phase: f32,   // Ángulo inicial en la órbita
+    pub orbital_speed: f32,   // Velocidad angular de la órbita (rad/s, kepleriana)
+    pub inclination: f32,     // Inclinación del plano orbital (radianes)
+    pub scale: f32,           // Radio de la roca
+    pub spin: Vector3,        // Giro propio en cada eje (rad/s)
+    pub mesh: usize,          // Variante de malla (0..AsteroidBelt::MESH_VARIANTS)
+    pub position: Vector3,    // Posición actual en el mundo (la mantiene `AsteroidBelt::update`)
+    pub state: AsteroidState,
+}
+
+impl Asteroid {
+    /// Posición en su órbita en el instante `time`
+    pub fn orbit_position(&self, time: f32) -> Vector3 {
+        Orbit::new(self.orbital_radius, self.orbital_phase, self.orbital_speed, self.inclination).offset(time)
+    }
+
+    /// Rotación propia (para create_model_matrix)
+    #[allow(dead_code)]
+    pub fn rotation(&self, time: f32) -> Vector3 {
+        Spin::Steady(self.spin).euler(time, 0.0).unwrap_or(Vector3::zero())
+    }
+}
+
+/// Sucesos del cinturón que el juego puede atender
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AsteroidEvent {
+    /// Un asteroide abandona el cinturón rumbo al planeta `planet`
+    Deflected { id: u32, planet: usize },
+    /// El asteroide choca con el planeta `planet` en `position` (punto de la superficie, en el
+    /// mundo); `size` es el radio del asteroide
+    Impact { id: u32, planet: usize, position: Vector3, size: f32 },
+}
+
+/// Cinturón de asteroides: rocas en órbitas keplerianas entre dos radios y, de vez en cuando
+/// (proceso de Poisson), una roca desviada que cae sobre un planeta
+/// Igual que `SolarActivity`, `update` solo devuelve sucesos: el juego añade el cráter y la
+/// explosión. Cada roca que choca se repone con otra nueva para que el cinturón no se vacíe
+pub struct AsteroidBelt {
+    pub enabled: bool,             // Impactos aleatorios activados
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub kepler_constant: f32,      // velocidad orbital = kepler_constant / radio^1.5
+    pub mean_impact_interval: f32, // Segundos medios entre rocas desviadas
+    pub impactor_speed: f32,       // Velocidad de las rocas que caen (unidades por segundo)
+    asteroids: Vec<Asteroid>,
+    index: SpatialIndex<usize>,    // Rocas por posición (índices en `asteroids`), al día tras cada cambio
+    next_impact: f32,
+    next_id: u32,
+    rng_state: u64,
+}
+
+impl AsteroidBelt {
+    /// Mallas distintas entre las que se reparten las rocas
+    pub const MESH_VARIANTS: usize = 4;
+    /// Radio de las mallas de roca respecto a `scale`: las ondas de `Obj::generate_asteroid`
+    /// sacan los vértices hasta 0.93 + 0.22 del radio unidad
+    pub const MESH_BOUNDS: f32 = 1.15;
+    /// Lado de las celdas del índice espacial: varias veces la roca más grande
+    const INDEX_CELL_SIZE: f32 = 2.0;
+
+    pub fn new(inner_radius: f32, outer_radius: f32, count: usize, kepler_constant: f32, seed: u64) -> Self {
+        let mut belt = AsteroidBelt {
+            enabled: true,
+            inner_radius,
+            outer_radius,
+            kepler_constant,
+            mean_impact_interval: 25.0,
+            impactor_speed: 8.0,
+            asteroids: Vec::with_capacity(count),
+            index: SpatialIndex::new(Self::INDEX_CELL_SIZE),
+            next_impact: 0.0,
+            next_id: 0,
+            rng_state: seed ^ 0xA57E_401D,
+        };
+        for _ in 0..count {
+            belt.spawn();
+        }
+        belt.next_impact = belt.random_interval();
+        belt.rebuild_index();
+        belt
+    }
+
+    /// Cinturón para `scene`: en el hueco más ancho entre los sistemas (anillos y lunas incluidos)
+    /// de dos planetas consecutivos, como el real entre Marte y Júpiter; si no hay sitio, más allá
+    /// del último planeta. None si la escena no tiene planetas
+    pub fn for_scene(scene: &Scene, count: usize, seed: u64) -> Option<Self> {
+        let mut orbits: Vec<(f32, f32)> = scene
+            .planets
+            .iter()
+            .enumerate()
+            .map(|(idx, planet)| {
+                let rings = planet.rings.as_ref().map_or(0.0, |rings| rings.outer_radius);
+                let moons = scene
+                    .moons
+                    .iter()
+                    .filter(|moon| moon.parent == idx)
+                    .map(|moon| moon.orbital_radius + moon.scale)
+                    .fold(0.0, f32::max);
+                (planet.orbital_radius, planet.scale.max(rings).max(moons))
+            })
+            .collect();
+        orbits.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // Constante de Kepler media de los planetas, para que las rocas giren a un ritmo coherente
+        let kepler_constant = scene
+            .planets
+            .iter()
+            .map(|planet| planet.orbital_speed.abs() * planet.orbital_radius.powf(1.5))
+            .sum::<f32>()
+            / scene.planets.len().max(1) as f32;
+
+        const MIN_WIDTH: f32 = 3.0;
+        let widest_gap = orbits
+            .windows(2)
+            .map(|pair| (pair[0].0 + pair[0].1 + 1.0, pair[1].0 - pair[1].1 - 1.0))
+            .filter(|(inner, outer)| outer - inner >= MIN_WIDTH)
+            .max_by(|a, b| (a.1 - a.0).total_cmp(&(b.1 - b.0)));
+        let (inner, outer) = match widest_gap {
+            Some(gap) => gap,
+            None => {
+                let &(radius, extent) = orbits.last()?;
+                (radius + extent + 4.0, radius + extent + 10.0)
+            }
+        };
+        Some(AsteroidBelt::new(inner, outer, count, kepler_constant, seed))
+    }
+
+    pub fn asteroids(&self) -> &[Asteroid] {
+        &self.asteroids
+    }
+
+    /// Índice espacial de las rocas (cada entrada apunta a su posición en `asteroids()`, con la
+    /// esfera que envuelve su malla). Lo usan los disparos para buscar impactos y el render para descartar las que no se ven
+    pub fn index(&self) -> &SpatialIndex<usize> {
+        &self.index
+    }
+
+    /// Destruye la roca `id` (sin reponerla) y la devuelve, o None si ya no existe
+    pub fn destroy(&mut self, id: u32) -> Option<Asteroid> {
+        let index = self.asteroids.iter().position(|asteroid| asteroid.id == id)?;
+        let asteroid = self.asteroids.remove(index);
+        self.rebuild_index();
+        Some(asteroid)
+    }
+
+    /// Reprograma el próximo impacto aleatorio a partir de `time`
+    pub fn reset(&mut self, time: f32) {
+        self.next_impact = time + self.random_interval();
+    }
+
+    /// Desvía hacia el planeta `planet` la roca en órbita más cercana a él
+    /// Devuelve el suceso, o None si el planeta no existe o no quedan rocas en órbita
+    pub fn deflect_toward(&mut self, planet: usize, scene: &Scene) -> Option<AsteroidEvent> {
+        let target = scene.planets.get(planet)?.position();
+        let distance_to_target = |asteroid: &Asteroid| {
+            let p = asteroid.position;
+            let (dx, dy, dz) = (p.x - target.x, p.y - target.y, p.z - target.z);
+            dx * dx + dy * dy + dz * dz
+        };
+        let asteroid = self
+            .asteroids
+            .iter_mut()
+            .filter(|asteroid| asteroid.state == AsteroidState::Orbiting)
+            .min_by(|a, b| distance_to_target(a).total_cmp(&distance_to_target(b)))?;
+        asteroid.state = AsteroidState::Falling { target: planet };
+        Some(AsteroidEvent::Deflected { id: asteroid.id, planet })
+    }
+
+    /// Avanza el cinturón hasta `time` y devuelve los sucesos ocurridos, en orden
+    /// Las rocas que caen persiguen la posición actual del planeta, así que siempre lo alcanzan
+    pub fn update(&mut self, time: f32, delta_time: f32, scene: &Scene) -> Vec<AsteroidEvent> {
+        let mut events = Vec::new();
+
+        if self.enabled && time >= self.next_impact {
+            if !scene.planets.is_empty() {
+                let planet = ((self.random() * scene.planets.len() as f32) as usize).min(scene.planets.len() - 1);
+                events.extend(self.deflect_toward(planet, scene));
+            }
+            self.next_impact = time + self.random_interval();
+        }
+
+        let step = self.impactor_speed * delta_time;
+        let mut impacts = 0;
+        for asteroid in &mut self.asteroids {
+            let target = match asteroid.state {
+                AsteroidState::Orbiting => {
+                    asteroid.position = asteroid.orbit_position(time);
+                    continue;
+                }
+                AsteroidState::Falling { target } => target,
+            };
+            // El planeta ya no existe (escena regenerada): la roca se pierde en el espacio
+            let Some(planet) = scene.planets.get(target) else {
+                asteroid.state = AsteroidState::Orbiting;
+                continue;
+            };
+
+            let center = planet.position();
+            let to_planet = Vector3::new(
+                center.x - asteroid.position.x,
+                center.y - asteroid.position.y,
+                center.z - asteroid.position.z,
+            );
+            let distance = (to_planet.x * to_planet.x + to_planet.y * to_planet.y + to_planet.z * to_planet.z).sqrt().max(0.0001);
+            let direction = Vector3::new(to_planet.x / distance, to_planet.y / distance, to_planet.z / distance);
+            if distance - planet.scale - asteroid.scale <= step {
+                events.push(AsteroidEvent::Impact {
+                    id: asteroid.id,
+                    planet: target,
+                    position: Vector3::new(
+                        center.x - direction.x * planet.scale,
+                        center.y - direction.y * planet.scale,
+                        center.z - direction.z * planet.scale,
+                    ),
+                    size: asteroid.scale,
+                });
+                impacts += 1;
+            } else {
+                asteroid.position.x += direction.x * step;
+                asteroid.position.y += direction.y * step;
+                asteroid.position.z += direction.z * step;
+            }
+        }
+
+        if impacts > 0 {
+            self.asteroids.retain(|asteroid| {
+                !events.iter().any(|event| matches!(event, AsteroidEvent::Impact { id, .. } if *id == asteroid.id))
+            });
+            for _ in 0..impacts {
+                let id = self.spawn();
+                self.asteroids[id].position = self.asteroids[id].orbit_position(time);
+            }
+        }
+        self.rebuild_index();
+
+        events
+    }
+
+    /// Añade `count` rocas nuevas en órbitas al azar dentro del cinturón, ya en su posición del
+    /// instante `time` (consola de desarrollo)
+    pub fn spawn_many(&mut self, count: usize, time: f32) {
+        for _ in 0..count {
+            let id = self.spawn();
+            self.asteroids[id].position = self.asteroids[id].orbit_position(time);
+        }
+        self.rebuild_index();
+    }
+
+    /// Añade una roca nueva en una órbita al azar dentro del cinturón; devuelve su índice
+    fn spawn(&mut self) -> usize {
+        let orbital_radius = self.inner_radius + (self.outer_radius - self.inner_radius) * self.random();
+        let asteroid = Asteroid {
+            id: self.next_id,
+            orbital_radius,
+            orbital_phase: self.random() * 2.0 * PI,
+            orbital_speed: self.kepler_constant / orbital_radius.max(0.0001).powf(1.5),
+            inclination: (self.random() - 0.5) * 0.2, // ±~6°
+            // Muchas rocas pequeñas y pocas grandes
+            scale: 0.08 + 0.35 * self.random().powi(3),
+            spin: Vector3::new(self.random() - 0.5, self.random() - 0.5, self.random() - 0.5),
+            mesh: (self.random() * Self::MESH_VARIANTS as f32) as usize % Self::MESH_VARIANTS,
+            position: Vector3::zero(),
+            state: AsteroidState::Orbiting,
+        };
+        self.next_id += 1;
+        self.asteroids.push(asteroid);
+        self.asteroids.len() - 1
+    }
+
+    /// Vuelve a llenar el índice espacial con las posiciones actuales
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (idx, asteroid) in self.asteroids.iter().enumerate() {
+            self.index.insert(idx, asteroid.position, asteroid.scale * Self::MESH_BOUNDS);
+        }
+    }
+
+    /// Valor pseudoaleatorio en [0, 1) (LCG, como las partículas)
+    fn random(&mut self) -> f32 {
+        self.rng_state = self.rng_state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.rng_state >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Espera hasta el próximo impacto: distribución exponencial de media `mean_impact_interval`
+    fn random_interval(&mut self) -> f32 {
+        -(1.0 - self.random()).ln() * self.mean_impact_interval.max(0.1)
+    }
+}
@@ -0,0 +1,281 @@
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+
+/// Frecuencia de muestreo de los sonidos sintetizados
+const SAMPLE_RATE: u32 = 22050;
+
+/// Volúmenes del sonido (ver `assets/audio.cfg`), todos entre 0 y 1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AudioConfig {
+    pub enabled: bool, // false: no se abre el dispositivo de audio
+    pub master: f32,   // Volumen general
+    pub music: f32,    // Música ambiental
+    pub effects: f32,  // Motor, warp, interfaz y cercanía del sol
+}
+
+impl AudioConfig {
+    pub fn new() -> Self {
+        AudioConfig { enabled: true, master: 0.8, music: 0.5, effects: 0.8 }
+    }
+
+    /// Cargar la configuración de un archivo de líneas `clave = valor` (`#` inicia un comentario)
+    /// Las claves que no aparecen conservan su valor por defecto
+    pub fn load(path: &str) -> io::Result<Self> {
+        AudioConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut config = AudioConfig::new();
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'clave = valor'"))?;
+            let value = value.trim();
+            let volume = |value: &str| match value.parse::<f32>() {
+                Ok(volume) if (0.0..=1.0).contains(&volume) => Ok(volume),
+                Ok(_) => Err(config_error(line_number, "el volumen va de 0 a 1")),
+                Err(e) => Err(config_error(line_number, &format!("{}", e))),
+            };
+            match key.trim() {
+                "enabled" => config.enabled = value.parse().map_err(|e| config_error(line_number, &format!("{}", e)))?,
+                "master" => config.master = volume(value)?,
+                "music" => config.music = volume(value)?,
+                "effects" => config.effects = volume(value)?,
+                other => return Err(config_error(line_number, &format!("clave desconocida '{}'", other))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lo que el sonido necesita saber del frame
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioState {
+    pub throttle: f32,      // Empuje de los motores (0 a 1)
+    pub warping: bool,      // La nave está en pleno salto de warp
+    pub sun_proximity: f32, // 0 lejos del sol, 1 rozando su superficie
+}
+
+/// Sonido del juego: música ambiental, motor cuyo tono sigue al empuje, el silbido del warp, los
+/// clics de la interfaz y un retumbar grave cerca del sol
+/// Todo se sintetiza al arrancar, así que no hace falta ningún archivo; si existe
+/// `assets/audio/music.ogg` se usa como música en lugar del pad sintetizado
+/// Sin dispositivo de audio (o con `enabled = false`) todas las llamadas son inocuas
+pub struct SoundSystem<'a> {
+    pub config: AudioConfig,
+    music: Option<Music<'a>>, // Los bucles son streams: se repiten solos sin cortes
+    engine: Option<Music<'a>>,
+    rumble: Option<Music<'a>>,
+    warp: Option<Sound<'a>>,
+    click: Option<Sound<'a>>,
+    was_warping: bool,
+    // WAV en memoria de los streams: raylib los lee mientras suenan, así que se sueltan después
+    // de ellos (los campos se destruyen en orden de declaración)
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<'a> SoundSystem<'a> {
+    pub fn new(audio: Option<&'a RaylibAudio>, config: AudioConfig) -> Self {
+        let mut system = SoundSystem {
+            config,
+            music: None,
+            engine: None,
+            rumble: None,
+            warp: None,
+            click: None,
+            was_warping: false,
+            buffers: Vec::new(),
+        };
+        let Some(audio) = audio.filter(|_| config.enabled) else {
+            return system;
+        };
+
+        let mut stream = |samples: Vec<f32>| {
+            let bytes = wav_bytes(&samples, SAMPLE_RATE);
+            let music = audio.new_music_from_memory(".wav", &bytes);
+            system.buffers.push(bytes);
+            music.map_err(|e| eprintln!("Error al crear un bucle de audio: {}", e)).ok()
+        };
+        let ambient = stream(ambient_pad(24.0));
+        let engine = stream(engine_hum(1.0));
+        let rumble = stream(sun_rumble(2.0));
+        let effect = |samples: Vec<f32>| {
+            let wave = audio.new_wave_from_memory(".wav", &wav_bytes(&samples, SAMPLE_RATE)).ok()?;
+            audio.new_sound_from_wave(&wave).map_err(|e| eprintln!("Error al crear un efecto de audio: {}", e)).ok()
+        };
+        system.warp = effect(warp_whoosh(1.2));
+        system.click = effect(ui_click());
+        system.music = audio.new_music("assets/audio/music.ogg").ok().or(ambient);
+        system.engine = engine;
+        system.rumble = rumble;
+
+        for stream in [&system.music, &system.engine, &system.rumble].into_iter().flatten() {
+            stream.set_volume(0.0);
+            stream.play_stream();
+        }
+        system.apply_volumes(&AudioState::default());
+        system
+    }
+
+    /// Actualizar los streams y ajustar volúmenes y tono al estado del frame
+    pub fn update(&mut self, state: &AudioState) {
+        for stream in [&self.music, &self.engine, &self.rumble].into_iter().flatten() {
+            stream.update_stream();
+        }
+        if state.warping && !self.was_warping
+            && let Some(warp) = &self.warp
+        {
+            warp.play();
+        }
+        self.was_warping = state.warping;
+        self.apply_volumes(state);
+    }
+
+    /// Clic de la interfaz
+    pub fn click(&self) {
+        if let Some(click) = &self.click {
+            click.play();
+        }
+    }
+
+    /// Subir o bajar el volumen general en `delta`
+    pub fn adjust_master(&mut self, delta: f32) {
+        self.config.master = (self.config.master + delta).clamp(0.0, 1.0);
+    }
+
+    fn apply_volumes(&self, state: &AudioState) {
+        let music = self.config.master * self.config.music;
+        let effects = self.config.master * self.config.effects;
+        let throttle = state.throttle.clamp(0.0, 1.0);
+        if let Some(stream) = &self.music {
+            stream.set_volume(music);
+        }
+        if let Some(engine) = &self.engine {
+            // Un murmullo al ralentí que sube de tono y de volumen con el empuje
+            engine.set_volume(effects * (0.08 + 0.5 * throttle));
+            engine.set_pitch(0.7 + 0.9 * throttle);
+        }
+        if let Some(rumble) = &self.rumble {
+            let proximity = state.sun_proximity.clamp(0.0, 1.0);
+            rumble.set_volume(effects * proximity * proximity);
+        }
+        for sound in [&self.warp, &self.click].into_iter().flatten() {
+            sound.set_volume(effects);
+        }
+    }
+}
+
+/// Pad ambiental: un acorde abierto de senos que respiran con LFO lentos
+/// Las frecuencias se redondean a múltiplos de 1/`seconds` para que el bucle no tenga saltos
+fn ambient_pad(seconds: f32) -> Vec<f32> {
+    let partials = [(110.0, 0.30), (164.8, 0.22), (220.0, 0.18), (277.2, 0.12), (329.6, 0.10), (440.0, 0.05)];
+    synthesize(seconds, |t| {
+        partials
+            .iter()
+            .enumerate()
+            .map(|(i, &(frequency, amplitude))| {
+                let breathing = 0.6 + 0.4 * (2.0 * PI * looped(0.05 + i as f32 * 0.03, seconds) * t + i as f32).sin();
+                amplitude * breathing * (2.0 * PI * looped(frequency, seconds) * t).sin()
+            })
+            .sum::<f32>()
+            * 0.5
+    })
+}
+
+/// Zumbido del motor: diente de sierra grave suavizado con su octava
+fn engine_hum(seconds: f32) -> Vec<f32> {
+    synthesize(seconds, |t| {
+        let phase = (looped(55.0, seconds) * t).fract();
+        let saw = 2.0 * phase - 1.0;
+        0.18 * saw + 0.3 * (2.0 * PI * looped(110.0, seconds) * t).sin() + 0.15 * (2.0 * PI * looped(27.5, seconds) * t).sin()
+    })
+}
+
+/// Retumbar del sol: muchos senos graves de fase aleatoria (ruido periódico, así el bucle es limpio)
+fn sun_rumble(seconds: f32) -> Vec<f32> {
+    let mut seed = 0x5EED_u32;
+    let components: Vec<(f32, f32)> = (0..48)
+        .map(|k| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            let phase = (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 * PI;
+            (looped(20.0 + k as f32 * 1.5, seconds), phase)
+        })
+        .collect();
+    synthesize(seconds, |t| {
+        components.iter().map(|&(frequency, phase)| (2.0 * PI * frequency * t + phase).sin()).sum::<f32>() * 0.06
+    })
+}
+
+/// Silbido del warp: ruido filtrado cuyo corte sube y baja, con una envolvente en campana
+fn warp_whoosh(seconds: f32) -> Vec<f32> {
+    let mut seed = 0xC0FFEE_u32;
+    let mut filtered = 0.0;
+    synthesize(seconds, |t| {
+        seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+        let noise = (seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0;
+        let progress = t / seconds;
+        let envelope = (PI * progress).sin().powi(2);
+        // Filtro de un polo: el corte barre de ~200 Hz a ~4 kHz y vuelve
+        let cutoff = 200.0 + 3800.0 * envelope;
+        let alpha = 1.0 - (-2.0 * PI * cutoff / SAMPLE_RATE as f32).exp();
+        filtered += (noise - filtered) * alpha;
+        filtered * envelope * 0.8
+    })
+}
+
+/// Clic de la interfaz: un seno agudo que se apaga en 40 ms
+fn ui_click() -> Vec<f32> {
+    synthesize(0.04, |t| (2.0 * PI * 1800.0 * t).sin() * (-t * 120.0).exp() * 0.5)
+}
+
+/// Frecuencia más cercana a `frequency` que da un número entero de ciclos en `seconds`
+fn looped(frequency: f32, seconds: f32) -> f32 {
+    (frequency * seconds).round().max(1.0) / seconds
+}
+
+/// Muestras de `seconds` segundos de la señal `signal(t)`
+fn synthesize(seconds: f32, mut signal: impl FnMut(f32) -> f32) -> Vec<f32> {
+    let count = (seconds * SAMPLE_RATE as f32) as usize;
+    (0..count).map(|i| signal(i as f32 / SAMPLE_RATE as f32)).collect()
+}
+
+/// Archivo WAV (PCM de 16 bits, mono) con las muestras en [-1, 1]
+fn wav_bytes(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_size = samples.len() as u32 * 2;
+    let mut bytes = Vec::with_capacity(44 + data_size as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_size).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes()); // Tamaño del bloque fmt
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    bytes.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // Bytes por segundo
+    bytes.extend_from_slice(&2u16.to_le_bytes()); // Bytes por muestra
+    bytes.extend_from_slice(&16u16.to_le_bytes()); // Bits por muestra
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_size.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes());
+    }
+    bytes
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
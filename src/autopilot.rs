@@ -0,0 +1,250 @@
+use raylib::prelude::*;
+use crate::scene::BodyRef;
+
+/// Piloto automático: lleva la nave hasta un cuerpo con una trayectoria suave
+/// Acelera hasta la velocidad de crucero, gira la proa hacia el rumbo y frena a tiempo
+/// para detenerse a `standoff_distance` de la superficie del objetivo
+pub struct Autopilot {
+    pub target: Option<BodyRef>, // Cuerpo al que se dirige
+    pub target_point: Option<Vector3>, // Punto fijo al que se dirige cuando no va a un cuerpo (rutas)
+    pub standoff_distance: f32,  // Distancia a la superficie donde se detiene
+    pub max_speed: f32,          // Velocidad de crucero (unidades por segundo)
+    pub acceleration: f32,       // Aceleración y frenado máximos (unidades por segundo²)
+    pub turn_rate: f32,          // Fracción del giro hacia el rumbo que se completa por segundo
+    pub velocity: Vector3,
+    pub heading: Vector3,        // Dirección de la proa (normalizada)
+    pub docking_distance: f32,   // Distancia al puerto de atraque donde se detiene
+    approach_axis: Option<Vector3>, // Dirección de llegada al puerto (solo al atracar)
+    start_distance: f32,
+    remaining_distance: f32,
+    last_target_position: Option<Vector3>,
+}
+
+impl Autopilot {
+    pub fn new() -> Self {
+        Autopilot {
+            target: None,
+            target_point: None,
+            standoff_distance: 6.0,
+            max_speed: 30.0,
+            acceleration: 12.0,
+            turn_rate: 3.0,
+            velocity: Vector3::zero(),
+            heading: Vector3::new(0.0, 0.0, -1.0),
+            docking_distance: 1.2,
+            approach_axis: None,
+            start_distance: 0.0,
+            remaining_distance: 0.0,
+            last_target_position: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.target.is_some() || self.target_point.is_some()
+    }
+
+    /// Activar el piloto automático desde la posición y orientación actuales de la nave
+    pub fn engage(&mut self, target: BodyRef, ship_position: Vector3, heading: Vector3, target_position: Vector3, target_radius: f32) {
+        self.approach_axis = None;
+        self.start(target, ship_position, heading, target_position, target_radius);
+    }
+
+    /// Activar el piloto automático hacia un puerto de atraque: la nave llega por `axis`
+    /// y se detiene a `docking_distance` del puerto. En `update`, `target_position` es el puerto
+    pub fn engage_dock(&mut self, target: BodyRef, ship_position: Vector3, heading: Vector3, port: Vector3, axis: Vector3) {
+        self.approach_axis = Some(normalize_or(axis, Vector3::new(0.0, 1.0, 0.0)));
+        self.start(target, ship_position, heading, port, 0.0);
+    }
+
+    /// Activar el piloto automático hacia un punto fijo del espacio: la nave se detiene sobre él
+    /// En `update`, `target_position` es el punto y `target_radius` se ignora
+    pub fn engage_point(&mut self, ship_position: Vector3, heading: Vector3, point: Vector3) {
+        self.approach_axis = None;
+        self.target = None;
+        self.target_point = Some(point);
+        self.velocity = Vector3::zero();
+        self.heading = normalize_or(heading, self.heading);
+        self.last_target_position = None;
+        self.start_distance = distance(ship_position, point).max(0.001);
+        self.remaining_distance = self.start_distance;
+    }
+
+    /// Si el trayecto actual termina en un puerto de atraque
+    pub fn is_docking(&self) -> bool {
+        self.target.is_some() && self.approach_axis.is_some()
+    }
+
+    fn start(&mut self, target: BodyRef, ship_position: Vector3, heading: Vector3, target_position: Vector3, target_radius: f32) {
+        self.target = Some(target);
+        self.target_point = None;
+        self.velocity = Vector3::zero();
+        self.heading = normalize_or(heading, self.heading);
+        self.last_target_position = None;
+        self.start_distance = distance(ship_position, self.stop_point(ship_position, target_position, target_radius)).max(0.001);
+        self.remaining_distance = self.start_distance;
+    }
+
+    /// Cancelar el piloto automático (la nave se queda donde está)
+    pub fn cancel(&mut self) {
+        self.target = None;
+        self.target_point = None;
+        self.approach_axis = None;
+        self.velocity = Vector3::zero();
+        self.last_target_position = None;
+    }
+
+    /// Progreso del trayecto en [0, 1]
+    pub fn progress(&self) -> f32 {
+        if self.start_distance <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - self.remaining_distance / self.start_distance).clamp(0.0, 1.0)
+    }
+
+    /// Distancia restante hasta el punto de parada
+    pub fn remaining_distance(&self) -> f32 {
+        self.remaining_distance
+    }
+
+    /// Punto de parada: sobre la línea objetivo-nave, a la distancia de seguridad de la superficie
+    /// Al atracar, frente al puerto sobre su eje de llegada
+    fn stop_point(&self, ship_position: Vector3, target_position: Vector3, target_radius: f32) -> Vector3 {
+        if self.target_point.is_some() {
+            return target_position;
+        }
+        if let Some(axis) = self.approach_axis {
+            return Vector3::new(
+                target_position.x + axis.x * self.docking_distance,
+                target_position.y + axis.y * self.docking_distance,
+                target_position.z + axis.z * self.docking_distance,
+            );
+        }
+        let away = normalize_or(
+            Vector3::new(
+                ship_position.x - target_position.x,
+                ship_position.y - target_position.y,
+                ship_position.z - target_position.z,
+            ),
+            Vector3::new(0.0, 0.0, 1.0),
+        );
+        let stop_radius = target_radius + self.standoff_distance;
+        Vector3::new(
+            target_position.x + away.x * stop_radius,
+            target_position.y + away.y * stop_radius,
+            target_position.z + away.z * stop_radius,
+        )
+    }
+
+    /// Avanzar un paso: devuelve la nueva posición de la nave
+    /// El objetivo se mueve por su órbita, así que su velocidad se estima entre frames y se suma
+    /// a la velocidad deseada; al llegar (cerca y casi en reposo relativo) se desactiva solo
+    pub fn update(&mut self, ship_position: Vector3, target_position: Vector3, target_radius: f32, delta_time: f32) -> Vector3 {
+        if !self.is_active() || delta_time <= 0.0 {
+            return ship_position;
+        }
+
+        let target_velocity = match self.last_target_position {
+            Some(last) => Vector3::new(
+                (target_position.x - last.x) / delta_time,
+                (target_position.y - last.y) / delta_time,
+                (target_position.z - last.z) / delta_time,
+            ),
+            None => Vector3::zero(),
+        };
+        self.last_target_position = Some(target_position);
+
+        let stop = self.stop_point(ship_position, target_position, target_radius);
+        let to_stop = Vector3::new(stop.x - ship_position.x, stop.y - ship_position.y, stop.z - ship_position.z);
+        let dist = distance(ship_position, stop);
+        self.remaining_distance = dist;
+
+        // Perfil de frenado: la velocidad máxima con la que aún se puede parar en `dist`
+        let approach_speed = (2.0 * self.acceleration * dist).sqrt().min(self.max_speed);
+        let direction = normalize_or(to_stop, self.heading);
+        let desired = Vector3::new(
+            target_velocity.x + direction.x * approach_speed,
+            target_velocity.y + direction.y * approach_speed,
+            target_velocity.z + direction.z * approach_speed,
+        );
+
+        // Cambio de velocidad limitado por la aceleración
+        let mut dv = Vector3::new(desired.x - self.velocity.x, desired.y - self.velocity.y, desired.z - self.velocity.z);
+        let dv_length = length(dv);
+        let max_dv = self.acceleration * delta_time;
+        if dv_length > max_dv {
+            dv = Vector3::new(dv.x / dv_length * max_dv, dv.y / dv_length * max_dv, dv.z / dv_length * max_dv);
+        }
+        self.velocity = Vector3::new(self.velocity.x + dv.x, self.velocity.y + dv.y, self.velocity.z + dv.z);
+
+        // La proa gira suavemente hacia el rumbo; al final del trayecto, hacia el objetivo
+        let relative_speed = length(Vector3::new(
+            self.velocity.x - target_velocity.x,
+            self.velocity.y - target_velocity.y,
+            self.velocity.z - target_velocity.z,
+        ));
+        let facing = if relative_speed > 1.0 {
+            Vector3::new(
+                self.velocity.x - target_velocity.x,
+                self.velocity.y - target_velocity.y,
+                self.velocity.z - target_velocity.z,
+            )
+        } else {
+            Vector3::new(
+                target_position.x - ship_position.x,
+                target_position.y - ship_position.y,
+                target_position.z - ship_position.z,
+            )
+        };
+        let facing = normalize_or(facing, self.heading);
+        let turn = (self.turn_rate * delta_time).min(1.0);
+        self.heading = normalize_or(
+            Vector3::new(
+                self.heading.x + (facing.x - self.heading.x) * turn,
+                self.heading.y + (facing.y - self.heading.y) * turn,
+                self.heading.z + (facing.z - self.heading.z) * turn,
+            ),
+            facing,
+        );
+
+        let new_position = Vector3::new(
+            ship_position.x + self.velocity.x * delta_time,
+            ship_position.y + self.velocity.y * delta_time,
+            ship_position.z + self.velocity.z * delta_time,
+        );
+
+        // Llegada
+        if dist < 0.2 && relative_speed < 0.5 {
+            self.remaining_distance = 0.0;
+            self.target = None;
+            self.target_point = None;
+            self.velocity = Vector3::zero();
+            self.last_target_position = None;
+            return stop;
+        }
+
+        new_position
+    }
+}
+
+impl Default for Autopilot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn length(v: Vector3) -> f32 {
+    (v.x * v.x + v.y * v.y + v.z * v.z).sqrt()
+}
+
+fn distance(a: Vector3, b: Vector3) -> f32 {
+    length(Vector3::new(b.x - a.x, b.y - a.y, b.z - a.z))
+}
+
+fn normalize_or(v: Vector3, fallback: Vector3) -> Vector3 {
+    let len = length(v);
+    if len > 0.0001 {
+        Vector3::new(v.x / len, v.y / len, v.z / len)
+    } else {
+        fallback
+    }
+}
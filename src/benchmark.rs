@@ -0,0 +1,116 @@
+use raylib::prelude::*;
+use crate::render_stats::RenderStats;
+use std::f32::consts::PI;
+use std::time::Duration;
+
+/// Paso de tiempo de cada frame del benchmark: la escena avanza igual en cualquier equipo
+pub const BENCH_FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Modo benchmark (`--bench N`): dibuja N frames con una cámara guionizada, sin entrada del
+/// jugador ni límite de FPS, y resume los tiempos de cada etapa del pipeline, los triángulos y
+/// fragmentos por frame y los percentiles del tiempo de frame. Como la cámara y el paso de tiempo
+/// son siempre los mismos, dos ejecuciones con la misma escena son comparables
+pub struct Benchmark {
+    pub frames: usize,          // Frames a medir
+    frame_times: Vec<Duration>, // Tiempo de cada frame medido
+    totals: RenderStats,        // Suma de las estadísticas de todos los frames
+}
+
+impl Benchmark {
+    pub fn new(frames: usize) -> Self {
+        Benchmark {
+            frames: frames.max(1),
+            frame_times: Vec::with_capacity(frames),
+            totals: RenderStats::new(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame_times.len() >= self.frames
+    }
+
+    /// Ojo y objetivo de la cámara en el frame actual: una vuelta completa alrededor del sol
+    /// durante el benchmark, acercándose y alejándose y subiendo sobre la eclíptica, para pasar
+    /// por vistas de cerca (muchos fragmentos) y de todo el sistema (muchos triángulos pequeños)
+    pub fn camera_pose(&self, extent: f32) -> (Vector3, Vector3) {
+        let t = self.frame_times.len() as f32 / self.frames as f32;
+        let angle = t * 2.0 * PI;
+        let radius = extent * (0.45 + 0.3 * (angle * 2.0).cos());
+        let height = extent * (0.08 + 0.12 * angle.sin().abs());
+        let eye = Vector3::new(radius * angle.cos(), height, radius * angle.sin());
+        (eye, Vector3::zero())
+    }
+
+    /// Registrar un frame medido
+    pub fn record(&mut self, frame_time: Duration, stats: &RenderStats) {
+        self.frame_times.push(frame_time);
+        self.totals.add(stats);
+    }
+
+    /// Tiempo de frame del percentil `p` (0-100) en milisegundos
+    pub fn percentile(&self, p: f32) -> f32 {
+        if self.frame_times.is_empty() {
+            return 0.0;
+        }
+        let mut sorted = self.frame_times.clone();
+        sorted.sort();
+        let index = ((p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f32).round() as usize;
+        sorted[index].as_secs_f32() * 1000.0
+    }
+
+    /// Resumen de los frames medidos, listo para imprimir
+    pub fn report(&self) -> String {
+        let count = self.frame_times.len().max(1) as f32;
+        let total: Duration = self.frame_times.iter().sum();
+        let average_ms = total.as_secs_f32() * 1000.0 / count;
+        let per_frame_ms = |time: Duration| time.as_secs_f32() * 1000.0 / count;
+        let other = total.saturating_sub(self.totals.pipeline_time());
+
+        let mut report = format!(
+            "Benchmark: {} frames en {:.2} s ({:.1} FPS de media)\n",
+            self.frame_times.len(),
+            total.as_secs_f32(),
+            1000.0 / average_ms.max(0.0001),
+        );
+        report.push_str(&format!(
+            "Tiempo de frame (ms): media {:.2}  p50 {:.2}  p90 {:.2}  p99 {:.2}  máx {:.2}\n",
+            average_ms,
+            self.percentile(50.0),
+            self.percentile(90.0),
+            self.percentile(99.0),
+            self.percentile(100.0),
+        ));
+        report.push_str("Etapas por frame (ms):\n");
+        for (name, time) in [
+            ("vertex", self.totals.vertex_time),
+            ("rasterización", self.totals.raster_time),
+            ("fragment", self.totals.fragment_time),
+            ("blit", self.totals.blit_time),
+            ("resto", other),
+        ] {
+            report.push_str(&format!(
+                "  {:<14} {:7.2}  ({:4.1}%)\n",
+                name,
+                per_frame_ms(time),
+                time.as_secs_f32() / total.as_secs_f32().max(0.000001) * 100.0,
+            ));
+        }
+        let per_frame = |value: u64| value as f32 / count;
+        report.push_str(&format!(
+            "Triángulos por frame: {:.0} ({:.0} descartados, {:.0} recortados)\n",
+            per_frame(self.totals.triangles),
+            per_frame(self.totals.culled_triangles),
+            per_frame(self.totals.clipped_triangles),
+        ));
+        report.push_str(&format!(
+            "Fragmentos por frame: {:.0} ({:.0} sombreados, {:.0} tapados)",
+            per_frame(self.totals.fragments),
+            per_frame(self.totals.shaded_fragments),
+            per_frame(self.totals.depth_rejected_fragments),
+        ));
+        if self.totals.gpu_fragments > 0 {
+            report.push_str(&format!(" ({:.0} en la GPU)", per_frame(self.totals.gpu_fragments)));
+        }
+        report
+    }
+}
@@ -1,9 +1,31 @@
-#![allow(dead_code)]
-
 use raylib::prelude::*;
+use crate::input_map::Action;
+use crate::input_state::InputState;
 use crate::matrix::create_view_matrix;
 use std::f32::consts::PI;
 
+/// Modos de cámara disponibles (se alternan con C)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CameraMode {
+    Chase,   // Tercera persona detrás de la nave (comportamiento original)
+    Cockpit, // Primera persona desde dentro de la nave (sin malla externa)
+    FreeFly, // Vuelo libre sin nave: la cámara mira hacia donde apunta
+    Orbit,   // Órbita alrededor del planeta seleccionado (arrastrar con el ratón + rueda para zoom)
+}
+
+impl CameraMode {
+    /// Siguiente modo en el ciclo Chase -> Cockpit -> FreeFly -> Orbit -> Chase
+    pub fn next(self) -> Self {
+        match self {
+            CameraMode::Chase => CameraMode::Cockpit,
+            CameraMode::Cockpit => CameraMode::FreeFly,
+            CameraMode::FreeFly => CameraMode::Orbit,
+            CameraMode::Orbit => CameraMode::Chase,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Camera {
     // Camera position/orientation
     pub eye: Vector3,        // Camera position
@@ -19,13 +41,40 @@ pub struct Camera {
     pub rotation_speed: f32,
     pub zoom_speed: f32,
     pub pan_speed: f32,
+    pub mouse_sensitivity: f32, // Radianes por píxel al arrastrar en modo órbita
+
+    // Inercia: el giro y el desplazamiento siguen a los controles con una velocidad suavizada
+    // Cada constante es la rapidez (1/s) con la que la velocidad se acerca a la pedida; infinito = sin inercia
+    pub rotation_acceleration: f32, // Al mantener una tecla de giro
+    pub rotation_damping: f32,      // Al soltarla (cuánto sigue girando por inercia)
+    pub pan_acceleration: f32,
+    pub pan_damping: f32,
+    yaw_velocity: f32,              // rad/s
+    pitch_velocity: f32,            // rad/s
+    pan_velocity: Vector3,          // Desplazamiento pedido en ejes de la cámara (x derecha, y arriba, z adelante), en [-1, 1]
+
+    pub mode: CameraMode,
+
+    // Transición suave entre modos: se interpola desde la vista anterior
+    transition_from_eye: Vector3,
+    transition_from_target: Vector3,
+    transition_progress: f32, // 0.0 = recién cambiado, 1.0 = transición terminada
+    pub transition_duration: f32, // Duración en segundos
+
+    // Ojo corregido por colisión en persecución (solo para renderizar; `eye` sigue siendo el deseado)
+    collision_eye: Option<Vector3>,
 
     // Planet tracking
     pub tracking_planet: Option<usize>, // Índice del planeta que se está siguiendo (None = modo libre)
+    pub tracking_moon: Option<usize>,   // Índice de la luna que se está siguiendo (tiene prioridad sobre el planeta)
+    pub follow_distance: f32, // Distancia de seguimiento al cuerpo rastreado (ajustable con la rueda del ratón)
     pub ecliptic_height: f32, // Altura fija sobre el plano eclíptico
 }
 
 impl Camera {
+    /// Separación mínima entre la cámara de persecución y la superficie de un cuerpo
+    pub const COLLISION_MARGIN: f32 = 0.5;
+
     pub fn new(eye: Vector3, target: Vector3, up: Vector3) -> Self {
         // Calculate initial yaw and pitch from eye and target
         let direction = Vector3::new(
@@ -48,10 +97,26 @@ impl Camera {
             yaw,
             pitch,
             distance,
-            rotation_speed: 0.02,  // Velocidad de rotación reducida
+            rotation_speed: 1.2,   // Velocidad de rotación con el teclado (rad/s)
             zoom_speed: 0.2,        // Velocidad de zoom reducida
-            pan_speed: 0.15,       // Velocidad de movimiento con flechas (aumentada)
+            pan_speed: 18.0,       // Velocidad de movimiento con flechas (unidades por segundo)
+            mouse_sensitivity: 0.005,
+            rotation_acceleration: 8.0,
+            rotation_damping: 5.0,
+            pan_acceleration: 12.0,
+            pan_damping: 12.0,
+            yaw_velocity: 0.0,
+            pitch_velocity: 0.0,
+            pan_velocity: Vector3::zero(),
+            mode: CameraMode::Chase,
+            transition_from_eye: eye,
+            transition_from_target: target,
+            transition_progress: 1.0,
+            transition_duration: 0.6,
+            collision_eye: None,
             tracking_planet: None, // Inicialmente no sigue ningún planeta
+            tracking_moon: None,
+            follow_distance: 12.0,
             ecliptic_height,
         }
     }
@@ -74,6 +139,13 @@ impl Camera {
     /// Configurar la cámara para seguir un planeta específico
     pub fn track_planet(&mut self, planet_index: Option<usize>) {
         self.tracking_planet = planet_index;
+        self.tracking_moon = None;
+    }
+
+    /// Configurar la cámara para seguir una luna específica
+    pub fn track_moon(&mut self, moon_index: Option<usize>) {
+        self.tracking_moon = moon_index;
+        self.tracking_planet = None;
     }
 
     /// Actualizar el target para seguir el planeta que se está rastreando
@@ -86,106 +158,325 @@ impl Camera {
         self.target.z += (planet_position.z - self.target.z) * smoothing;
     }
 
-    /// Get the view matrix for this camera
-    pub fn get_view_matrix(&self) -> Matrix {
-        create_view_matrix(self.eye, self.target, self.up)
+    /// Indica si la cámara está siguiendo algún cuerpo (planeta o luna)
+    pub fn is_tracking(&self) -> bool {
+        self.tracking_planet.is_some() || self.tracking_moon.is_some()
     }
 
-    /// Process keyboard input to control the camera libre (FPS-style)
-    /// Cámara libre que se desplaza por el skybox con zoom fijo
-    pub fn process_input(&mut self, window: &RaylibHandle) {
-        // Calcular direcciones de la cámara basadas en yaw y pitch
-        let cos_yaw = self.yaw.cos();
-        let sin_yaw = self.yaw.sin();
-        let cos_pitch = self.pitch.cos();
-        let sin_pitch = self.pitch.sin();
-        
-        // Dirección forward de la cámara
-        let forward = Vector3::new(
-            cos_yaw * cos_pitch,
-            sin_pitch,
-            sin_yaw * cos_pitch,
-        );
-        
-        // Dirección right de la cámara
-        let right = Vector3::new(
-            -sin_yaw,
-            0.0,
-            cos_yaw,
-        );
-        
-        // Dirección up de la cámara (no se usa actualmente, pero se mantiene para futuras extensiones)
-        let _up = Vector3::new(
-            -cos_yaw * sin_pitch,
-            cos_pitch,
-            -sin_yaw * sin_pitch,
+    /// Seguir un cuerpo en órbita: el target se acerca suavemente al cuerpo y el ojo
+    /// se coloca detrás de él según yaw/pitch a `follow_distance`
+    /// `body_radius` evita que la cámara entre en el cuerpo al acercarse
+    pub fn follow_tracked_body(&mut self, body_position: Vector3, body_radius: f32) {
+        self.update_planet_tracking(body_position);
+
+        self.follow_distance = self.follow_distance.max(body_radius * 1.5 + 1.0);
+        self.place_eye_behind_target(self.follow_distance);
+    }
+
+    /// Modo órbita: la cámara gira alrededor del cuerpo a `distance`
+    /// La distancia se limita para no entrar en el cuerpo ni alejarse demasiado
+    pub fn orbit_around(&mut self, body_position: Vector3, body_radius: f32) {
+        self.update_planet_tracking(body_position);
+
+        self.distance = self.distance.clamp(body_radius * 1.5 + 1.0, 150.0);
+        self.place_eye_behind_target(self.distance);
+    }
+
+    /// Colocar el ojo detrás del target en la dirección opuesta a forward (yaw/pitch)
+    fn place_eye_behind_target(&mut self, distance: f32) {
+        let forward = self.forward();
+        self.eye = Vector3::new(
+            self.target.x - forward.x * distance,
+            self.target.y - forward.y * distance,
+            self.target.z - forward.z * distance,
         );
+    }
 
-        // Rotation controls (yaw) - A/D
-        if window.is_key_down(KeyboardKey::KEY_A) {
-            self.yaw += self.rotation_speed;
+    /// Dirección forward de la cámara basada en yaw y pitch
+    pub fn forward(&self) -> Vector3 {
+        Vector3::new(
+            self.yaw.cos() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.sin() * self.pitch.cos(),
+        )
+    }
+
+    /// Cambiar de modo de cámara iniciando una transición suave desde la vista actual
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
         }
-        if window.is_key_down(KeyboardKey::KEY_D) {
-            self.yaw -= self.rotation_speed;
+        self.transition_from_eye = self.view_eye();
+        self.transition_from_target = self.view_target();
+        self.transition_progress = 0.0;
+        self.mode = mode;
+    }
+
+    /// Avanzar la transición entre modos
+    pub fn update_transition(&mut self, delta_time: f32) {
+        if self.transition_progress < 1.0 {
+            self.transition_progress = (self.transition_progress + delta_time / self.transition_duration.max(0.0001)).min(1.0);
         }
+    }
+
+    /// Indica si la vista es la de cabina (la nave no se dibuja y se muestra el marco)
+    pub fn is_cockpit_view(&self) -> bool {
+        self.mode == CameraMode::Cockpit && !self.is_tracking()
+    }
+
+    /// Factor de interpolación de la transición con easing (smoothstep)
+    fn transition_factor(&self) -> f32 {
+        let t = self.transition_progress;
+        t * t * (3.0 - 2.0 * t)
+    }
 
-        // Rotation controls (pitch) - W/S
-        if window.is_key_down(KeyboardKey::KEY_W) {
-            self.pitch += self.rotation_speed;
-            self.pitch = self.pitch.clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1); // Limitar pitch
+    /// Posición desde la que se renderiza (eye interpolado durante la transición)
+    /// Si hay una corrección por colisión activa, se usa en lugar de `eye`
+    pub fn view_eye(&self) -> Vector3 {
+        let eye = self.collision_eye.unwrap_or(self.eye);
+        if self.transition_progress >= 1.0 {
+            return eye;
         }
-        if window.is_key_down(KeyboardKey::KEY_S) {
-            self.pitch -= self.rotation_speed;
-            self.pitch = self.pitch.clamp(-PI / 2.0 + 0.1, PI / 2.0 - 0.1); // Limitar pitch
+        let t = self.transition_factor();
+        Vector3::new(
+            self.transition_from_eye.x + (eye.x - self.transition_from_eye.x) * t,
+            self.transition_from_eye.y + (eye.y - self.transition_from_eye.y) * t,
+            self.transition_from_eye.z + (eye.z - self.transition_from_eye.z) * t,
+        )
+    }
+
+    /// Evitar que la cámara de persecución quede dentro de un cuerpo: lanza un rayo desde
+    /// `pivot` (la nave) hasta el ojo deseado y, si cruza alguna esfera `(centro, radio)`,
+    /// adelanta la cámara a un punto delante del primer cuerpo intersectado
+    pub fn resolve_collisions(&mut self, pivot: Vector3, obstacles: &[(Vector3, f32)]) {
+        self.collision_eye = None;
+        let to_eye = Vector3::new(self.eye.x - pivot.x, self.eye.y - pivot.y, self.eye.z - pivot.z);
+        let length = (to_eye.x * to_eye.x + to_eye.y * to_eye.y + to_eye.z * to_eye.z).sqrt();
+        if length < 0.0001 {
+            return;
         }
+        let dir = Vector3::new(to_eye.x / length, to_eye.y / length, to_eye.z / length);
 
-        // Movimiento libre de la cámara (desplazamiento por el skybox)
-        // Q/E para movimiento lateral
-        if window.is_key_down(KeyboardKey::KEY_Q) {
-            self.eye.x -= right.x * self.pan_speed;
-            self.eye.z -= right.z * self.pan_speed;
+        // Intersección rayo-esfera: |pivot + dir * t - centro| = radio
+        let mut nearest = length;
+        for &(center, radius) in obstacles {
+            let radius = radius + Self::COLLISION_MARGIN;
+            let oc = Vector3::new(pivot.x - center.x, pivot.y - center.y, pivot.z - center.z);
+            let b = oc.x * dir.x + oc.y * dir.y + oc.z * dir.z;
+            let c = oc.x * oc.x + oc.y * oc.y + oc.z * oc.z - radius * radius;
+            if c <= 0.0 {
+                continue; // La nave misma está dentro del margen: no hay posición válida en el rayo
+            }
+            let discriminant = b * b - c;
+            if discriminant < 0.0 {
+                continue;
+            }
+            let t = -b - discriminant.sqrt();
+            if t >= 0.0 && t < nearest {
+                nearest = t;
+            }
         }
-        if window.is_key_down(KeyboardKey::KEY_E) {
-            self.eye.x += right.x * self.pan_speed;
-            self.eye.z += right.z * self.pan_speed;
+
+        if nearest < length {
+            let t = nearest.max(0.1);
+            self.collision_eye = Some(Vector3::new(pivot.x + dir.x * t, pivot.y + dir.y * t, pivot.z + dir.z * t));
         }
+    }
+
+    /// Quitar la corrección por colisión (modos en los que no se aplica)
+    pub fn clear_collision(&mut self) {
+        self.collision_eye = None;
+    }
+
+    /// Punto al que se mira durante el render (target interpolado durante la transición)
+    pub fn view_target(&self) -> Vector3 {
+        if self.transition_progress >= 1.0 {
+            return self.target;
+        }
+        let t = self.transition_factor();
+        Vector3::new(
+            self.transition_from_target.x + (self.target.x - self.transition_from_target.x) * t,
+            self.transition_from_target.y + (self.target.y - self.transition_from_target.y) * t,
+            self.transition_from_target.z + (self.target.z - self.transition_from_target.z) * t,
+        )
+    }
 
-        // Left/Right arrow keys para movimiento lateral
-        if window.is_key_down(KeyboardKey::KEY_LEFT) {
-            self.eye.x -= right.x * self.pan_speed;
-            self.eye.z -= right.z * self.pan_speed;
+    /// Colocar la cámara en `eye` mirando a `target`, ajustando yaw/pitch
+    /// para que los modos libres continúen con la misma orientación
+    pub fn look_from(&mut self, eye: Vector3, target: Vector3) {
+        let direction = Vector3::new(target.x - eye.x, target.y - eye.y, target.z - eye.z);
+        let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt();
+        if length > 0.0001 {
+            self.pitch = (direction.y / length).asin();
+            self.yaw = direction.z.atan2(direction.x);
         }
-        if window.is_key_down(KeyboardKey::KEY_RIGHT) {
-            self.eye.x += right.x * self.pan_speed;
-            self.eye.z += right.z * self.pan_speed;
+        self.eye = eye;
+        self.target = target;
+    }
+
+    /// Copia de la cámara a una fracción `alpha` del camino desde `previous` (el estado del paso
+    /// de simulación anterior) hasta la actual; solo para renderizar entre dos pasos
+    pub fn interpolated(&self, previous: &Camera, alpha: f32) -> Camera {
+        let lerp = |a: Vector3, b: Vector3| {
+            Vector3::new(a.x + (b.x - a.x) * alpha, a.y + (b.y - a.y) * alpha, a.z + (b.z - a.z) * alpha)
+        };
+        let mut camera = self.clone();
+        camera.eye = lerp(previous.eye, self.eye);
+        camera.target = lerp(previous.target, self.target);
+        camera.yaw = lerp_angle(previous.yaw, self.yaw, alpha);
+        camera.pitch = previous.pitch + (self.pitch - previous.pitch) * alpha;
+        camera.collision_eye = match (previous.collision_eye, self.collision_eye) {
+            (Some(a), Some(b)) => Some(lerp(a, b)),
+            (_, current) => current,
+        };
+        if previous.mode == self.mode {
+            camera.transition_progress = previous.transition_progress + (self.transition_progress - previous.transition_progress) * alpha;
+        }
+        camera
+    }
+
+    /// Get the view matrix for this camera
+    pub fn get_view_matrix(&self) -> Matrix {
+        create_view_matrix(self.view_eye(), self.view_target(), self.up)
+    }
+
+    /// Detiene la inercia del giro y del desplazamiento (teletransporte, controles bloqueados)
+    pub fn stop_motion(&mut self) {
+        self.yaw_velocity = 0.0;
+        self.pitch_velocity = 0.0;
+        self.pan_velocity = Vector3::zero();
+    }
+
+    /// Frena la inercia del giro con rapidez `rate` (1/s), mucho antes que `rotation_damping`
+    pub fn damp_rotation(&mut self, rate: f32, delta_time: f32) {
+        let keep = (-rate * delta_time).exp();
+        self.yaw_velocity *= keep;
+        self.pitch_velocity *= keep;
+    }
+
+    /// Gira la vista hacia `direction` como mucho `max_rate` rad/s, sin inercia (el SAS)
+    pub fn turn_towards(&mut self, direction: Vector3, max_rate: f32, delta_time: f32) {
+        let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt();
+        if length < 0.0001 {
+            return;
+        }
+        let pitch_limit = PI / 2.0 - 0.1;
+        let goal_pitch = (direction.y / length).asin().clamp(-pitch_limit, pitch_limit);
+        let goal_yaw = direction.z.atan2(direction.x);
+        // Por el camino corto: la diferencia de yaw se lleva a [-PI, PI)
+        let yaw_error = (goal_yaw - self.yaw + PI).rem_euclid(2.0 * PI) - PI;
+        let step = max_rate * delta_time;
+        self.yaw += yaw_error.clamp(-step, step);
+        self.pitch += (goal_pitch - self.pitch).clamp(-step, step);
+        self.yaw_velocity = 0.0;
+        self.pitch_velocity = 0.0;
+
+        if self.mode == CameraMode::FreeFly || self.mode == CameraMode::Cockpit {
+            let forward = self.forward();
+            self.target = Vector3::new(self.eye.x + forward.x, self.eye.y + forward.y, self.eye.z + forward.z);
         }
+    }
+
+    /// Desplazamiento pedido por los controles en el mundo, ya suavizado: cada tecla aporta
+    /// hasta longitud 1 en su dirección (laterales en el plano horizontal, vertical en Y)
+    pub fn pan_command(&self) -> Vector3 {
+        let forward = self.forward();
+        let right = Vector3::new(-self.yaw.sin(), 0.0, self.yaw.cos());
+        let pan = self.pan_velocity;
+        Vector3::new(
+            right.x * pan.x + forward.x * pan.z,
+            pan.y + forward.y * pan.z,
+            right.z * pan.x + forward.z * pan.z,
+        )
+    }
 
-        // Up/Down arrow keys para movimiento forward/backward
-        if window.is_key_down(KeyboardKey::KEY_UP) {
-            self.eye.x += forward.x * self.pan_speed;
-            self.eye.y += forward.y * self.pan_speed;
-            self.eye.z += forward.z * self.pan_speed;
+    /// Process keyboard input to control the camera libre (FPS-style)
+    /// Cámara libre que se desplaza por el skybox con zoom fijo
+    /// El giro y el desplazamiento tienen inercia: sus velocidades se acercan a las que piden
+    /// las teclas según `rotation_acceleration`/`pan_acceleration` y se frenan al soltarlas según
+    /// `rotation_damping`/`pan_damping`, siempre escaladas por `delta_time`
+    pub fn process_input(&mut self, input: &InputState, delta_time: f32) {
+        // C alterna entre persecución, cabina, vuelo libre y órbita
+        if input.is_pressed(Action::CameraMode) {
+            self.set_mode(self.mode.next());
         }
-        if window.is_key_down(KeyboardKey::KEY_DOWN) {
-            self.eye.x -= forward.x * self.pan_speed;
-            self.eye.y -= forward.y * self.pan_speed;
-            self.eye.z -= forward.z * self.pan_speed;
+
+        let axis = |positive: Action, negative: Action| {
+            (input.is_down(positive) as i32 - input.is_down(negative) as i32) as f32
+        };
+
+        // Rotation controls (yaw) - A/D, (pitch) - W/S
+        let target_yaw = axis(Action::YawLeft, Action::YawRight) * self.rotation_speed;
+        let target_pitch = axis(Action::PitchUp, Action::PitchDown) * self.rotation_speed;
+        self.yaw_velocity = approach(self.yaw_velocity, target_yaw, self.rotation_acceleration, self.rotation_damping, delta_time);
+        self.pitch_velocity = approach(self.pitch_velocity, target_pitch, self.rotation_acceleration, self.rotation_damping, delta_time);
+        self.yaw += self.yaw_velocity * delta_time;
+        let pitch_limit = PI / 2.0 - 0.1;
+        let pitch = self.pitch + self.pitch_velocity * delta_time;
+        self.pitch = pitch.clamp(-pitch_limit, pitch_limit);
+        if self.pitch != pitch {
+            // Al llegar al límite no queda inercia que empuje contra él
+            self.pitch_velocity = 0.0;
         }
 
-        // R/F para movimiento vertical
-        if window.is_key_down(KeyboardKey::KEY_R) {
-            self.eye.y += self.pan_speed;
+        // Modo órbita: arrastrar con el botón izquierdo gira alrededor del cuerpo
+        // y la rueda acerca/aleja (orbit_around limita la distancia)
+        if self.mode == CameraMode::Orbit {
+            self.pan_velocity = Vector3::zero();
+            if input.is_down(Action::OrbitDrag) {
+                let delta = input.mouse_delta;
+                self.yaw -= delta.x * self.mouse_sensitivity;
+                self.pitch -= delta.y * self.mouse_sensitivity;
+                self.pitch = self.pitch.clamp(-pitch_limit, pitch_limit);
+            }
+            let wheel = input.mouse_wheel;
+            if wheel != 0.0 {
+                // Zoom proporcional a la distancia para que se sienta igual de cerca y de lejos
+                self.distance *= 1.0 - wheel * self.zoom_speed * 0.5;
+            }
+            return;
         }
-        if window.is_key_down(KeyboardKey::KEY_F) {
-            self.eye.y -= self.pan_speed;
+
+        // Al seguir un cuerpo, la posición la decide follow_tracked_body:
+        // solo se permite rotar alrededor del cuerpo y ajustar la distancia con la rueda
+        if self.is_tracking() {
+            self.pan_velocity = Vector3::zero();
+            let wheel = input.mouse_wheel;
+            if wheel != 0.0 {
+                self.follow_distance = (self.follow_distance - wheel * self.zoom_speed * 5.0).clamp(3.0, 80.0);
+            }
+            return;
         }
 
-        // Zoom fijo - no se permite cambiar la distancia
-        // La distancia se mantiene constante
-        self.distance = 20.0; // Distancia fija para vista tercera persona
-        
-        // El target se actualiza en main.rs después de posicionar la nave
-        // No actualizamos el target aquí para evitar conflictos
+        // Movimiento libre de la cámara (desplazamiento por el skybox)
+        // Q/E o flechas izquierda/derecha lateral, arriba/abajo adelante/atrás, R/F vertical
+        let target_pan = Vector3::new(
+            axis(Action::StrafeRight, Action::StrafeLeft),
+            axis(Action::Ascend, Action::Descend),
+            axis(Action::Forward, Action::Backward),
+        );
+        let pan = |current: f32, target: f32| approach(current, target, self.pan_acceleration, self.pan_damping, delta_time);
+        self.pan_velocity = Vector3::new(
+            pan(self.pan_velocity.x, target_pan.x),
+            pan(self.pan_velocity.y, target_pan.y),
+            pan(self.pan_velocity.z, target_pan.z),
+        );
+        let command = self.pan_command();
+        let step = self.pan_speed * delta_time;
+        self.eye.x += command.x * step;
+        self.eye.y += command.y * step;
+        self.eye.z += command.z * step;
+
+        // Vuelo libre y cabina: la cámara mira hacia donde apunta
+        // En persecución el target se actualiza en el paso del juego (`app`) después de posicionar la nave
+        if self.mode == CameraMode::FreeFly || self.mode == CameraMode::Cockpit {
+            let forward = self.forward();
+            self.target = Vector3::new(
+                self.eye.x + forward.x,
+                self.eye.y + forward.y,
+                self.eye.z + forward.z,
+            );
+        }
     }
 
     /// Obtener el índice del planeta que se está siguiendo
@@ -193,6 +484,11 @@ impl Camera {
         self.tracking_planet
     }
 
+    /// Obtener el índice de la luna que se está siguiendo
+    pub fn get_tracking_moon(&self) -> Option<usize> {
+        self.tracking_moon
+    }
+
     /// Teletransportar la cámara a una nueva posición y target
     pub fn warp_to(&mut self, new_position: Vector3, new_target: Vector3) {
         // Calcular nueva distancia y ángulos
@@ -212,8 +508,25 @@ impl Camera {
         self.pitch = pitch;
         self.yaw = yaw;
         self.ecliptic_height = new_position.y;
+        self.stop_motion();
         
         // Actualizar posición del ojo basada en los nuevos parámetros
         self.update_eye_position();
     }
 }
+
+/// Acerca `current` a `target` con una respuesta exponencial independiente del paso de tiempo:
+/// con `acceleration` si se pide velocidad y con `damping` si los controles están sueltos
+fn approach(current: f32, target: f32, acceleration: f32, damping: f32, delta_time: f32) -> f32 {
+    if delta_time <= 0.0 {
+        return current;
+    }
+    let rate = if target != 0.0 { acceleration } else { damping };
+    current + (target - current) * (1.0 - (-rate * delta_time).exp())
+}
+
+/// Ángulo a una fracción `t` de `from` a `to` por el camino corto: la diferencia se lleva a
+/// [-PI, PI) para no dar la vuelta entera al cruzar de PI a -PI
+pub fn lerp_angle(from: f32, to: f32, t: f32) -> f32 {
+    from + ((to - from + PI).rem_euclid(2.0 * PI) - PI) * t
+}
@@ -0,0 +1,141 @@
+use raylib::prelude::*;
+use std::fs;
+use std::io;
+
+/// Un punto de control de la trayectoria: dónde está la cámara, hacia dónde mira y cuándo
+#[derive(Clone, Copy, Debug)]
+pub struct Keyframe {
+    pub position: Vector3,
+    pub target: Vector3,
+    pub time: f32, // Segundos desde el inicio de la trayectoria
+}
+
+/// Trayectoria de cámara para vuelos cinemáticos
+/// Los keyframes se graban en tiempo de ejecución y se reproducen con interpolación Catmull-Rom
+#[derive(Default)]
+pub struct CameraPath {
+    pub keyframes: Vec<Keyframe>,
+}
+
+impl CameraPath {
+    pub fn new() -> Self {
+        CameraPath { keyframes: Vec::new() }
+    }
+
+    /// Agregar un keyframe; se mantienen ordenados por tiempo
+    pub fn add_keyframe(&mut self, position: Vector3, target: Vector3, time: f32) {
+        let keyframe = Keyframe { position, target, time };
+        let index = self.keyframes.partition_point(|k| k.time <= time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+    }
+
+    /// Duración total de la trayectoria (tiempo del último keyframe)
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Se necesitan al menos dos keyframes para reproducir un vuelo
+    pub fn is_playable(&self) -> bool {
+        self.keyframes.len() >= 2
+    }
+
+    /// Posición y target de la cámara en el instante `time`
+    /// Fuera del rango se devuelve el primer o el último keyframe
+    pub fn sample(&self, time: f32) -> Option<(Vector3, Vector3)> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some((first.position, first.target));
+        }
+        if time >= last.time {
+            return Some((last.position, last.target));
+        }
+
+        // Segmento [i, i+1] que contiene el instante pedido
+        let i = self.keyframes.partition_point(|k| k.time <= time) - 1;
+        let k1 = &self.keyframes[i];
+        let k2 = &self.keyframes[i + 1];
+        // En los extremos se repite el keyframe para tener los cuatro puntos de control
+        let k0 = if i > 0 { &self.keyframes[i - 1] } else { k1 };
+        let k3 = self.keyframes.get(i + 2).unwrap_or(k2);
+
+        let span = (k2.time - k1.time).max(0.0001);
+        let t = (time - k1.time) / span;
+
+        Some((
+            catmull_rom(k0.position, k1.position, k2.position, k3.position, t),
+            catmull_rom(k0.target, k1.target, k2.target, k3.target, t),
+        ))
+    }
+
+    /// Guardar la trayectoria en un archivo de texto
+    /// Una línea por keyframe: `tiempo px py pz tx ty tz`
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut contents = String::new();
+        for k in &self.keyframes {
+            contents.push_str(&format!(
+                "{} {} {} {} {} {} {}\n",
+                k.time, k.position.x, k.position.y, k.position.z, k.target.x, k.target.y, k.target.z
+            ));
+        }
+        fs::write(path, contents)
+    }
+
+    /// Cargar una trayectoria guardada con `save`
+    /// Las líneas vacías y las que empiezan con `#` se ignoran
+    pub fn load(path: &str) -> io::Result<Self> {
+        CameraPath::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut camera_path = CameraPath::new();
+
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let values: Vec<f32> = line
+                .split_whitespace()
+                .map(|v| v.parse::<f32>())
+                .collect::<Result<_, _>>()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line_number + 1, e)))?;
+            if values.len() != 7 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("línea {}: se esperaban 7 valores, hay {}", line_number + 1, values.len()),
+                ));
+            }
+
+            camera_path.add_keyframe(
+                Vector3::new(values[1], values[2], values[3]),
+                Vector3::new(values[4], values[5], values[6]),
+                values[0],
+            );
+        }
+
+        Ok(camera_path)
+    }
+}
+
+/// Interpolación Catmull-Rom uniforme entre p1 y p2 (t en [0, 1])
+fn catmull_rom(p0: Vector3, p1: Vector3, p2: Vector3, p3: Vector3, t: f32) -> Vector3 {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let blend = |a: f32, b: f32, c: f32, d: f32| {
+        0.5 * ((2.0 * b)
+            + (-a + c) * t
+            + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2
+            + (-a + 3.0 * b - 3.0 * c + d) * t3)
+    };
+    Vector3::new(
+        blend(p0.x, p1.x, p2.x, p3.x),
+        blend(p0.y, p1.y, p2.y, p3.y),
+        blend(p0.z, p1.z, p2.z, p3.z),
+    )
+}
@@ -0,0 +1,101 @@
+/// Horas simuladas por segundo real por defecto: un día de 24 h dura dos minutos
+pub const DEFAULT_HOURS_PER_SECOND: f32 = 0.2;
+
+/// Reloj de la simulación en horas simuladas
+/// Avanza con el tiempo real multiplicado por `hours_per_second`, que se puede acelerar,
+/// frenar o pausar sin que los días de los planetas den saltos
+pub struct SimulationClock {
+    pub hours_per_second: f32, // Ritmo del reloj (0 = pausado)
+    hours: f64,                // Horas simuladas transcurridas (f64 para no perder precisión)
+}
+
+impl SimulationClock {
+    pub fn new(hours_per_second: f32) -> Self {
+        SimulationClock { hours_per_second, hours: 0.0 }
+    }
+
+    /// Avanzar el reloj `delta_time` segundos reales
+    pub fn advance(&mut self, delta_time: f32) {
+        self.hours += (delta_time * self.hours_per_second) as f64;
+    }
+
+    /// Horas simuladas transcurridas desde el inicio
+    pub fn hours(&self) -> f64 {
+        self.hours
+    }
+
+    /// Copia del reloj `seconds` segundos reales antes (para renderizar entre dos pasos)
+    pub fn rewound(&self, seconds: f32) -> SimulationClock {
+        SimulationClock {
+            hours_per_second: self.hours_per_second,
+            hours: self.hours - (seconds * self.hours_per_second) as f64,
+        }
+    }
+
+    /// Multiplicar el ritmo del reloj (p. ej. 2.0 para ir el doble de rápido), dentro de un rango razonable
+    pub fn scale_rate(&mut self, factor: f32) {
+        self.set_rate(self.hours_per_second * factor);
+    }
+
+    /// Fijar el ritmo del reloj en horas simuladas por segundo, dentro del mismo rango
+    pub fn set_rate(&mut self, hours_per_second: f32) {
+        self.hours_per_second = hours_per_second.clamp(0.0125, 51.2);
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::new(DEFAULT_HOURS_PER_SECOND)
+    }
+}
+
+/// Pasos por segundo de la simulación por defecto
+pub const SIMULATION_HZ: f32 = 120.0;
+
+/// Paso fijo de la simulación: acumula el tiempo real de cada frame y lo reparte en pasos de
+/// `step` segundos, así las órbitas y la física no dependen de los FPS. Lo que sobra queda para
+/// el frame siguiente y `alpha` dice cuánto se ha avanzado hacia el próximo paso (para
+/// interpolar lo que se dibuja entre el estado anterior y el actual)
+pub struct FixedTimestep {
+    pub step: f32,      // Duración de cada paso en segundos
+    pub max_steps: u32, // Pasos máximos por frame: tras un parón se descarta el tiempo sobrante
+    accumulator: f32,   // Tiempo real pendiente de simular
+}
+
+impl FixedTimestep {
+    pub fn new(steps_per_second: f32) -> Self {
+        FixedTimestep {
+            step: 1.0 / steps_per_second.max(1.0),
+            max_steps: 8,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Suma el tiempo real del frame y devuelve cuántos pasos hay que simular
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time.max(0.0);
+        let steps = ((self.accumulator / self.step) as u32).min(self.max_steps);
+        self.accumulator -= steps as f32 * self.step;
+        // Si el frame fue demasiado largo no se intenta recuperar: la simulación se frena
+        // un momento en lugar de encadenar frames cada vez más lentos
+        self.accumulator = self.accumulator.min(self.step);
+        steps
+    }
+
+    /// Fracción del siguiente paso ya transcurrida, en [0, 1]
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / self.step).clamp(0.0, 1.0)
+    }
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(SIMULATION_HZ)
+    }
+}
+
+/// Formatea una hora del día en [0, 24) como `HH:MM`
+pub fn format_time_of_day(hour: f32) -> String {
+    let minutes = (hour.rem_euclid(24.0) * 60.0) as u32;
+    format!("{:02}:{:02}", (minutes / 60) % 24, minutes % 60)
+}
@@ -0,0 +1,115 @@
+use raylib::prelude::*;
+
+/// Default display gamma used when encoding the linear framebuffer for output
+pub const DEFAULT_GAMMA: f32 = 2.2;
+
+/// Converts one sRGB-encoded channel in [0, 1] to linear light (exact sRGB curve)
+pub fn srgb_to_linear(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts one linear channel in [0, 1] to sRGB encoding (exact sRGB curve)
+pub fn linear_to_srgb(c: f32) -> f32 {
+    let c = c.clamp(0.0, 1.0);
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decodes a color authored in sRGB (palette constants, textures) to linear space
+/// Shaders call this on their albedo before applying any lighting
+pub fn srgb_to_linear_rgb(color: Vector3) -> Vector3 {
+    Vector3::new(srgb_to_linear(color.x), srgb_to_linear(color.y), srgb_to_linear(color.z))
+}
+
+/// Encodes a linear color to sRGB
+pub fn linear_to_srgb_rgb(color: Vector3) -> Vector3 {
+    Vector3::new(linear_to_srgb(color.x), linear_to_srgb(color.y), linear_to_srgb(color.z))
+}
+
+/// Approximate sRGB color of a blackbody at `kelvin` (fit of the CIE color matching curves,
+/// valid from 1000 K to 40000 K), normalized so the brightest channel is 1
+pub fn blackbody_srgb(kelvin: f32) -> Vector3 {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let r = if t <= 66.0 { 255.0 } else { 329.69873 * (t - 60.0).powf(-0.13320476) };
+    let g = if t <= 66.0 {
+        99.4708 * t.ln() - 161.11957
+    } else {
+        288.12217 * (t - 60.0).powf(-0.07551485)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * (t - 10.0).ln() - 305.0448
+    };
+    Vector3::new(
+        (r / 255.0).clamp(0.0, 1.0),
+        (g / 255.0).clamp(0.0, 1.0),
+        (b / 255.0).clamp(0.0, 1.0),
+    )
+}
+
+/// Lookup table from linear intensity to an 8-bit display value for a given gamma
+/// Avoids a `powf` per channel per pixel when the framebuffer is written out.
+/// Entries keep 8 fractional bits so the output can be dithered instead of rounded.
+pub struct GammaLut {
+    gamma: f32,
+    table: Vec<u16>, // Display value in 8.8 fixed point
+}
+
+impl GammaLut {
+    const SIZE: usize = 4096;
+
+    pub fn new(gamma: f32) -> Self {
+        let gamma = gamma.max(0.1);
+        let table = (0..Self::SIZE)
+            .map(|i| {
+                let linear = i as f32 / (Self::SIZE - 1) as f32;
+                (linear.powf(1.0 / gamma) * 255.0 * 256.0 + 0.5) as u16
+            })
+            .collect();
+        GammaLut { gamma, table }
+    }
+
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    fn lookup(&self, linear: f32) -> u16 {
+        let index = (linear.clamp(0.0, 1.0) * (Self::SIZE - 1) as f32 + 0.5) as usize;
+        self.table[index]
+    }
+
+    /// Encodes a linear channel (clamped to [0, 1])
+    pub fn encode(&self, linear: f32) -> u8 {
+        ((self.lookup(linear) + 128) >> 8) as u8
+    }
+
+    /// Encodes a linear channel, rounding up when its fractional part exceeds `threshold`
+    /// (0-255, from a dither pattern) instead of at one half
+    pub fn encode_dithered(&self, linear: f32, threshold: u8) -> u8 {
+        ((self.lookup(linear) as u32 + threshold as u32) >> 8).min(255) as u8
+    }
+
+    pub fn encode_rgb(&self, color: Vector3) -> Color {
+        Color::new(self.encode(color.x), self.encode(color.y), self.encode(color.z), 255)
+    }
+
+    pub fn encode_rgb_dithered(&self, color: Vector3, threshold: u8) -> Color {
+        Color::new(
+            self.encode_dithered(color.x, threshold),
+            self.encode_dithered(color.y, threshold),
+            self.encode_dithered(color.z, threshold),
+            255,
+        )
+    }
+}
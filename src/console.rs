@@ -0,0 +1,201 @@
+use raylib::prelude::*;
+use crate::input_map::{Action, InputMap};
+use crate::quality::QUALITY_LEVELS;
+use crate::shaders::ShaderQuality;
+
+/// Máximo de objetos que crea un solo `spawn` (cada asteroide cuesta una malla por frame)
+pub const MAX_SPAWN: usize = 1000;
+
+/// Ayuda que muestra `help`
+pub const HELP: &str = "warp X Y Z | timescale H | spawn asteroid N | track NOMBRE | quality baja|media|alta|ultra|0-4";
+
+/// Qué crea `spawn`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpawnKind {
+    Asteroid,
+}
+
+/// Orden de la consola ya interpretada; `main` la reparte a cada sistema
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Warp(Vector3),                              // Warp de la nave a esas coordenadas del mundo
+    TimeScale(f32),                             // Horas simuladas por segundo
+    Spawn { kind: SpawnKind, count: usize },    // Objetos nuevos en la escena
+    Track(String),                              // Seguir con la cámara al cuerpo con ese nombre
+    Quality(usize),                             // Nivel de calidad fijo (índice en `QUALITY_LEVELS`)
+    Help,
+}
+
+/// Interpretar una línea de la consola; el error es el mensaje para el usuario
+pub fn parse_command(line: &str) -> Result<Command, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((&name, args)) = words.split_first() else {
+        return Err("Orden vacía".to_string());
+    };
+    let number = |word: &str| word.parse::<f32>().ok().filter(|value| value.is_finite()).ok_or_else(|| format!("'{}' no es un número", word));
+
+    match name.to_ascii_lowercase().as_str() {
+        "warp" => match args {
+            [x, y, z] => Ok(Command::Warp(Vector3::new(number(x)?, number(y)?, number(z)?))),
+            _ => Err("Uso: warp X Y Z".to_string()),
+        },
+        "timescale" => match args {
+            [rate] => {
+                let rate = number(rate)?;
+                if rate <= 0.0 {
+                    return Err("El ritmo del reloj tiene que ser positivo".to_string());
+                }
+                Ok(Command::TimeScale(rate))
+            }
+            _ => Err("Uso: timescale H (horas simuladas por segundo)".to_string()),
+        },
+        "spawn" => {
+            let (kind, count) = match args {
+                [kind] => (kind, "1"),
+                [kind, count] => (kind, *count),
+                _ => return Err("Uso: spawn asteroid [N]".to_string()),
+            };
+            let kind = match kind.to_ascii_lowercase().as_str() {
+                "asteroid" | "asteroids" | "asteroide" | "asteroides" => SpawnKind::Asteroid,
+                _ => return Err(format!("No se pueden crear objetos de tipo '{}'", kind)),
+            };
+            let count = count.parse::<usize>().map_err(|_| format!("'{}' no es una cantidad", count))?;
+            if count == 0 || count > MAX_SPAWN {
+                return Err(format!("La cantidad tiene que estar entre 1 y {}", MAX_SPAWN));
+            }
+            Ok(Command::Spawn { kind, count })
+        }
+        // Los nombres de cuerpos pueden tener espacios
+        "track" if !args.is_empty() => Ok(Command::Track(args.join(" "))),
+        "track" => Err("Uso: track NOMBRE".to_string()),
+        "quality" => match args {
+            [level] => {
+                // Por nombre, el nivel más alto con esa calidad de shaders
+                let by_name = ShaderQuality::from_name(&level.to_ascii_lowercase())
+                    .and_then(|quality| QUALITY_LEVELS.iter().rposition(|settings| settings.shader_quality == quality));
+                let by_index = level.parse::<usize>().ok().filter(|&index| index < QUALITY_LEVELS.len());
+                by_name.or(by_index).map(Command::Quality).ok_or_else(|| format!("Calidad desconocida: '{}'", level))
+            }
+            _ => Err("Uso: quality baja|media|alta|ultra|0-4".to_string()),
+        },
+        "help" | "?" => Ok(Command::Help),
+        _ => Err(format!("Orden desconocida: '{}' (help para ver las órdenes)", name)),
+    }
+}
+
+/// Consola de desarrollo: una línea de texto en la parte de arriba de la ventana
+/// Se abre y se cierra con la acción `Console`; Intro ejecuta la línea, flechas arriba/abajo
+/// recorren las órdenes anteriores. Mientras está abierta ninguna acción del juego responde
+pub struct Console {
+    open: bool,
+    input: String,                 // Línea que se está escribiendo
+    history: Vec<String>,          // Órdenes ejecutadas, de la más antigua a la más reciente
+    history_cursor: Option<usize>, // Orden del historial que se está mostrando
+    log: Vec<String>,              // Últimas líneas de salida
+}
+
+impl Console {
+    /// Líneas de salida que se conservan
+    const LOG_LINES: usize = 8;
+
+    pub fn new() -> Self {
+        Console {
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            history_cursor: None,
+            log: Vec::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Añadir una línea a la salida (las respuestas de `main` a cada orden)
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        if self.log.len() > Self::LOG_LINES {
+            self.log.remove(0);
+        }
+    }
+
+    /// Atiende el teclado y suspende el resto de acciones mientras está abierta
+    /// Devuelve la orden de la línea al pulsar Intro (los errores se muestran en la consola)
+    pub fn update(&mut self, window: &mut RaylibHandle, map: &mut InputMap) -> Option<Command> {
+        map.suspended = false;
+
+        if !self.open {
+            self.open = map.is_pressed(window, Action::Console);
+            map.suspended = self.open;
+            return None;
+        }
+
+        let mut command = None;
+        if map.is_pressed(window, Action::Console) {
+            self.open = false;
+        } else if window.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            let line = std::mem::take(&mut self.input);
+            if !line.trim().is_empty() {
+                self.print(format!("> {}", line));
+                match parse_command(&line) {
+                    Ok(parsed) => command = Some(parsed),
+                    Err(message) => self.print(message),
+                }
+                self.history.push(line);
+            }
+            self.history_cursor = None;
+        } else if window.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            self.input.pop();
+        } else if window.is_key_pressed(KeyboardKey::KEY_UP) && !self.history.is_empty() {
+            let cursor = self.history_cursor.map_or(self.history.len() - 1, |cursor| cursor.saturating_sub(1));
+            self.history_cursor = Some(cursor);
+            self.input = self.history[cursor].clone();
+        } else if window.is_key_pressed(KeyboardKey::KEY_DOWN) && let Some(cursor) = self.history_cursor {
+            self.history_cursor = (cursor + 1 < self.history.len()).then_some(cursor + 1);
+            self.input = self.history_cursor.map_or_else(String::new, |cursor| self.history[cursor].clone());
+        } else {
+            while let Some(c) = window.get_char_pressed() {
+                if c.is_ascii_graphic() || c == ' ' {
+                    self.input.push(c);
+                }
+            }
+        }
+
+        map.suspended = self.open;
+        command
+    }
+
+    /// Dibuja la salida y la línea de entrada en una franja en la parte de arriba de la ventana
+    pub fn draw(&self, d: &mut RaylibDrawHandle, map: &InputMap, width: i32) {
+        if !self.open {
+            return;
+        }
+
+        let accent = Color::new(120, 200, 255, 255);
+        let text_color = Color::new(200, 210, 220, 255);
+        let row_height = 14;
+        let height = (Self::LOG_LINES as i32 + 2) * row_height + 10;
+        d.draw_rectangle(0, 0, width, height, Color::new(0, 0, 0, 210));
+        d.draw_line(0, height, width, height, accent);
+        d.draw_text(
+            &format!("CONSOLA   help: órdenes   {}: cerrar", map.describe(Action::Console)),
+            8,
+            6,
+            10,
+            accent,
+        );
+
+        for (row, line) in self.log.iter().enumerate() {
+            let color = if line.starts_with('>') { accent } else { text_color };
+            d.draw_text(line, 8, 6 + (row as i32 + 1) * row_height, 12, color);
+        }
+        d.draw_text(&format!("> {}_", self.input), 8, height - row_height - 4, 12, Color::WHITE);
+    }
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,142 @@
+use raylib::prelude::*;
+use crate::input_map::{Action, Binding, InputMap};
+
+/// Pantalla de controles: lista las acciones con sus teclas y permite reasignarlas en el juego
+/// Se abre y se cierra con la acción `Controls`; flechas arriba/abajo eligen la acción, Intro
+/// espera la nueva tecla (o botón del ratón) y Supr restaura la asignación por defecto.
+/// La navegación usa teclas fijas para que no se pueda perder con una mala asignación
+pub struct ControlsScreen {
+    open: bool,
+    selected: usize,          // Índice en `Action::ALL`
+    capturing: bool,          // Esperando la nueva tecla de la acción seleccionada
+    changed: bool,            // Hay cambios sin guardar desde que se abrió
+    message: Option<String>,  // Aviso de la última reasignación (conflictos)
+}
+
+impl ControlsScreen {
+    pub fn new() -> Self {
+        ControlsScreen {
+            open: false,
+            selected: 0,
+            capturing: false,
+            changed: false,
+            message: None,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Atiende la pantalla y suspende el resto de acciones mientras está abierta
+    /// Devuelve true al cerrarse con cambios (el llamador guarda las asignaciones)
+    pub fn update(&mut self, window: &mut RaylibHandle, map: &mut InputMap) -> bool {
+        map.suspended = false;
+
+        if !self.open {
+            if map.is_pressed(window, Action::Controls) {
+                self.open = true;
+                self.capturing = false;
+                self.changed = false;
+                self.message = None;
+            }
+            map.suspended = self.open;
+            return false;
+        }
+
+        let action = Action::ALL[self.selected];
+        if self.capturing {
+            let mouse = [MouseButton::MOUSE_BUTTON_LEFT, MouseButton::MOUSE_BUTTON_RIGHT, MouseButton::MOUSE_BUTTON_MIDDLE]
+                .into_iter()
+                .find(|&button| window.is_mouse_button_pressed(button))
+                .map(Binding::Mouse);
+            if let Some(binding) = mouse.or_else(|| window.get_key_pressed().map(Binding::Key)) {
+                map.set(action, binding);
+                let conflicts = map.conflicts(action, binding);
+                self.message = (!conflicts.is_empty()).then(|| {
+                    let names: Vec<&str> = conflicts.iter().map(|other| other.description()).collect();
+                    format!("{} también está asignada a: {}", binding.name(), names.join(", "))
+                });
+                self.capturing = false;
+                self.changed = true;
+            }
+            map.suspended = true;
+            return false;
+        }
+
+        let mut closed = false;
+        if map.is_pressed(window, Action::Controls) {
+            self.open = false;
+            closed = self.changed;
+        } else if window.is_key_pressed(KeyboardKey::KEY_DOWN) {
+            self.selected = (self.selected + 1) % Action::ALL.len();
+        } else if window.is_key_pressed(KeyboardKey::KEY_UP) {
+            self.selected = (self.selected + Action::ALL.len() - 1) % Action::ALL.len();
+        } else if window.is_key_pressed(KeyboardKey::KEY_ENTER) {
+            self.capturing = true;
+            self.message = None;
+        } else if window.is_key_pressed(KeyboardKey::KEY_DELETE) {
+            map.reset(action);
+            self.changed = true;
+            self.message = None;
+        }
+
+        map.suspended = self.open;
+        closed
+    }
+
+    /// Dibuja la lista de acciones centrada en la ventana, desplazada para que la seleccionada
+    /// quede siempre a la vista
+    pub fn draw(&self, d: &mut RaylibDrawHandle, map: &InputMap, width: i32, height: i32) {
+        if !self.open {
+            return;
+        }
+
+        let accent = Color::new(120, 200, 255, 255);
+        let text_color = Color::new(200, 210, 220, 255);
+        let row_height = 16;
+        let panel_width = 520.min(width - 20);
+        let panel_height = (height - 40).max(160);
+        let x = width / 2 - panel_width / 2;
+        let y = height / 2 - panel_height / 2;
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 210));
+        d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+        d.draw_text("CONTROLES", x + 12, y + 10, 18, accent);
+        d.draw_text(
+            &format!(
+                "Flechas: elegir   Intro: reasignar   Supr: por defecto   {}: cerrar y guardar",
+                map.describe(Action::Controls)
+            ),
+            x + 12,
+            y + 32,
+            10,
+            text_color,
+        );
+
+        let list_top = y + 52;
+        let visible = ((panel_height - 52 - 30) / row_height).max(1) as usize;
+        let first = self.selected.saturating_sub(visible / 2).min(Action::ALL.len().saturating_sub(visible));
+        for (row, action) in Action::ALL.iter().enumerate().skip(first).take(visible) {
+            let row_y = list_top + (row - first) as i32 * row_height;
+            let selected = row == self.selected;
+            if selected {
+                d.draw_rectangle(x + 6, row_y - 2, panel_width - 12, row_height, Color::new(40, 70, 100, 200));
+            }
+            let color = if selected { Color::WHITE } else { text_color };
+            d.draw_text(action.description(), x + 12, row_y, 12, color);
+            let bindings = if selected && self.capturing { "pulsa una tecla...".to_string() } else { map.describe(*action) };
+            let bindings_width = measure_text(&bindings, 12);
+            d.draw_text(&bindings, x + panel_width - 12 - bindings_width, row_y, 12, if selected { accent } else { color });
+        }
+
+        if let Some(message) = &self.message {
+            d.draw_text(message, x + 12, y + panel_height - 22, 10, Color::new(255, 170, 40, 255));
+        }
+    }
+}
+
+impl Default for ControlsScreen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
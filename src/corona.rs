@@ -0,0 +1,140 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::project_to_screen;
+use crate::uniforms::Uniforms;
+
+/// Posición de la estrella en pantalla
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScreenDisk {
+    pub center: Vector3,  // Centro proyectado (z = profundidad del centro)
+    pub radius: f32,      // Radio en píxeles
+    pub front_depth: f32, // Profundidad del punto de la esfera más cercano a la cámara
+}
+
+/// Proyecta la esfera de `radius` en `position` con las matrices de `uniforms`; None si la cámara
+/// está dentro o la esfera queda detrás de ella
+pub fn project_disk(uniforms: &Uniforms, position: Vector3, radius: f32) -> Option<ScreenDisk> {
+    let (view, projection, viewport) = (&uniforms.view_matrix, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+    let center = project_to_screen(position, view, projection, viewport)?;
+
+    let camera = uniforms.camera_position;
+    let to_camera = Vector3::new(camera.x - position.x, camera.y - position.y, camera.z - position.z);
+    let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt();
+    if distance <= radius {
+        return None;
+    }
+    let front_point = Vector3::new(
+        position.x + to_camera.x / distance * radius,
+        position.y + to_camera.y / distance * radius,
+        position.z + to_camera.z / distance * radius,
+    );
+    let front = project_to_screen(front_point, view, projection, viewport)?;
+
+    // Radio en pantalla a partir de un punto de la silueta, perpendicular a la línea de visión
+    // (como `GravitationalLens`)
+    let side = if to_camera.y.abs() < distance * 0.99 {
+        Vector3::new(to_camera.z, 0.0, -to_camera.x)
+    } else {
+        Vector3::new(0.0, to_camera.z, -to_camera.y)
+    };
+    let side_length = (side.x * side.x + side.y * side.y + side.z * side.z).sqrt().max(0.0001);
+    let edge_point = Vector3::new(
+        position.x + side.x / side_length * radius,
+        position.y + side.y / side_length * radius,
+        position.z + side.z / side_length * radius,
+    );
+    let radius_px = project_to_screen(edge_point, view, projection, viewport)
+        .map(|edge| ((edge.x - center.x).powi(2) + (edge.y - center.y).powi(2)).sqrt())
+        .unwrap_or(1.0)
+        .max(1.0);
+
+    Some(ScreenDisk { center, radius: radius_px, front_depth: front.z })
+}
+
+/// Corona de la estrella y rayos crepusculares
+/// La corona es un billboard aditivo orientado a la cámara a la profundidad del centro de la
+/// estrella: la cara visible de la malla del sol (más cercana) y los cuerpos que pasan por
+/// delante la tapan. Los rayos son una pasada de pantalla (`Framebuffer::god_rays_pass`) desde
+/// el disco de la estrella: los planetas que transitan por delante proyectan haces oscuros
+pub struct SunCorona {
+    pub corona_scale: f32,     // Radio del billboard en radios de la estrella
+    pub corona_intensity: f32, // Brillo junto al borde de la estrella (con `corona_strength` = 1)
+    pub streamers: f32,        // Contraste de los chorros radiales de la corona (0 = uniforme)
+    pub ray_scale: f32,        // Radio del disco que emite los rayos, en radios de la estrella
+    pub ray_strength: f32,     // Intensidad de los rayos
+    pub ray_samples: usize,    // Muestras por píxel a lo largo del rayo
+    pub ray_decay: f32,        // Atenuación de cada muestra respecto a la anterior
+}
+
+impl SunCorona {
+    pub fn new() -> Self {
+        SunCorona {
+            corona_scale: 4.0,
+            corona_intensity: 0.5,
+            streamers: 0.35,
+            ray_scale: 1.3,
+            ray_strength: 0.6,
+            ray_samples: 24,
+            ray_decay: 0.97,
+        }
+    }
+
+    /// Brillo de la corona a `r` radios del centro y en el ángulo `angle` alrededor de él
+    pub fn corona_profile(&self, r: f32, angle: f32, time: f32) -> f32 {
+        if r <= 1.0 || r >= self.corona_scale {
+            return 0.0;
+        }
+        // Cae con la distancia y llega a cero en el borde del billboard
+        let edge = (self.corona_scale - r) / (self.corona_scale - 1.0);
+        let streamer = 1.0 - self.streamers * 0.5 * (1.0 + (angle * 6.0 + (angle * 11.0 + time * 0.05).sin() * 2.0).sin());
+        self.corona_intensity * edge * edge / (r * r) * streamer
+    }
+
+    /// Dibuja la corona de la estrella de `radius` en `position`, de color `color` (lineal) y
+    /// fuerza `strength` (`Star::corona_strength`). Después de la geometría opaca
+    pub fn render_corona(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, position: Vector3, radius: f32, color: Vector3, strength: f32) {
+        if strength <= 0.0 {
+            return;
+        }
+        let Some(disk) = project_disk(uniforms, position, radius) else {
+            return;
+        };
+        let reach = disk.radius * self.corona_scale;
+        let (width, height) = (framebuffer.width as i32, framebuffer.height as i32);
+        let min_x = ((disk.center.x - reach).floor() as i32).max(0);
+        let max_x = ((disk.center.x + reach).ceil() as i32).min(width - 1);
+        let min_y = ((disk.center.y - reach).floor() as i32).max(0);
+        let max_y = ((disk.center.y + reach).ceil() as i32).min(height - 1);
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                // Detrás de la cara visible del sol y de lo que se cruce por delante
+                if framebuffer.depth_at(x, y) < disk.center.z {
+                    continue;
+                }
+                let (dx, dy) = (x as f32 + 0.5 - disk.center.x, y as f32 + 0.5 - disk.center.y);
+                let r = (dx * dx + dy * dy).sqrt() / disk.radius;
+                let glow = self.corona_profile(r, dy.atan2(dx), uniforms.time) * strength;
+                if glow > 0.0 {
+                    framebuffer.add_color(x, y, Vector3::new(color.x * glow, color.y * glow, color.z * glow));
+                }
+            }
+        }
+    }
+
+    /// Rayos crepusculares desde la estrella de `radius` en `position`; después del bloom
+    pub fn render_god_rays(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, position: Vector3, radius: f32, color: Vector3) {
+        if let Some(disk) = project_disk(uniforms, position, radius) {
+            let center = Vector2::new(disk.center.x, disk.center.y);
+            let s = self.ray_strength;
+            let color = Vector3::new(color.x * s, color.y * s, color.z * s);
+            framebuffer.god_rays_pass(center, disk.radius * self.ray_scale, disk.front_depth, color, self.ray_samples, self.ray_decay);
+        }
+    }
+}
+
+impl Default for SunCorona {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,228 @@
+use raylib::prelude::*;
+use crate::clock::format_time_of_day;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{inverse_rotate_vector, rotate_vector};
+use crate::renderer::Renderer;
+use crate::scene::{Planet, Scene};
+use crate::uniforms::Uniforms;
+use std::f32::consts::PI;
+
+/// Pasos con los que se recorre un día del planeta buscando el próximo amanecer o atardecer
+const DAY_SAMPLES: usize = 96;
+
+/// Día y noche de un planeta: el círculo del terminador (la mitad que amanece en azul y la que
+/// anochece en naranja), círculos de altura del sol sobre la cara iluminada y el punto subsolar,
+/// más la hora solar local bajo la nave y cuánto falta para que allí salga o se ponga el sol. Sirve
+/// para cuadrar capturas del terminador y para comprobar el modelo de iluminación
+pub struct DaylightView {
+    pub planet: Option<usize>, // Planeta seleccionado (None = modo apagado)
+    pub segments: usize,       // Segmentos de cada círculo
+    pub lift: f32,             // Radio de las líneas sobre la superficie (en radios del planeta)
+    pub elevations: Vec<f32>,  // Alturas del sol (grados) de los círculos de la cara iluminada
+}
+
+impl DaylightView {
+    pub fn new() -> Self {
+        DaylightView {
+            planet: None,
+            segments: 96,
+            lift: 1.02,
+            elevations: vec![30.0, 60.0],
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn is_active(&self) -> bool {
+        self.planet.is_some()
+    }
+
+    /// Mostrar el día y la noche de `planet`, o apagar el modo si ya estaba activo
+    pub fn toggle(&mut self, planet: Option<usize>) -> Option<usize> {
+        self.planet = if self.planet.is_some() { None } else { planet };
+        self.planet
+    }
+
+    /// Dirección (unitaria) del centro de `planet` hacia la estrella
+    pub fn sun_direction(planet: &Planet) -> Vector3 {
+        let p = planet.position();
+        let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt();
+        if length > 0.0001 {
+            Vector3::new(-p.x / length, -p.y / length, -p.z / length)
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        }
+    }
+
+    /// Altura del sol en grados sobre el horizonte del punto de la superficie bajo `world_point`
+    /// (90 en el punto subsolar, 0 en el terminador, negativa de noche)
+    pub fn sun_elevation(planet: &Planet, world_point: Vector3) -> f32 {
+        let center = planet.position();
+        let up = normalize(Vector3::new(world_point.x - center.x, world_point.y - center.y, world_point.z - center.z));
+        let sun = DaylightView::sun_direction(planet);
+        (up.x * sun.x + up.y * sun.y + up.z * sun.z).clamp(-1.0, 1.0).asin().to_degrees()
+    }
+
+    /// Horas simuladas hasta que el sol sale o se pone en el punto de la superficie bajo
+    /// `world_point` tras `hours` horas simuladas, y si es un amanecer. None si en el próximo día
+    /// no cambia (día o noche polar). Se sigue el giro del punto con el eje inclinado; el
+    /// movimiento orbital durante ese día no se tiene en cuenta
+    pub fn next_terminator_crossing(planet: &Planet, hours: f64, world_point: Vector3) -> Option<(f64, bool)> {
+        let center = planet.position();
+        let local = inverse_rotate_vector(
+            Vector3::new(world_point.x - center.x, world_point.y - center.y, world_point.z - center.z),
+            planet.rotation(hours),
+        );
+        let local = normalize(local);
+        let sun = DaylightView::sun_direction(planet);
+        let height = |h: f64| {
+            let up = rotate_vector(local, planet.rotation(h));
+            up.x * sun.x + up.y * sun.y + up.z * sun.z
+        };
+
+        let step = planet.rotation_period.abs().max(0.0001) as f64 / DAY_SAMPLES as f64;
+        let daytime = height(hours) > 0.0;
+        let mut before = hours;
+        for sample in 1..=DAY_SAMPLES {
+            let after = hours + step * sample as f64;
+            if (height(after) > 0.0) != daytime {
+                // Bisección dentro del paso en el que cambia de signo
+                let (mut low, mut high) = (before, after);
+                for _ in 0..20 {
+                    let middle = (low + high) * 0.5;
+                    if (height(middle) > 0.0) == daytime {
+                        low = middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                return Some((high - hours, !daytime));
+            }
+            before = after;
+        }
+        None
+    }
+
+    /// Línea de la interfaz con la hora local bajo `world_point` en el planeta seleccionado; el
+    /// tiempo hasta el próximo amanecer o atardecer está en segundos reales con el reloj a
+    /// `hours_per_second`. None con el modo apagado
+    pub fn readout(&self, scene: &Scene, hours: f64, world_point: Vector3, hours_per_second: f32) -> Option<String> {
+        let planet = scene.planets.get(self.planet?)?;
+        let local_time = planet.local_time_of_day(hours, world_point);
+        let elevation = DaylightView::sun_elevation(planet, world_point);
+        let state = if elevation > 0.0 { "día" } else { "noche" };
+        let next = match DaylightView::next_terminator_crossing(planet, hours, world_point) {
+            Some((wait, sunrise)) => {
+                let event = if sunrise { "amanecer" } else { "atardecer" };
+                let seconds = if hours_per_second > 0.0 { format!("{:.0} s", wait as f32 / hours_per_second) } else { String::from("reloj parado") };
+                format!("{} en {:.1} h ({})", event, wait, seconds)
+            }
+            None if elevation > 0.0 => String::from("sol de medianoche"),
+            None => String::from("noche polar"),
+        };
+        Some(format!(
+            "Día y noche en {}: {} hora local bajo la nave, sol a {:+.0}° ({}) - {}",
+            planet.name,
+            format_time_of_day(local_time),
+            elevation,
+            state,
+            next,
+        ))
+    }
+
+    /// Puntos del círculo de la superficie de `planet` con el sol a `elevation` grados sobre el
+    /// horizonte (0 = el terminador), a `lift` radios del centro
+    pub fn elevation_circle(&self, planet: &Planet, elevation: f32) -> Vec<Vector3> {
+        let center = planet.position();
+        let sun = DaylightView::sun_direction(planet);
+        let (u, v) = perpendicular_basis(sun);
+        let radius = planet.scale * self.lift;
+        let (along, across) = (elevation.to_radians().sin() * radius, elevation.to_radians().cos() * radius);
+        let segments = self.segments.max(3);
+        (0..=segments)
+            .map(|i| {
+                let angle = i as f32 / segments as f32 * 2.0 * PI;
+                let (c, s) = (angle.cos() * across, angle.sin() * across);
+                Vector3::new(
+                    center.x + sun.x * along + u.x * c + v.x * s,
+                    center.y + sun.y * along + u.y * c + v.y * s,
+                    center.z + sun.z * along + u.z * c + v.z * s,
+                )
+            })
+            .collect()
+    }
+
+    /// Si el punto `world_point` del terminador de `planet` está amaneciendo tras `hours` horas
+    /// simuladas: el giro del planeta lo lleva hacia el sol
+    pub fn is_dawn(planet: &Planet, hours: f64, world_point: Vector3) -> bool {
+        let center = planet.position();
+        let offset = Vector3::new(world_point.x - center.x, world_point.y - center.y, world_point.z - center.z);
+        let local = inverse_rotate_vector(offset, planet.rotation(hours));
+        let later = rotate_vector(local, planet.rotation(hours + planet.rotation_period.abs().max(0.0001) as f64 * 0.01));
+        let sun = DaylightView::sun_direction(planet);
+        (later.x - offset.x) * sun.x + (later.y - offset.y) * sun.y + (later.z - offset.z) * sun.z > 0.0
+    }
+
+    /// Dibuja el terminador, los círculos de altura y el punto subsolar del planeta seleccionado
+    /// (líneas con prueba de profundidad: la cara oculta del planeta las tapa)
+    pub fn render(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, scene: &Scene, hours: f64) {
+        let Some(planet) = self.planet.and_then(|idx| scene.planets.get(idx)) else {
+            return;
+        };
+        let dawn_color = Vector3::new(0.35, 0.6, 1.0);
+        let dusk_color = Vector3::new(1.0, 0.45, 0.12);
+        let terminator = self.elevation_circle(planet, 0.0);
+        for pair in terminator.windows(2) {
+            let color = if DaylightView::is_dawn(planet, hours, pair[0]) { dawn_color } else { dusk_color };
+            renderer.draw_line_3d(framebuffer, uniforms, pair[0], pair[1], color);
+        }
+
+        let day_color = Vector3::new(0.9, 0.85, 0.4);
+        for &elevation in &self.elevations {
+            let fade = 0.6 - elevation / 180.0;
+            let color = Vector3::new(day_color.x * fade, day_color.y * fade, day_color.z * fade);
+            for pair in self.elevation_circle(planet, elevation).windows(2) {
+                renderer.draw_line_3d(framebuffer, uniforms, pair[0], pair[1], color);
+            }
+        }
+
+        // Punto subsolar: una cruz sobre la superficie
+        let center = planet.position();
+        let sun = DaylightView::sun_direction(planet);
+        let radius = planet.scale * self.lift;
+        let point = Vector3::new(center.x + sun.x * radius, center.y + sun.y * radius, center.z + sun.z * radius);
+        let (u, v) = perpendicular_basis(sun);
+        let size = planet.scale * 0.1;
+        for axis in [u, v] {
+            let a = Vector3::new(point.x - axis.x * size, point.y - axis.y * size, point.z - axis.z * size);
+            let b = Vector3::new(point.x + axis.x * size, point.y + axis.y * size, point.z + axis.z * size);
+            renderer.draw_line_3d(framebuffer, uniforms, a, b, day_color);
+        }
+    }
+}
+
+impl Default for DaylightView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dos vectores unitarios perpendiculares entre sí y a `n` (unitario)
+fn perpendicular_basis(n: Vector3) -> (Vector3, Vector3) {
+    let helper = if n.y.abs() < 0.99 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+    let u = normalize(Vector3::new(
+        helper.y * n.z - helper.z * n.y,
+        helper.z * n.x - helper.x * n.z,
+        helper.x * n.y - helper.y * n.x,
+    ));
+    let v = Vector3::new(n.y * u.z - n.z * u.y, n.z * u.x - n.x * u.z, n.x * u.y - n.y * u.x);
+    (u, v)
+}
+
+fn normalize(v: Vector3) -> Vector3 {
+    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if length > 0.0 {
+        Vector3::new(v.x / length, v.y / length, v.z / length)
+    } else {
+        v
+    }
+}
@@ -0,0 +1,168 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{multiply_matrix_vector4, transform_normal};
+use crate::renderer::Renderer;
+use crate::uniforms::Uniforms;
+use crate::vertex::Vertex;
+use std::f32::consts::PI;
+
+/// A rendered object as seen by the debug overlay
+pub struct DebugObject<'a> {
+    pub model_matrix: Matrix,
+    pub vertices: &'a [Vertex],
+    pub center: Vector3,
+    pub radius: f32, // World-space bounding sphere radius
+}
+
+/// Debug gizmos drawn into the framebuffer with depth testing: world axes at the origin,
+/// bounding spheres, the ship's velocity vector and vertex normals.
+/// Each one is toggled independently; all are off by default.
+pub struct DebugDraw {
+    pub axes: bool,
+    pub bounding_spheres: bool,
+    pub velocity: bool,
+    pub normals: bool,
+    pub axis_length: f32,
+    pub normal_length: f32,
+    pub max_normals: usize,    // Per object; larger meshes show every n-th normal
+    pub velocity_scale: f32,   // Seconds of travel the velocity arrow represents
+    pub sphere_segments: usize,
+}
+
+impl DebugDraw {
+    pub fn new() -> Self {
+        DebugDraw {
+            axes: false,
+            bounding_spheres: false,
+            velocity: false,
+            normals: false,
+            axis_length: 50.0,
+            normal_length: 0.3,
+            max_normals: 2000,
+            velocity_scale: 1.0,
+            sphere_segments: 32,
+        }
+    }
+
+    pub fn any_enabled(&self) -> bool {
+        self.axes || self.bounding_spheres || self.velocity || self.normals
+    }
+
+    /// Names of the enabled gizmos, for the HUD
+    pub fn enabled_names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.axes {
+            names.push("ejes");
+        }
+        if self.bounding_spheres {
+            names.push("esferas");
+        }
+        if self.velocity {
+            names.push("velocidad");
+        }
+        if self.normals {
+            names.push("normales");
+        }
+        names
+    }
+
+    /// Draws every enabled gizmo. `uniforms` only provides the view, projection and viewport.
+    pub fn draw(
+        &self,
+        renderer: &Renderer,
+        framebuffer: &mut Framebuffer,
+        uniforms: &Uniforms,
+        objects: &[DebugObject],
+        ship_position: Vector3,
+        ship_velocity: Vector3,
+    ) {
+        if self.axes {
+            self.draw_axes(renderer, framebuffer, uniforms, Vector3::zero());
+        }
+        for object in objects {
+            if self.bounding_spheres {
+                self.draw_sphere(renderer, framebuffer, uniforms, object.center, object.radius, Vector3::new(0.9, 0.9, 0.2));
+            }
+            if self.normals {
+                self.draw_normals(renderer, framebuffer, uniforms, object);
+            }
+        }
+        if self.velocity {
+            let tip = Vector3::new(
+                ship_position.x + ship_velocity.x * self.velocity_scale,
+                ship_position.y + ship_velocity.y * self.velocity_scale,
+                ship_position.z + ship_velocity.z * self.velocity_scale,
+            );
+            let color = Vector3::new(1.0, 0.5, 0.0);
+            renderer.draw_line_3d(framebuffer, uniforms, ship_position, tip, color);
+            renderer.draw_point_3d(framebuffer, uniforms, tip, color, 3);
+        }
+    }
+
+    /// X (red), Y (green) and Z (blue) axes from `origin`
+    pub fn draw_axes(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, origin: Vector3) {
+        let length = self.axis_length;
+        let axes = [
+            (Vector3::new(length, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+            (Vector3::new(0.0, length, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(0.0, 0.0, length), Vector3::new(0.0, 0.0, 1.0)),
+        ];
+        for (axis, color) in axes {
+            let end = Vector3::new(origin.x + axis.x, origin.y + axis.y, origin.z + axis.z);
+            renderer.draw_line_3d(framebuffer, uniforms, origin, end, color);
+        }
+    }
+
+    /// Wireframe sphere as three great circles (XY, XZ and YZ planes)
+    pub fn draw_sphere(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, center: Vector3, radius: f32, color: Vector3) {
+        let segments = self.sphere_segments.max(3);
+        let circle_point = |plane: usize, angle: f32| -> Vector3 {
+            let (sin, cos) = angle.sin_cos();
+            let (a, b) = (cos * radius, sin * radius);
+            match plane {
+                0 => Vector3::new(center.x + a, center.y + b, center.z),
+                1 => Vector3::new(center.x + a, center.y, center.z + b),
+                _ => Vector3::new(center.x, center.y + a, center.z + b),
+            }
+        };
+        for plane in 0..3 {
+            for i in 0..segments {
+                let a0 = i as f32 / segments as f32 * 2.0 * PI;
+                let a1 = (i + 1) as f32 / segments as f32 * 2.0 * PI;
+                renderer.draw_line_3d(framebuffer, uniforms, circle_point(plane, a0), circle_point(plane, a1), color);
+            }
+        }
+    }
+
+    /// Short lines along the world-space vertex normals of an object
+    pub fn draw_normals(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, object: &DebugObject) {
+        let stride = object.vertices.len().div_ceil(self.max_normals.max(1)).max(1);
+        let color = Vector3::new(0.2, 0.8, 1.0);
+        for vertex in object.vertices.iter().step_by(stride) {
+            let p = vertex.position;
+            let world = multiply_matrix_vector4(&object.model_matrix, &Vector4::new(p.x, p.y, p.z, 1.0));
+            let start = Vector3::new(world.x, world.y, world.z);
+            let normal = transform_normal(&object.model_matrix, vertex.normal);
+            let end = Vector3::new(
+                start.x + normal.x * self.normal_length,
+                start.y + normal.y * self.normal_length,
+                start.z + normal.z * self.normal_length,
+            );
+            renderer.draw_line_3d(framebuffer, uniforms, start, end, color);
+        }
+    }
+}
+
+impl Default for DebugDraw {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Radius of the sphere around the object-space origin that contains every vertex
+pub fn bounding_radius(vertices: &[Vertex]) -> f32 {
+    vertices
+        .iter()
+        .map(|v| (v.position.x * v.position.x + v.position.y * v.position.y + v.position.z * v.position.z).sqrt())
+        .fold(0.0, f32::max)
+}
@@ -0,0 +1,165 @@
+use raylib::prelude::*;
+use crate::scene::BodyRef;
+
+/// Fase del atraque de la nave
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DockingState {
+    /// Vuelo libre
+    Free,
+    /// El piloto automático alinea la nave con el eje del puerto y la acerca
+    Approaching { station: BodyRef },
+    /// Atracada: la nave sigue al puerto y los controles de vuelo no responden
+    Docked { station: BodyRef },
+    /// La nave se separa del puerto a lo largo de su eje durante `Docking::undock_duration`
+    Undocking { station: BodyRef, elapsed: f32 },
+}
+
+/// Secuencia de atraque con las estaciones
+/// Solo se puede iniciar cerca del puerto y con poca velocidad relativa; la aproximación la hace
+/// el piloto automático y, una vez atracada, la posición de la nave se guarda relativa al puerto
+/// (como en `ParkingOrbit`), así que acompaña a la estación en su órbita
+pub struct Docking {
+    pub state: DockingState,
+    pub capture_range: f32,       // Distancia máxima al puerto para iniciar el atraque
+    pub max_relative_speed: f32,  // Velocidad máxima respecto al puerto para iniciarlo (unidades por segundo)
+    pub undock_speed: f32,        // Velocidad con la que la nave se separa del puerto
+    pub undock_duration: f32,     // Segundos que dura la separación
+    pub refueling: bool,          // Repostando (solo mientras está atracada)
+    pub heading: Vector3,         // Proa de la nave mientras está emparentada al puerto
+    standoff: f32,                // Distancia al puerto sobre su eje mientras está atracada
+    last_offset: Option<Vector3>, // Posición de la nave respecto al puerto en el frame anterior
+    relative_speed: f32,
+    port_distance: f32,
+}
+
+impl Docking {
+    pub fn new() -> Self {
+        Docking {
+            state: DockingState::Free,
+            capture_range: 15.0,
+            max_relative_speed: 8.0,
+            undock_speed: 4.0,
+            undock_duration: 2.5,
+            refueling: false,
+            heading: Vector3::new(0.0, -1.0, 0.0),
+            standoff: 1.2,
+            last_offset: None,
+            relative_speed: 0.0,
+            port_distance: f32::INFINITY,
+        }
+    }
+
+    /// Controles de vuelo bloqueados: cualquier fase salvo el vuelo libre
+    pub fn is_input_locked(&self) -> bool {
+        self.state != DockingState::Free
+    }
+
+    pub fn is_docked(&self) -> bool {
+        matches!(self.state, DockingState::Docked { .. })
+    }
+
+    /// Estación con la que se está atracando, atracada o de la que se separa
+    pub fn station(&self) -> Option<BodyRef> {
+        match self.state {
+            DockingState::Free => None,
+            DockingState::Approaching { station }
+            | DockingState::Docked { station }
+            | DockingState::Undocking { station, .. } => Some(station),
+        }
+    }
+
+    /// Mide la distancia y la velocidad de la nave respecto al puerto más cercano
+    /// Se llama cada frame en vuelo libre (`port` None si no hay estaciones)
+    pub fn track(&mut self, ship_position: Vector3, port: Option<Vector3>, delta_time: f32) {
+        let Some(port) = port else {
+            self.last_offset = None;
+            self.port_distance = f32::INFINITY;
+            return;
+        };
+        let offset = Vector3::new(ship_position.x - port.x, ship_position.y - port.y, ship_position.z - port.z);
+        self.port_distance = length(offset);
+        self.relative_speed = match self.last_offset {
+            Some(last) if delta_time > 0.0 => {
+                length(Vector3::new(offset.x - last.x, offset.y - last.y, offset.z - last.z)) / delta_time
+            }
+            _ => f32::INFINITY,
+        };
+        self.last_offset = Some(offset);
+    }
+
+    /// Distancia y velocidad relativa al puerto más cercano medidas en el último `track`
+    pub fn port_distance(&self) -> f32 {
+        self.port_distance
+    }
+
+    pub fn relative_speed(&self) -> f32 {
+        self.relative_speed
+    }
+
+    /// Cerca del puerto y casi en reposo respecto a él
+    pub fn can_dock(&self) -> bool {
+        self.state == DockingState::Free
+            && self.port_distance <= self.capture_range
+            && self.relative_speed <= self.max_relative_speed
+    }
+
+    /// Inicia la aproximación (el llamador activa el piloto automático hacia el puerto)
+    pub fn begin(&mut self, station: BodyRef, standoff: f32) {
+        self.state = DockingState::Approaching { station };
+        self.standoff = standoff;
+    }
+
+    /// El piloto automático dejó la nave frente al puerto: queda atracada con la proa `heading`
+    pub fn complete(&mut self, heading: Vector3) {
+        if let DockingState::Approaching { station } = self.state {
+            self.state = DockingState::Docked { station };
+            self.heading = heading;
+        }
+    }
+
+    /// Aproximación interrumpida (piloto automático cancelado, estación desaparecida)
+    pub fn abort(&mut self) {
+        self.state = DockingState::Free;
+        self.refueling = false;
+        self.last_offset = None;
+    }
+
+    /// Empieza a separarse del puerto
+    pub fn undock(&mut self) {
+        if let DockingState::Docked { station } = self.state {
+            self.state = DockingState::Undocking { station, elapsed: 0.0 };
+            self.refueling = false;
+        }
+    }
+
+    /// Avanza la separación y devuelve la posición de la nave emparentada al puerto (`port`, con
+    /// eje de llegada `axis`), o None si no está atracada ni separándose
+    /// Al terminar la separación vuelve al vuelo libre
+    pub fn update(&mut self, port: Vector3, axis: Vector3, delta_time: f32) -> Option<Vector3> {
+        let distance = match self.state {
+            DockingState::Docked { .. } => self.standoff,
+            DockingState::Undocking { station, elapsed } => {
+                let elapsed = elapsed + delta_time;
+                self.state = if elapsed >= self.undock_duration {
+                    self.last_offset = None;
+                    DockingState::Free
+                } else {
+                    DockingState::Undocking { station, elapsed }
+                };
+                self.standoff + self.undock_speed * elapsed.min(self.undock_duration)
+            }
+            _ => return None,
+        };
+        Some(Vector3::new(port.x + axis.x * distance, port.y + axis.y * distance, port.z + axis.z * distance))
+    }
+}
+
+impl Default for Docking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn length(v: Vector3) -> f32 {
+    (v.x * v.x + v.y * v.y + v.z * v.z).sqrt()
+}
@@ -0,0 +1,299 @@
+use raylib::prelude::*;
+use crate::ephemeris::Ephemeris;
+use crate::scene::{BodyRef, Scene};
+
+/// Cómo se ve el sol desde un punto a la sombra de un cuerpo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EclipseKind {
+    Partial, // En la penumbra: el cuerpo tapa parte del disco solar
+    Total,   // En la umbra: el cuerpo tapa el sol entero
+    Transit, // Más allá de la umbra: el cuerpo cruza el disco solar sin llegar a taparlo
+}
+
+impl EclipseKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EclipseKind::Partial => "parcial",
+            EclipseKind::Total => "total",
+            EclipseKind::Transit => "tránsito",
+        }
+    }
+}
+
+/// Desde dónde se ve el eclipse: la posición de la nave o el centro de un cuerpo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Observer {
+    Ship,
+    Body(BodyRef),
+}
+
+/// Eclipse previsto o en curso: `observer` queda a la sombra de `occluder`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Eclipse {
+    pub observer: Observer,
+    pub occluder: BodyRef,
+    pub kind: EclipseKind, // Fase en el momento de máxima profundidad
+    pub start: f32,        // Instante en que empieza
+    pub peak: f32,         // Instante de máxima profundidad
+    pub end: f32,          // Instante en que termina (el horizonte de la previsión si termina después)
+}
+
+impl Eclipse {
+    pub fn is_active(&self, time: f32) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Aviso para el HUD, en pasado, presente o futuro según `time`
+    pub fn headline(&self, scene: &Scene, time: f32) -> String {
+        let occluder = scene.body_name(self.occluder).unwrap_or("?");
+        let when = if self.is_active(time) {
+            format!("(quedan {})", countdown(self.end - time))
+        } else {
+            format!("en {}", countdown(self.start - time))
+        };
+        match self.observer {
+            Observer::Ship => match self.kind {
+                EclipseKind::Transit => format!("Tránsito de {} visible desde tu posición {}", occluder, when),
+                kind => format!("Eclipse solar {} visible desde tu posición {}", kind.name(), when),
+            },
+            Observer::Body(body) => {
+                let observer = scene.body_name(body).unwrap_or("?");
+                match self.kind {
+                    EclipseKind::Transit => format!("Tránsito de {} visto desde {} {}", occluder, observer, when),
+                    kind => format!("{} a la sombra de {} (eclipse {}) {}", observer, occluder, kind.name(), when),
+                }
+            }
+        }
+    }
+}
+
+/// Sucesos de los eclipses que el juego puede atender
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EclipseEvent {
+    Started(Eclipse),
+    Ended(Eclipse),
+}
+
+/// Sombra de un cuerpo de radio `occluder_radius` en `occluder` sobre una esfera de radio
+/// `radius` en `point`, con el sol (de radio `sun_radius`) en el origen
+/// Devuelve la fase y una profundidad en (0, 1] (1 = centrada en el eje de la sombra), o None si
+/// la esfera no toca la penumbra. Con `radius` = 0 es lo que ve un observador puntual
+pub fn shadow(point: Vector3, radius: f32, occluder: Vector3, occluder_radius: f32, sun_radius: f32) -> Option<(EclipseKind, f32)> {
+    let sun_distance = (occluder.x * occluder.x + occluder.y * occluder.y + occluder.z * occluder.z).sqrt();
+    if sun_distance <= sun_radius + occluder_radius {
+        return None;
+    }
+    let axis = Vector3::new(occluder.x / sun_distance, occluder.y / sun_distance, occluder.z / sun_distance);
+    let offset = Vector3::new(point.x - occluder.x, point.y - occluder.y, point.z - occluder.z);
+    let along = offset.x * axis.x + offset.y * axis.y + offset.z * axis.z;
+    if along <= 0.0 {
+        return None; // Del lado del sol: no hay sombra
+    }
+    let across = ((offset.x * offset.x + offset.y * offset.y + offset.z * offset.z) - along * along).max(0.0).sqrt();
+
+    // Conos de sombra: la penumbra se abre y la umbra se cierra (y pasado su vértice se abre
+    // como antumbra, donde el cuerpo se ve entero dentro del disco solar)
+    let penumbra = occluder_radius + along * (sun_radius + occluder_radius) / sun_distance;
+    let umbra = occluder_radius - along * (sun_radius - occluder_radius) / sun_distance;
+    if across - radius >= penumbra {
+        return None;
+    }
+    let kind = if umbra > 0.0 && across < umbra {
+        EclipseKind::Total
+    } else if umbra < 0.0 && across < -umbra {
+        EclipseKind::Transit
+    } else {
+        EclipseKind::Partial
+    };
+    Some((kind, (1.0 - across / penumbra).clamp(0.0001, 1.0)))
+}
+
+/// Previsión de eclipses: recorre las efemérides de planetas y lunas durante los próximos
+/// `horizon` segundos y anota cuándo la nave, un planeta o una luna entra en la sombra de otro
+/// cuerpo, o cuándo la nave ve un tránsito. La nave se supone quieta donde está; como se
+/// mueve, la previsión se rehace cada `refresh` segundos
+/// `update` devuelve los eclipses que empiezan y terminan, como la actividad solar
+pub struct EclipseForecast {
+    pub enabled: bool,
+    pub sun_radius: f32,   // Radio del sol (la escena no lo guarda: es el de la malla del sol)
+    pub horizon: f32,      // Segundos hacia adelante que cubre la previsión
+    pub step: f32,         // Paso de la búsqueda (los eclipses más cortos se pueden perder)
+    pub refresh: f32,      // Segundos entre previsiones
+    eclipses: Vec<Eclipse>, // Previstos o en curso, por orden de inicio
+    active: Vec<Eclipse>,   // En curso en la última actualización
+    computed_at: f32,       // Instante de la última previsión (NaN = nunca)
+}
+
+impl EclipseForecast {
+    pub fn new(sun_radius: f32) -> Self {
+        EclipseForecast {
+            enabled: true,
+            sun_radius,
+            horizon: 90.0,
+            step: 0.25,
+            refresh: 1.0,
+            eclipses: Vec::new(),
+            active: Vec::new(),
+            computed_at: f32::NAN,
+        }
+    }
+
+    /// Olvida la previsión (nueva escena o salto en el tiempo)
+    pub fn reset(&mut self) {
+        self.eclipses.clear();
+        self.active.clear();
+        self.computed_at = f32::NAN;
+    }
+
+    /// Eclipses previstos o en curso, por orden de inicio
+    #[allow(dead_code)]
+    pub fn eclipses(&self) -> &[Eclipse] {
+        &self.eclipses
+    }
+
+    /// Eclipse que merece el aviso del HUD: primero los que ve la nave, luego los totales y por
+    /// último cualquiera; entre iguales, el que antes empieza
+    pub fn featured(&self, time: f32) -> Option<&Eclipse> {
+        let upcoming = || self.eclipses.iter().filter(move |eclipse| eclipse.end > time);
+        upcoming()
+            .find(|eclipse| eclipse.observer == Observer::Ship)
+            .or_else(|| upcoming().find(|eclipse| eclipse.kind == EclipseKind::Total))
+            .or_else(|| upcoming().next())
+    }
+
+    /// Avanza hasta `time` y devuelve los eclipses que empiezan o terminan
+    pub fn update(&mut self, time: f32, scene: &Scene, ship_position: Vector3) -> Vec<EclipseEvent> {
+        let mut events = Vec::new();
+        if !self.enabled {
+            return events;
+        }
+
+        if self.computed_at.is_nan() || time < self.computed_at || time - self.computed_at >= self.refresh {
+            self.eclipses = self.predict(time, scene, ship_position);
+            self.computed_at = time;
+        }
+
+        // La previsión es por muestras: un eclipse está en curso si cubre el instante actual
+        let current: Vec<Eclipse> = self.eclipses.iter().copied().filter(|eclipse| eclipse.is_active(time)).collect();
+        let same = |a: &Eclipse, b: &Eclipse| a.observer == b.observer && a.occluder == b.occluder;
+        for eclipse in &current {
+            if !self.active.iter().any(|previous| same(previous, eclipse)) {
+                events.push(EclipseEvent::Started(*eclipse));
+            }
+        }
+        for previous in &self.active {
+            if !current.iter().any(|eclipse| same(previous, eclipse)) {
+                events.push(EclipseEvent::Ended(*previous));
+            }
+        }
+        self.active = current;
+        events
+    }
+
+    /// Buen sitio para ver `eclipse` en el instante `at`: sobre el eje de la sombra del cuerpo que
+    /// tapa el sol, dentro de la umbra, a unos tres radios del cuerpo. `epoch` es el instante en que
+    /// los ángulos orbitales de la escena son válidos (ver `Ephemeris`)
+    pub fn viewing_spot(&self, eclipse: &Eclipse, scene: &Scene, epoch: f32, at: f32) -> Option<Vector3> {
+        let occluder = Ephemeris::new(scene, epoch).position(eclipse.occluder, at)?;
+        let radius = scene.body_radius(eclipse.occluder)?;
+        let sun_distance = (occluder.x * occluder.x + occluder.y * occluder.y + occluder.z * occluder.z).sqrt().max(0.0001);
+        let umbra_length = if self.sun_radius > radius {
+            radius * sun_distance / (self.sun_radius - radius)
+        } else {
+            f32::INFINITY
+        };
+        let along = (radius * 3.0).min(umbra_length * 0.8).max(radius * 1.3);
+        Some(Vector3::new(
+            occluder.x + occluder.x / sun_distance * along,
+            occluder.y + occluder.y / sun_distance * along,
+            occluder.z + occluder.z / sun_distance * along,
+        ))
+    }
+
+    fn predict(&self, time: f32, scene: &Scene, ship_position: Vector3) -> Vec<Eclipse> {
+        let ephemeris = Ephemeris::new(scene, time);
+        let bodies: Vec<(BodyRef, f32)> = scene
+            .bodies()
+            .filter(|body| matches!(body, BodyRef::Planet(_) | BodyRef::Moon(_)))
+            .filter_map(|body| Some((body, scene.body_radius(body)?)))
+            .collect();
+
+        let mut open: Vec<(Eclipse, f32)> = Vec::new(); // Eclipses en curso en la muestra anterior, con su profundidad máxima
+        let mut closed = Vec::new();
+        let steps = (self.horizon / self.step.max(0.01)).ceil() as usize;
+        for i in 0..=steps {
+            let t = time + i as f32 * self.step;
+            let positions: Vec<Vector3> = bodies
+                .iter()
+                .map(|&(body, _)| ephemeris.position(body, t).unwrap_or(Vector3::zero()))
+                .collect();
+            let observers = std::iter::once((Observer::Ship, ship_position, 0.0))
+                .chain(bodies.iter().zip(&positions).map(|(&(body, radius), &position)| (Observer::Body(body), position, radius)));
+
+            let mut seen = Vec::new();
+            for (observer, point, radius) in observers {
+                for (&(occluder, occluder_radius), &occluder_position) in bodies.iter().zip(&positions) {
+                    if let Observer::Body(body) = observer
+                        && !same_system(scene, body, occluder)
+                    {
+                        continue;
+                    }
+                    let Some((kind, depth)) = shadow(point, radius, occluder_position, occluder_radius, self.sun_radius) else {
+                        continue;
+                    };
+                    // Un cuerpo solo está en la sombra en la penumbra o la umbra; los tránsitos
+                    // se avisan cuando los ve la nave
+                    if kind == EclipseKind::Transit && observer != Observer::Ship {
+                        continue;
+                    }
+                    seen.push((observer, occluder));
+                    match open.iter_mut().find(|(eclipse, _)| eclipse.observer == observer && eclipse.occluder == occluder) {
+                        Some((eclipse, deepest)) => {
+                            eclipse.end = t + self.step;
+                            if depth > *deepest {
+                                *deepest = depth;
+                                eclipse.kind = kind;
+                                eclipse.peak = t;
+                            }
+                        }
+                        None => open.push((Eclipse { observer, occluder, kind, start: t, peak: t, end: t + self.step }, depth)),
+                    }
+                }
+            }
+
+            // Los que no aparecen en esta muestra han terminado
+            let (still_open, ended): (Vec<_>, Vec<_>) = open
+                .into_iter()
+                .partition(|(eclipse, _)| seen.contains(&(eclipse.observer, eclipse.occluder)));
+            closed.extend(ended.into_iter().map(|(eclipse, _)| eclipse));
+            open = still_open;
+        }
+        closed.extend(open.into_iter().map(|(eclipse, _)| eclipse));
+        closed.sort_by(|a, b| a.start.total_cmp(&b.start));
+        closed
+    }
+}
+
+impl Default for EclipseForecast {
+    fn default() -> Self {
+        Self::new(3.0)
+    }
+}
+
+/// Si `a` y `b` son un planeta y una de sus lunas o dos lunas del mismo planeta: las sombras
+/// entre cuerpos solo se buscan dentro de cada sistema planetario (entre planetas distintos
+/// son casi siempre penumbras diluidas que no merecen aviso)
+fn same_system(scene: &Scene, a: BodyRef, b: BodyRef) -> bool {
+    let parent = |body: BodyRef| match body {
+        BodyRef::Planet(idx) => Some(idx),
+        BodyRef::Moon(idx) => scene.moons.get(idx).map(|moon| moon.parent),
+        _ => None,
+    };
+    a != b && parent(a).is_some() && parent(a) == parent(b)
+}
+
+/// Cuenta atrás `mm:ss`
+pub fn countdown(seconds: f32) -> String {
+    let seconds = seconds.max(0.0).ceil() as u32;
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,489 @@
+use raylib::prelude::*;
+use crate::debug_draw::DebugObject;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{create_model_matrix, inverse_rotate_vector, new_matrix4};
+use crate::obj::Obj;
+use crate::renderer::Renderer;
+use crate::scene::{Atmosphere, Crater, Eruption, Storm};
+use crate::shaders::{AuroraShader, RingUniforms, ShipShader, SurfaceShader};
+use crate::spatial_index::Frustum;
+use crate::star::Star;
+use crate::uniforms::{FillLight, Uniforms};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Identificador de una entidad del mundo: solo un índice en los almacenes de componentes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Entity(u32);
+
+impl Entity {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Almacén disperso de un tipo de componente, indexado por entidad
+pub struct Storage<T> {
+    items: Vec<Option<T>>,
+}
+
+impl<T> Storage<T> {
+    pub fn new() -> Self {
+        Storage { items: Vec::new() }
+    }
+
+    pub fn insert(&mut self, entity: Entity, component: T) {
+        let idx = entity.index();
+        if idx >= self.items.len() {
+            self.items.resize_with(idx + 1, || None);
+        }
+        self.items[idx] = Some(component);
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        self.items.get_mut(entity.index()).and_then(Option::take)
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        self.items.get(entity.index()).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        self.items.get_mut(entity.index()).and_then(Option::as_mut)
+    }
+
+    /// Entidades con este componente, en el orden en que se crearon
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| item.as_ref().map(|item| (Entity(idx as u32), item)))
+    }
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Orientación de una entidad
+#[derive(Clone, Copy, Debug)]
+pub enum Rotation {
+    /// Ángulos de Euler en el orden de `create_model_matrix`
+    Euler(Vector3),
+    /// Ejes X, Y y Z del espacio de objeto en el mundo (acoplamiento de marea)
+    Basis(Vector3, Vector3, Vector3),
+}
+
+/// Posición, orientación y escala uniforme en el mundo
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub position: Vector3,
+    pub rotation: Rotation,
+    pub scale: f32,
+}
+
+impl Transform {
+    /// Sin rotar, en `position` y con escala `scale`
+    pub fn at(position: Vector3, scale: f32) -> Self {
+        Transform { position, rotation: Rotation::Euler(Vector3::zero()), scale }
+    }
+
+    pub fn model_matrix(&self) -> Matrix {
+        match self.rotation {
+            Rotation::Euler(angles) => create_model_matrix(self.position, self.scale, angles),
+            Rotation::Basis(x, y, z) => {
+                let (s, p) = (self.scale, self.position);
+                new_matrix4(
+                    x.x * s, y.x * s, z.x * s, p.x,
+                    x.y * s, y.y * s, z.y * s, p.y,
+                    x.z * s, y.z * s, z.z * s, p.z,
+                    0.0,     0.0,     0.0,     1.0,
+                )
+            }
+        }
+    }
+
+    /// Dirección del mundo expresada en el espacio de objeto (inversa de la rotación)
+    pub fn to_object_space(&self, v: Vector3) -> Vector3 {
+        match self.rotation {
+            Rotation::Euler(angles) => inverse_rotate_vector(v, angles),
+            Rotation::Basis(x, y, z) => Vector3::new(
+                v.x * x.x + v.y * x.y + v.z * x.z,
+                v.x * y.x + v.y * y.y + v.z * y.z,
+                v.x * z.x + v.y * z.y + v.z * z.z,
+            ),
+        }
+    }
+
+    /// Dirección hacia el sol (en el origen) en el espacio de objeto
+    pub fn sun_direction(&self) -> Vector3 {
+        let p = self.position;
+        let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt().max(0.0001);
+        self.to_object_space(Vector3::new(-p.x / length, -p.y / length, -p.z / length))
+    }
+}
+
+/// Órbita circular alrededor del origen o de otra entidad
+/// El plano orbital es el XZ inclinado `inclination` alrededor del eje X, y el ángulo vale
+/// `phase` en el instante `epoch` (0 para lo que parte del inicio de la simulación; los planetas
+/// acumulan su ángulo cada paso y lo dan en el instante actual)
+#[derive(Clone, Copy, Debug)]
+pub struct Orbit {
+    pub parent: Option<Entity>, // None: alrededor del origen (la estrella)
+    pub radius: f32,
+    pub phase: f32,             // Ángulo en `epoch` (radianes)
+    pub speed: f32,             // Velocidad angular (rad/s)
+    pub inclination: f32,       // Inclinación del plano orbital (radianes)
+    pub epoch: f32,
+}
+
+impl Orbit {
+    pub fn new(radius: f32, phase: f32, speed: f32, inclination: f32) -> Self {
+        Orbit { parent: None, radius, phase, speed, inclination, epoch: 0.0 }
+    }
+
+    /// Ángulo en el instante `time`, en f64: en órbitas grandes el error del seno y el coseno
+    /// en f32, multiplicado por el radio, haría saltar al cuerpo de un frame a otro
+    pub fn angle(&self, time: f32) -> f64 {
+        self.phase as f64 + self.speed as f64 * (time as f64 - self.epoch as f64)
+    }
+
+    /// Posición relativa al centro de la órbita en el instante `time`
+    pub fn offset(&self, time: f32) -> Vector3 {
+        let angle = self.angle(time);
+        let radius = self.radius as f64;
+        let (x, z) = ((radius * angle.cos()) as f32, (radius * angle.sin()) as f32);
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        Vector3::new(x, z * sin_i, z * cos_i)
+    }
+
+    /// Ejes del espacio de objeto de un cuerpo con acoplamiento de marea: gira una vez por órbita,
+    /// así que X apunta siempre en sentido contrario al centro (la cara -X le mira), Y es la
+    /// normal del plano orbital y Z la dirección del movimiento
+    pub fn tidal_axes(&self, time: f32) -> (Vector3, Vector3, Vector3) {
+        let (sin_a, cos_a) = (self.angle(time) as f32).sin_cos();
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        (
+            Vector3::new(cos_a, sin_a * sin_i, sin_a * cos_i),
+            Vector3::new(0.0, cos_i, -sin_i),
+            Vector3::new(-sin_a, cos_a * sin_i, cos_a * cos_i),
+        )
+    }
+}
+
+/// Giro propio de una entidad
+#[derive(Clone, Copy, Debug)]
+pub enum Spin {
+    /// Día sideral de `period` horas simuladas alrededor del eje Y local, inclinado `axial_tilt`
+    /// sobre el eje Z (planetas)
+    Daily { period: f32, axial_tilt: f32, retrograde: bool },
+    /// Radianes por segundo alrededor de cada eje de Euler
+    Steady(Vector3),
+    /// Siempre la misma cara hacia el centro de su órbita (necesita un `Orbit`)
+    TidalLock,
+}
+
+impl Spin {
+    /// Ángulos de Euler en `time` segundos y `hours` horas simuladas (None si es acoplamiento de marea)
+    pub fn euler(&self, time: f32, hours: f64) -> Option<Vector3> {
+        match *self {
+            Spin::Daily { period, axial_tilt, retrograde } => {
+                let direction = if retrograde { -1.0 } else { 1.0 };
+                // Solo la fracción del día actual, para no perder precisión con relojes muy avanzados
+                let turns = (hours / period.max(0.001) as f64).fract() as f32;
+                Some(Vector3::new(0.0, turns * 2.0 * PI * direction, axial_tilt))
+            }
+            Spin::Steady(rate) => Some(Vector3::new(rate.x * time, rate.y * time, rate.z * time)),
+            Spin::TidalLock => None,
+        }
+    }
+}
+
+/// Pasada en la que se dibuja una entidad
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pass {
+    Opaque,
+    /// Después del fondo, mezclada sobre lo opaco (anillos, auroras)
+    Translucent,
+}
+
+/// Geometría de una entidad, compartida con las demás que usan la misma
+#[derive(Clone)]
+pub enum Mesh {
+    Single(Arc<Obj>),
+    /// Un nivel de detalle por calidad (ver `sphere_lods`), del más fino al más tosco
+    Lods(Arc<[Obj]>),
+}
+
+impl Mesh {
+    /// Malla del nivel de detalle `lod` (el último si no hay tantos)
+    pub fn lod(&self, lod: usize) -> &Obj {
+        match self {
+            Mesh::Single(mesh) => mesh,
+            Mesh::Lods(meshes) => &meshes[lod.min(meshes.len().saturating_sub(1))],
+        }
+    }
+}
+
+/// Malla que se dibuja en la posición de la entidad
+pub struct Renderable {
+    pub mesh: Mesh,
+    pub detail_mesh: Option<Obj>, // Sustituye a `mesh` mientras exista (el relieve de un planeta de cerca)
+    pub group: &'static str,      // Nombre del grupo en el perfil del renderer
+    pub pass: Pass,
+    pub lit: bool,                // false: emisiva, sin dirección del sol
+    pub visible: bool,            // false: se conserva pero no se dibuja (entidades de reserva de un `Pool`)
+    pub cull_radius: Option<f32>, // Radio con el que se descarta si queda fuera de la vista (None: siempre se dibuja)
+    pub bounds: Option<f32>,      // Radio de la esfera envolvente de los gizmos (None: sin gizmo)
+}
+
+impl Renderable {
+    /// Visible, iluminada, sin descarte por la vista ni gizmo
+    pub fn new(mesh: Mesh, group: &'static str, pass: Pass) -> Self {
+        Renderable { mesh, detail_mesh: None, group, pass, lit: true, visible: true, cull_radius: None, bounds: None }
+    }
+
+    /// Malla que se dibuja con el nivel de detalle `lod`
+    pub fn mesh(&self, lod: usize) -> &Obj {
+        self.detail_mesh.as_ref().unwrap_or_else(|| self.mesh.lod(lod))
+    }
+}
+
+/// Material de una entidad: el shader con el que se sombrea su malla
+pub enum Material {
+    Shader(Arc<dyn SurfaceShader>),
+    /// Cortinas de aurora; su intensidad sigue a la tormenta geomagnética del planeta
+    Aurora(AuroraShader),
+    /// Casco de la nave; el resplandor sigue a las toberas y al empuje
+    Ship(ShipShader),
+    /// Superficie de la estrella (vertex shader propio de `Renderer::draw_sun`)
+    Star(Star),
+}
+
+/// Datos de superficie que los shaders planetarios leen de los uniforms
+#[derive(Clone, Default)]
+pub struct SurfaceDetail {
+    pub rings: Option<RingUniforms>,
+    pub craters: Vec<Crater>,
+    pub eruptions: Vec<Eruption>,
+    pub storms: Vec<Storm>,
+    pub atmosphere: Option<Atmosphere>,
+    pub fill_light: Option<FillLight>,
+}
+
+impl SurfaceDetail {
+    /// Intercambia los datos con los de `uniforms` (sin copiar los vectores): se pasan a los
+    /// uniforms antes de dibujar la entidad y se recuperan después
+    fn swap(&mut self, uniforms: &mut Uniforms) {
+        std::mem::swap(&mut self.rings, &mut uniforms.rings);
+        std::mem::swap(&mut self.craters, &mut uniforms.craters);
+        std::mem::swap(&mut self.eruptions, &mut uniforms.eruptions);
+        std::mem::swap(&mut self.storms, &mut uniforms.storms);
+        std::mem::swap(&mut self.atmosphere, &mut uniforms.atmosphere);
+        std::mem::swap(&mut self.fill_light, &mut uniforms.fill_light);
+    }
+}
+
+/// Mundo de entidades y componentes: cada tipo de cuerpo (planeta, luna, estación, anillos,
+/// asteroide, nave...) es solo una combinación de componentes, y los sistemas de abajo los
+/// actualizan y dibujan sin saber de qué tipo son. Se crea al cargar la escena y vive entre
+/// frames; los sistemas modifican sus componentes en lugar de volver a crearlos
+pub struct World {
+    entities: u32,
+    pub transforms: Storage<Transform>,
+    pub orbits: Storage<Orbit>,
+    pub spins: Storage<Spin>,
+    pub renderables: Storage<Renderable>,
+    pub materials: Storage<Material>,
+    pub details: Storage<SurfaceDetail>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        World {
+            entities: 0,
+            transforms: Storage::new(),
+            orbits: Storage::new(),
+            spins: Storage::new(),
+            renderables: Storage::new(),
+            materials: Storage::new(),
+            details: Storage::new(),
+        }
+    }
+
+    /// Nueva entidad sin componentes
+    pub fn spawn(&mut self) -> Entity {
+        self.entities += 1;
+        Entity(self.entities - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entities as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entities == 0
+    }
+
+    /// Entidad dibujable con malla, material y transformación inicial
+    pub fn spawn_renderable(&mut self, transform: Transform, renderable: Renderable, material: Material) -> Entity {
+        let entity = self.spawn();
+        self.transforms.insert(entity, transform);
+        self.renderables.insert(entity, renderable);
+        self.materials.insert(entity, material);
+        entity
+    }
+
+    /// Objetos visibles con esfera envolvente, para los gizmos de depuración
+    pub fn debug_objects(&self, lod: usize) -> Vec<DebugObject<'_>> {
+        self.renderables
+            .iter()
+            .filter(|(_, renderable)| renderable.visible)
+            .filter_map(|(entity, renderable)| {
+                let transform = self.transforms.get(entity)?;
+                let radius = renderable.bounds?;
+                let vertices = &renderable.mesh(lod).vertices;
+                Some(DebugObject { model_matrix: transform.model_matrix(), vertices, center: transform.position, radius })
+            })
+            .collect()
+    }
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entidades iguales que aparecen y desaparecen de un frame a otro (asteroides, rocas de los
+/// anillos, proyectiles): se crean solo cuando hacen falta más que nunca y las que sobran se
+/// ocultan en lugar de borrarse, así que el mundo no crece ni se rehace cada frame
+pub struct Pool {
+    entities: Vec<Entity>,
+    active: usize,
+}
+
+impl Pool {
+    pub fn new() -> Self {
+        Pool { entities: Vec::new(), active: 0 }
+    }
+
+    /// Deja `count` entidades visibles, creando con `spawn` las que falten
+    pub fn resize(&mut self, world: &mut World, count: usize, mut spawn: impl FnMut(&mut World) -> Entity) {
+        while self.entities.len() < count {
+            self.entities.push(spawn(world));
+        }
+        for (idx, &entity) in self.entities.iter().enumerate() {
+            if let Some(renderable) = world.renderables.get_mut(entity) {
+                renderable.visible = idx < count;
+            }
+        }
+        self.active = count;
+    }
+
+    /// Entidades visibles, en el orden del último `resize`
+    pub fn active(&self) -> &[Entity] {
+        &self.entities[..self.active]
+    }
+}
+
+impl Default for Pool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Coloca cada entidad con órbita en el instante `time`, sumando la posición de su padre
+/// Las entidades se recorren en orden de creación, así que los padres deben crearse antes
+pub fn orbit_system(world: &mut World, time: f32) {
+    for idx in 0..world.orbits.items.len() {
+        let Some(orbit) = world.orbits.items[idx] else {
+            continue;
+        };
+        let center = orbit
+            .parent
+            .and_then(|parent| world.transforms.get(parent))
+            .map_or(Vector3::zero(), |parent| parent.position);
+        let offset = orbit.offset(time);
+        let position = Vector3::new(center.x + offset.x, center.y + offset.y, center.z + offset.z);
+        let entity = Entity(idx as u32);
+        match world.transforms.get_mut(entity) {
+            Some(transform) => transform.position = position,
+            None => world.transforms.insert(entity, Transform::at(position, 1.0)),
+        }
+    }
+}
+
+/// Orienta cada entidad con giro propio tras `time` segundos y `hours` horas simuladas
+pub fn spin_system(world: &mut World, time: f32, hours: f64) {
+    for (entity, spin) in world.spins.iter() {
+        let rotation = match spin.euler(time, hours) {
+            Some(angles) => Rotation::Euler(angles),
+            None => match world.orbits.get(entity) {
+                Some(orbit) => {
+                    let (x, y, z) = orbit.tidal_axes(time);
+                    Rotation::Basis(x, y, z)
+                }
+                None => continue,
+            },
+        };
+        if let Some(transform) = world.transforms.get_mut(entity) {
+            transform.rotation = rotation;
+        }
+    }
+}
+
+/// Dibuja las entidades visibles de la pasada `pass` en orden de creación con las matrices de
+/// cámara de `view` (sus uniforms sin matriz de modelo) y el nivel de detalle `lod`. Las que
+/// tienen `cull_radius` y quedan fuera de la vista se saltan
+pub fn render_system(world: &mut World, renderer: &Renderer, framebuffer: &mut Framebuffer, view: &Uniforms, pass: Pass, lod: usize) {
+    let frustum = Frustum::new(&view.view_matrix, &view.projection_matrix);
+    let mut uniforms = Uniforms::new(
+        Matrix::identity(),
+        view.view_matrix,
+        view.projection_matrix,
+        view.viewport_matrix,
+        view.time,
+        view.camera_position,
+        Vector3::zero(),
+    );
+    let mut group = None;
+    for (entity, renderable) in world.renderables.iter() {
+        if renderable.pass != pass || !renderable.visible {
+            continue;
+        }
+        let (Some(transform), Some(material)) = (world.transforms.get(entity), world.materials.get(entity)) else {
+            continue;
+        };
+        if let Some(radius) = renderable.cull_radius
+            && !frustum.intersects_sphere(transform.position, radius)
+        {
+            continue;
+        }
+        if group != Some(renderable.group) {
+            renderer.begin_object(renderable.group);
+            group = Some(renderable.group);
+        }
+
+        let sun_direction = if renderable.lit { transform.sun_direction() } else { Vector3::zero() };
+        uniforms.set_model(transform.model_matrix(), sun_direction);
+        // Los datos de superficie pasan a los uniforms solo mientras se dibuja la entidad
+        let mut detail = world.details.get_mut(entity);
+        if let Some(detail) = detail.as_deref_mut() {
+            detail.swap(&mut uniforms);
+        }
+
+        let mesh = renderable.mesh(lod);
+        match material {
+            Material::Shader(shader) => renderer.draw_indexed_mesh(framebuffer, &uniforms, &mesh.vertices, &mesh.indices, shader.as_ref()),
+            Material::Aurora(shader) => renderer.draw_indexed_mesh(framebuffer, &uniforms, &mesh.vertices, &mesh.indices, shader),
+            Material::Ship(shader) => renderer.draw_indexed_mesh(framebuffer, &uniforms, &mesh.vertices, &mesh.indices, shader),
+            Material::Star(star) => renderer.draw_sun(framebuffer, &uniforms, &mesh.vertices, &mesh.indices, star),
+        }
+        if let Some(detail) = detail {
+            detail.swap(&mut uniforms);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use raylib::prelude::*;
+use crate::scene::{BodyRef, Scene};
+use std::f32::consts::PI;
+
+/// Consultas de efemérides: posición, velocidad y distancias de los cuerpos de la escena
+/// en cualquier instante simulado, pasado o futuro, sin modificar la escena
+/// Los planetas guardan su ángulo orbital actual, así que las consultas se anclan en `epoch`:
+/// el instante (el mismo `time` que usa el resto de la escena) en el que esos ángulos son válidos
+pub struct Ephemeris<'a> {
+    scene: &'a Scene,
+    epoch: f32,
+}
+
+impl<'a> Ephemeris<'a> {
+    /// Paso de la diferencia central usada para las velocidades (segundos)
+    const VELOCITY_STEP: f32 = 0.01;
+    /// Paso y horizonte de la búsqueda de conjunciones (segundos)
+    const CONJUNCTION_STEP: f32 = 0.05;
+    const CONJUNCTION_HORIZON: f32 = 600.0;
+
+    pub fn new(scene: &'a Scene, epoch: f32) -> Self {
+        Ephemeris { scene, epoch }
+    }
+
+    /// Posición del cuerpo `body` en el instante `time`
+    pub fn position(&self, body: BodyRef, time: f32) -> Option<Vector3> {
+        let scene = self.scene;
+        let (parent, offset) = match body {
+            BodyRef::Planet(idx) => return scene.planets.get(idx).map(|planet| planet.position_after(time - self.epoch)),
+            BodyRef::Moon(idx) => {
+                let moon = scene.moons.get(idx)?;
+                (moon.parent, moon.offset(time))
+            }
+            BodyRef::Station(idx) => {
+                let station = scene.stations.get(idx)?;
+                (station.parent, station.offset(time))
+            }
+            BodyRef::BlackHole(idx) => return scene.black_holes.get(idx).map(|hole| hole.position),
+        };
+        let parent = scene.planets.get(parent)?.position_after(time - self.epoch);
+        Some(Vector3::new(parent.x + offset.x, parent.y + offset.y, parent.z + offset.z))
+    }
+
+    /// Velocidad del cuerpo `body` en el instante `time` (unidades por segundo)
+    pub fn velocity(&self, body: BodyRef, time: f32) -> Option<Vector3> {
+        let h = Self::VELOCITY_STEP;
+        let before = self.position(body, time - h)?;
+        let after = self.position(body, time + h)?;
+        Some(Vector3::new(
+            (after.x - before.x) / (2.0 * h),
+            (after.y - before.y) / (2.0 * h),
+            (after.z - before.z) / (2.0 * h),
+        ))
+    }
+
+    /// Distancia entre los centros de `a` y `b` en el instante `time`
+    pub fn distance(&self, a: BodyRef, b: BodyRef, time: f32) -> Option<f32> {
+        let pa = self.position(a, time)?;
+        let pb = self.position(b, time)?;
+        let (dx, dy, dz) = (pb.x - pa.x, pb.y - pa.y, pb.z - pa.z);
+        Some((dx * dx + dy * dy + dz * dz).sqrt())
+    }
+
+    /// Primer instante posterior a `after` en el que `a` y `b` están en conjunción: alineados
+    /// del mismo lado del sol (misma longitud vista desde el origen, en el plano XZ)
+    /// None si no ocurre dentro del horizonte de búsqueda o algún cuerpo no existe
+    pub fn next_conjunction(&self, a: BodyRef, b: BodyRef, after: f32) -> Option<f32> {
+        // Diferencia de longitudes envuelta a (-PI, PI]: la conjunción es un cruce por cero
+        // (los saltos de -PI a PI son la oposición y se descartan)
+        let separation = |time: f32| -> Option<f32> {
+            let pa = self.position(a, time)?;
+            let pb = self.position(b, time)?;
+            let difference = pb.z.atan2(pb.x) - pa.z.atan2(pa.x);
+            Some((difference + PI).rem_euclid(2.0 * PI) - PI)
+        };
+
+        let mut t0 = after;
+        let mut s0 = separation(t0)?;
+        while t0 < after + Self::CONJUNCTION_HORIZON {
+            let t1 = t0 + Self::CONJUNCTION_STEP;
+            let s1 = separation(t1)?;
+            if s1 == 0.0 {
+                return Some(t1);
+            }
+            if s0 * s1 < 0.0 && (s1 - s0).abs() < PI {
+                // Refinar el cruce por bisección
+                let (mut lo, mut hi, mut s_lo) = (t0, t1, s0);
+                for _ in 0..30 {
+                    let mid = 0.5 * (lo + hi);
+                    let s_mid = separation(mid)?;
+                    if s_lo * s_mid <= 0.0 {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                        s_lo = s_mid;
+                    }
+                }
+                return Some(0.5 * (lo + hi));
+            }
+            t0 = t1;
+            s0 = s1;
+        }
+        None
+    }
+
+    /// `position` por nombre (ver `Scene::find_body`)
+    pub fn position_of(&self, name: &str, time: f32) -> Option<Vector3> {
+        self.position(self.scene.find_body(name)?, time)
+    }
+
+    /// `velocity` por nombre
+    pub fn velocity_of(&self, name: &str, time: f32) -> Option<Vector3> {
+        self.velocity(self.scene.find_body(name)?, time)
+    }
+
+    /// `distance` por nombre
+    pub fn distance_between(&self, a: &str, b: &str, time: f32) -> Option<f32> {
+        self.distance(self.scene.find_body(a)?, self.scene.find_body(b)?, time)
+    }
+
+    /// `next_conjunction` por nombre
+    pub fn next_conjunction_of(&self, a: &str, b: &str, after: f32) -> Option<f32> {
+        self.next_conjunction(self.scene.find_body(a)?, self.scene.find_body(b)?, after)
+    }
+}
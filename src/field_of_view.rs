@@ -0,0 +1,88 @@
+use raylib::prelude::*;
+use crate::input_map::Action;
+use crate::input_state::InputState;
+use std::f32::consts::PI;
+
+/// Campo de visión vertical de la cámara del juego: el base se ajusta con las acciones
+/// `ZoomIn`/`ZoomOut`, mantener `Binoculars` lo estrecha como unos prismáticos para observar
+/// planetas lejanos y el warp lo abre un momento para dar sensación de velocidad. El valor
+/// cambia suavemente y `main` lo pasa a `create_projection_matrix` en cada frame
+pub struct FieldOfView {
+    pub base: f32,           // Campo elegido por el jugador (radianes)
+    pub step: f32,           // Cuánto cambia `base` con cada pulsación
+    pub binocular_zoom: f32, // Aumento de los prismáticos (el campo se divide por él)
+    pub warp_kick: f32,      // Radianes que se abre el campo en el punto álgido del warp
+    pub response: f32,       // Rapidez (1/s) con la que el campo sigue al pedido
+    current: f32,            // Campo actual, ya suavizado
+    binoculars: bool,        // Prismáticos en uso en el último paso
+    notice: f32,             // Segundos que le quedan al aviso del campo tras cambiarlo
+}
+
+impl FieldOfView {
+    pub const DEFAULT: f32 = PI / 3.0;   // 60°
+    pub const MIN: f32 = PI / 9.0;       // 20°
+    pub const MAX: f32 = PI * 5.0 / 9.0; // 100°
+
+    pub fn new() -> Self {
+        FieldOfView {
+            base: Self::DEFAULT,
+            step: PI / 36.0, // 5°
+            binocular_zoom: 6.0,
+            warp_kick: PI / 9.0,
+            response: 8.0,
+            current: Self::DEFAULT,
+            binoculars: false,
+            notice: 0.0,
+        }
+    }
+
+    /// Campo de visión con el que renderizar este frame (radianes)
+    pub fn current(&self) -> f32 {
+        self.current
+    }
+
+    /// Aumento respecto al campo base (mayor que 1 con los prismáticos)
+    pub fn magnification(&self) -> f32 {
+        self.base / self.current.max(0.0001)
+    }
+
+    /// Avanza un paso de simulación: atiende las acciones de zoom y acerca el campo actual al
+    /// pedido. `warp` es la intensidad del warp en [0, 1] (0 sin warp)
+    pub fn update(&mut self, input: &InputState, warp: f32, delta_time: f32) {
+        self.notice = (self.notice - delta_time).max(0.0);
+        let zoom = input.is_pressed(Action::ZoomOut) as i32 - input.is_pressed(Action::ZoomIn) as i32;
+        if zoom != 0 {
+            self.base = (self.base + zoom as f32 * self.step).clamp(Self::MIN, Self::MAX);
+            self.notice = 2.0;
+        }
+        self.binoculars = input.is_down(Action::Binoculars);
+
+        let zoom = if self.binoculars { self.binocular_zoom.max(1.0) } else { 1.0 };
+        let target = (self.base / zoom + self.warp_kick * warp.clamp(0.0, 1.0)).min(PI * 0.9);
+        self.current += (target - self.current) * (1.0 - (-self.response * delta_time).exp());
+    }
+
+    /// Indicador del zoom: el aumento con los prismáticos o el campo recién cambiado
+    pub fn draw(&self, d: &mut RaylibDrawHandle, width: i32, height: i32) {
+        let color = Color::new(200, 230, 255, 220);
+        if self.binoculars {
+            // Marco circular de los prismáticos con el aumento debajo
+            let center = Vector2::new(width as f32 / 2.0, height as f32 / 2.0);
+            let radius = height as f32 * 0.45;
+            d.draw_circle_lines(center.x as i32, center.y as i32, radius, color);
+            let text = format!("x{:.1}", self.magnification());
+            let text_width = measure_text(&text, 16);
+            d.draw_text(&text, center.x as i32 - text_width / 2, (center.y + radius) as i32 - 24, 16, color);
+        } else if self.notice > 0.0 {
+            let text = format!("Campo de visión: {:.0}°", self.base.to_degrees());
+            let text_width = measure_text(&text, 14);
+            d.draw_text(&text, width / 2 - text_width / 2, height - 60, 14, color);
+        }
+    }
+}
+
+impl Default for FieldOfView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
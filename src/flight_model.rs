@@ -0,0 +1,277 @@
+use raylib::prelude::*;
+use std::fs;
+use std::io;
+
+/// Modelo de vuelo manual de la nave (Tab alterna entre los dos)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlightMode {
+    /// La velocidad va alineada con la proa y tiene un tope; al soltar los controles la nave frena sola
+    Arcade,
+    /// El empuje suma velocidad y la nave conserva la inercia: girar no cambia el rumbo. El motor
+    /// principal empuja hacia la proa y los propulsores de maniobra (RCS) trasladan en el resto
+    Newtonian,
+}
+
+impl FlightMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            FlightMode::Arcade => FlightMode::Newtonian,
+            FlightMode::Newtonian => FlightMode::Arcade,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FlightMode::Arcade => "arcade",
+            FlightMode::Newtonian => "newtoniano",
+        }
+    }
+}
+
+/// Constantes de ajuste de los dos modelos de vuelo (ver `assets/flight.cfg`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightConfig {
+    pub arcade_max_speed: f32,    // Velocidad máxima hacia la proa (unidades por segundo)
+    pub arcade_strafe_speed: f32, // Velocidad máxima de las traslaciones laterales y verticales
+    pub arcade_acceleration: f32, // Aceleración hacia la velocidad pedida (unidades por segundo²)
+    pub arcade_brake: f32,        // Deceleración del freno automático al soltar los controles
+    pub main_thrust: f32,         // Newtoniano: aceleración del motor principal
+    pub rcs_thrust: f32,          // Newtoniano: aceleración de los propulsores de maniobra
+    pub gravity: f32,             // Newtoniano: constante de la gravedad de los cuerpos (0 = sin gravedad)
+    pub trajectory_seconds: f32,  // Newtoniano: segundos de trayectoria prevista que se dibujan (0 = ninguno)
+    pub rotation_acceleration: f32, // Inercia del giro de la cámara al mantener las teclas (1/s)
+    pub rotation_damping: f32,      // Inercia del giro al soltarlas (1/s; menos = gira más tiempo)
+    pub pan_acceleration: f32,      // Lo mismo para los controles de desplazamiento
+    pub pan_damping: f32,
+}
+
+impl FlightConfig {
+    pub fn new() -> Self {
+        FlightConfig {
+            arcade_max_speed: 9.0,
+            arcade_strafe_speed: 6.0,
+            arcade_acceleration: 12.0,
+            arcade_brake: 18.0,
+            main_thrust: 6.0,
+            rcs_thrust: 2.5,
+            gravity: 1.0,
+            trajectory_seconds: 20.0,
+            rotation_acceleration: 8.0,
+            rotation_damping: 5.0,
+            pan_acceleration: 12.0,
+            pan_damping: 12.0,
+        }
+    }
+
+    /// Cargar la configuración de un archivo de líneas `clave = valor` (`#` inicia un comentario)
+    /// Las claves que no aparecen conservan su valor por defecto
+    pub fn load(path: &str) -> io::Result<Self> {
+        FlightConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut config = FlightConfig::new();
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'clave = valor'"))?;
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|e| config_error(line_number, &format!("{}", e)))?;
+            let key = key.trim();
+            let field = config
+                .fields_mut()
+                .into_iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, field)| field)
+                .ok_or_else(|| config_error(line_number, &format!("clave desconocida '{}'", key)))?;
+            *field = value.max(0.0);
+        }
+
+        Ok(config)
+    }
+
+    /// Texto con todas las constantes en el formato que lee `parse`
+    pub fn to_config_string(mut self) -> String {
+        self.fields_mut().into_iter().map(|(name, value)| format!("{} = {}\n", name, value)).collect()
+    }
+
+    fn fields_mut(&mut self) -> [(&'static str, &mut f32); 12] {
+        [
+            ("arcade_max_speed", &mut self.arcade_max_speed),
+            ("arcade_strafe_speed", &mut self.arcade_strafe_speed),
+            ("arcade_acceleration", &mut self.arcade_acceleration),
+            ("arcade_brake", &mut self.arcade_brake),
+            ("main_thrust", &mut self.main_thrust),
+            ("rcs_thrust", &mut self.rcs_thrust),
+            ("gravity", &mut self.gravity),
+            ("trajectory_seconds", &mut self.trajectory_seconds),
+            ("rotation_acceleration", &mut self.rotation_acceleration),
+            ("rotation_damping", &mut self.rotation_damping),
+            ("pan_acceleration", &mut self.pan_acceleration),
+            ("pan_damping", &mut self.pan_damping),
+        ]
+    }
+}
+
+impl Default for FlightConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Integra la velocidad de la nave a partir de lo que piden los controles
+/// Los controles solo dan la dirección del empuje; cada modelo decide cómo cambia la velocidad.
+/// Sin potencia (sin combustible) ninguno de los dos responde y la nave sigue a la deriva
+pub struct FlightModel {
+    pub mode: FlightMode,
+    pub config: FlightConfig,
+    pub velocity: Vector3, // Velocidad actual (unidades por segundo)
+    heading: Vector3,      // Proa en el frame anterior (en arcade la velocidad gira con ella)
+    output: f32,           // Empuje usado en el último frame, en [0, 1]
+}
+
+impl FlightModel {
+    pub fn new(config: FlightConfig) -> Self {
+        FlightModel {
+            mode: FlightMode::Arcade,
+            config,
+            velocity: Vector3::zero(),
+            heading: Vector3::new(0.0, 0.0, 1.0),
+            output: 0.0,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.mode = self.mode.toggled();
+    }
+
+    pub fn speed(&self) -> f32 {
+        length(self.velocity)
+    }
+
+    /// Empuje usado en el último `update`, en [0, 1] (lo paga `ShipSystems::thrust`)
+    pub fn output(&self) -> f32 {
+        self.output
+    }
+
+    /// Detiene la nave (el warp, el piloto automático o el atraque la mueven por su cuenta)
+    pub fn stop(&mut self) {
+        self.velocity = Vector3::zero();
+        self.output = 0.0;
+    }
+
+    /// Si la gravedad cuenta: solo en el modelo newtoniano (en arcade la nave no tiene inercia)
+    pub fn feels_gravity(&self) -> bool {
+        self.mode == FlightMode::Newtonian && self.config.gravity > 0.0
+    }
+
+    /// Suma a la velocidad una aceleración externa (la gravedad) durante `delta_time` segundos,
+    /// con o sin combustible; en arcade no tiene efecto
+    pub fn accelerate(&mut self, acceleration: Vector3, delta_time: f32) {
+        if self.mode == FlightMode::Newtonian {
+            self.velocity = add(self.velocity, scale(acceleration, delta_time));
+        }
+    }
+
+    /// Avanza un frame y devuelve el desplazamiento de la nave
+    /// `command` es la dirección de empuje pedida en el mundo (cada tecla aporta longitud 1),
+    /// `heading` la proa actual normalizada y `powered` si los motores tienen combustible
+    pub fn update(&mut self, command: Vector3, heading: Vector3, powered: bool, delta_time: f32) -> Vector3 {
+        self.output = 0.0;
+        if powered && delta_time > 0.0 {
+            match self.mode {
+                FlightMode::Arcade => self.update_arcade(command, heading, delta_time),
+                FlightMode::Newtonian => self.update_newtonian(command, heading, delta_time),
+            }
+        }
+        self.heading = heading;
+        Vector3::new(self.velocity.x * delta_time, self.velocity.y * delta_time, self.velocity.z * delta_time)
+    }
+
+    fn update_arcade(&mut self, command: Vector3, heading: Vector3, delta_time: f32) {
+        let config = &self.config;
+
+        // Descomponer la velocidad respecto a la proa anterior y recomponerla con la actual:
+        // al girar la nave, la velocidad gira con ella sin perder rapidez
+        let previous = self.heading;
+        let along = dot(self.velocity, previous);
+        let lateral = sub(self.velocity, scale(previous, along));
+
+        let command_along = dot(command, heading);
+        let command_lateral = sub(command, scale(heading, command_along));
+
+        let target_along = command_along.clamp(-1.0, 1.0) * config.arcade_max_speed;
+        let rate = if command_along.abs() > 0.001 { config.arcade_acceleration } else { config.arcade_brake };
+        let new_along = along + (target_along - along).clamp(-rate * delta_time, rate * delta_time);
+
+        let lateral_length = length(command_lateral);
+        let target_lateral = scale(command_lateral, config.arcade_strafe_speed / lateral_length.max(1.0));
+        let rate = if lateral_length > 0.001 { config.arcade_acceleration } else { config.arcade_brake };
+        let difference = sub(target_lateral, lateral);
+        let step = length(difference).min(rate * delta_time);
+        let new_lateral = add(lateral, scale(difference, step / length(difference).max(0.0001)));
+
+        let mut velocity = add(scale(heading, new_along), new_lateral);
+        let speed = length(velocity);
+        if speed > config.arcade_max_speed {
+            velocity = scale(velocity, config.arcade_max_speed / speed);
+        }
+        self.velocity = velocity;
+
+        // Los motores trabajan mientras se mantienen los controles y mientras frenan
+        let braking = ((new_along - along).abs() + step) / (config.arcade_brake * delta_time).max(0.0001);
+        self.output = length(command).min(1.0).max(braking.min(1.0));
+    }
+
+    fn update_newtonian(&mut self, command: Vector3, heading: Vector3, delta_time: f32) {
+        let config = &self.config;
+
+        // El motor principal solo empuja hacia delante; lo demás (también frenar) es del RCS
+        let main = dot(command, heading).clamp(0.0, 1.0);
+        let rcs = sub(command, scale(heading, main));
+        let rcs = scale(rcs, 1.0 / length(rcs).max(1.0));
+        let acceleration = add(scale(heading, main * config.main_thrust), scale(rcs, config.rcs_thrust));
+
+        self.velocity = add(self.velocity, scale(acceleration, delta_time));
+        self.output = ((main * config.main_thrust + length(rcs) * config.rcs_thrust) / config.main_thrust.max(0.0001)).min(1.0);
+    }
+}
+
+impl Default for FlightModel {
+    fn default() -> Self {
+        Self::new(FlightConfig::default())
+    }
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
+
+fn dot(a: Vector3, b: Vector3) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+fn add(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+fn sub(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+fn scale(v: Vector3, s: f32) -> Vector3 {
+    Vector3::new(v.x * s, v.y * s, v.z * s)
+}
+
+fn length(v: Vector3) -> f32 {
+    dot(v, v).sqrt()
+}
@@ -1,12 +1,14 @@
-#![allow(dead_code)]
-
 use raylib::math::{Vector2, Vector3};
 
+#[derive(Clone, Copy)]
 pub struct Fragment {
     pub position: Vector2,      // Screen-space position
     pub color: Vector3,          // Interpolated color
     pub depth: f32,              // Interpolated depth
-    pub world_position: Vector3, // Interpolated world-space position
+    pub world_position: Vector3, // Interpolated world-space position (after the model transform)
+    pub object_position: Vector3, // Interpolated object-space position, for patterns fixed to the surface
+    pub normal: Vector3,         // Interpolated world-space normal
+    pub specular: Vector3,       // Specular highlight (Blinn-Phong), added after the fragment shader
 }
 
 impl Fragment {
@@ -16,6 +18,9 @@ impl Fragment {
             color,
             depth,
             world_position: Vector3::zero(),
+            object_position: Vector3::zero(),
+            normal: Vector3::zero(),
+            specular: Vector3::zero(),
         }
     }
 
@@ -25,6 +30,9 @@ impl Fragment {
             color,
             depth,
             world_position: world_pos,
+            object_position: world_pos,
+            normal: Vector3::zero(),
+            specular: Vector3::zero(),
         }
     }
 }
\ No newline at end of file
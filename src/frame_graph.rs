@@ -0,0 +1,96 @@
+use raylib::prelude::*;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Presupuestos de tiempo de frame en milisegundos (60 y 30 FPS)
+const BUDGET_60_MS: f32 = 1000.0 / 60.0;
+const BUDGET_30_MS: f32 = 1000.0 / 30.0;
+
+/// Gráfica de los últimos tiempos de frame (acción `FrameGraph`): una columna por frame, verde
+/// dentro del presupuesto de 60 FPS, amarilla hasta 30 FPS y roja por encima, con una línea en
+/// cada presupuesto. Sirve para ver los tirones al hacer warp, cruzar el cinturón de asteroides
+/// o acercarse a la nave
+pub struct FrameGraph {
+    pub visible: bool,
+    pub capacity: usize,    // Frames mostrados (uno por columna de píxeles)
+    pub height: i32,        // Alto de la gráfica en píxeles
+    pub max_ms: f32,        // Tiempo que llena la gráfica; los frames más largos se recortan
+    samples: VecDeque<f32>, // Tiempos de frame en milisegundos, el más reciente al final
+}
+
+impl FrameGraph {
+    pub fn new() -> Self {
+        FrameGraph {
+            visible: false,
+            capacity: 240,
+            height: 70,
+            max_ms: 50.0,
+            samples: VecDeque::with_capacity(240),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Añadir el tiempo de un frame (se guarda aunque la gráfica esté oculta)
+    pub fn record(&mut self, frame_time: Duration) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(frame_time.as_secs_f32() * 1000.0);
+    }
+
+    /// Tiempo máximo de los frames mostrados, en milisegundos
+    pub fn worst(&self) -> f32 {
+        self.samples.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Dibuja la gráfica con su esquina inferior derecha en (`right`, `bottom`)
+    pub fn draw(&self, d: &mut RaylibDrawHandle, right: i32, bottom: i32) {
+        if !self.visible {
+            return;
+        }
+
+        let width = self.capacity as i32;
+        let x = right - width;
+        let y = bottom - self.height;
+        let pixels_per_ms = self.height as f32 / self.max_ms;
+        d.draw_rectangle(x, y - 16, width, self.height + 16, Color::new(0, 0, 0, 170));
+
+        // Los frames más recientes quedan a la derecha
+        let first_column = x + width - self.samples.len() as i32;
+        for (i, &ms) in self.samples.iter().enumerate() {
+            let color = if ms <= BUDGET_60_MS {
+                Color::new(80, 220, 120, 255)
+            } else if ms <= BUDGET_30_MS {
+                Color::new(255, 200, 60, 255)
+            } else {
+                Color::new(255, 70, 50, 255)
+            };
+            let bar = ((ms * pixels_per_ms) as i32).clamp(1, self.height);
+            let column = first_column + i as i32;
+            d.draw_line(column, bottom, column, bottom - bar, color);
+        }
+
+        for budget in [BUDGET_60_MS, BUDGET_30_MS] {
+            let line_y = bottom - (budget * pixels_per_ms) as i32;
+            d.draw_line(x, line_y, x + width, line_y, Color::new(200, 210, 220, 110));
+        }
+
+        let last = self.samples.back().copied().unwrap_or(0.0);
+        d.draw_text(
+            &format!("FRAME {:5.1} ms   máx {:5.1} ms", last, self.worst()),
+            x + 4,
+            y - 13,
+            10,
+            Color::new(200, 210, 220, 255),
+        );
+    }
+}
+
+impl Default for FrameGraph {
+    fn default() -> Self {
+        Self::new()
+    }
+}
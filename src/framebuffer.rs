@@ -1,92 +1,918 @@
 use raylib::prelude::*;
+use crate::color::{GammaLut, DEFAULT_GAMMA};
+use crate::matrix::linearize_logarithmic_depth;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
 
+/// Inclusive pixel bounds of the area written since the last clear
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirtyRect {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+impl DirtyRect {
+    pub fn empty() -> Self {
+        DirtyRect { min_x: i32::MAX, min_y: i32::MAX, max_x: i32::MIN, max_y: i32::MIN }
+    }
+
+    pub fn full(width: u32, height: u32) -> Self {
+        DirtyRect { min_x: 0, min_y: 0, max_x: width as i32 - 1, max_y: height as i32 - 1 }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    pub fn width(&self) -> i32 {
+        if self.is_empty() { 0 } else { self.max_x - self.min_x + 1 }
+    }
+
+    pub fn height(&self) -> i32 {
+        if self.is_empty() { 0 } else { self.max_y - self.min_y + 1 }
+    }
+
+    fn include(&mut self, x: i32, y: i32) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    pub fn union(&self, other: &DirtyRect) -> DirtyRect {
+        DirtyRect {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+}
+
+/// Pixel rectangle that limits where drawing can happen (like raylib's scissor mode)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScissorRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl ScissorRect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        ScissorRect { x, y, width: width.max(0), height: height.max(0) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+
+    /// Overlap of two rectangles (empty if they do not overlap)
+    pub fn intersect(&self, other: &ScissorRect) -> ScissorRect {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.width).min(other.x + other.width);
+        let y1 = (self.y + self.height).min(other.y + other.height);
+        ScissorRect::new(x0, y0, x1 - x0, y1 - y0)
+    }
+}
+
+/// Box blur of `radius` along one axis of a 2D buffer, with a running sum: `lines` lines of
+/// `length` values, consecutive values `step` apart and consecutive lines `stride` apart.
+/// Values past the ends count as zero, so light fades out at the borders.
+/// `line` is scratch space for one line, kept by the caller so the blur does not allocate.
+fn box_blur(buffer: &mut [Vector3], line: &mut Vec<Vector3>, length: usize, lines: usize, radius: usize, step: usize, stride: usize) {
+    let inv = 1.0 / (2 * radius + 1) as f32;
+    line.clear();
+    line.resize(length, Vector3::zero());
+    for l in 0..lines {
+        for (i, value) in line.iter_mut().enumerate() {
+            *value = buffer[l * stride + i * step];
+        }
+        let mut sum = Vector3::zero();
+        for value in &line[..radius.min(length)] {
+            sum.x += value.x;
+            sum.y += value.y;
+            sum.z += value.z;
+        }
+        for i in 0..length {
+            if i + radius < length {
+                let entering = line[i + radius];
+                sum.x += entering.x;
+                sum.y += entering.y;
+                sum.z += entering.z;
+            }
+            if i > radius {
+                let leaving = line[i - radius - 1];
+                sum.x -= leaving.x;
+                sum.y -= leaving.y;
+                sum.z -= leaving.z;
+            }
+            buffer[l * stride + i * step] = Vector3::new(sum.x * inv, sum.y * inv, sum.z * inv);
+        }
+    }
+}
+
+/// 4x4 Bayer matrix scaled to 0-255 thresholds, each cell centred in its 1/16 step
+const BAYER_4X4: [[u8; 4]; 4] = [
+    [8, 136, 40, 168],
+    [200, 72, 232, 104],
+    [56, 184, 24, 152],
+    [248, 120, 216, 88],
+];
+
+/// Exposure, gamma and dithering that turn the linear color buffer into 8-bit display colors
+/// Cheap to clone (the gamma table is shared), so a copy can encode pixels away from the
+/// framebuffer that owns it
+#[derive(Clone)]
+pub struct PixelEncoder {
+    gamma_lut: Arc<GammaLut>,
+    dithering: bool,
+    exposure: f32, // Linear scale applied to every color before encoding
+}
+
+impl PixelEncoder {
+    pub fn new() -> Self {
+        PixelEncoder { gamma_lut: Arc::new(GammaLut::new(DEFAULT_GAMMA)), dithering: true, exposure: 1.0 }
+    }
+
+    /// Exposure-scaled, gamma-encoded (and optionally dithered) 8-bit color of pixel (x, y)
+    pub fn encode_pixel(&self, color: Vector3, x: usize, y: usize) -> Color {
+        let color = if self.exposure == 1.0 {
+            color
+        } else {
+            Vector3::new(color.x * self.exposure, color.y * self.exposure, color.z * self.exposure)
+        };
+        // The pattern is tied to screen coordinates so it stays still between frames
+        if self.dithering {
+            self.gamma_lut.encode_rgb_dithered(color, BAYER_4X4[y % 4][x % 4])
+        } else {
+            self.gamma_lut.encode_rgb(color)
+        }
+    }
+
+    /// Encodes the colors of `region`, copied with `Framebuffer::copy_region`, into `out` exactly
+    /// like `Framebuffer::encode_region` would. Returns the number of pixels written
+    pub fn encode_region(&self, region: &DirtyRect, colors: &[Vector3], out: &mut [u8]) -> usize {
+        if region.is_empty() {
+            return 0;
+        }
+        let (x0, x1) = (region.min_x as usize, region.max_x as usize);
+        let mut offset = 0;
+        let mut colors = colors.iter();
+        for y in region.min_y as usize..=region.max_y as usize {
+            for x in x0..=x1 {
+                let encoded = self.encode_pixel(*colors.next().unwrap(), x, y);
+                out[offset..offset + 4].copy_from_slice(&[encoded.r, encoded.g, encoded.b, encoded.a]);
+                offset += 4;
+            }
+        }
+        offset / 4
+    }
+}
+
+impl Default for PixelEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Colors are stored in linear space as floats; they are gamma-encoded to 8 bits
+/// only when the image is read back, so lighting and blending happen in linear light.
+/// The encoding is dithered with an ordered pattern by default, which hides the banding
+/// of smooth procedural gradients (gas giant bands, the sun's limb) in 8-bit output.
+///
+/// Self-illuminated surfaces also write their emission to a separate buffer; `bloom_pass`
+/// blurs it and adds it back so emissive geometry glows past its edges.
+///
+/// Writes are tracked with a dirty rectangle: `clear` only resets the area drawn since
+/// the previous clear, and `take_changed_region` reports the area that changed (this frame's
+/// drawing plus whatever the clear wiped), so a presenter only has to encode and upload that
+/// part and a mostly empty screen is cheap.
+///
+/// The framebuffer is plain CPU memory and never touches the window or the GPU: it can be
+/// created, drawn to and read back (`to_rgb8`, `encode_region`) without initializing raylib.
+/// Showing it on screen is the job of `ScreenTexture`.
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
-    image: Image,
+    color_buffer: Vec<Vector3>,
+    emission_buffer: Vec<Vector3>, // Linear emission of the visible surface at each pixel
+    encoder: PixelEncoder,         // Exposure, gamma and dithering of the 8-bit output
     background_color: Vector3,
-    texture: Option<Texture2D>,
     depth_buffer: Vec<f32>,
+    dirty: DirtyRect,         // Written since the last clear
+    cleared: DirtyRect,       // Reset by the last clear; must be presented once more
+    emissive: DirtyRect,      // Pixels with emission since the last clear
+    scissor: ScissorRect,     // Writable area, always inside the framebuffer
+    source_scratch: Vec<Vector3>, // Copy of the color buffer read by the full-screen passes
+    bloom_scratch: Vec<Vector3>,  // Low-resolution emission blurred by `bloom_pass`
+    line_scratch: Vec<Vector3>,   // One line of the bloom blur
+    ray_sources: Vec<f32>,        // Low-resolution light sources of `god_rays_pass`
+    rays: Vec<f32>,               // Low-resolution shafts gathered from them
 }
 
 impl Framebuffer {
     pub fn new(width: u32, height: u32) -> Self {
-        let image = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
         let buffer_size = (width * height) as usize;
+        let color_buffer = vec![Vector3::zero(); buffer_size];
+        let emission_buffer = vec![Vector3::zero(); buffer_size];
         let depth_buffer = vec![f32::INFINITY; buffer_size]; // Initialize with far plane
         Framebuffer {
             width,
             height,
-            image,
+            color_buffer,
+            emission_buffer,
+            encoder: PixelEncoder::new(),
             background_color: Vector3::zero(),
-            texture: None,
             depth_buffer,
+            // Everything is dirty at first so the first clear and presentation cover the whole screen
+            dirty: DirtyRect::full(width, height),
+            cleared: DirtyRect::empty(),
+            emissive: DirtyRect::empty(),
+            scissor: ScissorRect::new(0, 0, width as i32, height as i32),
+            source_scratch: Vec::new(),
+            bloom_scratch: Vec::new(),
+            line_scratch: Vec::new(),
+            ray_sources: Vec::new(),
+            rays: Vec::new(),
         }
     }
 
-    pub fn init_texture(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) {
-        self.texture = Some(rl.load_texture_from_image(thread, &self.image).unwrap());
+    /// Changes the render resolution, keeping the background color, gamma, dithering and exposure.
+    /// Reallocates every buffer; a `ScreenTexture` showing it has to be resized too.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let background_color = self.background_color;
+        let encoder = self.encoder.clone();
+        *self = Framebuffer::new(width.max(1), height.max(1));
+        self.background_color = background_color;
+        self.encoder = encoder;
     }
 
+    /// Resets color and depth, but only inside the area written since the previous clear;
+    /// everything outside it still holds the background and the far plane
     pub fn clear(&mut self) {
-        let bg_color = Color::new(
-            (self.background_color.x * 255.0) as u8,
-            (self.background_color.y * 255.0) as u8,
-            (self.background_color.z * 255.0) as u8,
-            255,
-        );
-        self.image.clear_background(bg_color);
+        let region = self.dirty;
+        if !region.is_empty() {
+            let width = self.width as usize;
+            let (x0, x1) = (region.min_x as usize, region.max_x as usize);
+            for y in region.min_y as usize..=region.max_y as usize {
+                let row = y * width;
+                self.color_buffer[row + x0..=row + x1].fill(self.background_color);
+                // Clear depth buffer to far plane
+                self.depth_buffer[row + x0..=row + x1].fill(f32::INFINITY);
+            }
+        }
+        let emissive = self.emissive;
+        if !emissive.is_empty() {
+            let width = self.width as usize;
+            for y in emissive.min_y as usize..=emissive.max_y as usize {
+                let row = y * width;
+                self.emission_buffer[row + emissive.min_x as usize..=row + emissive.max_x as usize].fill(Vector3::zero());
+            }
+        }
+        self.cleared = region;
+        self.dirty = DirtyRect::empty();
+        self.emissive = DirtyRect::empty();
+    }
 
-        // Clear depth buffer to far plane
-        self.depth_buffer.fill(f32::INFINITY);
+    /// Restricts `point`, `blend_point`, `add_color` and triangle rasterization to a rectangle
+    /// (clamped to the framebuffer); `None` makes the whole framebuffer writable again
+    pub fn set_scissor(&mut self, scissor: Option<ScissorRect>) {
+        let bounds = ScissorRect::new(0, 0, self.width as i32, self.height as i32);
+        self.scissor = match scissor {
+            Some(rect) => rect.intersect(&bounds),
+            None => bounds,
+        };
+    }
+
+    /// Current writable area; the rasterizer clamps triangle bounding boxes to it
+    pub fn scissor(&self) -> ScissorRect {
+        self.scissor
+    }
+
+    /// Area written since the last clear
+    pub fn dirty_rect(&self) -> DirtyRect {
+        self.dirty
+    }
+
+    /// Marks the whole screen as written, for passes that touch every pixel
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = DirtyRect::full(self.width, self.height);
     }
 
     pub fn point(&mut self, x: i32, y: i32, color: Vector3, depth: f32) -> bool {
-        if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+        if self.scissor.contains(x, y) {
             let index = (y * self.width as i32 + x) as usize;
 
             // Depth test: only draw if this fragment is closer
             if depth < self.depth_buffer[index] {
                 self.depth_buffer[index] = depth;
+                self.dirty.include(x, y);
+                // Whatever glowed here before is now hidden
+                self.emission_buffer[index] = Vector3::zero();
+
+                self.color_buffer[index] = Vector3::new(
+                    color.x.clamp(0.0, 1.0),
+                    color.y.clamp(0.0, 1.0),
+                    color.z.clamp(0.0, 1.0),
+                );
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Alpha-blends a translucent fragment over the current pixel.
+    /// The depth test is still applied, but the depth buffer is not written so
+    /// that other translucent surfaces behind this one remain visible.
+    pub fn blend_point(&mut self, x: i32, y: i32, color: Vector3, alpha: f32, depth: f32) -> bool {
+        if self.scissor.contains(x, y) {
+            let index = (y * self.width as i32 + x) as usize;
 
-                let pixel_color = Color::new(
-                    (color.x.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.y.clamp(0.0, 1.0) * 255.0) as u8,
-                    (color.z.clamp(0.0, 1.0) * 255.0) as u8,
-                    255,
+            if depth < self.depth_buffer[index] {
+                let alpha = alpha.clamp(0.0, 1.0);
+                let dst = self.color_buffer[index];
+                self.dirty.include(x, y);
+                let blend = |src: f32, dst: f32| -> f32 { src.clamp(0.0, 1.0) * alpha + dst * (1.0 - alpha) };
+                self.color_buffer[index] = Vector3::new(
+                    blend(color.x, dst.x),
+                    blend(color.y, dst.y),
+                    blend(color.z, dst.z),
                 );
-                self.image.draw_pixel(x, y, pixel_color);
                 return true;
             }
         }
         false
     }
 
+    /// Adds light to a pixel (additive blending) without depth testing.
+    /// Used for glows such as the sun lens flare.
+    pub fn add_color(&mut self, x: i32, y: i32, color: Vector3) {
+        if self.scissor.contains(x, y) {
+            let index = (y * self.width as i32 + x) as usize;
+            let dst = self.color_buffer[index];
+            self.dirty.include(x, y);
+            let add = |src: f32, dst: f32| -> f32 { (dst + src.max(0.0)).min(1.0) };
+            self.color_buffer[index] = Vector3::new(
+                add(color.x, dst.x),
+                add(color.y, dst.y),
+                add(color.z, dst.z),
+            );
+        }
+    }
+
+    /// Adds emission for the bloom pass at a pixel just written with `point` or `blend_point`.
+    /// The emission should already be part of the written color; this only makes it glow.
+    pub fn add_emission(&mut self, x: i32, y: i32, emission: Vector3) {
+        if emission.x <= 0.0 && emission.y <= 0.0 && emission.z <= 0.0 {
+            return;
+        }
+        if self.scissor.contains(x, y) {
+            let index = (y * self.width as i32 + x) as usize;
+            let dst = self.emission_buffer[index];
+            self.emissive.include(x, y);
+            self.emission_buffer[index] = Vector3::new(
+                dst.x + emission.x.max(0.0),
+                dst.y + emission.y.max(0.0),
+                dst.z + emission.z.max(0.0),
+            );
+        }
+    }
+
+    /// Copies the colors of `source` with its top-left corner at (x, y), clipped to the scissor,
+    /// in front of everything already drawn (depth -1, the camera plane). Used to composite
+    /// picture-in-picture views rendered into their own framebuffer; whatever glowed underneath
+    /// is hidden, so run it after `bloom_pass`
+    pub fn composite(&mut self, source: &Framebuffer, x: i32, y: i32) {
+        let target = self.scissor.intersect(&ScissorRect::new(x, y, source.width as i32, source.height as i32));
+        if target.is_empty() {
+            return;
+        }
+        for ty in target.y..target.y + target.height {
+            let source_row = ((ty - y) * source.width as i32 - x) as isize;
+            for tx in target.x..target.x + target.width {
+                let index = (ty * self.width as i32 + tx) as usize;
+                self.color_buffer[index] = source.color_buffer[(source_row + tx as isize) as usize];
+                self.depth_buffer[index] = -1.0;
+                self.emission_buffer[index] = Vector3::zero();
+            }
+        }
+        self.dirty = self.dirty.union(&DirtyRect {
+            min_x: target.x,
+            min_y: target.y,
+            max_x: target.x + target.width - 1,
+            max_y: target.y + target.height - 1,
+        });
+    }
+
+    /// Bloom: blurs the emission buffer and adds it on top of the image, scaled by `strength`.
+    /// The blur runs at a quarter of the resolution (two box blurs of `radius` cells in each
+    /// direction, close to a gaussian) and only around the pixels that emitted this frame.
+    pub fn bloom_pass(&mut self, strength: f32, radius: usize) {
+        const SCALE: usize = 4;
+        if self.emissive.is_empty() || strength <= 0.0 {
+            return;
+        }
+
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (low_width, low_height) = (width.div_ceil(SCALE), height.div_ceil(SCALE));
+        // Emissive area in low-resolution cells, grown by the reach of both blurs
+        let reach = radius * 2;
+        let x0 = (self.emissive.min_x as usize / SCALE).saturating_sub(reach);
+        let y0 = (self.emissive.min_y as usize / SCALE).saturating_sub(reach);
+        let x1 = (self.emissive.max_x as usize / SCALE + reach).min(low_width - 1);
+        let y1 = (self.emissive.max_y as usize / SCALE + reach).min(low_height - 1);
+        let (cells_x, cells_y) = (x1 - x0 + 1, y1 - y0 + 1);
+
+        // Downsample: average of each SCALE x SCALE block (into the reused scratch buffer)
+        let mut low = std::mem::take(&mut self.bloom_scratch);
+        low.clear();
+        low.resize(cells_x * cells_y, Vector3::zero());
+        for cy in 0..cells_y {
+            for cx in 0..cells_x {
+                let (px, py) = ((x0 + cx) * SCALE, (y0 + cy) * SCALE);
+                let mut sum = Vector3::zero();
+                for y in py..(py + SCALE).min(height) {
+                    for e in &self.emission_buffer[y * width + px..y * width + (px + SCALE).min(width)] {
+                        sum.x += e.x;
+                        sum.y += e.y;
+                        sum.z += e.z;
+                    }
+                }
+                let inv = 1.0 / (SCALE * SCALE) as f32;
+                low[cy * cells_x + cx] = Vector3::new(sum.x * inv, sum.y * inv, sum.z * inv);
+            }
+        }
+
+        let mut line = std::mem::take(&mut self.line_scratch);
+        for _ in 0..2 {
+            box_blur(&mut low, &mut line, cells_x, cells_y, radius, 1, cells_x);
+            box_blur(&mut low, &mut line, cells_y, cells_x, radius, cells_x, 1);
+        }
+        self.line_scratch = line;
+
+        // Upsample bilinearly and add on top of the image
+        let sample = |cx: usize, cy: usize| low[cy.min(cells_y - 1) * cells_x + cx.min(cells_x - 1)];
+        let (px0, py0) = (x0 * SCALE, y0 * SCALE);
+        let (px1, py1) = (((x1 + 1) * SCALE).min(width) - 1, ((y1 + 1) * SCALE).min(height) - 1);
+        for y in py0..=py1 {
+            let fy = ((y - py0) as f32 + 0.5) / SCALE as f32 - 0.5;
+            let (cy, ty) = (fy.max(0.0).floor() as usize, fy.max(0.0).fract());
+            for x in px0..=px1 {
+                let fx = ((x - px0) as f32 + 0.5) / SCALE as f32 - 0.5;
+                let (cx, tx) = (fx.max(0.0).floor() as usize, fx.max(0.0).fract());
+                let (a, b, c, d) = (sample(cx, cy), sample(cx + 1, cy), sample(cx, cy + 1), sample(cx + 1, cy + 1));
+                let lerp2 = |a: f32, b: f32, c: f32, d: f32| {
+                    (a + (b - a) * tx) * (1.0 - ty) + (c + (d - c) * tx) * ty
+                };
+                let glow = Vector3::new(lerp2(a.x, b.x, c.x, d.x), lerp2(a.y, b.y, c.y, d.y), lerp2(a.z, b.z, c.z, d.z));
+                if glow.x + glow.y + glow.z <= 0.0 {
+                    continue;
+                }
+                let dst = &mut self.color_buffer[y * width + x];
+                *dst = Vector3::new(
+                    (dst.x + glow.x * strength).min(1.0),
+                    (dst.y + glow.y * strength).min(1.0),
+                    (dst.z + glow.z * strength).min(1.0),
+                );
+            }
+        }
+        self.bloom_scratch = low;
+        self.dirty = self.dirty.union(&DirtyRect { min_x: px0 as i32, min_y: py0 as i32, max_x: px1 as i32, max_y: py1 as i32 });
+    }
+
+    /// Copy of the color buffer for a pass that reads the image while rewriting it, in the
+    /// scratch buffer kept between frames; hand it back with `self.source_scratch = source`
+    fn take_source_copy(&mut self) -> Vec<Vector3> {
+        let mut source = std::mem::take(&mut self.source_scratch);
+        source.clear();
+        source.extend_from_slice(&self.color_buffer);
+        source
+    }
+
+    /// Returns the depth stored at a pixel, or infinity outside the framebuffer.
+    /// Geometry depth is the logarithmic depth written by the rasterizer (-1 at the
+    /// camera, 1 at the far plane); use `linearize_depth` to get the view distance.
+    pub fn depth_at(&self, x: i32, y: i32) -> f32 {
+        if x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32 {
+            self.depth_buffer[(y * self.width as i32 + x) as usize]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Read-only view of the whole depth buffer (row-major, `width * height`)
+    pub fn depth_buffer(&self) -> &[f32] {
+        &self.depth_buffer
+    }
+
+    /// Converts a stored depth value back to view-space distance for the given far plane
+    pub fn linearize_depth(depth: f32, far: f32) -> f32 {
+        linearize_logarithmic_depth(depth, far)
+    }
+
+    /// Distance fog: blends geometry toward the background color as its view distance
+    /// goes from `fog_start` to `fog_end`, so far bodies fade out instead of popping
+    /// at the far plane. Pixels without geometry (background, stars) are left untouched.
+    pub fn fog_pass(&mut self, far: f32, fog_start: f32, fog_end: f32) {
+        let fog = self.background_color;
+        let range = (fog_end - fog_start).max(0.0001);
+
+        for (color, &depth) in self.color_buffer.iter_mut().zip(self.depth_buffer.iter()) {
+            if depth > 1.0 {
+                continue;
+            }
+            let distance = Self::linearize_depth(depth, far);
+            let t = ((distance - fog_start) / range).clamp(0.0, 1.0);
+            if t <= 0.0 {
+                continue;
+            }
+            let t = t * t * (3.0 - 2.0 * t); // smoothstep
+            *color = Vector3::new(
+                color.x + (fog.x - color.x) * t,
+                color.y + (fog.y - color.y) * t,
+                color.z + (fog.z - color.z) * t,
+            );
+        }
+    }
+
+    /// Full-screen warp tunnel post-process.
+    /// Each pixel is averaged with samples taken along the line toward the screen
+    /// center (radial streaks), with the red and blue channels sampled at slightly
+    /// different radii for chromatic aberration. `strength` in [0, 1] scales both.
+    pub fn warp_tunnel_pass(&mut self, strength: f32) {
+        let strength = strength.clamp(0.0, 1.0);
+        if strength <= 0.0 {
+            return;
+        }
+
+        const SAMPLES: usize = 8;
+        let streak_length = 0.35 * strength; // Fraction of the distance to the center
+        let aberration = 0.03 * strength;    // Radial offset between color channels
+
+        let width = self.width as i32;
+        let height = self.height as i32;
+        let center_x = self.width as f32 / 2.0;
+        let center_y = self.height as f32 / 2.0;
+        let source = self.take_source_copy();
+        self.mark_all_dirty();
+
+        let sample = |x: f32, y: f32| -> Vector3 {
+            let sx = (x as i32).clamp(0, width - 1);
+            let sy = (y as i32).clamp(0, height - 1);
+            source[(sy * width + sx) as usize]
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                let dx = x as f32 - center_x;
+                let dy = y as f32 - center_y;
+
+                let mut r = 0.0;
+                let mut g = 0.0;
+                let mut b = 0.0;
+                for i in 0..SAMPLES {
+                    // Scale toward the center: 1.0 at the pixel, (1 - streak_length) at the last sample
+                    let scale = 1.0 - streak_length * i as f32 / SAMPLES as f32;
+                    r += sample(center_x + dx * scale * (1.0 + aberration), center_y + dy * scale * (1.0 + aberration)).x;
+                    g += sample(center_x + dx * scale, center_y + dy * scale).y;
+                    b += sample(center_x + dx * scale * (1.0 - aberration), center_y + dy * scale * (1.0 - aberration)).z;
+                }
+
+                let inv = 1.0 / SAMPLES as f32;
+                self.color_buffer[(y * width + x) as usize] = Vector3::new(r * inv, g * inv, b * inv);
+            }
+        }
+        self.source_scratch = source;
+    }
+
+    /// Full-screen interference (radio static) post-process.
+    /// Rows are randomly displaced sideways and every pixel is mixed with white noise;
+    /// `strength` in [0, 1] scales both and `seed` changes the pattern (pass the frame time).
+    pub fn static_pass(&mut self, strength: f32, seed: f32) {
+        let strength = strength.clamp(0.0, 1.0);
+        if strength <= 0.0 {
+            return;
+        }
+
+        let width = self.width as i32;
+        let height = self.height as i32;
+        let frame = (seed * 60.0) as u32;
+        let source = self.take_source_copy();
+        self.mark_all_dirty();
+
+        // Integer hash in [0, 1), different for every (a, b, frame)
+        let hash = |a: u32, b: u32| -> f32 {
+            let mut h = a.wrapping_mul(374761393) ^ b.wrapping_mul(668265263) ^ frame.wrapping_mul(2246822519);
+            h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+            (h ^ (h >> 16)) as f32 / u32::MAX as f32
+        };
+
+        let max_shift = 24.0 * strength;
+        let noise_amount = 0.4 * strength;
+        for y in 0..height {
+            // A few rows tear sideways; more of them as the strength grows
+            let shift = if hash(y as u32, 0) < 0.15 * strength {
+                ((hash(y as u32, 1) * 2.0 - 1.0) * max_shift) as i32
+            } else {
+                0
+            };
+            for x in 0..width {
+                let sx = (x + shift).clamp(0, width - 1);
+                let color = source[(y * width + sx) as usize];
+                let noise = hash(x as u32, y as u32 + 2);
+                self.color_buffer[(y * width + x) as usize] = Vector3::new(
+                    color.x + (noise - color.x) * noise_amount,
+                    color.y + (noise - color.y) * noise_amount,
+                    color.z + (noise - color.z) * noise_amount,
+                );
+            }
+        }
+        self.source_scratch = source;
+    }
+
+    /// Gravitational lensing post-process around a point mass at `center` (pixels).
+    /// Each pixel within `reach` shows what lies along the bent ray: the image is sampled on the
+    /// same radial line at `r - einstein_radius² / r`, with the deflection faded out toward `reach`.
+    /// Rays that pass the center come out on the opposite side (the mirrored inner image) and the
+    /// disk inside `shadow_radius` goes black. Pixels closer than `front_depth` (geometry in front
+    /// of the lens) are left untouched, so this must run after the opaque geometry and skybox.
+    pub fn lensing_pass(&mut self, center: Vector2, einstein_radius: f32, shadow_radius: f32, reach: f32, front_depth: f32) {
+        if einstein_radius <= 0.0 || reach <= 0.0 {
+            return;
+        }
+
+        let width = self.width as i32;
+        let height = self.height as i32;
+        let area = DirtyRect {
+            min_x: ((center.x - reach).floor() as i32).max(0),
+            min_y: ((center.y - reach).floor() as i32).max(0),
+            max_x: ((center.x + reach).ceil() as i32).min(width - 1),
+            max_y: ((center.y + reach).ceil() as i32).min(height - 1),
+        };
+        if area.is_empty() {
+            return;
+        }
+
+        let source = self.take_source_copy();
+        self.dirty = self.dirty.union(&area);
+
+        let einstein_sq = einstein_radius * einstein_radius;
+        for y in area.min_y..=area.max_y {
+            for x in area.min_x..=area.max_x {
+                let index = (y * width + x) as usize;
+                if self.depth_buffer[index] < front_depth {
+                    continue;
+                }
+
+                let dx = x as f32 + 0.5 - center.x;
+                let dy = y as f32 + 0.5 - center.y;
+                let r = (dx * dx + dy * dy).sqrt();
+                if r >= reach {
+                    continue;
+                }
+                if r <= shadow_radius {
+                    self.color_buffer[index] = Vector3::zero();
+                    continue;
+                }
+
+                // Thin-lens deflection, smoothly reduced to zero at the edge of the lens
+                let t = r / reach;
+                let falloff = (1.0 - t * t) * (1.0 - t * t);
+                let scale = (r - einstein_sq / r * falloff) / r;
+                let sx = ((center.x + dx * scale) as i32).clamp(0, width - 1);
+                let sy = ((center.y + dy * scale) as i32).clamp(0, height - 1);
+                self.color_buffer[index] = source[(sy * width + sx) as usize];
+            }
+        }
+        self.source_scratch = source;
+    }
+
+    /// Light shafts (crepuscular rays) from a light at `center` (pixels) with an on-screen radius
+    /// of `radius`. Pixels of the light's disk that nothing closer than `front_depth` covers are
+    /// the sources; every pixel then gathers `samples` of them along the line toward the light,
+    /// each step weaker by `decay`, and adds `color` scaled by the result. Bodies
+    /// in front of the light block the sources behind them and cast dark shafts across the glow.
+    /// Runs at a quarter of the resolution, like `bloom_pass`, so run it after the opaque geometry.
+    pub fn god_rays_pass(&mut self, center: Vector2, radius: f32, front_depth: f32, color: Vector3, samples: usize, decay: f32) {
+        const SCALE: usize = 4;
+        if color.x + color.y + color.z <= 0.0 || radius <= 0.0 || samples == 0 {
+            return;
+        }
+
+        // Sources: visible pixels of the light's disk, brighter toward its center
+        let (width, height) = (self.width as usize, self.height as usize);
+        let (low_width, low_height) = (width.div_ceil(SCALE), height.div_ceil(SCALE));
+        let (low_x, low_y, low_radius) = (center.x / SCALE as f32, center.y / SCALE as f32, radius / SCALE as f32);
+        let mut sources = std::mem::take(&mut self.ray_sources);
+        sources.clear();
+        sources.resize(low_width * low_height, 0.0);
+        let mut any = false;
+        for cy in 0..low_height {
+            for cx in 0..low_width {
+                let d = ((cx as f32 + 0.5 - low_x).powi(2) + (cy as f32 + 0.5 - low_y).powi(2)).sqrt() / low_radius;
+                if d >= 1.0 {
+                    continue;
+                }
+                let (x, y) = ((cx * SCALE + SCALE / 2).min(width - 1), (cy * SCALE + SCALE / 2).min(height - 1));
+                if self.depth_buffer[y * width + x] >= front_depth {
+                    sources[cy * low_width + cx] = 1.0 - d * d;
+                    any = true;
+                }
+            }
+        }
+        if !any {
+            self.ray_sources = sources;
+            return;
+        }
+
+        // Gather along the line toward the light; the march covers the whole way to its center
+        let mut rays = std::mem::take(&mut self.rays);
+        rays.clear();
+        rays.resize(low_width * low_height, 0.0);
+        for cy in 0..low_height {
+            for cx in 0..low_width {
+                let (px, py) = (cx as f32 + 0.5, cy as f32 + 0.5);
+                let (step_x, step_y) = ((low_x - px) / samples as f32, (low_y - py) / samples as f32);
+                let (mut sum, mut weight) = (0.0, 1.0);
+                for k in 0..samples {
+                    let sx = px + step_x * k as f32;
+                    let sy = py + step_y * k as f32;
+                    if sx >= 0.0 && sy >= 0.0 && (sx as usize) < low_width && (sy as usize) < low_height {
+                        sum += sources[sy as usize * low_width + sx as usize] * weight;
+                    }
+                    weight *= decay;
+                }
+                rays[cy * low_width + cx] = sum / samples as f32;
+            }
+        }
+
+        // Upsample bilinearly and add on top of the image
+        let sample = |cx: usize, cy: usize| rays[cy.min(low_height - 1) * low_width + cx.min(low_width - 1)];
+        for y in 0..height {
+            let fy = (y as f32 + 0.5) / SCALE as f32 - 0.5;
+            let (cy, ty) = (fy.max(0.0).floor() as usize, fy.max(0.0).fract());
+            for x in 0..width {
+                let fx = (x as f32 + 0.5) / SCALE as f32 - 0.5;
+                let (cx, tx) = (fx.max(0.0).floor() as usize, fx.max(0.0).fract());
+                let top = sample(cx, cy) + (sample(cx + 1, cy) - sample(cx, cy)) * tx;
+                let bottom = sample(cx, cy + 1) + (sample(cx + 1, cy + 1) - sample(cx, cy + 1)) * tx;
+                let ray = top + (bottom - top) * ty;
+                if ray <= 0.0 {
+                    continue;
+                }
+                let dst = &mut self.color_buffer[y * width + x];
+                *dst = Vector3::new((dst.x + color.x * ray).min(1.0), (dst.y + color.y * ray).min(1.0), (dst.z + color.z * ray).min(1.0));
+            }
+        }
+        self.ray_sources = sources;
+        self.rays = rays;
+        self.mark_all_dirty();
+    }
+
+    /// Background color in linear space
+    /// A new color invalidates every pixel that still shows the old one
     pub fn set_background_color(&mut self, color: Vector3) {
+        if color != self.background_color {
+            self.mark_all_dirty();
+        }
         self.background_color = color;
     }
 
-    pub fn update_texture(&mut self) {
-        if let Some(texture) = &mut self.texture {
-            let colors = self.image.get_image_data();
-            // Safely cast the &[Color] slice to a &[u8] slice for the update function
-            let data: &[u8] = unsafe {
-                std::slice::from_raw_parts(
-                    colors.as_ptr() as *const u8,
-                    colors.len() * 4, // Each Color is 4 bytes (r,g,b,a)
-                )
-            };
-            texture.update_texture(data).unwrap();
-        } else {
-            panic!("Framebuffer texture has not been initialized. Call init_texture after creating the RaylibHandle.");
+    /// Display gamma used to encode the linear buffer (2.2 approximates sRGB; 1.0 disables encoding)
+    /// Re-encodes the whole screen on the next presentation
+    pub fn set_gamma(&mut self, gamma: f32) {
+        // A new table rather than an update: encoders handed out by `pixel_encoder` keep theirs
+        self.encoder.gamma_lut = Arc::new(GammaLut::new(gamma));
+        self.mark_all_dirty();
+    }
+
+    pub fn gamma(&self) -> f32 {
+        self.encoder.gamma_lut.gamma()
+    }
+
+    /// Enables or disables ordered dithering of the 8-bit output
+    /// Re-encodes the whole screen on the next presentation
+    pub fn set_dithering(&mut self, dithering: bool) {
+        self.encoder.dithering = dithering;
+        self.mark_all_dirty();
+    }
+
+    pub fn dithering(&self) -> bool {
+        self.encoder.dithering
+    }
+
+    /// Exposure multiplier applied to the linear buffer when it is encoded (1.0 leaves it as is)
+    /// A new value re-encodes the whole screen on the next presentation
+    pub fn set_exposure(&mut self, exposure: f32) {
+        let exposure = exposure.max(0.0);
+        if exposure != self.encoder.exposure {
+            self.mark_all_dirty();
         }
+        self.encoder.exposure = exposure;
+    }
+
+    pub fn exposure(&self) -> f32 {
+        self.encoder.exposure
+    }
+
+    /// Copy of the current exposure, gamma and dithering, to encode pixels copied out with
+    /// `copy_region` somewhere else (e.g. on another thread while the next frame is drawn)
+    pub fn pixel_encoder(&self) -> PixelEncoder {
+        self.encoder.clone()
+    }
+
+    fn encode_pixel(&self, color: Vector3, x: usize, y: usize) -> Color {
+        self.encoder.encode_pixel(color, x, y)
     }
 
-    pub fn draw_to(&self, d: &mut RaylibDrawHandle) {
-        if let Some(texture) = &self.texture {
-            d.draw_texture(texture, 0, 0, Color::WHITE);
+    /// Area that changed since the previous call: what was drawn this frame plus what the last
+    /// clear reset. Presenting only this region keeps the screen in sync with the buffer
+    pub fn take_changed_region(&mut self) -> DirtyRect {
+        let region = self.dirty.union(&self.cleared);
+        self.cleared = DirtyRect::empty();
+        region
+    }
+
+    /// Encodes `region` (exposure, gamma and dithering) into `out` as tightly packed 8-bit RGBA,
+    /// row by row from the top. Returns the number of pixels written; `out` must hold at least
+    /// four bytes per pixel of the region
+    pub fn encode_region(&self, region: &DirtyRect, out: &mut [u8]) -> usize {
+        if region.is_empty() {
+            return 0;
+        }
+        let width = self.width as usize;
+        let (x0, x1) = (region.min_x as usize, region.max_x as usize);
+        let mut offset = 0;
+        for y in region.min_y as usize..=region.max_y as usize {
+            for x in x0..=x1 {
+                let encoded = self.encode_pixel(self.color_buffer[y * width + x], x, y);
+                out[offset..offset + 4].copy_from_slice(&[encoded.r, encoded.g, encoded.b, encoded.a]);
+                offset += 4;
+            }
+        }
+        offset / 4
+    }
+
+    /// Copies the linear colors of `region` into `out` (cleared first), row by row from the top,
+    /// in the layout `PixelEncoder::encode_region` expects
+    pub fn copy_region(&self, region: &DirtyRect, out: &mut Vec<Vector3>) {
+        out.clear();
+        if region.is_empty() {
+            return;
+        }
+        let width = self.width as usize;
+        let (x0, x1) = (region.min_x as usize, region.max_x as usize);
+        for y in region.min_y as usize..=region.max_y as usize {
+            out.extend_from_slice(&self.color_buffer[y * width + x0..=y * width + x1]);
+        }
+    }
+
+    /// Encodes the whole color buffer like `encode_region` (exposure, gamma and dithering) into
+    /// tightly packed 8-bit RGB, row by row from the top: the rendered image, read back without
+    /// a window
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        let width = self.width as usize;
+        let mut pixels = Vec::with_capacity(self.color_buffer.len() * 3);
+        for (index, color) in self.color_buffer.iter().enumerate() {
+            let encoded = self.encode_pixel(*color, index % width, index / width);
+            pixels.extend_from_slice(&[encoded.r, encoded.g, encoded.b]);
+        }
+        pixels
+    }
+
+    /// Encodes the whole color buffer like `to_rgb8` and writes it to an image file
+    /// (the format follows the extension, e.g. `.png`), creating the parent directory if needed
+    pub fn export(&self, path: &str) -> io::Result<()> {
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut image = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+        let width = self.width as usize;
+        for (index, color) in self.color_buffer.iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            image.draw_pixel(x as i32, y as i32, self.encode_pixel(*color, x, y));
+        }
+        // raylib only logs a warning when the export fails (the safe wrapper drops ExportImage's
+        // bool), so remove any previous file first: afterwards the file exists only if this write worked
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+        image.export_image(path);
+        if Path::new(path).exists() {
+            Ok(())
         } else {
-            panic!("Framebuffer texture has not been initialized. Call init_texture after creating the RaylibHandle.");
+            Err(io::Error::other(format!("raylib could not write {}", path)))
         }
     }
-}
\ No newline at end of file
+}
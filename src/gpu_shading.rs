@@ -0,0 +1,321 @@
+use raylib::prelude::*;
+use crate::fragment::Fragment;
+use crate::shaders::{shader_quality, PlanetType};
+use crate::uniforms::Uniforms;
+use bytemuck::{Pod, Zeroable};
+use wgpu::util::DeviceExt;
+
+/// Tormentas que caben en el uniform del shader; un gigante con más se sombrea en la CPU
+const MAX_STORMS: usize = 8;
+/// Fragmentos por despacho: 65535 grupos de 64 es el máximo que garantiza wgpu en una dimensión
+const MAX_BATCH: usize = 65535 * 64;
+const WORKGROUP_SIZE: usize = 64;
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GpuStorm {
+    latitude: f32,
+    longitude: f32,
+    radius: f32,
+    spin: f32,
+    color: [f32; 3],
+    _padding: f32,
+}
+
+/// `Params` de gpu_shading.wgsl, con el mismo relleno que exige WGSL para los vec3
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GpuParams {
+    sun_direction: [f32; 3],
+    time: f32,
+    fill_direction: [f32; 3],
+    planet_type: u32,
+    fill_color: [f32; 3],
+    count: u32,
+    octave_reduction: i32,
+    ambient_occlusion: u32,
+    storm_count: u32,
+    _padding: u32,
+    storms: [GpuStorm; MAX_STORMS],
+}
+
+/// Entrada de cada fragmento: lo único que leen los shaders de los planetas
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct GpuFragment {
+    object_position: [f32; 3],
+    _padding0: f32,
+    color: [f32; 3],
+    _padding1: f32,
+}
+
+/// Búferes de un tamaño dado; se vuelven a crear cuando llega un lote más grande
+struct GpuBuffers {
+    capacity: usize,
+    fragments: wgpu::Buffer,
+    colors: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+}
+
+/// Fragment stage de los planetas en la GPU (característica `gpu`, opción `--gpu`)
+///
+/// El rasterizador de la CPU sigue generando los fragmentos; `Renderer` reúne los de cada
+/// malla de planeta o luna, y aquí se sube su posición en espacio de objeto y su color
+/// iluminado, un compute shader (gpu_shading.wgsl, la traducción a WGSL de los shaders
+/// procedurales) calcula el color base de todos a la vez y se leen de vuelta. Lo que depende de
+/// datos por planeta de tamaño variable (cráteres, atmósfera, anillos) lo termina la CPU con
+/// `SurfaceShader::finish_gpu`. Cada malla es un viaje de ida y vuelta síncrono: la GPU gana
+/// cuando un planeta llena buena parte de la pantalla, que es cuando la CPU no da abasto
+pub struct GpuShading {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    layout: wgpu::BindGroupLayout,
+    params: wgpu::Buffer,
+    buffers: Option<GpuBuffers>,
+    inputs: Vec<GpuFragment>, // Lote en curso, reutilizado entre mallas
+    adapter_name: String,
+    lost: bool,               // La GPU falló: todo vuelve a la CPU
+}
+
+impl GpuShading {
+    /// Abre el adaptador de mayor rendimiento disponible y compila el shader
+    pub fn new() -> Result<Self, String> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            compatible_surface: None,
+            force_fallback_adapter: false,
+        }))
+        .ok_or_else(|| String::from("no hay ningún adaptador de GPU compatible con wgpu"))?;
+        let adapter_name = adapter.get_info().name;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &wgpu::DeviceDescriptor {
+                label: Some("fragment stage"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::downlevel_defaults().using_resolution(adapter.limits()),
+                memory_hints: wgpu::MemoryHints::Performance,
+            },
+            None,
+        ))
+        .map_err(|e| format!("no se pudo abrir {}: {}", adapter_name, e))?;
+
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("gpu_shading.wgsl"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("gpu_shading.wgsl").into()),
+        });
+        let storage = |binding: u32, read_only: bool| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("fragment stage"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("fragment stage"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("fragment stage"),
+            layout: Some(&pipeline_layout),
+            module: &module,
+            entry_point: Some("shade_fragments"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("fragment stage params"),
+            contents: bytemuck::bytes_of(&GpuParams::zeroed()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Ok(GpuShading {
+            device,
+            queue,
+            pipeline,
+            layout,
+            params,
+            buffers: None,
+            inputs: Vec::new(),
+            adapter_name,
+            lost: false,
+        })
+    }
+
+    /// Nombre del adaptador, para el mensaje de arranque
+    pub fn adapter_name(&self) -> &str {
+        &self.adapter_name
+    }
+
+    /// Si el color base de `planet_type` con estos `uniforms` se puede calcular en la GPU
+    /// Los anillos, el sol y la nave tienen sus propios caminos y se quedan en la CPU
+    pub fn supports(&self, planet_type: PlanetType, uniforms: &Uniforms) -> bool {
+        !self.lost
+            && uniforms.storms.len() <= MAX_STORMS
+            && gpu_planet_id(planet_type).is_some()
+    }
+
+    /// Calcula en la GPU el color base de `planet_type` para cada fragmento de `fragments`
+    /// (lo mismo que `planet_surface_color` en la CPU) y lo deja en `colors`, en el mismo orden
+    pub fn shade_surface(&mut self, planet_type: PlanetType, uniforms: &Uniforms, fragments: &[Fragment], colors: &mut Vec<Vector3>) -> Result<(), String> {
+        let planet_id = gpu_planet_id(planet_type).ok_or_else(|| format!("{:?} no tiene shader WGSL", planet_type))?;
+        colors.clear();
+        for batch in fragments.chunks(MAX_BATCH) {
+            if let Err(e) = self.shade_batch(planet_id, uniforms, batch, colors) {
+                self.lost = true;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn shade_batch(&mut self, planet_id: u32, uniforms: &Uniforms, fragments: &[Fragment], colors: &mut Vec<Vector3>) -> Result<(), String> {
+        if fragments.is_empty() {
+            return Ok(());
+        }
+        self.reserve(fragments.len());
+        let Some(buffers) = &self.buffers else {
+            return Ok(());
+        };
+
+        let mut params = GpuParams::zeroed();
+        params.sun_direction = to_array(uniforms.sun_direction);
+        params.time = uniforms.time;
+        if let Some(fill) = &uniforms.fill_light {
+            params.fill_direction = to_array(fill.direction);
+            params.fill_color = to_array(fill.color);
+        }
+        params.planet_type = planet_id;
+        params.count = fragments.len() as u32;
+        let quality = shader_quality();
+        params.octave_reduction = quality.octave_reduction();
+        params.ambient_occlusion = quality.ambient_occlusion() as u32;
+        params.storm_count = uniforms.storms.len().min(MAX_STORMS) as u32;
+        for (slot, storm) in params.storms.iter_mut().zip(&uniforms.storms) {
+            *slot = GpuStorm {
+                latitude: storm.latitude,
+                longitude: storm.longitude,
+                radius: storm.radius,
+                spin: storm.spin,
+                color: to_array(storm.color),
+                _padding: 0.0,
+            };
+        }
+
+        self.inputs.clear();
+        self.inputs.extend(fragments.iter().map(|fragment| GpuFragment {
+            object_position: to_array(fragment.object_position),
+            _padding0: 0.0,
+            color: to_array(fragment.color),
+            _padding1: 0.0,
+        }));
+        self.queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&params));
+        self.queue.write_buffer(&buffers.fragments, 0, bytemuck::cast_slice(&self.inputs));
+
+        let output_size = (fragments.len() * std::mem::size_of::<[f32; 4]>()) as u64;
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("fragment stage") });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor { label: Some("fragment stage"), timestamp_writes: None });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &buffers.bind_group, &[]);
+            pass.dispatch_workgroups(fragments.len().div_ceil(WORKGROUP_SIZE) as u32, 1, 1);
+        }
+        encoder.copy_buffer_to_buffer(&buffers.colors, 0, &buffers.readback, 0, output_size);
+        self.queue.submit(Some(encoder.finish()));
+
+        // Lectura síncrona: el renderer necesita los colores para escribir el framebuffer
+        let slice = buffers.readback.slice(..output_size);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver
+            .recv()
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("no se pudieron leer los colores de la GPU: {}", e))?;
+        {
+            let data = slice.get_mapped_range();
+            let shaded: &[[f32; 4]] = bytemuck::cast_slice(&data);
+            colors.extend(shaded.iter().map(|color| Vector3::new(color[0], color[1], color[2])));
+        }
+        buffers.readback.unmap();
+        Ok(())
+    }
+
+    /// Asegura búferes para al menos `count` fragmentos (crecen a la siguiente potencia de dos)
+    fn reserve(&mut self, count: usize) {
+        if self.buffers.as_ref().is_some_and(|buffers| buffers.capacity >= count) {
+            return;
+        }
+        let capacity = count.next_power_of_two().min(MAX_BATCH);
+        let fragments = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fragment stage inputs"),
+            size: (capacity * std::mem::size_of::<GpuFragment>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let color_size = (capacity * std::mem::size_of::<[f32; 4]>()) as u64;
+        let colors = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fragment stage colors"),
+            size: color_size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("fragment stage readback"),
+            size: color_size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("fragment stage"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry { binding: 0, resource: self.params.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 1, resource: fragments.as_entire_binding() },
+                wgpu::BindGroupEntry { binding: 2, resource: colors.as_entire_binding() },
+            ],
+        });
+        self.buffers = Some(GpuBuffers { capacity, fragments, colors, readback, bind_group });
+    }
+}
+
+/// Identificador de cada shader en gpu_shading.wgsl (las constantes ROCKY, GAS_GIANT, ...)
+fn gpu_planet_id(planet_type: PlanetType) -> Option<u32> {
+    match planet_type {
+        PlanetType::Rocky => Some(0),
+        PlanetType::GasGiant => Some(1),
+        PlanetType::SciFi => Some(2),
+        PlanetType::Ice => Some(3),
+        PlanetType::Volcanic => Some(4),
+        PlanetType::Moon => Some(5),
+        PlanetType::Ring | PlanetType::Sun | PlanetType::Ship => None,
+    }
+}
+
+fn to_array(vector: Vector3) -> [f32; 3] {
+    [vector.x, vector.y, vector.z]
+}
@@ -0,0 +1,458 @@
+// Shaders procedurales de los planetas en WGSL, para `GpuShading` (característica `gpu`)
+// Es una traducción línea a línea de `shader_rocky_planet`, `shader_gas_giant`,
+// `shader_scifi_planet`, `shader_ice_planet`, `shader_volcanic_planet` y `shader_moon` de
+// shaders.rs: cualquier cambio en esas funciones tiene que repetirse aquí. Cada invocación
+// calcula el color base de un fragmento; cráteres, atmósfera, sombras de los anillos, brillo
+// especular, emisión y transparencia se siguen calculando en la CPU
+
+const PI: f32 = 3.14159265358979;
+const TAU: f32 = 6.28318530717959;
+const MAX_STORMS: u32 = 8u;
+
+const ROCKY: u32 = 0u;
+const GAS_GIANT: u32 = 1u;
+const SCIFI: u32 = 2u;
+const ICE: u32 = 3u;
+const VOLCANIC: u32 = 4u;
+const MOON: u32 = 5u;
+
+struct Storm {
+    latitude: f32,
+    longitude: f32,
+    radius: f32,
+    spin: f32,
+    color: vec3<f32>,
+    _padding: f32,
+}
+
+struct Params {
+    sun_direction: vec3<f32>,  // Dirección al sol en espacio de objeto
+    time: f32,
+    fill_direction: vec3<f32>, // Luz reflejada por el planeta padre (lunas)
+    planet_type: u32,
+    fill_color: vec3<f32>,     // Cero si no hay luz de relleno
+    count: u32,                // Fragmentos de este lote
+    octave_reduction: i32,     // `ShaderQuality::octave_reduction`
+    ambient_occlusion: u32,    // `ShaderQuality::ambient_occlusion`
+    storm_count: u32,
+    _padding: u32,
+    storms: array<Storm, MAX_STORMS>,
+}
+
+struct Fragment {
+    object_position: vec3<f32>,
+    color: vec3<f32>,          // Color iluminado por el rasterizador
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> fragments: array<Fragment>;
+@group(0) @binding(2) var<storage, read_write> colors: array<vec4<f32>>;
+
+fn noise_3d(pos: vec3<f32>, time: f32) -> f32 {
+    let scale = 4.0;
+    let n1 = sin(pos.x * scale + time * 0.1) * 0.5 + 0.5;
+    let n2 = sin(pos.y * scale * 1.3 + time * 0.15) * 0.5 + 0.5;
+    let n3 = sin(pos.z * scale * 0.7 + time * 0.12) * 0.5 + 0.5;
+    return (n1 + n2 + n3) / 3.0;
+}
+
+fn fractal_noise(pos: vec3<f32>, time: f32, octaves: i32) -> f32 {
+    // Siempre queda al menos una octava
+    let count = max(octaves - params.octave_reduction, 1);
+    var value = 0.0;
+    var amplitude = 0.5;
+    var frequency = 1.0;
+    for (var i = 0; i < count; i++) {
+        value += noise_3d(pos * frequency, time) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    return value;
+}
+
+fn height_occlusion(height: f32, strength: f32) -> f32 {
+    if (params.ambient_occlusion == 0u) {
+        return 1.0;
+    }
+    let depth = clamp((0.5 - height) / 0.4, 0.0, 1.0);
+    return 1.0 - strength * depth * depth * (3.0 - 2.0 * depth);
+}
+
+// (radio, latitud, longitud), como `spherical_coords`
+fn spherical_coords(pos: vec3<f32>) -> vec3<f32> {
+    let r = length(pos);
+    let r_xy = sqrt(pos.x * pos.x + pos.z * pos.z);
+    var theta = 0.0;
+    if (r_xy > 0.0001 || abs(pos.y) > 0.0001) {
+        theta = atan2(pos.y, r_xy);
+    }
+    var phi = 0.0;
+    if (abs(pos.x) > 0.0001 || abs(pos.z) > 0.0001) {
+        phi = atan2(pos.z, pos.x);
+    }
+    return vec3<f32>(max(r, 0.0001), theta, phi);
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    let value = clamp(c, 0.0, 1.0);
+    if (value <= 0.04045) {
+        return value / 12.92;
+    }
+    return pow((value + 0.055) / 1.055, 2.4);
+}
+
+fn srgb_to_linear_rgb(color: vec3<f32>) -> vec3<f32> {
+    return vec3<f32>(srgb_to_linear(color.x), srgb_to_linear(color.y), srgb_to_linear(color.z));
+}
+
+fn polar_caps(color: vec3<f32>, latitude: f32, sun_direction: vec3<f32>, terrain: f32) -> vec3<f32> {
+    let declination = asin(clamp(sun_direction.y, -1.0, 1.0));
+    var edge = 1.15 - declination * 0.8;
+    if (latitude >= 0.0) {
+        edge = 1.15 + declination * 0.8;
+    }
+    edge += (terrain - 0.5) * 0.2;
+    let ice = clamp((abs(latitude) - edge) / 0.06 + 0.5, 0.0, 1.0);
+    if (ice <= 0.0) {
+        return color;
+    }
+    return mix(color, srgb_to_linear_rgb(vec3<f32>(0.9, 0.94, 1.0)), ice);
+}
+
+fn shader_rocky_planet(object_pos: vec3<f32>, base_color: vec3<f32>, time: f32, sun_direction: vec3<f32>) -> vec3<f32> {
+    let coords = spherical_coords(object_pos);
+    let r = coords.x;
+    let theta = coords.y;
+
+    // CAPA 1: ruido fractal para el terreno base, sobre la esfera unitaria (rocky_terrain_noise)
+    let unit_pos = object_pos / r;
+    let noise1 = fractal_noise(unit_pos, time * 0.1, 4);
+    let noise2 = fractal_noise(vec3<f32>(unit_pos.x * 0.5, unit_pos.y * 2.0, unit_pos.z * 0.5), time * 0.05, 3);
+    let terrain_noise = noise1 * 0.7 + noise2 * 0.3;
+
+    // CAPA 2: gradientes de altitud
+    let altitude_gradient = sin(theta * 2.0) * 0.5 + 0.5;
+    let altitude_variation = terrain_noise * 0.3 + altitude_gradient * 0.7;
+
+    // CAPA 3: terminador (día/noche)
+    let normal = object_pos / r;
+    let sun_dot = max(dot(normal, sun_direction), 0.0);
+    let terminator = clamp(sun_dot * 3.0 - 1.5, 0.0, 1.0);
+    let day_night = sun_dot * 0.7 + 0.3;
+
+    // CAPA 4: erosión y valles
+    let erosion = fractal_noise(object_pos * 3.0, time * 0.02, 2);
+
+    let rock_color1 = vec3<f32>(0.4, 0.3, 0.2);
+    let rock_color2 = vec3<f32>(0.5, 0.4, 0.3);
+    let rock_color3 = vec3<f32>(0.6, 0.5, 0.4);
+    let rock_color4 = vec3<f32>(0.35, 0.35, 0.3);
+    let rock_color5 = vec3<f32>(0.7, 0.6, 0.5);
+
+    let color_mix = altitude_variation * 0.6 + terrain_noise * 0.4;
+    var planet_color: vec3<f32>;
+    if (color_mix < 0.25) {
+        planet_color = mix(rock_color1, rock_color2, color_mix / 0.25);
+    } else if (color_mix < 0.5) {
+        planet_color = mix(rock_color2, rock_color3, (color_mix - 0.25) / 0.25);
+    } else if (color_mix < 0.75) {
+        planet_color = mix(rock_color3, rock_color4, (color_mix - 0.5) / 0.25);
+    } else {
+        planet_color = mix(rock_color4, rock_color5, (color_mix - 0.75) / 0.25);
+    }
+
+    let eroded_color = polar_caps(srgb_to_linear_rgb(planet_color) * (1.0 - erosion * 0.2), theta, sun_direction, terrain_noise);
+    let occlusion = height_occlusion(terrain_noise * 0.7 + (1.0 - erosion) * 0.3, 0.45);
+    let final_color = eroded_color * day_night * terminator * occlusion;
+    return min(final_color * 0.8 + base_color * 0.2, vec3<f32>(1.0));
+}
+
+fn storm_vortices(color_in: vec3<f32>, latitude: f32, longitude: f32, time: f32) -> vec3<f32> {
+    var color = color_in;
+    for (var i = 0u; i < min(params.storm_count, MAX_STORMS); i++) {
+        let storm = params.storms[i];
+        let wrapped = longitude - storm.longitude + PI;
+        let delta_longitude = wrapped - TAU * floor(wrapped / TAU) - PI;
+        let x = delta_longitude * cos(storm.latitude) / max(storm.radius * 2.0, 0.0001);
+        let y = (latitude - storm.latitude) / max(storm.radius, 0.0001);
+        let d = sqrt(x * x + y * y);
+        if (d >= 1.3) {
+            continue;
+        }
+
+        // Rotación diferencial: el ángulo avanza más deprisa en el interior
+        let angle = atan2(y, x) - storm.spin * time * (1.5 - d);
+        let arms = sin(angle * 2.0 + d * 9.0) * 0.5 + 0.5;
+        let shade = 0.75 + 0.35 * arms;
+        let edge = clamp(1.0 - d, 0.0, 1.0);
+        let core = edge * edge * (3.0 - 2.0 * edge);
+        color = mix(color, storm.color * shade, core);
+
+        // Collar de nubes claras justo fuera del óvalo
+        let collar = clamp(1.0 - abs((d - 1.05) / 0.2), 0.0, 1.0) * 0.25;
+        color = mix(color, vec3<f32>(0.95, 0.9, 0.8), collar);
+    }
+    return color;
+}
+
+fn shader_gas_giant(object_pos: vec3<f32>, base_color: vec3<f32>, time: f32, sun_direction: vec3<f32>) -> vec3<f32> {
+    let coords = spherical_coords(object_pos);
+    let r = coords.x;
+    let theta = coords.y;
+    let phi = coords.z;
+
+    // CAPA 1: bandas de latitud
+    let band_frequency = 8.0;
+    let band_value = sin(theta * band_frequency + time * 0.3) * 0.5 + 0.5;
+    let band_gradient = sin(theta * band_frequency * 2.0 + time * 0.3) * 0.3 + 0.7;
+
+    // CAPA 2: ondas de gas turbulentas
+    let wave1 = sin(theta * 12.0 + phi * 6.0 + time * 0.5) * 0.3 + 0.7;
+    let wave2 = cos(theta * 8.0 - phi * 4.0 + time * 0.7) * 0.2 + 0.8;
+    let wave3 = sin(theta * 15.0 + phi * 10.0 + time * 0.6) * 0.15 + 0.85;
+    let turbulence = wave1 * wave2 * wave3;
+
+    // CAPA 3: iluminación con gradiente de profundidad
+    let depth_factor = (1.0 - abs(theta) / (PI * 2.0)) * 0.5 + 0.5;
+    let normal = object_pos / r;
+    let sun_dot = max(dot(normal, sun_direction), 0.0) * 0.8 + 0.2;
+    let atmospheric_light = depth_factor * sun_dot * 0.8 + 0.2;
+
+    // CAPA 4: remolinos y vórtices
+    let swirl = sin(phi * 3.0 + theta * 2.0 + time * 0.4) * 0.1 + 1.0;
+    let vortex = cos(phi * 5.0 + theta * 3.0 + time * 0.8) * 0.15 + 0.85;
+    let vortex_effect = swirl * vortex;
+
+    let gas_color1 = vec3<f32>(0.8, 0.5, 0.2);
+    let gas_color2 = vec3<f32>(0.9, 0.7, 0.3);
+    let gas_color3 = vec3<f32>(0.7, 0.4, 0.15);
+    let gas_color4 = vec3<f32>(0.6, 0.3, 0.1);
+    let gas_color5 = vec3<f32>(0.95, 0.8, 0.4);
+
+    let color_factor = band_value * band_gradient * turbulence;
+    var planet_color: vec3<f32>;
+    if (color_factor < 0.2) {
+        planet_color = mix(gas_color4, gas_color3, color_factor / 0.2);
+    } else if (color_factor < 0.4) {
+        planet_color = mix(gas_color3, gas_color1, (color_factor - 0.2) / 0.2);
+    } else if (color_factor < 0.7) {
+        planet_color = mix(gas_color1, gas_color2, (color_factor - 0.4) / 0.3);
+    } else {
+        planet_color = mix(gas_color2, gas_color5, (color_factor - 0.7) / 0.3);
+    }
+
+    // CAPA 5: tormentas persistentes
+    if (params.storm_count > 0u) {
+        planet_color = storm_vortices(planet_color, theta, phi, time);
+    }
+
+    let final_color = srgb_to_linear_rgb(planet_color) * atmospheric_light * vortex_effect;
+    return min(final_color * 0.7 + base_color * 0.3, vec3<f32>(1.0));
+}
+
+fn shader_scifi_planet(object_pos: vec3<f32>, base_color: vec3<f32>, time: f32, sun_direction: vec3<f32>) -> vec3<f32> {
+    let coords = spherical_coords(object_pos);
+    let r = coords.x;
+    let theta = coords.y;
+    let phi = coords.z;
+
+    // CAPA 1: energía pulsante
+    let energy_pulse1 = sin(time * 2.0 + theta * 10.0) * 0.5 + 0.5;
+    let energy_pulse2 = cos(time * 1.5 + phi * 8.0) * 0.5 + 0.5;
+    let energy_pulse3 = sin(time * 2.5 + theta * 12.0 + phi * 6.0) * 0.3 + 0.7;
+    let energy_pulse = (energy_pulse1 + energy_pulse2 + energy_pulse3) / 3.0;
+
+    // CAPA 2: circuitos y nodos
+    let circuit_pattern = abs(sin(theta * 20.0) * cos(phi * 15.0));
+    let circuit_intensity = select(0.6, 1.5, circuit_pattern > 0.8);
+    let node_pattern = abs(sin(theta * 25.0) * sin(phi * 20.0));
+    let node_intensity = select(1.0, 2.0, node_pattern > 0.9);
+    let circuit_effect = circuit_intensity * node_intensity * 0.7 + 0.3;
+
+    // CAPA 3: iluminación desde el sol
+    let normal = object_pos / r;
+    let energy_light = max(dot(normal, sun_direction), 0.0);
+    let energy_shadow = energy_light * 0.6 + 0.4;
+
+    // CAPA 4: brillo procedural
+    let glow_pattern = sin(phi * 8.0 + theta * 6.0 + time * 1.0) * 0.5 + 0.5;
+    let glow_intensity = abs(glow_pattern * 2.0 - 1.0) * 0.5 + 0.5;
+    let glow_effect = glow_intensity * 1.3 + 0.7;
+
+    let scifi_color1 = vec3<f32>(0.2, 0.8, 1.0);
+    let scifi_color2 = vec3<f32>(0.8, 0.2, 1.0);
+    let scifi_color3 = vec3<f32>(0.4, 0.3, 0.9);
+    let scifi_color4 = vec3<f32>(0.1, 0.5, 0.9);
+    let scifi_color5 = vec3<f32>(0.9, 0.3, 0.8);
+    let scifi_color6 = vec3<f32>(0.3, 0.9, 0.9);
+
+    let color_phase = sin(theta * 6.0 + phi * 4.0 + time * 0.3) * 0.5 + 0.5;
+    let color_variation = energy_pulse * 0.4 + color_phase * 0.6;
+    var planet_color: vec3<f32>;
+    if (color_variation < 0.2) {
+        planet_color = mix(scifi_color4, scifi_color1, color_variation / 0.2);
+    } else if (color_variation < 0.4) {
+        planet_color = mix(scifi_color1, scifi_color2, (color_variation - 0.2) / 0.2);
+    } else if (color_variation < 0.6) {
+        planet_color = mix(scifi_color2, scifi_color3, (color_variation - 0.4) / 0.2);
+    } else if (color_variation < 0.8) {
+        planet_color = mix(scifi_color3, scifi_color5, (color_variation - 0.6) / 0.2);
+    } else {
+        planet_color = mix(scifi_color5, scifi_color6, (color_variation - 0.8) / 0.2);
+    }
+
+    let energy_effect = energy_pulse * circuit_effect;
+    let final_color = srgb_to_linear_rgb(planet_color) * energy_shadow * glow_effect * energy_effect;
+    return min(final_color * 0.8 + base_color * 0.2, vec3<f32>(1.0));
+}
+
+fn shader_ice_planet(object_pos: vec3<f32>, base_color: vec3<f32>, time: f32, sun_direction: vec3<f32>) -> vec3<f32> {
+    let coords = spherical_coords(object_pos);
+    let r = coords.x;
+    let theta = coords.y;
+    let phi = coords.z;
+
+    // CAPA 1: hielo con fracturas
+    let ice_fracture = fractal_noise(object_pos, time * 0.05, 5);
+    let crack_pattern = sin(phi * 12.0 + theta * 8.0 + time * 0.2) * 0.3 + 0.7;
+
+    // CAPA 2: capas de nieve
+    let snow_depth = sin(theta * 3.0) * 0.5 + 0.5;
+    let snow_layers = fractal_noise(object_pos * 2.0, time * 0.03, 3);
+    let snow_gradient = snow_depth * 0.6 + snow_layers * 0.4;
+
+    // CAPA 3: reflexión del hielo
+    let normal = object_pos / r;
+    let ice_reflection = max(dot(normal, sun_direction), 0.0);
+    let ice_shine = ice_reflection * 1.5 + 0.5;
+
+    // CAPA 4: cristales y escarcha
+    let crystal_pattern = abs(sin(theta * 30.0) * cos(phi * 25.0));
+    let frost_effect = sin(phi * 10.0 + theta * 8.0 + time * 0.4) * 0.2 + 0.8;
+    let crystal_glow = select(1.0, 1.8, crystal_pattern > 0.95);
+
+    let ice_color1 = vec3<f32>(0.9, 0.95, 1.0);
+    let ice_color2 = vec3<f32>(0.7, 0.85, 0.95);
+    let ice_color3 = vec3<f32>(0.5, 0.7, 0.9);
+    let ice_color4 = vec3<f32>(0.8, 0.9, 0.98);
+    let ice_color5 = vec3<f32>(0.6, 0.8, 0.95);
+
+    let color_factor = ice_fracture * 0.4 + snow_gradient * 0.6;
+    var planet_color: vec3<f32>;
+    if (color_factor < 0.25) {
+        planet_color = mix(ice_color3, ice_color2, color_factor / 0.25);
+    } else if (color_factor < 0.5) {
+        planet_color = mix(ice_color2, ice_color1, (color_factor - 0.25) / 0.25);
+    } else if (color_factor < 0.75) {
+        planet_color = mix(ice_color1, ice_color4, (color_factor - 0.5) / 0.25);
+    } else {
+        planet_color = mix(ice_color4, ice_color5, (color_factor - 0.75) / 0.25);
+    }
+
+    let occlusion = height_occlusion(ice_fracture, 0.35);
+    let final_color = srgb_to_linear_rgb(planet_color) * ice_shine * crystal_glow * frost_effect * crack_pattern * occlusion;
+    return min(final_color * 0.8 + base_color * 0.2, vec3<f32>(1.0));
+}
+
+fn lava_factor(object_pos: vec3<f32>, time: f32) -> f32 {
+    let coords = spherical_coords(object_pos);
+    let theta = coords.y;
+    let phi = coords.z;
+    let lava_noise = fractal_noise(object_pos, time * 0.2, 4);
+    let lava_flow1 = sin(theta * 8.0 + phi * 6.0 + time * 0.8) * 0.5 + 0.5;
+    let lava_flow2 = cos(theta * 12.0 - phi * 4.0 + time * 1.0) * 0.3 + 0.7;
+    return lava_noise * 0.5 + lava_flow1 * lava_flow2 * 0.5;
+}
+
+fn shader_volcanic_planet(object_pos: vec3<f32>, base_color: vec3<f32>, time: f32) -> vec3<f32> {
+    let theta = spherical_coords(object_pos).y;
+
+    // CAPAS 1 y 2: lava y roca fundida
+    let color_factor = lava_factor(object_pos, time);
+
+    // CAPA 3: lava incandescente
+    let lava_glow = sin(time * 3.0 + theta * 5.0) * 0.3 + 0.7;
+    let incandescent = lava_glow * 1.5 + 0.5;
+
+    // CAPA 4: humo y ceniza
+    let smoke_pattern = fractal_noise(vec3<f32>(object_pos.x * 1.5, object_pos.y * 2.0, object_pos.z * 1.5), time * 0.15, 2);
+    let ash_layer = sin(theta * 4.0 + time * 0.5) * 0.2 + 0.8;
+
+    let lava_color1 = vec3<f32>(1.0, 0.3, 0.0);
+    let lava_color3 = vec3<f32>(0.6, 0.2, 0.1);
+    let lava_color4 = vec3<f32>(0.4, 0.15, 0.1);
+    let lava_color5 = vec3<f32>(0.8, 0.4, 0.2);
+
+    let is_lava = select(0.3, 1.0, color_factor > 0.6);
+    var planet_color: vec3<f32>;
+    if (color_factor < 0.3) {
+        planet_color = mix(lava_color4, lava_color3, color_factor / 0.3);
+    } else if (color_factor < 0.6) {
+        planet_color = mix(lava_color3, lava_color5, (color_factor - 0.3) / 0.3);
+    } else {
+        planet_color = mix(lava_color5, lava_color1, (color_factor - 0.6) / 0.4);
+    }
+
+    let final_color = srgb_to_linear_rgb(planet_color) * incandescent * is_lava * (1.0 - smoke_pattern * 0.3) * ash_layer;
+    return min(final_color * 0.8 + base_color * 0.2, vec3<f32>(1.0));
+}
+
+fn shader_moon(object_pos: vec3<f32>, time: f32, sun_direction: vec3<f32>) -> vec3<f32> {
+    let craters = fractal_noise(object_pos, time * 0.01, 5);
+    let crater_depth = max(1.0 - craters * 0.5, 0.3);
+    let surface_variation = fractal_noise(object_pos * 2.0, time * 0.02, 3);
+
+    let moon_color1 = vec3<f32>(0.6, 0.6, 0.65);
+    let moon_color2 = vec3<f32>(0.5, 0.5, 0.55);
+    let moon_color3 = vec3<f32>(0.4, 0.4, 0.45);
+
+    let color_factor = surface_variation * crater_depth;
+    var planet_color: vec3<f32>;
+    if (color_factor < 0.5) {
+        planet_color = mix(moon_color3, moon_color2, color_factor / 0.5);
+    } else {
+        planet_color = mix(moon_color2, moon_color1, (color_factor - 0.5) / 0.5);
+    }
+
+    let occlusion = height_occlusion(craters, 0.5);
+
+    // El sol sin atmósfera más la luz que refleja el planeta padre
+    let normal = object_pos / max(length(object_pos), 0.0001);
+    let sun = max(dot(normal, sun_direction), 0.0);
+    let fill = params.fill_color * max(dot(normal, params.fill_direction), 0.0);
+    let light = vec3<f32>(sun + 0.02) + fill;
+    return min(srgb_to_linear_rgb(planet_color) * light * crater_depth * occlusion, vec3<f32>(1.0));
+}
+
+@compute @workgroup_size(64)
+fn shade_fragments(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if (index >= params.count) {
+        return;
+    }
+    let fragment = fragments[index];
+    let position = fragment.object_position;
+    var color = fragment.color;
+    switch params.planet_type {
+        case ROCKY: {
+            color = shader_rocky_planet(position, fragment.color, params.time, params.sun_direction);
+        }
+        case GAS_GIANT: {
+            color = shader_gas_giant(position, fragment.color, params.time, params.sun_direction);
+        }
+        case SCIFI: {
+            color = shader_scifi_planet(position, fragment.color, params.time, params.sun_direction);
+        }
+        case ICE: {
+            color = shader_ice_planet(position, fragment.color, params.time, params.sun_direction);
+        }
+        case VOLCANIC: {
+            color = shader_volcanic_planet(position, fragment.color, params.time);
+        }
+        case MOON: {
+            color = shader_moon(position, params.time, params.sun_direction);
+        }
+        default: {}
+    }
+    colors[index] = vec4<f32>(color, 1.0);
+}
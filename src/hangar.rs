@@ -0,0 +1,132 @@
+use std::fs;
+use std::io;
+
+/// Nave del hangar: el modelo se normaliza al radio estándar de la nave al cargarlo y `scale` lo
+/// agranda o achica a partir de ahí (también las toberas, el morro y la cámara de persecución)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShipModel {
+    pub name: String,
+    pub path: String,       // Archivo OBJ del modelo
+    pub scale: f32,         // Tamaño relativo a la nave estándar
+    pub engine_offset: f32, // Distancia de la tobera detrás del centro, en radios de la nave
+    pub engine_size: f32,   // Radio del brillo de la tobera, en radios de la nave
+}
+
+impl ShipModel {
+    pub fn new(name: &str, path: &str) -> Self {
+        ShipModel {
+            name: String::from(name),
+            path: String::from(path),
+            scale: 1.0,
+            engine_offset: 0.5,
+            engine_size: 0.35,
+        }
+    }
+}
+
+/// Naves del hangar en el orden en que se recorren; la primera es la de salida
+/// El archivo tiene una sección `[ship]` por nave con líneas `clave = valor` (`#` inicia un
+/// comentario); `name` y `model` son obligatorias
+#[derive(Clone, Debug, PartialEq)]
+pub struct HangarConfig {
+    pub ships: Vec<ShipModel>,
+}
+
+impl HangarConfig {
+    /// Solo la nave original
+    pub fn new() -> Self {
+        HangarConfig { ships: vec![ShipModel::new("Explorador", "assets/models/Untitled.obj")] }
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        HangarConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut ships: Vec<ShipModel> = Vec::new();
+        let mut section_lines = Vec::new(); // Línea de cada `[ship]`, para los errores
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "[ship]" {
+                ships.push(ShipModel::new("", ""));
+                section_lines.push(line_number);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'clave = valor' o '[ship]'"))?;
+            let value = value.trim();
+            let Some(ship) = ships.last_mut() else {
+                return Err(config_error(line_number, "las claves van dentro de una sección [ship]"));
+            };
+            let number = || value.parse::<f32>().map_err(|e| config_error(line_number, &format!("{}", e)));
+            match key.trim() {
+                "name" => ship.name = String::from(value),
+                "model" => {
+                    let lower = value.to_lowercase();
+                    if lower.ends_with(".gltf") || lower.ends_with(".glb") {
+                        return Err(config_error(line_number, "solo se cargan modelos OBJ (exporta el glTF a OBJ)"));
+                    }
+                    ship.path = String::from(value);
+                }
+                "scale" => ship.scale = number()?.max(0.05),
+                "engine_offset" => ship.engine_offset = number()?,
+                "engine_size" => ship.engine_size = number()?.max(0.0),
+                other => return Err(config_error(line_number, &format!("clave desconocida '{}'", other))),
+            }
+        }
+
+        for (ship, line_number) in ships.iter().zip(section_lines) {
+            if ship.name.is_empty() || ship.path.is_empty() {
+                return Err(config_error(line_number, "cada nave necesita 'name' y 'model'"));
+            }
+        }
+        if ships.is_empty() {
+            return Err(config_error(contents.lines().count(), "no hay ninguna nave"));
+        }
+        Ok(HangarConfig { ships })
+    }
+}
+
+impl Default for HangarConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hangar: la nave activa entre las de la configuración
+pub struct Hangar {
+    pub config: HangarConfig,
+    active: usize,
+}
+
+impl Hangar {
+    pub fn new(config: HangarConfig) -> Self {
+        Hangar { config, active: 0 }
+    }
+
+    pub fn active(&self) -> &ShipModel {
+        &self.config.ships[self.active]
+    }
+
+    #[allow(dead_code)]
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Pasar a la siguiente nave (tras la última, la primera) y devolverla
+    pub fn cycle(&mut self) -> &ShipModel {
+        self.active = (self.active + 1) % self.config.ships.len();
+        self.active()
+    }
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
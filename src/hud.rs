@@ -0,0 +1,188 @@
+use raylib::prelude::*;
+use crate::docking::{Docking, DockingState};
+use crate::flight_model::FlightModel;
+use crate::ship_systems::ShipSystems;
+use crate::units::format_distance;
+use std::f32::consts::PI;
+
+/// Dibujar el panel del piloto automático: destino, barra de progreso y distancia restante
+pub fn draw_autopilot_hud(d: &mut RaylibDrawHandle, width: i32, height: i32, target_name: &str, progress: f32, remaining: f32) {
+    let panel_width = 260;
+    let panel_height = 62;
+    let x = width / 2 - panel_width / 2;
+    let y = height - panel_height - 16;
+    let accent = Color::new(80, 220, 160, 255);
+
+    d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 170));
+    d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+    d.draw_text(&format!("PILOTO AUTOMATICO -> {}", target_name), x + 10, y + 8, 14, accent);
+
+    let bar_width = panel_width - 20;
+    d.draw_rectangle_lines(x + 10, y + 28, bar_width, 10, accent);
+    d.draw_rectangle(x + 10, y + 28, (bar_width as f32 * progress) as i32, 10, accent);
+
+    d.draw_text(
+        &format!("{:3.0}%  distancia {}  (cualquier tecla cancela)", progress * 100.0, format_distance(remaining)),
+        x + 10,
+        y + 44,
+        10,
+        Color::new(200, 210, 220, 255),
+    );
+}
+
+/// Indicadores de la nave en la esquina inferior izquierda: modelo de vuelo y velocidad,
+/// combustible, empuje y carga de warp
+/// La barra de combustible parpadea en rojo cuando la nave va a la deriva
+pub fn draw_ship_systems_hud(d: &mut RaylibDrawHandle, height: i32, systems: &ShipSystems, flight: &FlightModel, time: f32) {
+    let x = 10;
+    let bar_width = 160;
+    let bar_height = 8;
+    let label_color = Color::new(200, 210, 220, 255);
+    let fuel_color = if systems.is_drifting() {
+        if (time * 3.0).fract() < 0.5 { Color::new(255, 60, 40, 255) } else { Color::new(120, 20, 10, 255) }
+    } else if systems.fuel_fraction() < 0.2 {
+        Color::new(255, 170, 40, 255)
+    } else {
+        Color::new(80, 220, 160, 255)
+    };
+    let warp_color = if systems.warp_charge + 0.0001 >= systems.warp_cost {
+        Color::new(120, 170, 255, 255)
+    } else {
+        Color::new(90, 100, 130, 255)
+    };
+    let gauges = [
+        ("COMBUSTIBLE", systems.fuel_fraction(), fuel_color),
+        ("EMPUJE", systems.thruster_output, Color::new(255, 200, 120, 255)),
+        ("WARP", systems.warp_charge, warp_color),
+    ];
+
+    let mut y = height - 60 - gauges.len() as i32 * 22;
+    d.draw_rectangle(x - 6, y - 22, bar_width + 12, gauges.len() as i32 * 22 + 24, Color::new(0, 0, 0, 150));
+    d.draw_text(
+        &format!("VUELO {}  {:5.1} u/s", flight.mode.name().to_uppercase(), flight.speed()),
+        x,
+        y - 16,
+        10,
+        label_color,
+    );
+    for (label, value, color) in gauges {
+        d.draw_text(label, x, y, 10, label_color);
+        d.draw_rectangle_lines(x, y + 11, bar_width, bar_height, color);
+        d.draw_rectangle(x, y + 11, (bar_width as f32 * value.clamp(0.0, 1.0)) as i32, bar_height, color);
+        y += 22;
+    }
+    if systems.is_drifting() {
+        d.draw_text("SIN COMBUSTIBLE - A LA DERIVA (reposta en una estación)", x, y, 10, fuel_color);
+    }
+}
+
+/// Panel de atraque en la parte inferior central: opciones de la estación mientras está atracada,
+/// o el aviso de que se puede atracar al acercarse al puerto
+pub fn draw_docking_hud(d: &mut RaylibDrawHandle, width: i32, height: i32, station_name: &str, docking: &Docking, systems: &ShipSystems) {
+    let accent = Color::new(120, 200, 255, 255);
+    let panel_width = 280;
+    let x = width / 2 - panel_width / 2;
+    match docking.state {
+        DockingState::Docked { .. } => {
+            let panel_height = 62;
+            let y = height - panel_height - 16;
+            d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 170));
+            d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+            d.draw_text(&format!("ATRACADA EN {}", station_name.to_uppercase()), x + 10, y + 8, 14, accent);
+            let status = if docking.refueling { "repostando..." } else { "U: repostar" };
+            d.draw_text(
+                &format!("Combustible {:3.0}%  -  {}", systems.fuel_fraction() * 100.0, status),
+                x + 10,
+                y + 28,
+                12,
+                Color::new(200, 210, 220, 255),
+            );
+            d.draw_text("H: desatracar", x + 10, y + 44, 12, Color::new(200, 210, 220, 255));
+        }
+        DockingState::Undocking { .. } => {
+            d.draw_text("DESATRACANDO...", x + 10, height - 40, 14, accent);
+        }
+        DockingState::Free if docking.can_dock() => {
+            d.draw_text(&format!("H: atracar en {}", station_name), x + 10, height - 40, 14, accent);
+        }
+        _ => {}
+    }
+}
+
+/// Aviso parpadeante de tormenta solar en la parte superior de la pantalla
+/// `strength` (0 a 1) controla la opacidad del panel
+pub fn draw_solar_storm_hud(d: &mut RaylibDrawHandle, width: i32, strength: f32, time: f32) {
+    let text = "TORMENTA SOLAR - INTERFERENCIAS";
+    let font_size = 18;
+    let text_width = measure_text(text, font_size);
+    let x = width / 2 - text_width / 2;
+    let y = 56;
+    let blink = if (time * 4.0).fract() < 0.5 { 1.0 } else { 0.6 };
+    let alpha = (strength.clamp(0.0, 1.0) * blink * 255.0) as u8;
+
+    d.draw_rectangle(x - 10, y - 6, text_width + 20, font_size + 12, Color::new(60, 0, 0, alpha / 2));
+    d.draw_rectangle_lines(x - 10, y - 6, text_width + 20, font_size + 12, Color::new(255, 90, 40, alpha));
+    d.draw_text(text, x, y, font_size, Color::new(255, 140, 60, alpha));
+}
+
+/// Aviso del próximo eclipse bajo el de tormenta solar, con la tecla para ir a verlo
+/// Los que ve la nave se resaltan
+pub fn draw_eclipse_hud(d: &mut RaylibDrawHandle, width: i32, headline: &str, from_ship: bool) {
+    let font_size = 14;
+    let hint = "; : ir a verlo";
+    let text_width = measure_text(headline, font_size).max(measure_text(hint, 10));
+    let x = width / 2 - text_width / 2;
+    let y = 86;
+    let accent = if from_ship { Color::new(255, 210, 120, 255) } else { Color::new(170, 185, 210, 220) };
+
+    d.draw_rectangle(x - 10, y - 6, text_width + 20, font_size + 24, Color::new(0, 0, 0, 150));
+    d.draw_rectangle_lines(x - 10, y - 6, text_width + 20, font_size + 24, accent);
+    d.draw_text(headline, x, y, font_size, accent);
+    d.draw_text(hint, x, y + font_size + 4, 10, Color::new(200, 210, 220, 200));
+}
+
+/// Dibujar el marco de la cabina sobre la imagen renderizada
+/// Panel de instrumentos abajo, montantes laterales y un indicador de cabeceo
+pub fn draw_cockpit_overlay(d: &mut RaylibDrawHandle, width: i32, height: i32, pitch: f32) {
+    let frame_color = Color::new(30, 34, 40, 255);
+    let edge_color = Color::new(90, 100, 115, 255);
+    let instrument_color = Color::new(80, 220, 160, 200);
+
+    // Marco superior
+    let top_height = height / 14;
+    d.draw_rectangle(0, 0, width, top_height, frame_color);
+    d.draw_line(0, top_height, width, top_height, edge_color);
+
+    // Panel de instrumentos
+    let panel_height = height / 5;
+    let panel_y = height - panel_height;
+    d.draw_rectangle(0, panel_y, width, panel_height, frame_color);
+    d.draw_line(0, panel_y, width, panel_y, edge_color);
+
+    // Montantes diagonales que unen el marco superior con el panel
+    let strut_inset = width as f32 * 0.12;
+    d.draw_line_ex(
+        Vector2::new(0.0, top_height as f32),
+        Vector2::new(strut_inset, panel_y as f32),
+        14.0,
+        frame_color,
+    );
+    d.draw_line_ex(
+        Vector2::new(width as f32, top_height as f32),
+        Vector2::new(width as f32 - strut_inset, panel_y as f32),
+        14.0,
+        frame_color,
+    );
+
+    // Instrumentos: dos diales y un indicador de cabeceo en el centro del panel
+    let dial_radius = panel_height as f32 * 0.3;
+    let dial_y = panel_y + panel_height / 2;
+    d.draw_circle_lines(width / 4, dial_y, dial_radius, instrument_color);
+    d.draw_circle_lines(width * 3 / 4, dial_y, dial_radius, instrument_color);
+
+    let indicator_width = width / 6;
+    let indicator_x = width / 2 - indicator_width / 2;
+    let pitch_offset = (pitch / (PI / 2.0) * dial_radius) as i32;
+    d.draw_rectangle_lines(indicator_x, dial_y - dial_radius as i32, indicator_width, dial_radius as i32 * 2, instrument_color);
+    d.draw_line(indicator_x, dial_y - pitch_offset, indicator_x + indicator_width, dial_y - pitch_offset, instrument_color);
+}
@@ -0,0 +1,715 @@
+use raylib::prelude::*;
+use std::fs;
+use std::io;
+
+/// Acciones del juego que se pueden asignar a teclas o botones del ratón
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    // Vuelo
+    PitchUp,
+    PitchDown,
+    YawLeft,
+    YawRight,
+    Forward,
+    Backward,
+    StrafeLeft,
+    StrafeRight,
+    Ascend,
+    Descend,
+    Modifier,
+    FlightModel,
+    CameraMode,
+    OrbitDrag,
+    // Navegación
+    TrackPlanet1,
+    TrackPlanet2,
+    TrackPlanet3,
+    TrackPlanet4,
+    TrackPlanet5,
+    TrackMoon,
+    DockAutopilot,
+    FreeCamera,
+    Warp1,
+    Warp2,
+    Warp3,
+    Warp4,
+    Warp5,
+    Warp6,
+    Warp7,
+    Warp8,
+    EclipseWarp,
+    RouteAdd,
+    RouteRemove,
+    RouteRun,
+    Dock,
+    Refuel,
+    ParkingOrbit,
+    NextShip,
+    SasTarget,
+    SasKillRotation,
+    SasPrograde,
+    SasRetrograde,
+    SasMatchVelocity,
+    // Juego
+    Fire,
+    DeflectAsteroid,
+    CoronalMassEjection,
+    NewSystem,
+    SlowerTime,
+    FasterTime,
+    // Vista
+    Minimap,
+    Labels,
+    Daylight,
+    GammaDown,
+    GammaUp,
+    Dithering,
+    RenderScale,
+    PhotoMode,
+    PhotoExposureDown,
+    PhotoExposureUp,
+    PhotoFovNarrow,
+    PhotoFovWide,
+    PhotoReset,
+    PhotoHud,
+    PhotoShip,
+    PhotoCapture,
+    ZoomIn,
+    ZoomOut,
+    Binoculars,
+    MapView,
+    SecondaryView,
+    VolumeDown,
+    VolumeUp,
+    // Trayectorias
+    PathRecord,
+    PathClear,
+    PathPlay,
+    PathSave,
+    PathLoad,
+    Tour,
+    // Depuración
+    DebugAxes,
+    DebugBounds,
+    DebugVelocity,
+    DebugNormals,
+    DebugStats,
+    FrameGraph,
+    ShaderQuality,
+    Console,
+    Controls,
+}
+
+impl Action {
+    /// Todas las acciones, en el orden en que aparecen en la pantalla de controles
+    pub const ALL: [Action; 87] = [
+        Action::PitchUp,
+        Action::PitchDown,
+        Action::YawLeft,
+        Action::YawRight,
+        Action::Forward,
+        Action::Backward,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::Ascend,
+        Action::Descend,
+        Action::Modifier,
+        Action::FlightModel,
+        Action::CameraMode,
+        Action::OrbitDrag,
+        Action::TrackPlanet1,
+        Action::TrackPlanet2,
+        Action::TrackPlanet3,
+        Action::TrackPlanet4,
+        Action::TrackPlanet5,
+        Action::TrackMoon,
+        Action::DockAutopilot,
+        Action::FreeCamera,
+        Action::Warp1,
+        Action::Warp2,
+        Action::Warp3,
+        Action::Warp4,
+        Action::Warp5,
+        Action::Warp6,
+        Action::Warp7,
+        Action::Warp8,
+        Action::EclipseWarp,
+        Action::RouteAdd,
+        Action::RouteRemove,
+        Action::RouteRun,
+        Action::Dock,
+        Action::Refuel,
+        Action::ParkingOrbit,
+        Action::NextShip,
+        Action::SasTarget,
+        Action::SasKillRotation,
+        Action::SasPrograde,
+        Action::SasRetrograde,
+        Action::SasMatchVelocity,
+        Action::Fire,
+        Action::DeflectAsteroid,
+        Action::CoronalMassEjection,
+        Action::NewSystem,
+        Action::SlowerTime,
+        Action::FasterTime,
+        Action::Minimap,
+        Action::Labels,
+        Action::Daylight,
+        Action::GammaDown,
+        Action::GammaUp,
+        Action::Dithering,
+        Action::RenderScale,
+        Action::PhotoMode,
+        Action::PhotoExposureDown,
+        Action::PhotoExposureUp,
+        Action::PhotoFovNarrow,
+        Action::PhotoFovWide,
+        Action::PhotoReset,
+        Action::PhotoHud,
+        Action::PhotoShip,
+        Action::PhotoCapture,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Binoculars,
+        Action::MapView,
+        Action::SecondaryView,
+        Action::VolumeDown,
+        Action::VolumeUp,
+        Action::PathRecord,
+        Action::PathClear,
+        Action::PathPlay,
+        Action::PathSave,
+        Action::PathLoad,
+        Action::Tour,
+        Action::DebugAxes,
+        Action::DebugBounds,
+        Action::DebugVelocity,
+        Action::DebugNormals,
+        Action::DebugStats,
+        Action::FrameGraph,
+        Action::ShaderQuality,
+        Action::Console,
+        Action::Controls,
+    ];
+
+    /// Seguir a los planetas 1-5 (con el modificador, piloto automático hacia ellos)
+    pub const TRACK_PLANET: [Action; 5] = [
+        Action::TrackPlanet1,
+        Action::TrackPlanet2,
+        Action::TrackPlanet3,
+        Action::TrackPlanet4,
+        Action::TrackPlanet5,
+    ];
+
+    /// Interruptores de la interfaz (suenan con un clic)
+    pub const INTERFACE: [Action; 15] = [
+        Action::Minimap,
+        Action::Labels,
+        Action::Daylight,
+        Action::GammaDown,
+        Action::GammaUp,
+        Action::Dithering,
+        Action::RenderScale,
+        Action::VolumeDown,
+        Action::VolumeUp,
+        Action::DebugAxes,
+        Action::DebugBounds,
+        Action::DebugVelocity,
+        Action::DebugNormals,
+        Action::DebugStats,
+        Action::FrameGraph,
+    ];
+
+    /// Ajustes del modo foto: solo responden con la simulación en pausa, así que pueden compartir
+    /// teclas con el resto de acciones sin que cuenten como conflicto
+    pub const PHOTO: [Action; 8] = [
+        Action::PhotoExposureDown,
+        Action::PhotoExposureUp,
+        Action::PhotoFovNarrow,
+        Action::PhotoFovWide,
+        Action::PhotoReset,
+        Action::PhotoHud,
+        Action::PhotoShip,
+        Action::PhotoCapture,
+    ];
+
+    /// Saltos de warp a los puntos de interés 1-8
+    pub const WARP: [Action; 8] = [
+        Action::Warp1,
+        Action::Warp2,
+        Action::Warp3,
+        Action::Warp4,
+        Action::Warp5,
+        Action::Warp6,
+        Action::Warp7,
+        Action::Warp8,
+    ];
+
+    /// Nombre de la acción en el archivo de configuración
+    pub fn name(self) -> &'static str {
+        self.info().0
+    }
+
+    /// Descripción para la pantalla de controles
+    pub fn description(self) -> &'static str {
+        self.info().1
+    }
+
+    /// Asignación por defecto (la distribución QWERTY original)
+    pub fn default_bindings(self) -> Vec<Binding> {
+        use KeyboardKey::*;
+        let keys: &[KeyboardKey] = match self {
+            Action::PitchUp => &[KEY_W],
+            Action::PitchDown => &[KEY_S],
+            Action::YawLeft => &[KEY_A],
+            Action::YawRight => &[KEY_D],
+            Action::Forward => &[KEY_UP],
+            Action::Backward => &[KEY_DOWN],
+            Action::StrafeLeft => &[KEY_Q, KEY_LEFT],
+            Action::StrafeRight => &[KEY_E, KEY_RIGHT],
+            Action::Ascend => &[KEY_R],
+            Action::Descend => &[KEY_F],
+            Action::Modifier => &[KEY_LEFT_SHIFT, KEY_RIGHT_SHIFT],
+            Action::FlightModel => &[KEY_TAB],
+            Action::CameraMode => &[KEY_C],
+            Action::OrbitDrag => return vec![Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT)],
+            Action::TrackPlanet1 => &[KEY_ONE],
+            Action::TrackPlanet2 => &[KEY_TWO],
+            Action::TrackPlanet3 => &[KEY_THREE],
+            Action::TrackPlanet4 => &[KEY_FOUR],
+            Action::TrackPlanet5 => &[KEY_FIVE],
+            Action::TrackMoon => &[KEY_SLASH],
+            Action::DockAutopilot => &[KEY_SIX],
+            Action::FreeCamera => &[KEY_ZERO],
+            Action::Warp1 => &[KEY_F1],
+            Action::Warp2 => &[KEY_F2],
+            Action::Warp3 => &[KEY_F3],
+            Action::Warp4 => &[KEY_F4],
+            Action::Warp5 => &[KEY_F5],
+            Action::Warp6 => &[KEY_F6],
+            Action::Warp7 => &[KEY_F7],
+            Action::Warp8 => &[KEY_F8],
+            Action::EclipseWarp => &[KEY_SEMICOLON],
+            Action::RouteAdd => &[KEY_X],
+            Action::RouteRemove => &[KEY_Z],
+            Action::RouteRun => &[KEY_ENTER],
+            Action::Dock => &[KEY_H],
+            Action::Refuel => &[KEY_U],
+            Action::ParkingOrbit => &[KEY_O],
+            Action::NextShip => &[KEY_PAGE_DOWN],
+            Action::SasTarget => &[KEY_KP_0],
+            Action::SasKillRotation => &[KEY_KP_5],
+            Action::SasPrograde => &[KEY_KP_8],
+            Action::SasRetrograde => &[KEY_KP_2],
+            Action::SasMatchVelocity => &[KEY_KP_6],
+            Action::Fire => &[KEY_SPACE],
+            Action::DeflectAsteroid => &[KEY_I],
+            Action::CoronalMassEjection => &[KEY_INSERT],
+            Action::NewSystem => &[KEY_Y],
+            Action::SlowerTime => &[KEY_COMMA],
+            Action::FasterTime => &[KEY_PERIOD],
+            Action::Minimap => &[KEY_M],
+            Action::Labels => &[KEY_L],
+            Action::Daylight => &[KEY_PAGE_UP],
+            Action::GammaDown => &[KEY_LEFT_BRACKET],
+            Action::GammaUp => &[KEY_RIGHT_BRACKET],
+            Action::Dithering => &[KEY_BACKSLASH],
+            Action::RenderScale => &[KEY_F11],
+            Action::PhotoMode => &[KEY_P],
+            Action::PhotoExposureDown => &[KEY_ONE],
+            Action::PhotoExposureUp => &[KEY_TWO],
+            Action::PhotoFovNarrow => &[KEY_THREE],
+            Action::PhotoFovWide => &[KEY_FOUR],
+            Action::PhotoReset => &[KEY_ZERO],
+            Action::PhotoHud => &[KEY_H],
+            Action::PhotoShip => &[KEY_N],
+            Action::PhotoCapture => &[KEY_ENTER],
+            Action::ZoomIn => &[KEY_KP_ADD],
+            Action::ZoomOut => &[KEY_KP_SUBTRACT],
+            Action::Binoculars => return vec![Binding::Mouse(MouseButton::MOUSE_BUTTON_RIGHT)],
+            Action::MapView => &[KEY_HOME],
+            Action::SecondaryView => &[KEY_END],
+            Action::VolumeDown => &[KEY_MINUS],
+            Action::VolumeUp => &[KEY_EQUAL],
+            Action::PathRecord => &[KEY_K],
+            Action::PathClear => &[KEY_J],
+            Action::PathPlay => &[KEY_APOSTROPHE],
+            Action::PathSave => &[KEY_F9],
+            Action::PathLoad => &[KEY_F10],
+            Action::Tour => &[KEY_T],
+            Action::DebugAxes => &[KEY_G],
+            Action::DebugBounds => &[KEY_B],
+            Action::DebugVelocity => &[KEY_V],
+            Action::DebugNormals => &[KEY_N],
+            // F3 es el warp al tercer punto de interés (F1-F8 van seguidas), así que va en el 7
+            Action::DebugStats => &[KEY_SEVEN],
+            Action::FrameGraph => &[KEY_EIGHT],
+            Action::ShaderQuality => &[KEY_NINE],
+            Action::Console => &[KEY_GRAVE],
+            Action::Controls => &[KEY_BACKSPACE],
+        };
+        keys.iter().map(|&key| Binding::Key(key)).collect()
+    }
+
+    fn info(self) -> (&'static str, &'static str) {
+        match self {
+            Action::PitchUp => ("pitch_up", "Cabecear hacia arriba"),
+            Action::PitchDown => ("pitch_down", "Cabecear hacia abajo"),
+            Action::YawLeft => ("yaw_left", "Girar a la izquierda"),
+            Action::YawRight => ("yaw_right", "Girar a la derecha"),
+            Action::Forward => ("forward", "Avanzar"),
+            Action::Backward => ("backward", "Retroceder"),
+            Action::StrafeLeft => ("strafe_left", "Desplazarse a la izquierda"),
+            Action::StrafeRight => ("strafe_right", "Desplazarse a la derecha"),
+            Action::Ascend => ("ascend", "Subir"),
+            Action::Descend => ("descend", "Bajar"),
+            Action::Modifier => ("modifier", "Modificador (piloto automático, borrar ruta)"),
+            Action::FlightModel => ("flight_model", "Alternar modelo de vuelo"),
+            Action::CameraMode => ("camera_mode", "Cambiar modo de cámara"),
+            Action::OrbitDrag => ("orbit_drag", "Arrastrar en modo órbita"),
+            Action::TrackPlanet1 => ("track_planet_1", "Seguir planeta 1"),
+            Action::TrackPlanet2 => ("track_planet_2", "Seguir planeta 2"),
+            Action::TrackPlanet3 => ("track_planet_3", "Seguir planeta 3"),
+            Action::TrackPlanet4 => ("track_planet_4", "Seguir planeta 4"),
+            Action::TrackPlanet5 => ("track_planet_5", "Seguir planeta 5"),
+            Action::TrackMoon => ("track_moon", "Seguir la siguiente luna"),
+            Action::DockAutopilot => ("dock_autopilot", "Atraque automático (con el modificador)"),
+            Action::FreeCamera => ("free_camera", "Dejar de seguir"),
+            Action::Warp1 => ("warp_1", "Warp: vista general"),
+            Action::Warp2 => ("warp_2", "Warp: sol"),
+            Action::Warp3 => ("warp_3", "Warp: planeta 1"),
+            Action::Warp4 => ("warp_4", "Warp: planeta 2"),
+            Action::Warp5 => ("warp_5", "Warp: planeta 3"),
+            Action::Warp6 => ("warp_6", "Warp: planeta 4"),
+            Action::Warp7 => ("warp_7", "Warp: planeta 5"),
+            Action::Warp8 => ("warp_8", "Warp: siguiente luna"),
+            Action::EclipseWarp => ("eclipse_warp", "Warp: ver el próximo eclipse"),
+            Action::RouteAdd => ("route_add", "Añadir punto de ruta"),
+            Action::RouteRemove => ("route_remove", "Quitar punto de ruta"),
+            Action::RouteRun => ("route_run", "Recorrer/detener la ruta"),
+            Action::Dock => ("dock", "Atracar/desatracar"),
+            Action::Refuel => ("refuel", "Repostar"),
+            Action::ParkingOrbit => ("parking_orbit", "Órbita de estacionamiento"),
+            Action::NextShip => ("next_ship", "Cambiar de nave (hangar)"),
+            Action::SasTarget => ("sas_target", "SAS: fijar objetivo bajo la mira"),
+            Action::SasKillRotation => ("sas_kill_rotation", "SAS: frenar el giro"),
+            Action::SasPrograde => ("sas_prograde", "SAS: mantener progrado"),
+            Action::SasRetrograde => ("sas_retrograde", "SAS: mantener retrógrado"),
+            Action::SasMatchVelocity => ("sas_match_velocity", "SAS: igualar velocidad con el objetivo"),
+            Action::Fire => ("fire", "Disparar"),
+            Action::DeflectAsteroid => ("deflect_asteroid", "Desviar un asteroide"),
+            Action::CoronalMassEjection => ("coronal_mass_ejection", "Eyección de masa coronal"),
+            Action::NewSystem => ("new_system", "Generar otro sistema"),
+            Action::SlowerTime => ("slower_time", "Frenar el reloj"),
+            Action::FasterTime => ("faster_time", "Acelerar el reloj"),
+            Action::Minimap => ("minimap", "Minimapa"),
+            Action::Labels => ("labels", "Etiquetas"),
+            Action::Daylight => ("daylight", "Día y noche del planeta bajo la mira"),
+            Action::GammaDown => ("gamma_down", "Bajar la gamma"),
+            Action::GammaUp => ("gamma_up", "Subir la gamma"),
+            Action::Dithering => ("dithering", "Activar/desactivar el tramado del color"),
+            Action::RenderScale => ("render_scale", "Escala de render"),
+            Action::PhotoMode => ("photo_mode", "Modo foto"),
+            Action::PhotoExposureDown => ("photo_exposure_down", "Modo foto: bajar la exposición (mantener)"),
+            Action::PhotoExposureUp => ("photo_exposure_up", "Modo foto: subir la exposición (mantener)"),
+            Action::PhotoFovNarrow => ("photo_fov_narrow", "Modo foto: cerrar el campo de visión (mantener)"),
+            Action::PhotoFovWide => ("photo_fov_wide", "Modo foto: abrir el campo de visión (mantener)"),
+            Action::PhotoReset => ("photo_reset", "Modo foto: restablecer exposición y campo de visión"),
+            Action::PhotoHud => ("photo_hud", "Modo foto: mostrar/ocultar el HUD"),
+            Action::PhotoShip => ("photo_ship", "Modo foto: mostrar/ocultar la nave"),
+            Action::PhotoCapture => ("photo_capture", "Modo foto: guardar una captura"),
+            Action::ZoomIn => ("zoom_in", "Cerrar el campo de visión"),
+            Action::ZoomOut => ("zoom_out", "Abrir el campo de visión"),
+            Action::Binoculars => ("binoculars", "Prismáticos (mantener)"),
+            Action::MapView => ("map_view", "Vista de mapa"),
+            Action::SecondaryView => ("secondary_view", "Retrovisor / cámara del planeta"),
+            Action::VolumeDown => ("volume_down", "Bajar el volumen"),
+            Action::VolumeUp => ("volume_up", "Subir el volumen"),
+            Action::PathRecord => ("path_record", "Grabar keyframe"),
+            Action::PathClear => ("path_clear", "Borrar trayectoria"),
+            Action::PathPlay => ("path_play", "Reproducir trayectoria"),
+            Action::PathSave => ("path_save", "Guardar trayectoria"),
+            Action::PathLoad => ("path_load", "Cargar trayectoria"),
+            Action::Tour => ("tour", "Visita guiada"),
+            Action::DebugAxes => ("debug_axes", "Ejes del mundo"),
+            Action::DebugBounds => ("debug_bounds", "Esferas envolventes"),
+            Action::DebugVelocity => ("debug_velocity", "Vector de velocidad"),
+            Action::DebugNormals => ("debug_normals", "Normales"),
+            Action::DebugStats => ("debug_stats", "Estadísticas del pipeline"),
+            Action::FrameGraph => ("frame_graph", "Gráfica de tiempos de frame"),
+            Action::ShaderQuality => ("shader_quality", "Calidad de los shaders"),
+            Action::Console => ("console", "Consola de desarrollo"),
+            Action::Controls => ("controls", "Pantalla de controles"),
+        }
+    }
+
+    /// Posición en `Action::ALL` (para guardar datos por acción en arrays)
+    pub fn index(self) -> usize {
+        Action::ALL.iter().position(|&a| a == self).unwrap_or(0)
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| action.name() == name)
+    }
+}
+
+/// Tecla o botón del ratón asignado a una acción
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    /// Nombre en el archivo de configuración: el de raylib sin prefijo (`W`, `LEFT_SHIFT`, `MOUSE_LEFT`)
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key) => KEY_NAMES
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map_or_else(|| format!("{:?}", key), |(_, name)| name.to_string()),
+            Binding::Mouse(button) => MOUSE_NAMES
+                .iter()
+                .find(|(b, _)| *b == button)
+                .map_or_else(|| format!("{:?}", button), |(_, name)| name.to_string()),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Binding> {
+        let name = name.trim().to_uppercase();
+        let name = name.strip_prefix("KEY_").unwrap_or(&name);
+        let mouse = MOUSE_NAMES.iter().find(|(_, n)| *n == name).map(|&(button, _)| Binding::Mouse(button));
+        mouse.or_else(|| KEY_NAMES.iter().find(|(_, n)| *n == name).map(|&(key, _)| Binding::Key(key)))
+    }
+
+    fn is_down(self, window: &RaylibHandle) -> bool {
+        match self {
+            Binding::Key(key) => window.is_key_down(key),
+            Binding::Mouse(button) => window.is_mouse_button_down(button),
+        }
+    }
+
+    fn is_pressed(self, window: &RaylibHandle) -> bool {
+        match self {
+            Binding::Key(key) => window.is_key_pressed(key),
+            Binding::Mouse(button) => window.is_mouse_button_pressed(button),
+        }
+    }
+}
+
+/// Asignación de acciones a teclas y botones, en lugar de constantes repartidas por el código
+/// Se carga de un archivo de líneas `acción = tecla[, tecla]` (ver `assets/controls.cfg`) y se
+/// puede cambiar en el juego con la pantalla de controles
+pub struct InputMap {
+    bindings: Vec<Vec<Binding>>, // Una lista por acción, en el orden de `Action::ALL`
+    pub suspended: bool,         // Ninguna acción responde (p. ej. con la pantalla de controles abierta)
+}
+
+impl InputMap {
+    pub fn new() -> Self {
+        InputMap {
+            bindings: Action::ALL.iter().map(|action| action.default_bindings()).collect(),
+            suspended: false,
+        }
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        &self.bindings[action.index()]
+    }
+
+    /// Reemplaza las asignaciones de `action` por `binding`
+    pub fn set(&mut self, action: Action, binding: Binding) {
+        self.bindings[action.index()] = vec![binding];
+    }
+
+    pub fn reset(&mut self, action: Action) {
+        self.bindings[action.index()] = action.default_bindings();
+    }
+
+    /// Otras acciones que usan `binding` (para avisar de conflictos); las del modo foto solo
+    /// chocan entre sí
+    pub fn conflicts(&self, action: Action, binding: Binding) -> Vec<Action> {
+        let photo = Action::PHOTO.contains(&action);
+        Action::ALL
+            .iter()
+            .copied()
+            .filter(|&other| other != action && Action::PHOTO.contains(&other) == photo)
+            .filter(|&other| self.bindings(other).contains(&binding))
+            .collect()
+    }
+
+    /// Texto con las asignaciones de `action` (`Q / LEFT`)
+    pub fn describe(&self, action: Action) -> String {
+        let names: Vec<String> = self.bindings(action).iter().map(|binding| binding.name()).collect();
+        if names.is_empty() { "-".to_string() } else { names.join(" / ") }
+    }
+
+    /// La acción se mantiene pulsada
+    pub fn is_down(&self, window: &RaylibHandle, action: Action) -> bool {
+        !self.suspended && self.bindings(action).iter().any(|binding| binding.is_down(window))
+    }
+
+    /// La acción se pulsó en este frame
+    pub fn is_pressed(&self, window: &RaylibHandle, action: Action) -> bool {
+        !self.suspended && self.bindings(action).iter().any(|binding| binding.is_pressed(window))
+    }
+
+    /// Cargar las asignaciones de un archivo; las acciones que no aparecen conservan las de
+    /// por defecto. `#` inicia un comentario
+    pub fn load(path: &str) -> io::Result<Self> {
+        InputMap::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut map = InputMap::new();
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'acción = tecla'"))?;
+            let action = Action::from_name(name.trim())
+                .ok_or_else(|| config_error(line_number, &format!("acción desconocida '{}'", name.trim())))?;
+            let bindings = value
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(|key| Binding::from_name(key).ok_or_else(|| config_error(line_number, &format!("tecla desconocida '{}'", key))))
+                .collect::<io::Result<Vec<_>>>()?;
+            map.bindings[action.index()] = bindings;
+        }
+
+        Ok(map)
+    }
+
+    /// Guardar todas las asignaciones (el formato que lee `load`)
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut contents = String::from("# Controles: acción = tecla[, tecla] (nombres de raylib sin KEY_; MOUSE_LEFT/RIGHT/MIDDLE)\n");
+        for action in Action::ALL {
+            let names: Vec<String> = self.bindings(action).iter().map(|binding| binding.name()).collect();
+            contents.push_str(&format!("{} = {}\n", action.name(), names.join(", ")));
+        }
+        fs::write(path, contents)
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
+
+/// Botones del ratón que se pueden asignar
+const MOUSE_NAMES: &[(MouseButton, &str)] = &[
+    (MouseButton::MOUSE_BUTTON_LEFT, "MOUSE_LEFT"),
+    (MouseButton::MOUSE_BUTTON_RIGHT, "MOUSE_RIGHT"),
+    (MouseButton::MOUSE_BUTTON_MIDDLE, "MOUSE_MIDDLE"),
+];
+
+/// Teclas que se pueden asignar, con su nombre en el archivo de configuración
+const KEY_NAMES: &[(KeyboardKey, &str)] = &[
+    (KeyboardKey::KEY_A, "A"),
+    (KeyboardKey::KEY_B, "B"),
+    (KeyboardKey::KEY_C, "C"),
+    (KeyboardKey::KEY_D, "D"),
+    (KeyboardKey::KEY_E, "E"),
+    (KeyboardKey::KEY_F, "F"),
+    (KeyboardKey::KEY_G, "G"),
+    (KeyboardKey::KEY_H, "H"),
+    (KeyboardKey::KEY_I, "I"),
+    (KeyboardKey::KEY_J, "J"),
+    (KeyboardKey::KEY_K, "K"),
+    (KeyboardKey::KEY_L, "L"),
+    (KeyboardKey::KEY_M, "M"),
+    (KeyboardKey::KEY_N, "N"),
+    (KeyboardKey::KEY_O, "O"),
+    (KeyboardKey::KEY_P, "P"),
+    (KeyboardKey::KEY_Q, "Q"),
+    (KeyboardKey::KEY_R, "R"),
+    (KeyboardKey::KEY_S, "S"),
+    (KeyboardKey::KEY_T, "T"),
+    (KeyboardKey::KEY_U, "U"),
+    (KeyboardKey::KEY_V, "V"),
+    (KeyboardKey::KEY_W, "W"),
+    (KeyboardKey::KEY_X, "X"),
+    (KeyboardKey::KEY_Y, "Y"),
+    (KeyboardKey::KEY_Z, "Z"),
+    (KeyboardKey::KEY_ZERO, "ZERO"),
+    (KeyboardKey::KEY_ONE, "ONE"),
+    (KeyboardKey::KEY_TWO, "TWO"),
+    (KeyboardKey::KEY_THREE, "THREE"),
+    (KeyboardKey::KEY_FOUR, "FOUR"),
+    (KeyboardKey::KEY_FIVE, "FIVE"),
+    (KeyboardKey::KEY_SIX, "SIX"),
+    (KeyboardKey::KEY_SEVEN, "SEVEN"),
+    (KeyboardKey::KEY_EIGHT, "EIGHT"),
+    (KeyboardKey::KEY_NINE, "NINE"),
+    (KeyboardKey::KEY_F1, "F1"),
+    (KeyboardKey::KEY_F2, "F2"),
+    (KeyboardKey::KEY_F3, "F3"),
+    (KeyboardKey::KEY_F4, "F4"),
+    (KeyboardKey::KEY_F5, "F5"),
+    (KeyboardKey::KEY_F6, "F6"),
+    (KeyboardKey::KEY_F7, "F7"),
+    (KeyboardKey::KEY_F8, "F8"),
+    (KeyboardKey::KEY_F9, "F9"),
+    (KeyboardKey::KEY_F10, "F10"),
+    (KeyboardKey::KEY_F11, "F11"),
+    (KeyboardKey::KEY_F12, "F12"),
+    (KeyboardKey::KEY_SPACE, "SPACE"),
+    (KeyboardKey::KEY_ENTER, "ENTER"),
+    (KeyboardKey::KEY_TAB, "TAB"),
+    (KeyboardKey::KEY_BACKSPACE, "BACKSPACE"),
+    (KeyboardKey::KEY_INSERT, "INSERT"),
+    (KeyboardKey::KEY_DELETE, "DELETE"),
+    (KeyboardKey::KEY_HOME, "HOME"),
+    (KeyboardKey::KEY_END, "END"),
+    (KeyboardKey::KEY_PAGE_UP, "PAGE_UP"),
+    (KeyboardKey::KEY_PAGE_DOWN, "PAGE_DOWN"),
+    (KeyboardKey::KEY_UP, "UP"),
+    (KeyboardKey::KEY_DOWN, "DOWN"),
+    (KeyboardKey::KEY_LEFT, "LEFT"),
+    (KeyboardKey::KEY_RIGHT, "RIGHT"),
+    (KeyboardKey::KEY_LEFT_SHIFT, "LEFT_SHIFT"),
+    (KeyboardKey::KEY_RIGHT_SHIFT, "RIGHT_SHIFT"),
+    (KeyboardKey::KEY_LEFT_CONTROL, "LEFT_CONTROL"),
+    (KeyboardKey::KEY_RIGHT_CONTROL, "RIGHT_CONTROL"),
+    (KeyboardKey::KEY_LEFT_ALT, "LEFT_ALT"),
+    (KeyboardKey::KEY_RIGHT_ALT, "RIGHT_ALT"),
+    (KeyboardKey::KEY_APOSTROPHE, "APOSTROPHE"),
+    (KeyboardKey::KEY_COMMA, "COMMA"),
+    (KeyboardKey::KEY_MINUS, "MINUS"),
+    (KeyboardKey::KEY_PERIOD, "PERIOD"),
+    (KeyboardKey::KEY_SLASH, "SLASH"),
+    (KeyboardKey::KEY_SEMICOLON, "SEMICOLON"),
+    (KeyboardKey::KEY_EQUAL, "EQUAL"),
+    (KeyboardKey::KEY_LEFT_BRACKET, "LEFT_BRACKET"),
+    (KeyboardKey::KEY_RIGHT_BRACKET, "RIGHT_BRACKET"),
+    (KeyboardKey::KEY_BACKSLASH, "BACKSLASH"),
+    (KeyboardKey::KEY_GRAVE, "GRAVE"),
+    (KeyboardKey::KEY_KP_0, "KP_0"),
+    (KeyboardKey::KEY_KP_1, "KP_1"),
+    (KeyboardKey::KEY_KP_2, "KP_2"),
+    (KeyboardKey::KEY_KP_3, "KP_3"),
+    (KeyboardKey::KEY_KP_4, "KP_4"),
+    (KeyboardKey::KEY_KP_5, "KP_5"),
+    (KeyboardKey::KEY_KP_6, "KP_6"),
+    (KeyboardKey::KEY_KP_7, "KP_7"),
+    (KeyboardKey::KEY_KP_8, "KP_8"),
+    (KeyboardKey::KEY_KP_9, "KP_9"),
+    (KeyboardKey::KEY_KP_DECIMAL, "KP_DECIMAL"),
+    (KeyboardKey::KEY_KP_DIVIDE, "KP_DIVIDE"),
+    (KeyboardKey::KEY_KP_MULTIPLY, "KP_MULTIPLY"),
+    (KeyboardKey::KEY_KP_SUBTRACT, "KP_SUBTRACT"),
+    (KeyboardKey::KEY_KP_ADD, "KP_ADD"),
+    (KeyboardKey::KEY_KP_ENTER, "KP_ENTER"),
+];
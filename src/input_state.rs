@@ -0,0 +1,113 @@
+use raylib::prelude::*;
+use crate::input_map::{Action, InputMap};
+
+const ACTION_COUNT: usize = Action::ALL.len();
+// Las acciones se guardan como bits de un u128 en las repeticiones
+const _: () = assert!(ACTION_COUNT <= 128);
+
+/// Instantánea de la entrada en un frame: acciones mantenidas y pulsadas, y el ratón
+/// Se captura de raylib una vez por frame (`capture`) y la lógica de la cámara, la nave y el warp
+/// solo lee esta instantánea, así que se puede simular sin ventana construyéndola a mano
+/// (`hold`/`press`) o reproduciendo una grabada
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputState {
+    down: [bool; ACTION_COUNT],    // Acciones mantenidas, en el orden de `Action::ALL`
+    pressed: [bool; ACTION_COUNT], // Acciones pulsadas en este frame
+    pub mouse_delta: Vector2,      // Desplazamiento del ratón en píxeles desde el frame anterior
+    pub mouse_wheel: f32,          // Giro de la rueda en este frame
+    pub any_pressed: bool,         // Se pulsó cualquier tecla o botón (cancela el piloto automático)
+}
+
+impl InputState {
+    /// Sin ninguna entrada
+    pub fn new() -> Self {
+        InputState {
+            down: [false; ACTION_COUNT],
+            pressed: [false; ACTION_COUNT],
+            mouse_delta: Vector2::zero(),
+            mouse_wheel: 0.0,
+            any_pressed: false,
+        }
+    }
+
+    /// Lee la entrada de este frame con las asignaciones de `map`
+    /// Con el mapa suspendido (pantalla de controles abierta) la instantánea queda vacía
+    pub fn capture(window: &mut RaylibHandle, map: &InputMap) -> Self {
+        let mut state = InputState::new();
+        if map.suspended {
+            return state;
+        }
+
+        for action in Action::ALL {
+            state.down[action.index()] = map.is_down(window, action);
+            state.pressed[action.index()] = map.is_pressed(window, action);
+        }
+        state.mouse_delta = window.get_mouse_delta();
+        state.mouse_wheel = window.get_mouse_wheel_move();
+        state.any_pressed = window.get_key_pressed().is_some()
+            || window.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)
+            || window.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT)
+            || state.mouse_wheel != 0.0;
+        state
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down[action.index()]
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed[action.index()]
+    }
+
+    /// Mantiene `action` pulsada (sin contar como pulsación nueva)
+    pub fn hold(&mut self, action: Action) {
+        self.down[action.index()] = true;
+    }
+
+    /// Pulsa `action` en este frame
+    pub fn press(&mut self, action: Action) {
+        self.down[action.index()] = true;
+        self.pressed[action.index()] = true;
+        self.any_pressed = true;
+    }
+
+    /// Añade la entrada de un frame más reciente a la pendiente de simular: las teclas mantenidas
+    /// son las nuevas, pero las pulsaciones y el ratón se acumulan hasta que un paso los consuma
+    pub fn accumulate(&mut self, newer: &InputState) {
+        self.down = newer.down;
+        for (pressed, newer) in self.pressed.iter_mut().zip(newer.pressed) {
+            *pressed |= newer;
+        }
+        self.mouse_delta = Vector2::new(self.mouse_delta.x + newer.mouse_delta.x, self.mouse_delta.y + newer.mouse_delta.y);
+        self.mouse_wheel += newer.mouse_wheel;
+        self.any_pressed |= newer.any_pressed;
+    }
+
+    /// Solo las acciones mantenidas, sin pulsaciones ni ratón (lo que ve un paso de simulación
+    /// después de que otro del mismo frame consumiera las pulsaciones)
+    pub fn held(&self) -> InputState {
+        InputState { down: self.down, ..InputState::new() }
+    }
+
+    /// Acciones mantenidas y pulsadas como máscaras de bits (bit i = `Action::ALL[i]`)
+    pub fn action_bits(&self) -> (u128, u128) {
+        let to_bits = |flags: &[bool; ACTION_COUNT]| {
+            flags.iter().enumerate().filter(|(_, flag)| **flag).fold(0u128, |bits, (i, _)| bits | (1 << i))
+        };
+        (to_bits(&self.down), to_bits(&self.pressed))
+    }
+
+    /// Inverso de `action_bits`
+    pub fn set_action_bits(&mut self, down: u128, pressed: u128) {
+        for i in 0..ACTION_COUNT {
+            self.down[i] = down & (1 << i) != 0;
+            self.pressed[i] = pressed & (1 << i) != 0;
+        }
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,101 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::project_to_screen;
+use crate::uniforms::Uniforms;
+
+/// A body that can be labeled: name, world position and radius
+pub struct LabelTarget<'a> {
+    pub name: &'a str,
+    pub position: Vector3,
+    pub radius: f32,
+}
+
+/// Name labels drawn above bodies that are on screen and not hidden behind other geometry
+pub struct Labels {
+    pub visible: bool,
+    pub max_distance: f32, // Labels fade out completely at this camera distance
+    pub font_size: i32,
+    pub color: Color,
+}
+
+impl Labels {
+    pub fn new(max_distance: f32) -> Self {
+        Labels {
+            visible: true,
+            max_distance,
+            font_size: 14,
+            color: Color::new(220, 230, 255, 255),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Opacity for a body at `distance` from the camera: full up to 60% of the
+    /// maximum distance, then a smooth fade to zero
+    fn fade(&self, distance: f32) -> f32 {
+        let fade_start = self.max_distance * 0.6;
+        let t = ((distance - fade_start) / (self.max_distance - fade_start).max(0.0001)).clamp(0.0, 1.0);
+        1.0 - t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Draws the labels on top of the rendered frame
+    /// Occlusion uses the framebuffer depth at the body's center: the body is visible if
+    /// nothing closer than its nearest point to the camera was drawn there
+    /// `screen_scale` converts framebuffer pixels to window pixels (window size / render size)
+    pub fn draw(&self, d: &mut RaylibDrawHandle, framebuffer: &Framebuffer, uniforms: &Uniforms, targets: &[LabelTarget], screen_scale: f32) {
+        if !self.visible {
+            return;
+        }
+
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let viewport = &uniforms.viewport_matrix;
+        let camera = uniforms.camera_position;
+
+        for target in targets {
+            let to_camera = Vector3::new(
+                camera.x - target.position.x,
+                camera.y - target.position.y,
+                camera.z - target.position.z,
+            );
+            let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt();
+            if distance <= target.radius || distance > self.max_distance {
+                continue;
+            }
+
+            let Some(center) = project_to_screen(target.position, view, projection, viewport) else {
+                continue;
+            };
+            let cx = center.x as i32;
+            let cy = center.y as i32;
+            if cx < 0 || cy < 0 || cx >= framebuffer.width as i32 || cy >= framebuffer.height as i32 {
+                continue;
+            }
+
+            let front_point = Vector3::new(
+                target.position.x + to_camera.x / distance * target.radius,
+                target.position.y + to_camera.y / distance * target.radius,
+                target.position.z + to_camera.z / distance * target.radius,
+            );
+            let Some(front) = project_to_screen(front_point, view, projection, viewport) else {
+                continue;
+            };
+            if framebuffer.depth_at(cx, cy) + 0.0001 < front.z {
+                continue; // Something closer covers the body
+            }
+
+            // Anchor the text just above the body's top edge
+            let top = Vector3::new(target.position.x, target.position.y + target.radius, target.position.z);
+            let anchor_y = project_to_screen(top, view, projection, viewport).map_or(center.y, |p| p.y.min(center.y));
+
+            let alpha = self.fade(distance);
+            let text_width = measure_text(target.name, self.font_size);
+            let x = (center.x * screen_scale) as i32 - text_width / 2;
+            let y = (anchor_y * screen_scale) as i32 - self.font_size - 4;
+            d.draw_text(target.name, x + 1, y + 1, self.font_size, Color::new(0, 0, 0, (200.0 * alpha) as u8));
+            d.draw_text(target.name, x, y, self.font_size, self.color.fade(alpha));
+        }
+    }
+}
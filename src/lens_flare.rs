@@ -0,0 +1,154 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::project_to_screen;
+use crate::uniforms::Uniforms;
+
+/// One flare element placed along the axis from the sun through the screen center
+#[derive(Clone, Copy, Debug)]
+pub struct FlareGhost {
+    pub offset: f32,   // Position on the axis: 0.0 = sun, 1.0 = screen center, 2.0 = mirrored sun
+    pub radius: f32,   // Radius in pixels
+    pub color: Vector3, // Additive color at full visibility
+}
+
+/// Screen-space lens flare for a bright light source
+pub struct LensFlare {
+    pub halo_radius: f32,
+    pub halo_color: Vector3,
+    pub ghosts: Vec<FlareGhost>,
+    pub occlusion_samples: i32, // Samples per axis of the occlusion grid
+}
+
+impl LensFlare {
+    /// Default warm flare chain for the sun
+    pub fn new() -> Self {
+        LensFlare {
+            halo_radius: 90.0,
+            halo_color: Vector3::new(0.5, 0.4, 0.2),
+            ghosts: vec![
+                FlareGhost { offset: 0.4, radius: 14.0, color: Vector3::new(0.25, 0.2, 0.1) },
+                FlareGhost { offset: 0.8, radius: 30.0, color: Vector3::new(0.1, 0.15, 0.25) },
+                FlareGhost { offset: 1.2, radius: 10.0, color: Vector3::new(0.3, 0.15, 0.3) },
+                FlareGhost { offset: 1.5, radius: 45.0, color: Vector3::new(0.08, 0.2, 0.12) },
+                FlareGhost { offset: 1.9, radius: 20.0, color: Vector3::new(0.25, 0.12, 0.05) },
+            ],
+            occlusion_samples: 7,
+        }
+    }
+
+    /// Fraction of the light's disk that is not hidden by closer geometry (0.0 to 1.0)
+    /// The disk is sampled on a grid and compared against the depth of its nearest point,
+    /// so samples covered by the light's own surface still count as visible
+    pub fn visibility(&self, framebuffer: &Framebuffer, center: Vector3, radius_px: f32, front_depth: f32) -> f32 {
+        let n = self.occlusion_samples.max(1);
+        let mut visible = 0;
+        let mut total = 0;
+
+        for i in 0..n {
+            for j in 0..n {
+                // Grid cell centers in [-1, 1]
+                let u = (i as f32 + 0.5) / n as f32 * 2.0 - 1.0;
+                let v = (j as f32 + 0.5) / n as f32 * 2.0 - 1.0;
+                if u * u + v * v > 1.0 {
+                    continue;
+                }
+                total += 1;
+
+                let x = (center.x + u * radius_px) as i32;
+                let y = (center.y + v * radius_px) as i32;
+                let on_screen = x >= 0 && y >= 0 && x < framebuffer.width as i32 && y < framebuffer.height as i32;
+                if on_screen && framebuffer.depth_at(x, y) >= front_depth {
+                    visible += 1;
+                }
+            }
+        }
+
+        if total == 0 { 0.0 } else { visible as f32 / total as f32 }
+    }
+
+    /// Projects the light, tests occlusion against the depth buffer and composites the flare
+    /// Must run after all opaque geometry has been drawn
+    /// Only the view, projection, viewport and camera position of `uniforms` are used
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, light_position: Vector3, light_radius: f32) {
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let viewport = &uniforms.viewport_matrix;
+        let camera_position = uniforms.camera_position;
+
+        let Some(center) = project_to_screen(light_position, view, projection, viewport) else {
+            return;
+        };
+
+        // Nearest point of the light's sphere to the camera, for the occlusion depth
+        let to_camera = Vector3::new(
+            camera_position.x - light_position.x,
+            camera_position.y - light_position.y,
+            camera_position.z - light_position.z,
+        );
+        let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt();
+        if distance <= light_radius {
+            return;
+        }
+        let front_point = Vector3::new(
+            light_position.x + to_camera.x / distance * light_radius,
+            light_position.y + to_camera.y / distance * light_radius,
+            light_position.z + to_camera.z / distance * light_radius,
+        );
+        let Some(front) = project_to_screen(front_point, view, projection, viewport) else {
+            return;
+        };
+
+        // Approximate on-screen radius from a point on the sphere's edge
+        let edge_point = Vector3::new(light_position.x, light_position.y + light_radius, light_position.z);
+        let radius_px = project_to_screen(edge_point, view, projection, viewport)
+            .map(|edge| ((edge.x - center.x).powi(2) + (edge.y - center.y).powi(2)).sqrt())
+            .unwrap_or(1.0)
+            .max(1.0);
+
+        let visibility = self.visibility(framebuffer, center, radius_px * 0.8, front.z);
+        if visibility <= 0.0 {
+            return;
+        }
+
+        // Halo around the light itself
+        draw_glow(framebuffer, center.x, center.y, self.halo_radius, self.halo_color, visibility);
+
+        // Ghosts along the axis through the screen center
+        let screen_center_x = framebuffer.width as f32 / 2.0;
+        let screen_center_y = framebuffer.height as f32 / 2.0;
+        let axis_x = screen_center_x - center.x;
+        let axis_y = screen_center_y - center.y;
+        for ghost in &self.ghosts {
+            let x = center.x + axis_x * ghost.offset;
+            let y = center.y + axis_y * ghost.offset;
+            draw_glow(framebuffer, x, y, ghost.radius, ghost.color, visibility);
+        }
+    }
+}
+
+impl Default for LensFlare {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Additive soft disk with quadratic falloff toward the edge
+fn draw_glow(framebuffer: &mut Framebuffer, cx: f32, cy: f32, radius: f32, color: Vector3, intensity: f32) {
+    let min_x = (cx - radius).floor() as i32;
+    let max_x = (cx + radius).ceil() as i32;
+    let min_y = (cy - radius).floor() as i32;
+    let max_y = (cy + radius).ceil() as i32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            let dx = x as f32 - cx;
+            let dy = y as f32 - cy;
+            let d = (dx * dx + dy * dy).sqrt() / radius;
+            if d >= 1.0 {
+                continue;
+            }
+            let falloff = (1.0 - d) * (1.0 - d) * intensity;
+            framebuffer.add_color(x, y, Vector3::new(color.x * falloff, color.y * falloff, color.z * falloff));
+        }
+    }
+}
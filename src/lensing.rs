@@ -0,0 +1,89 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::project_to_screen;
+use crate::uniforms::Uniforms;
+
+/// Screen-space gravitational lens around a black hole
+/// Sizes are multiples of the event horizon's on-screen radius, so the effect keeps its shape
+/// at any distance
+pub struct GravitationalLens {
+    pub einstein_scale: f32, // Einstein ring radius
+    pub shadow_scale: f32,   // Disk that is always black (the horizon itself)
+    pub reach_scale: f32,    // Radius where the distortion fades out completely
+}
+
+impl GravitationalLens {
+    pub fn new() -> Self {
+        GravitationalLens {
+            einstein_scale: 2.2,
+            shadow_scale: 1.0,
+            reach_scale: 7.0,
+        }
+    }
+
+    /// Projects the black hole and bends everything drawn behind it
+    /// Must run after the opaque geometry and the skybox, and before translucent surfaces
+    /// (the accretion disk is drawn afterwards so it is not distorted twice)
+    /// Only the view, projection, viewport and camera position of `uniforms` are used
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, position: Vector3, radius: f32) {
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let viewport = &uniforms.viewport_matrix;
+        let camera_position = uniforms.camera_position;
+
+        let Some(center) = project_to_screen(position, view, projection, viewport) else {
+            return;
+        };
+
+        // Nearest point of the horizon to the camera: anything closer is in front of the lens
+        let to_camera = Vector3::new(
+            camera_position.x - position.x,
+            camera_position.y - position.y,
+            camera_position.z - position.z,
+        );
+        let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt();
+        if distance <= radius {
+            return;
+        }
+        let front_point = Vector3::new(
+            position.x + to_camera.x / distance * radius,
+            position.y + to_camera.y / distance * radius,
+            position.z + to_camera.z / distance * radius,
+        );
+        let Some(front) = project_to_screen(front_point, view, projection, viewport) else {
+            return;
+        };
+
+        // Approximate on-screen radius from a point on the silhouette: perpendicular to the
+        // line of sight, so the estimate holds when looking down on the black hole too
+        let side = if to_camera.y.abs() < distance * 0.99 {
+            Vector3::new(to_camera.z, 0.0, -to_camera.x)
+        } else {
+            Vector3::new(0.0, to_camera.z, -to_camera.y)
+        };
+        let side_length = (side.x * side.x + side.y * side.y + side.z * side.z).sqrt().max(0.0001);
+        let edge_point = Vector3::new(
+            position.x + side.x / side_length * radius,
+            position.y + side.y / side_length * radius,
+            position.z + side.z / side_length * radius,
+        );
+        let radius_px = project_to_screen(edge_point, view, projection, viewport)
+            .map(|edge| ((edge.x - center.x).powi(2) + (edge.y - center.y).powi(2)).sqrt())
+            .unwrap_or(1.0)
+            .max(1.0);
+
+        framebuffer.lensing_pass(
+            Vector2::new(center.x, center.y),
+            radius_px * self.einstein_scale,
+            radius_px * self.shadow_scale,
+            radius_px * self.reach_scale,
+            front.z,
+        );
+    }
+}
+
+impl Default for GravitationalLens {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,58 @@
+//! Rasterizador por software del sistema solar.
+//!
+//! Todo el pipeline (framebuffer, shaders, rasterización de triángulos, matrices,
+//! cámara y carga de modelos OBJ) vive en esta biblioteca para poder reutilizarlo
+//! desde otros proyectos, junto con la escena que dibuja (`Scene`, sus cuerpos como
+//! entidades de `ecs` y sus efemérides). El juego (la nave, el HUD, el warp, la consola...)
+//! son módulos del binario, declarados en `main.rs`.
+//!
+//! Uso mínimo:
+//!
+//! ```ignore
+//! let renderer = Renderer::new(Light::new(Vector3::new(5.0, 5.0, 5.0)));
+//! let mut framebuffer = Framebuffer::new(800, 600);
+//! framebuffer.clear();
+//! let sphere = Obj::generate_sphere(1.0, 32);
+//! renderer.draw_indexed_mesh(&mut framebuffer, &uniforms, &sphere.vertices, &sphere.indices, &PlanetType::Rocky);
+//! ```
+
+pub mod camera;
+pub mod clock;
+pub mod color;
+pub mod debug_draw;
+pub mod ecs;
+pub mod ephemeris;
+pub mod fragment;
+pub mod framebuffer;
+#[cfg(feature = "gpu")]
+pub mod gpu_shading;
+pub mod input_map;
+pub mod input_state;
+pub mod light;
+pub mod line;
+pub mod material;
+pub mod matrix;
+pub mod nebula;
+pub mod obj;
+pub mod presets;
+pub mod render_stats;
+pub mod renderer;
+pub mod scene;
+pub mod shaders;
+pub mod spatial_index;
+pub mod star;
+pub mod triangle;
+pub mod uniforms;
+pub mod units;
+pub mod vertex;
+
+pub use camera::{Camera, CameraMode};
+pub use framebuffer::Framebuffer;
+pub use light::Light;
+pub use material::Material;
+pub use obj::Obj;
+pub use renderer::Renderer;
+pub use scene::Scene;
+pub use shaders::{FnShader, PlanetType, SurfaceShader, TintShader};
+pub use uniforms::Uniforms;
+pub use vertex::Vertex;
@@ -1,13 +1,19 @@
-#![allow(dead_code)]
-
 use raylib::prelude::*;
 
 pub struct Light {
     pub position: Vector3,
+    pub color: Vector3,
 }
 
 impl Light {
     pub fn new(position: Vector3) -> Self {
-        Light { position }
+        Light {
+            position,
+            color: Vector3::new(1.0, 1.0, 1.0), // Luz blanca
+        }
+    }
+
+    pub fn with_color(position: Vector3, color: Vector3) -> Self {
+        Light { position, color }
     }
 }
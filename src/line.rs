@@ -1,19 +1,56 @@
-#![allow(dead_code)]
-
 use crate::fragment::Fragment;
+use crate::framebuffer::ScissorRect;
 use crate::vertex::Vertex;
 use raylib::math::Vector3;
 
-pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
+/// Rasterizes the segment between two screen-space vertices with Bresenham's algorithm.
+/// The segment is first clipped to the scissor rectangle (Liang-Barsky), so endpoints far
+/// off-screen cost nothing. Depth and vertex color are interpolated along the segment.
+pub fn line(a: &Vertex, b: &Vertex, scissor: &ScissorRect) -> Vec<Fragment> {
     let mut fragments = Vec::new();
+    if scissor.is_empty() {
+        return fragments;
+    }
 
     let start = a.transformed_position;
     let end = b.transformed_position;
+    let dir_x = end.x - start.x;
+    let dir_y = end.y - start.y;
+
+    // Liang-Barsky: shrink the parameter range [t0, t1] to the part inside the rectangle
+    let min_x = scissor.x as f32;
+    let min_y = scissor.y as f32;
+    let max_x = (scissor.x + scissor.width) as f32 - 0.001;
+    let max_y = (scissor.y + scissor.height) as f32 - 0.001;
+    let mut t0: f32 = 0.0;
+    let mut t1: f32 = 1.0;
+    for (p, q) in [
+        (-dir_x, start.x - min_x),
+        (dir_x, max_x - start.x),
+        (-dir_y, start.y - min_y),
+        (dir_y, max_y - start.y),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return fragments; // Parallel to this border and outside it
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    if t0 > t1 || !t0.is_finite() || !t1.is_finite() {
+        return fragments;
+    }
 
-    let mut x0 = start.x as i32;
-    let mut y0 = start.y as i32;
-    let x1 = end.x as i32;
-    let y1 = end.y as i32;
+    let mut x0 = (start.x + dir_x * t0) as i32;
+    let mut y0 = (start.y + dir_y * t0) as i32;
+    let x1 = (start.x + dir_x * t1) as i32;
+    let y1 = (start.y + dir_y * t1) as i32;
 
     let dx = (x1 - x0).abs();
     let dy = (y1 - y0).abs();
@@ -23,11 +60,18 @@ pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
 
     let mut err = if dx > dy { dx / 2 } else { -dy / 2 };
 
+    let steps = dx.max(dy).max(1) as f32;
+    let mut step = 0;
     loop {
-        let z = start.z + (end.z - start.z) * (x0 - start.x as i32) as f32 / (end.x - start.x) as f32;
-        // For now, we'll just use white for the line color.
-        // A more advanced implementation would interpolate the vertex colors.
-        fragments.push(Fragment::new(x0 as f32, y0 as f32, Vector3::new(1.0, 1.0, 1.0), z));
+        // Position along the original segment, from the clipped range
+        let t = t0 + (t1 - t0) * step as f32 / steps;
+        let z = start.z + (end.z - start.z) * t;
+        let color = Vector3::new(
+            a.color.x + (b.color.x - a.color.x) * t,
+            a.color.y + (b.color.y - a.color.y) * t,
+            a.color.z + (b.color.z - a.color.z) * t,
+        );
+        fragments.push(Fragment::new(x0 as f32, y0 as f32, color, z));
 
         if x0 == x1 && y0 == y1 { break; }
 
@@ -40,6 +84,7 @@ pub fn line(a: &Vertex, b: &Vertex) -> Vec<Fragment> {
             err += dx;
             y0 += sy;
         }
+        step += 1;
     }
 
     fragments
@@ -0,0 +1,152 @@
+use raylib::prelude::*;
+use crate::camera::{Camera, CameraMode};
+use crate::framebuffer::Framebuffer;
+use crate::input_map::Action;
+use crate::input_state::InputState;
+use crate::matrix::Projection;
+use crate::renderer::Renderer;
+use crate::scene::Scene;
+use crate::uniforms::Uniforms;
+use std::f32::consts::PI;
+
+/// Vista de mapa del sistema: la cámara se coloca sobre la eclíptica mirando hacia abajo y el
+/// render pasa a una proyección ortográfica (`Projection::Orthographic`), así que las órbitas se
+/// ven a escala real, sin perspectiva. Es el mismo pipeline que la vista normal; solo cambian la
+/// cámara y la proyección. La simulación sigue corriendo, pero la entrada de vuelo mueve el mapa
+pub struct MapView {
+    active: bool,
+    pub center: Vector2,   // Punto del plano eclíptico (x, z) en el centro de la pantalla
+    pub height: f32,       // Unidades del mundo que caben en el alto de la pantalla
+    pub pan_speed: f32,    // Fracción de la pantalla que se recorre por segundo
+    pub zoom_step: f32,    // Factor de `height` por cada paso de la rueda
+}
+
+impl MapView {
+    const MIN_HEIGHT: f32 = 5.0;
+
+    pub fn new() -> Self {
+        MapView {
+            active: false,
+            center: Vector2::zero(),
+            height: 100.0,
+            pan_speed: 0.6,
+            zoom_step: 0.85,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Entra en la vista de mapa encuadrando un sistema de radio `extent`
+    pub fn enter(&mut self, extent: f32) {
+        self.center = Vector2::zero();
+        self.height = (extent * 2.2).max(Self::MIN_HEIGHT);
+        self.active = true;
+    }
+
+    pub fn exit(&mut self) {
+        self.active = false;
+    }
+
+    pub fn toggle(&mut self, extent: f32) {
+        if self.active {
+            self.exit();
+        } else {
+            self.enter(extent);
+        }
+    }
+
+    /// Desplaza el mapa con las acciones de vuelo (arriba en pantalla es -Z) y hace zoom con la
+    /// rueda; el modificador acelera el desplazamiento. `delta_time` es el tiempo real del frame
+    pub fn update(&mut self, input: &InputState, extent: f32, delta_time: f32) {
+        if input.mouse_wheel != 0.0 {
+            let max_height = (extent * 4.0).max(Self::MIN_HEIGHT);
+            self.height = (self.height * self.zoom_step.powf(input.mouse_wheel)).clamp(Self::MIN_HEIGHT, max_height);
+        }
+        let axis = |positive: Action, negative: Action| {
+            (input.is_down(positive) as i32 - input.is_down(negative) as i32) as f32
+        };
+        let boost = if input.is_down(Action::Modifier) { 3.0 } else { 1.0 };
+        let step = self.pan_speed * self.height * boost * delta_time;
+        self.center.x += axis(Action::StrafeRight, Action::StrafeLeft) * step;
+        self.center.y -= axis(Action::Forward, Action::Backward) * step;
+        // Sin alejarse del sistema más que su propio radio
+        let limit = extent.max(1.0);
+        self.center.x = self.center.x.clamp(-limit, limit);
+        self.center.y = self.center.y.clamp(-limit, limit);
+    }
+
+    /// Coloca `camera` sobre el centro del mapa mirando hacia abajo (solo la copia que se renderiza)
+    /// La altura solo tiene que dejar todo el sistema entre los planos near y far
+    pub fn apply(&self, camera: &mut Camera, extent: f32) {
+        let altitude = extent * 2.0 + 10.0;
+        camera.up = Vector3::new(0.0, 0.0, -1.0);
+        camera.look_from(
+            Vector3::new(self.center.x, altitude, self.center.y),
+            Vector3::new(self.center.x, 0.0, self.center.y),
+        );
+        camera.clear_collision();
+        camera.track_planet(None);
+        camera.mode = CameraMode::FreeFly;
+        camera.update_transition(f32::INFINITY);
+    }
+
+    /// Proyección ortográfica del mapa para una pantalla de proporción `aspect`
+    pub fn projection(&self, aspect: f32, far: f32) -> Projection {
+        Projection::Orthographic { height: self.height, aspect, near: 0.1, far }
+    }
+
+    /// Órbitas de los planetas y marcadores de los cuerpos y la nave, dibujados en 3D con la
+    /// cámara del mapa para que siempre se vean aunque los cuerpos ocupen menos de un píxel
+    pub fn render_overlay(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, scene: &Scene, ship_position: Vector3) {
+        const SEGMENTS: usize = 64;
+        let orbit_color = Vector3::new(0.18, 0.22, 0.3);
+        for planet in &scene.planets {
+            let point = |i: usize| {
+                let angle = i as f32 / SEGMENTS as f32 * 2.0 * PI;
+                Vector3::new(planet.orbital_radius * angle.cos(), 0.0, planet.orbital_radius * angle.sin())
+            };
+            for i in 0..SEGMENTS {
+                renderer.draw_line_3d(framebuffer, uniforms, point(i), point(i + 1), orbit_color);
+            }
+        }
+
+        for planet in &scene.planets {
+            renderer.draw_point_3d(framebuffer, uniforms, planet.position(), planet.planet_type.albedo(), 2);
+        }
+        renderer.draw_point_3d(framebuffer, uniforms, ship_position, Vector3::new(0.3, 1.0, 0.55), 3);
+    }
+
+    /// Panel con la escala del mapa y la ayuda de los controles
+    pub fn draw(&self, d: &mut RaylibDrawHandle, width: i32, height: i32) {
+        if !self.active {
+            return;
+        }
+
+        let accent = Color::new(140, 200, 255, 255);
+        let text_color = Color::new(220, 220, 220, 255);
+        let panel_width = 240;
+        let panel_height = 74;
+        let x = width - panel_width - 10;
+        let y = height - panel_height - 10;
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 170));
+        d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+        d.draw_text("MAPA DEL SISTEMA", x + 10, y + 8, 16, accent);
+
+        // Barra de escala: una décima parte del alto de la pantalla
+        let bar = height / 10;
+        let units = self.height / 10.0;
+        d.draw_rectangle(x + 10, y + 34, 2, 8, text_color);
+        d.draw_rectangle(x + 10, y + 37, bar, 2, text_color);
+        d.draw_rectangle(x + 8 + bar, y + 34, 2, 8, text_color);
+        d.draw_text(&format!("{:.0} u", units), x + 18 + bar, y + 33, 10, text_color);
+        d.draw_text("Vuelo: desplazar   Rueda: zoom", x + 10, y + 54, 10, text_color);
+    }
+}
+
+impl Default for MapView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,42 @@
+use raylib::prelude::*;
+
+/// Surface properties consumed by the lighting stage (Blinn-Phong model)
+/// diffuse_color: albedo used for the Lambertian term
+/// specular_color: tint of the specular highlight
+/// shininess: Blinn-Phong exponent (higher = smaller, sharper highlight)
+/// emissive: linear light the surface gives off by itself; it skips lighting and feeds bloom
+#[derive(Clone, Copy, Debug)]
+pub struct Material {
+    pub diffuse_color: Vector3,
+    pub specular_color: Vector3,
+    pub shininess: f32,
+    pub emissive: Vector3,
+}
+
+impl Material {
+    pub fn new(diffuse_color: Vector3, specular_color: Vector3, shininess: f32) -> Self {
+        Material {
+            diffuse_color,
+            specular_color,
+            shininess,
+            emissive: Vector3::zero(),
+        }
+    }
+
+    /// Same material glowing with `emissive` (linear color)
+    pub fn with_emissive(self, emissive: Vector3) -> Self {
+        Material { emissive, ..self }
+    }
+
+    /// Purely diffuse material (no specular highlight)
+    pub fn matte(diffuse_color: Vector3) -> Self {
+        Material::new(diffuse_color, Vector3::zero(), 1.0)
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        // Base gray used by the rasterizer before materials existed
+        Material::matte(Vector3::new(0.5, 0.5, 0.5))
+    }
+}
@@ -1,5 +1,3 @@
-#![allow(dead_code)]
-
 use raylib::prelude::*;
 
 /// Creates a 4x4 matrix from 16 float values, specified in traditional row-major order.
@@ -90,6 +88,58 @@ pub fn create_model_matrix(translation: Vector3, scale: f32, rotation: Vector3)
     translation_matrix * rotation_matrix * scale_matrix
 }
 
+/// Applies the inverse of the rotation used by `create_model_matrix` to a direction vector
+/// Useful to bring a world-space direction (e.g. towards the light) into object space
+pub fn inverse_rotate_vector(v: Vector3, rotation: Vector3) -> Vector3 {
+    // create_model_matrix rotates with Rz * Ry * Rx, so the inverse is Rx^-1 * Ry^-1 * Rz^-1
+    let (sin_z, cos_z) = (-rotation.z).sin_cos();
+    let after_z = Vector3::new(
+        cos_z * v.x - sin_z * v.y,
+        sin_z * v.x + cos_z * v.y,
+        v.z,
+    );
+
+    let (sin_y, cos_y) = (-rotation.y).sin_cos();
+    let after_y = Vector3::new(
+        cos_y * after_z.x + sin_y * after_z.z,
+        after_z.y,
+        -sin_y * after_z.x + cos_y * after_z.z,
+    );
+
+    let (sin_x, cos_x) = (-rotation.x).sin_cos();
+    Vector3::new(
+        after_y.x,
+        cos_x * after_y.y - sin_x * after_y.z,
+        sin_x * after_y.y + cos_x * after_y.z,
+    )
+}
+
+/// Applies the rotation used by `create_model_matrix` to a direction vector
+/// The inverse of `inverse_rotate_vector`: brings an object-space direction into world space
+pub fn rotate_vector(v: Vector3, rotation: Vector3) -> Vector3 {
+    // Same order as create_model_matrix: Rx first, then Ry, then Rz
+    let (sin_x, cos_x) = rotation.x.sin_cos();
+    let after_x = Vector3::new(
+        v.x,
+        cos_x * v.y - sin_x * v.z,
+        sin_x * v.y + cos_x * v.z,
+    );
+
+    let (sin_y, cos_y) = rotation.y.sin_cos();
+    let after_y = Vector3::new(
+        cos_y * after_x.x + sin_y * after_x.z,
+        after_x.y,
+        -sin_y * after_x.x + cos_y * after_x.z,
+    );
+
+    let (sin_z, cos_z) = rotation.z.sin_cos();
+    Vector3::new(
+        cos_z * after_y.x - sin_z * after_y.y,
+        sin_z * after_y.x + cos_z * after_y.y,
+        after_y.z,
+    )
+}
+
 /// Creates a view matrix using camera position, target, and up vector
 /// This implements a lookAt matrix for camera transformations
 pub fn create_view_matrix(eye: Vector3, target: Vector3, up: Vector3) -> Matrix {
@@ -150,6 +200,45 @@ pub fn create_projection_matrix(fov_y: f32, aspect: f32, near: f32, far: f32) ->
     )
 }
 
+/// Creates an orthographic projection matrix (no perspective division)
+/// left, right, bottom, top: View volume bounds in view space
+/// near, far: Clipping plane distances
+pub fn create_orthographic_matrix(left: f32, right: f32, bottom: f32, top: f32, near: f32, far: f32) -> Matrix {
+    new_matrix4(
+        2.0 / (right - left), 0.0, 0.0, -(right + left) / (right - left),
+        0.0, 2.0 / (top - bottom), 0.0, -(top + bottom) / (top - bottom),
+        0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near),
+        0.0, 0.0, 0.0, 1.0,
+    )
+}
+
+/// Camera projection, so the same pipeline renders both the perspective views and the
+/// orthographic top-down map. Everything downstream only sees the matrix: the helpers below
+/// (`projection_far`, `view_distance`, `pixel_ray`...) work out which kind it is from it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection {
+    /// `fov_y` is the vertical field of view in radians
+    Perspective { fov_y: f32, aspect: f32, near: f32, far: f32 },
+    /// `height` world units fit vertically on screen at any distance
+    Orthographic { height: f32, aspect: f32, near: f32, far: f32 },
+}
+
+impl Projection {
+    pub fn matrix(&self) -> Matrix {
+        match *self {
+            Projection::Perspective { fov_y, aspect, near, far } => create_projection_matrix(fov_y, aspect, near, far),
+            Projection::Orthographic { height, aspect, near, far } => {
+                let (half_width, half_height) = (height * aspect / 2.0, height / 2.0);
+                create_orthographic_matrix(-half_width, half_width, -half_height, half_height, near, far)
+            }
+        }
+    }
+
+    pub fn is_orthographic(&self) -> bool {
+        matches!(self, Projection::Orthographic { .. })
+    }
+}
+
 /// Creates a viewport matrix to transform NDC coordinates to screen space
 /// x, y: Viewport position (typically 0, 0)
 /// width, height: Viewport dimensions in pixels
@@ -163,4 +252,209 @@ pub fn create_viewport_matrix(x: f32, y: f32, width: f32, height: f32) -> Matrix
         0.0, 0.0, 1.0, 0.0,
         0.0, 0.0, 0.0, 1.0,
     )
-}
\ No newline at end of file
+}
+
+// This function manually multiplies a 4x4 matrix with a 4D vector (in homogeneous coordinates)
+pub fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
+    Vector4::new(
+        matrix.m0 * vector.x + matrix.m4 * vector.y + matrix.m8 * vector.z + matrix.m12 * vector.w,
+        matrix.m1 * vector.x + matrix.m5 * vector.y + matrix.m9 * vector.z + matrix.m13 * vector.w,
+        matrix.m2 * vector.x + matrix.m6 * vector.y + matrix.m10 * vector.z + matrix.m14 * vector.w,
+        matrix.m3 * vector.x + matrix.m7 * vector.y + matrix.m11 * vector.z + matrix.m15 * vector.w,
+    )
+}
+
+/// Mathematical product `a * b` of two column-major matrices: the result applies `b` first, then `a`
+/// (raylib's `Mul` operator uses the opposite order, so it is spelled out here)
+pub fn multiply_matrices(a: &Matrix, b: &Matrix) -> Matrix {
+    let col = |m: &Matrix, c: usize| -> [f32; 4] {
+        match c {
+            0 => [m.m0, m.m1, m.m2, m.m3],
+            1 => [m.m4, m.m5, m.m6, m.m7],
+            2 => [m.m8, m.m9, m.m10, m.m11],
+            _ => [m.m12, m.m13, m.m14, m.m15],
+        }
+    };
+    let mut out = [[0.0f32; 4]; 4];
+    for (c, column) in out.iter_mut().enumerate() {
+        let v = col(b, c);
+        let transformed = multiply_matrix_vector4(a, &Vector4::new(v[0], v[1], v[2], v[3]));
+        *column = [transformed.x, transformed.y, transformed.z, transformed.w];
+    }
+    Matrix {
+        m0: out[0][0], m1: out[0][1], m2: out[0][2], m3: out[0][3],
+        m4: out[1][0], m5: out[1][1], m6: out[1][2], m7: out[1][3],
+        m8: out[2][0], m9: out[2][1], m10: out[2][2], m11: out[2][3],
+        m12: out[3][0], m13: out[3][1], m14: out[3][2], m15: out[3][3],
+    }
+}
+
+/// View-space position of a world point, subtracting the camera position in f64 before rotating
+/// `view` must be a look-at matrix built from `eye` (only its rotation is used). Going through
+/// `view`'s own translation would add two large, nearly opposite f32 values far from the origin,
+/// and the error of that cancellation shows up as vertex jitter
+pub fn camera_relative_point(view: &Matrix, eye: Vector3, point: Vector3) -> Vector4 {
+    let x = (point.x as f64 - eye.x as f64) as f32;
+    let y = (point.y as f64 - eye.y as f64) as f32;
+    let z = (point.z as f64 - eye.z as f64) as f32;
+    Vector4::new(
+        view.m0 * x + view.m4 * y + view.m8 * z,
+        view.m1 * x + view.m5 * y + view.m9 * z,
+        view.m2 * x + view.m6 * y + view.m10 * z,
+        1.0,
+    )
+}
+
+/// `projection * view * model` composed relative to the camera: the model translation is taken
+/// relative to `eye` (in f64) before the view rotation is applied, so only small numbers reach
+/// f32 and objects far from the origin don't jitter. `view` must be a look-at matrix from `eye`
+pub fn camera_relative_mvp(projection: &Matrix, view: &Matrix, model: &Matrix, eye: Vector3) -> Matrix {
+    let rotation = Matrix { m12: 0.0, m13: 0.0, m14: 0.0, ..*view };
+    let linear = Matrix { m12: 0.0, m13: 0.0, m14: 0.0, ..*model };
+    let mut model_view = multiply_matrices(&rotation, &linear);
+    let translation = camera_relative_point(view, eye, Vector3::new(model.m12, model.m13, model.m14));
+    model_view.m12 = translation.x;
+    model_view.m13 = translation.y;
+    model_view.m14 = translation.z;
+    multiply_matrices(projection, &model_view)
+}
+
+/// Four homogeneous points in structure-of-arrays layout (one array per coordinate)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Points4 {
+    pub x: [f32; 4],
+    pub y: [f32; 4],
+    pub z: [f32; 4],
+    pub w: [f32; 4],
+}
+
+/// Transforms four points (w = 1) by the same matrix at once.
+/// Each output coordinate is a multiply-add over whole `[f32; 4]` lanes, which the compiler
+/// lowers to 4-wide SIMD instructions on stable Rust (no `std::simd` needed).
+pub fn transform_points4(matrix: &Matrix, x: &[f32; 4], y: &[f32; 4], z: &[f32; 4]) -> Points4 {
+    let mut out = Points4::default();
+    for i in 0..4 {
+        out.x[i] = matrix.m0 * x[i] + matrix.m4 * y[i] + matrix.m8 * z[i] + matrix.m12;
+        out.y[i] = matrix.m1 * x[i] + matrix.m5 * y[i] + matrix.m9 * z[i] + matrix.m13;
+        out.z[i] = matrix.m2 * x[i] + matrix.m6 * y[i] + matrix.m10 * z[i] + matrix.m14;
+        out.w[i] = matrix.m3 * x[i] + matrix.m7 * y[i] + matrix.m11 * z[i] + matrix.m15;
+    }
+    out
+}
+
+/// Transforms a direction (w = 0) by a model matrix and renormalizes it
+/// Valid for normals as long as the scale is uniform, which is all `create_model_matrix` builds
+pub fn transform_normal(matrix: &Matrix, normal: Vector3) -> Vector3 {
+    let n = multiply_matrix_vector4(matrix, &Vector4::new(normal.x, normal.y, normal.z, 0.0));
+    let length = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt();
+    if length > 0.0 {
+        Vector3::new(n.x / length, n.y / length, n.z / length)
+    } else {
+        Vector3::new(n.x, n.y, n.z)
+    }
+}
+
+/// Whether `projection` was built with `create_orthographic_matrix` (its last row is 0, 0, 0, 1,
+/// while a perspective one copies -z into w)
+pub fn is_orthographic(projection: &Matrix) -> bool {
+    projection.m15 != 0.0
+}
+
+/// Recovers the far plane distance from a matrix built with `create_projection_matrix`
+/// or `create_orthographic_matrix`
+pub fn projection_far(projection: &Matrix) -> f32 {
+    if is_orthographic(projection) {
+        // m10 = -2 / (far - near), m14 = -(far + near) / (far - near)
+        (projection.m14 - 1.0) / projection.m10
+    } else {
+        // m10 = -(far + near) / (far - near), m14 = -2 * far * near / (far - near)
+        projection.m14 / (projection.m10 + 1.0)
+    }
+}
+
+/// Recovers the near plane distance from a matrix built with `create_projection_matrix`
+/// or `create_orthographic_matrix`
+pub fn projection_near(projection: &Matrix) -> f32 {
+    if is_orthographic(projection) {
+        (projection.m14 + 1.0) / projection.m10
+    } else {
+        projection.m14 / (projection.m10 - 1.0)
+    }
+}
+
+/// Distance in front of the camera of a clip-space point: its w under a perspective projection;
+/// an orthographic one leaves w at 1, so the distance is recovered from z instead
+pub fn view_distance(clip_z: f32, clip_w: f32, projection: &Matrix) -> f32 {
+    if is_orthographic(projection) {
+        // clip z = m10 * view z + m14, and the camera looks down -Z
+        (projection.m14 - clip_z) / projection.m10
+    } else {
+        clip_w
+    }
+}
+
+/// Logarithmic depth in [-1, 1] for a view-space distance (clip w)
+/// Unlike the hyperbolic NDC depth, precision is spread over the whole range,
+/// so the far plane can be pushed out without z-fighting between nearby surfaces
+/// It is not linear in screen space, so triangles interpolate the view distance and convert it
+/// per fragment (see `triangle`). A point behind the camera gets the far end of the range, so
+/// it never wins a depth test against anything in front
+pub fn logarithmic_depth(view_distance: f32, far: f32) -> f32 {
+    if view_distance < 0.0 {
+        return 1.0;
+    }
+    (1.0 + view_distance).log2() / (1.0 + far).log2() * 2.0 - 1.0
+}
+
+/// Inverse of `logarithmic_depth`: view-space distance for a stored depth value
+pub fn linearize_logarithmic_depth(depth: f32, far: f32) -> f32 {
+    ((depth + 1.0) * 0.5 * (1.0 + far).log2()).exp2() - 1.0
+}
+
+/// Projects a world-space point to screen space with the same transform chain as the vertex shader
+/// Returns (screen x, screen y, depth), or None if the point is behind the camera
+pub fn project_to_screen(point: Vector3, view: &Matrix, projection: &Matrix, viewport: &Matrix) -> Option<Vector3> {
+    let world = Vector4::new(point.x, point.y, point.z, 1.0);
+    let view_position = multiply_matrix_vector4(view, &world);
+    let clip = multiply_matrix_vector4(projection, &view_position);
+    if view_distance(clip.z, clip.w, projection) <= 0.0 {
+        return None;
+    }
+    Some(clip_to_screen(&clip, projection, viewport))
+}
+
+/// Normalized world-space view direction through the center of pixel (x, y) of a width x height screen
+/// The inverse of `project_to_screen` for points at infinity: used by the background passes
+/// Under an orthographic projection every ray is parallel, so all pixels get the view direction
+pub fn pixel_ray(x: i32, y: i32, width: i32, height: i32, view: &Matrix, projection: &Matrix) -> Vector3 {
+    // Pixel center -> NDC (screen Y grows downward) -> view-space ray at depth 1
+    let ndc_x = (2.0 * x as f32 + 1.0) / width as f32 - 1.0;
+    let ndc_y = 1.0 - (2.0 * y as f32 + 1.0) / height as f32;
+    let (view_x, view_y) = if is_orthographic(projection) {
+        (0.0, 0.0)
+    } else {
+        (ndc_x / projection.m0, ndc_y / projection.m5)
+    };
+
+    // Camera basis from the rows of the view matrix
+    let right = Vector3::new(view.m0, view.m4, view.m8);
+    let up = Vector3::new(view.m1, view.m5, view.m9);
+    let back = Vector3::new(view.m2, view.m6, view.m10);
+
+    // View -> world: transpose of the view rotation; the camera looks down -Z
+    let dir = Vector3::new(
+        right.x * view_x + up.x * view_y - back.x,
+        right.y * view_x + up.y * view_y - back.y,
+        right.z * view_x + up.z * view_y - back.z,
+    );
+    let length = (dir.x * dir.x + dir.y * dir.y + dir.z * dir.z).sqrt();
+    Vector3::new(dir.x / length, dir.y / length, dir.z / length)
+}
+
+/// Perspective divide, logarithmic depth and viewport for a clip-space point in front of the camera
+pub fn clip_to_screen(clip: &Vector4, projection: &Matrix, viewport: &Matrix) -> Vector3 {
+    let depth = logarithmic_depth(view_distance(clip.z, clip.w, projection), projection_far(projection));
+    let ndc = Vector4::new(clip.x / clip.w, clip.y / clip.w, depth, 1.0);
+    let screen = multiply_matrix_vector4(viewport, &ndc);
+    Vector3::new(screen.x, screen.y, screen.z)
+}
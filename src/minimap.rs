@@ -0,0 +1,129 @@
+use raylib::prelude::*;
+use crate::matrix::{create_orthographic_matrix, create_view_matrix, create_viewport_matrix, multiply_matrix_vector4};
+use crate::scene::Scene;
+use crate::shaders::PlanetType;
+
+/// Top-down overview of the system drawn as a 2D inset in a corner of the screen
+/// World positions go through a top-down view matrix and an orthographic projection
+/// sized to the scene, then through a viewport matrix that targets the inset rectangle
+pub struct Minimap {
+    pub x: i32,
+    pub y: i32,
+    pub size: i32,
+    pub visible: bool,
+}
+
+impl Minimap {
+    pub fn new(x: i32, y: i32, size: i32) -> Self {
+        Minimap { x, y, size, visible: true }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Combined world -> inset pixel transform for a scene of radius `world_radius`
+    fn transform(&self, world_radius: f32) -> (Matrix, Matrix, Matrix) {
+        // Looking straight down the Y axis; -Z points up on the map
+        let view = create_view_matrix(
+            Vector3::new(0.0, 1000.0, 0.0),
+            Vector3::zero(),
+            Vector3::new(0.0, 0.0, -1.0),
+        );
+        let projection = create_orthographic_matrix(-world_radius, world_radius, -world_radius, world_radius, 0.1, 2000.0);
+        let viewport = create_viewport_matrix(self.x as f32, self.y as f32, self.size as f32, self.size as f32);
+        (view, projection, viewport)
+    }
+
+    fn project(transform: &(Matrix, Matrix, Matrix), point: Vector3) -> Vector2 {
+        let (view, projection, viewport) = transform;
+        let world = Vector4::new(point.x, point.y, point.z, 1.0);
+        let clip = multiply_matrix_vector4(projection, &multiply_matrix_vector4(view, &world));
+        // Orthographic: w stays 1, no perspective division needed
+        let screen = multiply_matrix_vector4(viewport, &clip);
+        Vector2::new(screen.x, screen.y)
+    }
+
+    /// Draws the sun, every orbit, planets, moons and the ship (pointing along `ship_heading`)
+    pub fn draw(&self, d: &mut RaylibDrawHandle, scene: &Scene, time: f32, ship_position: Vector3, ship_heading: Vector3) {
+        if !self.visible {
+            return;
+        }
+
+        let world_radius = scene.extent().max(1.0) * 1.1;
+        let transform = self.transform(world_radius);
+        let pixels_per_unit = self.size as f32 / (2.0 * world_radius);
+
+        // Background panel
+        d.draw_rectangle(self.x, self.y, self.size, self.size, Color::new(0, 0, 0, 170));
+        d.draw_rectangle_lines(self.x, self.y, self.size, self.size, Color::new(120, 140, 170, 255));
+
+        let sun = Self::project(&transform, Vector3::zero());
+
+        // Orbits
+        for planet in &scene.planets {
+            let radius = planet.orbital_radius * pixels_per_unit;
+            d.draw_circle_lines(sun.x as i32, sun.y as i32, radius, Color::new(90, 100, 120, 160));
+        }
+
+        // Sun
+        d.draw_circle_v(sun, 4.0, Color::new(255, 200, 60, 255));
+
+        // Planets, moons and stations
+        for planet in &scene.planets {
+            let position = Self::project(&transform, planet.position());
+            let radius = (planet.scale * pixels_per_unit).max(2.5);
+            d.draw_circle_v(position, radius, icon_color(planet.planet_type));
+        }
+        for moon_idx in 0..scene.moons.len() {
+            let position = Self::project(&transform, scene.moon_position(moon_idx, time));
+            d.draw_circle_v(position, 1.5, Color::new(180, 180, 180, 255));
+        }
+        for station_idx in 0..scene.stations.len() {
+            let position = Self::project(&transform, scene.station_position(station_idx, time));
+            d.draw_rectangle((position.x - 1.5) as i32, (position.y - 1.5) as i32, 3, 3, Color::new(120, 255, 160, 255));
+        }
+        // Black holes: dark disk outlined by the accretion disk
+        for hole in &scene.black_holes {
+            let position = Self::project(&transform, hole.position);
+            let radius = (hole.disk_outer * pixels_per_unit).max(3.5);
+            d.draw_circle_v(position, radius, Color::new(0, 0, 0, 255));
+            d.draw_circle_lines(position.x as i32, position.y as i32, radius, Color::new(255, 170, 80, 255));
+        }
+
+        // Ship: small triangle pointing along its heading (projected onto the XZ plane)
+        // Clamped to the panel edge when the ship is outside the mapped area
+        let ship = Self::project(&transform, ship_position);
+        let ship = Vector2::new(
+            ship.x.clamp(self.x as f32 + 6.0, (self.x + self.size) as f32 - 6.0),
+            ship.y.clamp(self.y as f32 + 6.0, (self.y + self.size) as f32 - 6.0),
+        );
+        let ahead = Self::project(
+            &transform,
+            Vector3::new(ship_position.x + ship_heading.x, ship_position.y, ship_position.z + ship_heading.z),
+        );
+        let ship_unclamped = Self::project(&transform, ship_position);
+        let (dx, dy) = (ahead.x - ship_unclamped.x, ahead.y - ship_unclamped.y);
+        let length = (dx * dx + dy * dy).sqrt();
+        let (fx, fy) = if length > 0.0001 { (dx / length, dy / length) } else { (0.0, -1.0) };
+        let tip = Vector2::new(ship.x + fx * 6.0, ship.y + fy * 6.0);
+        let left = Vector2::new(ship.x - fx * 4.0 + fy * 4.0, ship.y - fy * 4.0 - fx * 4.0);
+        let right = Vector2::new(ship.x - fx * 4.0 - fy * 4.0, ship.y - fy * 4.0 + fx * 4.0);
+        // tip -> left -> right is counter-clockwise on screen, as raylib expects
+        d.draw_triangle(tip, left, right, icon_color(PlanetType::Ship));
+    }
+}
+
+/// Icon color per body type
+fn icon_color(planet_type: PlanetType) -> Color {
+    match planet_type {
+        PlanetType::Rocky => Color::new(170, 120, 80, 255),
+        PlanetType::GasGiant => Color::new(220, 180, 120, 255),
+        PlanetType::SciFi => Color::new(120, 220, 255, 255),
+        PlanetType::Ice => Color::new(200, 230, 255, 255),
+        PlanetType::Volcanic => Color::new(255, 90, 40, 255),
+        PlanetType::Moon | PlanetType::Ring => Color::new(180, 180, 180, 255),
+        PlanetType::Sun => Color::new(255, 200, 60, 255),
+        PlanetType::Ship => Color::new(80, 255, 140, 255),
+    }
+}
@@ -0,0 +1,210 @@
+use raylib::prelude::*;
+use crate::color::srgb_to_linear_rgb;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::pixel_ray;
+use crate::uniforms::Uniforms;
+use std::f32::consts::PI;
+
+/// Nebula settings (colors in sRGB)
+#[derive(Clone, Debug)]
+pub struct NebulaConfig {
+    pub core_color: Vector3,  // Color of the dense cores
+    pub edge_color: Vector3,  // Color of the thin wisps around them
+    pub density: f32,         // Amount of gas: 0 = clear sky, 1 = thick clouds
+    pub intensity: f32,       // Brightness of the glow
+    pub scale: f32,           // Noise frequency: higher values give smaller, more broken clouds
+    pub thickness: f32,       // Slab half-thickness as a fraction of the marched distance (1 = whole sky)
+    pub tilt: f32,            // Angle between the slab normal and +Y (radians)
+    pub heading: f32,         // Rotation of that tilt around +Y (radians)
+    pub seed: u32,
+}
+
+impl Default for NebulaConfig {
+    fn default() -> Self {
+        NebulaConfig {
+            core_color: Vector3::new(0.9, 0.3, 0.6),
+            edge_color: Vector3::new(0.2, 0.4, 0.9),
+            density: 0.6,
+            intensity: 0.6,
+            scale: 3.0,
+            thickness: 0.35,
+            tilt: 0.4,
+            heading: 0.0,
+            seed: 1,
+        }
+    }
+}
+
+/// Gas cloud surrounding the system, drawn additively over the background
+/// The glow is raymarched once through a slab of 3D noise into a low-resolution longitude/latitude
+/// panorama; every frame only looks up the view direction of each background pixel, so it costs
+/// about the same as the skybox
+pub struct Nebula {
+    pub config: NebulaConfig,
+    pixels: Vec<Vector3>, // Linear glow, PANORAMA_WIDTH x PANORAMA_HEIGHT
+}
+
+impl Nebula {
+    pub const PANORAMA_WIDTH: usize = 256;
+    pub const PANORAMA_HEIGHT: usize = 128;
+    /// Samples along each ray through the slab
+    const MARCH_STEPS: usize = 24;
+    /// Distance where the march starts: the gas right around the camera looks the same in every
+    /// direction and would only add a flat haze
+    const MARCH_START: f32 = 0.25;
+
+    pub fn new(config: NebulaConfig) -> Self {
+        let pixels = bake(&config);
+        Nebula { config, pixels }
+    }
+
+    /// Glow seen along a normalized world-space direction (bilinear; u wraps, v clamps)
+    pub fn sample(&self, dir: Vector3) -> Vector3 {
+        let width = Self::PANORAMA_WIDTH;
+        let height = Self::PANORAMA_HEIGHT;
+        let u = 0.5 + dir.z.atan2(dir.x) / (2.0 * PI);
+        let v = 0.5 - dir.y.clamp(-1.0, 1.0).asin() / PI;
+
+        let x = u.rem_euclid(1.0) * width as f32 - 0.5;
+        let y = (v * height as f32 - 0.5).clamp(0.0, height as f32 - 1.0);
+        let fx = x - x.floor();
+        let fy = y - y.floor();
+        let x0 = (x.floor() as isize).rem_euclid(width as isize) as usize;
+        let x1 = (x0 + 1) % width;
+        let y0 = y as usize;
+        let y1 = (y0 + 1).min(height - 1);
+
+        let texel = |x: usize, y: usize| self.pixels[y * width + x];
+        let lerp = |a: Vector3, b: Vector3, t: f32| {
+            Vector3::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t, a.z + (b.z - a.z) * t)
+        };
+        let top = lerp(texel(x0, y0), texel(x1, y0), fx);
+        let bottom = lerp(texel(x0, y1), texel(x1, y1), fx);
+        lerp(top, bottom, fy)
+    }
+
+    /// Adds the glow to every pixel where no geometry was drawn (stars stay visible through it)
+    /// Run after the opaque pass and the skybox, before translucent surfaces
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms) {
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let width = framebuffer.width as i32;
+        let height = framebuffer.height as i32;
+
+        for y in 0..height {
+            for x in 0..width {
+                if framebuffer.depth_at(x, y) <= 1.0 {
+                    continue;
+                }
+                let glow = self.sample(pixel_ray(x, y, width, height, view, projection));
+                if glow.x + glow.y + glow.z > 0.002 {
+                    framebuffer.add_color(x, y, glow);
+                }
+            }
+        }
+    }
+}
+
+/// Raymarches the slab for every texel of the panorama
+/// Rays leave the camera (the system sits inside the cloud) and end one unit away; the slab is
+/// the set of points within `thickness` of the plane through the origin, so looking along the
+/// plane crosses much more gas than looking out of it, which gives the cloud a band-like shape
+fn bake(config: &NebulaConfig) -> Vec<Vector3> {
+    let width = Nebula::PANORAMA_WIDTH;
+    let height = Nebula::PANORAMA_HEIGHT;
+    let normal = Vector3::new(
+        config.tilt.sin() * config.heading.cos(),
+        config.tilt.cos(),
+        config.tilt.sin() * config.heading.sin(),
+    );
+    let core = srgb_to_linear_rgb(config.core_color);
+    let edge = srgb_to_linear_rgb(config.edge_color);
+    let thickness = config.thickness.max(0.01);
+    let step = (1.0 - Nebula::MARCH_START) / Nebula::MARCH_STEPS as f32;
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        // Inverse of the lookup in `Nebula::sample`
+        let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * PI;
+        for x in 0..width {
+            let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+            let dir = Vector3::new(
+                latitude.cos() * longitude.cos(),
+                latitude.sin(),
+                latitude.cos() * longitude.sin(),
+            );
+
+            let mut color = Vector3::zero();
+            let mut transmittance = 1.0;
+            for i in 0..Nebula::MARCH_STEPS {
+                let t = Nebula::MARCH_START + (i as f32 + 0.5) * step;
+                let p = Vector3::new(dir.x * t, dir.y * t, dir.z * t);
+
+                // Soft slab boundary: full density in the middle 60%
+                let distance_to_plane = (p.x * normal.x + p.y * normal.y + p.z * normal.z).abs();
+                let slab = ((thickness - distance_to_plane) / (thickness * 0.4)).clamp(0.0, 1.0);
+                if slab <= 0.0 {
+                    continue;
+                }
+
+                let q = Vector3::new(p.x * config.scale, p.y * config.scale, p.z * config.scale);
+                let gas = fbm(q, config.seed);
+                let density = ((gas - 0.42) * 4.0).max(0.0) * slab * config.density;
+                if density <= 0.0 {
+                    continue;
+                }
+
+                // Dense cores take the core color, thin gas the edge color
+                let mix = ((gas - 0.42) * 3.0).clamp(0.0, 1.0);
+                let emission = transmittance * density * step * config.intensity * 2.0;
+                color = Vector3::new(
+                    color.x + (edge.x + (core.x - edge.x) * mix) * emission,
+                    color.y + (edge.y + (core.y - edge.y) * mix) * emission,
+                    color.z + (edge.z + (core.z - edge.z) * mix) * emission,
+                );
+                transmittance *= (-density * step * 3.0).exp();
+            }
+            pixels.push(color);
+        }
+    }
+    pixels
+}
+
+/// Hash-based value noise in [0, 1] on an integer lattice
+fn lattice(x: i32, y: i32, z: i32, seed: u32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(374761393)
+        ^ (y as u32).wrapping_mul(668265263)
+        ^ (z as u32).wrapping_mul(2147483647)
+        ^ seed.wrapping_mul(2246822519);
+    h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+    (h ^ (h >> 16)) as f32 / u32::MAX as f32
+}
+
+fn value_noise(p: Vector3, seed: u32) -> f32 {
+    let (x0, y0, z0) = (p.x.floor() as i32, p.y.floor() as i32, p.z.floor() as i32);
+    let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+    let sx = smooth(p.x - x0 as f32);
+    let sy = smooth(p.y - y0 as f32);
+    let sz = smooth(p.z - z0 as f32);
+
+    let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+    let plane = |z: i32| {
+        let top = lerp(lattice(x0, y0, z, seed), lattice(x0 + 1, y0, z, seed), sx);
+        let bottom = lerp(lattice(x0, y0 + 1, z, seed), lattice(x0 + 1, y0 + 1, z, seed), sx);
+        lerp(top, bottom, sy)
+    };
+    lerp(plane(z0), plane(z0 + 1), sz)
+}
+
+/// Four octaves of value noise, normalized to [0, 1]
+fn fbm(p: Vector3, seed: u32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..4 {
+        sum += value_noise(Vector3::new(p.x * frequency, p.y * frequency, p.z * frequency), seed) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / 0.9375
+}
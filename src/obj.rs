@@ -1,5 +1,10 @@
+use crate::matrix::{create_model_matrix, multiply_matrix_vector4, transform_normal};
 use crate::vertex::Vertex;
-use raylib::math::{Vector2, Vector3};
+use raylib::math::{Matrix, Vector2, Vector3, Vector4};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use tobj;
 
 pub struct Obj {
@@ -7,17 +12,101 @@ pub struct Obj {
     pub indices: Vec<u32>,
 }
 
+/// How vertex normals are obtained when a model is loaded
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NormalMode {
+    /// Use the file's normals; vertices without one get smooth normals
+    FromFile,
+    /// Ignore the file's normals and compute smooth (angle-weighted) normals
+    Smooth,
+    /// One normal per face (vertices are split so each triangle has its own)
+    Flat,
+}
+
+/// Options for `Obj::load_with_options`
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    pub normals: NormalMode,
+    /// Recenter the mesh at its centroid and scale it so the farthest vertex is at this radius
+    pub normalize_radius: Option<f32>,
+    /// Bake per-vertex ambient occlusion (see `Obj::bake_ambient_occlusion`)
+    pub ambient_occlusion: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions { normals: NormalMode::FromFile, normalize_radius: None, ambient_occlusion: false }
+    }
+}
+
+/// Rays per vertex and reach (fraction of the bounding box diagonal) of the ambient occlusion
+/// baked at load time
+const AO_SAMPLES: u32 = 12;
+const AO_DISTANCE: f32 = 0.03;
+
+/// Summary of a mesh, for validating loaded models
+#[derive(Clone, Debug)]
+pub struct MeshStats {
+    pub vertex_count: usize,
+    pub triangle_count: usize,
+    pub min: Vector3,               // Bounding box
+    pub max: Vector3,
+    pub centroid: Vector3,          // Average vertex position
+    pub radius: f32,                // Farthest vertex from the object-space origin
+    pub degenerate_triangles: usize, // Zero-area or sliver triangles
+    pub invalid_indices: usize,     // Indices past the end of the vertex list
+}
+
+impl MeshStats {
+    pub fn extents(&self) -> Vector3 {
+        Vector3::new(self.max.x - self.min.x, self.max.y - self.min.y, self.max.z - self.min.z)
+    }
+}
+
+impl fmt::Display for MeshStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let extents = self.extents();
+        write!(
+            f,
+            "{} vértices, {} triángulos, tamaño {:.2} x {:.2} x {:.2}, radio {:.2}, centroide ({:.2}, {:.2}, {:.2})",
+            self.vertex_count,
+            self.triangle_count,
+            extents.x,
+            extents.y,
+            extents.z,
+            self.radius,
+            self.centroid.x,
+            self.centroid.y,
+            self.centroid.z,
+        )?;
+        if self.degenerate_triangles > 0 {
+            write!(f, ", {} triángulos degenerados", self.degenerate_triangles)?;
+        }
+        if self.invalid_indices > 0 {
+            write!(f, ", {} índices fuera de rango", self.invalid_indices)?;
+        }
+        Ok(())
+    }
+}
+
 impl Obj {
     #[allow(dead_code)]
     pub fn load(path: &str) -> Result<Self, tobj::LoadError> {
+        Self::load_with_options(path, &LoadOptions::default())
+    }
+
+    pub fn load_with_options(path: &str, options: &LoadOptions) -> Result<Self, tobj::LoadError> {
         let (models, _materials) = tobj::load_obj(path, &tobj::GPU_LOAD_OPTIONS)?;
 
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
+        let mut missing_normals = false;
 
         for model in models {
             let mesh = &model.mesh;
             let num_vertices = mesh.positions.len() / 3;
+            let base = vertices.len() as u32;
+            missing_normals |= mesh.normals.is_empty();
 
             for i in 0..num_vertices {
                 let x = mesh.positions[i * 3];
@@ -44,12 +133,355 @@ impl Obj {
 
                 vertices.push(Vertex::new(position, normal, tex_coords));
             }
-            indices.extend_from_slice(&mesh.indices);
+
+            // Flipping Y mirrors the mesh and reverses its winding; swap two corners so triangles
+            // stay counter-clockwise seen from outside, like the generated meshes
+            for triangle in mesh.indices.chunks_exact(3) {
+                indices.extend_from_slice(&[base + triangle[0], base + triangle[2], base + triangle[1]]);
+            }
+        }
+
+        // tobj already shares vertices referenced by the same v/vt/vn triple; welding also merges
+        // repeated lines with identical values
+        let mut obj = Obj { vertices, indices };
+        obj.weld();
+        match options.normals {
+            NormalMode::FromFile if missing_normals => {
+                let smooth = obj.smooth_normals();
+                for (vertex, normal) in obj.vertices.iter_mut().zip(smooth) {
+                    if vertex.normal.x == 0.0 && vertex.normal.y == 0.0 && vertex.normal.z == 0.0 {
+                        vertex.normal = normal;
+                    }
+                }
+            }
+            NormalMode::FromFile => {}
+            NormalMode::Smooth => obj.compute_smooth_normals(),
+            NormalMode::Flat => obj.compute_flat_normals(),
+        }
+        if let Some(radius) = options.normalize_radius {
+            obj.normalize(radius);
+        }
+        if options.ambient_occlusion {
+            let extents = obj.stats().extents();
+            let diagonal = (extents.x * extents.x + extents.y * extents.y + extents.z * extents.z).sqrt();
+            obj.bake_ambient_occlusion(AO_SAMPLES, diagonal * AO_DISTANCE);
+        }
+        Ok(obj)
+    }
+
+    /// Vertex/triangle counts, bounds and problems found in the mesh
+    pub fn stats(&self) -> MeshStats {
+        let mut min = Vector3::new(f32::MAX, f32::MAX, f32::MAX);
+        let mut max = Vector3::new(f32::MIN, f32::MIN, f32::MIN);
+        let mut sum = [0.0f64; 3];
+        let mut radius = 0.0f32;
+        for vertex in &self.vertices {
+            let p = vertex.position;
+            min = Vector3::new(min.x.min(p.x), min.y.min(p.y), min.z.min(p.z));
+            max = Vector3::new(max.x.max(p.x), max.y.max(p.y), max.z.max(p.z));
+            sum[0] += p.x as f64;
+            sum[1] += p.y as f64;
+            sum[2] += p.z as f64;
+            radius = radius.max((p.x * p.x + p.y * p.y + p.z * p.z).sqrt());
+        }
+        let count = self.vertices.len().max(1) as f64;
+        if self.vertices.is_empty() {
+            min = Vector3::zero();
+            max = Vector3::zero();
         }
 
-        Ok(Obj { vertices, indices })
+        let invalid_indices = self.indices.iter().filter(|&&index| index as usize >= self.vertices.len()).count();
+        let degenerate_triangles = self
+            .indices
+            .chunks_exact(3)
+            .filter(|triangle| {
+                let [Some(a), Some(b), Some(c)] = [0, 1, 2].map(|k| self.vertices.get(triangle[k] as usize)) else {
+                    return false;
+                };
+                let normal = face_normal(a.position, b.position, c.position);
+                normal.x == 0.0 && normal.y == 0.0 && normal.z == 0.0
+            })
+            .count();
+
+        MeshStats {
+            vertex_count: self.vertices.len(),
+            triangle_count: self.indices.len() / 3,
+            min,
+            max,
+            centroid: Vector3::new((sum[0] / count) as f32, (sum[1] / count) as f32, (sum[2] / count) as f32),
+            radius,
+            degenerate_triangles,
+            invalid_indices,
+        }
     }
 
+    /// Moves the centroid to the origin and scales the mesh so its farthest vertex is at `radius`
+    /// Normals are unchanged (uniform scale and translation do not rotate them)
+    pub fn normalize(&mut self, radius: f32) {
+        let centroid = self.stats().centroid;
+        let farthest = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let dx = vertex.position.x - centroid.x;
+                let dy = vertex.position.y - centroid.y;
+                let dz = vertex.position.z - centroid.z;
+                (dx * dx + dy * dy + dz * dz).sqrt()
+            })
+            .fold(0.0, f32::max);
+        let scale = if farthest > 0.0 { radius / farthest } else { 1.0 };
+        for vertex in &mut self.vertices {
+            let p = vertex.position;
+            vertex.position = Vector3::new((p.x - centroid.x) * scale, (p.y - centroid.y) * scale, (p.z - centroid.z) * scale);
+        }
+    }
+
+    /// Replaces every normal with the angle-weighted average of the faces around the vertex
+    /// Vertices at the same position (split by UV seams) share one normal, so seams stay invisible
+    pub fn compute_smooth_normals(&mut self) {
+        let normals = self.smooth_normals();
+        for (vertex, normal) in self.vertices.iter_mut().zip(normals) {
+            vertex.normal = normal;
+        }
+    }
+
+    /// Gives each triangle its own three vertices with the face normal (faceted look)
+    /// The mesh is no longer shared between faces: vertex count becomes the index count
+    pub fn compute_flat_normals(&mut self) {
+        let mut vertices = Vec::with_capacity(self.indices.len());
+        for triangle in self.indices.chunks_exact(3) {
+            let corners = [
+                self.vertices[triangle[0] as usize].position,
+                self.vertices[triangle[1] as usize].position,
+                self.vertices[triangle[2] as usize].position,
+            ];
+            let normal = face_normal(corners[0], corners[1], corners[2]);
+            for &index in triangle {
+                let mut vertex = self.vertices[index as usize].clone();
+                vertex.normal = normal;
+                vertices.push(vertex);
+            }
+        }
+        self.indices = (0..vertices.len() as u32).collect();
+        self.vertices = vertices;
+    }
+
+    /// Bakes per-vertex ambient occlusion into `Vertex::occlusion`: from each vertex, `samples`
+    /// rays spread over the hemisphere around its normal are tested against the triangles within
+    /// `max_distance`, and the cosine-weighted fraction that escapes is stored (1 = fully open,
+    /// lower in creases and cavities). Triangles are bucketed in a uniform grid of cells
+    /// `max_distance` wide, so each vertex only tests the 3x3x3 cells around it
+    pub fn bake_ambient_occlusion(&mut self, samples: u32, max_distance: f32) {
+        if self.vertices.is_empty() || max_distance <= 0.0 {
+            return;
+        }
+
+        let triangles: Vec<[Vector3; 3]> = self
+            .indices
+            .chunks_exact(3)
+            .filter_map(|triangle| {
+                let [Some(a), Some(b), Some(c)] = [0, 1, 2].map(|k| self.vertices.get(triangle[k] as usize)) else {
+                    return None;
+                };
+                Some([a.position, b.position, c.position])
+            })
+            .collect();
+
+        let origin = self.stats().min;
+        let cell_of = |p: Vector3| {
+            [
+                ((p.x - origin.x) / max_distance).floor() as i32,
+                ((p.y - origin.y) / max_distance).floor() as i32,
+                ((p.z - origin.z) / max_distance).floor() as i32,
+            ]
+        };
+        let mut grid: HashMap<[i32; 3], Vec<u32>> = HashMap::new();
+        for (index, [a, b, c]) in triangles.iter().enumerate() {
+            let low = cell_of(Vector3::new(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y), a.z.min(b.z).min(c.z)));
+            let high = cell_of(Vector3::new(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y), a.z.max(b.z).max(c.z)));
+            for x in low[0]..=high[0] {
+                for y in low[1]..=high[1] {
+                    for z in low[2]..=high[2] {
+                        grid.entry([x, y, z]).or_default().push(index as u32);
+                    }
+                }
+            }
+        }
+
+        let directions = hemisphere_directions(samples.max(1));
+        let mut visited = vec![usize::MAX; triangles.len()]; // Last vertex that gathered each triangle
+        let mut nearby = Vec::new();
+        for (vertex_index, vertex) in self.vertices.iter_mut().enumerate() {
+            let normal = normalize_or_zero(vertex.normal);
+            if normal.x == 0.0 && normal.y == 0.0 && normal.z == 0.0 {
+                vertex.occlusion = 1.0;
+                continue;
+            }
+
+            // Only triangles within reach and not entirely below the tangent plane can block a ray
+            let p = vertex.position;
+            let reachable = |[a, b, c]: &[Vector3; 3]| {
+                let above = |q: Vector3| (q.x - p.x) * normal.x + (q.y - p.y) * normal.y + (q.z - p.z) * normal.z > 0.0;
+                let distance = |u: Vector3, v: Vector3| ((u.x - v.x) * (u.x - v.x) + (u.y - v.y) * (u.y - v.y) + (u.z - v.z) * (u.z - v.z)).sqrt();
+                // Every point of the triangle is within its longest edge of each corner
+                let closest = distance(p, *a).min(distance(p, *b)).min(distance(p, *c));
+                let longest_edge = distance(*a, *b).max(distance(*b, *c)).max(distance(*c, *a));
+                (above(*a) || above(*b) || above(*c)) && closest - longest_edge <= max_distance
+            };
+            nearby.clear();
+            let center = cell_of(p);
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    for dz in -1..=1 {
+                        let Some(cell) = grid.get(&[center[0] + dx, center[1] + dy, center[2] + dz]) else {
+                            continue;
+                        };
+                        for &triangle in cell {
+                            if visited[triangle as usize] != vertex_index {
+                                visited[triangle as usize] = vertex_index;
+                                if reachable(&triangles[triangle as usize]) {
+                                    nearby.push(triangle);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            // Start slightly above the surface so the vertex's own faces do not block its rays
+            let (tangent, bitangent) = tangent_basis(normal);
+            let lift = max_distance * 1e-3;
+            let start = Vector3::new(p.x + normal.x * lift, p.y + normal.y * lift, p.z + normal.z * lift);
+            let mut open = 0.0;
+            let mut total = 0.0;
+            for d in &directions {
+                let direction = Vector3::new(
+                    tangent.x * d.x + bitangent.x * d.y + normal.x * d.z,
+                    tangent.y * d.x + bitangent.y * d.y + normal.y * d.z,
+                    tangent.z * d.x + bitangent.z * d.y + normal.z * d.z,
+                );
+                // Uniform samples weighted by the cosine: light from overhead counts the most
+                total += d.z;
+                let blocked = nearby
+                    .iter()
+                    .any(|&triangle| ray_hits_triangle(start, direction, &triangles[triangle as usize], max_distance));
+                if !blocked {
+                    open += d.z;
+                }
+            }
+            vertex.occlusion = if total > 0.0 { open / total } else { 1.0 };
+        }
+    }
+
+    /// Angle-weighted smooth normal for every vertex, welding vertices by exact position
+    fn smooth_normals(&self) -> Vec<Vector3> {
+        // `+ 0.0` turns -0.0 into 0.0 so both hash the same
+        let key = |p: Vector3| [(p.x + 0.0).to_bits(), (p.y + 0.0).to_bits(), (p.z + 0.0).to_bits()];
+        let mut groups: HashMap<[u32; 3], usize> = HashMap::new();
+        let group_of: Vec<usize> = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                let next = groups.len();
+                *groups.entry(key(vertex.position)).or_insert(next)
+            })
+            .collect();
+
+        let mut sums = vec![Vector3::zero(); groups.len()];
+        for triangle in self.indices.chunks_exact(3) {
+            let p = [
+                self.vertices[triangle[0] as usize].position,
+                self.vertices[triangle[1] as usize].position,
+                self.vertices[triangle[2] as usize].position,
+            ];
+            let normal = face_normal(p[0], p[1], p[2]);
+            for corner in 0..3 {
+                // Weight by the triangle's angle at this corner, so the result does not depend
+                // on how a flat region happens to be split into triangles
+                let a = p[corner];
+                let b = p[(corner + 1) % 3];
+                let c = p[(corner + 2) % 3];
+                let e1 = normalize_or_zero(Vector3::new(b.x - a.x, b.y - a.y, b.z - a.z));
+                let e2 = normalize_or_zero(Vector3::new(c.x - a.x, c.y - a.y, c.z - a.z));
+                let angle = (e1.x * e2.x + e1.y * e2.y + e1.z * e2.z).clamp(-1.0, 1.0).acos();
+
+                let sum = &mut sums[group_of[triangle[corner] as usize]];
+                sum.x += normal.x * angle;
+                sum.y += normal.y * angle;
+                sum.z += normal.z * angle;
+            }
+        }
+
+        group_of.iter().map(|&group| normalize_or_zero(sums[group])).collect()
+    }
+
+    /// Writes the mesh as a Wavefront OBJ file (positions, normals, UVs and triangular faces)
+    /// Y is flipped back the same way `load` flips it, so a saved mesh reloads unchanged
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "# {} vertices, {} triangles", self.vertices.len(), self.indices.len() / 3)?;
+
+        for vertex in &self.vertices {
+            let p = vertex.position;
+            writeln!(writer, "v {} {} {}", p.x, -p.y, p.z)?;
+        }
+        for vertex in &self.vertices {
+            let n = vertex.normal;
+            writeln!(writer, "vn {} {} {}", n.x, n.y, n.z)?;
+        }
+        for vertex in &self.vertices {
+            let t = vertex.tex_coords;
+            writeln!(writer, "vt {} {}", t.x, t.y)?;
+        }
+
+        // OBJ indices are 1-based; position, UV and normal share the same index
+        // Corners are swapped back to match the flipped Y (see `load_with_options`)
+        for triangle in self.indices.chunks_exact(3) {
+            let (a, b, c) = (triangle[0] + 1, triangle[2] + 1, triangle[1] + 1);
+            writeln!(writer, "f {a}/{a}/{a} {b}/{b}/{b} {c}/{c}/{c}")?;
+        }
+
+        writer.flush()
+    }
+
+    /// Merges vertices that are exactly equal (position, normal and UV) and remaps the indices
+    /// Returns how many vertices were removed
+    pub fn weld(&mut self) -> usize {
+        let key = |vertex: &Vertex| {
+            [
+                vertex.position.x, vertex.position.y, vertex.position.z,
+                vertex.normal.x, vertex.normal.y, vertex.normal.z,
+                vertex.tex_coords.x, vertex.tex_coords.y,
+            ]
+            .map(|value| (value + 0.0).to_bits())
+        };
+
+        let mut unique: HashMap<[u32; 8], u32> = HashMap::new();
+        let mut vertices = Vec::with_capacity(self.vertices.len());
+        let remap: Vec<u32> = self
+            .vertices
+            .iter()
+            .map(|vertex| {
+                *unique.entry(key(vertex)).or_insert_with(|| {
+                    vertices.push(vertex.clone());
+                    (vertices.len() - 1) as u32
+                })
+            })
+            .collect();
+
+        let removed = self.vertices.len() - vertices.len();
+        for index in &mut self.indices {
+            if let Some(&new_index) = remap.get(*index as usize) {
+                *index = new_index;
+            }
+        }
+        self.vertices = vertices;
+        removed
+    }
+
+    /// Flat list with three vertices per triangle (expands the indices)
+    /// The renderer draws `Obj` meshes indexed (`Renderer::draw_indexed_mesh`); this copy is only
+    /// for code that needs a plain triangle list
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
         let mut vertex_array = Vec::new();
         for &index in &self.indices {
@@ -117,6 +549,30 @@ impl Obj {
         Obj { vertices, indices }
     }
 
+    /// Casquete de la esfera unitaria alrededor de +Y, desde el polo hasta `half_angle` radianes
+    /// Sirve para frentes de onda que se expanden en un cono (eyecciones de masa coronal)
+    pub fn generate_spherical_cap(half_angle: f32, rings: u32, segments: u32) -> Self {
+        let rings = rings.max(1);
+        let segments = segments.max(3);
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for i in 0..=rings {
+            let theta = half_angle * i as f32 / rings as f32;
+            let (sin_theta, cos_theta) = theta.sin_cos();
+            for j in 0..=segments {
+                let phi = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+                let position = Vector3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi);
+                let tex_coords = Vector2::new(j as f32 / segments as f32, i as f32 / rings as f32);
+                vertices.push(Vertex::new(position, position, tex_coords));
+            }
+        }
+        push_grid_indices(&mut indices, 0, rings, segments);
+
+        Obj { vertices, indices }
+    }
+
     /// Genera anillos planetarios usando un disco fino
     /// inner_radius: radio interno del anillo
     /// outer_radius: radio externo del anillo
@@ -170,4 +626,340 @@ impl Obj {
 
         Obj { vertices, indices }
     }
+
+    /// Genera un toro (anillo con sección circular) alrededor del eje Y
+    /// major_radius: distancia del centro al centro del tubo
+    /// minor_radius: radio del tubo
+    /// segments_major: segmentos alrededor del anillo
+    /// segments_minor: segmentos alrededor del tubo
+    pub fn generate_torus(major_radius: f32, minor_radius: f32, segments_major: u32, segments_minor: u32) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+
+        for i in 0..=segments_major {
+            let u = 2.0 * std::f32::consts::PI * i as f32 / segments_major as f32;
+            let (sin_u, cos_u) = u.sin_cos();
+
+            for j in 0..=segments_minor {
+                let v = 2.0 * std::f32::consts::PI * j as f32 / segments_minor as f32;
+                let (sin_v, cos_v) = v.sin_cos();
+
+                let ring = major_radius + minor_radius * cos_v;
+                let position = Vector3::new(ring * cos_u, minor_radius * sin_v, ring * sin_u);
+
+                // Normal: del centro del tubo hacia la superficie
+                let normal = Vector3::new(cos_v * cos_u, sin_v, cos_v * sin_u);
+
+                let tex_coords = Vector2::new(i as f32 / segments_major as f32, j as f32 / segments_minor as f32);
+                vertices.push(Vertex::new(position, normal, tex_coords));
+            }
+        }
+
+        push_grid_indices(&mut indices, 0, segments_major, segments_minor);
+
+        Obj { vertices, indices }
+    }
+
+    /// Genera un cilindro cerrado centrado en el origen, con el eje a lo largo de Y
+    /// radius: radio del cilindro
+    /// height: altura total
+    /// segments: segmentos alrededor del eje
+    pub fn generate_cylinder(radius: f32, height: f32, segments: u32) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let half_height = height / 2.0;
+
+        // Pared lateral: dos filas de vértices (abajo y arriba) con normales radiales
+        for i in 0..=1 {
+            let y = if i == 0 { -half_height } else { half_height };
+            for j in 0..=segments {
+                let angle = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+                let (sin_a, cos_a) = angle.sin_cos();
+                let position = Vector3::new(radius * cos_a, y, radius * sin_a);
+                let normal = Vector3::new(cos_a, 0.0, sin_a);
+                let tex_coords = Vector2::new(j as f32 / segments as f32, i as f32);
+                vertices.push(Vertex::new(position, normal, tex_coords));
+            }
+        }
+        push_grid_indices(&mut indices, 0, 1, segments);
+
+        // Tapas: vértices propios para que la normal sea plana (±Y)
+        push_disc(&mut vertices, &mut indices, radius, half_height, 1.0, segments);
+        push_disc(&mut vertices, &mut indices, radius, -half_height, -1.0, segments);
+
+        Obj { vertices, indices }
+    }
+
+    /// Genera una caja centrada en el origen
+    /// width, height, depth: tamaño en X, Y y Z
+    pub fn generate_box(width: f32, height: f32, depth: f32) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let (hx, hy, hz) = (width / 2.0, height / 2.0, depth / 2.0);
+
+        // Cada cara tiene sus 4 vértices para que la normal sea la de la cara
+        // (normal, eje u, eje v) con u × v = normal
+        let faces = [
+            (Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(0.0, 1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, -1.0)),
+            (Vector3::new(0.0, -1.0, 0.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0)),
+            (Vector3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+            (Vector3::new(0.0, 0.0, -1.0), Vector3::new(-1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        ];
+
+        for (normal, u, v) in faces {
+            let base = vertices.len() as u32;
+            for (su, sv) in [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)] {
+                let position = Vector3::new(
+                    (normal.x + u.x * su + v.x * sv) * hx,
+                    (normal.y + u.y * su + v.y * sv) * hy,
+                    (normal.z + u.z * su + v.z * sv) * hz,
+                );
+                let tex_coords = Vector2::new((su + 1.0) / 2.0, (sv + 1.0) / 2.0);
+                vertices.push(Vertex::new(position, normal, tex_coords));
+            }
+            indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+
+        Obj { vertices, indices }
+    }
+
+    /// Genera una cápsula (cilindro con semiesferas en los extremos) a lo largo de Y
+    /// radius: radio del cilindro y de las semiesferas
+    /// height: longitud de la parte cilíndrica (la altura total es height + 2 * radius)
+    /// segments: segmentos alrededor del eje
+    /// rings: anillos de cada semiesfera
+    pub fn generate_capsule(radius: f32, height: f32, segments: u32, rings: u32) -> Self {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        let half_height = height / 2.0;
+        let rings = rings.max(1);
+
+        // Filas de polo a polo: la semiesfera superior termina en el ecuador desplazado hacia arriba
+        // y la inferior empieza en el ecuador desplazado hacia abajo; la banda entre ambas
+        // filas del ecuador es la pared del cilindro
+        let rows = 2 * rings + 1;
+        for i in 0..=rows {
+            let (theta, offset) = if i <= rings {
+                (std::f32::consts::FRAC_PI_2 * i as f32 / rings as f32, half_height)
+            } else {
+                (std::f32::consts::FRAC_PI_2 * (i - 1) as f32 / rings as f32, -half_height)
+            };
+            let (sin_theta, cos_theta) = theta.sin_cos();
+
+            for j in 0..=segments {
+                let phi = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+                let (sin_phi, cos_phi) = phi.sin_cos();
+
+                let normal = Vector3::new(sin_theta * cos_phi, cos_theta, sin_theta * sin_phi);
+                let position = Vector3::new(radius * normal.x, radius * normal.y + offset, radius * normal.z);
+                let tex_coords = Vector2::new(j as f32 / segments as f32, i as f32 / rows as f32);
+                vertices.push(Vertex::new(position, normal, tex_coords));
+            }
+        }
+
+        push_grid_indices(&mut indices, 0, rows, segments);
+
+        Obj { vertices, indices }
+    }
+
+    /// Añade los triángulos de `other` transformados por `transform` (posiciones y normales)
+    /// Sirve para armar mallas compuestas a partir de las primitivas
+    pub fn append(&mut self, other: &Obj, transform: &Matrix) {
+        let base = self.vertices.len() as u32;
+        for vertex in &other.vertices {
+            let p = vertex.position;
+            let position = multiply_matrix_vector4(transform, &Vector4::new(p.x, p.y, p.z, 1.0));
+            let normal = transform_normal(transform, vertex.normal);
+            self.vertices.push(Vertex::new(Vector3::new(position.x, position.y, position.z), normal, vertex.tex_coords));
+        }
+        self.indices.extend(other.indices.iter().map(|index| base + index));
+    }
+
+    /// Módulo central de la estación espacial (no gira): cilindro a lo largo de Y con el
+    /// módulo de atraque en el extremo superior, cuya cara alcanza y = 0.85 (Station::DOCKING_PORT)
+    pub fn generate_station_hub() -> Self {
+        let mut hub = Obj::generate_cylinder(0.18, 1.4, 24);
+        let dock = Obj::generate_box(0.3, 0.15, 0.3);
+        hub.append(&dock, &create_model_matrix(Vector3::new(0.0, 0.775, 0.0), 1.0, Vector3::zero()));
+        let antenna = Obj::generate_cylinder(0.03, 0.4, 8);
+        hub.append(&antenna, &create_model_matrix(Vector3::new(0.0, -0.9, 0.0), 1.0, Vector3::zero()));
+        hub
+    }
+
+    /// Anillo habitable de la estación: toro de radio 1 en el plano XZ unido al módulo
+    /// central por cuatro radios
+    pub fn generate_station_ring() -> Self {
+        let mut ring = Obj::generate_torus(1.0, 0.12, 48, 12);
+        let spoke = Obj::generate_cylinder(0.04, 0.76, 8);
+        // Los radios van del módulo central (r = 0.18) al interior del toro (r = 0.88)
+        let center = 0.53;
+        let spokes = [
+            (Vector3::new(center, 0.0, 0.0), Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2)),
+            (Vector3::new(-center, 0.0, 0.0), Vector3::new(0.0, 0.0, std::f32::consts::FRAC_PI_2)),
+            (Vector3::new(0.0, 0.0, center), Vector3::new(std::f32::consts::FRAC_PI_2, 0.0, 0.0)),
+            (Vector3::new(0.0, 0.0, -center), Vector3::new(std::f32::consts::FRAC_PI_2, 0.0, 0.0)),
+        ];
+        for (translation, rotation) in spokes {
+            ring.append(&spoke, &create_model_matrix(translation, 1.0, rotation));
+        }
+        ring
+    }
+
+    /// Roca irregular para el cinturón de asteroides: esfera unitaria de baja resolución cuyo radio
+    /// varía entre ~0.7 y ~1.15 con tres ondas de baja frecuencia elegidas por `seed`
+    /// El desplazamiento depende solo de la dirección, así que la costura de la esfera sigue cerrada
+    pub fn generate_asteroid(seed: u32, segments: u32) -> Self {
+        let mut rock = Obj::generate_sphere(1.0, segments.max(4));
+
+        // LCG (mismas constantes que las partículas) para la dirección, frecuencia y fase de cada onda
+        let mut state = seed as u64 ^ 0x9E37_79B9_7F4A_7C15;
+        let mut random = || {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 40) as f32 / (1u64 << 24) as f32
+        };
+        let waves: Vec<(Vector3, f32, f32)> = (0..3)
+            .map(|_| {
+                let direction = normalize_or_zero(Vector3::new(random() * 2.0 - 1.0, random() * 2.0 - 1.0, random() * 2.0 - 1.0));
+                (direction, 1.5 + random() * 2.5, random() * 2.0 * std::f32::consts::PI)
+            })
+            .collect();
+
+        for vertex in &mut rock.vertices {
+            // Redondear: los vértices duplicados de la costura y del polo sur difieren por error de
+            // redondeo y no se soldarían al calcular las normales suaves
+            let snap = |value: f32| (value * 1.0e4).round() / 1.0e4;
+            let p = Vector3::new(snap(vertex.position.x), snap(vertex.position.y), snap(vertex.position.z));
+            let offset: f32 = waves
+                .iter()
+                .map(|(d, frequency, phase)| ((p.x * d.x + p.y * d.y + p.z * d.z) * frequency + phase).sin())
+                .sum::<f32>()
+                / waves.len() as f32;
+            let radius = 0.93 + 0.22 * offset;
+            vertex.position = Vector3::new(p.x * radius, p.y * radius, p.z * radius);
+        }
+        rock.compute_smooth_normals();
+        rock
+    }
+}
+
+/// Índices de una malla en cuadrícula de (rows + 1) x (columns + 1) vértices a partir de `base`
+/// (dos triángulos por celda, con el mismo orden que `generate_sphere`)
+fn push_grid_indices(indices: &mut Vec<u32>, base: u32, rows: u32, columns: u32) {
+    for i in 0..rows {
+        for j in 0..columns {
+            let first = base + i * (columns + 1) + j;
+            let second = first + 1;
+            let third = base + (i + 1) * (columns + 1) + j;
+            let fourth = third + 1;
+
+            indices.extend_from_slice(&[first, second, third]);
+            indices.extend_from_slice(&[second, fourth, third]);
+        }
+    }
+}
+
+/// Tapa circular plana a la altura `y` con normal (0, normal_y, 0): un vértice central y el borde
+fn push_disc(vertices: &mut Vec<Vertex>, indices: &mut Vec<u32>, radius: f32, y: f32, normal_y: f32, segments: u32) {
+    let normal = Vector3::new(0.0, normal_y, 0.0);
+    let center = vertices.len() as u32;
+    vertices.push(Vertex::new(Vector3::new(0.0, y, 0.0), normal, Vector2::new(0.5, 0.5)));
+
+    for j in 0..=segments {
+        let angle = 2.0 * std::f32::consts::PI * j as f32 / segments as f32;
+        let (sin_a, cos_a) = angle.sin_cos();
+        let tex_coords = Vector2::new(0.5 + cos_a * 0.5, 0.5 + sin_a * 0.5);
+        vertices.push(Vertex::new(Vector3::new(radius * cos_a, y, radius * sin_a), normal, tex_coords));
+    }
+    for j in 0..segments {
+        indices.extend_from_slice(&[center, center + 1 + j, center + 2 + j]);
+    }
+}
+
+/// Unit normal of a counter-clockwise triangle, or zero for degenerate (sliver) triangles
+/// whose direction would be numerical noise, such as the pole rows of `generate_sphere`
+fn face_normal(a: Vector3, b: Vector3, c: Vector3) -> Vector3 {
+    let e1 = Vector3::new(b.x - a.x, b.y - a.y, b.z - a.z);
+    let e2 = Vector3::new(c.x - a.x, c.y - a.y, c.z - a.z);
+    let e3 = Vector3::new(c.x - b.x, c.y - b.y, c.z - b.z);
+    let cross = Vector3::new(
+        e1.y * e2.z - e1.z * e2.y,
+        e1.z * e2.x - e1.x * e2.z,
+        e1.x * e2.y - e1.y * e2.x,
+    );
+    let length_sq = |v: Vector3| v.x * v.x + v.y * v.y + v.z * v.z;
+    let longest_sq = length_sq(e1).max(length_sq(e2)).max(length_sq(e3));
+    if length_sq(cross).sqrt() <= longest_sq * 1e-5 {
+        return Vector3::zero();
+    }
+    normalize_or_zero(cross)
+}
+
+/// Evenly spread unit directions over the upper (+Z) hemisphere, on a Fibonacci spiral
+fn hemisphere_directions(samples: u32) -> Vec<Vector3> {
+    let golden_angle = std::f32::consts::PI * (3.0 - 5.0f32.sqrt());
+    (0..samples)
+        .map(|i| {
+            let z = 1.0 - (i as f32 + 0.5) / samples as f32;
+            let r = (1.0 - z * z).sqrt();
+            let angle = i as f32 * golden_angle;
+            Vector3::new(r * angle.cos(), r * angle.sin(), z)
+        })
+        .collect()
+}
+
+/// Two unit vectors perpendicular to `normal` and to each other
+fn tangent_basis(normal: Vector3) -> (Vector3, Vector3) {
+    let helper = if normal.x.abs() < 0.9 { Vector3::new(1.0, 0.0, 0.0) } else { Vector3::new(0.0, 1.0, 0.0) };
+    let tangent = normalize_or_zero(Vector3::new(
+        helper.y * normal.z - helper.z * normal.y,
+        helper.z * normal.x - helper.x * normal.z,
+        helper.x * normal.y - helper.y * normal.x,
+    ));
+    let bitangent = Vector3::new(
+        normal.y * tangent.z - normal.z * tangent.y,
+        normal.z * tangent.x - normal.x * tangent.z,
+        normal.x * tangent.y - normal.y * tangent.x,
+    );
+    (tangent, bitangent)
+}
+
+/// Whether the ray from `origin` along the unit `direction` hits the triangle closer than
+/// `max_distance` (Möller-Trumbore, both sides)
+fn ray_hits_triangle(origin: Vector3, direction: Vector3, triangle: &[Vector3; 3], max_distance: f32) -> bool {
+    let [a, b, c] = *triangle;
+    let e1 = Vector3::new(b.x - a.x, b.y - a.y, b.z - a.z);
+    let e2 = Vector3::new(c.x - a.x, c.y - a.y, c.z - a.z);
+    let p = Vector3::new(
+        direction.y * e2.z - direction.z * e2.y,
+        direction.z * e2.x - direction.x * e2.z,
+        direction.x * e2.y - direction.y * e2.x,
+    );
+    let det = e1.x * p.x + e1.y * p.y + e1.z * p.z;
+    if det.abs() < 1e-12 {
+        return false;
+    }
+    let inv_det = 1.0 / det;
+    let s = Vector3::new(origin.x - a.x, origin.y - a.y, origin.z - a.z);
+    let u = (s.x * p.x + s.y * p.y + s.z * p.z) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return false;
+    }
+    let q = Vector3::new(s.y * e1.z - s.z * e1.y, s.z * e1.x - s.x * e1.z, s.x * e1.y - s.y * e1.x);
+    let v = (direction.x * q.x + direction.y * q.y + direction.z * q.z) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return false;
+    }
+    let t = (e2.x * q.x + e2.y * q.y + e2.z * q.z) * inv_det;
+    t > 0.0 && t < max_distance
+}
+
+fn normalize_or_zero(v: Vector3) -> Vector3 {
+    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if length > 1e-12 {
+        Vector3::new(v.x / length, v.y / length, v.z / length)
+    } else {
+        Vector3::zero()
+    }
 }
@@ -0,0 +1,54 @@
+use raylib::prelude::*;
+use crate::scene::BodyRef;
+
+/// Órbita circular de estacionamiento de la nave alrededor de un cuerpo
+/// La posición de la nave queda emparentada al cuerpo: se guarda relativa a él
+/// (radio, ángulo y altura) y se recalcula cada frame a partir de su posición
+pub struct ParkingOrbit {
+    pub parent: BodyRef,     // Cuerpo alrededor del cual orbita la nave
+    pub radius: f32,         // Radio de la órbita en el plano horizontal
+    pub height: f32,         // Altura constante respecto al centro del cuerpo
+    pub angle: f32,          // Ángulo actual en la órbita (radianes)
+    pub angular_speed: f32,  // Velocidad angular (rad/s)
+}
+
+impl ParkingOrbit {
+    /// Velocidad orbital a ras de la superficie; decrece con la raíz de la distancia, como con gravedad
+    pub const SURFACE_SPEED: f32 = 8.0;
+
+    /// Insertarse en órbita desde la posición actual de la nave
+    /// El radio se limita para no quedar dentro del cuerpo
+    pub fn insert(parent: BodyRef, parent_position: Vector3, parent_radius: f32, ship_position: Vector3) -> Self {
+        let dx = ship_position.x - parent_position.x;
+        let dz = ship_position.z - parent_position.z;
+        let height = (ship_position.y - parent_position.y).clamp(-parent_radius, parent_radius);
+        let radius = (dx * dx + dz * dz).sqrt().max(parent_radius * 1.5 + 1.0);
+        let speed = Self::SURFACE_SPEED * (parent_radius / radius).sqrt();
+
+        ParkingOrbit {
+            parent,
+            radius,
+            height,
+            angle: dz.atan2(dx),
+            angular_speed: speed / radius,
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        self.angle = (self.angle + self.angular_speed * delta_time) % (2.0 * std::f32::consts::PI);
+    }
+
+    /// Posición de la nave en el mundo dada la posición actual del cuerpo padre
+    pub fn position(&self, parent_position: Vector3) -> Vector3 {
+        Vector3::new(
+            parent_position.x + self.radius * self.angle.cos(),
+            parent_position.y + self.height,
+            parent_position.z + self.radius * self.angle.sin(),
+        )
+    }
+
+    /// Dirección de avance de la nave (tangente a la órbita)
+    pub fn heading(&self) -> Vector3 {
+        Vector3::new(-self.angle.sin(), 0.0, self.angle.cos())
+    }
+}
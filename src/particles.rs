@@ -0,0 +1,294 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::{is_orthographic, multiply_matrix_vector4, project_to_screen};
+use crate::uniforms::Uniforms;
+
+/// A single particle in world space
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub age: f32,      // Seconds since spawn
+    pub lifetime: f32, // Seconds until the particle dies
+}
+
+/// Point gravity pulling every particle of an emitter towards a body, so particles launched
+/// from its surface follow ballistic arcs and fall back onto it
+#[derive(Clone, Copy, Debug)]
+pub struct Attractor {
+    pub center: Vector3, // World position of the body (update it every frame if the body moves)
+    pub gravity: f32,    // Acceleration towards the center (world units per second squared)
+    pub radius: f32,     // Surface radius: particles that fall below it are removed
+}
+
+/// Reusable particle emitter: spawns particles at a given rate, integrates them
+/// and renders them as additive billboards into the framebuffer
+pub struct ParticleEmitter {
+    pub particles: Vec<Particle>,
+    pub max_particles: usize,
+    pub spawn_rate: f32,       // Particles per second while emitting
+    pub lifetime: f32,         // Base lifetime in seconds
+    pub speed: f32,            // Initial speed along the emission direction
+    pub spread: f32,           // Random velocity added on each axis (world units per second)
+    pub drag: f32,             // Fraction of velocity lost per second
+    pub start_size: f32,       // Billboard radius in world units at spawn
+    pub end_size: f32,         // Billboard radius at the end of life
+    pub start_color: Vector3,  // Additive color at spawn
+    pub end_color: Vector3,    // Additive color at the end of life
+    pub attractor: Option<Attractor>, // Gravity source for ballistic particles (None = straight lines)
+    spawn_accumulator: f32,
+    rng_state: u64,
+}
+
+impl ParticleEmitter {
+    pub fn new(max_particles: usize) -> Self {
+        ParticleEmitter {
+            particles: Vec::with_capacity(max_particles),
+            max_particles,
+            spawn_rate: 60.0,
+            lifetime: 1.0,
+            speed: 5.0,
+            spread: 1.0,
+            drag: 0.5,
+            start_size: 0.3,
+            end_size: 0.05,
+            start_color: Vector3::new(1.0, 1.0, 1.0),
+            end_color: Vector3::new(0.0, 0.0, 0.0),
+            attractor: None,
+            spawn_accumulator: 0.0,
+            rng_state: 0x2545F4914F6CDD1D,
+        }
+    }
+
+    /// Glowing blue-white engine exhaust that fades to dark orange
+    pub fn engine_exhaust() -> Self {
+        ParticleEmitter {
+            spawn_rate: 120.0,
+            lifetime: 0.6,
+            speed: 12.0,
+            spread: 1.5,
+            drag: 1.5,
+            start_size: 0.35,
+            end_size: 0.08,
+            start_color: Vector3::new(0.5, 0.7, 1.0),
+            end_color: Vector3::new(0.3, 0.08, 0.0),
+            ..ParticleEmitter::new(400)
+        }
+    }
+
+    /// Fiery debris cloud for impacts and explosions (spawned with `burst`)
+    pub fn explosion() -> Self {
+        ParticleEmitter {
+            lifetime: 1.6,
+            speed: 6.0,
+            spread: 1.0,
+            drag: 1.2,
+            start_size: 0.6,
+            end_size: 0.15,
+            start_color: Vector3::new(1.0, 0.75, 0.35),
+            end_color: Vector3::new(0.35, 0.06, 0.0),
+            ..ParticleEmitter::new(800)
+        }
+    }
+
+    /// Incandescent volcanic ejecta (launched with `fountain` and `emit`); without drag so the
+    /// particles keep the planet's orbital velocity, and meant to be used with an `Attractor`
+    pub fn volcanic_ejecta() -> Self {
+        ParticleEmitter {
+            spawn_rate: 50.0,
+            lifetime: 2.5,
+            speed: 3.0,
+            spread: 0.8,
+            drag: 0.0,
+            start_size: 0.14,
+            end_size: 0.05,
+            start_color: Vector3::new(1.6, 0.7, 0.2),
+            end_color: Vector3::new(0.4, 0.05, 0.0),
+            ..ParticleEmitter::new(600)
+        }
+    }
+
+    /// Pseudo-random value in [-1, 1] (simple LCG, like the star field)
+    fn random(&mut self) -> f32 {
+        self.rng_state = self.rng_state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.rng_state >> 33) as f32 / (1u64 << 31) as f32) * 2.0 - 1.0
+    }
+
+    /// Spawn particles for this frame at `origin`, moving along `direction`
+    /// `base_velocity` is added so particles inherit the emitter's motion
+    pub fn emit(&mut self, origin: Vector3, direction: Vector3, base_velocity: Vector3, delta_time: f32) {
+        self.spawn_accumulator += self.spawn_rate * delta_time;
+        while self.spawn_accumulator >= 1.0 {
+            self.spawn_accumulator -= 1.0;
+            if self.particles.len() >= self.max_particles {
+                continue;
+            }
+
+            let jitter = Vector3::new(self.random(), self.random(), self.random());
+            let lifetime = self.lifetime * (1.0 + self.random() * 0.25);
+            self.particles.push(Particle {
+                position: origin,
+                velocity: Vector3::new(
+                    base_velocity.x + direction.x * self.speed + jitter.x * self.spread,
+                    base_velocity.y + direction.y * self.speed + jitter.y * self.spread,
+                    base_velocity.z + direction.z * self.speed + jitter.z * self.spread,
+                ),
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Spawn `count` particles at once at `origin`, flying out in random directions at up to
+    /// `speed` (explosions and impacts); `base_velocity` is added as in `emit`
+    pub fn burst(&mut self, origin: Vector3, count: usize, base_velocity: Vector3) {
+        for _ in 0..count {
+            if self.particles.len() >= self.max_particles {
+                break;
+            }
+
+            // Random direction inside the unit sphere, scaled so some debris is slow and some fast
+            let direction = Vector3::new(self.random(), self.random(), self.random());
+            let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt().max(0.0001);
+            let speed = self.speed * (0.3 + 0.7 * (self.random() * 0.5 + 0.5)) / length;
+            let jitter = Vector3::new(self.random(), self.random(), self.random());
+            let lifetime = self.lifetime * (1.0 + self.random() * 0.25);
+            self.particles.push(Particle {
+                position: origin,
+                velocity: Vector3::new(
+                    base_velocity.x + direction.x * speed + jitter.x * self.spread,
+                    base_velocity.y + direction.y * speed + jitter.y * self.spread,
+                    base_velocity.z + direction.z * speed + jitter.z * self.spread,
+                ),
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Spawn `count` particles at once at `origin`, launched along `direction` at between 60%
+    /// and 100% of `speed` plus the usual jitter (an eruption or a geyser rather than a blast)
+    pub fn fountain(&mut self, origin: Vector3, direction: Vector3, count: usize, base_velocity: Vector3) {
+        for _ in 0..count {
+            if self.particles.len() >= self.max_particles {
+                break;
+            }
+
+            let speed = self.speed * (0.8 + 0.2 * self.random());
+            let jitter = Vector3::new(self.random(), self.random(), self.random());
+            let lifetime = self.lifetime * (1.0 + self.random() * 0.25);
+            self.particles.push(Particle {
+                position: origin,
+                velocity: Vector3::new(
+                    base_velocity.x + direction.x * speed + jitter.x * self.spread,
+                    base_velocity.y + direction.y * speed + jitter.y * self.spread,
+                    base_velocity.z + direction.z * speed + jitter.z * self.spread,
+                ),
+                age: 0.0,
+                lifetime,
+            });
+        }
+    }
+
+    /// Integrate positions, apply drag and gravity and remove dead particles
+    /// (with an attractor, also the ones that fell back below its surface)
+    pub fn update(&mut self, delta_time: f32) {
+        let damping = (1.0 - self.drag * delta_time).max(0.0);
+        for particle in &mut self.particles {
+            if let Some(attractor) = &self.attractor {
+                let to_center = Vector3::new(
+                    attractor.center.x - particle.position.x,
+                    attractor.center.y - particle.position.y,
+                    attractor.center.z - particle.position.z,
+                );
+                let distance = (to_center.x * to_center.x + to_center.y * to_center.y + to_center.z * to_center.z).sqrt().max(0.0001);
+                let pull = attractor.gravity * delta_time / distance;
+                particle.velocity.x += to_center.x * pull;
+                particle.velocity.y += to_center.y * pull;
+                particle.velocity.z += to_center.z * pull;
+            }
+            particle.position.x += particle.velocity.x * delta_time;
+            particle.position.y += particle.velocity.y * delta_time;
+            particle.position.z += particle.velocity.z * delta_time;
+            particle.velocity.x *= damping;
+            particle.velocity.y *= damping;
+            particle.velocity.z *= damping;
+            particle.age += delta_time;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        if let Some(attractor) = &self.attractor {
+            let radius_squared = attractor.radius * attractor.radius;
+            self.particles.retain(|p| {
+                let (dx, dy, dz) = (p.position.x - attractor.center.x, p.position.y - attractor.center.y, p.position.z - attractor.center.z);
+                dx * dx + dy * dy + dz * dz >= radius_squared
+            });
+        }
+    }
+
+    /// Render every particle as a camera-facing soft disk with additive blending
+    /// Particles are depth tested against opaque geometry but do not write depth
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms) {
+        // Pixels per world unit at view depth 1: projection Y scale * half the viewport height
+        // Orthographic projections keep that scale at every depth
+        let focal_px = uniforms.projection_matrix.m5 * framebuffer.height as f32 * 0.5;
+        let orthographic = is_orthographic(&uniforms.projection_matrix);
+
+        for particle in &self.particles {
+            let Some(screen) = project_to_screen(
+                particle.position,
+                &uniforms.view_matrix,
+                &uniforms.projection_matrix,
+                &uniforms.viewport_matrix,
+            ) else {
+                continue;
+            };
+
+            let view_position = multiply_matrix_vector4(
+                &uniforms.view_matrix,
+                &Vector4::new(particle.position.x, particle.position.y, particle.position.z, 1.0),
+            );
+            let view_depth = -view_position.z;
+            if view_depth <= 0.0 {
+                continue;
+            }
+
+            let t = (particle.age / particle.lifetime).clamp(0.0, 1.0);
+            let size = self.start_size + (self.end_size - self.start_size) * t;
+            let fade = 1.0 - t;
+            let color = Vector3::new(
+                (self.start_color.x + (self.end_color.x - self.start_color.x) * t) * fade,
+                (self.start_color.y + (self.end_color.y - self.start_color.y) * t) * fade,
+                (self.start_color.z + (self.end_color.z - self.start_color.z) * t) * fade,
+            );
+
+            let perspective_depth = if orthographic { 1.0 } else { view_depth };
+            let radius = (size * focal_px / perspective_depth).max(0.5);
+            draw_billboard(framebuffer, screen, radius, color);
+        }
+    }
+}
+
+/// Additive soft disk at a screen position, skipping pixels hidden by closer geometry
+fn draw_billboard(framebuffer: &mut Framebuffer, screen: Vector3, radius: f32, color: Vector3) {
+    let min_x = (screen.x - radius).floor() as i32;
+    let max_x = (screen.x + radius).ceil() as i32;
+    let min_y = (screen.y - radius).floor() as i32;
+    let max_y = (screen.y + radius).ceil() as i32;
+
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if framebuffer.depth_at(x, y) < screen.z {
+                continue;
+            }
+            let dx = x as f32 - screen.x;
+            let dy = y as f32 - screen.y;
+            let d = (dx * dx + dy * dy).sqrt() / radius;
+            if d >= 1.0 {
+                continue;
+            }
+            let falloff = 1.0 - d * d;
+            framebuffer.add_color(x, y, Vector3::new(color.x * falloff, color.y * falloff, color.z * falloff));
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use raylib::prelude::*;
+use crate::camera::{Camera, CameraMode};
+use crate::input_map::{Action, InputMap};
+use crate::input_state::InputState;
+use std::f32::consts::PI;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Modo foto: congela el reloj de la simulación y separa una cámara libre, lenta y precisa,
+/// para encuadrar sin mover la nave. Permite ocultar el HUD y la nave, ajustar la exposición
+/// y el campo de visión y guardar capturas renderizadas a mayor resolución que la ventana
+/// El movimiento usa las acciones de vuelo y los ajustes las acciones `Action::PHOTO`, que solo
+/// responden aquí, mientras la simulación está parada
+pub struct PhotoMode {
+    active: bool,
+    pub eye: Vector3,               // Posición de la cámara libre
+    pub yaw: f32,
+    pub pitch: f32,
+    pub speed: f32,                 // Unidades por segundo (la rueda del ratón la ajusta)
+    pub turn_speed: f32,            // Radianes por segundo al girar con el teclado
+    pub exposure_ev: f32,           // Exposición en pasos (EV): cada paso duplica la luz
+    pub fov: f32,                   // Campo de visión vertical en radianes
+    pub show_hud: bool,
+    pub show_ship: bool,
+    pub supersampling: u32,         // Factor por eje de la captura (2 = 4 muestras por píxel)
+    capture_requested: bool,
+    message: Option<(String, f32)>, // Aviso de la última captura y segundos que le quedan
+}
+
+impl PhotoMode {
+    pub const MIN_FOV: f32 = PI / 18.0;      // 10°
+    pub const MAX_FOV: f32 = PI * 2.0 / 3.0; // 120°
+    pub const MAX_EV: f32 = 3.0;             // La exposición va de -MAX_EV a +MAX_EV
+    const MIN_SPEED: f32 = 0.05;
+    const MAX_SPEED: f32 = 200.0;
+
+    pub fn new() -> Self {
+        PhotoMode {
+            active: false,
+            eye: Vector3::zero(),
+            yaw: 0.0,
+            pitch: 0.0,
+            speed: 2.0,
+            turn_speed: 0.4,
+            exposure_ev: 0.0,
+            fov: PI / 3.0,
+            show_hud: true,
+            show_ship: true,
+            supersampling: 2,
+            capture_requested: false,
+            message: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Entra en el modo foto desde la vista actual de `camera` con el campo de visión `fov`
+    pub fn enter(&mut self, camera: &Camera, fov: f32) {
+        let eye = camera.view_eye();
+        let target = camera.view_target();
+        let direction = Vector3::new(target.x - eye.x, target.y - eye.y, target.z - eye.z);
+        let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt();
+        if length > 0.0001 {
+            self.pitch = (direction.y / length).asin();
+            self.yaw = direction.z.atan2(direction.x);
+        } else {
+            self.pitch = camera.pitch;
+            self.yaw = camera.yaw;
+        }
+        self.eye = eye;
+        self.fov = fov.clamp(Self::MIN_FOV, Self::MAX_FOV);
+        self.exposure_ev = 0.0;
+        self.capture_requested = false;
+        self.message = None;
+        self.active = true;
+    }
+
+    /// Sale del modo foto; la simulación sigue donde se quedó
+    pub fn exit(&mut self) {
+        self.active = false;
+        self.capture_requested = false;
+    }
+
+    /// Multiplicador lineal de la exposición (ver `Framebuffer::set_exposure`)
+    pub fn exposure(&self) -> f32 {
+        2.0f32.powf(self.exposure_ev)
+    }
+
+    /// Dirección en la que mira la cámara libre
+    pub fn forward(&self) -> Vector3 {
+        Vector3::new(
+            self.yaw.cos() * self.pitch.cos(),
+            self.pitch.sin(),
+            self.yaw.sin() * self.pitch.cos(),
+        )
+    }
+
+    /// Coloca `camera` en la cámara libre (solo la copia que se renderiza)
+    pub fn apply(&self, camera: &mut Camera) {
+        let forward = self.forward();
+        camera.look_from(self.eye, Vector3::new(self.eye.x + forward.x, self.eye.y + forward.y, self.eye.z + forward.z));
+        camera.clear_collision();
+        camera.track_planet(None);
+        camera.mode = CameraMode::FreeFly;
+        camera.update_transition(f32::INFINITY);
+    }
+
+    /// Mueve la cámara con la entrada del frame y atiende los ajustes
+    /// `delta_time` es el tiempo real del frame: la simulación está parada
+    pub fn update(&mut self, input: &InputState, delta_time: f32) {
+        if let Some((_, remaining)) = &mut self.message {
+            *remaining -= delta_time;
+            if *remaining <= 0.0 {
+                self.message = None;
+            }
+        }
+
+        // Con el modificador, cinco veces más rápido; la rueda multiplica o divide la velocidad
+        if input.mouse_wheel != 0.0 {
+            self.speed = (self.speed * 1.25f32.powf(input.mouse_wheel)).clamp(Self::MIN_SPEED, Self::MAX_SPEED);
+        }
+        let boost = if input.is_down(Action::Modifier) { 5.0 } else { 1.0 };
+        let step = self.speed * boost * delta_time;
+        let turn = self.turn_speed * boost * delta_time;
+
+        if input.is_down(Action::YawLeft) {
+            self.yaw += turn;
+        }
+        if input.is_down(Action::YawRight) {
+            self.yaw -= turn;
+        }
+        if input.is_down(Action::PitchUp) {
+            self.pitch += turn;
+        }
+        if input.is_down(Action::PitchDown) {
+            self.pitch -= turn;
+        }
+        self.pitch = self.pitch.clamp(-PI / 2.0 + 0.01, PI / 2.0 - 0.01);
+
+        let forward = self.forward();
+        let right = Vector3::new(-self.yaw.sin(), 0.0, self.yaw.cos());
+        let axis = |positive: Action, negative: Action| {
+            (input.is_down(positive) as i32 - input.is_down(negative) as i32) as f32
+        };
+        let along = axis(Action::Forward, Action::Backward) * step;
+        let side = axis(Action::StrafeRight, Action::StrafeLeft) * step;
+        let up = axis(Action::Ascend, Action::Descend) * step;
+        self.eye.x += forward.x * along + right.x * side;
+        self.eye.y += forward.y * along + up;
+        self.eye.z += forward.z * along + right.z * side;
+
+        // Ajustes: exposición y campo de visión (mantener), HUD, nave y captura
+        let exposure_step = axis(Action::PhotoExposureUp, Action::PhotoExposureDown) * delta_time;
+        self.exposure_ev = (self.exposure_ev + exposure_step).clamp(-Self::MAX_EV, Self::MAX_EV);
+        let fov_step = axis(Action::PhotoFovWide, Action::PhotoFovNarrow) * (PI / 9.0) * delta_time;
+        self.fov = (self.fov + fov_step).clamp(Self::MIN_FOV, Self::MAX_FOV);
+        if input.is_pressed(Action::PhotoReset) {
+            self.exposure_ev = 0.0;
+            self.fov = PI / 3.0;
+        }
+        if input.is_pressed(Action::PhotoHud) {
+            self.show_hud = !self.show_hud;
+        }
+        if input.is_pressed(Action::PhotoShip) {
+            self.show_ship = !self.show_ship;
+        }
+        if input.is_pressed(Action::PhotoCapture) {
+            self.capture_requested = true;
+        }
+    }
+
+    /// Devuelve true (una sola vez) si se pidió una captura
+    pub fn take_capture_request(&mut self) -> bool {
+        std::mem::take(&mut self.capture_requested)
+    }
+
+    /// Ruta de la próxima captura: `screenshots/foto_<segundos desde 1970>.png`
+    pub fn capture_path(&self) -> String {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        format!("screenshots/foto_{}.png", seconds)
+    }
+
+    /// Muestra `text` en el panel durante unos segundos (resultado de la captura)
+    pub fn notify(&mut self, text: String) {
+        self.message = Some((text, 4.0));
+    }
+
+    /// Panel con los ajustes y sus teclas según `map`; con el HUD oculto solo queda una línea
+    /// discreta de ayuda
+    pub fn draw(&self, d: &mut RaylibDrawHandle, map: &InputMap, width: i32, height: i32) {
+        if !self.active {
+            return;
+        }
+
+        let accent = Color::new(255, 220, 140, 255);
+        let text_color = Color::new(220, 220, 220, 255);
+        if !self.show_hud {
+            if let Some((message, _)) = &self.message {
+                d.draw_text(message, 10, height - 20, 10, text_color);
+            }
+            return;
+        }
+
+        let panel_width = 240;
+        let panel_height = 150;
+        let x = width - panel_width - 10;
+        let y = height - panel_height - 10;
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 170));
+        d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+        d.draw_text("MODO FOTO", x + 10, y + 8, 16, accent);
+
+        let slider = |d: &mut RaylibDrawHandle, row: i32, label: &str, value: String, fraction: f32| {
+            let row_y = y + 32 + row * 24;
+            d.draw_text(&format!("{}: {}", label, value), x + 10, row_y, 10, text_color);
+            d.draw_rectangle(x + 10, row_y + 12, panel_width - 20, 4, Color::new(60, 60, 60, 255));
+            d.draw_rectangle(x + 10, row_y + 12, ((panel_width - 20) as f32 * fraction.clamp(0.0, 1.0)) as i32, 4, accent);
+        };
+        let keys = |a: Action, b: Action| format!("{}/{}", map.describe(a), map.describe(b));
+        slider(
+            d,
+            0,
+            &format!("Exposición ({})", keys(Action::PhotoExposureDown, Action::PhotoExposureUp)),
+            format!("{:+.1} EV", self.exposure_ev),
+            (self.exposure_ev + Self::MAX_EV) / (2.0 * Self::MAX_EV),
+        );
+        slider(
+            d,
+            1,
+            &format!("Campo de visión ({})", keys(Action::PhotoFovNarrow, Action::PhotoFovWide)),
+            format!("{:.0}°", self.fov.to_degrees()),
+            (self.fov - Self::MIN_FOV) / (Self::MAX_FOV - Self::MIN_FOV),
+        );
+        d.draw_text(&format!("Velocidad (rueda): {:.2} u/s", self.speed), x + 10, y + 84, 10, text_color);
+        d.draw_text(
+            &format!(
+                "{}: HUD   {}: nave ({})   {}: restablecer",
+                map.describe(Action::PhotoHud),
+                map.describe(Action::PhotoShip),
+                if self.show_ship { "visible" } else { "oculta" },
+                map.describe(Action::PhotoReset),
+            ),
+            x + 10,
+            y + 100,
+            10,
+            text_color,
+        );
+        d.draw_text(
+            &format!("{}: guardar captura ({}x)", map.describe(Action::PhotoCapture), self.supersampling * self.supersampling),
+            x + 10,
+            y + 116,
+            10,
+            text_color,
+        );
+        if let Some((message, _)) = &self.message {
+            d.draw_text(message, x + 10, y + 132, 10, accent);
+        }
+    }
+}
+
+impl Default for PhotoMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
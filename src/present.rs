@@ -0,0 +1,222 @@
+use crate::framebuffer::Framebuffer;
+use crate::screen_texture::ScreenTexture;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Destino donde se presenta cada frame terminado del framebuffer: la ventana de raylib
+/// (`ScreenTexture`), una carpeta de imágenes (`FrameDump`) o la terminal (`TerminalOutput`)
+/// Solo la ventana consume la zona cambiada del framebuffer; el resto lee la imagen completa,
+/// así que se pueden combinar varios destinos en el mismo frame
+pub trait Present {
+    /// Muestra o guarda el contenido actual de `framebuffer`
+    fn present(&mut self, framebuffer: &mut Framebuffer) -> io::Result<()>;
+
+    /// Nombre corto para los mensajes de error
+    fn name(&self) -> String;
+}
+
+impl Present for ScreenTexture {
+    /// Sube a la textura la zona cambiada; dibujarla en la ventana sigue siendo `draw_to`,
+    /// dentro del frame de raylib y debajo del HUD
+    fn present(&mut self, framebuffer: &mut Framebuffer) -> io::Result<()> {
+        self.update(framebuffer);
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        String::from("ventana")
+    }
+}
+
+/// Formato de las imágenes de `FrameDump`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DumpFormat {
+    Ppm, // PPM binario, escrito sin raylib
+    Png, // PNG, codificado por raylib (no necesita ventana)
+}
+
+impl DumpFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Ppm => "ppm",
+            DumpFormat::Png => "png",
+        }
+    }
+}
+
+/// Guarda cada frame como una imagen numerada (`frame_000000.ppm`, ...) en una carpeta, para
+/// montar un vídeo después (por ejemplo con `ffmpeg -i frame_%06d.png`)
+pub struct FrameDump {
+    pub directory: PathBuf,
+    pub format: DumpFormat,
+    pub every: u32, // Guardar uno de cada `every` frames
+    frame: u64,     // Frames presentados
+    saved: u64,     // Imágenes escritas (numeración de los archivos)
+}
+
+impl FrameDump {
+    /// Crea la carpeta si no existe
+    pub fn new(directory: impl Into<PathBuf>, format: DumpFormat) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        Ok(FrameDump { directory, format, every: 1, frame: 0, saved: 0 })
+    }
+
+    /// Ruta de la próxima imagen
+    pub fn next_path(&self) -> PathBuf {
+        self.directory.join(format!("frame_{:06}.{}", self.saved, self.format.extension()))
+    }
+
+    /// Imágenes escritas hasta ahora
+    #[allow(dead_code)]
+    pub fn saved(&self) -> u64 {
+        self.saved
+    }
+}
+
+impl Present for FrameDump {
+    fn present(&mut self, framebuffer: &mut Framebuffer) -> io::Result<()> {
+        let frame = self.frame;
+        self.frame += 1;
+        if !frame.is_multiple_of(self.every.max(1) as u64) {
+            return Ok(());
+        }
+        let path = self.next_path();
+        match self.format {
+            DumpFormat::Ppm => write_ppm(framebuffer, &path)?,
+            DumpFormat::Png => framebuffer.export(&path.to_string_lossy())?,
+        }
+        self.saved += 1;
+        Ok(())
+    }
+
+    fn name(&self) -> String {
+        format!("{}:{}", self.format.extension(), self.directory.display())
+    }
+}
+
+/// Escribe el framebuffer (codificado como en pantalla) como PPM binario de 8 bits
+pub fn write_ppm(framebuffer: &Framebuffer, path: &Path) -> io::Result<()> {
+    let mut data = format!("P6\n{} {}\n255\n", framebuffer.width, framebuffer.height).into_bytes();
+    data.extend_from_slice(&framebuffer.to_rgb8());
+    fs::write(path, data)
+}
+
+/// Dibuja el frame en la terminal con color verdadero ANSI, a baja resolución: cada carácter
+/// es un medio bloque superior (`▀`) con el píxel de arriba como color del texto y el de abajo
+/// como fondo, así que una celda (que es el doble de alta que de ancha) muestra dos píxeles
+/// cuadrados. Cada píxel es la media de la zona del framebuffer que le corresponde
+pub struct TerminalOutput {
+    pub columns: u32,
+    started: bool, // Ya se limpió la pantalla y se ocultó el cursor
+    text: String,  // Búfer de salida, reutilizado entre frames
+}
+
+impl TerminalOutput {
+    pub fn new(columns: u32) -> Self {
+        TerminalOutput { columns: columns.max(1), started: false, text: String::new() }
+    }
+
+    /// Tamaño en píxeles de la imagen reducida (el alto siempre par) para un framebuffer dado
+    pub fn pixel_size(&self, width: u32, height: u32) -> (u32, u32) {
+        let columns = self.columns.min(width.max(1));
+        let rows = ((height as f32 * columns as f32 / width.max(1) as f32 / 2.0).round() as u32).max(1);
+        (columns, rows * 2)
+    }
+}
+
+impl Present for TerminalOutput {
+    fn present(&mut self, framebuffer: &mut Framebuffer) -> io::Result<()> {
+        let (width, height) = (framebuffer.width as usize, framebuffer.height as usize);
+        let (columns, pixel_rows) = self.pixel_size(framebuffer.width, framebuffer.height);
+        let (columns, pixel_rows) = (columns as usize, pixel_rows as usize);
+        let rgb = framebuffer.to_rgb8();
+
+        // Media de la caja de píxeles del framebuffer que cae en el píxel (column, row)
+        let sample = |column: usize, row: usize| -> [u8; 3] {
+            let (x0, x1) = (column * width / columns, ((column + 1) * width / columns).max(column * width / columns + 1));
+            let (y0, y1) = (row * height / pixel_rows, ((row + 1) * height / pixel_rows).max(row * height / pixel_rows + 1));
+            let mut sum = [0u32; 3];
+            for y in y0..y1.min(height) {
+                for x in x0..x1.min(width) {
+                    let index = (y * width + x) * 3;
+                    sum[0] += rgb[index] as u32;
+                    sum[1] += rgb[index + 1] as u32;
+                    sum[2] += rgb[index + 2] as u32;
+                }
+            }
+            let count = ((x1.min(width) - x0) * (y1.min(height) - y0)).max(1) as u32;
+            [(sum[0] / count) as u8, (sum[1] / count) as u8, (sum[2] / count) as u8]
+        };
+
+        let text = &mut self.text;
+        text.clear();
+        if !self.started {
+            // Limpiar la pantalla y ocultar el cursor una sola vez
+            text.push_str("\x1b[2J\x1b[?25l");
+            self.started = true;
+        }
+        text.push_str("\x1b[H");
+        for row in 0..pixel_rows / 2 {
+            // Los códigos de color solo se repiten cuando cambian respecto a la celda anterior
+            let mut last: Option<([u8; 3], [u8; 3])> = None;
+            for column in 0..columns {
+                let top = sample(column, row * 2);
+                let bottom = sample(column, row * 2 + 1);
+                if last != Some((top, bottom)) {
+                    text.push_str(&format!(
+                        "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m",
+                        top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]
+                    ));
+                    last = Some((top, bottom));
+                }
+                text.push('▀');
+            }
+            text.push_str("\x1b[0m\n");
+        }
+
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(text.as_bytes())?;
+        stdout.flush()
+    }
+
+    fn name(&self) -> String {
+        String::from("terminal")
+    }
+}
+
+impl Drop for TerminalOutput {
+    fn drop(&mut self) {
+        if self.started {
+            // Devolver el cursor y los colores de la terminal
+            print!("\x1b[0m\x1b[?25h");
+            let _ = io::stdout().flush();
+        }
+    }
+}
+
+/// Interpreta un destino de `--output`: `ppm:<carpeta>`, `png:<carpeta>` o `terminal[:<columnas>]`
+pub fn parse_output(spec: &str) -> Result<Box<dyn Present>, String> {
+    let (kind, argument) = match spec.split_once(':') {
+        Some((kind, argument)) => (kind, Some(argument)),
+        None => (spec, None),
+    };
+    match kind {
+        "ppm" | "png" => {
+            let format = if kind == "ppm" { DumpFormat::Ppm } else { DumpFormat::Png };
+            let directory = argument.filter(|directory| !directory.is_empty()).unwrap_or("frames");
+            FrameDump::new(directory, format)
+                .map(|dump| Box::new(dump) as Box<dyn Present>)
+                .map_err(|e| format!("no se pudo crear {}: {}", directory, e))
+        }
+        "terminal" => {
+            let columns = match argument {
+                Some(columns) => columns.parse::<u32>().map_err(|_| format!("columnas no válidas: {}", columns))?,
+                None => 80,
+            };
+            Ok(Box::new(TerminalOutput::new(columns)))
+        }
+        _ => Err(format!("destino desconocido: {} (ppm:<carpeta>, png:<carpeta> o terminal[:<columnas>])", spec)),
+    }
+}
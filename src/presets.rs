@@ -0,0 +1,122 @@
+use raylib::prelude::*;
+use crate::scene::{Atmosphere, Moon, Planet, RingGap, RingSystem, Scene, Storm};
+use crate::shaders::{PlanetType, TintShader};
+use crate::star::Star;
+use crate::units::{au_to_units, orbital_speed_for_period, radius_scale, DAYS_PER_YEAR};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Datos de un planeta real
+struct PlanetData {
+    name: &'static str,
+    semi_major_axis: f32, // UA
+    orbital_period: f32,  // Años terrestres
+    radius: f32,          // Radios terrestres
+    day_hours: f32,       // Día sideral en horas
+    axial_tilt: f32,      // Grados (los retrógrados se guardan como 180 - inclinación)
+    retrograde: bool,
+    longitude: f32,       // Longitud media en J2000 (grados): posición inicial
+    planet_type: PlanetType,
+    tint: Vector3,        // Tinte sobre la paleta del tipo de shader
+}
+
+const PLANETS: [PlanetData; 9] = [
+    PlanetData { name: "Mercurio", semi_major_axis: 0.387, orbital_period: 0.241, radius: 0.383, day_hours: 1407.6, axial_tilt: 0.03, retrograde: false, longitude: 252.25, planet_type: PlanetType::Moon, tint: Vector3::new(1.05, 0.95, 0.85) },
+    PlanetData { name: "Venus", semi_major_axis: 0.723, orbital_period: 0.615, radius: 0.949, day_hours: 5832.5, axial_tilt: 2.64, retrograde: true, longitude: 181.98, planet_type: PlanetType::GasGiant, tint: Vector3::new(1.1, 1.05, 0.8) },
+    PlanetData { name: "Tierra", semi_major_axis: 1.0, orbital_period: 1.0, radius: 1.0, day_hours: 23.93, axial_tilt: 23.44, retrograde: false, longitude: 100.46, planet_type: PlanetType::Ice, tint: Vector3::new(0.5, 0.75, 1.0) },
+    PlanetData { name: "Marte", semi_major_axis: 1.524, orbital_period: 1.881, radius: 0.532, day_hours: 24.62, axial_tilt: 25.19, retrograde: false, longitude: 355.45, planet_type: PlanetType::Rocky, tint: Vector3::new(1.4, 0.65, 0.4) },
+    PlanetData { name: "Júpiter", semi_major_axis: 5.203, orbital_period: 11.86, radius: 11.21, day_hours: 9.93, axial_tilt: 3.13, retrograde: false, longitude: 34.40, planet_type: PlanetType::GasGiant, tint: Vector3::new(1.0, 0.9, 0.8) },
+    PlanetData { name: "Saturno", semi_major_axis: 9.537, orbital_period: 29.46, radius: 9.45, day_hours: 10.66, axial_tilt: 26.73, retrograde: false, longitude: 49.94, planet_type: PlanetType::GasGiant, tint: Vector3::new(1.1, 1.05, 0.75) },
+    PlanetData { name: "Urano", semi_major_axis: 19.19, orbital_period: 84.01, radius: 4.01, day_hours: 17.24, axial_tilt: 82.23, retrograde: true, longitude: 313.23, planet_type: PlanetType::Ice, tint: Vector3::new(0.7, 1.0, 1.0) },
+    PlanetData { name: "Neptuno", semi_major_axis: 30.07, orbital_period: 164.8, radius: 3.88, day_hours: 16.11, axial_tilt: 28.32, retrograde: false, longitude: 304.88, planet_type: PlanetType::Ice, tint: Vector3::new(0.45, 0.6, 1.2) },
+    PlanetData { name: "Plutón", semi_major_axis: 39.48, orbital_period: 248.0, radius: 0.187, day_hours: 153.3, axial_tilt: 57.47, retrograde: true, longitude: 238.93, planet_type: PlanetType::Moon, tint: Vector3::new(1.1, 0.95, 0.8) },
+];
+
+/// Índices de la Tierra, Júpiter y Saturno en `PLANETS` (padres de la Luna, Titán y los anillos)
+const EARTH: usize = 2;
+const JUPITER: usize = 4;
+const SATURN: usize = 5;
+const PLUTO: usize = 8;
+
+/// Sistema solar real: los ocho planetas (y Plutón si `include_pluto`) con órbitas, periodos,
+/// días e inclinaciones axiales reales escalados, más la Luna, Titán y los anillos de Saturno
+pub fn real_solar_system(include_pluto: bool) -> Scene {
+    let planets: Vec<Planet> = PLANETS
+        .iter()
+        .enumerate()
+        .filter(|&(idx, _)| include_pluto || idx != PLUTO)
+        .map(|(_, data)| Planet {
+            name: String::from(data.name),
+            orbital_radius: au_to_units(data.semi_major_axis),
+            orbital_angle: data.longitude.to_radians(),
+            orbital_speed: orbital_speed_for_period(data.orbital_period * DAYS_PER_YEAR),
+            rotation_period: data.day_hours,
+            scale: radius_scale(data.radius),
+            axial_tilt: data.axial_tilt.to_radians(),
+            retrograde: data.retrograde,
+            planet_type: data.planet_type,
+            shader: Some(Arc::new(TintShader::new(data.planet_type, data.tint))),
+            rings: None,
+            craters: Vec::new(),
+            eruptions: Vec::new(),
+            storms: Vec::new(),
+            atmosphere: Atmosphere::for_type(data.planet_type),
+        })
+        .collect();
+    let mut planets = planets;
+
+    // Luces de las ciudades en la cara nocturna de la Tierra
+    planets[EARTH].atmosphere = planets[EARTH].atmosphere.map(|atmosphere| atmosphere.with_city_lights(1.0));
+
+    // Gran Mancha Roja: ~22° S, el doble de ancha que de alta, girando en sentido antihorario
+    // y derivando hacia el oeste
+    planets[JUPITER].storms.push(Storm {
+        latitude: -22.0f32.to_radians(),
+        longitude: 0.0,
+        radius: 0.12,
+        drift: -0.03,
+        spin: 0.6,
+        color: Vector3::new(0.78, 0.3, 0.18),
+    });
+
+    // Anillos de Saturno en radios de Saturno: del anillo C (1.24) al borde del A (2.27),
+    // con la división de Cassini y el hueco de Encke
+    let saturn_radius = planets[SATURN].scale;
+    planets[SATURN].rings = Some(RingSystem {
+        inner_radius: 1.24 * saturn_radius,
+        outer_radius: 2.27 * saturn_radius,
+        opacity: 0.85,
+        gaps: vec![
+            RingGap { radius: 1.97 * saturn_radius, width: 0.06 * saturn_radius, density: 0.05 }, // Cassini
+            RingGap { radius: 2.21 * saturn_radius, width: 0.02 * saturn_radius, density: 0.1 },  // Encke
+        ],
+    });
+
+    // Lunas: periodos reales, distancias comprimidas para que queden cerca de su planeta
+    let moons = vec![
+        Moon {
+            name: String::from("Luna"),
+            parent: EARTH,
+            orbital_radius: 2.0,
+            orbital_speed: orbital_speed_for_period(27.32),
+            orbital_phase: 0.0,
+            inclination: 5.14f32.to_radians(),
+            scale: radius_scale(0.273),
+            planet_type: PlanetType::Moon,
+            shader: None,
+        },
+        Moon {
+            name: String::from("Titán"),
+            parent: SATURN,
+            orbital_radius: 2.27 * saturn_radius + 1.5,
+            orbital_speed: orbital_speed_for_period(15.95),
+            orbital_phase: PI / 2.0,
+            inclination: 0.35f32.to_radians(),
+            scale: radius_scale(0.404),
+            planet_type: PlanetType::GasGiant,
+            shader: Some(Arc::new(TintShader::new(PlanetType::GasGiant, Vector3::new(1.0, 0.8, 0.5)))),
+        },
+    ];
+
+    Scene { star: Star::yellow_dwarf(), planets, moons, stations: Vec::new(), black_holes: Vec::new(), nebula: None, scripts: Vec::new(), facts: Vec::new() }
+}
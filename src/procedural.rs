@@ -0,0 +1,279 @@
+use raylib::prelude::*;
+use crate::nebula::NebulaConfig;
+use crate::scene::{Atmosphere, Moon, Planet, RingGap, RingSystem, Scene, Storm};
+use crate::shaders::{PlanetType, TintShader};
+use crate::star::{SpectralClass, Star};
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+/// Parámetros del generador de sistemas aleatorios
+pub struct SystemGenerator {
+    pub min_planets: usize,
+    pub max_planets: usize,
+    pub first_orbit: (f32, f32),    // Rango del radio de la órbita más interna
+    pub spacing_ratio: (f32, f32),  // Rango del cociente entre órbitas consecutivas (Titius-Bode)
+    pub sun_clearance: f32,         // Distancia mínima al centro de cualquier luna o anillo (el sol mide 3)
+    pub snow_line: f32,             // A partir de este radio los planetas son gigantes gaseosos o helados
+    pub kepler_constant: f32,       // velocidad orbital = kepler_constant / radio^1.5
+    pub max_moons: usize,           // Lunas máximas por gigante (los rocosos tienen como mucho una)
+    pub ring_chance: f32,           // Probabilidad de anillos en un gigante
+    pub nebula_chance: f32,         // Probabilidad de que el sistema esté dentro de una nebulosa
+}
+
+impl SystemGenerator {
+    pub fn new() -> Self {
+        SystemGenerator {
+            min_planets: 3,
+            max_planets: 8,
+            first_orbit: (8.0, 11.0),
+            spacing_ratio: (1.35, 1.7),
+            sun_clearance: 5.0,
+            snow_line: 25.0,
+            kepler_constant: 0.5 * 12.0f32.powf(1.5), // Igual que Ferrum: 0.5 rad/s a 12 unidades
+            max_moons: 3,
+            ring_chance: 0.4,
+            nebula_chance: 0.35,
+        }
+    }
+
+    /// Genera un sistema completo a partir de `seed`; la misma semilla produce siempre el mismo sistema
+    /// Las órbitas crecen geométricamente (radio_n = primera · cociente^n, como la ley de Titius-Bode),
+    /// dentro de la línea de nieve hay mundos rocosos, volcánicos o sci-fi y fuera gigantes gaseosos
+    /// o helados; cada planeta recibe un tinte aleatorio sobre la paleta de su tipo
+    pub fn generate(&self, seed: u64) -> Scene {
+        let mut rng = Rng::new(seed);
+        let star = random_star(&mut rng);
+        let planet_count = rng.range_usize(self.min_planets, self.max_planets);
+        let first_orbit = rng.range(self.first_orbit.0, self.first_orbit.1);
+        let ratio = rng.range(self.spacing_ratio.0, self.spacing_ratio.1);
+
+        let mut planets = Vec::with_capacity(planet_count);
+        let mut moons = Vec::new();
+        let mut previous_edge = self.sun_clearance; // Borde exterior (anillos y lunas) del planeta anterior
+        for idx in 0..planet_count {
+            // Pequeña variación alrededor de la progresión geométrica; el tipo depende de este radio
+            let base_radius = first_orbit * ratio.powi(idx as i32) * rng.range(0.95, 1.05);
+            let name = planet_name(&mut rng);
+            let giant = base_radius > self.snow_line;
+            let planet_type = if giant {
+                rng.pick(&[PlanetType::GasGiant, PlanetType::GasGiant, PlanetType::Ice])
+            } else {
+                rng.pick(&[PlanetType::Rocky, PlanetType::Rocky, PlanetType::Volcanic, PlanetType::SciFi, PlanetType::Ice])
+            };
+            let scale = if giant { rng.range(1.8, 3.2) } else { rng.range(0.6, 1.6) };
+
+            let rings = if giant && rng.next_f32() < self.ring_chance {
+                Some(random_rings(&mut rng, scale))
+            } else {
+                None
+            };
+
+            // Lunas con el nombre del planeta y un numeral, fuera de los anillos y cada vez más lejos
+            let moon_count = if giant { rng.range_usize(0, self.max_moons) } else { rng.range_usize(0, 1) };
+            let mut extent = rings.as_ref().map_or(scale, |rings| rings.outer_radius);
+            let mut moon_orbit = extent.max(scale * 1.6) + 1.0;
+            for moon_idx in 0..moon_count {
+                let moon_scale = rng.range(0.2, 0.5);
+                moon_orbit += moon_scale + rng.range(0.5, 1.5);
+                moons.push(Moon {
+                    name: format!("{} {}", name, roman(moon_idx + 1)),
+                    parent: idx,
+                    orbital_radius: moon_orbit,
+                    orbital_speed: rng.range(0.4, 1.2) * if rng.next_f32() < 0.1 { -1.0 } else { 1.0 },
+                    orbital_phase: rng.range(0.0, 2.0 * PI),
+                    inclination: rng.range(0.0, 15.0).to_radians(),
+                    scale: moon_scale,
+                    planet_type: PlanetType::Moon,
+                    shader: Some(Arc::new(TintShader::new(PlanetType::Moon, random_tint(&mut rng, 0.2)))),
+                });
+                moon_orbit += moon_scale;
+                extent = moon_orbit;
+            }
+
+            // Separar la órbita lo necesario para que no se crucen los sistemas de lunas vecinos
+            let orbital_radius = base_radius.max(previous_edge + extent + 2.0);
+            previous_edge = orbital_radius + extent;
+
+            // Tormentas de los gigantes gaseosos y ciudades de los rocosos con su propio generador,
+            // para que las semillas existentes sigan produciendo el mismo sistema
+            let mut extras = Rng::new(seed ^ (idx as u64 + 1).wrapping_mul(0xD1B5_4A32_D192_ED03));
+            let storms = if matches!(planet_type, PlanetType::GasGiant) {
+                random_storms(&mut extras)
+            } else {
+                Vec::new()
+            };
+            let inhabited = matches!(planet_type, PlanetType::Rocky) && extras.next_f32() < 0.3;
+            let atmosphere = Atmosphere::for_type(planet_type)
+                .map(|atmosphere| if inhabited { atmosphere.with_city_lights(extras.range(0.5, 1.0)) } else { atmosphere });
+
+            planets.push(Planet {
+                name,
+                orbital_radius,
+                orbital_angle: rng.range(0.0, 2.0 * PI),
+                orbital_speed: self.kepler_constant / orbital_radius.powf(1.5),
+                rotation_period: if giant { rng.range(8.0, 20.0) } else { rng.range(15.0, 60.0) },
+                scale,
+                axial_tilt: rng.range(0.0, 30.0).to_radians(),
+                retrograde: rng.next_f32() < 0.1,
+                planet_type,
+                shader: Some(Arc::new(TintShader::new(planet_type, random_tint(&mut rng, 0.4)))),
+                rings,
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                storms,
+                atmosphere,
+            });
+        }
+
+        let nebula = (rng.next_f32() < self.nebula_chance).then(|| random_nebula(&mut rng));
+
+        Scene { star, planets, moons, stations: Vec::new(), black_holes: Vec::new(), nebula, scripts: Vec::new(), facts: Vec::new() }
+    }
+}
+
+impl Default for SystemGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sistema aleatorio con los parámetros por defecto
+pub fn generate_system(seed: u64) -> Scene {
+    SystemGenerator::new().generate(seed)
+}
+
+/// Estrella con una clase espectral al azar (las enanas frías son las más comunes) y su
+/// temperatura variada un ±10% respecto a la típica de la clase
+fn random_star(rng: &mut Rng) -> Star {
+    let class = rng.pick(&[
+        SpectralClass::M, SpectralClass::M, SpectralClass::M, SpectralClass::M,
+        SpectralClass::K, SpectralClass::K, SpectralClass::K,
+        SpectralClass::G, SpectralClass::G, SpectralClass::G,
+        SpectralClass::F, SpectralClass::A, SpectralClass::B,
+    ]);
+    let mut star = Star::from_class(class);
+    star.set_temperature(star.temperature() * rng.range(0.9, 1.1));
+    star
+}
+
+/// Anillos entre 1.3 y 2.6 radios del planeta con hasta dos bandas vacías
+fn random_rings(rng: &mut Rng, scale: f32) -> RingSystem {
+    let inner_radius = scale * rng.range(1.3, 1.6);
+    let outer_radius = scale * rng.range(2.0, 2.6);
+    let gap_count = rng.range_usize(0, 2);
+    let gaps = (0..gap_count)
+        .map(|_| RingGap {
+            radius: rng.range(inner_radius, outer_radius),
+            width: (outer_radius - inner_radius) * rng.range(0.03, 0.12),
+            density: rng.range(0.0, 0.2),
+        })
+        .collect();
+    RingSystem { inner_radius, outer_radius, opacity: rng.range(0.6, 0.9), gaps }
+}
+
+/// Entre ninguna y tres tormentas: la primera puede ser una gran mancha, las demás óvalos menores
+fn random_storms(rng: &mut Rng) -> Vec<Storm> {
+    let count = rng.range_usize(0, 3);
+    (0..count)
+        .map(|idx| {
+            let radius = if idx == 0 { rng.range(0.1, 0.22) } else { rng.range(0.04, 0.1) };
+            let color = rng.pick(&[
+                Vector3::new(0.78, 0.3, 0.18),  // Rojiza
+                Vector3::new(0.95, 0.9, 0.85),  // Blanca
+                Vector3::new(0.45, 0.3, 0.22),  // Parda
+            ]);
+            Storm {
+                latitude: rng.range(-0.9, 0.9),
+                longitude: rng.range(0.0, 2.0 * PI),
+                radius,
+                drift: rng.range(-0.08, 0.08),
+                spin: rng.range(0.3, 0.9) * if rng.next_f32() < 0.5 { -1.0 } else { 1.0 },
+                color,
+            }
+        })
+        .collect()
+}
+
+/// Nebulosa con una pareja de colores de emisión (hidrógeno, oxígeno, azufre...) y forma al azar
+fn random_nebula(rng: &mut Rng) -> NebulaConfig {
+    let (core_color, edge_color) = rng.pick(&[
+        (Vector3::new(0.9, 0.3, 0.5), Vector3::new(0.3, 0.4, 0.9)),  // Rosa y azul
+        (Vector3::new(0.2, 0.8, 0.7), Vector3::new(0.2, 0.3, 0.8)),  // Turquesa y azul
+        (Vector3::new(1.0, 0.6, 0.2), Vector3::new(0.8, 0.2, 0.2)),  // Naranja y rojo
+        (Vector3::new(0.7, 0.4, 1.0), Vector3::new(0.9, 0.3, 0.4)),  // Violeta y carmesí
+        (Vector3::new(0.5, 0.9, 0.4), Vector3::new(0.2, 0.5, 0.6)),  // Verde y azul verdoso
+    ]);
+    NebulaConfig {
+        core_color,
+        edge_color,
+        density: rng.range(0.4, 0.9),
+        intensity: rng.range(0.4, 0.8),
+        scale: rng.range(2.0, 4.5),
+        thickness: rng.range(0.2, 0.6),
+        tilt: rng.range(0.0, PI),
+        heading: rng.range(0.0, 2.0 * PI),
+        seed: rng.next_u64() as u32,
+    }
+}
+
+/// Tinte alrededor de blanco: cada canal en [1 - amount, 1 + amount]
+fn random_tint(rng: &mut Rng, amount: f32) -> Vector3 {
+    Vector3::new(
+        rng.range(1.0 - amount, 1.0 + amount),
+        rng.range(1.0 - amount, 1.0 + amount),
+        rng.range(1.0 - amount, 1.0 + amount),
+    )
+}
+
+/// Nombre pronunciable de dos o tres sílabas
+fn planet_name(rng: &mut Rng) -> String {
+    const SYLLABLES: [&str; 20] = [
+        "ka", "ze", "tor", "mi", "lu", "ven", "dra", "os", "qui", "ra",
+        "nex", "sol", "ta", "bri", "gan", "el", "mor", "xi", "pha", "dun",
+    ];
+    let count = rng.range_usize(2, 3);
+    let name: String = (0..count).map(|_| rng.pick(&SYLLABLES)).collect();
+    let mut chars = name.chars();
+    chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+}
+
+/// Numeral romano para las lunas (I-X)
+fn roman(n: usize) -> &'static str {
+    const NUMERALS: [&str; 10] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", "X"];
+    NUMERALS[(n.max(1) - 1).min(NUMERALS.len() - 1)]
+}
+
+/// LCG de 64 bits (mismas constantes que las partículas), sembrado con la semilla del sistema
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        let mut rng = Rng(seed ^ 0x9E37_79B9_7F4A_7C15);
+        rng.next_u64();
+        rng
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0
+    }
+
+    /// Valor en [0, 1)
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Valor en [min, max)
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Entero en [min, max] (ambos incluidos)
+    fn range_usize(&mut self, min: usize, max: usize) -> usize {
+        min + ((self.next_u64() >> 33) % (max.saturating_sub(min) as u64 + 1)) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, options: &[T]) -> T {
+        options[self.range_usize(0, options.len() - 1)]
+    }
+}
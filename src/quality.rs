@@ -0,0 +1,183 @@
+use crate::obj::Obj;
+use crate::shaders::ShaderQuality;
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+/// Ajustes de render de un nivel de calidad
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QualitySettings {
+    pub sphere_lod: usize,             // Malla de esfera a usar (0 = la más detallada, ver `LOD_DETAIL`)
+    pub star_fraction: f32,            // Fracción de las estrellas del fondo que se dibujan
+    pub shader_quality: ShaderQuality, // Octavas de ruido y corona de los shaders procedurales
+    pub render_scale: f32,             // Factor sobre la escala de render elegida con F11
+}
+
+/// Niveles de calidad, del más bajo al más alto. Al bajar se renuncia primero a lo que menos se
+/// nota: calidad de los shaders, luego segmentos de las esferas y estrellas y por último resolución
+pub const QUALITY_LEVELS: [QualitySettings; 5] = [
+    QualitySettings { sphere_lod: 2, star_fraction: 0.5, shader_quality: ShaderQuality::Low, render_scale: 0.75 },
+    QualitySettings { sphere_lod: 2, star_fraction: 0.5, shader_quality: ShaderQuality::Medium, render_scale: 1.0 },
+    QualitySettings { sphere_lod: 1, star_fraction: 0.5, shader_quality: ShaderQuality::Medium, render_scale: 1.0 },
+    QualitySettings { sphere_lod: 1, star_fraction: 1.0, shader_quality: ShaderQuality::High, render_scale: 1.0 },
+    QualitySettings { sphere_lod: 0, star_fraction: 1.0, shader_quality: ShaderQuality::Ultra, render_scale: 1.0 },
+];
+
+/// Fracción de los segmentos originales de cada malla de esfera por nivel de detalle
+pub const LOD_DETAIL: [f32; 3] = [1.0, 0.75, 0.5];
+
+/// Una esfera por nivel de detalle de `LOD_DETAIL`, a partir de la resolución máxima
+pub fn sphere_lods(radius: f32, segments: u32) -> Vec<Obj> {
+    LOD_DETAIL
+        .iter()
+        .map(|detail| Obj::generate_sphere(radius, ((segments as f32 * detail) as u32).max(6)))
+        .collect()
+}
+
+/// Límites del ajuste automático de calidad (ver `assets/quality.cfg`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QualityConfig {
+    pub adaptive: bool,   // Ajustar la calidad según el tiempo de frame
+    pub target_fps: f32,  // FPS que se intentan mantener
+    pub min_level: usize, // Nivel más bajo al que puede bajar (índice en `QUALITY_LEVELS`)
+    pub max_level: usize, // Nivel más alto al que puede subir (y con el que se empieza)
+}
+
+impl QualityConfig {
+    pub fn new() -> Self {
+        QualityConfig {
+            adaptive: true,
+            target_fps: 60.0,
+            min_level: 0,
+            max_level: QUALITY_LEVELS.len() - 1,
+        }
+    }
+
+    /// Cargar la configuración de un archivo de líneas `clave = valor` (`#` inicia un comentario)
+    /// Las claves que no aparecen conservan su valor por defecto
+    pub fn load(path: &str) -> io::Result<Self> {
+        QualityConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut config = QualityConfig::new();
+        let top = QUALITY_LEVELS.len() - 1;
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'clave = valor'"))?;
+            let value = value.trim();
+            let level = |value: &str| match value.parse::<usize>() {
+                Ok(level) if level <= top => Ok(level),
+                Ok(_) => Err(config_error(line_number, &format!("el nivel va de 0 a {}", top))),
+                Err(e) => Err(config_error(line_number, &format!("{}", e))),
+            };
+            match key.trim() {
+                "adaptive" => config.adaptive = value.parse().map_err(|e| config_error(line_number, &format!("{}", e)))?,
+                "target_fps" => {
+                    let fps: f32 = value.parse().map_err(|e| config_error(line_number, &format!("{}", e)))?;
+                    config.target_fps = fps.max(1.0);
+                }
+                "min_level" => config.min_level = level(value)?,
+                "max_level" => config.max_level = level(value)?,
+                other => return Err(config_error(line_number, &format!("clave desconocida '{}'", other))),
+            }
+        }
+
+        if config.min_level > config.max_level {
+            return Err(config_error(contents.lines().count(), "min_level no puede ser mayor que max_level"));
+        }
+        Ok(config)
+    }
+}
+
+impl Default for QualityConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Regulador de calidad: vigila el tiempo de frame y baja un nivel cuando el render no llega a
+/// los FPS objetivo, o lo sube cuando sobra margen. Baja rápido y sube despacio, para no
+/// oscilar entre dos niveles
+pub struct QualityGovernor {
+    pub config: QualityConfig,
+    level: usize,     // Nivel actual (índice en `QUALITY_LEVELS`)
+    average_ms: f32,  // Media móvil del tiempo de frame en milisegundos
+    cooldown: u32,    // Frames que faltan para poder volver a cambiar de nivel
+}
+
+impl QualityGovernor {
+    /// Frames tras un cambio antes de volver a bajar y antes de volver a subir
+    const DOWN_COOLDOWN: u32 = 30;
+    const UP_COOLDOWN: u32 = 180;
+
+    pub fn new(config: QualityConfig) -> Self {
+        QualityGovernor {
+            config,
+            level: config.max_level,
+            average_ms: 0.0,
+            cooldown: Self::DOWN_COOLDOWN,
+        }
+    }
+
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    pub fn settings(&self) -> QualitySettings {
+        QUALITY_LEVELS[self.level]
+    }
+
+    /// Fijar el nivel a mano (p. ej. desde la consola); la calidad deja de ajustarse sola
+    pub fn set_level(&mut self, level: usize) {
+        self.level = level.min(QUALITY_LEVELS.len() - 1);
+        self.config.adaptive = false;
+        self.average_ms = 0.0;
+    }
+
+    /// Registrar el tiempo de trabajo de un frame; devuelve true si cambió el nivel
+    pub fn update(&mut self, frame_time: Duration) -> bool {
+        if !self.config.adaptive {
+            return false;
+        }
+
+        let ms = frame_time.as_secs_f32() * 1000.0;
+        self.average_ms = if self.average_ms == 0.0 { ms } else { self.average_ms * 0.9 + ms * 0.1 };
+        if self.cooldown > 0 {
+            self.cooldown -= 1;
+            return false;
+        }
+
+        let budget_ms = 1000.0 / self.config.target_fps;
+        if self.average_ms > budget_ms * 1.1 && self.level > self.config.min_level {
+            self.level -= 1;
+            self.cooldown = Self::DOWN_COOLDOWN;
+        } else if self.average_ms < budget_ms * 0.6 && self.level < self.config.max_level {
+            self.level += 1;
+            self.cooldown = Self::UP_COOLDOWN;
+        } else {
+            return false;
+        }
+        // La media anterior ya no vale para el nuevo nivel
+        self.average_ms = 0.0;
+        true
+    }
+}
+
+impl Default for QualityGovernor {
+    fn default() -> Self {
+        Self::new(QualityConfig::default())
+    }
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
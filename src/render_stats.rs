@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+/// Contadores y tiempos de las etapas del pipeline acumulados durante un frame
+/// El `Renderer` los suma en cada dibujo (los tiempos solo con `profiling` activo: medir cada
+/// triángulo tiene su coste) y el bucle principal los recoge con `Renderer::take_stats`
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderStats {
+    pub triangles: u64,                    // Triángulos enviados al rasterizador
+    pub culled_triangles: u64,             // Descartados sin rasterizar (degenerados, fuera de la pantalla o detrás de la cámara)
+    pub clipped_triangles: u64,            // Recortados al borde de la pantalla o al plano cercano
+    pub fragments: u64,                    // Fragmentos generados por el rasterizador
+    pub shaded_fragments: u64,             // Fragmentos que pasaron por el fragment shader
+    pub depth_rejected_fragments: u64,     // Descartados por la prueba de profundidad antes de sombrearlos
+    pub gpu_fragments: u64,                // De los sombreados, los que calculó la GPU (`--gpu`)
+    pub vertex_time: Duration,             // Vertex shader
+    pub raster_time: Duration,             // Rasterización de los triángulos en fragmentos
+    pub fragment_time: Duration,           // Fragment shader y escritura en el framebuffer
+    pub blit_time: Duration,               // Subida de la textura y dibujo en la ventana (lo mide main)
+    pub objects: Vec<(&'static str, Duration)>, // Tiempo de cada objeto o grupo (`Renderer::begin_object`)
+}
+
+impl RenderStats {
+    pub fn new() -> Self {
+        RenderStats {
+            triangles: 0,
+            culled_triangles: 0,
+            clipped_triangles: 0,
+            fragments: 0,
+            shaded_fragments: 0,
+            depth_rejected_fragments: 0,
+            gpu_fragments: 0,
+            vertex_time: Duration::ZERO,
+            raster_time: Duration::ZERO,
+            fragment_time: Duration::ZERO,
+            blit_time: Duration::ZERO,
+            objects: Vec::new(),
+        }
+    }
+
+    /// Suma los contadores y tiempos de `other` (los objetos con el mismo nombre se juntan)
+    pub fn add(&mut self, other: &RenderStats) {
+        self.triangles += other.triangles;
+        self.culled_triangles += other.culled_triangles;
+        self.clipped_triangles += other.clipped_triangles;
+        self.fragments += other.fragments;
+        self.shaded_fragments += other.shaded_fragments;
+        self.depth_rejected_fragments += other.depth_rejected_fragments;
+        self.gpu_fragments += other.gpu_fragments;
+        self.vertex_time += other.vertex_time;
+        self.raster_time += other.raster_time;
+        self.fragment_time += other.fragment_time;
+        self.blit_time += other.blit_time;
+        for (name, time) in &other.objects {
+            self.add_object(name, *time);
+        }
+    }
+
+    /// Suma `time` al objeto `name`
+    pub fn add_object(&mut self, name: &'static str, time: Duration) {
+        match self.objects.iter_mut().find(|(object, _)| *object == name) {
+            Some((_, total)) => *total += time,
+            None => self.objects.push((name, time)),
+        }
+    }
+
+    /// Tiempo total de las etapas medidas
+    pub fn pipeline_time(&self) -> Duration {
+        self.vertex_time + self.raster_time + self.fragment_time + self.blit_time
+    }
+}
+
+impl Default for RenderStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
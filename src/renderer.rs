@@ -0,0 +1,437 @@
+use raylib::prelude::*;
+use crate::fragment::Fragment;
+use crate::framebuffer::Framebuffer;
+#[cfg(feature = "gpu")]
+use crate::gpu_shading::GpuShading;
+use crate::light::Light;
+use crate::line::line;
+use crate::matrix::{camera_relative_point, clip_to_screen, multiply_matrix_vector4, projection_far, projection_near, view_distance};
+use crate::render_stats::RenderStats;
+use crate::shaders::{vertex_shader_batch, vertex_shader_sun, shader_sun, PlanetType, SurfaceShader};
+#[cfg(feature = "gpu")]
+use crate::shaders::planet_surface_color;
+use crate::star::Star;
+use crate::triangle::{clip_near, coverage, triangle, Coverage};
+use crate::uniforms::Uniforms;
+use crate::vertex::Vertex;
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Rasterizador por software: ejecuta el pipeline de shaders sobre un Framebuffer
+/// Los vértices transformados se escriben en un búfer propio que se reutiliza de una malla a
+/// otra y los fragmentos se sombrean según salen del rasterizador, sin guardarlos: una vez que
+/// el búfer crece hasta la malla más grande, dibujar un frame no pide memoria
+///
+/// Con la característica `gpu` y `set_gpu_shading`, el color base de los planetas y lunas se
+/// calcula en la GPU (ver `GpuShading`); el resto del pipeline sigue en la CPU
+pub struct Renderer {
+    pub light: Light,
+    pub profiling: bool,                               // Medir el tiempo de cada etapa y objeto (los contadores se llevan siempre)
+    stats: RefCell<RenderStats>,                       // Estadísticas desde el último `take_stats`
+    current_object: RefCell<Option<(&'static str, Instant)>>, // Objeto que se está dibujando y desde cuándo
+    vertex_scratch: RefCell<Vec<Vertex>>,              // Vértices transformados de la malla en curso
+    #[cfg(feature = "gpu")]
+    gpu: Option<RefCell<GpuShading>>,                  // Fragment stage de los planetas en la GPU
+    #[cfg(feature = "gpu")]
+    gpu_scratch: RefCell<(Vec<Fragment>, Vec<Vector3>)>, // Fragmentos de la malla en curso y sus colores
+}
+
+impl Renderer {
+    pub fn new(light: Light) -> Self {
+        Renderer {
+            light,
+            profiling: false,
+            stats: RefCell::new(RenderStats::new()),
+            current_object: RefCell::new(None),
+            vertex_scratch: RefCell::new(Vec::new()),
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            #[cfg(feature = "gpu")]
+            gpu_scratch: RefCell::new((Vec::new(), Vec::new())),
+        }
+    }
+
+    /// Usa `gpu` para el color base de los planetas o, con None, vuelve a sombrear todo en la CPU
+    #[cfg(feature = "gpu")]
+    pub fn set_gpu_shading(&mut self, gpu: Option<GpuShading>) {
+        self.gpu = gpu.map(RefCell::new);
+    }
+
+    /// Estadísticas acumuladas desde la última llamada (normalmente, las del frame)
+    pub fn take_stats(&self) -> RenderStats {
+        self.end_object();
+        self.stats.replace(RenderStats::new())
+    }
+
+    /// Empieza a contar el tiempo de `name` (un objeto o un grupo de ellos) hasta el siguiente
+    /// `begin_object` o `end_object`; cuenta todo lo que se haga entretanto, también lo que no
+    /// pasa por el renderer (fondos, partículas). Solo con `profiling` activo
+    pub fn begin_object(&self, name: &'static str) {
+        self.end_object();
+        if self.profiling {
+            *self.current_object.borrow_mut() = Some((name, Instant::now()));
+        }
+    }
+
+    pub fn end_object(&self) {
+        if let Some((name, start)) = self.current_object.borrow_mut().take() {
+            self.stats.borrow_mut().add_object(name, start.elapsed());
+        }
+    }
+
+    /// Ejecuta una etapa del pipeline y, con `profiling` activo, devuelve cuánto tardó
+    fn measure<T>(&self, stage: impl FnOnce() -> T) -> (T, Duration) {
+        if !self.profiling {
+            return (stage(), Duration::ZERO);
+        }
+        let start = Instant::now();
+        let result = stage();
+        (result, start.elapsed())
+    }
+
+    /// Dibujar una malla con el pipeline completo: vertex shader, ensamblado de triángulos,
+    /// rasterización con el material del shader de superficie y fragment shader
+    /// Los fragmentos con alpha < 1 (anillos) se mezclan sin escribir profundidad
+    /// `shader` puede ser un PlanetType o cualquier shader propio que implemente SurfaceShader
+    /// `vertex_array` es una lista plana (tres vértices por triángulo); para mallas indexadas
+    /// usar `draw_indexed_mesh`, que transforma cada vértice una sola vez
+    pub fn draw_mesh(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], shader: &dyn SurfaceShader) {
+        // Optimización: Early exit si el array está vacío
+        if vertex_array.is_empty() {
+            return;
+        }
+    
+        // Optimización: Límite de vértices para modelos muy grandes (solo para la nave)
+        const MAX_VERTICES: usize = 100000; // Aumentado para modelos grandes
+        let effective_array = if vertex_array.len() > MAX_VERTICES {
+            // Para modelos muy grandes, usar solo los primeros MAX_VERTICES
+            &vertex_array[..MAX_VERTICES]
+        } else {
+            vertex_array
+        };
+    
+        // Vertex Shader Stage: transformación por lotes de 4 vértices con la MVP combinada
+        let mut transformed_vertices = self.vertex_scratch.take();
+        let ((), vertex_time) = self.measure(|| vertex_shader_batch(effective_array, uniforms, &mut transformed_vertices));
+        self.stats.borrow_mut().vertex_time += vertex_time;
+
+        // Primitive Assembly Stage: cada tres vértices consecutivos forman un triángulo
+        let triangles = transformed_vertices.chunks_exact(3).map(|tri| [&tri[0], &tri[1], &tri[2]]);
+        self.rasterize(framebuffer, uniforms, triangles, shader);
+        self.vertex_scratch.replace(transformed_vertices);
+    }
+
+    /// Dibujar una malla indexada (vértices únicos + índices, como `Obj`)
+    /// El vertex shader corre una vez por vértice único y los triángulos toman los vértices
+    /// transformados por índice (caché de vértices): en modelos con muchos vértices compartidos,
+    /// como la nave, es una fracción del trabajo de expandir la malla con `get_vertex_array`
+    pub fn draw_indexed_mesh(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertices: &[Vertex], indices: &[u32], shader: &dyn SurfaceShader) {
+        if vertices.is_empty() || indices.len() < 3 {
+            return;
+        }
+
+        // Vertex Shader Stage: el resultado queda indexado igual que `vertices`
+        let mut transformed_vertices = self.vertex_scratch.take();
+        let ((), vertex_time) = self.measure(|| vertex_shader_batch(vertices, uniforms, &mut transformed_vertices));
+        self.stats.borrow_mut().vertex_time += vertex_time;
+
+        // Primitive Assembly Stage: triángulos por índice (los índices fuera de rango se ignoran)
+        let triangles = indices.chunks_exact(3).filter_map(|tri| {
+            Some([
+                transformed_vertices.get(tri[0] as usize)?,
+                transformed_vertices.get(tri[1] as usize)?,
+                transformed_vertices.get(tri[2] as usize)?,
+            ])
+        });
+        self.rasterize(framebuffer, uniforms, triangles, shader);
+        self.vertex_scratch.replace(transformed_vertices);
+    }
+
+    /// Rasterización y fragment shader de triángulos ya transformados
+    fn rasterize<'a>(
+        &self,
+        framebuffer: &mut Framebuffer,
+        uniforms: &Uniforms,
+        triangles: impl Iterator<Item = [&'a Vertex; 3]>,
+        shader: &dyn SurfaceShader,
+    ) {
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &self.gpu
+            && let Some(planet_type) = shader.gpu_surface()
+            && gpu.borrow().supports(planet_type, uniforms)
+        {
+            self.rasterize_gpu(gpu, planet_type, framebuffer, uniforms, triangles, shader);
+            return;
+        }
+
+        // Rasterization Stage: recortado al plano cercano y a la zona visible del framebuffer
+        let material = shader.material();
+        let emissive = material.emissive;
+        let scissor = framebuffer.scissor();
+        let (near, far) = (projection_near(&uniforms.projection_matrix), projection_far(&uniforms.projection_matrix));
+        let mut stats = RenderStats::new();
+        for tri in triangles {
+            stats.triangles += 1;
+            match coverage(tri[0], tri[1], tri[2], near, &scissor) {
+                Coverage::Culled => {
+                    stats.culled_triangles += 1;
+                    continue;
+                }
+                Coverage::Clipped => stats.clipped_triangles += 1,
+                Coverage::Inside => {}
+            }
+            // Fragment Processing Stage: cada fragmento se sombrea y se escribe en cuanto el
+            // rasterizador lo genera. Las dos etapas van entrelazadas, así que el tiempo del
+            // fragment shader se mide fragmento a fragmento y el resto cuenta como rasterización
+            let mut fragment_time = Duration::ZERO;
+            let ((), triangle_time) = self.measure(|| {
+                clip_near(tri, near, &uniforms.projection_matrix, &uniforms.viewport_matrix, |tri| triangle(tri, &self.light, &material, uniforms.camera_position, far, &scissor, |fragment| {
+                    stats.fragments += 1;
+                    // Prueba de profundidad anticipada: no sombrear lo que ya está tapado
+                    if fragment.depth >= framebuffer.depth_at(fragment.position.x as i32, fragment.position.y as i32) {
+                        stats.depth_rejected_fragments += 1;
+                        return;
+                    }
+                    stats.shaded_fragments += 1;
+
+                    let ((), shading_time) = self.measure(|| {
+                        // Run the surface shader to compute final color and coverage
+                        let shaded_color = shader.shade(fragment, uniforms);
+                        write_fragment(framebuffer, fragment, shaded_color, shader, uniforms, emissive);
+                    });
+                    fragment_time += shading_time;
+                }))
+            });
+            stats.raster_time += triangle_time.saturating_sub(fragment_time);
+            stats.fragment_time += fragment_time;
+        }
+        self.stats.borrow_mut().add(&stats);
+    }
+
+    /// `rasterize` con el color base en la GPU: los fragmentos que pasan la prueba de
+    /// profundidad anticipada se guardan, la GPU los sombrea todos juntos y después se terminan y
+    /// se escriben en el orden en que salieron, así que el resultado es el mismo que en la CPU
+    #[cfg(feature = "gpu")]
+    fn rasterize_gpu<'a>(
+        &self,
+        gpu: &RefCell<GpuShading>,
+        planet_type: PlanetType,
+        framebuffer: &mut Framebuffer,
+        uniforms: &Uniforms,
+        triangles: impl Iterator<Item = [&'a Vertex; 3]>,
+        shader: &dyn SurfaceShader,
+    ) {
+        let material = shader.material();
+        let emissive = material.emissive;
+        let scissor = framebuffer.scissor();
+        let (near, far) = (projection_near(&uniforms.projection_matrix), projection_far(&uniforms.projection_matrix));
+        let mut stats = RenderStats::new();
+        let (mut fragments, mut colors) = self.gpu_scratch.take();
+        fragments.clear();
+
+        // Rasterization Stage: igual que en la CPU, pero los fragmentos visibles se guardan
+        let ((), raster_time) = self.measure(|| {
+            for tri in triangles {
+                stats.triangles += 1;
+                match coverage(tri[0], tri[1], tri[2], near, &scissor) {
+                    Coverage::Culled => {
+                        stats.culled_triangles += 1;
+                        continue;
+                    }
+                    Coverage::Clipped => stats.clipped_triangles += 1,
+                    Coverage::Inside => {}
+                }
+                clip_near(tri, near, &uniforms.projection_matrix, &uniforms.viewport_matrix, |tri| triangle(tri, &self.light, &material, uniforms.camera_position, far, &scissor, |fragment| {
+                    stats.fragments += 1;
+                    if fragment.depth >= framebuffer.depth_at(fragment.position.x as i32, fragment.position.y as i32) {
+                        stats.depth_rejected_fragments += 1;
+                        return;
+                    }
+                    fragments.push(*fragment);
+                }));
+            }
+        });
+
+        // Fragment Processing Stage: color base en la GPU y el resto en la CPU. Si la GPU
+        // falla, este lote (y los siguientes, ver `GpuShading::supports`) se sombrea en la CPU
+        let ((), fragment_time) = self.measure(|| {
+            match gpu.borrow_mut().shade_surface(planet_type, uniforms, &fragments, &mut colors) {
+                Ok(()) => stats.gpu_fragments += fragments.len() as u64,
+                Err(e) => {
+                    eprintln!("Fragment stage en la GPU desactivado: {}", e);
+                    colors.clear();
+                    colors.extend(fragments.iter().map(|fragment| planet_surface_color(fragment, uniforms, planet_type)));
+                }
+            }
+            for (fragment, base) in fragments.iter().zip(&colors) {
+                let shaded_color = shader.finish_gpu(*base, fragment, uniforms);
+                write_fragment(framebuffer, fragment, shaded_color, shader, uniforms, emissive);
+            }
+        });
+        stats.shaded_fragments += fragments.len() as u64;
+        stats.raster_time += raster_time;
+        stats.fragment_time += fragment_time;
+        self.gpu_scratch.replace((fragments, colors));
+        self.stats.borrow_mut().add(&stats);
+    }
+
+    /// Función especializada para renderizar el sol con vertex shader especial
+    /// Malla indexada, como `draw_indexed_mesh`; `star` define la paleta, la corona y las llamaradas
+    pub fn draw_sun(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertices: &[Vertex], indices: &[u32], star: &Star) {
+        // Vertex Shader Stage - Usa el vertex shader especial del sol (una vez por vértice único)
+        let mut transformed_vertices = self.vertex_scratch.take();
+        let ((), vertex_time) = self.measure(|| {
+            transformed_vertices.clear();
+            transformed_vertices.extend(vertices.iter().map(|vertex| vertex_shader_sun(vertex, uniforms)));
+        });
+
+        // Primitive Assembly Stage
+        let triangles = indices.chunks_exact(3).filter_map(|tri| {
+            Some([
+                transformed_vertices.get(tri[0] as usize)?,
+                transformed_vertices.get(tri[1] as usize)?,
+                transformed_vertices.get(tri[2] as usize)?,
+            ])
+        });
+
+        // Rasterization Stage
+        let material = PlanetType::Sun.material();
+        let scissor = framebuffer.scissor();
+        let (near, far) = (projection_near(&uniforms.projection_matrix), projection_far(&uniforms.projection_matrix));
+        let mut stats = RenderStats { vertex_time, ..RenderStats::new() };
+        for tri in triangles {
+            stats.triangles += 1;
+            match coverage(tri[0], tri[1], tri[2], near, &scissor) {
+                Coverage::Culled => {
+                    stats.culled_triangles += 1;
+                    continue;
+                }
+                Coverage::Clipped => stats.clipped_triangles += 1,
+                Coverage::Inside => {}
+            }
+            // Fragment Processing Stage - Usa el shader del sol, fragmento a fragmento como `rasterize`
+            let mut fragment_time = Duration::ZERO;
+            let ((), triangle_time) = self.measure(|| {
+                clip_near(tri, near, &uniforms.projection_matrix, &uniforms.viewport_matrix, |tri| triangle(tri, &self.light, &material, uniforms.camera_position, far, &scissor, |fragment| {
+                    stats.fragments += 1;
+                    if fragment.depth >= framebuffer.depth_at(fragment.position.x as i32, fragment.position.y as i32) {
+                        stats.depth_rejected_fragments += 1;
+                        return;
+                    }
+                    stats.shaded_fragments += 1;
+
+                    let ((), shading_time) = self.measure(|| {
+                        let final_color = shader_sun(fragment, uniforms.time, star);
+
+                        // El sol es todo emisión: su superficie entera alimenta el bloom
+                        let (x, y) = (fragment.position.x as i32, fragment.position.y as i32);
+                        if framebuffer.point(x, y, final_color, fragment.depth) {
+                            framebuffer.add_emission(x, y, final_color);
+                        }
+                    });
+                    fragment_time += shading_time;
+                }))
+            });
+            stats.raster_time += triangle_time.saturating_sub(fragment_time);
+            stats.fragment_time += fragment_time;
+        }
+        self.vertex_scratch.replace(transformed_vertices);
+        self.stats.borrow_mut().add(&stats);
+    }
+
+    /// Dibujar una línea 3D en espacio de mundo (órbitas, ejes, rayos de depuración)
+    /// Los extremos se transforman con la vista y la proyección de `uniforms` (la matriz de modelo
+    /// no se usa), se recortan contra el plano cercano y la línea se rasteriza con prueba de
+    /// profundidad; `color` está en espacio lineal. Como la MVP de las mallas, los extremos se
+    /// pasan a espacio de vista relativos a la cámara para que no tiemblen lejos del origen
+    pub fn draw_line_3d(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, a: Vector3, b: Vector3, color: Vector3) {
+        let (view, eye) = (&uniforms.view_matrix, uniforms.camera_position);
+        let projection = &uniforms.projection_matrix;
+        let near = projection_near(projection);
+        let mut clip_a = multiply_matrix_vector4(projection, &camera_relative_point(view, eye, a));
+        let mut clip_b = multiply_matrix_vector4(projection, &camera_relative_point(view, eye, b));
+
+        // Recorte contra el plano cercano: la distancia a la cámara es lineal en espacio de recorte
+        // (w en perspectiva, z en ortográfica), así que también lo es la interpolación
+        let distance = |clip: &Vector4| view_distance(clip.z, clip.w, projection);
+        let (distance_a, distance_b) = (distance(&clip_a), distance(&clip_b));
+        if distance_a < near && distance_b < near {
+            return;
+        }
+        let clip_to_near = |inside: Vector4, outside: Vector4, inside_distance: f32, outside_distance: f32| -> Vector4 {
+            let t = (near - inside_distance) / (outside_distance - inside_distance);
+            Vector4::new(
+                inside.x + (outside.x - inside.x) * t,
+                inside.y + (outside.y - inside.y) * t,
+                inside.z + (outside.z - inside.z) * t,
+                inside.w + (outside.w - inside.w) * t,
+            )
+        };
+        if distance_a < near {
+            clip_a = clip_to_near(clip_b, clip_a, distance_b, distance_a);
+        } else if distance_b < near {
+            clip_b = clip_to_near(clip_a, clip_b, distance_a, distance_b);
+        }
+
+        let mut start = Vertex::new_with_color(a, color);
+        let mut end = Vertex::new_with_color(b, color);
+        start.transformed_position = clip_to_screen(&clip_a, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+        end.transformed_position = clip_to_screen(&clip_b, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+
+        for fragment in line(&start, &end, &framebuffer.scissor()) {
+            framebuffer.point(fragment.position.x as i32, fragment.position.y as i32, fragment.color, fragment.depth);
+        }
+    }
+
+    /// Dibujar un punto 3D en espacio de mundo como un cuadrado de `size` píxeles con prueba de profundidad
+    pub fn draw_point_3d(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms, point: Vector3, color: Vector3, size: i32) {
+        let view_position = camera_relative_point(&uniforms.view_matrix, uniforms.camera_position, point);
+        let clip = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+        if view_distance(clip.z, clip.w, &uniforms.projection_matrix) < projection_near(&uniforms.projection_matrix) {
+            return;
+        }
+
+        let screen = clip_to_screen(&clip, &uniforms.projection_matrix, &uniforms.viewport_matrix);
+        let size = size.max(1);
+        let x0 = screen.x as i32 - size / 2;
+        let y0 = screen.y as i32 - size / 2;
+        for y in y0..y0 + size {
+            for x in x0..x0 + size {
+                framebuffer.point(x, y, color, screen.z);
+            }
+        }
+    }
+}
+
+/// Escribe en el framebuffer un fragmento ya sombreado con `shaded_color`: le suma la emisión
+/// del shader y la del material (`emissive`), sin pasar por la iluminación, y lo mezcla sin
+/// escribir profundidad si el shader lo hace translúcido
+fn write_fragment(
+    framebuffer: &mut Framebuffer,
+    fragment: &Fragment,
+    shaded_color: Vector3,
+    shader: &dyn SurfaceShader,
+    uniforms: &Uniforms,
+    emissive: Vector3,
+) {
+    let alpha = shader.alpha(fragment, uniforms);
+    let emission = shader.emission(fragment, uniforms);
+    let emission = Vector3::new(emission.x + emissive.x, emission.y + emissive.y, emission.z + emissive.z);
+    let final_color = Vector3::new(
+        shaded_color.x + emission.x,
+        shaded_color.y + emission.y,
+        shaded_color.z + emission.z,
+    );
+
+    let (x, y) = (fragment.position.x as i32, fragment.position.y as i32);
+    if alpha >= 1.0 {
+        if framebuffer.point(x, y, final_color, fragment.depth) {
+            framebuffer.add_emission(x, y, emission);
+        }
+    } else if alpha > 0.0 {
+        // Fragmentos translúcidos (anillos): mezclar sin escribir profundidad
+        if framebuffer.blend_point(x, y, final_color, alpha, fragment.depth) {
+            framebuffer.add_emission(x, y, Vector3::new(emission.x * alpha, emission.y * alpha, emission.z * alpha));
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use raylib::prelude::*;
+use crate::flight_model::FlightConfig;
+use crate::input_map::Action;
+use crate::input_state::InputState;
+use std::fs;
+use std::io;
+
+/// Un frame grabado: el paso de tiempo que usó la simulación y la entrada que leyó
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplayFrame {
+    pub delta_time: f32,
+    pub input: InputState,
+}
+
+/// Grabación de una partida desde el arranque: las opciones que fijan el estado inicial y, por
+/// cada frame, el paso de tiempo y la `InputState`. Como la simulación solo depende de eso,
+/// reproducirla repite exactamente el mismo vuelo (útil para comparar imágenes del renderer)
+///
+/// Formato de texto: cabecera de líneas `clave = valor` (opciones y constantes de vuelo) y una
+/// línea por frame con `dt ratón_x ratón_y rueda cualquier_tecla mantenidas pulsadas` (las dos
+/// últimas son máscaras de bits en hexadecimal, ver `InputState::action_bits`). La cabecera
+/// `actions` lista los nombres de las acciones en el orden de los bits, así que una repetición
+/// se sigue leyendo bien aunque `Action::ALL` cambie de orden o gane acciones
+pub struct Replay {
+    pub preset: Option<String>, // Escena predefinida (--preset)
+    pub pluto: bool,            // --pluto
+    pub seed: Option<u64>,      // Semilla del sistema generado (--seed)
+    pub new_system_seed: u64,   // Semilla del primer sistema que genera Y sin --seed
+    pub flight: FlightConfig,   // Constantes de vuelo con las que se grabó
+    pub frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    pub fn new(preset: Option<String>, pluto: bool, seed: Option<u64>, new_system_seed: u64, flight: FlightConfig) -> Self {
+        Replay {
+            preset,
+            pluto,
+            seed,
+            new_system_seed,
+            flight,
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, delta_time: f32, input: &InputState) {
+        self.frames.push(ReplayFrame { delta_time, input: input.clone() });
+    }
+
+    /// Segundos simulados de la grabación
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.delta_time).sum()
+    }
+
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let mut contents = String::from("# Repetición: opciones iniciales, constantes de vuelo y un frame por línea\n");
+        if let Some(preset) = &self.preset {
+            contents.push_str(&format!("preset = {}\n", preset));
+        }
+        contents.push_str(&format!("pluto = {}\n", self.pluto));
+        if let Some(seed) = self.seed {
+            contents.push_str(&format!("seed = {}\n", seed));
+        }
+        contents.push_str(&format!("new_system_seed = {}\n", self.new_system_seed));
+        contents.push_str(&self.flight.to_config_string());
+        let names: Vec<&str> = Action::ALL.iter().map(|action| action.name()).collect();
+        contents.push_str(&format!("actions = {}\n", names.join(", ")));
+
+        // `{}` escribe el f32 más corto que se vuelve a leer igual: la reproducción es exacta
+        for frame in &self.frames {
+            let input = &frame.input;
+            let (down, pressed) = input.action_bits();
+            contents.push_str(&format!(
+                "{} {} {} {} {} {:x} {:x}\n",
+                frame.delta_time,
+                input.mouse_delta.x,
+                input.mouse_delta.y,
+                input.mouse_wheel,
+                input.any_pressed as u8,
+                down,
+                pressed,
+            ));
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn load(path: &str) -> io::Result<Self> {
+        Replay::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut replay = Replay::new(None, false, None, 0, FlightConfig::default());
+        // Las líneas de vuelo se pasan a FlightConfig conservando su número de línea
+        let mut flight_lines = Vec::new();
+        // Orden de los bits de las máscaras (None: una acción que ya no existe) y máscaras leídas
+        let mut order: Option<Vec<Option<Action>>> = None;
+        let mut action_bits = Vec::new();
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                flight_lines.push("");
+                continue;
+            }
+
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim();
+                match key.trim() {
+                    "preset" => replay.preset = Some(value.to_string()),
+                    "pluto" => replay.pluto = value.parse().map_err(|e| replay_error(line_number, &format!("{}", e)))?,
+                    "seed" => replay.seed = Some(value.parse().map_err(|e| replay_error(line_number, &format!("{}", e)))?),
+                    "new_system_seed" => {
+                        replay.new_system_seed = value.parse().map_err(|e| replay_error(line_number, &format!("{}", e)))?
+                    }
+                    "actions" => {
+                        let names: Vec<&str> = value.split(',').map(str::trim).filter(|name| !name.is_empty()).collect();
+                        if names.len() > 128 {
+                            return Err(replay_error(line_number, "más de 128 acciones"));
+                        }
+                        order = Some(names.into_iter().map(Action::from_name).collect());
+                    }
+                    _ => {
+                        flight_lines.push(line);
+                        continue;
+                    }
+                }
+                flight_lines.push("");
+                continue;
+            }
+
+            flight_lines.push("");
+            let values: Vec<&str> = line.split_whitespace().collect();
+            if values.len() != 7 {
+                return Err(replay_error(line_number, &format!("se esperaban 7 valores, hay {}", values.len())));
+            }
+            let float = |value: &str| value.parse::<f32>().map_err(|e| replay_error(line_number, &format!("{}", e)));
+            let bits = |value: &str| u128::from_str_radix(value, 16).map_err(|e| replay_error(line_number, &format!("{}", e)));
+
+            let mut input = InputState::new();
+            input.mouse_delta = Vector2::new(float(values[1])?, float(values[2])?);
+            input.mouse_wheel = float(values[3])?;
+            input.any_pressed = values[4] == "1";
+            action_bits.push((bits(values[5])?, bits(values[6])?));
+            replay.frames.push(ReplayFrame { delta_time: float(values[0])?, input });
+        }
+
+        // Las repeticiones anteriores a la cabecera `actions` se leen con el orden actual
+        let order = order.unwrap_or_else(|| Action::ALL.iter().map(|&action| Some(action)).collect());
+        for (frame, (down, pressed)) in replay.frames.iter_mut().zip(action_bits) {
+            frame.input.set_action_bits(remap_action_bits(down, &order), remap_action_bits(pressed, &order));
+        }
+
+        replay.flight = FlightConfig::parse(&flight_lines.join("\n"))?;
+        // Las repeticiones grabadas antes de que hubiera gravedad se reproducen sin ella
+        if !flight_lines.iter().any(|line| line.split('=').next().is_some_and(|key| key.trim() == "gravity")) {
+            replay.flight.gravity = 0.0;
+        }
+        Ok(replay)
+    }
+}
+
+/// Reproduce una grabación frame a frame
+pub struct ReplayPlayer {
+    replay: Replay,
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        ReplayPlayer { replay, cursor: 0 }
+    }
+
+    /// Siguiente frame, o None al terminar
+    pub fn next_frame(&mut self) -> Option<ReplayFrame> {
+        let frame = self.replay.frames.get(self.cursor).cloned();
+        self.cursor += 1;
+        frame
+    }
+
+    /// Fracción reproducida en [0, 1]
+    pub fn progress(&self) -> f32 {
+        (self.cursor as f32 / self.replay.frames.len().max(1) as f32).min(1.0)
+    }
+
+    #[allow(dead_code)]
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
+
+/// Pasa una máscara grabada con los bits en el orden `order` al orden de `Action::ALL`; los bits
+/// de acciones que ya no existen se descartan
+fn remap_action_bits(bits: u128, order: &[Option<Action>]) -> u128 {
+    order
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .filter_map(|(_, action)| *action)
+        .fold(0, |mapped, action| mapped | (1 << action.index()))
+}
+
+fn replay_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
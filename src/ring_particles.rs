@@ -0,0 +1,149 @@
+use raylib::prelude::*;
+use crate::matrix::{inverse_rotate_vector, rotate_vector};
+use crate::obj::Obj;
+use crate::scene::{Planet, RingSystem};
+use crate::shaders::{ring_density, ring_near_fade};
+use std::sync::Arc;
+
+/// Mallas distintas entre las que se reparten las rocas
+pub const MESH_VARIANTS: usize = 4;
+
+/// Roca de los anillos vista de cerca, ya en el mundo
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RingRock {
+    pub position: Vector3,
+    pub scale: f32,
+    pub spin: Vector3, // Giro propio en cada eje (rad/s)
+    pub mesh: usize,   // Variante de malla (0..MESH_VARIANTS)
+}
+
+/// Anillos de cerca: el disco plano se ve bien de lejos, pero al acercarse se nota que es una
+/// textura. Cerca de la cámara se sustituye por miles de rocas pequeñas que giran sobre sí mismas
+/// dentro de la corona del anillo; el disco se desvanece alrededor de la cámara a la vez que las
+/// rocas aparecen (`ring_near_fade`), así que no hay salto entre las dos representaciones
+/// Las rocas salen de una cuadrícula fija en el plano del anillo (una semilla por celda), así que
+/// al moverse la cámara siempre aparecen las mismas en el mismo sitio y giran con el anillo
+pub struct RingParticles {
+    pub fade_start: f32,       // Distancia de la cámara a la corona del anillo a la que empiezan a aparecer rocas
+    pub fade_end: f32,         // Distancia por debajo de la cual solo se ven rocas alrededor de la cámara
+    pub field_radius: f32,     // Radio alrededor de la cámara en el que se colocan rocas
+    pub cell_size: f32,        // Lado de las celdas de la cuadrícula del plano del anillo
+    pub rocks_per_cell: u32,   // Rocas por celda donde el anillo es más denso
+    pub rock_size: f32,        // Radio de las rocas más grandes
+    pub thickness: f32,        // Grosor del anillo
+    pub meshes: Vec<Arc<Obj>>, // Una malla por variante, de muy pocos triángulos
+}
+
+impl RingParticles {
+    pub fn new() -> Self {
+        RingParticles {
+            fade_start: 3.0,
+            fade_end: 1.0,
+            field_radius: 2.0,
+            cell_size: 0.12,
+            rocks_per_cell: 6,
+            rock_size: 0.03,
+            thickness: 0.05,
+            meshes: (0..MESH_VARIANTS as u32).map(|variant| Arc::new(Obj::generate_asteroid(variant + 100, 4))).collect(),
+        }
+    }
+
+    /// Cuánto se ven las rocas de los anillos de `planet` desde `camera` tras `hours` horas
+    /// simuladas: 0 de lejos (solo el disco) y 1 dentro o muy cerca de la corona
+    pub fn blend(&self, planet: &Planet, camera: Vector3, hours: f64) -> f32 {
+        let Some(rings) = &planet.rings else {
+            return 0.0;
+        };
+        let local = to_ring_space(planet, camera, hours);
+        let radial = (local.x * local.x + local.z * local.z).sqrt();
+        let outside = (rings.inner_radius - radial).max(radial - rings.outer_radius).max(0.0);
+        let distance = (outside * outside + local.y * local.y).sqrt();
+        let t = ((self.fade_start - distance) / (self.fade_start - self.fade_end).max(0.0001)).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// Rocas de los anillos del planeta `planet_idx` alrededor de `camera` (vacío de lejos)
+    #[allow(dead_code)]
+    pub fn rocks(&self, planet_idx: usize, planet: &Planet, camera: Vector3, hours: f64) -> Vec<RingRock> {
+        let mut rocks = Vec::new();
+        self.rocks_into(planet_idx, planet, camera, hours, &mut rocks);
+        rocks
+    }
+
+    /// Como `rocks`, pero añadiéndolas al final de `rocks` (para reutilizar el vector cada frame)
+    pub fn rocks_into(&self, planet_idx: usize, planet: &Planet, camera: Vector3, hours: f64, rocks: &mut Vec<RingRock>) {
+        let blend = self.blend(planet, camera, hours);
+        let Some(rings) = planet.rings.as_ref().filter(|_| blend > 0.0) else {
+            return;
+        };
+        let rotation = planet.rotation(hours);
+        let center = planet.position();
+        let local = to_ring_space(planet, camera, hours);
+        let reach = self.field_radius;
+        let cell = self.cell_size.max(0.001);
+        let (min_i, max_i) = (((local.x - reach) / cell).floor() as i64, ((local.x + reach) / cell).floor() as i64);
+        let (min_j, max_j) = (((local.z - reach) / cell).floor() as i64, ((local.z + reach) / cell).floor() as i64);
+
+        for i in min_i..=max_i {
+            for j in min_j..=max_j {
+                let mut random = cell_random(planet_idx, i, j);
+                for _ in 0..self.rocks_per_cell {
+                    let x = (i as f32 + random()) * cell;
+                    let z = (j as f32 + random()) * cell;
+                    let y = (random() - 0.5) * self.thickness;
+                    let (size, keep) = (random(), random());
+                    let spin = Vector3::new(random() * 4.0 - 2.0, random() * 4.0 - 2.0, random() * 4.0 - 2.0);
+                    let mesh = ((random() * MESH_VARIANTS as f32) as usize).min(MESH_VARIANTS - 1);
+
+                    // Solo dentro de la corona y tantas como la densidad del anillo (las
+                    // divisiones quedan vacías)
+                    let radial = (x * x + z * z).sqrt();
+                    if radial < rings.inner_radius || radial > rings.outer_radius || keep > density(rings, radial) {
+                        continue;
+                    }
+                    let (dx, dy, dz) = (x - local.x, y - local.y, z - local.z);
+                    let fade = ring_near_fade((dx * dx + dy * dy + dz * dz).sqrt(), self.field_radius) * blend;
+                    if fade <= 0.0 {
+                        continue;
+                    }
+
+                    let offset = rotate_vector(Vector3::new(x, y, z), rotation);
+                    rocks.push(RingRock {
+                        position: Vector3::new(center.x + offset.x, center.y + offset.y, center.z + offset.z),
+                        scale: self.rock_size * (0.4 + size * 0.6) * fade,
+                        spin,
+                        mesh,
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Default for RingParticles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `point` en el espacio de los anillos de `planet`: el plano del anillo es XZ (el ecuador)
+fn to_ring_space(planet: &Planet, point: Vector3, hours: f64) -> Vector3 {
+    let position = planet.position();
+    inverse_rotate_vector(Vector3::new(point.x - position.x, point.y - position.y, point.z - position.z), planet.rotation(hours))
+}
+
+/// Probabilidad de que haya roca a `radial` del centro: la densidad del disco y su opacidad
+fn density(rings: &RingSystem, radial: f32) -> f32 {
+    ring_density(radial, &rings.gaps) * rings.opacity
+}
+
+/// Generador de números en [0, 1) propio de una celda (LCG con las constantes de las partículas)
+fn cell_random(planet_idx: usize, i: i64, j: i64) -> impl FnMut() -> f32 {
+    let mut state = (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (j as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ (planet_idx as u64).wrapping_mul(0x1656_67B1_9E37_79F9);
+    move || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
@@ -0,0 +1,199 @@
+use raylib::prelude::*;
+use crate::autopilot::Autopilot;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::project_to_screen;
+use crate::renderer::Renderer;
+use crate::scene::{BodyRef, Scene};
+use crate::uniforms::Uniforms;
+
+/// Punto de paso de una ruta: un cuerpo (se sigue su movimiento) o unas coordenadas fijas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Waypoint {
+    Body(BodyRef),
+    Point(Vector3),
+}
+
+/// Tramo de la ruta entre dos puntos, con su longitud en línea recta
+#[derive(Clone, Copy, Debug)]
+pub struct RouteLeg {
+    pub from: Vector3,
+    pub to: Vector3,
+    pub distance: f32,
+}
+
+/// Ruta planificada: puntos de paso en orden que el piloto automático recorre uno tras otro
+/// Se dibuja como líneas 3D desde la nave, con la distancia de cada tramo anotada en pantalla
+pub struct Route {
+    pub waypoints: Vec<Waypoint>,
+    pub color: Vector3,      // Color de las líneas (lineal)
+    current: Option<usize>,  // Punto de paso hacia el que vuela el piloto automático (None = parada)
+}
+
+impl Route {
+    pub fn new() -> Self {
+        Route {
+            waypoints: Vec::new(),
+            color: Vector3::new(0.3, 0.9, 1.0),
+            current: None,
+        }
+    }
+
+    pub fn add(&mut self, waypoint: Waypoint) {
+        self.waypoints.push(waypoint);
+    }
+
+    /// Quita el último punto de paso; si era el que se estaba recorriendo, la ruta se detiene
+    pub fn remove_last(&mut self) -> Option<Waypoint> {
+        let removed = self.waypoints.pop();
+        if self.current.is_some_and(|current| current >= self.waypoints.len()) {
+            self.current = None;
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.waypoints.clear();
+        self.current = None;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    /// El piloto automático está recorriendo la ruta
+    pub fn is_running(&self) -> bool {
+        self.current.is_some()
+    }
+
+    /// Índice del punto de paso en curso
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Empieza a recorrer la ruta desde el primer punto de paso; devuelve ese punto
+    pub fn start(&mut self) -> Option<Waypoint> {
+        self.current = (!self.waypoints.is_empty()).then_some(0);
+        self.current_waypoint()
+    }
+
+    /// Pasa al siguiente punto de paso al llegar al actual; None (y parada) al terminar la ruta
+    pub fn advance(&mut self) -> Option<Waypoint> {
+        self.current = self.current.map(|current| current + 1).filter(|&next| next < self.waypoints.len());
+        self.current_waypoint()
+    }
+
+    pub fn stop(&mut self) {
+        self.current = None;
+    }
+
+    pub fn current_waypoint(&self) -> Option<Waypoint> {
+        self.current.and_then(|current| self.waypoints.get(current).copied())
+    }
+
+    /// Posición del punto de paso en el instante `time` y radio del cuerpo (0 para coordenadas)
+    /// None si el cuerpo ya no existe en la escena
+    pub fn locate(waypoint: Waypoint, scene: &Scene, time: f32) -> Option<(Vector3, f32)> {
+        match waypoint {
+            Waypoint::Body(body) => scene.body_position(body, time).zip(scene.body_radius(body)),
+            Waypoint::Point(point) => Some((point, 0.0)),
+        }
+    }
+
+    /// Nombre del punto de paso para la interfaz
+    pub fn name(waypoint: Waypoint, scene: &Scene) -> String {
+        match waypoint {
+            Waypoint::Body(body) => scene.body_name(body).unwrap_or("?").to_string(),
+            Waypoint::Point(point) => format!("({:.0}, {:.0}, {:.0})", point.x, point.y, point.z),
+        }
+    }
+
+    /// Tramos pendientes: desde `start` (la nave) hasta el punto en curso y de ahí al resto
+    /// Sin recorrer la ruta se muestran todos los puntos de paso
+    pub fn legs(&self, scene: &Scene, time: f32, start: Vector3) -> Vec<RouteLeg> {
+        let first = self.current.unwrap_or(0);
+        let mut legs = Vec::new();
+        let mut from = start;
+        for &waypoint in self.waypoints.iter().skip(first) {
+            let Some((to, _)) = Self::locate(waypoint, scene, time) else {
+                continue;
+            };
+            let (dx, dy, dz) = (to.x - from.x, to.y - from.y, to.z - from.z);
+            legs.push(RouteLeg { from, to, distance: (dx * dx + dy * dy + dz * dz).sqrt() });
+            from = to;
+        }
+        legs
+    }
+
+    /// Longitud total de la ruta pendiente
+    pub fn total_distance(&self, scene: &Scene, time: f32, start: Vector3) -> f32 {
+        self.legs(scene, time, start).iter().map(|leg| leg.distance).sum()
+    }
+
+    /// Activa el piloto automático hacia el punto de paso en curso
+    /// Devuelve false si no hay punto en curso o su cuerpo ya no existe
+    pub fn engage(&self, autopilot: &mut Autopilot, scene: &Scene, time: f32, ship_position: Vector3, heading: Vector3) -> bool {
+        let Some(waypoint) = self.current_waypoint() else {
+            return false;
+        };
+        let Some((position, radius)) = Self::locate(waypoint, scene, time) else {
+            return false;
+        };
+        match waypoint {
+            Waypoint::Body(body) => autopilot.engage(body, ship_position, heading, position, radius),
+            Waypoint::Point(point) => autopilot.engage_point(ship_position, heading, point),
+        }
+        true
+    }
+
+    /// Dibuja los tramos pendientes como líneas 3D (con prueba de profundidad) y una pequeña cruz
+    /// en cada punto de paso; el tramo en curso va más brillante
+    pub fn render(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, scene: &Scene, time: f32, start: Vector3) {
+        let dim = Vector3::new(self.color.x * 0.5, self.color.y * 0.5, self.color.z * 0.5);
+        for (idx, leg) in self.legs(scene, time, start).iter().enumerate() {
+            let color = if idx == 0 && self.is_running() { self.color } else { dim };
+            renderer.draw_line_3d(framebuffer, uniforms, leg.from, leg.to, color);
+            let size = 0.6;
+            let p = leg.to;
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x - size, p.y, p.z), Vector3::new(p.x + size, p.y, p.z), color);
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x, p.y - size, p.z), Vector3::new(p.x, p.y + size, p.z), color);
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x, p.y, p.z - size), Vector3::new(p.x, p.y, p.z + size), color);
+        }
+    }
+
+    /// Anota la distancia de cada tramo en su punto medio y el orden de cada punto de paso
+    /// `screen_scale` convierte píxeles del framebuffer a píxeles de la ventana (como en `Labels`)
+    pub fn draw_annotations(&self, d: &mut RaylibDrawHandle, uniforms: &Uniforms, scene: &Scene, time: f32, start: Vector3, screen_scale: f32) {
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let viewport = &uniforms.viewport_matrix;
+        let color = Color::new(
+            (self.color.x.clamp(0.0, 1.0) * 255.0) as u8,
+            (self.color.y.clamp(0.0, 1.0) * 255.0) as u8,
+            (self.color.z.clamp(0.0, 1.0) * 255.0) as u8,
+            255,
+        );
+        let first = self.current.unwrap_or(0);
+
+        for (idx, leg) in self.legs(scene, time, start).iter().enumerate() {
+            let middle = Vector3::new(
+                (leg.from.x + leg.to.x) * 0.5,
+                (leg.from.y + leg.to.y) * 0.5,
+                (leg.from.z + leg.to.z) * 0.5,
+            );
+            if let Some(screen) = project_to_screen(middle, view, projection, viewport) {
+                let text = format!("{:.0} u", leg.distance);
+                d.draw_text(&text, (screen.x * screen_scale) as i32 + 4, (screen.y * screen_scale) as i32 - 6, 12, color);
+            }
+            if let Some(screen) = project_to_screen(leg.to, view, projection, viewport) {
+                let text = format!("{}", first + idx + 1);
+                d.draw_text(&text, (screen.x * screen_scale) as i32 + 6, (screen.y * screen_scale) as i32 + 4, 14, color);
+            }
+        }
+    }
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,178 @@
+use raylib::prelude::*;
+use crate::camera::Camera;
+use crate::ephemeris::Ephemeris;
+use crate::input_map::Action;
+use crate::input_state::InputState;
+use crate::matrix::project_to_screen;
+use crate::scene::{BodyRef, Scene};
+use crate::uniforms::Uniforms;
+
+/// Velocidad relativa por debajo de la cual no hay dirección de avance que seguir (u/s)
+const MIN_RELATIVE_SPEED: f32 = 0.05;
+/// Distancia a la que se proyectan los marcadores de dirección desde la cámara
+const MARKER_DISTANCE: f32 = 50.0;
+
+/// Modos del sistema de estabilidad (SAS)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SasMode {
+    KillRotation,  // Frena la inercia del giro
+    Prograde,      // Mantiene la proa en la dirección de la velocidad relativa al objetivo
+    Retrograde,    // La mantiene en contra (para frenar con el motor principal)
+    MatchVelocity, // Empuja hasta igualar la velocidad del objetivo (solo en vuelo newtoniano)
+}
+
+impl SasMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            SasMode::KillRotation => "estabilizar",
+            SasMode::Prograde => "progrado",
+            SasMode::Retrograde => "retrógrado",
+            SasMode::MatchVelocity => "igualar velocidad",
+        }
+    }
+}
+
+/// Asistente de estabilidad: un modo activo como mucho y un cuerpo objetivo respecto al que se
+/// miden las velocidades (sin objetivo, respecto a la estrella). Los controles manuales siempre
+/// tienen prioridad: mientras se gira o se empuja a mano, el SAS no interviene
+pub struct Sas {
+    pub mode: Option<SasMode>,
+    pub target: Option<BodyRef>,
+    pub turn_rate: f32,      // Giro máximo de la proa hacia la dirección pedida (rad/s)
+    pub kill_rate: f32,      // Rapidez con la que se frena el giro (1/s)
+    pub match_response: f32, // Empuje por unidad de velocidad relativa al igualar (1/u)
+}
+
+impl Sas {
+    pub fn new() -> Self {
+        Sas {
+            mode: None,
+            target: None,
+            turn_rate: 1.5,
+            kill_rate: 10.0,
+            match_response: 1.0,
+        }
+    }
+
+    /// Activar `mode`, o apagar el SAS si ya estaba en ese modo; devuelve el modo resultante
+    pub fn toggle(&mut self, mode: SasMode) -> Option<SasMode> {
+        self.mode = if self.mode == Some(mode) { None } else { Some(mode) };
+        self.mode
+    }
+
+    /// Velocidad de la nave menos la del objetivo en el instante `time`
+    pub fn relative_velocity(&self, scene: &Scene, time: f32, ship_velocity: Vector3) -> Vector3 {
+        let target_velocity = self
+            .target
+            .and_then(|target| Ephemeris::new(scene, time).velocity(target, time))
+            .unwrap_or(Vector3::zero());
+        Vector3::new(
+            ship_velocity.x - target_velocity.x,
+            ship_velocity.y - target_velocity.y,
+            ship_velocity.z - target_velocity.z,
+        )
+    }
+
+    /// Dirección de avance (progrado) de una velocidad relativa; None casi en reposo
+    pub fn prograde(relative_velocity: Vector3) -> Option<Vector3> {
+        let v = relative_velocity;
+        let speed = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+        (speed >= MIN_RELATIVE_SPEED).then(|| Vector3::new(v.x / speed, v.y / speed, v.z / speed))
+    }
+
+    /// Un paso del SAS: gira la proa según el modo y devuelve el empuje que pide para igualar la
+    /// velocidad (None si no lo pide o se está empujando a mano)
+    pub fn update(&self, camera: &mut Camera, input: &InputState, relative_velocity: Vector3, delta_time: f32) -> Option<Vector3> {
+        let rotating = [Action::PitchUp, Action::PitchDown, Action::YawLeft, Action::YawRight]
+            .iter()
+            .any(|&action| input.is_down(action));
+        let translating = [Action::Forward, Action::Backward, Action::StrafeLeft, Action::StrafeRight, Action::Ascend, Action::Descend]
+            .iter()
+            .any(|&action| input.is_down(action));
+
+        let mode = self.mode?;
+        match mode {
+            SasMode::KillRotation if !rotating => camera.damp_rotation(self.kill_rate, delta_time),
+            SasMode::Prograde | SasMode::Retrograde if !rotating => {
+                if let Some(prograde) = Sas::prograde(relative_velocity) {
+                    let sign = if mode == SasMode::Retrograde { -1.0 } else { 1.0 };
+                    let goal = Vector3::new(prograde.x * sign, prograde.y * sign, prograde.z * sign);
+                    camera.turn_towards(goal, self.turn_rate, delta_time);
+                }
+            }
+            SasMode::MatchVelocity if !translating => {
+                // Empuje opuesto a la velocidad relativa, proporcional a ella para no pasarse
+                let v = relative_velocity;
+                let response = self.match_response;
+                let command = Vector3::new(-v.x * response, -v.y * response, -v.z * response);
+                let length = (command.x * command.x + command.y * command.y + command.z * command.z).sqrt();
+                return Some(Vector3::new(command.x / length.max(1.0), command.y / length.max(1.0), command.z / length.max(1.0)));
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Marcadores de dirección en pantalla: progrado (círculo con punto), retrógrado (círculo con
+    /// aspa) y objetivo (rombo). `screen_scale` convierte píxeles del framebuffer a los de la ventana
+    pub fn draw_markers(&self, d: &mut RaylibDrawHandle, uniforms: &Uniforms, eye: Vector3, relative_velocity: Vector3, target_position: Option<Vector3>, screen_scale: f32) {
+        let project = |direction: Vector3| {
+            let point = Vector3::new(
+                eye.x + direction.x * MARKER_DISTANCE,
+                eye.y + direction.y * MARKER_DISTANCE,
+                eye.z + direction.z * MARKER_DISTANCE,
+            );
+            project_to_screen(point, &uniforms.view_matrix, &uniforms.projection_matrix, &uniforms.viewport_matrix)
+                .map(|screen| ((screen.x * screen_scale) as i32, (screen.y * screen_scale) as i32))
+        };
+        let prograde_color = Color::new(230, 220, 60, 255);
+        let target_color = Color::new(230, 90, 230, 255);
+
+        if let Some(prograde) = Sas::prograde(relative_velocity) {
+            if let Some((x, y)) = project(prograde) {
+                d.draw_circle_lines(x, y, 9.0, prograde_color);
+                d.draw_circle(x, y, 2.0, prograde_color);
+                d.draw_line(x - 15, y, x - 9, y, prograde_color);
+                d.draw_line(x + 9, y, x + 15, y, prograde_color);
+                d.draw_line(x, y - 15, x, y - 9, prograde_color);
+            }
+            if let Some((x, y)) = project(Vector3::new(-prograde.x, -prograde.y, -prograde.z)) {
+                d.draw_circle_lines(x, y, 9.0, prograde_color);
+                d.draw_line(x - 6, y - 6, x + 6, y + 6, prograde_color);
+                d.draw_line(x - 6, y + 6, x + 6, y - 6, prograde_color);
+            }
+        }
+
+        let direction = target_position.and_then(|position| {
+            let delta = Vector3::new(position.x - eye.x, position.y - eye.y, position.z - eye.z);
+            let distance = (delta.x * delta.x + delta.y * delta.y + delta.z * delta.z).sqrt();
+            (distance > 0.0001).then(|| Vector3::new(delta.x / distance, delta.y / distance, delta.z / distance))
+        });
+        if let Some((x, y)) = direction.and_then(project) {
+            let size = 10;
+            d.draw_line(x, y - size, x + size, y, target_color);
+            d.draw_line(x + size, y, x, y + size, target_color);
+            d.draw_line(x, y + size, x - size, y, target_color);
+            d.draw_line(x - size, y, x, y - size, target_color);
+        }
+    }
+
+    /// Estado del SAS sobre el panel de sistemas de la nave: modo, objetivo y velocidad relativa
+    pub fn draw_status(&self, d: &mut RaylibDrawHandle, scene: &Scene, relative_velocity: Vector3, x: i32, y: i32) {
+        if self.mode.is_none() && self.target.is_none() {
+            return;
+        }
+        let v = relative_velocity;
+        let speed = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+        let mode = self.mode.map_or("apagado", SasMode::name);
+        let target = self.target.and_then(|target| scene.body_name(target)).unwrap_or("sol");
+        let text = format!("SAS {}  objetivo {}  {:.1} u/s relativa", mode.to_uppercase(), target, speed);
+        d.draw_text(&text, x, y, 10, Color::new(120, 220, 255, 255));
+    }
+}
+
+impl Default for Sas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,1127 @@
+use crate::clock::DEFAULT_HOURS_PER_SECOND;
+use crate::ecs::{Orbit, Rotation, Spin, Transform};
+use crate::matrix::{inverse_rotate_vector, rotate_vector};
+use crate::nebula::NebulaConfig;
+use crate::presets::real_solar_system;
+use crate::shaders::{PlanetType, SurfaceShader};
+use crate::spatial_index::SpatialIndex;
+use crate::star::{SpectralClass, Star};
+use crate::units::{orbital_speed_for_period, radius_km_to_scale, split_quantity, DistanceUnit, TimeUnit};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+use std::sync::Arc;
+
+/// Banda vacía dentro de un sistema de anillos (estilo división de Cassini)
+#[derive(Clone, Copy, Debug)]
+pub struct RingGap {
+    pub radius: f32,   // Radio central de la banda
+    pub width: f32,    // Ancho total de la banda
+    pub density: f32,  // Densidad residual dentro de la banda (0.0 = completamente vacía)
+}
+
+/// Sistema de anillos de un planeta
+#[derive(Clone, Debug)]
+pub struct RingSystem {
+    pub inner_radius: f32,   // Radio interno del anillo
+    pub outer_radius: f32,   // Radio externo del anillo
+    pub opacity: f32,        // Opacidad máxima en las zonas más densas
+    pub gaps: Vec<RingGap>,  // Bandas vacías
+}
+
+/// Cráter de impacto sobre la superficie de un planeta (lo dibuja el fragment shader)
+#[derive(Clone, Copy, Debug)]
+pub struct Crater {
+    pub center: Vector3,  // Centro en espacio de objeto (dirección normalizada sobre la esfera unitaria)
+    pub radius: f32,      // Radio angular del cráter (radianes)
+    pub time: f32,        // Instante del impacto: el fondo brilla al rojo vivo mientras se enfría
+}
+
+/// Erupción volcánica en la superficie de un planeta: mientras dura, la lava alrededor de la boca
+/// brilla más (lo dibuja el fragment shader) y la boca expulsa eyecta incandescente
+#[derive(Clone, Copy, Debug)]
+pub struct Eruption {
+    pub center: Vector3,  // Boca en espacio de objeto (dirección normalizada sobre la esfera unitaria)
+    pub radius: f32,      // Radio angular de la zona de lava que se aviva (radianes)
+    pub start_time: f32,  // Instante en que empieza
+    pub duration: f32,    // Segundos que dura
+}
+
+impl Eruption {
+    /// Intensidad en [0, 1]: se enciende en medio segundo y se apaga poco a poco hasta el final
+    pub fn intensity(&self, time: f32) -> f32 {
+        let age = time - self.start_time;
+        if age < 0.0 || age >= self.duration {
+            return 0.0;
+        }
+        (age / 0.5).min(1.0) * (1.0 - age / self.duration).sqrt()
+    }
+
+    pub fn is_over(&self, time: f32) -> bool {
+        time - self.start_time >= self.duration
+    }
+}
+
+/// Tormenta persistente en la atmósfera de un gigante gaseoso (estilo Gran Mancha Roja): un
+/// óvalo que gira sobre sí mismo y deriva despacio en longitud con el tiempo simulado
+#[derive(Clone, Copy, Debug)]
+pub struct Storm {
+    pub latitude: f32,   // Latitud del centro (radianes)
+    pub longitude: f32,  // Longitud del centro al empezar la simulación (radianes)
+    pub radius: f32,     // Semieje norte-sur (radianes); el óvalo es el doble de ancho en longitud
+    pub drift: f32,      // Deriva en longitud (radianes por hora simulada; negativo = hacia el oeste)
+    pub spin: f32,       // Giro del remolino (radianes por segundo; negativo = sentido horario)
+    pub color: Vector3,  // Color sRGB del núcleo
+}
+
+impl Storm {
+    /// La tormenta tras `hours` horas simuladas (con la longitud ya desplazada)
+    pub fn at(&self, hours: f64) -> Storm {
+        let longitude = (self.longitude as f64 + self.drift as f64 * hours).rem_euclid(2.0 * std::f64::consts::PI) as f32;
+        Storm { longitude, ..*self }
+    }
+}
+
+/// Atmósfera de un planeta para la aproximación de dispersión simple del fragment shader: la luz
+/// rasante pierde antes el azul (atardeceres naranjas en el terminador) y el borde del disco se
+/// ilumina de azul de día y de naranja cerca del terminador
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Atmosphere {
+    pub rayleigh: Vector3,  // Coeficientes de dispersión relativos por canal (el azul se dispersa más)
+    pub density: f32,       // Grosor óptico: cuánto se enrojece la luz rasante y cuánto brilla el borde
+    pub city_lights: f32,   // Brillo de las luces de las ciudades en el lado nocturno (0 = deshabitado)
+}
+
+impl Atmosphere {
+    pub fn new(rayleigh: Vector3, density: f32) -> Self {
+        Atmosphere { rayleigh, density, city_lights: 0.0 }
+    }
+
+    pub fn with_city_lights(self, city_lights: f32) -> Self {
+        Atmosphere { city_lights, ..self }
+    }
+
+    /// Atmósfera típica de cada tipo de planeta (None para los cuerpos sin aire)
+    pub fn for_type(planet_type: PlanetType) -> Option<Atmosphere> {
+        match planet_type {
+            PlanetType::Rocky => Some(Atmosphere::new(Vector3::new(0.18, 0.42, 1.0), 0.25)),
+            PlanetType::GasGiant => Some(Atmosphere::new(Vector3::new(0.3, 0.5, 1.0), 0.4)),
+            PlanetType::SciFi => Some(Atmosphere::new(Vector3::new(0.5, 0.25, 1.0), 0.2)),
+            PlanetType::Ice => Some(Atmosphere::new(Vector3::new(0.15, 0.55, 1.0), 0.3)),
+            _ => None,
+        }
+    }
+}
+
+// Estructura para representar un planeta en el sistema solar
+pub struct Planet {
+    pub name: String,               // Nombre mostrado en etiquetas e interfaz
+    pub orbital_radius: f32,      // Radio de la órbita
+    pub orbital_angle: f32,         // Ángulo actual en la órbita
+    pub orbital_speed: f32,         // Velocidad angular de la órbita
+    pub rotation_period: f32,       // Duración del día sideral (una vuelta completa) en horas simuladas
+    pub scale: f32,                 // Escala del planeta
+    pub axial_tilt: f32,            // Inclinación del eje de rotación (oblicuidad, radianes)
+    pub retrograde: bool,           // Si es true, gira en sentido contrario a su órbita
+    pub planet_type: PlanetType,    // Tipo de shader del planeta
+    pub shader: Option<Arc<dyn SurfaceShader>>, // Shader propio (reemplaza al de planet_type si existe)
+    pub rings: Option<RingSystem>,  // Anillos del planeta (None = sin anillos)
+    pub craters: Vec<Crater>,       // Cráteres de impacto acumulados durante la simulación
+    pub eruptions: Vec<Eruption>,   // Erupciones volcánicas en curso
+    pub storms: Vec<Storm>,         // Tormentas persistentes de la atmósfera (gigantes gaseosos)
+    pub atmosphere: Option<Atmosphere>, // Dispersión de la atmósfera y luces nocturnas (None = sin aire)
+}
+
+impl Planet {
+    /// Cráteres que se conservan: al superarlos se borran los más antiguos (erosionados)
+    pub const MAX_CRATERS: usize = 48;
+
+    /// Erupciones simultáneas como máximo: al superarlas se apaga la más antigua
+    pub const MAX_ERUPTIONS: usize = 4;
+
+    /// Posición orbital actual en el plano eclíptico (XZ, Y=0)
+    pub fn position(&self) -> Vector3 {
+        self.position_after(0.0)
+    }
+
+    /// Posición orbital dentro de `dt` segundos (negativo: en el pasado), sin modificar el planeta
+    /// Se calcula en f64 y solo se redondea al final: en órbitas muy grandes el error del seno y el
+    /// coseno en f32, multiplicado por el radio, haría saltar al planeta de un frame a otro
+    pub fn position_after(&self, dt: f32) -> Vector3 {
+        self.orbit().offset(dt)
+    }
+
+    /// Órbita del planeta con el ángulo actual como fase en el instante 0
+    pub fn orbit(&self) -> Orbit {
+        Orbit::new(self.orbital_radius, self.orbital_angle, self.orbital_speed, 0.0)
+    }
+
+    /// Giro diario del planeta alrededor de su eje inclinado
+    pub fn spin(&self) -> Spin {
+        Spin::Daily { period: self.rotation_period, axial_tilt: self.axial_tilt, retrograde: self.retrograde }
+    }
+
+    /// Rotación del planeta (para create_model_matrix) tras `hours` horas simuladas (SimulationClock)
+    /// El giro propio ocurre alrededor del eje Y local, que queda inclinado `axial_tilt` sobre el eje Z
+    pub fn rotation(&self, hours: f64) -> Vector3 {
+        self.spin().euler(0.0, hours).unwrap_or(Vector3::zero())
+    }
+
+    /// Hora solar local en [0, 24) en el punto de la superficie bajo `world_point` tras `hours`
+    /// horas simuladas: 12 con el sol en el cenit, 6 al amanecer y 18 al atardecer
+    /// (escala de 24 h sea cual sea la duración del día; no tiene en cuenta la inclinación del eje)
+    pub fn local_time_of_day(&self, hours: f64, world_point: Vector3) -> f32 {
+        let rotation = self.rotation(hours);
+        let position = self.position();
+
+        // Longitud del punto y del punto subsolar, ambas en espacio de objeto del planeta
+        let to_point = inverse_rotate_vector(
+            Vector3::new(world_point.x - position.x, world_point.y - position.y, world_point.z - position.z),
+            rotation,
+        );
+        let to_sun = inverse_rotate_vector(Vector3::new(-position.x, -position.y, -position.z), rotation);
+        let longitude = to_point.z.atan2(to_point.x);
+        let subsolar_longitude = to_sun.z.atan2(to_sun.x);
+
+        // El punto subsolar avanza en longitud con el giro (al revés si es retrógrado)
+        let direction = if self.retrograde { -1.0 } else { 1.0 };
+        (12.0 + direction * (subsolar_longitude - longitude) / (2.0 * PI) * 24.0).rem_euclid(24.0)
+    }
+
+    /// Registra el impacto de un asteroide de radio `size` en `world_point` (sobre la superficie)
+    /// tras `hours` horas simuladas; el cráter queda fijo en la superficie y gira con el planeta
+    pub fn add_crater(&mut self, world_point: Vector3, size: f32, hours: f64, time: f32) {
+        let position = self.position();
+        let local = inverse_rotate_vector(
+            Vector3::new(world_point.x - position.x, world_point.y - position.y, world_point.z - position.z),
+            self.rotation(hours),
+        );
+        let length = (local.x * local.x + local.y * local.y + local.z * local.z).sqrt();
+        if length < 0.0001 {
+            return;
+        }
+        if self.craters.len() >= Self::MAX_CRATERS {
+            self.craters.remove(0);
+        }
+        self.craters.push(Crater {
+            center: Vector3::new(local.x / length, local.y / length, local.z / length),
+            // Un cráter mide varias veces el proyectil; radio angular = arco / radio del planeta
+            radius: (size * 2.0 / self.scale.max(0.0001)).clamp(0.03, 0.35),
+            time,
+        });
+    }
+
+    /// Inicia una erupción de `duration` segundos en `center` (dirección en espacio de objeto)
+    /// y olvida las que ya terminaron
+    pub fn add_eruption(&mut self, center: Vector3, time: f32, duration: f32) {
+        let length = (center.x * center.x + center.y * center.y + center.z * center.z).sqrt();
+        if length < 0.0001 {
+            return;
+        }
+        self.eruptions.retain(|eruption| !eruption.is_over(time));
+        if self.eruptions.len() >= Self::MAX_ERUPTIONS {
+            self.eruptions.remove(0);
+        }
+        self.eruptions.push(Eruption {
+            center: Vector3::new(center.x / length, center.y / length, center.z / length),
+            radius: 0.3,
+            start_time: time,
+            duration,
+        });
+    }
+
+    /// Punto de la superficie en la dirección `direction` (espacio de objeto) tras `hours` horas
+    /// simuladas: posición y normal en espacio de mundo (lo contrario de `add_crater`)
+    pub fn surface_point(&self, direction: Vector3, hours: f64) -> (Vector3, Vector3) {
+        let position = self.position();
+        let normal = rotate_vector(direction, self.rotation(hours));
+        let point = Vector3::new(
+            position.x + normal.x * self.scale,
+            position.y + normal.y * self.scale,
+            position.z + normal.z * self.scale,
+        );
+        (point, normal)
+    }
+
+    /// Shader con el que se dibuja el planeta: el propio si se registró uno, si no el de su tipo
+    pub fn surface(&self) -> &dyn SurfaceShader {
+        match &self.shader {
+            Some(shader) => shader.as_ref(),
+            None => &self.planet_type,
+        }
+    }
+}
+
+// Estructura para representar una luna orbitando un planeta
+pub struct Moon {
+    pub name: String,               // Nombre mostrado en etiquetas e interfaz
+    pub parent: usize,              // Índice del planeta alrededor del cual orbita
+    pub orbital_radius: f32,        // Radio de la órbita alrededor del planeta
+    pub orbital_speed: f32,         // Velocidad angular de la órbita (rad/s)
+    pub orbital_phase: f32,         // Ángulo inicial en la órbita
+    pub inclination: f32,           // Inclinación del plano orbital respecto al ecuador del planeta (radianes)
+    pub scale: f32,                 // Escala de la luna (radio de la esfera unitaria)
+    pub planet_type: PlanetType,    // Tipo de shader de la luna
+    pub shader: Option<Arc<dyn SurfaceShader>>, // Shader propio (reemplaza al de planet_type si existe)
+}
+
+impl Moon {
+    /// Posición de la luna relativa a su planeta en el instante `time`
+    pub fn offset(&self, time: f32) -> Vector3 {
+        self.orbit().offset(time)
+    }
+
+    /// Órbita alrededor del planeta (sin padre: en el mundo de entidades se lo asigna quien la crea)
+    pub fn orbit(&self) -> Orbit {
+        Orbit::new(self.orbital_radius, self.orbital_phase, self.orbital_speed, self.inclination)
+    }
+
+    /// Transformación en `position` (ver `Scene::moon_position`) en el instante `time`, con
+    /// acoplamiento de marea: siempre la misma cara hacia el planeta (ver `Orbit::tidal_axes`)
+    pub fn transform(&self, position: Vector3, time: f32) -> Transform {
+        let (x, y, z) = self.orbit().tidal_axes(time);
+        Transform { position, rotation: Rotation::Basis(x, y, z), scale: self.scale }
+    }
+
+    /// Matriz de modelo en `position` en el instante `time`
+    /// Se construye con la base de los ejes en lugar de ángulos de Euler (como las eyecciones solares)
+    pub fn model_matrix(&self, position: Vector3, time: f32) -> Matrix {
+        self.transform(position, time).model_matrix()
+    }
+
+    /// Dirección del mundo expresada en el espacio de objeto de la luna (inversa de la rotación
+    /// de `model_matrix`)
+    pub fn to_object_space(&self, v: Vector3, time: f32) -> Vector3 {
+        self.transform(Vector3::zero(), time).to_object_space(v)
+    }
+
+    /// Shader con el que se dibuja la luna: el propio si se registró uno, si no el de su tipo
+    pub fn surface(&self) -> &dyn SurfaceShader {
+        match &self.shader {
+            Some(shader) => shader.as_ref(),
+            None => &self.planet_type,
+        }
+    }
+}
+
+// Estación espacial orbitando un planeta
+// En espacio de objeto (unidades de `scale`): módulo central a lo largo del eje Y, anillo habitable
+// de radio 1 en el plano XZ unido por radios, y módulo de atraque sobre el extremo superior
+pub struct Station {
+    pub name: String,               // Nombre mostrado en etiquetas e interfaz
+    pub parent: usize,              // Índice del planeta alrededor del cual orbita
+    pub orbital_radius: f32,        // Radio de la órbita alrededor del planeta
+    pub orbital_speed: f32,         // Velocidad angular de la órbita (rad/s)
+    pub orbital_phase: f32,         // Ángulo inicial en la órbita
+    pub inclination: f32,           // Inclinación del plano orbital (radianes)
+    pub scale: f32,                 // Radio del anillo habitable
+    pub spin_speed: f32,            // Giro del anillo habitable alrededor del módulo central (rad/s)
+}
+
+impl Station {
+    /// Radio exterior en espacio de objeto (anillo más el grosor del tubo)
+    pub const OUTER_RADIUS: f32 = 1.12;
+    /// Punto de atraque en espacio de objeto: sobre el módulo de atraque, en el eje del módulo central
+    pub const DOCKING_PORT: Vector3 = Vector3::new(0.0, 0.85, 0.0);
+
+    /// Posición de la estación relativa a su planeta en el instante `time`
+    pub fn offset(&self, time: f32) -> Vector3 {
+        self.orbit().offset(time)
+    }
+
+    /// Órbita alrededor del planeta (sin padre, como la de `Moon::orbit`)
+    pub fn orbit(&self) -> Orbit {
+        Orbit::new(self.orbital_radius, self.orbital_phase, self.orbital_speed, self.inclination)
+    }
+
+    /// Giro del anillo habitable alrededor del módulo central, que no gira
+    pub fn ring_spin(&self) -> Spin {
+        Spin::Steady(Vector3::new(0.0, self.spin_speed, 0.0))
+    }
+}
+
+// Agujero negro fijo en el espacio: horizonte de sucesos esférico rodeado de un disco de acreción
+// en su plano XZ local (inclinado `disk_tilt` alrededor del eje X) y de una lente gravitacional
+pub struct BlackHole {
+    pub name: String,               // Nombre mostrado en etiquetas e interfaz
+    pub position: Vector3,          // Posición en el mundo (no orbita)
+    pub radius: f32,                // Radio del horizonte de sucesos
+    pub disk_inner: f32,            // Radio interno del disco de acreción (unidades del mundo)
+    pub disk_outer: f32,            // Radio externo del disco de acreción
+    pub disk_tilt: f32,             // Inclinación del disco respecto a la eclíptica (radianes)
+    pub disk_temperature: f32,      // Temperatura del borde interno del disco (K)
+    pub disk_speed: f32,            // Velocidad angular del borde interno del disco (rad/s)
+}
+
+impl BlackHole {
+    /// Rotación del disco de acreción (para create_model_matrix); el giro lo anima el shader
+    pub fn disk_rotation(&self) -> Vector3 {
+        Vector3::new(self.disk_tilt, 0.0, 0.0)
+    }
+}
+
+/// Referencia a un cuerpo de la escena por su índice
+/// Permite emparentar otros objetos (la nave, la cámara) a un planeta o una luna
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BodyRef {
+    Planet(usize),
+    Moon(usize),
+    Station(usize),
+    BlackHole(usize),
+}
+
+/// Datos de la escena: la estrella central y todos los cuerpos que la orbitan
+pub struct Scene {
+    pub star: Star,
+    pub planets: Vec<Planet>,
+    pub moons: Vec<Moon>,
+    pub stations: Vec<Station>,
+    pub black_holes: Vec<BlackHole>,
+    pub nebula: Option<NebulaConfig>,  // Nube de gas que rodea el sistema (None = cielo despejado)
+    pub scripts: Vec<String>,          // Scripts de Rhai de la escena (rutas relativas al archivo de escena)
+    pub facts: Vec<(BodyRef, String)>, // Curiosidades de cada cuerpo (`fact`), para la visita guiada
+}
+
+impl Scene {
+    /// Lado de las celdas de `body_index`: los planetas más grandes van a la lista de esferas grandes
+    const BODY_INDEX_CELL_SIZE: f32 = 16.0;
+
+    /// Sistema solar por defecto con 5 planetas orbitando
+    /// Separación aumentada entre planetas y tamaños incrementados
+    pub fn solar_system() -> Self {
+        let planets = vec![
+            Planet {
+                name: String::from("Ferrum"),
+                orbital_radius: 12.0,      // Órbita cercana (aumentado de 4.0)
+                orbital_angle: 0.0,        // Empieza en ángulo 0
+                orbital_speed: 0.5,        // Velocidad rápida
+                rotation_period: 24.0,     // Día de 24 horas
+                scale: 1.5,                 // Planeta pequeño (aumentado de 0.8)
+                axial_tilt: 0.41,           // ~23.4° como la Tierra
+                retrograde: false,
+                planet_type: PlanetType::Rocky,
+                shader: None,
+                rings: None,
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                storms: Vec::new(),
+                atmosphere: Atmosphere::for_type(PlanetType::Rocky).map(|atmosphere| atmosphere.with_city_lights(0.8)),
+            },
+            Planet {
+                name: String::from("Jovis"),
+                orbital_radius: 18.0,       // Órbita media (aumentado de 6.0)
+                orbital_angle: PI * 2.0 / 5.0, // Empieza a 72 grados
+                orbital_speed: 0.3,        // Velocidad media
+                rotation_period: 40.0,
+                scale: 2.0,                // Planeta mediano (aumentado de 1.2)
+                axial_tilt: 0.47,           // ~26.7° como Saturno
+                retrograde: false,
+                planet_type: PlanetType::GasGiant,
+                shader: None,
+                // Anillos alrededor del gigante gaseoso (tamaño aumentado de 2.5, 3.5)
+                rings: Some(RingSystem {
+                    inner_radius: 4.0,
+                    outer_radius: 5.5,
+                    opacity: 0.85,
+                    gaps: vec![
+                        RingGap { radius: 4.85, width: 0.2, density: 0.05 },  // División principal
+                        RingGap { radius: 5.3, width: 0.06, density: 0.2 },   // Hueco fino exterior
+                    ],
+                }),
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                // Gran mancha roja en el hemisferio sur y un óvalo blanco más pequeño al norte
+                storms: vec![
+                    Storm { latitude: -0.38, longitude: 0.0, radius: 0.2, drift: -0.05, spin: 0.5, color: Vector3::new(0.75, 0.28, 0.15) },
+                    Storm { latitude: 0.55, longitude: 2.0, radius: 0.08, drift: 0.08, spin: 0.8, color: Vector3::new(0.95, 0.9, 0.85) },
+                ],
+                atmosphere: Atmosphere::for_type(PlanetType::GasGiant),
+            },
+            Planet {
+                name: String::from("Neon"),
+                orbital_radius: 24.0,       // Órbita lejana (aumentado de 8.0)
+                orbital_angle: PI * 4.0 / 5.0, // Empieza a 144 grados
+                orbital_speed: 0.2,        // Velocidad lenta
+                rotation_period: 60.0,
+                scale: 1.8,                // Planeta normal (aumentado de 1.0)
+                axial_tilt: 0.05,           // Casi vertical
+                retrograde: false,
+                planet_type: PlanetType::SciFi,
+                shader: None,
+                rings: None,
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                storms: Vec::new(),
+                atmosphere: Atmosphere::for_type(PlanetType::SciFi),
+            },
+            Planet {
+                name: String::from("Glacies"),
+                orbital_radius: 30.0,      // Órbita muy lejana (aumentado de 10.0)
+                orbital_angle: PI * 6.0 / 5.0, // Empieza a 216 grados
+                orbital_speed: 0.15,       // Velocidad muy lenta
+                rotation_period: 30.0,
+                scale: 1.6,                // Planeta helado (aumentado de 0.9)
+                axial_tilt: 1.71,           // ~98° como Urano (gira de lado)
+                retrograde: false,
+                planet_type: PlanetType::Ice,
+                shader: None,
+                rings: None,
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                storms: Vec::new(),
+                atmosphere: Atmosphere::for_type(PlanetType::Ice),
+            },
+            Planet {
+                name: String::from("Ignis"),
+                orbital_radius: 36.0,      // Órbita más lejana (aumentado de 12.0)
+                orbital_angle: PI * 8.0 / 5.0, // Empieza a 288 grados
+                orbital_speed: 0.12,       // Velocidad muy lenta
+                rotation_period: 20.0,
+                scale: 1.9,                // Planeta volcánico (aumentado de 1.1)
+                axial_tilt: 0.05,           // Casi vertical
+                retrograde: true,           // Gira al revés, como Venus
+                planet_type: PlanetType::Volcanic,
+                shader: None,
+                rings: None,
+                craters: Vec::new(),
+                eruptions: Vec::new(),
+                storms: Vec::new(),
+                atmosphere: Atmosphere::for_type(PlanetType::Volcanic),
+            },
+        ];
+
+        let moons = vec![
+            // Luna del planeta rocoso (radio orbital aumentado proporcionalmente al tamaño del planeta)
+            Moon {
+                name: String::from("Selene"),
+                parent: 0,
+                orbital_radius: 2.5,
+                orbital_speed: 1.0,
+                orbital_phase: 0.0,
+                inclination: 0.12, // Ligeramente elevada
+                scale: 0.5,
+                planet_type: PlanetType::Moon,
+                shader: None,
+            },
+            // Lunas del gigante gaseoso (fuera de los anillos)
+            Moon {
+                name: String::from("Calypso"),
+                parent: 1,
+                orbital_radius: 7.0,
+                orbital_speed: 0.6,
+                orbital_phase: PI / 3.0,
+                inclination: 0.05,
+                scale: 0.45,
+                planet_type: PlanetType::Moon,
+                shader: None,
+            },
+            Moon {
+                name: String::from("Rime"),
+                parent: 1,
+                orbital_radius: 8.5,
+                orbital_speed: 0.4,
+                orbital_phase: PI,
+                inclination: -0.2,
+                scale: 0.35,
+                planet_type: PlanetType::Ice,
+                shader: None,
+            },
+            // Luna del planeta helado
+            Moon {
+                name: String::from("Nix"),
+                parent: 3,
+                orbital_radius: 3.0,
+                orbital_speed: 0.8,
+                orbital_phase: PI / 2.0,
+                inclination: 0.3,
+                scale: 0.4,
+                planet_type: PlanetType::Moon,
+                shader: None,
+            },
+        ];
+
+        // Estación espacial en órbita de Neon
+        let stations = vec![Station {
+            name: String::from("Atalaya"),
+            parent: 2,
+            orbital_radius: 4.5,
+            orbital_speed: 0.25,
+            orbital_phase: 0.0,
+            inclination: 0.1, // ~5.7°
+            scale: 0.8,
+            spin_speed: 0.4,
+        }];
+
+        // Agujero negro lejos de las órbitas, ligeramente sobre la eclíptica
+        let black_holes = vec![BlackHole {
+            name: String::from("Abismo"),
+            position: Vector3::new(60.0, 4.0, -45.0),
+            radius: 2.0,
+            disk_inner: 3.5,
+            disk_outer: 9.0,
+            disk_tilt: 0.35, // ~20°
+            disk_temperature: 12000.0,
+            disk_speed: 1.5,
+        }];
+
+        Scene { star: Star::default(), planets, moons, stations, black_holes, nebula: None, scripts: Vec::new(), facts: Vec::new() }
+    }
+
+    /// Nombres aceptados por `Scene::preset`
+    pub const PRESET_NAMES: [&'static str; 2] = ["default", "real"];
+
+    /// Escena predefinida por nombre: `default` (sistema ficticio) o `real` (sistema solar real
+    /// escalado, ver `presets::real_solar_system`). `include_pluto` solo afecta a `real`
+    pub fn preset(name: &str, include_pluto: bool) -> Option<Self> {
+        match name {
+            "default" => Some(Scene::solar_system()),
+            "real" => Some(real_solar_system(include_pluto)),
+            _ => None,
+        }
+    }
+
+    /// Radio de la esfera centrada en el sol que contiene todos los cuerpos (incluye anillos y lunas)
+    pub fn extent(&self) -> f32 {
+        let planets = self.planets.iter().map(|planet| {
+            let ring_radius = planet.rings.as_ref().map_or(1.0, |rings| rings.outer_radius.max(1.0));
+            planet.orbital_radius + planet.scale * ring_radius
+        });
+        let moons = self.moons.iter().filter_map(|moon| {
+            self.planets
+                .get(moon.parent)
+                .map(|parent| parent.orbital_radius + moon.orbital_radius + moon.scale)
+        });
+        let stations = self.stations.iter().filter_map(|station| {
+            self.planets
+                .get(station.parent)
+                .map(|parent| parent.orbital_radius + station.orbital_radius + station.scale * Station::OUTER_RADIUS)
+        });
+        let black_holes = self.black_holes.iter().map(|hole| {
+            let p = hole.position;
+            (p.x * p.x + p.y * p.y + p.z * p.z).sqrt() + hole.radius.max(hole.disk_outer)
+        });
+        planets.chain(moons).chain(stations).chain(black_holes).fold(0.0, f32::max)
+    }
+
+    /// Far plane dinámico para una cámara en `eye`: lo bastante lejos para abarcar todo el
+    /// sistema (con un 20% de margen) y nunca más cerca que `min_far`. Con profundidad
+    /// logarítmica alejarlo no cuesta precisión
+    pub fn far_plane(&self, eye: Vector3, min_far: f32) -> f32 {
+        let eye_distance = (eye.x * eye.x + eye.y * eye.y + eye.z * eye.z).sqrt();
+        ((eye_distance + self.extent()) * 1.2).max(min_far)
+    }
+
+    /// Cargar una escena desde un archivo de texto (ver `assets/scenes/solar_system.scene`)
+    pub fn load(path: &str) -> io::Result<Self> {
+        Scene::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Interpretar el contenido de un archivo de escena
+    /// Secciones `[star]` (estrella central), `[planet]`, `[moon]`, `[station]`, `[black_hole]`, `[rings]`
+    /// (anillos del último planeta), `[gap]` (banda vacía de los últimos anillos), `[storm]` (tormenta
+    /// del último planeta), `[nebula]` (nube de gas de fondo), `[preset]` (añade los cuerpos de una
+    /// escena predefinida) y `[script]` (script de Rhai que se ejecuta con la escena), con líneas
+    /// `clave = valor`. Los cuerpos aceptan además líneas `fact` (curiosidades para la visita guiada)
+    /// Los ángulos van en grados y los colores son `r, g, b` sRGB entre 0 y 1. Las distancias
+    /// admiten unidades reales (`1.52 au`, `227.9e6 km`), el tamaño de un cuerpo su radio en km y
+    /// los periodos un sufijo `h`, `d` o `y`; `orbital_period` sustituye a `orbital_speed`
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut planets: Vec<Planet> = Vec::new();
+        let mut moons = Vec::new();
+        let mut stations = Vec::new();
+        let mut black_holes = Vec::new();
+        let mut nebula = None;
+        let mut scripts = Vec::new();
+        let mut facts = Vec::new();
+        let mut star = Star::default();
+
+        for block in parse_blocks(contents)? {
+            match block.kind.as_str() {
+                "planet" => {
+                    let planet_type = block.planet_type_or("type", PlanetType::Rocky)?;
+                    // La atmósfera típica del tipo, salvo que se desactive o se ajuste
+                    let typical = Atmosphere::for_type(planet_type);
+                    let atmosphere = if block.bool_or("atmosphere", typical.is_some())? {
+                        let base = typical.unwrap_or(Atmosphere::new(Vector3::new(0.18, 0.42, 1.0), 0.25));
+                        Some(Atmosphere {
+                            rayleigh: block.color_or("rayleigh", base.rayleigh)?,
+                            density: block.f32_or("atmosphere_density", base.density)?.max(0.0),
+                            city_lights: block.f32_or("city_lights", 0.0)?.max(0.0),
+                        })
+                    } else {
+                        None
+                    };
+                    planets.push(Planet {
+                        name: block.string_or("name", &format!("Planeta {}", planets.len() + 1)),
+                        orbital_radius: block.required_distance("orbital_radius")?,
+                        orbital_angle: block.degrees_or("orbital_angle", 0.0)?,
+                        orbital_speed: block.orbital_speed_or(0.2)?,
+                        rotation_period: block.rotation_period()?,
+                        scale: block.scale_or(1.0)?,
+                        axial_tilt: block.degrees_or("axial_tilt", 0.0)?,
+                        retrograde: block.bool_or("retrograde", false)?,
+                        planet_type,
+                        shader: None,
+                        rings: None,
+                        craters: Vec::new(),
+                        eruptions: Vec::new(),
+                        storms: Vec::new(),
+                        atmosphere,
+                    });
+                    block.add_facts(BodyRef::Planet(planets.len() - 1), &mut facts);
+                }
+                "rings" => {
+                    let planet = planets
+                        .last_mut()
+                        .ok_or_else(|| scene_error(block.line, "[rings] debe ir después de un [planet]"))?;
+                    planet.rings = Some(RingSystem {
+                        inner_radius: block.required_f32("inner_radius")?,
+                        outer_radius: block.required_f32("outer_radius")?,
+                        opacity: block.f32_or("opacity", 0.85)?,
+                        gaps: Vec::new(),
+                    });
+                }
+                "gap" => {
+                    let rings = planets
+                        .last_mut()
+                        .and_then(|planet| planet.rings.as_mut())
+                        .ok_or_else(|| scene_error(block.line, "[gap] debe ir después de un [rings]"))?;
+                    rings.gaps.push(RingGap {
+                        radius: block.required_f32("radius")?,
+                        width: block.required_f32("width")?,
+                        density: block.f32_or("density", 0.0)?,
+                    });
+                }
+                "storm" => {
+                    let planet = planets
+                        .last_mut()
+                        .ok_or_else(|| scene_error(block.line, "[storm] debe ir después de un [planet]"))?;
+                    planet.storms.push(Storm {
+                        latitude: block.degrees_or("latitude", 0.0)?,
+                        longitude: block.degrees_or("longitude", 0.0)?,
+                        radius: block.degrees_or("radius", 10.0)?,
+                        drift: block.degrees_or("drift", 0.0)?,
+                        spin: block.f32_or("spin", 0.5)?,
+                        color: block.color_or("color", Vector3::new(0.78, 0.3, 0.18))?,
+                    });
+                }
+                "moon" => {
+                    let parent = block.parent_planet(&planets)?;
+                    moons.push(Moon {
+                        name: block.string_or("name", &format!("Luna {}", moons.len() + 1)),
+                        parent,
+                        orbital_radius: block.required_distance("orbital_radius")?,
+                        orbital_speed: block.orbital_speed_or(0.5)?,
+                        orbital_phase: block.degrees_or("orbital_phase", 0.0)?,
+                        inclination: block.degrees_or("inclination", 0.0)?,
+                        scale: block.scale_or(0.4)?,
+                        planet_type: block.planet_type_or("type", PlanetType::Moon)?,
+                        shader: None,
+                    });
+                    block.add_facts(BodyRef::Moon(moons.len() - 1), &mut facts);
+                }
+                "station" => {
+                    let parent = block.parent_planet(&planets)?;
+                    stations.push(Station {
+                        name: block.string_or("name", &format!("Estación {}", stations.len() + 1)),
+                        parent,
+                        orbital_radius: block.required_distance("orbital_radius")?,
+                        orbital_speed: block.orbital_speed_or(0.25)?,
+                        orbital_phase: block.degrees_or("orbital_phase", 0.0)?,
+                        inclination: block.degrees_or("inclination", 0.0)?,
+                        scale: block.f32_or("scale", 0.8)?,
+                        spin_speed: block.f32_or("spin_speed", 0.4)?,
+                    });
+                    block.add_facts(BodyRef::Station(stations.len() - 1), &mut facts);
+                }
+                "black_hole" => {
+                    let radius = block.distance_or("radius", 2.0)?;
+                    let disk_inner = block.distance_or("disk_inner", radius * 1.75)?;
+                    let disk_outer = block.distance_or("disk_outer", radius * 4.5)?;
+                    if disk_outer <= disk_inner {
+                        return Err(scene_error(block.line_of("disk_outer"), "'disk_outer' debe ser mayor que 'disk_inner'"));
+                    }
+                    black_holes.push(BlackHole {
+                        name: block.string_or("name", &format!("Agujero negro {}", black_holes.len() + 1)),
+                        position: Vector3::new(
+                            block.required_distance("x")?,
+                            block.distance_or("y", 0.0)?,
+                            block.required_distance("z")?,
+                        ),
+                        radius,
+                        disk_inner,
+                        disk_outer,
+                        disk_tilt: block.degrees_or("disk_tilt", 0.0)?,
+                        disk_temperature: block.f32_or("disk_temperature", 12000.0)?,
+                        disk_speed: block.f32_or("disk_speed", 1.5)?,
+                    });
+                    block.add_facts(BodyRef::BlackHole(black_holes.len() - 1), &mut facts);
+                }
+                "preset" => {
+                    let name = block.string_or("name", "real");
+                    let preset = Scene::preset(&name, block.bool_or("pluto", false)?)
+                        .ok_or_else(|| scene_error(block.line, &format!("escena predefinida desconocida '{}'", name)))?;
+                    // Los padres de lunas y estaciones se desplazan tras los planetas ya definidos
+                    let offset = planets.len();
+                    moons.extend(preset.moons.into_iter().map(|moon| Moon { parent: moon.parent + offset, ..moon }));
+                    stations.extend(preset.stations.into_iter().map(|station| Station { parent: station.parent + offset, ..station }));
+                    planets.extend(preset.planets);
+                    black_holes.extend(preset.black_holes);
+                    nebula = nebula.or(preset.nebula);
+                    star = preset.star;
+                }
+                "nebula" => {
+                    let base = NebulaConfig::default();
+                    nebula = Some(NebulaConfig {
+                        core_color: block.color_or("color", base.core_color)?,
+                        edge_color: block.color_or("edge_color", base.edge_color)?,
+                        density: block.f32_or("density", base.density)?.max(0.0),
+                        intensity: block.f32_or("intensity", base.intensity)?.max(0.0),
+                        scale: block.f32_or("scale", base.scale)?,
+                        thickness: block.f32_or("thickness", base.thickness)?,
+                        tilt: block.degrees_or("tilt", base.tilt.to_degrees())?,
+                        heading: block.degrees_or("heading", base.heading.to_degrees())?,
+                        seed: block.f32_or("seed", base.seed as f32)? as u32,
+                    });
+                }
+                "star" => {
+                    // La clase da valores típicos; temperature, corona y flares los ajustan
+                    let base = match block.get("class") {
+                        Some(letter) => SpectralClass::from_letter(letter)
+                            .map(Star::from_class)
+                            .ok_or_else(|| scene_error(block.line_of("class"), &format!("clase espectral desconocida '{}'", letter)))?,
+                        None => Star::default(),
+                    };
+                    let temperature = block.f32_or("temperature", base.temperature())?;
+                    if temperature <= 0.0 {
+                        return Err(scene_error(block.line_of("temperature"), "'temperature' debe ser positiva"));
+                    }
+                    star = Star::new(
+                        temperature,
+                        block.f32_or("corona", base.corona_strength)?,
+                        block.f32_or("flares", base.flare_frequency)?,
+                    );
+                }
+                "script" => {
+                    let path = block.get("path").ok_or_else(|| scene_error(block.line, "[script] necesita 'path'"))?;
+                    scripts.push(path.to_string());
+                }
+                other => return Err(scene_error(block.line, &format!("sección desconocida [{}]", other))),
+            }
+        }
+
+        Ok(Scene { star, planets, moons, stations, black_holes, nebula, scripts, facts })
+    }
+
+    /// Todos los cuerpos de la escena: primero los planetas, luego las lunas, las estaciones y los
+    /// agujeros negros
+    pub fn bodies(&self) -> impl Iterator<Item = BodyRef> {
+        (0..self.planets.len())
+            .map(BodyRef::Planet)
+            .chain((0..self.moons.len()).map(BodyRef::Moon))
+            .chain((0..self.stations.len()).map(BodyRef::Station))
+            .chain((0..self.black_holes.len()).map(BodyRef::BlackHole))
+    }
+
+    /// Posición en el mundo del cuerpo `body` (None si el índice no existe)
+    pub fn body_position(&self, body: BodyRef, time: f32) -> Option<Vector3> {
+        match body {
+            BodyRef::Planet(idx) => self.planets.get(idx).map(|planet| planet.position()),
+            BodyRef::Moon(idx) => (idx < self.moons.len()).then(|| self.moon_position(idx, time)),
+            BodyRef::Station(idx) => (idx < self.stations.len()).then(|| self.station_position(idx, time)),
+            BodyRef::BlackHole(idx) => self.black_holes.get(idx).map(|hole| hole.position),
+        }
+    }
+
+    /// Radio del cuerpo `body`
+    pub fn body_radius(&self, body: BodyRef) -> Option<f32> {
+        match body {
+            BodyRef::Planet(idx) => self.planets.get(idx).map(|planet| planet.scale),
+            BodyRef::Moon(idx) => self.moons.get(idx).map(|moon| moon.scale),
+            BodyRef::Station(idx) => self.stations.get(idx).map(|station| station.scale * Station::OUTER_RADIUS),
+            BodyRef::BlackHole(idx) => self.black_holes.get(idx).map(|hole| hole.radius),
+        }
+    }
+
+    /// Cuerpo con el nombre `name` (primero la coincidencia exacta, luego sin distinguir mayúsculas)
+    pub fn find_body(&self, name: &str) -> Option<BodyRef> {
+        self.bodies()
+            .find(|&body| self.body_name(body) == Some(name))
+            .or_else(|| self.bodies().find(|&body| self.body_name(body).is_some_and(|n| n.eq_ignore_ascii_case(name))))
+    }
+
+    /// Nombre del cuerpo `body`
+    pub fn body_name(&self, body: BodyRef) -> Option<&str> {
+        match body {
+            BodyRef::Planet(idx) => self.planets.get(idx).map(|planet| planet.name.as_str()),
+            BodyRef::Moon(idx) => self.moons.get(idx).map(|moon| moon.name.as_str()),
+            BodyRef::Station(idx) => self.stations.get(idx).map(|station| station.name.as_str()),
+            BodyRef::BlackHole(idx) => self.black_holes.get(idx).map(|hole| hole.name.as_str()),
+        }
+    }
+
+    /// Punto de atraque del cuerpo `body` en el mundo y la dirección por la que se llega a él
+    /// (None si el cuerpo no tiene dónde atracar: solo las estaciones tienen)
+    pub fn docking_point(&self, body: BodyRef, time: f32) -> Option<(Vector3, Vector3)> {
+        let BodyRef::Station(idx) = body else {
+            return None;
+        };
+        let station = self.stations.get(idx)?;
+        let position = self.station_position(idx, time);
+        let port = Station::DOCKING_PORT;
+        Some((
+            Vector3::new(
+                position.x + port.x * station.scale,
+                position.y + port.y * station.scale,
+                position.z + port.z * station.scale,
+            ),
+            Vector3::new(0.0, 1.0, 0.0), // El módulo central no gira: el puerto mira siempre hacia +Y
+        ))
+    }
+
+    /// Curiosidades del cuerpo `body` (líneas `fact` de su sección del archivo de escena)
+    pub fn facts(&self, body: BodyRef) -> impl Iterator<Item = &str> {
+        self.facts.iter().filter(move |(of, _)| *of == body).map(|(_, fact)| fact.as_str())
+    }
+
+    /// Índice espacial de todos los cuerpos (sin la estrella) en el instante `time`
+    /// El juego lo reconstruye en cada paso y lo comparten la selección con la mira, la colisión
+    /// de la cámara y las búsquedas del cuerpo o la estación más cercanos
+    pub fn body_index(&self, time: f32) -> SpatialIndex<BodyRef> {
+        let mut index = SpatialIndex::new(Self::BODY_INDEX_CELL_SIZE);
+        for body in self.bodies() {
+            if let Some((position, radius)) = self.body_position(body, time).zip(self.body_radius(body)) {
+                index.insert(body, position, radius);
+            }
+        }
+        index
+    }
+
+    /// Posición en el mundo de la estación `station_idx` en el instante `time`
+    pub fn station_position(&self, station_idx: usize, time: f32) -> Vector3 {
+        let station = &self.stations[station_idx];
+        let parent = self.planets[station.parent].position();
+        let offset = station.offset(time);
+        Vector3::new(parent.x + offset.x, parent.y + offset.y, parent.z + offset.z)
+    }
+
+    /// Posición en el mundo de la luna `moon_idx` en el instante `time`
+    pub fn moon_position(&self, moon_idx: usize, time: f32) -> Vector3 {
+        let moon = &self.moons[moon_idx];
+        let parent = self.planets[moon.parent].position();
+        let offset = moon.offset(time);
+        Vector3::new(parent.x + offset.x, parent.y + offset.y, parent.z + offset.z)
+    }
+
+    /// Luz que el planeta de la luna `moon_idx` refleja sobre ella (como la luz cenicienta de la
+    /// Luna): dirección de mundo hacia el planeta y fuerza relativa a la luz directa del sol
+    /// Crece con la fracción iluminada del planeta vista desde la luna (llena cuando la luna está
+    /// entre el planeta y el sol) y con su tamaño aparente
+    pub fn planetshine(&self, moon_idx: usize, time: f32) -> (Vector3, f32) {
+        let moon = &self.moons[moon_idx];
+        let parent = &self.planets[moon.parent];
+        let parent_position = parent.position();
+        let offset = moon.offset(time);
+        let distance = (offset.x * offset.x + offset.y * offset.y + offset.z * offset.z).sqrt().max(0.0001);
+        let to_parent = Vector3::new(-offset.x / distance, -offset.y / distance, -offset.z / distance);
+
+        let sun_distance = (parent_position.x * parent_position.x + parent_position.y * parent_position.y + parent_position.z * parent_position.z)
+            .sqrt()
+            .max(0.0001);
+        let cos_phase = -(parent_position.x * offset.x + parent_position.y * offset.y + parent_position.z * offset.z) / (sun_distance * distance);
+        let lit_fraction = (1.0 + cos_phase) * 0.5;
+        let apparent_size = parent.scale / distance;
+        (to_parent, (lit_fraction * apparent_size * apparent_size * 0.6).min(0.25))
+    }
+}
+
+/// Sección `[tipo]` del archivo de escena con sus pares clave = valor
+struct SceneBlock {
+    kind: String,
+    line: usize,
+    fields: Vec<(String, String, usize)>, // (clave, valor, línea)
+}
+
+impl SceneBlock {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _, _)| k == key).map(|(_, v, _)| v.as_str())
+    }
+
+    fn line_of(&self, key: &str) -> usize {
+        self.fields.iter().find(|(k, _, _)| k == key).map_or(self.line, |(_, _, line)| *line)
+    }
+
+    /// Añadir a `facts` las líneas `fact` del bloque (puede haber varias) como curiosidades de `body`
+    fn add_facts(&self, body: BodyRef, facts: &mut Vec<(BodyRef, String)>) {
+        facts.extend(self.fields.iter().filter(|(k, _, _)| k == "fact").map(|(_, v, _)| (body, v.clone())));
+    }
+
+    fn string_or(&self, key: &str, default: &str) -> String {
+        self.get(key).unwrap_or(default).to_string()
+    }
+
+    fn f32_or(&self, key: &str, default: f32) -> io::Result<f32> {
+        match self.get(key) {
+            Some(value) => value
+                .parse::<f32>()
+                .map_err(|_| scene_error(self.line_of(key), &format!("'{}' no es un número válido", value))),
+            None => Ok(default),
+        }
+    }
+
+    fn required_f32(&self, key: &str) -> io::Result<f32> {
+        if self.get(key).is_none() {
+            return Err(scene_error(self.line, &format!("falta '{}' en [{}]", key, self.kind)));
+        }
+        self.f32_or(key, 0.0)
+    }
+
+    /// Distancia en unidades de la escena; sin sufijo (o con `u`) ya lo está, con `au`/`ua` o `km`
+    /// se convierte
+    fn distance_or(&self, key: &str, default: f32) -> io::Result<f32> {
+        let Some(value) = self.get(key) else {
+            return Ok(default);
+        };
+        let error = || scene_error(self.line_of(key), &format!("'{}' no es una distancia válida (número con au, ua o km)", value));
+        let (number, suffix) = split_quantity(value).ok_or_else(error)?;
+        Ok(DistanceUnit::from_suffix(suffix).ok_or_else(error)?.to_units(number))
+    }
+
+    fn required_distance(&self, key: &str) -> io::Result<f32> {
+        if self.get(key).is_none() {
+            return Err(scene_error(self.line, &format!("falta '{}' en [{}]", key, self.kind)));
+        }
+        self.distance_or(key, 0.0)
+    }
+
+    /// Duración en días; sin sufijo se entiende en `unit` y si no con `h`, `d` o `y`
+    fn days_or(&self, key: &str, unit: TimeUnit, default_days: f64) -> io::Result<f64> {
+        let Some(value) = self.get(key) else {
+            return Ok(default_days);
+        };
+        let error = || scene_error(self.line_of(key), &format!("'{}' no es una duración válida (número con h, d o y)", value));
+        let (number, suffix) = split_quantity(value).ok_or_else(error)?;
+        let unit = if suffix.is_empty() { unit } else { TimeUnit::from_suffix(suffix).ok_or_else(error)? };
+        Ok(unit.to_days(number))
+    }
+
+    /// `scale` del cuerpo, o su radio real con `km` (comprimido como en el sistema solar real)
+    fn scale_or(&self, default: f32) -> io::Result<f32> {
+        let Some(value) = self.get("scale") else {
+            return Ok(default);
+        };
+        match split_quantity(value) {
+            Some((scale, "")) => Ok(scale as f32),
+            Some((radius, suffix)) if suffix.eq_ignore_ascii_case("km") => Ok(radius_km_to_scale(radius as f32)),
+            _ => Err(scene_error(self.line_of("scale"), &format!("'{}' no es una escala válida (número o radio en km)", value))),
+        }
+    }
+
+    /// Velocidad orbital en rad/s, o la de `orbital_period` (en días si no lleva sufijo)
+    fn orbital_speed_or(&self, default: f32) -> io::Result<f32> {
+        if self.get("orbital_period").is_none() {
+            return self.f32_or("orbital_speed", default);
+        }
+        let days = self.days_or("orbital_period", TimeUnit::Days, 0.0)?;
+        if days <= 0.0 {
+            return Err(scene_error(self.line_of("orbital_period"), "'orbital_period' debe ser positivo"));
+        }
+        Ok(orbital_speed_for_period(days as f32))
+    }
+
+    /// `rotation_period` en horas simuladas (sin sufijo ya lo está); los archivos antiguos con
+    /// `rotation_speed` (rad/s reales) se convierten al ritmo por defecto del reloj
+    fn rotation_period(&self) -> io::Result<f32> {
+        if self.get("rotation_period").is_none() && self.get("rotation_speed").is_some() {
+            let speed = self.f32_or("rotation_speed", 0.0)?;
+            return Ok(2.0 * PI / speed.abs().max(0.0001) * DEFAULT_HOURS_PER_SECOND);
+        }
+        let period = (self.days_or("rotation_period", TimeUnit::Hours, 40.0 / 24.0)? * 24.0) as f32;
+        if period <= 0.0 {
+            return Err(scene_error(self.line_of("rotation_period"), "'rotation_period' debe ser positivo"));
+        }
+        Ok(period)
+    }
+
+    fn degrees_or(&self, key: &str, default_degrees: f32) -> io::Result<f32> {
+        Ok(self.f32_or(key, default_degrees)?.to_radians())
+    }
+
+    /// Color `r, g, b` con componentes entre 0 y 1
+    fn color_or(&self, key: &str, default: Vector3) -> io::Result<Vector3> {
+        let Some(value) = self.get(key) else {
+            return Ok(default);
+        };
+        let error = || scene_error(self.line_of(key), &format!("'{}' no es un color 'r, g, b' válido", value));
+        let components = value
+            .split(',')
+            .map(|component| component.trim().parse::<f32>().map_err(|_| error()))
+            .collect::<io::Result<Vec<f32>>>()?;
+        match components[..] {
+            [r, g, b] => Ok(Vector3::new(r.clamp(0.0, 1.0), g.clamp(0.0, 1.0), b.clamp(0.0, 1.0))),
+            _ => Err(error()),
+        }
+    }
+
+    fn bool_or(&self, key: &str, default: bool) -> io::Result<bool> {
+        match self.get(key) {
+            Some("true") => Ok(true),
+            Some("false") => Ok(false),
+            Some(value) => Err(scene_error(self.line_of(key), &format!("'{}' debe ser true o false", value))),
+            None => Ok(default),
+        }
+    }
+
+    /// Índice del planeta nombrado en `parent` (por nombre o por índice)
+    fn parent_planet(&self, planets: &[Planet]) -> io::Result<usize> {
+        let parent_name = self
+            .get("parent")
+            .ok_or_else(|| scene_error(self.line, &format!("falta 'parent' en [{}]", self.kind)))?;
+        planets
+            .iter()
+            .position(|planet| planet.name == parent_name)
+            .or_else(|| parent_name.parse::<usize>().ok().filter(|&idx| idx < planets.len()))
+            .ok_or_else(|| scene_error(self.line, &format!("planeta '{}' no encontrado", parent_name)))
+    }
+
+    fn planet_type_or(&self, key: &str, default: PlanetType) -> io::Result<PlanetType> {
+        match self.get(key) {
+            Some(value) => PlanetType::from_name(value)
+                .ok_or_else(|| scene_error(self.line_of(key), &format!("tipo de cuerpo desconocido '{}'", value))),
+            None => Ok(default),
+        }
+    }
+}
+
+/// Separar el archivo en secciones; `#` inicia un comentario
+fn parse_blocks(contents: &str) -> io::Result<Vec<SceneBlock>> {
+    let mut blocks: Vec<SceneBlock> = Vec::new();
+
+    for (index, raw_line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(kind) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            blocks.push(SceneBlock { kind: kind.trim().to_string(), line: line_number, fields: Vec::new() });
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| scene_error(line_number, "se esperaba 'clave = valor'"))?;
+        let block = blocks
+            .last_mut()
+            .ok_or_else(|| scene_error(line_number, "clave fuera de una sección"))?;
+        block.fields.push((key.trim().to_string(), value.trim().to_string(), line_number));
+    }
+
+    Ok(blocks)
+}
+
+fn scene_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
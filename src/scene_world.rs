@@ -0,0 +1,362 @@
+use raylib::prelude::*;
+use crate::asteroids::AsteroidBelt;
+use crate::ecs::{Entity, Material, Mesh, Orbit, Pass, Pool, Renderable, Rotation, Spin, SurfaceDetail, Transform, World};
+use crate::material;
+use crate::obj::Obj;
+use crate::quality::sphere_lods;
+use crate::ring_particles::{RingParticles, RingRock};
+use crate::scene::{Scene, Station};
+use crate::shaders::{AuroraShader, FnShader, PlanetType, RingUniforms, ShipShader, StationShader, SurfaceShader};
+use crate::solar_activity::SolarActivity;
+use crate::terrain::SurfaceTerrain;
+use crate::uniforms::FillLight;
+use crate::weapons::Weapons;
+use std::sync::Arc;
+
+/// Mallas compartidas por las entidades del mundo; se generan una vez al arrancar y sirven para
+/// cualquier escena (las de los anillos dependen de cada planeta y las crea `SceneWorld::new`)
+pub struct BodyMeshes {
+    pub sphere: Arc<[Obj]>,       // Planetas, auroras y horizontes: radio 1, un nivel por calidad
+    pub moon: Arc<[Obj]>,         // Lunas: radio 1, con menos segmentos
+    pub sun: Arc<[Obj]>,          // Estrella, ya con su radio
+    pub sun_radius: f32,
+    pub station_hub: Arc<Obj>,
+    pub station_ring: Arc<Obj>,
+    pub asteroids: Vec<Arc<Obj>>, // Una por variante (AsteroidBelt::MESH_VARIANTS)
+    pub projectile: Arc<Obj>,
+}
+
+impl BodyMeshes {
+    pub fn new(sun_radius: f32) -> Self {
+        BodyMeshes {
+            sphere: sphere_lods(1.0, 32).into(),
+            moon: sphere_lods(1.0, 16).into(),
+            sun: sphere_lods(sun_radius, 64).into(),
+            sun_radius,
+            station_hub: Arc::new(Obj::generate_station_hub()),
+            station_ring: Arc::new(Obj::generate_station_ring()),
+            asteroids: (0..AsteroidBelt::MESH_VARIANTS as u32).map(|variant| Arc::new(Obj::generate_asteroid(variant, 8))).collect(),
+            projectile: Arc::new(Obj::generate_sphere(1.0, 6)),
+        }
+    }
+}
+
+/// Entidades de los cuerpos de una escena: se crean una vez al cargarla (o al generar otro
+/// sistema) y cada frame los sistemas de abajo copian en sus componentes lo que cambió en la
+/// simulación (ángulos orbitales, cráteres, tormentas, rocas, proyectiles, la nave)
+/// El orden de creación es el de dibujo, y los planetas van antes que las lunas y estaciones que
+/// los orbitan
+pub struct SceneWorld {
+    pub world: World,
+    planets: Vec<Entity>,
+    rings: Vec<Option<Entity>>,          // Disco de los anillos de cada planeta
+    auroras: Vec<(usize, Entity, f32)>,  // Planeta, capa de aurora y tormenta geomagnética (0 a 1)
+    moons: Vec<Entity>,
+    ship: Entity,
+    asteroids: Pool,
+    ring_rocks: Pool,
+    projectiles: Pool,
+    rocks: Vec<RingRock>,                // Rocas de los anillos del frame (se reutiliza)
+    asteroid_meshes: Vec<Arc<Obj>>,
+    projectile_mesh: Arc<Obj>,
+    rock_shader: Arc<dyn SurfaceShader>, // Asteroides y rocas de los anillos (el de las lunas)
+    projectile_shader: Arc<dyn SurfaceShader>,
+}
+
+impl SceneWorld {
+    pub fn new(scene: &Scene, meshes: &BodyMeshes, particles: &RingParticles) -> Self {
+        let mut world = World::new();
+
+        // El sol en el centro, con una rotación lenta; no se ilumina a sí mismo
+        let star = world.spawn_renderable(
+            Transform::at(Vector3::zero(), 1.0),
+            Renderable { lit: false, bounds: Some(meshes.sun_radius), ..Renderable::new(Mesh::Lods(meshes.sun.clone()), "Sol", Pass::Opaque) },
+            Material::Star(scene.star.clone()),
+        );
+        world.spins.insert(star, Spin::Steady(Vector3::new(0.0, 0.1, 0.0)));
+
+        // Planetas con su giro diario y los datos de superficie que leen sus shaders (la órbita
+        // y lo que cambia durante la simulación lo pone `planet_system`)
+        let mut planets = Vec::with_capacity(scene.planets.len());
+        let mut rings = Vec::with_capacity(scene.planets.len());
+        for planet in &scene.planets {
+            let ring_uniforms = planet.rings.as_ref().map(|rings| RingUniforms {
+                planet_radius: planet.scale,
+                inner_radius: rings.inner_radius,
+                outer_radius: rings.outer_radius,
+                opacity: rings.opacity,
+                gaps: rings.gaps.clone(),
+                near_blend: 0.0,
+                near_center: Vector3::zero(),
+                near_radius: particles.field_radius,
+            });
+            let shader = planet.shader.clone().unwrap_or_else(|| Arc::new(planet.planet_type));
+            let entity = world.spawn_renderable(
+                Transform::at(Vector3::zero(), planet.scale),
+                Renderable { bounds: Some(planet.scale), ..Renderable::new(Mesh::Lods(meshes.sphere.clone()), "Planetas", Pass::Opaque) },
+                Material::Shader(shader),
+            );
+            world.orbits.insert(entity, planet.orbit());
+            world.spins.insert(entity, planet.spin());
+            world.details.insert(entity, SurfaceDetail {
+                rings: ring_uniforms.clone(),
+                atmosphere: planet.atmosphere,
+                ..SurfaceDetail::default()
+            });
+            planets.push(entity);
+
+            // Anillos: translúcidos, en el plano ecuatorial y girando con el planeta
+            rings.push(planet.rings.as_ref().zip(ring_uniforms).map(|(planet_rings, ring_uniforms)| {
+                let mesh = Obj::generate_rings(planet_rings.inner_radius, planet_rings.outer_radius, 16, 32);
+                let entity = world.spawn_renderable(
+                    Transform::at(Vector3::zero(), 1.0),
+                    Renderable::new(Mesh::Single(Arc::new(mesh)), "Anillos", Pass::Translucent),
+                    Material::Shader(Arc::new(PlanetType::Ring)),
+                );
+                world.orbits.insert(entity, planet.orbit());
+                world.spins.insert(entity, planet.spin());
+                world.details.insert(entity, SurfaceDetail { rings: Some(ring_uniforms), ..SurfaceDetail::default() });
+                entity
+            }));
+        }
+
+        // Aurora de los planetas helados: una capa algo mayor que el planeta, inclinada con su eje
+        // pero sin el giro diario
+        let mut auroras = Vec::new();
+        for (idx, planet) in scene.planets.iter().enumerate().filter(|(_, planet)| matches!(planet.planet_type, PlanetType::Ice)) {
+            let entity = world.spawn_renderable(
+                Transform { position: Vector3::zero(), rotation: Rotation::Euler(Vector3::new(0.0, 0.0, planet.axial_tilt)), scale: planet.scale * 1.06 },
+                Renderable::new(Mesh::Lods(meshes.sphere.clone()), "Auroras", Pass::Translucent),
+                Material::Aurora(AuroraShader::new(0.0)),
+            );
+            world.orbits.insert(entity, planet.orbit());
+            auroras.push((idx, entity, 0.0));
+        }
+
+        // Lunas: siempre la misma cara hacia el planeta, que además las ilumina débilmente (luz
+        // cenicienta, ver `planetshine_system`)
+        let mut moons = Vec::with_capacity(scene.moons.len());
+        for moon in &scene.moons {
+            let shader = moon.shader.clone().unwrap_or_else(|| Arc::new(moon.planet_type));
+            let entity = world.spawn_renderable(
+                Transform::at(Vector3::zero(), moon.scale),
+                Renderable { bounds: Some(moon.scale), ..Renderable::new(Mesh::Lods(meshes.moon.clone()), "Lunas", Pass::Opaque) },
+                Material::Shader(shader),
+            );
+            world.orbits.insert(entity, Orbit { parent: planets.get(moon.parent).copied(), ..moon.orbit() });
+            world.spins.insert(entity, Spin::TidalLock);
+            world.details.insert(entity, SurfaceDetail::default());
+            moons.push(entity);
+        }
+
+        // Estaciones: el módulo central no gira y el anillo habitable gira sobre Y
+        let station_shader: Arc<dyn SurfaceShader> = Arc::new(StationShader::new());
+        for station in &scene.stations {
+            let orbit = Orbit { parent: planets.get(station.parent).copied(), ..station.orbit() };
+            let bounds = Some(station.scale * Station::OUTER_RADIUS);
+            for (part, spin) in [(&meshes.station_hub, None), (&meshes.station_ring, Some(station.ring_spin()))] {
+                let entity = world.spawn_renderable(
+                    Transform::at(Vector3::zero(), station.scale),
+                    Renderable { bounds, ..Renderable::new(Mesh::Single(part.clone()), "Estaciones", Pass::Opaque) },
+                    Material::Shader(station_shader.clone()),
+                );
+                world.orbits.insert(entity, orbit);
+                if let Some(spin) = spin {
+                    world.spins.insert(entity, spin);
+                }
+            }
+        }
+
+        // Horizonte de sucesos de cada agujero negro (esfera negra opaca)
+        let horizon_shader: Arc<dyn SurfaceShader> = Arc::new(FnShader::new(material::Material::matte(Vector3::zero()), |_, _| Vector3::zero()));
+        for hole in &scene.black_holes {
+            world.spawn_renderable(
+                Transform::at(hole.position, hole.radius),
+                Renderable { bounds: Some(hole.radius), ..Renderable::new(Mesh::Lods(meshes.sphere.clone()), "Agujeros negros", Pass::Opaque) },
+                Material::Shader(horizon_shader.clone()),
+            );
+        }
+
+        // La nave: la malla, las toberas y la visibilidad las pone `ship_system` cada frame
+        let ship = world.spawn_renderable(
+            Transform::at(Vector3::zero(), 1.0),
+            Renderable { visible: false, ..Renderable::new(Mesh::Single(meshes.projectile.clone()), "Nave", Pass::Opaque) },
+            Material::Ship(ShipShader::new(Vector3::zero(), 0.0, 0.0)),
+        );
+
+        SceneWorld {
+            world,
+            planets,
+            rings,
+            auroras,
+            moons,
+            ship,
+            asteroids: Pool::new(),
+            ring_rocks: Pool::new(),
+            projectiles: Pool::new(),
+            rocks: Vec::new(),
+            asteroid_meshes: meshes.asteroids.clone(),
+            projectile_mesh: meshes.projectile.clone(),
+            rock_shader: Arc::new(PlanetType::Moon),
+            projectile_shader: Arc::new(FnShader::new(material::Material::matte(Vector3::zero()), |_, _| Vector3::new(0.6, 2.0, 1.2))),
+        }
+    }
+
+    /// Planetas, sus anillos y sus auroras en el ángulo orbital actual (`orbital_angle` ya es el
+    /// del instante `time`) y con los cráteres, erupciones y tormentas tras `hours` horas simuladas
+    pub fn planet_system(&mut self, scene: &Scene, time: f32, hours: f64) {
+        let world = &mut self.world;
+        for ((planet, &entity), rings) in scene.planets.iter().zip(&self.planets).zip(&self.rings) {
+            let orbit = Orbit { epoch: time, ..planet.orbit() };
+            world.orbits.insert(entity, orbit);
+            if let Some(rings) = *rings {
+                world.orbits.insert(rings, orbit);
+            }
+            if let Some(detail) = world.details.get_mut(entity) {
+                detail.craters.clone_from(&planet.craters);
+                detail.eruptions.clone_from(&planet.eruptions);
+                detail.storms.clear();
+                detail.storms.extend(planet.storms.iter().map(|storm| storm.at(hours)));
+            }
+        }
+        for &(idx, entity, _) in &self.auroras {
+            world.orbits.insert(entity, Orbit { epoch: time, ..scene.planets[idx].orbit() });
+        }
+    }
+
+    /// Luz cenicienta: cada luna recibe por el lado que mira a su planeta la luz del sol que este
+    /// refleja, con su color
+    pub fn planetshine_system(&mut self, scene: &Scene, time: f32) {
+        for (moon_idx, (moon, &entity)) in scene.moons.iter().zip(&self.moons).enumerate() {
+            let (to_parent, strength) = scene.planetshine(moon_idx, time);
+            let albedo = scene.planets[moon.parent].planet_type.albedo();
+            if let Some(detail) = self.world.details.get_mut(entity) {
+                detail.fill_light = Some(FillLight {
+                    direction: moon.to_object_space(to_parent, time),
+                    color: Vector3::new(albedo.x * strength, albedo.y * strength, albedo.z * strength),
+                });
+            }
+        }
+    }
+
+    /// Un paso de `delta_time` de las tormentas geomagnéticas: cada frente de plasma que atraviesa
+    /// un planeta helado aviva su aurora, que se apaga en unos 20 s
+    pub fn aurora_system(&mut self, scene: &Scene, solar_activity: &SolarActivity, time: f32, delta_time: f32) {
+        for (idx, entity, storm) in &mut self.auroras {
+            let exposure = solar_activity.exposure(scene.planets[*idx].position(), time);
+            *storm = (*storm - delta_time * 0.05).max(exposure);
+            if let Some(Material::Aurora(shader)) = self.world.materials.get_mut(*entity) {
+                *shader = AuroraShader::new(*storm);
+            }
+        }
+    }
+
+    /// Anillos vistos de cerca desde `camera`: el disco se desvanece a su alrededor y en su lugar
+    /// aparecen las rocas de `particles`
+    pub fn ring_system(&mut self, scene: &Scene, particles: &RingParticles, camera: Vector3, hours: f64) {
+        let SceneWorld { world, planets, rings, ring_rocks, rocks, rock_shader, .. } = self;
+        rocks.clear();
+        for (idx, planet) in scene.planets.iter().enumerate() {
+            let blend = particles.blend(planet, camera, hours);
+            for entity in [Some(planets[idx]), rings[idx]].into_iter().flatten() {
+                if let Some(rings) = world.details.get_mut(entity).and_then(|detail| detail.rings.as_mut()) {
+                    rings.near_blend = blend;
+                    rings.near_center = camera;
+                }
+            }
+            if blend > 0.0 {
+                particles.rocks_into(idx, planet, camera, hours, rocks);
+            }
+        }
+
+        ring_rocks.resize(world, rocks.len(), |world| {
+            world.spawn_renderable(
+                Transform::at(Vector3::zero(), 1.0),
+                Renderable::new(Mesh::Single(particles.meshes[0].clone()), "Anillos", Pass::Opaque),
+                Material::Shader(rock_shader.clone()),
+            )
+        });
+        for (&entity, rock) in ring_rocks.active().iter().zip(rocks.iter()) {
+            place(world, entity, rock.position, rock.scale, rock.spin);
+            if let Some(renderable) = world.renderables.get_mut(entity) {
+                renderable.mesh = Mesh::Single(particles.meshes[rock.mesh].clone());
+            }
+        }
+    }
+
+    /// Relieve del planeta rocoso al que se ha acercado `point` (como mucho uno a la vez); los
+    /// demás vuelven a la esfera lisa
+    pub fn terrain_system(&mut self, scene: &Scene, terrain: &SurfaceTerrain, point: Vector3, hours: f64, time: f32) {
+        let engaged = scene.planets.iter().position(|planet| terrain.engages(planet, point));
+        for (idx, &entity) in self.planets.iter().enumerate() {
+            if let Some(renderable) = self.world.renderables.get_mut(entity) {
+                renderable.detail_mesh = (engaged == Some(idx)).then(|| terrain.build_for(&scene.planets[idx], point, hours, time));
+            }
+        }
+    }
+
+    /// Rocas del cinturón en su posición actual; se descartan las que quedan fuera de la vista
+    pub fn asteroid_system(&mut self, belt: Option<&AsteroidBelt>) {
+        let SceneWorld { world, asteroids, asteroid_meshes, rock_shader, .. } = self;
+        let rocks = belt.map_or(&[][..], |belt| belt.asteroids());
+        asteroids.resize(world, rocks.len(), |world| {
+            world.spawn_renderable(
+                Transform::at(Vector3::zero(), 1.0),
+                Renderable::new(Mesh::Single(asteroid_meshes[0].clone()), "Asteroides", Pass::Opaque),
+                Material::Shader(rock_shader.clone()),
+            )
+        });
+        for (&entity, asteroid) in asteroids.active().iter().zip(rocks) {
+            place(world, entity, asteroid.position, asteroid.scale, asteroid.spin);
+            if let Some(renderable) = world.renderables.get_mut(entity) {
+                renderable.mesh = Mesh::Single(asteroid_meshes[asteroid.mesh].clone());
+                renderable.cull_radius = Some(asteroid.scale * AsteroidBelt::MESH_BOUNDS);
+            }
+        }
+    }
+
+    /// Proyectiles en vuelo: esferas pequeñas emisivas
+    pub fn projectile_system(&mut self, weapons: &Weapons) {
+        let SceneWorld { world, projectiles, projectile_mesh, projectile_shader, .. } = self;
+        projectiles.resize(world, weapons.projectiles().len(), |world| {
+            world.spawn_renderable(
+                Transform::at(Vector3::zero(), 1.0),
+                Renderable { lit: false, ..Renderable::new(Mesh::Single(projectile_mesh.clone()), "Proyectiles", Pass::Opaque) },
+                Material::Shader(projectile_shader.clone()),
+            )
+        });
+        for (&entity, projectile) in projectiles.active().iter().zip(weapons.projectiles()) {
+            if let Some(transform) = world.transforms.get_mut(entity) {
+                *transform = Transform::at(projectile.position, weapons.radius);
+            }
+        }
+    }
+
+    /// La nave con `model`, el resplandor de sus toberas y la esfera de `bounds` para los gizmos
+    pub fn ship_system(&mut self, transform: Transform, model: &Arc<Obj>, bounds: f32, shader: ShipShader) {
+        self.world.transforms.insert(self.ship, transform);
+        if let Some(renderable) = self.world.renderables.get_mut(self.ship) {
+            if !matches!(&renderable.mesh, Mesh::Single(mesh) if Arc::ptr_eq(mesh, model)) {
+                renderable.mesh = Mesh::Single(model.clone());
+            }
+            renderable.bounds = Some(bounds);
+        }
+        self.world.materials.insert(self.ship, Material::Ship(shader));
+    }
+
+    /// Muestra u oculta la nave (oculta en cabina, en el retrovisor o si el modo foto lo pide)
+    pub fn set_ship_visible(&mut self, visible: bool) {
+        if let Some(renderable) = self.world.renderables.get_mut(self.ship) {
+            renderable.visible = visible && !renderable.mesh.lod(0).vertices.is_empty();
+        }
+    }
+}
+
+/// Coloca una entidad de un `Pool` que gira sobre sí misma
+fn place(world: &mut World, entity: Entity, position: Vector3, scale: f32, spin: Vector3) {
+    if let Some(transform) = world.transforms.get_mut(entity) {
+        transform.position = position;
+        transform.scale = scale;
+    }
+    world.spins.insert(entity, Spin::Steady(spin));
+}
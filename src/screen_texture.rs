@@ -0,0 +1,269 @@
+use raylib::prelude::*;
+use crate::framebuffer::{DirtyRect, Framebuffer, PixelEncoder};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Textura de la ventana que muestra un `Framebuffer`: lo único del render que necesita raylib
+/// inicializado. Cada frame codifica y sube solo la zona que cambió (`take_changed_region`) y
+/// dibuja la textura escalada al tamaño de la ventana. Sin ella, el framebuffer se puede usar
+/// sin ventana (pruebas, render en servidor) y leer con `Framebuffer::to_rgb8`
+///
+/// Con `set_threaded(true)` la codificación pasa a un hilo propio con doble búfer: `update`
+/// solo copia los colores lineales de la zona cambiada y se los pasa al hilo, y sube la zona
+/// que el hilo codificó en el frame anterior. Así el siguiente frame se rasteriza mientras el
+/// anterior se codifica, a cambio de mostrar la imagen con un frame de retraso
+pub struct ScreenTexture {
+    texture: Texture2D,
+    width: u32,
+    height: u32,
+    upload_buffer: Vec<u8>,        // Zona cambiada ya codificada, empaquetada para la subida
+    upload_time: Duration,         // Tiempo de la última `update` en el hilo principal
+    uploaded_pixels: usize,        // Píxeles codificados y subidos en la última `update`
+    encoder: Option<EncodeWorker>, // Hilo de codificación (None: se codifica en `update`)
+}
+
+/// Una zona del framebuffer de camino a la textura: los colores lineales copiados en el hilo
+/// principal y los bytes que el hilo de codificación deja listos para la subida
+struct EncodeJob {
+    width: u32,              // Tamaño del framebuffer del que se copió
+    height: u32,
+    region: DirtyRect,
+    encoder: PixelEncoder,   // Exposición, gamma y tramado del frame copiado
+    colors: Vec<Vector3>,    // Colores lineales de `region`, fila a fila
+    rgba: Vec<u8>,           // `region` codificada; del tamaño de toda la textura
+    encode_time: Duration,   // Tiempo de codificación en el hilo
+}
+
+impl EncodeJob {
+    fn encode(&mut self) {
+        let start = Instant::now();
+        let size = (self.width * self.height) as usize * 4;
+        if self.rgba.len() != size {
+            self.rgba = vec![0; size];
+        }
+        self.encoder.encode_region(&self.region, &self.colors, &mut self.rgba);
+        self.encode_time = start.elapsed();
+    }
+}
+
+/// Hilo de codificación de `ScreenTexture`: recibe zonas por un canal y las devuelve
+/// codificadas por otro. Los dos `EncodeJob` circulan entre ambos hilos, sin reservas por frame
+struct EncodeWorker {
+    jobs: Option<Sender<EncodeJob>>, // Se suelta al destruirlo para que el hilo termine
+    results: Receiver<EncodeJob>,
+    free: Vec<EncodeJob>,            // Trabajos devueltos, listos para la siguiente copia
+    in_flight: bool,                 // Hay un trabajo en el hilo
+    thread: Option<JoinHandle<()>>,
+    encode_time: Duration,           // Tiempo del hilo con el último trabajo subido
+}
+
+impl EncodeWorker {
+    fn new() -> Self {
+        let (jobs, incoming) = mpsc::channel::<EncodeJob>();
+        let (done, results) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name(String::from("screen-encode"))
+            .spawn(move || {
+                for mut job in incoming {
+                    job.encode();
+                    if done.send(job).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("no se pudo crear el hilo de codificación de la pantalla");
+        EncodeWorker {
+            jobs: Some(jobs),
+            results,
+            free: Vec::with_capacity(2),
+            in_flight: false,
+            thread: Some(thread),
+            encode_time: Duration::ZERO,
+        }
+    }
+
+    /// Copia la zona `region` de `framebuffer` en un trabajo libre y se la pasa al hilo
+    fn submit(&mut self, framebuffer: &Framebuffer, region: DirtyRect) {
+        let mut job = self.free.pop().unwrap_or_else(|| EncodeJob {
+            width: 0,
+            height: 0,
+            region,
+            encoder: PixelEncoder::new(),
+            colors: Vec::new(),
+            rgba: Vec::new(),
+            encode_time: Duration::ZERO,
+        });
+        job.width = framebuffer.width;
+        job.height = framebuffer.height;
+        job.region = region;
+        job.encoder = framebuffer.pixel_encoder();
+        framebuffer.copy_region(&region, &mut job.colors);
+        if let Some(jobs) = &self.jobs {
+            self.in_flight = jobs.send(job).is_ok();
+        }
+    }
+
+    /// Espera al trabajo que está en el hilo, si lo hay
+    fn wait(&mut self) -> Option<EncodeJob> {
+        if !self.in_flight {
+            return None;
+        }
+        self.in_flight = false;
+        let job = self.results.recv().ok()?;
+        self.encode_time = job.encode_time;
+        Some(job)
+    }
+}
+
+impl Drop for EncodeWorker {
+    fn drop(&mut self) {
+        self.jobs = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl ScreenTexture {
+    /// Crea una textura del tamaño de `framebuffer`; necesita la ventana ya abierta
+    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread, framebuffer: &Framebuffer) -> Self {
+        let (width, height) = (framebuffer.width, framebuffer.height);
+        let image = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
+        let texture = rl.load_texture_from_image(thread, &image).unwrap();
+        // Filtro bilineal para que un framebuffer más pequeño o más grande que la ventana se vea suave
+        texture.set_texture_filter(thread, TextureFilter::TEXTURE_FILTER_BILINEAR);
+        ScreenTexture {
+            texture,
+            width,
+            height,
+            upload_buffer: vec![0; (width * height) as usize * 4],
+            upload_time: Duration::ZERO,
+            uploaded_pixels: 0,
+            encoder: None,
+        }
+    }
+
+    /// Activa o desactiva la codificación en un hilo aparte (ver el tipo). Al desactivarla se
+    /// sube antes lo que quedara en el hilo, así que la pantalla no pierde ninguna zona
+    pub fn set_threaded(&mut self, threaded: bool) {
+        if threaded == self.is_threaded() {
+            return;
+        }
+        match self.encoder.take() {
+            Some(mut worker) => {
+                if let Some(job) = worker.wait() {
+                    self.upload(&job);
+                }
+            }
+            None => self.encoder = Some(EncodeWorker::new()),
+        }
+    }
+
+    pub fn is_threaded(&self) -> bool {
+        self.encoder.is_some()
+    }
+
+    /// Vuelve a crear la textura si `framebuffer` cambió de tamaño (la zona cambiada del
+    /// framebuffer recién redimensionado ya cubre toda la pantalla)
+    pub fn resize(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread, framebuffer: &Framebuffer) {
+        if (self.width, self.height) != (framebuffer.width, framebuffer.height) {
+            let threaded = self.is_threaded();
+            *self = ScreenTexture::new(rl, thread, framebuffer);
+            self.set_threaded(threaded);
+        }
+    }
+
+    /// Codifica la zona de `framebuffer` que cambió desde la última llamada y la sube a la textura
+    /// En modo con hilo la entrega al hilo y sube la que este codificó en la llamada anterior
+    pub fn update(&mut self, framebuffer: &mut Framebuffer) {
+        assert_eq!(
+            (self.width, self.height),
+            (framebuffer.width, framebuffer.height),
+            "La textura de la ventana no tiene el tamaño del framebuffer; falta llamar a ScreenTexture::resize"
+        );
+        let start = Instant::now();
+        let region = framebuffer.take_changed_region();
+        self.upload_time = Duration::ZERO;
+        self.uploaded_pixels = 0;
+        if let Some(mut worker) = self.encoder.take() {
+            let previous = worker.wait();
+            if !region.is_empty() {
+                worker.submit(framebuffer, region);
+            }
+            if let Some(job) = previous {
+                self.upload(&job);
+                worker.free.push(job);
+            }
+            self.encoder = Some(worker);
+            self.upload_time = start.elapsed();
+            return;
+        }
+        if region.is_empty() {
+            return;
+        }
+
+        // raylib lee los píxeles del rectángulo empaquetados desde el principio del slice,
+        // pero el binding exige un slice del tamaño de toda la textura
+        self.uploaded_pixels = framebuffer.encode_region(&region, &mut self.upload_buffer);
+        let rect = Rectangle::new(
+            region.min_x as f32,
+            region.min_y as f32,
+            region.width() as f32,
+            region.height() as f32,
+        );
+        self.texture.update_texture_rec(rect, &self.upload_buffer).unwrap();
+        self.upload_time = start.elapsed();
+    }
+
+    /// Sube una zona que codificó el hilo; se descarta si se copió de un framebuffer de otro tamaño
+    fn upload(&mut self, job: &EncodeJob) {
+        if (job.width, job.height) != (self.width, self.height) || job.region.is_empty() {
+            return;
+        }
+        let region = &job.region;
+        let rect = Rectangle::new(
+            region.min_x as f32,
+            region.min_y as f32,
+            region.width() as f32,
+            region.height() as f32,
+        );
+        self.texture.update_texture_rec(rect, &job.rgba).unwrap();
+        self.uploaded_pixels = (region.width() * region.height()) as usize;
+    }
+
+    /// Tiempo que tardó el hilo en codificar la última zona subida (cero sin hilo)
+    pub fn encode_time(&self) -> Duration {
+        self.encoder.as_ref().map_or(Duration::ZERO, |worker| worker.encode_time)
+    }
+
+    /// Tiempo de codificación y subida de la última `update`; con hilo, solo la copia, la
+    /// espera al hilo y la subida
+    pub fn upload_time(&self) -> Duration {
+        self.upload_time
+    }
+
+    /// Píxeles codificados y subidos en la última `update` (la zona cambiada)
+    pub fn uploaded_pixels(&self) -> usize {
+        self.uploaded_pixels
+    }
+
+    /// Dibuja la textura estirada sobre un área de `dest_width` x `dest_height` en el origen,
+    /// para que la resolución de render pueda ser distinta de la de la ventana
+    #[allow(dead_code)]
+    pub fn draw_to(&self, d: &mut RaylibDrawHandle, dest_width: i32, dest_height: i32) {
+        self.draw_to_offset(d, dest_width, dest_height, Vector2::zero());
+    }
+
+    /// Como `draw_to`, desplazada `offset` píxeles de la ventana (temblor de cámara)
+    pub fn draw_to_offset(&self, d: &mut RaylibDrawHandle, dest_width: i32, dest_height: i32, offset: Vector2) {
+        d.draw_texture_pro(
+            &self.texture,
+            Rectangle::new(0.0, 0.0, self.width as f32, self.height as f32),
+            Rectangle::new(offset.x, offset.y, dest_width as f32, dest_height as f32),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use raylib::prelude::*;
+use crate::console::{parse_command, Command};
+use rhai::{Array, CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Operaciones que puede hacer un script en cada llamada antes de cortarlo (evita que un bucle
+/// infinito congele el juego)
+const MAX_OPERATIONS: u64 = 1_000_000;
+/// Anidamiento de expresiones a nivel global y dentro de funciones (los límites por defecto de
+/// Rhai, más bajos con las comprobaciones de depuración, se quedan cortos con plantillas `${}`)
+const MAX_EXPR_DEPTHS: (usize, usize) = (128, 64);
+
+/// Lo que ven los scripts del juego en cada paso (solo lectura)
+#[derive(Clone, Debug, Default)]
+pub struct ScriptContext {
+    pub time: f32,                      // Segundos de simulación
+    pub hours: f64,                     // Horas simuladas del reloj
+    pub ship: Vector3,                  // Posición de la nave
+    pub camera: Vector3,                // Posición de la cámara
+    pub bodies: Vec<(String, Vector3)>, // Nombre y posición de cada cuerpo de la escena
+}
+
+/// Estado compartido entre el anfitrión y las funciones registradas en el motor
+#[derive(Default)]
+struct Shared {
+    context: ScriptContext,
+    commands: Vec<Command>, // Órdenes pedidas por los scripts desde la última llamada
+    messages: Vec<String>,  // `print` de los scripts y errores
+}
+
+/// Un script cargado, con su objeto de estado (`this` dentro de sus funciones)
+struct Script {
+    name: String,
+    ast: AST,
+    state: Dynamic,
+    has_update: bool, // Define `update(dt)`
+    has_event: bool,  // Define `on_event(nombre, detalle)`
+    failed: bool,     // Dio un error y ya no se ejecuta
+}
+
+/// Scripts de Rhai de una escena. Cada script puede definir `init()` (al cargarse),
+/// `update(dt)` (en cada paso de la simulación) y `on_event(nombre, detalle)` (sucesos del juego:
+/// `impact`, `eclipse`, `warp_end`); dentro de ellas `this` es un objeto que se conserva entre
+/// llamadas. Los scripts leen el juego con `time()`, `hours()`, `ship()`, `camera()`, `body(nombre)`
+/// y `bodies()`, y actúan con las mismas órdenes que la consola: `warp(x, y, z)`, `track(nombre)`,
+/// `timescale(h)` o cualquier línea con `command("spawn asteroid 10")`
+pub struct ScriptHost {
+    engine: Engine,
+    scripts: Vec<Script>,
+    shared: Rc<RefCell<Shared>>,
+}
+
+impl ScriptHost {
+    pub fn new() -> Self {
+        let shared = Rc::new(RefCell::new(Shared::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.set_max_expr_depths(MAX_EXPR_DEPTHS.0, MAX_EXPR_DEPTHS.1);
+
+        let output = shared.clone();
+        engine.on_print(move |text| output.borrow_mut().messages.push(text.to_string()));
+
+        // Lectura del estado del juego
+        let state = shared.clone();
+        engine.register_fn("time", move || state.borrow().context.time as f64);
+        let state = shared.clone();
+        engine.register_fn("hours", move || state.borrow().context.hours);
+        let state = shared.clone();
+        engine.register_fn("ship", move || vector_map(state.borrow().context.ship));
+        let state = shared.clone();
+        engine.register_fn("camera", move || vector_map(state.borrow().context.camera));
+        let state = shared.clone();
+        engine.register_fn("body", move |name: &str| {
+            let shared = state.borrow();
+            let body = shared.context.bodies.iter().find(|(body, _)| body.eq_ignore_ascii_case(name));
+            body.map_or(Dynamic::UNIT, |&(_, position)| vector_map(position))
+        });
+        let state = shared.clone();
+        engine.register_fn("bodies", move || {
+            state.borrow().context.bodies.iter().map(|(name, _)| Dynamic::from(name.clone())).collect::<Array>()
+        });
+
+        // Órdenes: se guardan y `main` las atiende como las de la consola
+        let queue = shared.clone();
+        engine.register_fn("warp", move |x: Dynamic, y: Dynamic, z: Dynamic| -> Result<(), Box<EvalAltResult>> {
+            let target = Vector3::new(number(&x)?, number(&y)?, number(&z)?);
+            queue.borrow_mut().commands.push(Command::Warp(target));
+            Ok(())
+        });
+        let queue = shared.clone();
+        engine.register_fn("track", move |name: &str| queue.borrow_mut().commands.push(Command::Track(name.to_string())));
+        let queue = shared.clone();
+        engine.register_fn("timescale", move |rate: Dynamic| -> Result<(), Box<EvalAltResult>> {
+            queue.borrow_mut().commands.push(Command::TimeScale(number(&rate)?.max(0.0)));
+            Ok(())
+        });
+        let queue = shared.clone();
+        engine.register_fn("command", move |line: &str| -> Result<(), Box<EvalAltResult>> {
+            let command = parse_command(line)?;
+            queue.borrow_mut().commands.push(command);
+            Ok(())
+        });
+
+        ScriptHost { engine, scripts: Vec::new(), shared }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.scripts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Compilar el script de `path`, ejecutar su código de nivel superior y su `init()`
+    pub fn load(&mut self, path: &Path) -> Result<(), String> {
+        let name = path.display().to_string();
+        let ast = self.engine.compile_file(path.to_path_buf()).map_err(|e| format!("{}: {}", name, e))?;
+        let defines = |function: &str, params: usize| ast.iter_functions().any(|f| f.name == function && f.params.len() == params);
+        let (has_init, has_update, has_event) = (defines("init", 0), defines("update", 1), defines("on_event", 2));
+        self.engine.run_ast(&ast).map_err(|e| format!("{}: {}", name, e))?;
+
+        let mut script = Script { name, ast, state: Dynamic::from_map(Map::new()), has_update, has_event, failed: false };
+        if has_init {
+            self.call(&mut script, "init", ());
+        }
+        if script.failed {
+            return Err(self.shared.borrow_mut().messages.pop().unwrap_or_default());
+        }
+        self.scripts.push(script);
+        Ok(())
+    }
+
+    /// Un paso de la simulación: avisa de los sucesos del paso y llama a `update(dt)` de cada
+    /// script. Devuelve las órdenes que han pedido
+    pub fn update(&mut self, context: ScriptContext, delta_time: f32, events: &[(&str, String)]) -> Vec<Command> {
+        self.shared.borrow_mut().context = context;
+        let mut scripts = std::mem::take(&mut self.scripts);
+        for script in scripts.iter_mut() {
+            for (name, detail) in events {
+                if script.has_event && !script.failed {
+                    self.call(script, "on_event", (name.to_string(), detail.clone()));
+                }
+            }
+            if script.has_update && !script.failed {
+                self.call(script, "update", (delta_time as f64,));
+            }
+        }
+        self.scripts = scripts;
+        std::mem::take(&mut self.shared.borrow_mut().commands)
+    }
+
+    /// Mensajes pendientes: lo que han escrito los scripts con `print` y sus errores
+    pub fn take_messages(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.shared.borrow_mut().messages)
+    }
+
+    /// Llamar a la función `function` del script; un error lo desactiva para no repetirse en
+    /// cada paso
+    fn call(&self, script: &mut Script, function: &str, args: impl rhai::FuncArgs) {
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(&mut script.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), &script.ast, function, args);
+        if let Err(e) = result {
+            script.failed = true;
+            self.shared.borrow_mut().messages.push(format!("Error en {} ({}): {}; el script se detiene", script.name, function, e));
+        }
+    }
+}
+
+impl Default for ScriptHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Posición como objeto de Rhai `#{x, y, z}`
+fn vector_map(v: Vector3) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("x".into(), Dynamic::from_float(v.x as f64));
+    map.insert("y".into(), Dynamic::from_float(v.y as f64));
+    map.insert("z".into(), Dynamic::from_float(v.z as f64));
+    Dynamic::from_map(map)
+}
+
+/// Número de un argumento, entero o decimal (Rhai no convierte solo entre los dos)
+fn number(value: &Dynamic) -> Result<f32, Box<EvalAltResult>> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|int| int as f64))
+        .map(|value| value as f32)
+        .map_err(|type_name| format!("se esperaba un número y llegó {}", type_name).into())
+}
@@ -0,0 +1,123 @@
+use raylib::prelude::*;
+use crate::camera::Camera;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::Projection;
+use std::f32::consts::PI;
+
+/// Qué muestra la vista secundaria
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InsetKind {
+    RearView,  // Retrovisor: desde la nave hacia atrás
+    PlanetCam, // Cámara que orbita despacio el planeta seleccionado
+}
+
+/// Vista secundaria (picture-in-picture): cada frame el sistema se renderiza otra vez desde una
+/// segunda cámara en un framebuffer propio, más pequeño, y se compone sobre la imagen principal
+/// con `Framebuffer::composite` antes de presentarla. `main` dibuja la escena con el mismo pase
+/// que la vista principal; este módulo solo elige la cámara, el tamaño y el marco
+pub struct SecondaryView {
+    kind: Option<InsetKind>,       // None: vista oculta
+    pub framebuffer: Framebuffer,  // Destino del render de la vista
+    pub width_fraction: f32,       // Ancho respecto a la vista principal
+    pub fov_y: f32,                // Campo de visión vertical (radianes)
+    pub orbit_speed: f32,          // Radianes por segundo de la cámara del planeta
+    pub orbit_distance: f32,       // Radios del planeta a los que orbita la cámara
+    pub orbit_elevation: f32,      // Radianes sobre la eclíptica de la cámara del planeta
+}
+
+impl SecondaryView {
+    pub fn new() -> Self {
+        let mut framebuffer = Framebuffer::new(1, 1);
+        framebuffer.set_background_color(Vector3::zero());
+        SecondaryView {
+            kind: None,
+            framebuffer,
+            width_fraction: 0.28,
+            fov_y: PI / 3.0,
+            orbit_speed: 0.25,
+            orbit_distance: 4.0,
+            orbit_elevation: 0.35,
+        }
+    }
+
+    pub fn kind(&self) -> Option<InsetKind> {
+        self.kind
+    }
+
+    /// Pasa a la siguiente vista: retrovisor, cámara del planeta y oculta
+    pub fn cycle(&mut self) -> Option<InsetKind> {
+        self.kind = match self.kind {
+            None => Some(InsetKind::RearView),
+            Some(InsetKind::RearView) => Some(InsetKind::PlanetCam),
+            Some(InsetKind::PlanetCam) => None,
+        };
+        self.kind
+    }
+
+    /// Rectángulo de la vista (x, y, ancho, alto) dentro de una vista principal de
+    /// `width` x `height`: arriba al centro, como un retrovisor, con la misma proporción
+    pub fn layout(&self, width: u32, height: u32) -> (i32, i32, u32, u32) {
+        let inset_width = ((width as f32 * self.width_fraction) as u32).max(1);
+        let inset_height = ((inset_width as f32 * height as f32 / width.max(1) as f32) as u32).max(1);
+        let margin = height / 60 + 1;
+        (((width - inset_width) / 2) as i32, margin as i32, inset_width, inset_height)
+    }
+
+    /// Ajusta el framebuffer de la vista a una vista principal de `width` x `height` y lo limpia
+    /// Devuelve la posición en la que componerlo
+    pub fn prepare(&mut self, width: u32, height: u32) -> (i32, i32) {
+        let (x, y, inset_width, inset_height) = self.layout(width, height);
+        if (self.framebuffer.width, self.framebuffer.height) != (inset_width, inset_height) {
+            self.framebuffer.resize(inset_width, inset_height);
+        }
+        self.framebuffer.clear();
+        (x, y)
+    }
+
+    /// Proyección en perspectiva de la vista hasta `far`
+    pub fn projection(&self, far: f32) -> Projection {
+        let aspect = self.framebuffer.width as f32 / self.framebuffer.height.max(1) as f32;
+        Projection::Perspective { fov_y: self.fov_y, aspect, near: 0.1, far }
+    }
+
+    /// Retrovisor: justo encima de la nave en `ship_position`, mirando en contra de `heading`
+    /// `lift` separa la cámara del casco para que no quede dentro de la malla
+    pub fn rear_camera(&self, ship_position: Vector3, heading: Vector3, lift: f32) -> Camera {
+        let eye = Vector3::new(ship_position.x, ship_position.y + lift, ship_position.z);
+        let target = Vector3::new(eye.x - heading.x, eye.y - heading.y, eye.z - heading.z);
+        Camera::new(eye, target, Vector3::new(0.0, 1.0, 0.0))
+    }
+
+    /// Cámara del planeta: da vueltas alrededor del planeta de centro `center` y radio `radius`
+    /// a `orbit_distance` radios, algo por encima de la eclíptica, mirando a su centro
+    pub fn planet_camera(&self, center: Vector3, radius: f32, time: f32) -> Camera {
+        let angle = time * self.orbit_speed;
+        let distance = radius * self.orbit_distance;
+        let eye = Vector3::new(
+            center.x + distance * self.orbit_elevation.cos() * angle.cos(),
+            center.y + distance * self.orbit_elevation.sin(),
+            center.z + distance * self.orbit_elevation.cos() * angle.sin(),
+        );
+        Camera::new(eye, center, Vector3::new(0.0, 1.0, 0.0))
+    }
+
+    /// Marco y título de la vista sobre la ventana; `screen_scale` pasa de píxeles del
+    /// framebuffer principal a píxeles de la ventana
+    pub fn draw(&self, d: &mut RaylibDrawHandle, main_width: u32, main_height: u32, screen_scale: f32, title: &str) {
+        if self.kind.is_none() {
+            return;
+        }
+        let (x, y, width, height) = self.layout(main_width, main_height);
+        let (x, y) = ((x as f32 * screen_scale) as i32, (y as f32 * screen_scale) as i32);
+        let (width, height) = ((width as f32 * screen_scale) as i32, (height as f32 * screen_scale) as i32);
+        let color = Color::new(180, 200, 230, 220);
+        d.draw_rectangle_lines(x - 1, y - 1, width + 2, height + 2, color);
+        d.draw_text(title, x + 4, y + height - 14, 10, color);
+    }
+}
+
+impl Default for SecondaryView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,18 +1,15 @@
 use raylib::prelude::*;
 use crate::vertex::Vertex;
+use crate::color::{blackbody_srgb, srgb_to_linear_rgb};
 use crate::fragment::Fragment;
-use crate::Uniforms;
+use crate::material::Material;
+use crate::scene::{Atmosphere, Crater, Eruption, RingGap, Station, Storm};
+use crate::star::Star;
+use crate::uniforms::{FillLight, Uniforms};
+use crate::matrix::{logarithmic_depth, multiply_matrix_vector4, projection_far, transform_normal, transform_points4, view_distance};
 use std::f32::consts::PI;
-
-// This function manually multiplies a 4x4 matrix with a 4D vector (in homogeneous coordinates)
-fn multiply_matrix_vector4(matrix: &Matrix, vector: &Vector4) -> Vector4 {
-    Vector4::new(
-        matrix.m0 * vector.x + matrix.m4 * vector.y + matrix.m8 * vector.z + matrix.m12 * vector.w,
-        matrix.m1 * vector.x + matrix.m5 * vector.y + matrix.m9 * vector.z + matrix.m13 * vector.w,
-        matrix.m2 * vector.x + matrix.m6 * vector.y + matrix.m10 * vector.z + matrix.m14 * vector.w,
-        matrix.m3 * vector.x + matrix.m7 * vector.y + matrix.m11 * vector.z + matrix.m15 * vector.w,
-    )
-}
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicU8, Ordering};
 
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
   // Convert vertex position to homogeneous coordinates (Vec4) by adding a w-component of 1.0
@@ -23,14 +20,11 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     1.0
   );
 
-  // Apply Model transformation
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
-
-  // Apply View transformation (camera)
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
+  // Apply Model, View and Projection at once with the precomputed MVP matrix
+  let clip_position = multiply_matrix_vector4(&uniforms.mvp_matrix, &position_vec4);
 
-  // Apply Projection transformation (perspective)
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+  // World-space position and normal for lighting
+  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
 
   // Perform perspective division to get NDC (Normalized Device Coordinates)
   let ndc = if clip_position.w != 0.0 {
@@ -43,6 +37,10 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
       Vector3::new(clip_position.x, clip_position.y, clip_position.z)
   };
 
+  // Replace the hyperbolic NDC depth with a logarithmic one for precision at large distances
+  let distance = view_distance(clip_position.z, clip_position.w, &uniforms.projection_matrix);
+  let ndc = Vector3::new(ndc.x, ndc.y, logarithmic_depth(distance, projection_far(&uniforms.projection_matrix)));
+
   // Apply Viewport transformation to get screen coordinates
   let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
   let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
@@ -60,7 +58,69 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     tex_coords: vertex.tex_coords,
     color: vertex.color,
     transformed_position,
-    transformed_normal: vertex.normal, // Note: Correct normal transformation is more complex
+    transformed_normal: transform_normal(&uniforms.model_matrix, vertex.normal),
+    world_position: Vector3::new(world_position.x, world_position.y, world_position.z),
+    clip_position,
+    view_distance: distance,
+    occlusion: vertex.occlusion,
+  }
+}
+
+/// Batched equivalent of `vertex_shader` for a whole mesh.
+/// Positions are transformed by the precomputed MVP matrix (`Uniforms::mvp_matrix`) four at a
+/// time in structure-of-arrays lanes (see `transform_points4`).
+/// The perspective divide, logarithmic depth and viewport are applied lane-wise as well.
+/// `transformed` is cleared and refilled (same order as `vertices`), so its capacity is reused.
+pub fn vertex_shader_batch(vertices: &[Vertex], uniforms: &Uniforms, transformed: &mut Vec<Vertex>) {
+  let far = projection_far(&uniforms.projection_matrix);
+
+  transformed.clear();
+  transformed.reserve(vertices.len());
+  for chunk in vertices.chunks(4) {
+    // Gather positions into lanes; a short final chunk repeats its last vertex
+    let mut x = [0.0; 4];
+    let mut y = [0.0; 4];
+    let mut z = [0.0; 4];
+    for lane in 0..4 {
+      let position = chunk[lane.min(chunk.len() - 1)].position;
+      x[lane] = position.x;
+      y[lane] = position.y;
+      z[lane] = position.z;
+    }
+
+    let clip = transform_points4(&uniforms.mvp_matrix, &x, &y, &z);
+    let world = transform_points4(&uniforms.model_matrix, &x, &y, &z);
+
+    // Perspective division and logarithmic depth, as in `vertex_shader`
+    let mut ndc_x = [0.0; 4];
+    let mut ndc_y = [0.0; 4];
+    let mut ndc_z = [0.0; 4];
+    let mut distances = [0.0; 4];
+    for lane in 0..4 {
+      let w = clip.w[lane];
+      let inv_w = if w != 0.0 { 1.0 / w } else { 1.0 };
+      ndc_x[lane] = clip.x[lane] * inv_w;
+      ndc_y[lane] = clip.y[lane] * inv_w;
+      distances[lane] = view_distance(clip.z[lane], w, &uniforms.projection_matrix);
+      ndc_z[lane] = logarithmic_depth(distances[lane], far);
+    }
+
+    let screen = transform_points4(&uniforms.viewport_matrix, &ndc_x, &ndc_y, &ndc_z);
+
+    for (lane, vertex) in chunk.iter().enumerate() {
+      transformed.push(Vertex {
+        position: vertex.position,
+        normal: vertex.normal,
+        tex_coords: vertex.tex_coords,
+        color: vertex.color,
+        transformed_position: Vector3::new(screen.x[lane], screen.y[lane], screen.z[lane]),
+        transformed_normal: transform_normal(&uniforms.model_matrix, vertex.normal),
+        world_position: Vector3::new(world.x[lane], world.y[lane], world.z[lane]),
+        clip_position: Vector4::new(clip.x[lane], clip.y[lane], clip.z[lane], clip.w[lane]),
+        view_distance: distances[lane],
+        occlusion: vertex.occlusion,
+      });
+    }
   }
 }
 
@@ -144,14 +204,11 @@ pub fn vertex_shader_sun(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     1.0
   );
 
-  // Apply Model transformation
-  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
+  // Apply Model, View and Projection at once with the precomputed MVP matrix
+  let clip_position = multiply_matrix_vector4(&uniforms.mvp_matrix, &position_vec4);
 
-  // Apply View transformation (camera)
-  let view_position = multiply_matrix_vector4(&uniforms.view_matrix, &world_position);
-
-  // Apply Projection transformation (perspective)
-  let clip_position = multiply_matrix_vector4(&uniforms.projection_matrix, &view_position);
+  // World-space position and normal for lighting
+  let world_position = multiply_matrix_vector4(&uniforms.model_matrix, &position_vec4);
 
   // Perform perspective division to get NDC (Normalized Device Coordinates)
   let ndc = if clip_position.w != 0.0 {
@@ -164,6 +221,10 @@ pub fn vertex_shader_sun(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
       Vector3::new(clip_position.x, clip_position.y, clip_position.z)
   };
 
+  // Replace the hyperbolic NDC depth with a logarithmic one for precision at large distances
+  let distance = view_distance(clip_position.z, clip_position.w, &uniforms.projection_matrix);
+  let ndc = Vector3::new(ndc.x, ndc.y, logarithmic_depth(distance, projection_far(&uniforms.projection_matrix)));
+
   // Apply Viewport transformation to get screen coordinates
   let ndc_vec4 = Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
   let screen_position = multiply_matrix_vector4(&uniforms.viewport_matrix, &ndc_vec4);
@@ -181,7 +242,11 @@ pub fn vertex_shader_sun(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
     tex_coords: vertex.tex_coords,
     color: vertex.color,
     transformed_position,
-    transformed_normal: vertex.normal,
+    transformed_normal: transform_normal(&uniforms.model_matrix, vertex.normal),
+    world_position: Vector3::new(world_position.x, world_position.y, world_position.z),
+    clip_position,
+    view_distance: distance,
+    occlusion: vertex.occlusion,
   }
 }
 
@@ -190,11 +255,11 @@ pub fn vertex_shader_sun(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
 /// Example 1: Random flickering colors per fragment
 #[allow(dead_code)]
 fn shader_random_flicker(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
 
     // Create pseudo-random values based on position and time
-    let seed = world_pos.x * 12.9898 + world_pos.y * 78.233 + world_pos.z * 45.164 + time * 3.0;
+    let seed = object_pos.x * 12.9898 + object_pos.y * 78.233 + object_pos.z * 45.164 + time * 3.0;
     let random = (seed.sin() * 43758.5453).fract();
 
     let flicker_color = Vector3::new(
@@ -214,12 +279,12 @@ fn shader_random_flicker(fragment: &Fragment, time: f32) -> Vector3 {
 /// Example 2: Horizontal stripes moving upward
 #[allow(dead_code)]
 fn shader_moving_stripes(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
 
     // Add time to Y position to make stripes move upward
     let stripe_frequency = 1.0;
-    let animated_y = world_pos.y + time * 0.5; // Speed of movement
+    let animated_y = object_pos.y + time * 0.5; // Speed of movement
     let stripe = ((animated_y * stripe_frequency).floor() % 2.0).abs();
 
     let stripe_color1 = Vector3::new(1.0, 0.3, 0.1); // Orange
@@ -241,13 +306,13 @@ fn shader_moving_stripes(fragment: &Fragment, time: f32) -> Vector3 {
 /// Example 3: Pulsing color waves
 #[allow(dead_code)]
 fn shader_pulsing_waves(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
 
     // Animated sine waves that pulse over time
-    let wave1 = (world_pos.x * 3.0 + time * 2.0).sin() * 0.5 + 0.5;
-    let wave2 = (world_pos.y * 3.0 + time * 1.5).cos() * 0.5 + 0.5;
-    let wave3 = (world_pos.z * 3.0 + time * 2.5).sin() * 0.5 + 0.5;
+    let wave1 = (object_pos.x * 3.0 + time * 2.0).sin() * 0.5 + 0.5;
+    let wave2 = (object_pos.y * 3.0 + time * 1.5).cos() * 0.5 + 0.5;
+    let wave3 = (object_pos.z * 3.0 + time * 2.5).sin() * 0.5 + 0.5;
 
     let wave_color = Vector3::new(wave1, wave2, wave3);
 
@@ -261,11 +326,11 @@ fn shader_pulsing_waves(fragment: &Fragment, time: f32) -> Vector3 {
 /// Example 4: Rotating rainbow gradient
 #[allow(dead_code)]
 fn shader_rotating_rainbow(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
 
     // Create rotating rainbow effect
-    let angle = world_pos.x.atan2(world_pos.z) + time;
+    let angle = object_pos.x.atan2(object_pos.z) + time;
     let hue = (angle / (2.0 * 3.14159)) % 1.0;
 
     // Convert hue to RGB (simplified HSV to RGB)
@@ -285,11 +350,11 @@ fn shader_rotating_rainbow(fragment: &Fragment, time: f32) -> Vector3 {
 /// Example 5: Expanding rings from origin
 #[allow(dead_code)]
 fn shader_expanding_rings(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
 
     // Distance from origin
-    let distance = (world_pos.x * world_pos.x + world_pos.y * world_pos.y + world_pos.z * world_pos.z).sqrt();
+    let distance = (object_pos.x * object_pos.x + object_pos.y * object_pos.y + object_pos.z * object_pos.z).sqrt();
 
     // Animated rings expanding outward
     let ring = (distance * 2.0 - time * 2.0).sin() * 0.5 + 0.5;
@@ -335,8 +400,87 @@ fn noise_3d(pos: Vector3, time: f32) -> f32 {
     (n1 + n2 + n3) / 3.0
 }
 
+/// Calidad de los shaders procedurales, común a todos (un uniform global): cada nivel quita
+/// octavas al ruido fractal y los dos más bajos simplifican la corona y la turbulencia del sol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShaderQuality {
+    Low,
+    Medium,
+    High,
+    Ultra,
+}
+
+impl ShaderQuality {
+    pub const ALL: [ShaderQuality; 4] = [ShaderQuality::Low, ShaderQuality::Medium, ShaderQuality::High, ShaderQuality::Ultra];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShaderQuality::Low => "baja",
+            ShaderQuality::Medium => "media",
+            ShaderQuality::High => "alta",
+            ShaderQuality::Ultra => "ultra",
+        }
+    }
+
+    /// Calidad a partir de su nombre, en español o en inglés (`baja`/`low`, `media`/`medium`...)
+    pub fn from_name(name: &str) -> Option<ShaderQuality> {
+        match name {
+            "baja" | "low" => Some(ShaderQuality::Low),
+            "media" | "medium" => Some(ShaderQuality::Medium),
+            "alta" | "high" => Some(ShaderQuality::High),
+            "ultra" => Some(ShaderQuality::Ultra),
+            _ => None,
+        }
+    }
+
+    /// Siguiente calidad, volviendo a la más baja después de la más alta
+    pub fn next(&self) -> ShaderQuality {
+        ShaderQuality::ALL[(*self as usize + 1) % ShaderQuality::ALL.len()]
+    }
+
+    /// Octavas que pierde cada llamada de ruido fractal: las altas son las que más cuestan y
+    /// menos se ven
+    pub fn octave_reduction(&self) -> i32 {
+        ShaderQuality::Ultra as i32 - *self as i32
+    }
+
+    /// Corona sin oscurecimiento de limbo ni animación y sol con una sola capa de turbulencia
+    pub fn simplified_corona(&self) -> bool {
+        matches!(self, ShaderQuality::Low | ShaderQuality::Medium)
+    }
+
+    /// Bloom de las superficies emisivas (ver `Framebuffer::bloom_pass`); la calidad baja lo omite
+    pub fn bloom(&self) -> bool {
+        *self != ShaderQuality::Low
+    }
+
+    /// Rayos crepusculares del sol (ver `Framebuffer::god_rays_pass`); la calidad baja los omite
+    pub fn god_rays(&self) -> bool {
+        *self != ShaderQuality::Low
+    }
+
+    /// Oclusión ambiental procedural del terreno (ver `height_occlusion`); la calidad baja la omite
+    pub fn ambient_occlusion(&self) -> bool {
+        *self != ShaderQuality::Low
+    }
+}
+
+static SHADER_QUALITY: AtomicU8 = AtomicU8::new(ShaderQuality::Ultra as u8);
+
+/// Calidad con la que dibujan todos los shaders procedurales
+pub fn shader_quality() -> ShaderQuality {
+    ShaderQuality::ALL[SHADER_QUALITY.load(Ordering::Relaxed) as usize]
+}
+
+/// Cambiar la calidad de los shaders; se aplica desde el siguiente fragmento
+pub fn set_shader_quality(quality: ShaderQuality) {
+    SHADER_QUALITY.store(quality as u8, Ordering::Relaxed);
+}
+
 /// Helper function to create fractal noise (multiple octaves)
 fn fractal_noise(pos: Vector3, time: f32, octaves: i32) -> f32 {
+    // Siempre queda al menos una octava
+    let octaves = (octaves - shader_quality().octave_reduction()).max(1);
     let mut value = 0.0;
     let mut amplitude = 0.5;
     let mut frequency = 1.0;
@@ -355,6 +499,19 @@ fn fractal_noise(pos: Vector3, time: f32, octaves: i32) -> f32 {
     value
 }
 
+/// Oclusión ambiental procedural a partir del campo de ruido que da la altura del terreno: las
+/// zonas bajas (valles, grietas, interior de los cráteres) reciben menos luz indirecta porque el
+/// terreno que las rodea tapa parte del cielo. `height` es el valor del ruido (0-1 aprox.) y
+/// `strength` cuánto se oscurece lo más hondo. Devuelve el factor que multiplica la iluminación
+fn height_occlusion(height: f32, strength: f32) -> f32 {
+    if !shader_quality().ambient_occlusion() {
+        return 1.0;
+    }
+    // Por encima de la altura media no hay oclusión; por debajo crece de forma suave
+    let depth = ((0.5 - height) / 0.4).clamp(0.0, 1.0);
+    1.0 - strength * depth * depth * (3.0 - 2.0 * depth)
+}
+
 /// Helper function to convert spherical coordinates
 fn spherical_coords(pos: Vector3) -> (f32, f32, f32) {
     let r = (pos.x * pos.x + pos.y * pos.y + pos.z * pos.z).sqrt();
@@ -373,34 +530,150 @@ fn spherical_coords(pos: Vector3) -> (f32, f32, f32) {
     (r.max(0.0001), theta, phi) // Asegurar r > 0
 }
 
+/// Altura del terreno de los planetas rocosos (0-1 aprox.) en la dirección `direction` desde el
+/// centro: la capa base de `shader_rocky_planet`, que `terrain` usa también para desplazar los
+/// vértices del relieve de cerca
+pub fn rocky_terrain_noise(direction: Vector3, time: f32) -> f32 {
+    let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt().max(0.0001);
+    let p = Vector3::new(direction.x / length, direction.y / length, direction.z / length);
+    let noise1 = fractal_noise(p, time * 0.1, 4);
+    let noise2 = fractal_noise(Vector3::new(p.x * 0.5, p.y * 2.0, p.z * 0.5), time * 0.05, 3);
+    noise1 * 0.7 + noise2 * 0.3
+}
+
+/// Emisión propia de cada tipo de planeta: los circuitos del planeta sci-fi, las grietas de lava
+/// del volcánico y las ciudades de los mundos habitados brillan también en la cara oscura (el sol
+/// se dibuja aparte con `draw_sun`)
+pub fn planet_emission(fragment: &Fragment, uniforms: &Uniforms, planet_type: PlanetType) -> Vector3 {
+    let time = uniforms.time;
+    match planet_type {
+        PlanetType::SciFi => scifi_circuit_glow(fragment.object_position, time),
+        PlanetType::Rocky | PlanetType::Ice => match &uniforms.atmosphere {
+            Some(atmosphere) if atmosphere.city_lights > 0.0 => {
+                city_lights(fragment.object_position, uniforms.sun_direction, atmosphere.city_lights)
+            }
+            _ => Vector3::zero(),
+        },
+        PlanetType::Volcanic if uniforms.eruptions.is_empty() => lava_crack_glow(fragment.object_position, time),
+        PlanetType::Volcanic => eruption_glow(
+            lava_crack_glow(fragment.object_position, time),
+            fragment.object_position,
+            &uniforms.eruptions,
+            time,
+        ),
+        _ => Vector3::zero(),
+    }
+}
+
+/// Líneas y nodos de la red de circuitos del planeta sci-fi (los mismos patrones que su CAPA 2),
+/// con un pulso de energía que los recorre
+fn scifi_circuit_glow(object_pos: Vector3, time: f32) -> Vector3 {
+    let (_r, theta, phi) = spherical_coords(object_pos);
+    let circuit_pattern = ((theta * 20.0).sin() * (phi * 15.0).cos()).abs();
+    let node_pattern = ((theta * 25.0).sin() * (phi * 20.0).sin()).abs();
+    let line = ((circuit_pattern - 0.8) / 0.2).clamp(0.0, 1.0);
+    let node = ((node_pattern - 0.9) / 0.1).clamp(0.0, 1.0);
+    let pulse = (time * 2.0 + theta * 10.0 - phi * 4.0).sin() * 0.35 + 0.65;
+    let glow = (line * 0.5 + node * 1.2) * pulse;
+    let color = Vector3::new(0.2, 0.8, 1.0);
+    Vector3::new(color.x * glow, color.y * glow, color.z * glow)
+}
+
+/// Mezcla de ruido y flujos del planeta volcánico: por encima de 0.6 es lava (CAPAS 1 y 2)
+pub fn lava_factor(object_pos: Vector3, time: f32) -> f32 {
+    let (_r, theta, phi) = spherical_coords(object_pos);
+    let lava_noise = fractal_noise(object_pos, time * 0.2, 4);
+    let lava_flow1 = (theta * 8.0 + phi * 6.0 + time * 0.8).sin() * 0.5 + 0.5;
+    let lava_flow2 = (theta * 12.0 - phi * 4.0 + time * 1.0).cos() * 0.3 + 0.7;
+    lava_noise * 0.5 + lava_flow1 * lava_flow2 * 0.5
+}
+
+/// Brillo incandescente de las grietas de lava del planeta volcánico
+fn lava_crack_glow(object_pos: Vector3, time: f32) -> Vector3 {
+    let lava = ((lava_factor(object_pos, time) - 0.6) / 0.4).clamp(0.0, 1.0);
+    let (_r, theta, _phi) = spherical_coords(object_pos);
+    let pulse = (time * 3.0 + theta * 5.0).sin() * 0.2 + 0.8;
+    let glow = lava.sqrt() * pulse * 0.9;
+    Vector3::new(1.0 * glow, 0.35 * glow, 0.05 * glow)
+}
+
+/// Lava avivada alrededor de las erupciones en curso: las grietas cercanas a la boca brillan
+/// varias veces más y la propia boca es un charco incandescente, todo según la intensidad
+fn eruption_glow(glow: Vector3, object_pos: Vector3, eruptions: &[Eruption], time: f32) -> Vector3 {
+    let p = object_pos;
+    let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt().max(0.0001);
+    let n = Vector3::new(p.x / length, p.y / length, p.z / length);
+
+    let mut boost = 0.0f32;
+    let mut vent = 0.0f32;
+    for eruption in eruptions {
+        let c = eruption.center;
+        let angle = (n.x * c.x + n.y * c.y + n.z * c.z).clamp(-1.0, 1.0).acos();
+        let t = angle / eruption.radius.max(0.0001);
+        if t >= 1.0 {
+            continue;
+        }
+        let intensity = eruption.intensity(time);
+        let falloff = (1.0 - t) * (1.0 - t);
+        boost = boost.max(intensity * falloff);
+        vent = vent.max(intensity * ((0.25 - t) / 0.25).clamp(0.0, 1.0));
+    }
+
+    let scale = 1.0 + boost * 3.0;
+    Vector3::new(
+        glow.x * scale + boost * 0.4 + vent * 2.0,
+        glow.y * scale + boost * 0.1 + vent * 0.9,
+        glow.z * scale + vent * 0.2,
+    )
+}
+
+/// Casquetes de hielo en los polos con estaciones: `sun_direction` está en el espacio de objeto
+/// del planeta (eje de giro = Y), así que su altura es la declinación del sol, que oscila a lo
+/// largo de la órbita según la oblicuidad. El casquete del hemisferio en verano se retira hacia el
+/// polo y el del hemisferio en invierno avanza hacia el ecuador; sin inclinación no hay estaciones
+/// `latitude` en radianes; `terrain` (0 a 1) deshilacha el borde siguiendo el relieve
+pub fn polar_caps(color: Vector3, latitude: f32, sun_direction: Vector3, terrain: f32) -> Vector3 {
+    let declination = sun_direction.y.clamp(-1.0, 1.0).asin();
+    // Latitud del borde de cada casquete: ~66° en los equinoccios, ±0.8 veces la declinación
+    let edge = if latitude >= 0.0 { 1.15 + declination * 0.8 } else { 1.15 - declination * 0.8 };
+    let edge = edge + (terrain - 0.5) * 0.2;
+    let ice = ((latitude.abs() - edge) / 0.06 + 0.5).clamp(0.0, 1.0);
+    if ice <= 0.0 {
+        return color;
+    }
+    let ice_color = srgb_to_linear_rgb(Vector3::new(0.9, 0.94, 1.0));
+    Vector3::new(
+        color.x + (ice_color.x - color.x) * ice,
+        color.y + (ice_color.y - color.y) * ice,
+        color.z + (ice_color.z - color.z) * ice,
+    )
+}
+
 /// Planeta 1: Planeta Rocoso (MÚLTIPLES CAPAS)
 /// CAPA 1: Ruido fractal para terreno base
 /// CAPA 2: Gradientes de altitud simulados
 /// CAPA 3: Iluminación simulada con terminador (día/noche)
 /// CAPA 4: Efectos de erosión y valles
-pub fn shader_rocky_planet(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+pub fn shader_rocky_planet(fragment: &Fragment, time: f32, sun_direction: Vector3) -> Vector3 {
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
     // Convertir a coordenadas esféricas para crear patrones
-    let (r, theta, _phi) = spherical_coords(world_pos);
+    let (r, theta, _phi) = spherical_coords(object_pos);
     
     // === CAPA 1: Ruido fractal para terreno base ===
-    let noise1 = fractal_noise(world_pos, time * 0.1, 4);
-    let noise2 = fractal_noise(Vector3::new(world_pos.x * 0.5, world_pos.y * 2.0, world_pos.z * 0.5), time * 0.05, 3);
-    let terrain_noise = noise1 * 0.7 + noise2 * 0.3;
+    // Sobre la esfera unitaria, igual que el relieve de `terrain` para que los colores caigan
+    // sobre sus montañas
+    let terrain_noise = rocky_terrain_noise(object_pos, time);
     
     // === CAPA 2: Gradientes de altitud simulados ===
     // Simular diferentes altitudes basadas en latitud
     let altitude_gradient = (theta * 2.0).sin() * 0.5 + 0.5; // Más alto en el ecuador
     let altitude_variation = terrain_noise * 0.3 + altitude_gradient * 0.7;
     
-    // === CAPA 3: Iluminación simulada con terminador (día/noche) ===
-    // Simular posición del sol (luz direccional)
-    let sun_dir_raw = Vector3::new(1.0, 0.5, 0.3);
-    let sun_dir_len = (sun_dir_raw.x * sun_dir_raw.x + sun_dir_raw.y * sun_dir_raw.y + sun_dir_raw.z * sun_dir_raw.z).sqrt().max(0.0001);
-    let sun_direction = Vector3::new(sun_dir_raw.x / sun_dir_len, sun_dir_raw.y / sun_dir_len, sun_dir_raw.z / sun_dir_len);
-    let normal = Vector3::new(world_pos.x / r, world_pos.y / r, world_pos.z / r);
+    // === CAPA 3: Iluminación con terminador (día/noche) ===
+    // La dirección al sol llega en espacio de objeto, así que respeta la inclinación y el giro del planeta
+    let normal = Vector3::new(object_pos.x / r, object_pos.y / r, object_pos.z / r);
     let sun_dot = (normal.x * sun_direction.x + normal.y * sun_direction.y + normal.z * sun_direction.z).max(0.0);
     
     // Terminador (zona crepuscular) más suave
@@ -408,7 +681,7 @@ pub fn shader_rocky_planet(fragment: &Fragment, time: f32) -> Vector3 {
     let day_night = sun_dot * 0.7 + 0.3; // Nunca completamente oscuro
     
     // === CAPA 4: Efectos de erosión y valles ===
-    let erosion = fractal_noise(Vector3::new(world_pos.x * 3.0, world_pos.y * 3.0, world_pos.z * 3.0), time * 0.02, 2);
+    let erosion = fractal_noise(Vector3::new(object_pos.x * 3.0, object_pos.y * 3.0, object_pos.z * 3.0), time * 0.02, 2);
     
     // Colores base para planeta rocoso con variaciones
     let rock_color1 = Vector3::new(0.4, 0.3, 0.2); // Marrón oscuro (valles)
@@ -449,6 +722,9 @@ pub fn shader_rocky_planet(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
     // Aplicar efectos de erosión
     let eroded_color = Vector3::new(
         planet_color.x * (1.0 - erosion * 0.2),
@@ -456,11 +732,17 @@ pub fn shader_rocky_planet(fragment: &Fragment, time: f32) -> Vector3 {
         planet_color.z * (1.0 - erosion * 0.2),
     );
     
+    // Casquetes polares que crecen en invierno y menguan en verano
+    let eroded_color = polar_caps(eroded_color, theta, sun_direction, terrain_noise);
+
+    // Oclusión ambiental: los valles y las zonas erosionadas quedan más oscuros
+    let occlusion = height_occlusion(terrain_noise * 0.7 + (1.0 - erosion) * 0.3, 0.45);
+
     // Aplicar iluminación simulada (día/noche) y terminador
     let final_color = Vector3::new(
-        eroded_color.x * day_night * terminator,
-        eroded_color.y * day_night * terminator,
-        eroded_color.z * day_night * terminator,
+        eroded_color.x * day_night * terminator * occlusion,
+        eroded_color.y * day_night * terminator * occlusion,
+        eroded_color.z * day_night * terminator * occlusion,
     );
     
     // Combinar con iluminación base del sistema
@@ -476,12 +758,12 @@ pub fn shader_rocky_planet(fragment: &Fragment, time: f32) -> Vector3 {
 /// CAPA 2: Ondas de gas turbulentas animadas
 /// CAPA 3: Iluminación simulada con gradiente de profundidad
 /// CAPA 4: Remolinos y vórtices procedurales
-pub fn shader_gas_giant(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+pub fn shader_gas_giant(fragment: &Fragment, time: f32, sun_direction: Vector3, storms: &[Storm]) -> Vector3 {
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
     // Convertir a coordenadas esféricas
-    let (_r, theta, phi) = spherical_coords(world_pos);
+    let (r, theta, phi) = spherical_coords(object_pos);
     
     // === CAPA 1: Bandas de latitud con gradientes ===
     let band_frequency = 8.0;
@@ -498,8 +780,9 @@ pub fn shader_gas_giant(fragment: &Fragment, time: f32) -> Vector3 {
     // === CAPA 3: Iluminación simulada con gradiente de profundidad ===
     // Simular profundidad de la atmósfera (más brillante en el centro)
     let depth_factor = (1.0 - (theta.abs() / (PI * 2.0))) * 0.5 + 0.5;
-    // Simular iluminación solar
-    let sun_dot = (theta.sin() * 0.8 + 0.2).max(0.0);
+    // Iluminación solar con la normal rotada (dirección al sol en espacio de objeto)
+    let normal = Vector3::new(object_pos.x / r, object_pos.y / r, object_pos.z / r);
+    let sun_dot = (normal.x * sun_direction.x + normal.y * sun_direction.y + normal.z * sun_direction.z).max(0.0) * 0.8 + 0.2;
     let atmospheric_light = depth_factor * sun_dot * 0.8 + 0.2;
     
     // === CAPA 4: Remolinos y vórtices procedurales ===
@@ -542,7 +825,13 @@ pub fn shader_gas_giant(fragment: &Fragment, time: f32) -> Vector3 {
             gas_color2.z + (gas_color5.z - gas_color2.z) * ((color_factor - 0.7) / 0.3),
         )
     };
+
+    // === CAPA 5: Tormentas persistentes (gran mancha roja) ===
+    let planet_color = if storms.is_empty() { planet_color } else { storm_vortices(planet_color, theta, phi, storms, time) };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
     // Aplicar todas las capas
     let final_color = Vector3::new(
         planet_color.x * atmospheric_light * vortex_effect,
@@ -558,17 +847,55 @@ pub fn shader_gas_giant(fragment: &Fragment, time: f32) -> Vector3 {
     )
 }
 
+/// Tormentas persistentes de un gigante gaseoso: cada una es un óvalo el doble de ancho que de
+/// alto con brazos en espiral que giran más deprisa cerca del centro (se enrollan con el tiempo)
+/// y un collar claro alrededor. `color` en sRGB; `latitude` y `longitude` del fragmento en radianes
+pub fn storm_vortices(color: Vector3, latitude: f32, longitude: f32, storms: &[Storm], time: f32) -> Vector3 {
+    let mut color = color;
+    for storm in storms {
+        // Coordenadas del óvalo en unidades de sus semiejes: x hacia el este, y hacia el norte
+        let delta_longitude = (longitude - storm.longitude + PI).rem_euclid(2.0 * PI) - PI;
+        let x = delta_longitude * storm.latitude.cos() / (storm.radius * 2.0).max(0.0001);
+        let y = (latitude - storm.latitude) / storm.radius.max(0.0001);
+        let d = (x * x + y * y).sqrt();
+        if d >= 1.3 {
+            continue;
+        }
+
+        // Rotación diferencial: el ángulo avanza más deprisa en el interior
+        let angle = y.atan2(x) - storm.spin * time * (1.5 - d);
+        let arms = (angle * 2.0 + d * 9.0).sin() * 0.5 + 0.5;
+        let shade = 0.75 + 0.35 * arms;
+        let core = (1.0 - d).clamp(0.0, 1.0);
+        let core = core * core * (3.0 - 2.0 * core);
+        color = Vector3::new(
+            color.x + (storm.color.x * shade - color.x) * core,
+            color.y + (storm.color.y * shade - color.y) * core,
+            color.z + (storm.color.z * shade - color.z) * core,
+        );
+
+        // Collar de nubes claras justo fuera del óvalo
+        let collar = (1.0 - ((d - 1.05) / 0.2).abs()).clamp(0.0, 1.0) * 0.25;
+        color = Vector3::new(
+            color.x + (0.95 - color.x) * collar,
+            color.y + (0.9 - color.y) * collar,
+            color.z + (0.8 - color.z) * collar,
+        );
+    }
+    color
+}
+
 /// Planeta 3: Planeta Sci-Fi (MÚLTIPLES CAPAS)
 /// CAPA 1: Patrones de energía pulsante con múltiples frecuencias
 /// CAPA 2: Redes de circuitos y nodos energéticos
 /// CAPA 3: Gradientes de color dinámicos con iluminación simulada
 /// CAPA 4: Efectos de brillo y resplandor procedural
-pub fn shader_scifi_planet(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+pub fn shader_scifi_planet(fragment: &Fragment, time: f32, sun_direction: Vector3) -> Vector3 {
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
     // Convertir a coordenadas esféricas
-    let (r, theta, phi) = spherical_coords(world_pos);
+    let (r, theta, phi) = spherical_coords(object_pos);
     
     // === CAPA 1: Patrones de energía pulsante con múltiples frecuencias ===
     let energy_pulse1 = (time * 2.0 + theta * 10.0).sin() * 0.5 + 0.5;
@@ -585,11 +912,9 @@ pub fn shader_scifi_planet(fragment: &Fragment, time: f32) -> Vector3 {
     let circuit_effect = circuit_intensity * node_intensity * 0.7 + 0.3;
     
     // === CAPA 3: Gradientes de color dinámicos con iluminación simulada ===
-    // Simular iluminación direccional para energía
-    let energy_dir = Vector3::new(0.7, 0.5, 0.3);
-    let energy_dir_len = (energy_dir.x * energy_dir.x + energy_dir.y * energy_dir.y + energy_dir.z * energy_dir.z).sqrt().max(0.0001);
-    let energy_direction = Vector3::new(energy_dir.x / energy_dir_len, energy_dir.y / energy_dir_len, energy_dir.z / energy_dir_len);
-    let normal = Vector3::new(world_pos.x / r, world_pos.y / r, world_pos.z / r);
+    // Iluminación direccional desde el sol real (espacio de objeto)
+    let energy_direction = sun_direction;
+    let normal = Vector3::new(object_pos.x / r, object_pos.y / r, object_pos.z / r);
     let energy_light = (normal.x * energy_direction.x + normal.y * energy_direction.y + normal.z * energy_direction.z).max(0.0);
     let energy_shadow = energy_light * 0.6 + 0.4;
     
@@ -642,6 +967,9 @@ pub fn shader_scifi_planet(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
     // Aplicar todas las capas
     let energy_effect = energy_pulse * circuit_effect;
     let final_color = Vector3::new(
@@ -674,21 +1002,39 @@ pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
     shader_base_color(fragment, time) // Default: just show the lighting
 }
 
+/// Densidad estática de los anillos a una distancia radial dada
+/// Combina la variación de bandas con las bandas vacías (divisiones) definidas en la escena
+pub fn ring_density(radial_dist: f32, gaps: &[RingGap]) -> f32 {
+    let mut density = (radial_dist * 12.0).sin() * 0.3 + 0.7;
+
+    for gap in gaps {
+        let half_width = gap.width * 0.5;
+        let offset = (radial_dist - gap.radius).abs();
+        if offset < half_width {
+            // Bordes suaves: el último 30% de la banda se funde con el anillo
+            let edge = ((half_width - offset) / (half_width * 0.3)).min(1.0);
+            density *= 1.0 - (1.0 - gap.density) * edge;
+        }
+    }
+
+    density
+}
+
 /// Shader para anillos procedurales
 /// Simula anillos planetarios con partículas y variaciones de densidad
-pub fn shader_rings(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+pub fn shader_rings(fragment: &Fragment, time: f32, gaps: &[RingGap]) -> Vector3 {
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
     // Anillos están en el plano XZ, así que usamos distancia radial
-    let radial_dist = (world_pos.x * world_pos.x + world_pos.z * world_pos.z).sqrt().max(0.0001);
+    let radial_dist = (object_pos.x * object_pos.x + object_pos.z * object_pos.z).sqrt().max(0.0001);
     
     // Crear bandas de anillos
     let ring_bands = (radial_dist * 8.0 + time * 0.2).sin() * 0.5 + 0.5;
     
-    // Variación de densidad
-    let density = (radial_dist * 12.0).sin() * 0.3 + 0.7;
-    let density_variation = fractal_noise(Vector3::new(world_pos.x, 0.0, world_pos.z), time * 0.1, 3);
+    // Variación de densidad (incluye las bandas vacías)
+    let density = ring_density(radial_dist, gaps);
+    let density_variation = fractal_noise(Vector3::new(object_pos.x, 0.0, object_pos.z), time * 0.1, 3);
     
     // Color de anillos (grises con variaciones)
     let ring_color = Vector3::new(0.6, 0.6, 0.65);
@@ -703,25 +1049,53 @@ pub fn shader_rings(fragment: &Fragment, time: f32) -> Vector3 {
         ring_color_dark.z + (ring_color.z - ring_color_dark.z) * color_factor,
     );
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
+    // El brillo ya no se multiplica por la densidad: la densidad controla la transparencia
     Vector3::new(
-        (planet_color.x * base_color.x * final_density).min(1.0),
-        (planet_color.y * base_color.y * final_density).min(1.0),
-        (planet_color.z * base_color.z * final_density).min(1.0),
+        (planet_color.x * base_color.x.max(0.3) * 1.5).min(1.0),
+        (planet_color.y * base_color.y.max(0.3) * 1.5).min(1.0),
+        (planet_color.z * base_color.z.max(0.3) * 1.5).min(1.0),
     )
 }
 
+/// Tamaño de las rocas de los anillos a `distance` de la cámara con un campo de radio `radius`:
+/// 1 cerca y 0 en el borde. El disco se desvanece en la misma proporción
+pub fn ring_near_fade(distance: f32, radius: f32) -> f32 {
+    let inner = radius * 0.6;
+    let t = ((radius - distance) / (radius - inner).max(0.0001)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Transparencia de los anillos basada en la densidad de partículas
+/// Las zonas poco densas y las divisiones dejan ver las estrellas y el planeta detrás
+pub fn ring_alpha(fragment: &Fragment, time: f32, rings: &RingUniforms) -> f32 {
+    let object_pos = fragment.object_position;
+    let radial_dist = (object_pos.x * object_pos.x + object_pos.z * object_pos.z).sqrt().max(0.0001);
+
+    let density = ring_density(radial_dist, &rings.gaps);
+    let density_variation = fractal_noise(Vector3::new(object_pos.x, 0.0, object_pos.z), time * 0.1, 3);
+
+    // Desvanecer en los bordes interno y externo del anillo
+    let fade_width = (rings.outer_radius - rings.inner_radius) * 0.08;
+    let inner_fade = ((radial_dist - rings.inner_radius) / fade_width).clamp(0.0, 1.0);
+    let outer_fade = ((rings.outer_radius - radial_dist) / fade_width).clamp(0.0, 1.0);
+
+    (density * (0.6 + density_variation * 0.4) * inner_fade * outer_fade * rings.opacity).clamp(0.0, 1.0)
+}
+
 /// Shader para luna procedural
 /// Simula superficie lunar con cráteres y variaciones
-pub fn shader_moon(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
-    let base_color = fragment.color;
+pub fn shader_moon(fragment: &Fragment, time: f32, sun_direction: Vector3, fill_light: Option<&FillLight>) -> Vector3 {
+    let object_pos = fragment.object_position;
     
     // Cráteres con ruido fractal
-    let craters = fractal_noise(world_pos, time * 0.01, 5);
+    let craters = fractal_noise(object_pos, time * 0.01, 5);
     let crater_depth = (1.0 - craters * 0.5).max(0.3);
     
     // Variaciones de superficie lunar
-    let surface_variation = fractal_noise(Vector3::new(world_pos.x * 2.0, world_pos.y * 2.0, world_pos.z * 2.0), time * 0.02, 3);
+    let surface_variation = fractal_noise(Vector3::new(object_pos.x * 2.0, object_pos.y * 2.0, object_pos.z * 2.0), time * 0.02, 3);
     
     // Colores lunares (grises)
     let moon_color1 = Vector3::new(0.6, 0.6, 0.65); // Gris claro
@@ -744,10 +1118,29 @@ pub fn shader_moon(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
+    // Oclusión ambiental: el interior de los cráteres (ruido bajo) queda en sombra
+    let occlusion = height_occlusion(craters, 0.5);
+
+    // Iluminación: el sol real (sin atmósfera, terminador nítido) más la luz que refleja el
+    // planeta padre, que aclara un poco la cara nocturna
+    let length = (object_pos.x * object_pos.x + object_pos.y * object_pos.y + object_pos.z * object_pos.z).sqrt().max(0.0001);
+    let normal = Vector3::new(object_pos.x / length, object_pos.y / length, object_pos.z / length);
+    let sun = (normal.x * sun_direction.x + normal.y * sun_direction.y + normal.z * sun_direction.z).max(0.0);
+    let fill = fill_light.map_or(Vector3::zero(), |fill| {
+        let d = fill.direction;
+        let facing = (normal.x * d.x + normal.y * d.y + normal.z * d.z).max(0.0);
+        Vector3::new(fill.color.x * facing, fill.color.y * facing, fill.color.z * facing)
+    });
+    let ambient = 0.02;
+    let light = Vector3::new(sun + fill.x + ambient, sun + fill.y + ambient, sun + fill.z + ambient);
+
     Vector3::new(
-        (planet_color.x * base_color.x * crater_depth).min(1.0),
-        (planet_color.y * base_color.y * crater_depth).min(1.0),
-        (planet_color.z * base_color.z * crater_depth).min(1.0),
+        (planet_color.x * light.x * crater_depth * occlusion).min(1.0),
+        (planet_color.y * light.y * crater_depth * occlusion).min(1.0),
+        (planet_color.z * light.z * crater_depth * occlusion).min(1.0),
     )
 }
 
@@ -760,45 +1153,53 @@ pub fn shader_moon(fragment: &Fragment, time: f32) -> Vector3 {
 /// - CAPA 5: Gradiente de temperatura (color dinámico)
 /// - CAPA 6: Corona solar con resplandor
 /// - CAPA 7: Llamaradas solares procedurales
-pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
-    let (r, theta, phi) = spherical_coords(world_pos);
+///
+/// La paleta, la fuerza de la corona y la frecuencia de llamaradas vienen de `star`
+pub fn shader_sun(fragment: &Fragment, time: f32, star: &Star) -> Vector3 {
+    let object_pos = fragment.object_position;
+    let (r, theta, phi) = spherical_coords(object_pos);
+    let simplified = shader_quality().simplified_corona();
     
     // ======================================
     // CAPA 1: RUIDO PERLIN SIMULADO (Turbulencias Solares)
     // Usando múltiples octavas para simular Perlin noise
     // ======================================
-    let perlin_octave1 = fractal_noise(world_pos, time * 0.3, 6);
-    let perlin_octave2 = fractal_noise(
-        Vector3::new(world_pos.x * 2.3, world_pos.y * 2.3, world_pos.z * 2.3),
-        time * 0.25,
-        4
-    );
-    let perlin_octave3 = fractal_noise(
-        Vector3::new(world_pos.x * 4.7, world_pos.y * 4.7, world_pos.z * 4.7),
-        time * 0.4,
-        3
-    );
-    // Combinar octavas con diferentes pesos (simulando Perlin noise real)
-    let perlin_turbulence = perlin_octave1 * 0.5 + perlin_octave2 * 0.3 + perlin_octave3 * 0.2;
+    let perlin_octave1 = fractal_noise(object_pos, time * 0.3, 6);
+    // Con la calidad simplificada basta la primera capa
+    let perlin_turbulence = if simplified {
+        perlin_octave1
+    } else {
+        let perlin_octave2 = fractal_noise(
+            Vector3::new(object_pos.x * 2.3, object_pos.y * 2.3, object_pos.z * 2.3),
+            time * 0.25,
+            4
+        );
+        let perlin_octave3 = fractal_noise(
+            Vector3::new(object_pos.x * 4.7, object_pos.y * 4.7, object_pos.z * 4.7),
+            time * 0.4,
+            3
+        );
+        // Combinar octavas con diferentes pesos (simulando Perlin noise real)
+        perlin_octave1 * 0.5 + perlin_octave2 * 0.3 + perlin_octave3 * 0.2
+    };
     
     // ======================================
     // CAPA 2: RUIDO CELLULAR SIMULADO (Manchas Solares)
     // Simulando el patrón de Voronoi/Cellular noise
     // ======================================
     let cell_scale = 8.0;
-    let cell_x = (world_pos.x * cell_scale + time * 0.1).floor();
-    let cell_y = (world_pos.y * cell_scale + time * 0.08).floor();
-    let cell_z = (world_pos.z * cell_scale + time * 0.12).floor();
+    let cell_x = (object_pos.x * cell_scale + time * 0.1).floor();
+    let cell_y = (object_pos.y * cell_scale + time * 0.08).floor();
+    let cell_z = (object_pos.z * cell_scale + time * 0.12).floor();
     
     // Generar "puntos de celda" procedurales
     let cell_seed = cell_x * 127.1 + cell_y * 311.7 + cell_z * 74.7;
     let cell_random = (cell_seed.sin() * 43758.5453).fract();
     
     // Distancia al centro de la celda (simula manchas solares)
-    let cell_dist = ((world_pos.x * cell_scale - cell_x).abs() + 
-                     (world_pos.y * cell_scale - cell_y).abs() + 
-                     (world_pos.z * cell_scale - cell_z).abs()) * 0.5;
+    let cell_dist = ((object_pos.x * cell_scale - cell_x).abs() + 
+                     (object_pos.y * cell_scale - cell_y).abs() + 
+                     (object_pos.z * cell_scale - cell_z).abs()) * 0.5;
     let cellular_pattern = (1.0 - cell_dist.min(1.0)) * cell_random;
     
     // Manchas solares (regiones más oscuras y frías)
@@ -815,12 +1216,12 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
     // ======================================
     let simplex_freq1 = 3.5;
     let simplex_freq2 = 7.2;
-    let simplex_flow1 = ((world_pos.x * simplex_freq1 + time * 0.5).sin() * 
-                         (world_pos.y * simplex_freq1 - time * 0.4).cos() *
-                         (world_pos.z * simplex_freq1 + time * 0.6).sin()) * 0.5 + 0.5;
-    let simplex_flow2 = ((world_pos.x * simplex_freq2 - time * 0.7).cos() * 
-                         (world_pos.y * simplex_freq2 + time * 0.5).sin() *
-                         (world_pos.z * simplex_freq2 - time * 0.8).cos()) * 0.5 + 0.5;
+    let simplex_flow1 = ((object_pos.x * simplex_freq1 + time * 0.5).sin() * 
+                         (object_pos.y * simplex_freq1 - time * 0.4).cos() *
+                         (object_pos.z * simplex_freq1 + time * 0.6).sin()) * 0.5 + 0.5;
+    let simplex_flow2 = ((object_pos.x * simplex_freq2 - time * 0.7).cos() * 
+                         (object_pos.y * simplex_freq2 + time * 0.5).sin() *
+                         (object_pos.z * simplex_freq2 - time * 0.8).cos()) * 0.5 + 0.5;
     let simplex_plasma = simplex_flow1 * 0.6 + simplex_flow2 * 0.4;
     
     // ======================================
@@ -850,9 +1251,9 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
     // Simula diferentes temperaturas en la superficie solar
     // ======================================
     // Temperatura base calculada desde el centro hacia el borde
-    let distance_from_center = (world_pos.x * world_pos.x + 
-                                world_pos.y * world_pos.y + 
-                                world_pos.z * world_pos.z).sqrt();
+    let distance_from_center = (object_pos.x * object_pos.x + 
+                                object_pos.y * object_pos.y + 
+                                object_pos.z * object_pos.z).sqrt();
     let radial_gradient = 1.0 - (distance_from_center / r).min(1.0);
     
     // Temperatura variando con el ruido y el tiempo
@@ -861,15 +1262,15 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
                      simplex_plasma * 0.2 + 
                      cellular_pattern * 0.1;
     
-    // Definir colores basados en temperatura (negro de cuerpo)
-    // Temperaturas más altas = más blanco/azul
-    // Temperaturas medias = amarillo/naranja
-    // Temperaturas bajas = rojo/naranja oscuro
-    let temp_hot = Vector3::new(1.0, 0.95, 0.8);      // Blanco-amarillo (centro, muy caliente)
-    let temp_medium = Vector3::new(1.0, 0.7, 0.2);    // Amarillo-naranja (medio)
-    let temp_warm = Vector3::new(1.0, 0.5, 0.1);      // Naranja (caliente)
-    let temp_cool = Vector3::new(0.9, 0.3, 0.05);     // Rojo-naranja (relativamente frío)
-    let temp_sunspot = Vector3::new(0.4, 0.15, 0.05); // Rojo oscuro (manchas solares)
+    // Colores basados en temperatura (cuerpo negro de la estrella)
+    // Temperaturas más altas = color de la estrella (blanco-amarillo en una G, azul en una B)
+    // Temperaturas bajas = fracciones de su temperatura, más rojas y oscuras (manchas solares)
+    let palette = star.palette();
+    let temp_hot = palette.hot;         // Centro, muy caliente
+    let temp_medium = palette.medium;   // Medio
+    let temp_warm = palette.warm;       // Caliente
+    let temp_cool = palette.cool;       // Relativamente frío
+    let temp_sunspot = palette.sunspot; // Manchas solares
     
     // Gradiente de temperatura con transiciones suaves
     let base_color = if temperature > 0.8 {
@@ -906,22 +1307,25 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de aplicar la emisión
+    let base_color = srgb_to_linear_rgb(base_color);
+
     // ======================================
     // CAPA 6: CORONA SOLAR (Resplandor en los Bordes)
     // Simula la corona solar visible en los bordes
     // ======================================
-    let normal = Vector3::new(world_pos.x / r, world_pos.y / r, world_pos.z / r);
+    let normal = Vector3::new(object_pos.x / r, object_pos.y / r, object_pos.z / r);
     // Simular vista desde la cámara (aproximación)
     let view_dir = Vector3::new(0.0, 0.0, 1.0); // Vista simplificada
     let view_dot = (normal.x * view_dir.x + normal.y * view_dir.y + normal.z * view_dir.z).abs();
     
-    // Efecto limbo (más brillante en los bordes)
-    let limb_darkening = view_dot.powf(0.4); // Exponente < 1 para efecto inverso en bordes
+    // Efecto limbo (más brillante en los bordes); la versión simplificada evita la potencia
+    let limb_darkening = if simplified { view_dot } else { view_dot.powf(0.4) }; // Exponente < 1 para efecto inverso en bordes
     let corona_brightness = (1.0 - limb_darkening) * 0.5 + 0.5;
     
-    // Corona animada
-    let corona_variation = (time * 0.8 + theta * 4.0).sin() * 0.2 + 0.8;
-    let corona_effect = corona_brightness * corona_variation * 1.3;
+    // Corona animada (fija con la calidad simplificada)
+    let corona_variation = if simplified { 0.8 } else { (time * 0.8 + theta * 4.0).sin() * 0.2 + 0.8 };
+    let corona_effect = 1.0 + (corona_brightness * corona_variation * 1.3 - 1.0) * star.corona_strength;
     
     // ======================================
     // CAPA 7: LLAMARADAS SOLARES (Flares Procedurales)
@@ -929,8 +1333,10 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
     // ======================================
     let flare_pattern = ((theta * 15.0 + time * 3.0).sin() * 
                          (phi * 12.0 - time * 2.5).cos()).abs();
-    let flare_intensity = if flare_pattern > 0.85 {
-        let flare_strength = (flare_pattern - 0.85) / 0.15;
+    // Umbral del patrón: más llamaradas cuanto más bajo (0.85 para el sol)
+    let flare_threshold = (1.0 - 0.15 * star.flare_frequency).clamp(0.5, 0.99);
+    let flare_intensity = if flare_pattern > flare_threshold {
+        let flare_strength = (flare_pattern - flare_threshold) / (1.0 - flare_threshold);
         let flare_pulse = (time * 5.0).sin() * 0.5 + 0.5;
         1.0 + flare_strength * flare_pulse * 2.0 // Puede aumentar hasta 3x
     } else {
@@ -946,57 +1352,304 @@ pub fn shader_sun(fragment: &Fragment, time: f32) -> Vector3 {
         base_color.z * emission_intensity * is_sunspot * corona_effect * flare_intensity,
     );
     
-    // Intensidad mínima para que el sol siempre sea visible y brillante
+    // Intensidad mínima para que el sol siempre sea visible y brillante (con el tono medio de la estrella)
     let min_intensity = 0.5;
+    let floor = srgb_to_linear_rgb(temp_medium);
     Vector3::new(
-        (combined_color.x.max(min_intensity) * 1.5).min(3.0), // Permitir valores > 1.0 para efecto HDR
-        (combined_color.y.max(min_intensity * 0.5) * 1.5).min(3.0),
-        (combined_color.z.max(min_intensity * 0.2) * 1.5).min(3.0),
+        (combined_color.x.max(min_intensity * floor.x) * 1.5).min(3.0), // Permitir valores > 1.0 para efecto HDR
+        (combined_color.y.max(min_intensity * floor.y) * 1.5).min(3.0),
+        (combined_color.z.max(min_intensity * floor.z) * 1.5).min(3.0),
     )
 }
 
+/// Parámetros de anillos para los shaders: transparencia, divisiones y sombras mutuas con el planeta
+/// Todas las distancias están en el espacio local del planeta (centrado en el planeta, sin escala)
+#[derive(Clone, Debug)]
+pub struct RingUniforms {
+    pub planet_radius: f32,      // Radio del planeta (escala aplicada a la esfera unitaria)
+    pub inner_radius: f32,       // Radio interno del anillo
+    pub outer_radius: f32,       // Radio externo del anillo
+    pub opacity: f32,            // Opacidad máxima del anillo
+    pub gaps: Vec<RingGap>,      // Bandas vacías (divisiones)
+    pub near_blend: f32,         // Cuánto se ven las rocas de cerca (0 = solo el disco, ver `ring_particles`)
+    pub near_center: Vector3,    // Punto del mundo alrededor del cual el disco deja paso a las rocas
+    pub near_radius: f32,        // Radio del campo de rocas alrededor de `near_center`
+}
+
+/// Sombra del planeta sobre los anillos
+/// Lanza un rayo desde el fragmento del anillo hacia el sol y prueba la intersección con la esfera del planeta
+fn planet_shadow_on_ring(pos: Vector3, rings: &RingUniforms, sun_direction: Vector3) -> f32 {
+    let s = sun_direction;
+    // Distancia a lo largo del rayo hasta el punto más cercano al centro del planeta
+    let t = -(pos.x * s.x + pos.y * s.y + pos.z * s.z);
+    if t <= 0.0 {
+        return 1.0; // El planeta está detrás del fragmento (respecto al sol)
+    }
+    let closest = Vector3::new(pos.x + s.x * t, pos.y + s.y * t, pos.z + s.z * t);
+    let distance = (closest.x * closest.x + closest.y * closest.y + closest.z * closest.z).sqrt();
+
+    // Penumbra suave en el borde del cilindro de sombra
+    let edge = ((distance - rings.planet_radius * 0.95) / (rings.planet_radius * 0.1)).clamp(0.0, 1.0);
+    let shadow = 0.2; // Los anillos no quedan totalmente negros (luz reflejada del planeta)
+    shadow + (1.0 - shadow) * edge
+}
+
+/// Sombra de los anillos sobre el planeta
+/// Intersecta el rayo fragmento->sol con el plano de los anillos (Y=0 local) y evalúa la densidad en ese radio
+fn ring_shadow_on_planet(pos: Vector3, rings: &RingUniforms, sun_direction: Vector3) -> f32 {
+    let s = sun_direction;
+    if s.y.abs() < 0.0001 {
+        return 1.0; // Sol en el plano de los anillos: sombra de canto
+    }
+    let t = -pos.y / s.y;
+    if t <= 0.0 {
+        return 1.0; // El plano de los anillos está del lado opuesto al sol
+    }
+    let hit_x = pos.x + s.x * t;
+    let hit_z = pos.z + s.z * t;
+    let radial_dist = (hit_x * hit_x + hit_z * hit_z).sqrt();
+    if radial_dist < rings.inner_radius || radial_dist > rings.outer_radius {
+        return 1.0;
+    }
+
+    // Misma densidad que shader_rings para que la banda de sombra (y sus divisiones) coincida
+    let density = ring_density(radial_dist, &rings.gaps);
+    1.0 - density * rings.opacity * 0.7
+}
+
+/// Estrella usada cuando el sol se dibuja como `PlanetType::Sun` sin parámetros propios
+static DEFAULT_STAR: LazyLock<Star> = LazyLock::new(Star::default);
+
 /// Fragment shader with planet type selection
 pub fn fragment_shader_planet(fragment: &Fragment, uniforms: &Uniforms, planet_type: PlanetType) -> Vector3 {
+    finish_planet_color(planet_surface_color(fragment, uniforms, planet_type), fragment, uniforms, planet_type)
+}
+
+/// Color base del shader procedural de cada tipo, antes de cráteres, atmósfera y sombras
+/// Es la parte que gpu_shading.wgsl repite en la GPU con `--gpu`
+pub fn planet_surface_color(fragment: &Fragment, uniforms: &Uniforms, planet_type: PlanetType) -> Vector3 {
     let time = uniforms.time;
-    
     match planet_type {
-        PlanetType::Rocky => shader_rocky_planet(fragment, time),
-        PlanetType::GasGiant => shader_gas_giant(fragment, time),
-        PlanetType::SciFi => shader_scifi_planet(fragment, time),
-        PlanetType::Ice => shader_ice_planet(fragment, time),
+        PlanetType::Rocky => shader_rocky_planet(fragment, time, uniforms.sun_direction),
+        PlanetType::GasGiant => shader_gas_giant(fragment, time, uniforms.sun_direction, &uniforms.storms),
+        PlanetType::SciFi => shader_scifi_planet(fragment, time, uniforms.sun_direction),
+        PlanetType::Ice => shader_ice_planet(fragment, time, uniforms.sun_direction),
         PlanetType::Volcanic => shader_volcanic_planet(fragment, time),
-        PlanetType::Ring => shader_rings(fragment, time),
-        PlanetType::Moon => shader_moon(fragment, time),
-        PlanetType::Sun => shader_sun(fragment, time),
+        PlanetType::Ring => shader_rings(fragment, time, uniforms.rings.as_ref().map_or(&[], |rings| rings.gaps.as_slice())),
+        PlanetType::Moon => shader_moon(fragment, time, uniforms.sun_direction, uniforms.fill_light.as_ref()),
+        PlanetType::Sun => shader_sun(fragment, time, &DEFAULT_STAR),
         PlanetType::Ship => shader_ship(fragment, time),
     }
 }
 
+/// Resto de `fragment_shader_planet` sobre el color base `color`: cráteres, atmósfera, sombras
+/// mutuas con los anillos y brillo especular
+pub fn finish_planet_color(color: Vector3, fragment: &Fragment, uniforms: &Uniforms, planet_type: PlanetType) -> Vector3 {
+    let time = uniforms.time;
+
+    // Cráteres de impacto sobre las superficies sólidas (y cicatrices oscuras en los gigantes)
+    let color = match planet_type {
+        PlanetType::Ring | PlanetType::Sun | PlanetType::Ship => color,
+        _ if uniforms.craters.is_empty() => color,
+        _ => crater_shading(color, fragment.object_position, &uniforms.craters, time),
+    };
+
+    // Relieve: las laderas del terreno de cerca se iluminan según su inclinación respecto al sol
+    let color = match planet_type {
+        PlanetType::Rocky => {
+            let relief = relief_shading(fragment, uniforms);
+            Vector3::new(color.x * relief, color.y * relief, color.z * relief)
+        }
+        _ => color,
+    };
+
+    // Atmósfera: atardeceres naranjas en el terminador y borde azul (naranja cerca del terminador)
+    let color = match (&uniforms.atmosphere, planet_type) {
+        (Some(atmosphere), PlanetType::Rocky | PlanetType::GasGiant | PlanetType::SciFi | PlanetType::Ice | PlanetType::Volcanic | PlanetType::Moon) => {
+            atmosphere_scattering(color, fragment, uniforms, atmosphere)
+        }
+        _ => color,
+    };
+
+    // Sombras mutuas planeta/anillos (solo si el cuerpo tiene anillos)
+    let shadow = match (&uniforms.rings, planet_type) {
+        (Some(rings), PlanetType::Ring) => planet_shadow_on_ring(fragment.object_position, rings, uniforms.sun_direction),
+        (Some(rings), PlanetType::GasGiant) => {
+            // La esfera es unitaria en espacio de objeto; escalar al radio real del planeta
+            let surface = Vector3::new(
+                fragment.object_position.x * rings.planet_radius,
+                fragment.object_position.y * rings.planet_radius,
+                fragment.object_position.z * rings.planet_radius,
+            );
+            ring_shadow_on_planet(surface, rings, uniforms.sun_direction)
+        },
+        _ => 1.0,
+    };
+
+    // Sumar el brillo especular (Blinn-Phong) calculado en el rasterizador
+    Vector3::new(
+        (color.x + fragment.specular.x) * shadow,
+        (color.y + fragment.specular.y) * shadow,
+        (color.z + fragment.specular.z) * shadow,
+    )
+}
+
+/// Factor de iluminación de las laderas: compara la normal de la malla con la de la esfera lisa
+/// frente al sol. Sobre la esfera de siempre las dos coinciden y el factor es 1; sobre el relieve
+/// de `terrain` las laderas que miran al sol se aclaran y las opuestas se oscurecen
+fn relief_shading(fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+    let sun = transform_normal(&uniforms.model_matrix, uniforms.sun_direction);
+    let sphere = transform_normal(&uniforms.model_matrix, fragment.object_position);
+    let n = fragment.normal;
+    let sphere_light = sphere.x * sun.x + sphere.y * sun.y + sphere.z * sun.z;
+    let terrain_light = n.x * sun.x + n.y * sun.y + n.z * sun.z;
+    // En la cara de noche el shader ya oscurece todo y la diferencia no se vería
+    if sphere_light <= 0.0 {
+        return 1.0;
+    }
+    (1.0 + (terrain_light - sphere_light) * 1.5).clamp(0.35, 1.6)
+}
+
+/// Oscurece el fondo de los cráteres, aclara el borde de eyecta y hace brillar los impactos
+/// recientes mientras se enfrían (unos segundos); `object_position` en la esfera unitaria
+pub fn crater_shading(color: Vector3, object_position: Vector3, craters: &[Crater], time: f32) -> Vector3 {
+    let p = object_position;
+    let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt().max(0.0001);
+    let n = Vector3::new(p.x / length, p.y / length, p.z / length);
+
+    let mut shade = 1.0;
+    let mut heat = 0.0f32;
+    for crater in craters {
+        let c = crater.center;
+        let angle = (n.x * c.x + n.y * c.y + n.z * c.z).clamp(-1.0, 1.0).acos();
+        let t = angle / crater.radius.max(0.0001);
+        if t >= 1.5 {
+            continue;
+        }
+        if t < 1.0 {
+            // Cuenco: más oscuro en el centro
+            shade *= 0.45 + 0.55 * t * t;
+            let age = time - crater.time;
+            if age >= 0.0 {
+                heat = heat.max((-age / 6.0).exp() * (1.0 - t));
+            }
+        } else {
+            // Borde elevado y eyecta: se aclara y se desvanece hacia fuera
+            shade *= 1.0 + 0.25 * (1.0 - (t - 1.0) / 0.5);
+        }
+    }
+
+    Vector3::new(
+        color.x * shade + heat * 1.6,
+        color.y * shade + heat * 0.5,
+        color.z * shade + heat * 0.08,
+    )
+}
+
+/// Aproximación de dispersión simple de la atmósfera
+/// La luz del sol que llega a cada punto atraviesa más aire cuanto más rasante es (grosor óptico
+/// ~ 1 / altura del sol) y pierde cada canal según `rayleigh`: el azul primero, así que la
+/// superficie se tiñe de naranja junto al terminador. El borde del disco, donde la mirada cruza
+/// mucha atmósfera, suma el cielo dispersado: azul de día y del color del sol filtrado (naranja)
+/// cerca del terminador; en el lado nocturno no hay nada que dispersar
+pub fn atmosphere_scattering(color: Vector3, fragment: &Fragment, uniforms: &Uniforms, atmosphere: &Atmosphere) -> Vector3 {
+    let p = fragment.object_position;
+    let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt().max(0.0001);
+    let s = uniforms.sun_direction;
+    let sun_height = (p.x * s.x + p.y * s.y + p.z * s.z) / length;
+
+    // Transmitancia relativa a la del sol en el cénit (1 a mediodía)
+    let depth = atmosphere.density * 0.5 * (1.0 / (sun_height.max(0.0) + 0.1) - 1.0 / 1.1);
+    let beta = atmosphere.rayleigh;
+    let transmittance = Vector3::new((-beta.x * depth).exp(), (-beta.y * depth).exp(), (-beta.z * depth).exp());
+
+    // Borde: cuánto aire cruza la mirada (normal y vista en espacio de mundo)
+    let n = fragment.normal;
+    let n_length = (n.x * n.x + n.y * n.y + n.z * n.z).sqrt().max(0.0001);
+    let view = Vector3::new(
+        uniforms.camera_position.x - fragment.world_position.x,
+        uniforms.camera_position.y - fragment.world_position.y,
+        uniforms.camera_position.z - fragment.world_position.z,
+    );
+    let view_length = (view.x * view.x + view.y * view.y + view.z * view.z).sqrt().max(0.0001);
+    let facing = ((n.x * view.x + n.y * view.y + n.z * view.z) / (n_length * view_length)).clamp(0.0, 1.0);
+    let rim = (1.0 - facing).powi(3);
+    let day = ((sun_height + 0.25) / 0.5).clamp(0.0, 1.0);
+    let day = day * day * (3.0 - 2.0 * day);
+
+    // Color del cielo: el dispersado (azul) de día, la luz filtrada (naranja) al atardecer
+    let normalize = |c: Vector3| {
+        let max = c.x.max(c.y).max(c.z).max(0.0001);
+        Vector3::new(c.x / max, c.y / max, c.z / max)
+    };
+    let sky = normalize(Vector3::new(beta.x * transmittance.x, beta.y * transmittance.y, beta.z * transmittance.z));
+    let sunset = normalize(transmittance);
+    let w = 1.0 - (sun_height * 3.0).clamp(0.0, 1.0);
+    let strength = rim * day * (atmosphere.density * 2.5).min(1.0) * 0.6;
+
+    Vector3::new(
+        color.x * transmittance.x + (sky.x + (sunset.x - sky.x) * w) * strength,
+        color.y * transmittance.y + (sky.y + (sunset.y - sky.y) * w) * strength,
+        color.z * transmittance.z + (sky.z + (sunset.z - sky.z) * w) * strength,
+    )
+}
+
+/// Luces de las ciudades en la cara nocturna: racimos de puntos cálidos sobre los continentes
+/// (ruido de baja frecuencia) que se encienden al pasar el terminador
+pub fn city_lights(object_pos: Vector3, sun_direction: Vector3, brightness: f32) -> Vector3 {
+    let length = (object_pos.x * object_pos.x + object_pos.y * object_pos.y + object_pos.z * object_pos.z).sqrt().max(0.0001);
+    let n = Vector3::new(object_pos.x / length, object_pos.y / length, object_pos.z / length);
+    let sun_height = n.x * sun_direction.x + n.y * sun_direction.y + n.z * sun_direction.z;
+    let night = ((-sun_height - 0.02) / 0.15).clamp(0.0, 1.0);
+    if night <= 0.0 || n.y.abs() > 0.8 {
+        return Vector3::zero();
+    }
+
+    let land = ((fractal_noise(Vector3::new(n.x * 2.0, n.y * 2.0, n.z * 2.0), 0.0, 2) - 0.45) / 0.1).clamp(0.0, 1.0);
+    let clusters = ((fractal_noise(Vector3::new(n.x * 9.0, n.y * 9.0, n.z * 9.0), 0.0, 2) - 0.5) / 0.2).clamp(0.0, 1.0);
+    let sparkle = ((noise_3d(Vector3::new(n.x * 60.0, n.y * 60.0, n.z * 60.0), 0.0) - 0.4) / 0.3).clamp(0.0, 1.0);
+    let glow = land * clusters * sparkle * night * brightness;
+    Vector3::new(1.0 * glow, 0.72 * glow, 0.38 * glow)
+}
+
+/// Opacidad del fragmento según el tipo de cuerpo (1.0 = opaco)
+pub fn fragment_alpha_planet(fragment: &Fragment, uniforms: &Uniforms, planet_type: PlanetType) -> f32 {
+    match (planet_type, &uniforms.rings) {
+        (PlanetType::Ring, Some(rings)) => {
+            // De cerca el disco se desvanece donde lo sustituyen las rocas
+            let p = fragment.world_position;
+            let c = rings.near_center;
+            let distance = ((p.x - c.x).powi(2) + (p.y - c.y).powi(2) + (p.z - c.z).powi(2)).sqrt();
+            let near = rings.near_blend * ring_near_fade(distance, rings.near_radius);
+            ring_alpha(fragment, uniforms.time, rings) * (1.0 - near)
+        }
+        _ => 1.0,
+    }
+}
+
 /// Planeta 4: Planeta Helado (MÚLTIPLES CAPAS)
 /// CAPA 1: Superficie de hielo con fracturas
 /// CAPA 2: Capas de nieve con gradientes de profundidad
 /// CAPA 3: Iluminación simulada con reflexión de hielo
 /// CAPA 4: Efectos de cristales y escarcha
-pub fn shader_ice_planet(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+pub fn shader_ice_planet(fragment: &Fragment, time: f32, sun_direction: Vector3) -> Vector3 {
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
-    let (r, theta, phi) = spherical_coords(world_pos);
+    let (r, theta, phi) = spherical_coords(object_pos);
     
     // === CAPA 1: Superficie de hielo con fracturas ===
-    let ice_fracture = fractal_noise(world_pos, time * 0.05, 5);
+    let ice_fracture = fractal_noise(object_pos, time * 0.05, 5);
     let crack_pattern = (phi * 12.0 + theta * 8.0 + time * 0.2).sin() * 0.3 + 0.7;
     
     // === CAPA 2: Capas de nieve con gradientes de profundidad ===
     let snow_depth = (theta * 3.0).sin() * 0.5 + 0.5;
-    let snow_layers = fractal_noise(Vector3::new(world_pos.x * 2.0, world_pos.y * 2.0, world_pos.z * 2.0), time * 0.03, 3);
+    let snow_layers = fractal_noise(Vector3::new(object_pos.x * 2.0, object_pos.y * 2.0, object_pos.z * 2.0), time * 0.03, 3);
     let snow_gradient = snow_depth * 0.6 + snow_layers * 0.4;
     
     // === CAPA 3: Iluminación simulada con reflexión de hielo ===
-    let ice_dir = Vector3::new(0.8, 0.4, 0.2);
-    let ice_dir_len = (ice_dir.x * ice_dir.x + ice_dir.y * ice_dir.y + ice_dir.z * ice_dir.z).sqrt().max(0.0001);
-    let ice_direction = Vector3::new(ice_dir.x / ice_dir_len, ice_dir.y / ice_dir_len, ice_dir.z / ice_dir_len);
-    let normal = Vector3::new(world_pos.x / r, world_pos.y / r, world_pos.z / r);
+    let ice_direction = sun_direction;
+    let normal = Vector3::new(object_pos.x / r, object_pos.y / r, object_pos.z / r);
     let ice_reflection = (normal.x * ice_direction.x + normal.y * ice_direction.y + normal.z * ice_direction.z).max(0.0);
     let ice_shine = ice_reflection * 1.5 + 0.5; // Brillo de hielo
     
@@ -1040,10 +1693,16 @@ pub fn shader_ice_planet(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
+    // Oclusión ambiental en el fondo de las fracturas del hielo
+    let occlusion = height_occlusion(ice_fracture, 0.35);
+
     let final_color = Vector3::new(
-        planet_color.x * ice_shine * crystal_glow * frost_effect * crack_pattern,
-        planet_color.y * ice_shine * crystal_glow * frost_effect * crack_pattern,
-        planet_color.z * ice_shine * crystal_glow * frost_effect * crack_pattern,
+        planet_color.x * ice_shine * crystal_glow * frost_effect * crack_pattern * occlusion,
+        planet_color.y * ice_shine * crystal_glow * frost_effect * crack_pattern * occlusion,
+        planet_color.z * ice_shine * crystal_glow * frost_effect * crack_pattern * occlusion,
     );
     
     Vector3::new(
@@ -1059,7 +1718,7 @@ pub fn shader_ship(fragment: &Fragment, _time: f32) -> Vector3 {
     let base_color = fragment.color;
     
     // Color gris metálico más brillante para mejor visibilidad
-    let ship_gray = Vector3::new(0.7, 0.7, 0.75); // Gris metálico más claro
+    let ship_gray = srgb_to_linear_rgb(Vector3::new(0.7, 0.7, 0.75)); // Gris metálico más claro (sRGB -> lineal)
     
     // Aplicar iluminación con un mínimo de brillo para asegurar visibilidad
     let min_brightness = 0.3; // Brillo mínimo para que siempre sea visible
@@ -1080,25 +1739,20 @@ pub fn shader_ship(fragment: &Fragment, _time: f32) -> Vector3 {
 /// CAPA 3: Iluminación simulada de lava incandescente
 /// CAPA 4: Efectos de humo y ceniza
 pub fn shader_volcanic_planet(fragment: &Fragment, time: f32) -> Vector3 {
-    let world_pos = fragment.world_position;
+    let object_pos = fragment.object_position;
     let base_color = fragment.color;
     
-    let (_r, theta, phi) = spherical_coords(world_pos);
+    let (_r, theta, _phi) = spherical_coords(object_pos);
     
-    // === CAPA 1: Superficie de lava y roca fundida ===
-    let lava_noise = fractal_noise(world_pos, time * 0.2, 4);
-    
-    // === CAPA 2: Flujos de lava animados ===
-    let lava_flow1 = (theta * 8.0 + phi * 6.0 + time * 0.8).sin() * 0.5 + 0.5;
-    let lava_flow2 = (theta * 12.0 - phi * 4.0 + time * 1.0).cos() * 0.3 + 0.7;
-    let lava_flow = lava_flow1 * lava_flow2;
+    // === CAPAS 1 y 2: Superficie de lava y roca fundida con flujos animados ===
+    let color_factor = lava_factor(object_pos, time);
     
     // === CAPA 3: Iluminación simulada de lava incandescente ===
     let lava_glow = (time * 3.0 + theta * 5.0).sin() * 0.3 + 0.7;
     let incandescent = lava_glow * 1.5 + 0.5;
     
     // === CAPA 4: Efectos de humo y ceniza ===
-    let smoke_pattern = fractal_noise(Vector3::new(world_pos.x * 1.5, world_pos.y * 2.0, world_pos.z * 1.5), time * 0.15, 2);
+    let smoke_pattern = fractal_noise(Vector3::new(object_pos.x * 1.5, object_pos.y * 2.0, object_pos.z * 1.5), time * 0.15, 2);
     let ash_layer = (theta * 4.0 + time * 0.5).sin() * 0.2 + 0.8;
     
     // Colores volcánicos
@@ -1107,7 +1761,6 @@ pub fn shader_volcanic_planet(fragment: &Fragment, time: f32) -> Vector3 {
     let lava_color4 = Vector3::new(0.4, 0.15, 0.1); // Marrón rojizo
     let lava_color5 = Vector3::new(0.8, 0.4, 0.2); // Naranja oscuro
     
-    let color_factor = lava_noise * 0.5 + lava_flow * 0.5;
     let is_lava = if color_factor > 0.6 { 1.0 } else { 0.3 };
     
     let planet_color = if color_factor < 0.3 {
@@ -1130,6 +1783,9 @@ pub fn shader_volcanic_planet(fragment: &Fragment, time: f32) -> Vector3 {
         )
     };
     
+    // Paleta definida en sRGB: pasar a espacio lineal antes de iluminar
+    let planet_color = srgb_to_linear_rgb(planet_color);
+
     let final_color = Vector3::new(
         planet_color.x * incandescent * is_lava * (1.0 - smoke_pattern * 0.3) * ash_layer,
         planet_color.y * incandescent * is_lava * (1.0 - smoke_pattern * 0.3) * ash_layer,
@@ -1155,4 +1811,543 @@ pub enum PlanetType {
     Moon,       // Para luna (usa shader especial)
     Sun,        // Para el sol (shader especial avanzado)
     Ship,       // Para la nave espacial (shader gris eficiente)
-}
\ No newline at end of file
+}
+
+impl PlanetType {
+    /// Tipo a partir de su nombre en el archivo de escena (`rocky`, `gas_giant`, ...)
+    pub fn from_name(name: &str) -> Option<PlanetType> {
+        match name {
+            "rocky" => Some(PlanetType::Rocky),
+            "gas_giant" => Some(PlanetType::GasGiant),
+            "scifi" => Some(PlanetType::SciFi),
+            "ice" => Some(PlanetType::Ice),
+            "volcanic" => Some(PlanetType::Volcanic),
+            "moon" => Some(PlanetType::Moon),
+            _ => None,
+        }
+    }
+
+    /// Color medio (lineal) de la luz que refleja cada tipo de cuerpo, para la luz de relleno
+    /// que un planeta devuelve sobre sus lunas
+    pub fn albedo(&self) -> Vector3 {
+        match self {
+            PlanetType::Rocky => Vector3::new(0.45, 0.42, 0.4),
+            PlanetType::GasGiant => Vector3::new(0.85, 0.7, 0.5),
+            PlanetType::SciFi => Vector3::new(0.4, 0.55, 0.75),
+            PlanetType::Ice => Vector3::new(0.6, 0.8, 1.0),
+            PlanetType::Volcanic => Vector3::new(0.55, 0.25, 0.12),
+            _ => Vector3::new(0.5, 0.5, 0.5),
+        }
+    }
+
+    /// Material (propiedades especulares) asociado a cada tipo de cuerpo
+    pub fn material(&self) -> Material {
+        let base = Vector3::new(0.5, 0.5, 0.5);
+        match self {
+            PlanetType::Rocky => Material::new(base, Vector3::new(0.05, 0.05, 0.05), 4.0),
+            PlanetType::GasGiant => Material::new(base, Vector3::new(0.1, 0.09, 0.07), 8.0),
+            PlanetType::SciFi => Material::new(base, Vector3::new(0.3, 0.4, 0.5), 24.0),
+            PlanetType::Ice => Material::new(base, Vector3::new(0.8, 0.9, 1.0), 64.0), // Hielo muy reflectivo
+            PlanetType::Volcanic => Material::new(base, Vector3::new(0.15, 0.1, 0.08), 16.0),
+            PlanetType::Ring => Material::new(base, Vector3::new(0.05, 0.05, 0.05), 4.0),
+            PlanetType::Moon => Material::matte(base),
+            PlanetType::Sun => Material::matte(base), // El sol es emisivo, sin especular
+            PlanetType::Ship => Material::new(base, Vector3::new(0.7, 0.7, 0.75), 32.0), // Metal
+        }
+    }
+}
+
+/// Shader de superficie asignable a cualquier objeto renderizable
+/// Permite registrar fragment shaders propios sin tocar PlanetType ni el dispatcher
+pub trait SurfaceShader: Send + Sync {
+    /// Material (propiedades especulares) usado durante la rasterización
+    fn material(&self) -> Material;
+
+    /// Color final del fragmento; `fragment.specular` trae el brillo especular ya calculado
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3;
+
+    /// Cobertura del fragmento: 1.0 opaco, 0.0 descartado, intermedio se mezcla
+    fn alpha(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> f32 {
+        1.0
+    }
+
+    /// Luz propia del fragmento (lineal): el renderer la suma a `shade` sin pasar por la
+    /// iluminación, junto con la emisión del material, y la usa para el bloom
+    fn emission(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Vector3 {
+        Vector3::zero()
+    }
+
+    /// Tipo de planeta cuyo color base (`planet_surface_color`) puede calcular la GPU en lugar
+    /// de `shade` (ver `GpuShading`); None, lo normal, sombrea siempre en la CPU
+    fn gpu_surface(&self) -> Option<PlanetType> {
+        None
+    }
+
+    /// Color final a partir del color base que devolvió la GPU para `gpu_surface`: tiene que
+    /// dar lo mismo que `shade` con ese color base
+    fn finish_gpu(&self, base: Vector3, _fragment: &Fragment, _uniforms: &Uniforms) -> Vector3 {
+        base
+    }
+}
+
+impl SurfaceShader for PlanetType {
+    fn material(&self) -> Material {
+        PlanetType::material(self)
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        fragment_shader_planet(fragment, uniforms, *self)
+    }
+
+    fn alpha(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        fragment_alpha_planet(fragment, uniforms, *self)
+    }
+
+    fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        planet_emission(fragment, uniforms, *self)
+    }
+
+    fn gpu_surface(&self) -> Option<PlanetType> {
+        Some(*self)
+    }
+
+    fn finish_gpu(&self, base: Vector3, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        finish_planet_color(base, fragment, uniforms, *self)
+    }
+}
+
+/// Shader de superficie a partir de una función o closure
+/// La forma más simple de registrar un shader propio:
+/// `FnShader::new(Material::default(), |fragment, uniforms| ...)`
+pub struct FnShader<F> {
+    pub material: Material,
+    pub shade_fn: F,
+}
+
+impl<F> FnShader<F>
+where
+    F: Fn(&Fragment, &Uniforms) -> Vector3 + Send + Sync,
+{
+    pub fn new(material: Material, shade_fn: F) -> Self {
+        FnShader { material, shade_fn }
+    }
+}
+
+impl<F> SurfaceShader for FnShader<F>
+where
+    F: Fn(&Fragment, &Uniforms) -> Vector3 + Send + Sync,
+{
+    fn material(&self) -> Material {
+        self.material
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        (self.shade_fn)(fragment, uniforms)
+    }
+}
+
+/// Shader de un tipo de planeta con la paleta teñida: multiplica el color final por `tint`
+/// Permite dar tonos propios (Marte rojizo, Neptuno azul) sin escribir un shader nuevo
+pub struct TintShader {
+    pub base: PlanetType,
+    pub tint: Vector3, // Factor lineal por canal (1.0 = sin cambio)
+}
+
+impl TintShader {
+    pub fn new(base: PlanetType, tint: Vector3) -> Self {
+        TintShader { base, tint }
+    }
+}
+
+impl SurfaceShader for TintShader {
+    fn material(&self) -> Material {
+        self.base.material()
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let color = fragment_shader_planet(fragment, uniforms, self.base);
+        Vector3::new(color.x * self.tint.x, color.y * self.tint.y, color.z * self.tint.z)
+    }
+
+    fn alpha(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        fragment_alpha_planet(fragment, uniforms, self.base)
+    }
+
+    fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let emission = planet_emission(fragment, uniforms, self.base);
+        Vector3::new(emission.x * self.tint.x, emission.y * self.tint.y, emission.z * self.tint.z)
+    }
+
+    fn gpu_surface(&self) -> Option<PlanetType> {
+        Some(self.base)
+    }
+
+    fn finish_gpu(&self, base: Vector3, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let color = finish_planet_color(base, fragment, uniforms, self.base);
+        Vector3::new(color.x * self.tint.x, color.y * self.tint.y, color.z * self.tint.z)
+    }
+}
+
+/// Shader del frente de plasma de una eyección de masa coronal: emisivo y translúcido, con
+/// filamentos turbulentos que se desvanecen hacia el borde del cono
+/// Trabaja sobre el casquete unitario de `Obj::generate_spherical_cap` (eje +Y)
+pub struct CmeShader {
+    pub color: Vector3,   // Color lineal del plasma
+    pub half_angle: f32,  // Semiapertura del casquete (radianes)
+    pub intensity: f32,   // Opacidad global (0 a 1, la eyección se apaga al expandirse)
+}
+
+impl CmeShader {
+    pub fn new(color: Vector3, half_angle: f32, intensity: f32) -> Self {
+        CmeShader { color, half_angle, intensity }
+    }
+
+    /// Filamentos del plasma en [0, 1]
+    fn turbulence(&self, fragment: &Fragment, time: f32) -> f32 {
+        let pos = fragment.object_position;
+        fractal_noise(Vector3::new(pos.x * 6.0, pos.y * 6.0, pos.z * 6.0), time * 0.8, 3)
+    }
+}
+
+impl SurfaceShader for CmeShader {
+    fn material(&self) -> Material {
+        Material::matte(self.color)
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let glow = 1.2 + self.turbulence(fragment, uniforms.time) * 1.5;
+        Vector3::new(self.color.x * glow, self.color.y * glow, self.color.z * glow)
+    }
+
+    fn alpha(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        let pos = fragment.object_position;
+        let length = (pos.x * pos.x + pos.y * pos.y + pos.z * pos.z).sqrt().max(0.0001);
+        let angle = (pos.y / length).clamp(-1.0, 1.0).acos();
+        let rim = (1.0 - angle / self.half_angle.max(0.0001)).clamp(0.0, 1.0);
+        let filaments = 0.3 + 0.7 * self.turbulence(fragment, uniforms.time);
+        (self.intensity * rim.sqrt() * filaments * 0.6).clamp(0.0, 0.95)
+    }
+}
+
+/// Shader de la aurora de un planeta: cortinas emisivas y translúcidas en un óvalo alrededor de
+/// cada polo, con pliegues verticales que ondulan y derivan en longitud
+/// Trabaja sobre una esfera unitaria algo mayor que el planeta, inclinada con su eje pero sin su
+/// giro diario (el óvalo queda fijo respecto al sol, como en la Tierra)
+/// `storm` (0 a 1) es la fuerza de la tormenta geomagnética: la aurora se aviva, baja hacia el
+/// ecuador y su parte alta pasa del verde al rojo y violeta
+pub struct AuroraShader {
+    pub color: Vector3,      // Color lineal de la base de las cortinas
+    pub top_color: Vector3,  // Color lineal del borde de las cortinas
+    pub intensity: f32,      // Brillo global
+    pub latitude: f32,       // Latitud del centro del óvalo (radianes)
+    pub width: f32,          // Semiancho del óvalo en latitud (radianes)
+}
+
+impl AuroraShader {
+    /// Aurora en calma (`storm` = 0) o durante una tormenta solar de fuerza `storm`
+    pub fn new(storm: f32) -> Self {
+        let storm = storm.clamp(0.0, 1.0);
+        AuroraShader {
+            color: Vector3::new(0.1, 1.0, 0.35),
+            top_color: Vector3::new(0.3 + 0.7 * storm, 0.5 - 0.4 * storm, 0.6 + 0.2 * storm),
+            intensity: 0.35 + 1.15 * storm,
+            latitude: 1.2 - 0.25 * storm, // ~69° en calma, ~54° en una tormenta fuerte
+            width: 0.1 + 0.08 * storm,
+        }
+    }
+
+    /// Cobertura de las cortinas en [0, 1] y posición dentro del óvalo (0 en el centro, 1 en el borde)
+    fn curtain(&self, fragment: &Fragment, time: f32) -> (f32, f32) {
+        let (_r, theta, phi) = spherical_coords(fragment.object_position);
+        let band = ((theta.abs() - self.latitude) / self.width.max(0.0001)).abs();
+        if band >= 1.0 {
+            return (0.0, 1.0);
+        }
+        let mask = 1.0 - band * band;
+
+        // Pliegues finos que ondulan con un ruido lento y derivan en longitud
+        let pos = fragment.object_position;
+        let warp = fractal_noise(Vector3::new(pos.x * 3.0, pos.y * 3.0, pos.z * 3.0), time * 0.3, 2);
+        let folds = ((phi * 24.0 + warp * 8.0 + time * 0.4).sin() * 0.5 + 0.5).powi(3);
+        let ribbons = (phi * 5.0 - time * 0.15 + warp * 3.0).sin() * 0.5 + 0.5;
+        ((mask * (0.25 + 0.75 * folds) * (0.3 + 0.7 * ribbons)).clamp(0.0, 1.0), band)
+    }
+
+    /// Visibilidad según el lado: en el lado diurno la luz del sol la eclipsa casi por completo
+    fn night_factor(fragment: &Fragment, sun_direction: Vector3) -> f32 {
+        let pos = fragment.object_position;
+        let length = (pos.x * pos.x + pos.y * pos.y + pos.z * pos.z).sqrt().max(0.0001);
+        let sun_height = (pos.x * sun_direction.x + pos.y * sun_direction.y + pos.z * sun_direction.z) / length;
+        0.3 + 0.7 * (-sun_height * 2.0 + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+impl SurfaceShader for AuroraShader {
+    fn material(&self) -> Material {
+        Material::matte(self.color)
+    }
+
+    /// Solo luz propia: todo el color sale de `emission`
+    fn shade(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Vector3 {
+        Vector3::zero()
+    }
+
+    fn alpha(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        let (coverage, _band) = self.curtain(fragment, uniforms.time);
+        (coverage * self.intensity * Self::night_factor(fragment, uniforms.sun_direction)).clamp(0.0, 0.9)
+    }
+
+    fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let (coverage, band) = self.curtain(fragment, uniforms.time);
+        if coverage <= 0.0 {
+            return Vector3::zero();
+        }
+        // Verde en el centro del óvalo, el color del borde hacia fuera
+        let t = band * band;
+        let glow = 1.5 + self.intensity;
+        Vector3::new(
+            (self.color.x + (self.top_color.x - self.color.x) * t) * glow,
+            (self.color.y + (self.top_color.y - self.color.y) * t) * glow,
+            (self.color.z + (self.top_color.z - self.color.z) * t) * glow,
+        )
+    }
+}
+
+/// Shader del disco de acreción de un agujero negro: emisivo y translúcido, más caliente hacia el
+/// borde interno (T ∝ r^-3/4, disco fino) y con efecto Doppler relativista: el lado que se acerca a
+/// la cámara se ve más brillante y azulado, el que se aleja más tenue y rojizo
+/// Trabaja sobre la malla de `Obj::generate_rings` en unidades del mundo (disco en el plano XZ local)
+pub struct AccretionDiskShader {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub temperature: f32,     // Temperatura del borde interno (K)
+    pub spin_speed: f32,      // Velocidad angular del borde interno (rad/s), antihoraria vista desde +Y
+    pub inner_velocity: f32,  // Velocidad orbital del borde interno como fracción de la de la luz
+}
+
+impl AccretionDiskShader {
+    pub fn new(inner_radius: f32, outer_radius: f32, temperature: f32, spin_speed: f32) -> Self {
+        AccretionDiskShader {
+            inner_radius,
+            outer_radius,
+            temperature,
+            spin_speed,
+            inner_velocity: 0.5,
+        }
+    }
+
+    /// Distancia al centro en el plano del disco (nunca menor que el borde interno)
+    fn radius(&self, pos: Vector3) -> f32 {
+        (pos.x * pos.x + pos.z * pos.z).sqrt().max(self.inner_radius).max(0.0001)
+    }
+
+    /// Factor Doppler relativista D = 1 / (γ (1 - β cos θ)), con θ el ángulo entre la velocidad
+    /// orbital del plasma y la dirección hacia la cámara (D > 1 en el lado que se acerca)
+    fn doppler(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        let pos = fragment.object_position;
+        let r = self.radius(pos);
+        // Tangente de la órbita kepleriana en espacio de objeto, llevada al mundo
+        let spin = if self.spin_speed < 0.0 { -1.0 } else { 1.0 };
+        let tangent = transform_normal(&uniforms.model_matrix, Vector3::new(-pos.z / r * spin, 0.0, pos.x / r * spin));
+
+        let camera = uniforms.camera_position;
+        let world = fragment.world_position;
+        let to_camera = Vector3::new(camera.x - world.x, camera.y - world.y, camera.z - world.z);
+        let distance = (to_camera.x * to_camera.x + to_camera.y * to_camera.y + to_camera.z * to_camera.z).sqrt().max(0.0001);
+        let cos_theta = (tangent.x * to_camera.x + tangent.y * to_camera.y + tangent.z * to_camera.z) / distance;
+
+        let beta = (self.inner_velocity * (self.inner_radius / r).sqrt()).clamp(0.0, 0.95);
+        let gamma = 1.0 / (1.0 - beta * beta).sqrt();
+        1.0 / (gamma * (1.0 - beta * cos_theta))
+    }
+
+    /// Grumos de plasma en [0, 1] arrastrados por la rotación diferencial (más rápida por dentro)
+    fn turbulence(&self, fragment: &Fragment, time: f32) -> f32 {
+        let pos = fragment.object_position;
+        let r = self.radius(pos);
+        let angular_speed = self.spin_speed * (self.inner_radius / r).powf(1.5);
+        let angle = pos.z.atan2(pos.x) - time * angular_speed;
+        // El ángulo entra por su seno y coseno para que no haya costura en ±PI
+        fractal_noise(Vector3::new(angle.cos() * 4.0, r * 1.2, angle.sin() * 4.0), time * 0.2, 3)
+    }
+}
+
+impl SurfaceShader for AccretionDiskShader {
+    fn material(&self) -> Material {
+        Material::matte(Vector3::new(1.0, 0.8, 0.5))
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let r = self.radius(fragment.object_position);
+        let doppler = self.doppler(fragment, uniforms);
+
+        // El desplazamiento Doppler cambia la temperatura aparente y el realce relativista (D^3)
+        // el brillo: un lado del disco domina claramente sobre el otro
+        let temperature = self.temperature * (self.inner_radius / r).powf(0.75) * doppler;
+        let color = srgb_to_linear_rgb(blackbody_srgb(temperature));
+        let beaming = doppler.powi(3).clamp(0.05, 6.0);
+        let glow = beaming * (0.6 + 0.8 * self.turbulence(fragment, uniforms.time)) * 1.5;
+        Vector3::new(color.x * glow, color.y * glow, color.z * glow)
+    }
+
+    fn alpha(&self, fragment: &Fragment, uniforms: &Uniforms) -> f32 {
+        let r = self.radius(fragment.object_position);
+        let t = ((r - self.inner_radius) / (self.outer_radius - self.inner_radius).max(0.0001)).clamp(0.0, 1.0);
+        // Borde interno nítido (última órbita estable) y borde externo difuso
+        let edge = (t * 12.0).min(1.0) * (1.0 - t).powf(1.5);
+        let clumps = 0.5 + 0.5 * self.turbulence(fragment, uniforms.time);
+        (edge * clumps * 1.4).clamp(0.0, 0.95)
+    }
+}
+
+/// Shader de la estación espacial: casco metálico iluminado por el sol, luces de navegación
+/// que parpadean en el borde exterior del anillo habitable y una baliza sobre el puerto de atraque
+/// Trabaja en espacio de objeto de la estación (anillo de radio 1 en el plano XZ)
+pub struct StationShader {
+    pub light_count: usize,      // Luces repartidas por el borde exterior del anillo
+    pub blink_speed: f32,        // Parpadeos por segundo
+    pub light_color: Vector3,    // Color lineal de las luces del anillo
+    pub beacon_color: Vector3,   // Color lineal de la baliza de atraque
+}
+
+impl StationShader {
+    /// Radio de cada luz en espacio de objeto
+    const LIGHT_RADIUS: f32 = 0.05;
+    /// Centro de la baliza: el puerto de atraque
+    const BEACON: Vector3 = Station::DOCKING_PORT;
+
+    pub fn new() -> Self {
+        StationShader {
+            light_count: 12,
+            blink_speed: 0.8,
+            light_color: Vector3::new(1.0, 0.25, 0.15),
+            beacon_color: Vector3::new(0.3, 1.0, 0.4),
+        }
+    }
+
+    /// Intensidad emisiva de las luces del anillo en `pos` (0 si el punto no está en una luz)
+    /// Las luces se encienden en secuencia alrededor del anillo
+    fn ring_light(&self, pos: Vector3, time: f32) -> f32 {
+        let count = self.light_count.max(1);
+        let angle = pos.z.atan2(pos.x).rem_euclid(2.0 * PI);
+        let step = 2.0 * PI / count as f32;
+        let index = (angle / step + 0.5).floor();
+        let light_angle = index * step;
+        // Luz sobre el ecuador exterior del toro
+        let radius = Station::OUTER_RADIUS;
+        let light = Vector3::new(light_angle.cos() * radius, 0.0, light_angle.sin() * radius);
+        let dx = pos.x - light.x;
+        let dy = pos.y - light.y;
+        let dz = pos.z - light.z;
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+        if distance > Self::LIGHT_RADIUS {
+            return 0.0;
+        }
+
+        let phase = time * self.blink_speed - index / count as f32;
+        let blink = if phase.rem_euclid(1.0) < 0.25 { 1.0 } else { 0.0 };
+        blink * (1.0 - distance / Self::LIGHT_RADIUS)
+    }
+
+    /// Intensidad emisiva de la baliza de atraque: pulso suave, siempre algo encendida
+    fn beacon(&self, pos: Vector3, time: f32) -> f32 {
+        let dx = pos.x - Self::BEACON.x;
+        let dy = pos.y - Self::BEACON.y;
+        let dz = pos.z - Self::BEACON.z;
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+        if distance > Self::LIGHT_RADIUS * 1.5 {
+            return 0.0;
+        }
+        let pulse = (time * 3.0).sin() * 0.35 + 0.65;
+        pulse * (1.0 - distance / (Self::LIGHT_RADIUS * 1.5))
+    }
+}
+
+impl Default for StationShader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SurfaceShader for StationShader {
+    fn material(&self) -> Material {
+        Material::new(Vector3::new(0.5, 0.5, 0.5), Vector3::new(0.8, 0.8, 0.85), 48.0)
+    }
+
+    fn shade(&self, fragment: &Fragment, _uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.object_position;
+        let base_color = fragment.color;
+
+        // Paneles del casco: bandas alrededor del eje Y con un tono ligeramente distinto
+        let angle = pos.z.atan2(pos.x);
+        let panel = if ((angle * 24.0 / PI).floor() as i32).rem_euclid(2) == 0 { 1.0 } else { 0.88 };
+        let hull = srgb_to_linear_rgb(Vector3::new(0.72 * panel, 0.74 * panel, 0.78 * panel));
+
+        // Iluminación con un mínimo de brillo para que la estación se vea en la cara oscura
+        let brightness = base_color.x.max(base_color.y).max(base_color.z).max(0.15);
+        Vector3::new(
+            hull.x * brightness + fragment.specular.x,
+            hull.y * brightness + fragment.specular.y,
+            hull.z * brightness + fragment.specular.z,
+        )
+    }
+
+    /// Luces de navegación y baliza: emisivas, se ven igual en la cara oscura
+    fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let pos = fragment.object_position;
+        let ring = self.ring_light(pos, uniforms.time);
+        let beacon = self.beacon(pos, uniforms.time);
+        Vector3::new(
+            self.light_color.x * ring * 2.0 + self.beacon_color.x * beacon * 2.0,
+            self.light_color.y * ring * 2.0 + self.beacon_color.y * beacon * 2.0,
+            self.light_color.z * ring * 2.0 + self.beacon_color.z * beacon * 2.0,
+        )
+    }
+}
+
+/// Shader de la nave: el casco metálico de `PlanetType::Ship` más el resplandor de las toberas,
+/// que se enciende con el empuje. Las toberas se sitúan en espacio de mundo (`nozzle`, detrás
+/// de la nave según su rumbo), porque el modelo cargado no trae marcada su orientación
+pub struct ShipShader {
+    pub nozzle: Vector3,       // Posición de las toberas en espacio de mundo
+    pub nozzle_radius: f32,    // Alcance del resplandor en unidades del mundo
+    pub thrust: f32,           // 0 = motor en reposo (brillo tenue), 1 = empuje completo
+    pub glow_color: Vector3,   // Color lineal del escape
+}
+
+impl ShipShader {
+    pub fn new(nozzle: Vector3, nozzle_radius: f32, thrust: f32) -> Self {
+        ShipShader {
+            nozzle,
+            nozzle_radius,
+            thrust,
+            glow_color: Vector3::new(0.45, 0.7, 1.0),
+        }
+    }
+}
+
+impl SurfaceShader for ShipShader {
+    fn material(&self) -> Material {
+        PlanetType::Ship.material()
+    }
+
+    fn shade(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        fragment_shader_planet(fragment, uniforms, PlanetType::Ship)
+    }
+
+    fn emission(&self, fragment: &Fragment, uniforms: &Uniforms) -> Vector3 {
+        let world = fragment.world_position;
+        let dx = world.x - self.nozzle.x;
+        let dy = world.y - self.nozzle.y;
+        let dz = world.z - self.nozzle.z;
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+        if distance > self.nozzle_radius {
+            return Vector3::zero();
+        }
+        let falloff = 1.0 - distance / self.nozzle_radius.max(0.0001);
+        // Parpadeo rápido del escape, más vivo con el motor a fondo
+        let flicker = 0.85 + 0.15 * (uniforms.time * 40.0).sin();
+        let glow = falloff * falloff * (0.2 + 1.8 * self.thrust.clamp(0.0, 1.0)) * flicker;
+        Vector3::new(self.glow_color.x * glow, self.glow_color.y * glow, self.glow_color.z * glow)
+    }
+}
@@ -0,0 +1,398 @@
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+/// Radio de la nave en unidades del mundo: el modelo se normaliza a este tamaño al cargarlo
+pub const SHIP_RADIUS: f32 = 2.5;
+
+// Estructura para la nave espacial
+#[derive(Clone, Copy)]
+pub struct Ship {
+    pub position: Vector3,           // Posición de la nave
+    pub rotation: Vector3,           // Rotación de la nave (yaw, pitch, roll)
+    pub velocity: Vector3,           // Velocidad de la nave
+    #[allow(dead_code)]
+    speed: f32,                      // Velocidad máxima (no se usa actualmente, la nave sigue a la cámara)
+    #[allow(dead_code)]
+    rotation_speed: f32,            // Velocidad de rotación (no se usa actualmente, la nave sigue a la cámara)
+    pub scale: f32,                  // Escala del modelo
+    pub use_direct_rotation: bool,   // Si es true, usa rotación directa; si es false, sigue a la cámara
+}
+
+// Los métodos de rotación son para mover la nave desde código (ver los ejemplos comentados en
+// `app`); el juego solo usa algunos
+#[allow(dead_code)]
+impl Ship {
+    pub fn new() -> Self {
+        Ship {
+            position: Vector3::new(0.0, 20.0, 40.0), // Posición inicial delante de la cámara
+            rotation: Vector3::new(0.0, 0.0, 0.0),  // Sin rotación inicial
+            velocity: Vector3::zero(),
+            speed: 25.0,          // Velocidad de movimiento con flechas (aumentada)
+            rotation_speed: 1.0,  // Velocidad de rotación con WASD (disminuida)
+            scale: 1.0,           // El modelo se normaliza al cargarlo (ver SHIP_RADIUS)
+            use_direct_rotation: false, // Por defecto, la nave sigue a la cámara
+        }
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        // Aplicar velocidad a la posición
+        self.position.x += self.velocity.x * delta_time;
+        self.position.y += self.velocity.y * delta_time;
+        self.position.z += self.velocity.z * delta_time;
+        
+        // Aplicar fricción a la velocidad
+        self.velocity.x *= 0.95;
+        self.velocity.y *= 0.95;
+        self.velocity.z *= 0.95;
+    }
+
+    /// Establece la rotación de la nave directamente en valores absolutos (en radianes)
+    /// 
+    /// # Argumentos
+    /// * `pitch` - Rotación alrededor del eje X (pitch) en radianes
+    /// * `yaw` - Rotación alrededor del eje Y (yaw) en radianes
+    /// * `roll` - Rotación alrededor del eje Z (roll) en radianes
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// use std::f32::consts::PI;
+    /// ship.set_rotation(0.5, 1.0, 0.0); // Pitch: 0.5 rad, Yaw: 1.0 rad, Roll: 0.0 rad
+    /// ship.set_rotation(45.0 * PI / 180.0, 90.0 * PI / 180.0, 0.0); // Usando grados convertidos
+    /// ```
+    pub fn set_rotation(&mut self, pitch: f32, yaw: f32, roll: f32) {
+        self.rotation.x = pitch;
+        self.rotation.y = yaw;
+        self.rotation.z = roll;
+    }
+
+    /// Establece solo el pitch (rotación X) de la nave
+    pub fn set_pitch(&mut self, pitch: f32) {
+        self.rotation.x = pitch;
+    }
+
+    /// Establece solo el yaw (rotación Y) de la nave
+    pub fn set_yaw(&mut self, yaw: f32) {
+        self.rotation.y = yaw;
+    }
+
+    /// Establece solo el roll (rotación Z) de la nave
+    pub fn set_roll(&mut self, roll: f32) {
+        self.rotation.z = roll;
+    }
+
+    /// Obtiene la rotación actual de la nave
+    pub fn get_rotation(&self) -> Vector3 {
+        self.rotation
+    }
+
+    /// Habilita o deshabilita la rotación directa
+    /// Si `enabled` es true, la nave usará rotación directa (no seguirá a la cámara)
+    /// Si `enabled` es false, la nave seguirá la rotación de la cámara
+    pub fn set_direct_rotation(&mut self, enabled: bool) {
+        self.use_direct_rotation = enabled;
+    }
+
+    // ======================================
+    // MÉTODOS PARA ROTAR EL MODELO 3D DIRECTAMENTE
+    // ======================================
+
+    /// Rota el modelo alrededor del eje X (pitch) agregando rotación
+    /// 
+    /// # Argumentos
+    /// * `angle` - Ángulo en radianes a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_pitch(0.5); // Rota 0.1 radianes alrededor del eje X
+    /// ```
+    pub fn rotate_pitch(&mut self, angle: f32) {
+        self.rotation.x += angle;
+    }
+
+    /// Rota el modelo alrededor del eje Y (yaw) agregando rotación
+    /// 
+    /// # Argumentos
+    /// * `angle` - Ángulo en radianes a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_yaw(0.6); // Rota 0.1 radianes alrededor del eje Y
+    /// ```
+    pub fn rotate_yaw(&mut self, angle: f32) {
+        self.rotation.y += angle;
+    }
+
+    /// Rota el modelo alrededor del eje Z (roll) agregando rotación
+    /// 
+    /// # Argumentos
+    /// * `angle` - Ángulo en radianes a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_roll(0.3); // Rota 0.1 radianes alrededor del eje Z
+    /// ```
+    pub fn rotate_roll(&mut self, angle: f32) {
+        self.rotation.z += angle;
+    }
+
+    /// Rota el modelo en todos los ejes agregando rotación
+    /// 
+    /// # Argumentos
+    /// * `pitch` - Rotación a agregar en X (radianes)
+    /// * `yaw` - Rotación a agregar en Y (radianes)
+    /// * `roll` - Rotación a agregar en Z (radianes)
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate(0.1, 0.2, 0.05); // Rota en todos los ejes
+    /// ```
+    pub fn rotate(&mut self, pitch: f32, yaw: f32, roll: f32) {
+        self.rotation.x += pitch;
+        self.rotation.y += yaw;
+        self.rotation.z += roll;
+    }
+
+    /// Rota el modelo alrededor del eje X usando grados
+    /// 
+    /// # Argumentos
+    /// * `degrees` - Ángulo en grados a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_pitch_degrees(45.0); // Rota 45 grados alrededor del eje X
+    /// ```
+    pub fn rotate_pitch_degrees(&mut self, degrees: f32) {
+        use std::f32::consts::PI;
+        self.rotation.x += degrees * PI / 180.0;
+    }
+
+    /// Rota el modelo alrededor del eje Y usando grados
+    /// 
+    /// # Argumentos
+    /// * `degrees` - Ángulo en grados a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_yaw_degrees(90.0); // Rota 90 grados alrededor del eje Y
+    /// ```
+    pub fn rotate_yaw_degrees(&mut self, degrees: f32) {
+        use std::f32::consts::PI;
+        self.rotation.y += degrees * PI / 180.0;
+    }
+
+    /// Rota el modelo alrededor del eje Z usando grados
+    /// 
+    /// # Argumentos
+    /// * `degrees` - Ángulo en grados a agregar a la rotación actual
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_roll_degrees(180.0); // Rota 180 grados alrededor del eje Z
+    /// ```
+    pub fn rotate_roll_degrees(&mut self, degrees: f32) {
+        use std::f32::consts::PI;
+        self.rotation.z += degrees * PI / 180.0;
+    }
+
+    /// Rota el modelo en todos los ejes usando grados
+    /// 
+    /// # Argumentos
+    /// * `pitch_degrees` - Rotación a agregar en X (grados)
+    /// * `yaw_degrees` - Rotación a agregar en Y (grados)
+    /// * `roll_degrees` - Rotación a agregar en Z (grados)
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_degrees(45.0, 90.0, 0.0); // Rota usando grados
+    /// ```
+    pub fn rotate_degrees(&mut self, pitch_degrees: f32, yaw_degrees: f32, roll_degrees: f32) {
+        use std::f32::consts::PI;
+        self.rotation.x += pitch_degrees * PI / 180.0;
+        self.rotation.y += yaw_degrees * PI / 180.0;
+        self.rotation.z += roll_degrees * PI / 180.0;
+    }
+
+    /// Establece la rotación usando grados (más intuitivo que radianes)
+    /// 
+    /// # Argumentos
+    /// * `pitch_degrees` - Pitch en grados
+    /// * `yaw_degrees` - Yaw en grados
+    /// * `roll_degrees` - Roll en grados
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.set_rotation_degrees(45.0, 90.0, 0.0); // Establece rotación usando grados
+    /// ```
+    pub fn set_rotation_degrees(&mut self, pitch_degrees: f32, yaw_degrees: f32, roll_degrees: f32) {
+        use std::f32::consts::PI;
+        self.rotation.x = pitch_degrees * PI / 180.0;
+        self.rotation.y = yaw_degrees * PI / 180.0;
+        self.rotation.z = roll_degrees * PI / 180.0;
+    }
+
+    /// Rota el modelo continuamente a una velocidad específica
+    /// Útil para animaciones o rotación automática
+    /// 
+    /// # Argumentos
+    /// * `pitch_speed` - Velocidad de rotación en X (radianes por segundo)
+    /// * `yaw_speed` - Velocidad de rotación en Y (radianes por segundo)
+    /// * `roll_speed` - Velocidad de rotación en Z (radianes por segundo)
+    /// * `delta_time` - Tiempo transcurrido desde el último frame
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_continuous(0.0, 1.0, 0.0, delta_time); // Rota continuamente en Y
+    /// ```
+    pub fn rotate_continuous(&mut self, pitch_speed: f32, yaw_speed: f32, roll_speed: f32, delta_time: f32) {
+        self.rotation.x += pitch_speed * delta_time;
+        self.rotation.y += yaw_speed * delta_time;
+        self.rotation.z += roll_speed * delta_time;
+    }
+
+    /// Rota el modelo continuamente usando grados por segundo
+    /// 
+    /// # Argumentos
+    /// * `pitch_degrees_per_sec` - Velocidad de rotación en X (grados por segundo)
+    /// * `yaw_degrees_per_sec` - Velocidad de rotación en Y (grados por segundo)
+    /// * `roll_degrees_per_sec` - Velocidad de rotación en Z (grados por segundo)
+    /// * `delta_time` - Tiempo transcurrido desde el último frame
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.rotate_continuous_degrees(0.0, 90.0, 0.0, delta_time); // Rota 90 grados/seg en Y
+    /// ```
+    pub fn rotate_continuous_degrees(&mut self, pitch_degrees_per_sec: f32, yaw_degrees_per_sec: f32, roll_degrees_per_sec: f32, delta_time: f32) {
+        use std::f32::consts::PI;
+        self.rotation.x += pitch_degrees_per_sec * PI / 180.0 * delta_time;
+        self.rotation.y += yaw_degrees_per_sec * PI / 180.0 * delta_time;
+        self.rotation.z += roll_degrees_per_sec * PI / 180.0 * delta_time;
+    }
+
+    /// Resetea la rotación del modelo a cero
+    /// 
+    /// # Ejemplo
+    /// ```ignore
+    /// ship.reset_rotation(); // Vuelve la rotación a (0, 0, 0)
+    /// ```
+    pub fn reset_rotation(&mut self) {
+        self.rotation.x = 0.0;
+        self.rotation.y = 0.0;
+        self.rotation.z = 0.0;
+    }
+
+    #[allow(dead_code)]
+    fn process_input(&mut self, window: &RaylibHandle, delta_time: f32) {
+        // Rotación con Q y E (roll)
+        if window.is_key_down(KeyboardKey::KEY_Q) {
+            self.rotation.z += self.rotation_speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_E) {
+            self.rotation.z -= self.rotation_speed * delta_time;
+        }
+
+        // Rotación con A y D (yaw)
+        if window.is_key_down(KeyboardKey::KEY_A) {
+            self.rotation.y += self.rotation_speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_D) {
+            self.rotation.y -= self.rotation_speed * delta_time;
+        }
+
+        // Rotación con W y S (pitch)
+        if window.is_key_down(KeyboardKey::KEY_W) {
+            self.rotation.x += self.rotation_speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_S) {
+            self.rotation.x -= self.rotation_speed * delta_time;
+        }
+
+        // Movimiento con flechas - más directo y responsivo
+        // Calcular dirección forward basada en la rotación completa (yaw y pitch)
+        let cos_y = self.rotation.y.cos();
+        let sin_y = self.rotation.y.sin();
+        let cos_x = self.rotation.x.cos();
+        let sin_x = self.rotation.x.sin();
+        
+        let forward = Vector3::new(
+            sin_y * cos_x,
+            -sin_x,
+            cos_y * cos_x,
+        );
+        
+        // Movimiento hacia adelante/atrás con flechas arriba/abajo
+        if window.is_key_down(KeyboardKey::KEY_UP) {
+            self.velocity.x += forward.x * self.speed * delta_time;
+            self.velocity.y += forward.y * self.speed * delta_time;
+            self.velocity.z += forward.z * self.speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_DOWN) {
+            self.velocity.x -= forward.x * self.speed * delta_time;
+            self.velocity.y -= forward.y * self.speed * delta_time;
+            self.velocity.z -= forward.z * self.speed * delta_time;
+        }
+
+        // Movimiento lateral con flechas izquierda/derecha
+        let right = Vector3::new(
+            (self.rotation.y + PI / 2.0).sin() * cos_x,
+            0.0,
+            (self.rotation.y + PI / 2.0).cos() * cos_x,
+        );
+        
+        if window.is_key_down(KeyboardKey::KEY_LEFT) {
+            self.velocity.x += right.x * self.speed * delta_time;
+            self.velocity.z += right.z * self.speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_RIGHT) {
+            self.velocity.x -= right.x * self.speed * delta_time;
+            self.velocity.z -= right.z * self.speed * delta_time;
+        }
+
+        // Movimiento vertical con R y F
+        if window.is_key_down(KeyboardKey::KEY_R) {
+            self.velocity.y += self.speed * delta_time;
+        }
+        if window.is_key_down(KeyboardKey::KEY_F) {
+            self.velocity.y -= self.speed * delta_time;
+        }
+    }
+    
+    // Obtener la dirección forward de la nave (para la cámara)
+    #[allow(dead_code)]
+    fn get_forward_direction(&self) -> Vector3 {
+        let cos_y = self.rotation.y.cos();
+        let sin_y = self.rotation.y.sin();
+        let cos_x = self.rotation.x.cos();
+        let sin_x = self.rotation.x.sin();
+        
+        Vector3::new(
+            sin_y * cos_x,
+            -sin_x,
+            cos_y * cos_x,
+        )
+    }
+    
+    // Obtener la dirección up de la nave (para la cámara)
+    #[allow(dead_code)]
+    fn get_up_direction(&self) -> Vector3 {
+        let cos_y = self.rotation.y.cos();
+        let sin_y = self.rotation.y.sin();
+        let cos_x = self.rotation.x.cos();
+        let sin_x = self.rotation.x.sin();
+        let cos_z = self.rotation.z.cos();
+        let sin_z = self.rotation.z.sin();
+        
+        // Calcular up basado en roll
+        let up_local = Vector3::new(
+            sin_y * sin_x * cos_z - cos_y * sin_z,
+            cos_x * cos_z,
+            cos_y * sin_x * cos_z + sin_y * sin_z,
+        );
+        up_local
+    }
+}
+
+impl Default for Ship {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,92 @@
+/// Recursos de la nave: combustible de los motores y carga del motor de warp
+/// El combustible se gasta con el empuje de los motores y solo se recupera repostando en una
+/// estación; la carga de warp se regenera sola con el tiempo. Sin combustible la nave no puede
+/// maniobrar y sigue a la deriva (el modelo de vuelo conserva la velocidad que llevaba)
+pub struct ShipSystems {
+    pub fuel: f32,               // Combustible restante
+    pub fuel_capacity: f32,
+    pub fuel_per_unit: f32,      // Combustible por unidad de distancia recorrida con el piloto automático
+    pub burn_rate: f32,          // Combustible por segundo con el empuje al 100%
+    pub thruster_output: f32,    // Empuje actual en [0, 1] (suavizado, para el HUD)
+    pub warp_charge: f32,        // Carga del motor de warp en [0, 1]
+    pub warp_cost: f32,          // Carga que consume cada salto
+    pub warp_recharge_rate: f32, // Carga recuperada por segundo
+    pub refuel_rate: f32,        // Combustible por segundo al repostar
+}
+
+impl ShipSystems {
+    pub fn new() -> Self {
+        ShipSystems {
+            fuel: 100.0,
+            fuel_capacity: 100.0,
+            fuel_per_unit: 0.06,
+            burn_rate: 0.54,
+            thruster_output: 0.0,
+            warp_charge: 1.0,
+            warp_cost: 0.5,
+            warp_recharge_rate: 0.05,
+            refuel_rate: 20.0,
+        }
+    }
+
+    /// Fracción de combustible restante en [0, 1]
+    pub fn fuel_fraction(&self) -> f32 {
+        (self.fuel / self.fuel_capacity.max(0.0001)).clamp(0.0, 1.0)
+    }
+
+    pub fn has_fuel(&self) -> bool {
+        self.fuel > 0.0
+    }
+
+    /// Sin combustible: la nave va a la deriva
+    pub fn is_drifting(&self) -> bool {
+        !self.has_fuel()
+    }
+
+    /// Paga el empuje que usa el modelo de vuelo en este frame (`output` en [0, 1])
+    /// Sin combustible los motores no responden; devuelve si queda combustible
+    pub fn thrust(&mut self, output: f32, delta_time: f32) -> bool {
+        let output = if self.has_fuel() { output.clamp(0.0, 1.0) } else { 0.0 };
+        self.fuel = (self.fuel - output * self.burn_rate * delta_time).max(0.0);
+        self.thruster_output += (output - self.thruster_output) * (1.0 - (-delta_time * 8.0).exp());
+        self.has_fuel()
+    }
+
+    /// Gasta el combustible de recorrer `distance` con los motores (p. ej. el piloto automático)
+    /// Devuelve la fracción del recorrido que se pudo pagar: 1 si sobraba combustible
+    pub fn burn(&mut self, distance: f32) -> f32 {
+        let cost = distance * self.fuel_per_unit;
+        if cost <= 0.0 {
+            return 1.0;
+        }
+        let fraction = (self.fuel / cost).min(1.0);
+        self.fuel = (self.fuel - cost).max(0.0);
+        fraction
+    }
+
+    /// Consume la carga de un salto si hay suficiente; si no, devuelve false y no la toca
+    pub fn try_warp(&mut self) -> bool {
+        if self.warp_charge + 0.0001 < self.warp_cost {
+            return false;
+        }
+        self.warp_charge = (self.warp_charge - self.warp_cost).max(0.0);
+        true
+    }
+
+    /// Repuesto en una estación: añade combustible y devuelve true cuando el depósito está lleno
+    pub fn refuel(&mut self, delta_time: f32) -> bool {
+        self.fuel = (self.fuel + self.refuel_rate * delta_time).min(self.fuel_capacity);
+        self.fuel >= self.fuel_capacity
+    }
+
+    /// Regenera la carga de warp
+    pub fn update(&mut self, delta_time: f32) {
+        self.warp_charge = (self.warp_charge + self.warp_recharge_rate * delta_time).min(1.0);
+    }
+}
+
+impl Default for ShipSystems {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,153 @@
+use raylib::prelude::*;
+use crate::color::srgb_to_linear;
+use crate::framebuffer::Framebuffer;
+use crate::matrix::pixel_ray;
+use crate::starfield::BACKGROUND_DEPTH;
+use crate::uniforms::Uniforms;
+use std::f32::consts::PI;
+use std::io;
+
+/// Decoded image converted from sRGB to linear floats for sampling
+struct SkyImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<Vector3>,
+}
+
+impl SkyImage {
+    fn load(path: &str) -> io::Result<Self> {
+        let image = Image::load_image(path)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path, e)))?;
+        let width = image.width().max(0) as usize;
+        let height = image.height().max(0) as usize;
+        if width == 0 || height == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{}: empty image", path)));
+        }
+        let pixels = image
+            .get_image_data()
+            .iter()
+            .map(|c| {
+                Vector3::new(
+                    srgb_to_linear(c.r as f32 / 255.0),
+                    srgb_to_linear(c.g as f32 / 255.0),
+                    srgb_to_linear(c.b as f32 / 255.0),
+                )
+            })
+            .collect();
+        Ok(SkyImage { width, height, pixels })
+    }
+
+    /// Bilinear sample at normalized coordinates (u wraps, v clamps)
+    fn sample(&self, u: f32, v: f32) -> Vector3 {
+        let x = u.rem_euclid(1.0) * self.width as f32 - 0.5;
+        let y = (v.clamp(0.0, 1.0) * self.height as f32 - 0.5).clamp(0.0, self.height as f32 - 1.0);
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+
+        let wrap_x = |x: f32| (x as isize).rem_euclid(self.width as isize) as usize;
+        let x0i = wrap_x(x0);
+        let x1i = wrap_x(x0 + 1.0);
+        let y0i = y0 as usize;
+        let y1i = (y0i + 1).min(self.height - 1);
+
+        let texel = |x: usize, y: usize| self.pixels[y * self.width + x];
+        let lerp = |a: Vector3, b: Vector3, t: f32| {
+            Vector3::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t, a.z + (b.z - a.z) * t)
+        };
+        let top = lerp(texel(x0i, y0i), texel(x1i, y0i), fx);
+        let bottom = lerp(texel(x0i, y1i), texel(x1i, y1i), fx);
+        lerp(top, bottom, fy)
+    }
+}
+
+enum SkyProjection {
+    /// Faces in +X, -X, +Y, -Y, +Z, -Z order
+    Cubemap(Vec<SkyImage>),
+    /// Single longitude/latitude panorama
+    Equirectangular(SkyImage),
+}
+
+/// Image-based sky drawn behind all geometry
+/// Each background pixel is turned back into a world-space view ray with the inverse of the
+/// projection and view matrices, and that direction is looked up in the sky texture
+pub struct Skybox {
+    projection: SkyProjection,
+    pub intensity: f32, // Brightness multiplier so the sky does not compete with the planets
+}
+
+impl Skybox {
+    /// Face file names expected by `load_cubemap_dir`, in +X, -X, +Y, -Y, +Z, -Z order
+    pub const FACE_NAMES: [&'static str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+    /// Loads six cube faces in +X, -X, +Y, -Y, +Z, -Z order
+    pub fn load_cubemap(paths: [&str; 6]) -> io::Result<Self> {
+        let faces = paths.iter().map(|path| SkyImage::load(path)).collect::<io::Result<Vec<_>>>()?;
+        Ok(Skybox { projection: SkyProjection::Cubemap(faces), intensity: 1.0 })
+    }
+
+    /// Loads `px`, `nx`, `py`, `ny`, `pz` and `nz` with the given extension from a directory
+    pub fn load_cubemap_dir(dir: &str, extension: &str) -> io::Result<Self> {
+        let paths: Vec<String> = Self::FACE_NAMES
+            .iter()
+            .map(|face| format!("{}/{}.{}", dir, face, extension))
+            .collect();
+        Self::load_cubemap([&paths[0], &paths[1], &paths[2], &paths[3], &paths[4], &paths[5]])
+    }
+
+    /// Loads a 2:1 equirectangular panorama
+    pub fn load_equirectangular(path: &str) -> io::Result<Self> {
+        Ok(Skybox { projection: SkyProjection::Equirectangular(SkyImage::load(path)?), intensity: 1.0 })
+    }
+
+    /// Sky color seen along a normalized world-space direction
+    pub fn sample(&self, dir: Vector3) -> Vector3 {
+        let color = match &self.projection {
+            SkyProjection::Equirectangular(image) => {
+                let u = 0.5 + dir.z.atan2(dir.x) / (2.0 * PI);
+                let v = 0.5 - dir.y.clamp(-1.0, 1.0).asin() / PI;
+                image.sample(u, v)
+            }
+            SkyProjection::Cubemap(faces) => {
+                let (face, u, v) = cube_face(dir);
+                faces[face].sample(u, v)
+            }
+        };
+        Vector3::new(color.x * self.intensity, color.y * self.intensity, color.z * self.intensity)
+    }
+
+    /// Fills every pixel where no geometry was drawn (depth beyond the clip range)
+    /// Run after the opaque pass and before translucent surfaces, which do not write depth
+    pub fn render(&self, framebuffer: &mut Framebuffer, uniforms: &Uniforms) {
+        let view = &uniforms.view_matrix;
+        let projection = &uniforms.projection_matrix;
+        let width = framebuffer.width as i32;
+        let height = framebuffer.height as i32;
+
+        for y in 0..height {
+            for x in 0..width {
+                if framebuffer.depth_at(x, y) <= 1.0 {
+                    continue;
+                }
+                let dir = pixel_ray(x, y, width, height, view, projection);
+                framebuffer.point(x, y, self.sample(dir), BACKGROUND_DEPTH);
+            }
+        }
+    }
+}
+
+/// Selects the cube face hit by `dir` and the (u, v) on it, following the usual cubemap layout
+fn cube_face(dir: Vector3) -> (usize, f32, f32) {
+    let (ax, ay, az) = (dir.x.abs(), dir.y.abs(), dir.z.abs());
+    let (face, major, sc, tc) = if ax >= ay && ax >= az {
+        if dir.x > 0.0 { (0, ax, -dir.z, -dir.y) } else { (1, ax, dir.z, -dir.y) }
+    } else if ay >= az {
+        if dir.y > 0.0 { (2, ay, dir.x, dir.z) } else { (3, ay, dir.x, -dir.z) }
+    } else if dir.z > 0.0 {
+        (4, az, dir.x, -dir.y)
+    } else {
+        (5, az, -dir.x, -dir.y)
+    };
+    (face, 0.5 * (sc / major + 1.0), 0.5 * (tc / major + 1.0))
+}
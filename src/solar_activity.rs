@@ -0,0 +1,228 @@
+use raylib::prelude::*;
+use crate::matrix::new_matrix4;
+use crate::star::Star;
+use std::f32::consts::PI;
+
+/// Eyección de masa coronal: un frente de plasma que sale del sol dentro de un cono y se
+/// expande a velocidad constante hasta disiparse
+#[derive(Clone, Debug)]
+pub struct CoronalMassEjection {
+    pub id: u32,
+    pub direction: Vector3,  // Eje del cono (normalizado)
+    pub half_angle: f32,     // Semiapertura del cono (radianes)
+    pub start_time: f32,     // Instante de la erupción
+    pub speed: f32,          // Velocidad del frente (unidades por segundo)
+    pub max_radius: f32,     // Distancia a la que se disipa
+    ship_inside: bool,       // La nave estaba dentro del frente en la última actualización
+}
+
+impl CoronalMassEjection {
+    /// Radio del frente en `time` (0 antes de la erupción)
+    pub fn radius(&self, time: f32) -> f32 {
+        ((time - self.start_time) * self.speed).max(0.0)
+    }
+
+    /// Grosor del frente: crece al expandirse para que el plasma se vea cada vez más difuso
+    pub fn thickness(&self, time: f32) -> f32 {
+        (self.radius(time) * 0.15).max(2.0)
+    }
+
+    /// Intensidad en [0, 1]: aparece rápido y se apaga al acercarse a `max_radius`
+    pub fn intensity(&self, time: f32) -> f32 {
+        let t = self.radius(time) / self.max_radius.max(0.0001);
+        ((t * 20.0).min(1.0) * (1.0 - t)).clamp(0.0, 1.0)
+    }
+
+    pub fn is_dissipated(&self, time: f32) -> bool {
+        self.radius(time) >= self.max_radius
+    }
+
+    /// Fuerza máxima del frente al pasar por `point`: 1 en el eje del cono, 0 en su borde o fuera,
+    /// multiplicado por la intensidad actual de la eyección
+    pub fn peak_strength_at(&self, point: Vector3, time: f32) -> f32 {
+        let distance = (point.x * point.x + point.y * point.y + point.z * point.z).sqrt();
+        if distance < 0.0001 {
+            return 0.0;
+        }
+        let d = self.direction;
+        let cos_angle = (point.x * d.x + point.y * d.y + point.z * d.z) / distance;
+        let angle = cos_angle.clamp(-1.0, 1.0).acos();
+        if angle >= self.half_angle {
+            return 0.0;
+        }
+        (1.0 - angle / self.half_angle).sqrt() * self.intensity(time)
+    }
+
+    /// Fuerza del frente en `point` ahora mismo (0 fuera del frente): la fuerza máxima atenuada
+    /// según la distancia al centro del grosor del frente
+    pub fn strength_at(&self, point: Vector3, time: f32) -> f32 {
+        let distance = (point.x * point.x + point.y * point.y + point.z * point.z).sqrt();
+        let shell = 1.0 - ((distance - self.radius(time)).abs() / (self.thickness(time) * 0.5));
+        if shell <= 0.0 {
+            return 0.0;
+        }
+        shell * self.peak_strength_at(point, time)
+    }
+
+    /// Matriz de modelo del casquete unitario (`Obj::generate_spherical_cap`) en `time`:
+    /// centrado en el sol, escalado al radio del frente y con +Y apuntando a `direction`
+    /// Se construye con una base ortonormal (X, `direction`, Z) en lugar de ángulos de Euler
+    pub fn model_matrix(&self, time: f32) -> Matrix {
+        let d = self.direction;
+        let r = self.radius(time);
+        // Eje X perpendicular a `direction`, tomado respecto a un vector que no sea paralelo
+        let reference = if d.y.abs() < 0.99 { Vector3::new(0.0, 1.0, 0.0) } else { Vector3::new(1.0, 0.0, 0.0) };
+        let x = Vector3::new(
+            reference.y * d.z - reference.z * d.y,
+            reference.z * d.x - reference.x * d.z,
+            reference.x * d.y - reference.y * d.x,
+        );
+        let x_length = (x.x * x.x + x.y * x.y + x.z * x.z).sqrt();
+        let x = Vector3::new(x.x / x_length, x.y / x_length, x.z / x_length);
+        let z = Vector3::new(x.y * d.z - x.z * d.y, x.z * d.x - x.x * d.z, x.x * d.y - x.y * d.x);
+        new_matrix4(
+            x.x * r, d.x * r, z.x * r, 0.0,
+            x.y * r, d.y * r, z.y * r, 0.0,
+            x.z * r, d.z * r, z.z * r, 0.0,
+            0.0,     0.0,     0.0,     1.0,
+        )
+    }
+}
+
+/// Sucesos de la actividad solar que el juego puede atender
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolarEvent {
+    /// El sol expulsa una eyección hacia `direction`
+    Eruption { id: u32, direction: Vector3 },
+    /// El frente alcanza la nave; `strength` (0 a 1) es la fuerza máxima que tendrá al atravesarla
+    ShockArrived { id: u32, strength: f32 },
+    /// El frente termina de pasar por la nave
+    ShockPassed { id: u32 },
+    /// La eyección se disipa y deja de existir
+    Dissipated { id: u32 },
+}
+
+/// Generador de eyecciones de masa coronal: erupciones a intervalos aleatorios (proceso de
+/// Poisson) y seguimiento de los frentes activos respecto a la nave
+/// `update` devuelve los sucesos de cada frame; el juego reacciona a ellos (temblor de
+/// pantalla, interferencias, avisos) sin que este módulo sepa nada de la interfaz
+pub struct SolarActivity {
+    pub enabled: bool,
+    pub mean_interval: f32, // Segundos medios entre erupciones
+    pub speed: f32,         // Velocidad de los frentes
+    pub half_angle: f32,    // Semiapertura de los conos
+    pub max_radius: f32,    // Distancia a la que se disipan (normalmente algo más que el sistema)
+    ejections: Vec<CoronalMassEjection>,
+    next_eruption: f32,
+    next_id: u32,
+    rng_state: u64,
+}
+
+impl SolarActivity {
+    pub fn new(mean_interval: f32, max_radius: f32) -> Self {
+        let mut activity = SolarActivity {
+            enabled: true,
+            mean_interval,
+            speed: 25.0,
+            half_angle: 0.35, // ~20°
+            max_radius,
+            ejections: Vec::new(),
+            next_eruption: 0.0,
+            next_id: 0,
+            rng_state: 0x5EED_C0DE,
+        };
+        activity.next_eruption = activity.random_interval();
+        activity
+    }
+
+    /// Actividad acorde a la estrella: una erupción cada 45 s de media para el sol, más a
+    /// menudo cuanto mayor sea su frecuencia de llamaradas
+    pub fn for_star(star: &Star, max_radius: f32) -> Self {
+        SolarActivity::new(45.0 / star.flare_frequency.max(0.05), max_radius)
+    }
+
+    pub fn ejections(&self) -> &[CoronalMassEjection] {
+        &self.ejections
+    }
+
+    /// Elimina los frentes activos y reprograma la próxima erupción a partir de `time`
+    pub fn reset(&mut self, time: f32) {
+        self.ejections.clear();
+        self.next_eruption = time + self.random_interval();
+    }
+
+    /// Provoca una erupción inmediata hacia `direction` (p. ej. hacia la nave)
+    pub fn trigger(&mut self, time: f32, direction: Vector3) -> SolarEvent {
+        let length = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt();
+        let direction = if length > 0.0001 {
+            Vector3::new(direction.x / length, direction.y / length, direction.z / length)
+        } else {
+            Vector3::new(1.0, 0.0, 0.0)
+        };
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ejections.push(CoronalMassEjection {
+            id,
+            direction,
+            half_angle: self.half_angle,
+            start_time: time,
+            speed: self.speed,
+            max_radius: self.max_radius,
+            ship_inside: false,
+        });
+        SolarEvent::Eruption { id, direction }
+    }
+
+    /// Avanza la actividad hasta `time` y devuelve los sucesos ocurridos, en orden
+    pub fn update(&mut self, time: f32, ship_position: Vector3) -> Vec<SolarEvent> {
+        let mut events = Vec::new();
+
+        if self.enabled && time >= self.next_eruption {
+            // Cerca del plano de la eclíptica, donde están los planetas
+            let azimuth = self.random() * 2.0 * PI;
+            let elevation = (self.random() - 0.5) * 0.3;
+            events.push(self.trigger(time, Vector3::new(azimuth.cos(), elevation, azimuth.sin())));
+            self.next_eruption = time + self.random_interval();
+        }
+
+        for ejection in &mut self.ejections {
+            let strength = ejection.strength_at(ship_position, time);
+            if strength > 0.0 && !ejection.ship_inside {
+                let strength = ejection.peak_strength_at(ship_position, time);
+                events.push(SolarEvent::ShockArrived { id: ejection.id, strength });
+            } else if strength <= 0.0 && ejection.ship_inside {
+                events.push(SolarEvent::ShockPassed { id: ejection.id });
+            }
+            ejection.ship_inside = strength > 0.0;
+        }
+
+        self.ejections.retain(|ejection| {
+            let dissipated = ejection.is_dissipated(time);
+            if dissipated {
+                events.push(SolarEvent::Dissipated { id: ejection.id });
+            }
+            !dissipated
+        });
+
+        events
+    }
+
+    /// Fuerza del frente más intenso que atraviesa `point` ahora mismo (0 si ninguno)
+    pub fn exposure(&self, point: Vector3, time: f32) -> f32 {
+        self.ejections
+            .iter()
+            .map(|ejection| ejection.strength_at(point, time))
+            .fold(0.0, f32::max)
+    }
+
+    /// Valor pseudoaleatorio en [0, 1) (LCG, como las partículas)
+    fn random(&mut self) -> f32 {
+        self.rng_state = self.rng_state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.rng_state >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Espera hasta la próxima erupción: distribución exponencial de media `mean_interval`
+    fn random_interval(&mut self) -> f32 {
+        -(1.0 - self.random()).ln() * self.mean_interval.max(0.1)
+    }
+}
@@ -0,0 +1,288 @@
+use raylib::prelude::*;
+use crate::matrix::multiply_matrices;
+use std::collections::BTreeMap;
+
+/// Esfera envolvente guardada en el índice, con el objeto al que pertenece
+#[derive(Clone, Copy, Debug)]
+pub struct Entry<T> {
+    pub item: T,
+    pub center: Vector3,
+    pub radius: f32,
+}
+
+/// Índice espacial de esferas envolventes sobre una rejilla uniforme
+/// Cada esfera se guarda en la celda que contiene su centro; las consultas amplían su región con
+/// el mayor radio guardado, así que ninguna esfera aparece dos veces (y el orden de las celdas es
+/// fijo: las repeticiones dan siempre el mismo resultado). Las esferas mayores que una
+/// celda (el sol, los planetas gigantes) van a una lista aparte que se prueba siempre. Los cuerpos
+/// se mueven cada frame, así que el índice se reconstruye entero en vez de actualizarse
+pub struct SpatialIndex<T> {
+    cell_size: f32,
+    entries: Vec<Entry<T>>,
+    cells: BTreeMap<(i32, i32, i32), Vec<usize>>, // Celda -> posiciones en `entries`
+    large: Vec<usize>,                            // Esferas de radio mayor que una celda
+    max_radius: f32,                              // Mayor radio de las esferas de la rejilla
+}
+
+impl<T: Copy> SpatialIndex<T> {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialIndex {
+            cell_size: cell_size.max(0.0001),
+            entries: Vec::new(),
+            cells: BTreeMap::new(),
+            large: Vec::new(),
+            max_radius: 0.0,
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[Entry<T>] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.cells.clear();
+        self.large.clear();
+        self.max_radius = 0.0;
+    }
+
+    pub fn insert(&mut self, item: T, center: Vector3, radius: f32) {
+        let idx = self.entries.len();
+        self.entries.push(Entry { item, center, radius });
+        if radius > self.cell_size {
+            self.large.push(idx);
+        } else {
+            self.max_radius = self.max_radius.max(radius);
+            self.cells.entry(self.cell_of(center)).or_default().push(idx);
+        }
+    }
+
+    /// Esferas que se solapan con la esfera (`center`, `radius`)
+    pub fn overlapping(&self, center: Vector3, radius: f32) -> Vec<&Entry<T>> {
+        let min = Vector3::new(center.x - radius, center.y - radius, center.z - radius);
+        let max = Vector3::new(center.x + radius, center.y + radius, center.z + radius);
+        let mut found = Vec::new();
+        self.visit_box(min, max, |entry| {
+            let reach = entry.radius + radius;
+            if distance_squared(entry.center, center) <= reach * reach {
+                found.push(entry);
+            }
+        });
+        found
+    }
+
+    /// Esferas que toca el segmento `start` -> `end` engordado `padding` unidades
+    /// (p. ej. el radio de un proyectil o el margen de la cámara)
+    pub fn along_segment(&self, start: Vector3, end: Vector3, padding: f32) -> Vec<&Entry<T>> {
+        let min = Vector3::new(start.x.min(end.x) - padding, start.y.min(end.y) - padding, start.z.min(end.z) - padding);
+        let max = Vector3::new(start.x.max(end.x) + padding, start.y.max(end.y) + padding, start.z.max(end.z) + padding);
+        let mut found = Vec::new();
+        self.visit_box(min, max, |entry| {
+            let reach = entry.radius + padding;
+            if segment_distance_squared(start, end, entry.center) <= reach * reach {
+                found.push(entry);
+            }
+        });
+        found
+    }
+
+    /// Esfera cuya superficie está más cerca de `point` entre las que aceptan `filter`,
+    /// con esa distancia (negativa si `point` está dentro)
+    /// Las celdas se descartan en cuanto su caja queda más lejos que el mejor candidato
+    pub fn nearest(&self, point: Vector3, filter: impl Fn(T) -> bool) -> Option<(&Entry<T>, f32)> {
+        let mut best: Option<(&Entry<T>, f32)> = None;
+        let cells = self.cells.iter().map(|(&cell, indices)| (Some(cell), indices));
+        for (cell, indices) in std::iter::once((None, &self.large)).chain(cells) {
+            if let Some(((_, best_distance), cell)) = best.zip(cell) {
+                let (min, max) = self.cell_bounds(cell);
+                if box_distance_squared(min, max, point).sqrt() - self.max_radius >= best_distance {
+                    continue;
+                }
+            }
+            for entry in indices.iter().map(|&idx| &self.entries[idx]).filter(|entry| filter(entry.item)) {
+                let distance = distance_squared(entry.center, point).sqrt() - entry.radius;
+                if best.is_none_or(|(_, best_distance)| distance < best_distance) {
+                    best = Some((entry, distance));
+                }
+            }
+        }
+        best
+    }
+
+    /// Esferas que asoman dentro del cono de vértice `origin`, eje `direction` (normalizado) y
+    /// semiapertura `half_angle`, con la separación angular entre su borde y el eje (negativa si el
+    /// eje las atraviesa). Las que contienen a `origin` no tienen separación definida y se ignoran
+    pub fn within_cone(&self, origin: Vector3, direction: Vector3, half_angle: f32) -> Vec<(&Entry<T>, f32)> {
+        let separation = |center: Vector3, radius: f32| -> Option<f32> {
+            let to_center = Vector3::new(center.x - origin.x, center.y - origin.y, center.z - origin.z);
+            let distance = distance_squared(center, origin).sqrt();
+            if distance <= radius {
+                return None;
+            }
+            let cos_angle = (to_center.x * direction.x + to_center.y * direction.y + to_center.z * direction.z) / distance;
+            Some(cos_angle.clamp(-1.0, 1.0).acos() - (radius / distance).asin())
+        };
+
+        let mut found = Vec::new();
+        let cell_radius = self.cell_size * 0.5 * 3.0f32.sqrt() + self.max_radius;
+        let cells = self.cells.iter().map(|(&cell, indices)| (Some(cell), indices));
+        for (cell, indices) in std::iter::once((None, &self.large)).chain(cells) {
+            // La celda entera (con lo que sobresale de ella) fuera del cono: se salta
+            if cell.is_some_and(|cell| separation(self.cell_center(cell), cell_radius).is_some_and(|angle| angle >= half_angle)) {
+                continue;
+            }
+            for entry in indices.iter().map(|&idx| &self.entries[idx]) {
+                if let Some(angle) = separation(entry.center, entry.radius).filter(|&angle| angle < half_angle) {
+                    found.push((entry, angle));
+                }
+            }
+        }
+        found
+    }
+
+    /// Esferas al menos en parte dentro de `frustum`
+    pub fn in_frustum(&self, frustum: &Frustum) -> Vec<&Entry<T>> {
+        let mut found: Vec<&Entry<T>> = self
+            .large
+            .iter()
+            .map(|&idx| &self.entries[idx])
+            .filter(|entry| frustum.intersects_sphere(entry.center, entry.radius))
+            .collect();
+        let cell_radius = self.cell_size * 0.5 * 3.0f32.sqrt() + self.max_radius;
+        for (&cell, indices) in &self.cells {
+            if !frustum.intersects_sphere(self.cell_center(cell), cell_radius) {
+                continue;
+            }
+            found.extend(
+                indices
+                    .iter()
+                    .map(|&idx| &self.entries[idx])
+                    .filter(|entry| frustum.intersects_sphere(entry.center, entry.radius)),
+            );
+        }
+        found
+    }
+
+    fn cell_of(&self, point: Vector3) -> (i32, i32, i32) {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+            (point.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    fn cell_bounds(&self, (x, y, z): (i32, i32, i32)) -> (Vector3, Vector3) {
+        let size = self.cell_size;
+        let min = Vector3::new(x as f32 * size, y as f32 * size, z as f32 * size);
+        (min, Vector3::new(min.x + size, min.y + size, min.z + size))
+    }
+
+    fn cell_center(&self, (x, y, z): (i32, i32, i32)) -> Vector3 {
+        let size = self.cell_size;
+        Vector3::new((x as f32 + 0.5) * size, (y as f32 + 0.5) * size, (z as f32 + 0.5) * size)
+    }
+
+    /// Llama a `visit` con cada esfera que podría tocar la caja `min`..`max`: las grandes y las de
+    /// las celdas de la caja ampliada con `max_radius`. Si la caja cubre más celdas de las que
+    /// están ocupadas, recorre las ocupadas en su lugar
+    fn visit_box<'a>(&'a self, min: Vector3, max: Vector3, mut visit: impl FnMut(&'a Entry<T>)) {
+        for &idx in &self.large {
+            visit(&self.entries[idx]);
+        }
+        let reach = self.max_radius;
+        let (x0, y0, z0) = self.cell_of(Vector3::new(min.x - reach, min.y - reach, min.z - reach));
+        let (x1, y1, z1) = self.cell_of(Vector3::new(max.x + reach, max.y + reach, max.z + reach));
+        let span = |a: i32, b: i32| (b as i64 - a as i64 + 1).max(0) as u64;
+        let volume = span(x0, x1).saturating_mul(span(y0, y1)).saturating_mul(span(z0, z1));
+        if volume > self.cells.len() as u64 {
+            for (&(x, y, z), indices) in &self.cells {
+                if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) && (z0..=z1).contains(&z) {
+                    indices.iter().for_each(|&idx| visit(&self.entries[idx]));
+                }
+            }
+            return;
+        }
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                for z in z0..=z1 {
+                    if let Some(indices) = self.cells.get(&(x, y, z)) {
+                        indices.iter().for_each(|&idx| visit(&self.entries[idx]));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pirámide de visión como seis planos (normal hacia dentro, distancia) extraídos de la matriz
+/// proyección * vista; sirve tanto para la perspectiva como para la proyección ortográfica del mapa
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [Vector4; 6],
+}
+
+impl Frustum {
+    pub fn new(view: &Matrix, projection: &Matrix) -> Self {
+        let m = multiply_matrices(projection, view);
+        let row = |r: usize| match r {
+            0 => Vector4::new(m.m0, m.m4, m.m8, m.m12),
+            1 => Vector4::new(m.m1, m.m5, m.m9, m.m13),
+            2 => Vector4::new(m.m2, m.m6, m.m10, m.m14),
+            _ => Vector4::new(m.m3, m.m7, m.m11, m.m15),
+        };
+        let (w, planes) = (row(3), [row(0), row(0), row(1), row(1), row(2), row(2)]);
+        let mut out = [Vector4::new(0.0, 0.0, 0.0, 0.0); 6];
+        for (i, plane) in planes.iter().enumerate() {
+            // Planos pares: w + fila >= 0; impares: w - fila >= 0
+            let sign = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let p = Vector4::new(w.x + sign * plane.x, w.y + sign * plane.y, w.z + sign * plane.z, w.w + sign * plane.w);
+            let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt().max(1e-12);
+            out[i] = Vector4::new(p.x / length, p.y / length, p.z / length, p.w / length);
+        }
+        Frustum { planes: out }
+    }
+
+    /// true si la esfera está al menos en parte dentro (puede dar falsos positivos en las esquinas)
+    pub fn intersects_sphere(&self, center: Vector3, radius: f32) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.x * center.x + plane.y * center.y + plane.z * center.z + plane.w >= -radius)
+    }
+}
+
+fn distance_squared(a: Vector3, b: Vector3) -> f32 {
+    let (dx, dy, dz) = (a.x - b.x, a.y - b.y, a.z - b.z);
+    dx * dx + dy * dy + dz * dz
+}
+
+/// Distancia al cuadrado de `point` al segmento `start` -> `end`
+fn segment_distance_squared(start: Vector3, end: Vector3, point: Vector3) -> f32 {
+    let segment = Vector3::new(end.x - start.x, end.y - start.y, end.z - start.z);
+    let length_squared = segment.x * segment.x + segment.y * segment.y + segment.z * segment.z;
+    let t = if length_squared > 0.0 {
+        let along = (point.x - start.x) * segment.x + (point.y - start.y) * segment.y + (point.z - start.z) * segment.z;
+        (along / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    distance_squared(Vector3::new(start.x + segment.x * t, start.y + segment.y * t, start.z + segment.z * t), point)
+}
+
+/// Distancia al cuadrado de `point` a la caja `min`..`max` (0 si está dentro)
+fn box_distance_squared(min: Vector3, max: Vector3, point: Vector3) -> f32 {
+    let axis = |p: f32, lo: f32, hi: f32| (lo - p).max(0.0).max(p - hi);
+    let (dx, dy, dz) = (axis(point.x, min.x, max.x), axis(point.y, min.y, max.y), axis(point.z, min.z, max.z));
+    dx * dx + dy * dy + dz * dz
+}
@@ -0,0 +1,183 @@
+use raylib::prelude::*;
+use crate::color::blackbody_srgb;
+
+/// Temperatura efectiva del sol real, referencia para el color de la luz
+pub const SUN_TEMPERATURE: f32 = 5778.0;
+
+/// Clase espectral de Harvard, de la más caliente (O) a la más fría (M)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpectralClass {
+    O,
+    B,
+    A,
+    F,
+    G,
+    K,
+    M,
+}
+
+impl SpectralClass {
+    /// Clase a partir de su letra (`"G"`, `"m"`...)
+    pub fn from_letter(letter: &str) -> Option<SpectralClass> {
+        match letter.to_ascii_uppercase().as_str() {
+            "O" => Some(SpectralClass::O),
+            "B" => Some(SpectralClass::B),
+            "A" => Some(SpectralClass::A),
+            "F" => Some(SpectralClass::F),
+            "G" => Some(SpectralClass::G),
+            "K" => Some(SpectralClass::K),
+            "M" => Some(SpectralClass::M),
+            _ => None,
+        }
+    }
+
+    /// Clase a la que pertenece una temperatura efectiva (K)
+    pub fn from_temperature(kelvin: f32) -> SpectralClass {
+        match kelvin {
+            t if t >= 30000.0 => SpectralClass::O,
+            t if t >= 10000.0 => SpectralClass::B,
+            t if t >= 7500.0 => SpectralClass::A,
+            t if t >= 6000.0 => SpectralClass::F,
+            t if t >= 5200.0 => SpectralClass::G,
+            t if t >= 3700.0 => SpectralClass::K,
+            _ => SpectralClass::M,
+        }
+    }
+
+    pub fn letter(&self) -> &'static str {
+        match self {
+            SpectralClass::O => "O",
+            SpectralClass::B => "B",
+            SpectralClass::A => "A",
+            SpectralClass::F => "F",
+            SpectralClass::G => "G",
+            SpectralClass::K => "K",
+            SpectralClass::M => "M",
+        }
+    }
+
+    /// Temperatura efectiva representativa de la clase (K)
+    pub fn temperature(&self) -> f32 {
+        match self {
+            SpectralClass::O => 35000.0,
+            SpectralClass::B => 20000.0,
+            SpectralClass::A => 8500.0,
+            SpectralClass::F => 6500.0,
+            SpectralClass::G => SUN_TEMPERATURE,
+            SpectralClass::K => 4500.0,
+            SpectralClass::M => 3200.0,
+        }
+    }
+}
+
+/// Colores (sRGB) de la superficie de la estrella, de la zona más caliente a las manchas
+#[derive(Clone, Copy, Debug)]
+pub struct StarPalette {
+    pub hot: Vector3,
+    pub medium: Vector3,
+    pub warm: Vector3,
+    pub cool: Vector3,
+    pub sunspot: Vector3,
+}
+
+impl StarPalette {
+    /// Paleta de cuerpo negro: cada banda es la estrella a una fracción de su temperatura,
+    /// así una G sale amarilla con bordes naranjas, una M roja y una B blanco azulada
+    pub fn blackbody(temperature: f32) -> Self {
+        let sunspot = blackbody_srgb(temperature * 0.25);
+        StarPalette {
+            hot: blackbody_srgb(temperature),
+            medium: blackbody_srgb(temperature * 0.5),
+            warm: blackbody_srgb(temperature * 0.4),
+            cool: blackbody_srgb(temperature * 0.32),
+            sunspot: Vector3::new(sunspot.x * 0.4, sunspot.y * 0.4, sunspot.z * 0.4),
+        }
+    }
+}
+
+/// Estrella central del sistema: temperatura efectiva, fuerza de la corona y frecuencia de
+/// llamaradas. Parametriza `shader_sun` y el color de la luz que reciben los planetas
+#[derive(Clone, Debug)]
+pub struct Star {
+    temperature: f32,         // Temperatura efectiva (K)
+    pub corona_strength: f32, // 0 = sin corona, 1 = como el sol, > 1 más intensa
+    pub flare_frequency: f32, // Fracción de la superficie en llamarada relativa al sol (1 = como el sol)
+    palette: StarPalette,     // Derivada de la temperatura (se recalcula al cambiarla)
+}
+
+impl Star {
+    pub fn new(temperature: f32, corona_strength: f32, flare_frequency: f32) -> Self {
+        Star {
+            temperature,
+            corona_strength,
+            flare_frequency,
+            palette: StarPalette::blackbody(temperature),
+        }
+    }
+
+    /// Estrella típica de una clase espectral: las enanas rojas tienen coronas débiles y
+    /// llamaradas frecuentes, las gigantes azules coronas intensas y pocas llamaradas
+    pub fn from_class(class: SpectralClass) -> Self {
+        let (corona_strength, flare_frequency) = match class {
+            SpectralClass::O | SpectralClass::B => (1.5, 0.5),
+            SpectralClass::A | SpectralClass::F => (1.2, 0.8),
+            SpectralClass::G => (1.0, 1.0),
+            SpectralClass::K => (0.8, 1.4),
+            SpectralClass::M => (0.6, 2.0),
+        };
+        Star::new(class.temperature(), corona_strength, flare_frequency)
+    }
+
+    /// Enana roja (M, ~3200 K)
+    pub fn red_dwarf() -> Self {
+        Star::from_class(SpectralClass::M)
+    }
+
+    /// Enana amarilla como el sol (G2, 5778 K)
+    pub fn yellow_dwarf() -> Self {
+        Star::from_class(SpectralClass::G)
+    }
+
+    /// Gigante azul (B, ~20000 K)
+    pub fn blue_giant() -> Self {
+        Star::from_class(SpectralClass::B)
+    }
+
+    pub fn temperature(&self) -> f32 {
+        self.temperature
+    }
+
+    pub fn set_temperature(&mut self, temperature: f32) {
+        self.temperature = temperature;
+        self.palette = StarPalette::blackbody(temperature);
+    }
+
+    pub fn spectral_class(&self) -> SpectralClass {
+        SpectralClass::from_temperature(self.temperature)
+    }
+
+    pub fn palette(&self) -> &StarPalette {
+        &self.palette
+    }
+
+    /// Color de la luz que emite, relativo al sol (una G2 da luz blanca) y con el canal más
+    /// brillante en 1. El cociente se toma entre colores sRGB para que el tinte sea perceptible
+    /// pero no domine el color de los planetas
+    pub fn light_color(&self) -> Vector3 {
+        let color = blackbody_srgb(self.temperature);
+        let sun = blackbody_srgb(SUN_TEMPERATURE);
+        let relative = Vector3::new(
+            color.x / sun.x.max(0.0001),
+            color.y / sun.y.max(0.0001),
+            color.z / sun.z.max(0.0001),
+        );
+        let max = relative.x.max(relative.y).max(relative.z).max(0.0001);
+        Vector3::new(relative.x / max, relative.y / max, relative.z / max)
+    }
+}
+
+impl Default for Star {
+    fn default() -> Self {
+        Star::yellow_dwarf()
+    }
+}
@@ -0,0 +1,207 @@
+use raylib::prelude::*;
+use crate::color::srgb_to_linear_rgb;
+use crate::framebuffer::Framebuffer;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Depth used for the background so every body is drawn in front of it
+pub const BACKGROUND_DEPTH: f32 = 999.0;
+
+/// Star field settings
+#[derive(Clone, Debug)]
+pub struct StarfieldConfig {
+    pub count: usize,
+    pub min_brightness: f32,
+    pub max_brightness: f32,
+    pub brightness_exponent: f32, // > 1.0 makes dim stars more common than bright ones
+    pub colored_fraction: f32,    // Fraction of stars tinted as blue/red giants
+    pub twinkle_amount: f32,      // Maximum brightness loss while twinkling (0.0 = static)
+    pub twinkle_speed: f32,       // Base twinkle frequency in radians per second
+    pub milky_way: bool,          // Draw the procedural Milky Way band
+    pub milky_way_intensity: f32,
+    pub milky_way_width: f32,     // Band half-width as a fraction of the screen diagonal
+}
+
+impl Default for StarfieldConfig {
+    fn default() -> Self {
+        StarfieldConfig {
+            count: 2000,
+            min_brightness: 0.3,
+            max_brightness: 1.0,
+            brightness_exponent: 2.0,
+            colored_fraction: 0.06,
+            twinkle_amount: 0.35,
+            twinkle_speed: 2.0,
+            milky_way: true,
+            milky_way_intensity: 0.12,
+            milky_way_width: 0.12,
+        }
+    }
+}
+
+struct Star {
+    x: i32,
+    y: i32,
+    color: Vector3,      // Base color including brightness (linear)
+    twinkle_rate: f32,   // Per-star frequency multiplier
+    twinkle_phase: f32,
+}
+
+/// Screen-space star background with twinkling stars and an optional Milky Way band
+pub struct Starfield {
+    pub config: StarfieldConfig,
+    stars: Vec<Star>,
+    milky_way: Vec<(i32, i32, Vector3)>, // Precomputed band pixels
+}
+
+impl Starfield {
+    /// Generates the star field with a fixed seed so it looks the same on every run
+    pub fn new(config: StarfieldConfig, width: i32, height: i32) -> Self {
+        let mut hasher = DefaultHasher::new();
+        "star_seed".hash(&mut hasher);
+        let mut rng = Lcg(hasher.finish());
+
+        let mut stars = Vec::with_capacity(config.count);
+        for _ in 0..config.count {
+            let x = (rng.next_u64() % width.max(1) as u64) as i32;
+            let y = (rng.next_u64() % height.max(1) as u64) as i32;
+
+            let brightness = config.min_brightness
+                + (config.max_brightness - config.min_brightness) * rng.next_f32().powf(config.brightness_exponent);
+
+            // A few stars are tinted: blue giants, red giants and yellow stars
+            let tint = if rng.next_f32() < config.colored_fraction {
+                match rng.next_u64() % 3 {
+                    0 => Vector3::new(0.6, 0.75, 1.0),
+                    1 => Vector3::new(1.0, 0.55, 0.4),
+                    _ => Vector3::new(1.0, 0.95, 0.7),
+                }
+            } else {
+                Vector3::new(1.0, 1.0, 1.0)
+            };
+
+            stars.push(Star {
+                x,
+                y,
+                color: srgb_to_linear_rgb(Vector3::new(tint.x * brightness, tint.y * brightness, tint.z * brightness)),
+                twinkle_rate: 0.5 + rng.next_f32(),
+                twinkle_phase: rng.next_f32() * std::f32::consts::TAU,
+            });
+        }
+
+        let milky_way = if config.milky_way {
+            generate_milky_way(&config, width, height)
+        } else {
+            Vec::new()
+        };
+
+        Starfield { config, stars, milky_way }
+    }
+
+    /// Draws the background into a freshly cleared framebuffer
+    /// Stars go first: the band uses the same depth, so it never overwrites a star
+    pub fn render(&self, framebuffer: &mut Framebuffer, time: f32) {
+        for star in &self.stars {
+            let twinkle = 1.0
+                - self.config.twinkle_amount
+                    * (0.5 + 0.5 * (time * self.config.twinkle_speed * star.twinkle_rate + star.twinkle_phase).sin());
+            let color = Vector3::new(star.color.x * twinkle, star.color.y * twinkle, star.color.z * twinkle);
+            framebuffer.point(star.x, star.y, color, BACKGROUND_DEPTH);
+        }
+
+        if self.config.milky_way {
+            for &(x, y, color) in &self.milky_way {
+                framebuffer.point(x, y, color, BACKGROUND_DEPTH);
+            }
+        }
+    }
+}
+
+/// Diagonal band of faint light modulated by fractal value noise
+fn generate_milky_way(config: &StarfieldConfig, width: i32, height: i32) -> Vec<(i32, i32, Vector3)> {
+    let diagonal = ((width * width + height * height) as f32).sqrt();
+    let half_width = config.milky_way_width * diagonal;
+
+    // Band direction: from the bottom-left corner to the top-right corner
+    let dir_x = width as f32 / diagonal;
+    let dir_y = -(height as f32) / diagonal;
+
+    let mut pixels = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            // Perpendicular distance to the line through the screen center
+            let px = x as f32 - width as f32 / 2.0;
+            let py = y as f32 - height as f32 / 2.0;
+            let distance = (px * dir_y - py * dir_x).abs();
+            let band = (-(distance / half_width).powi(2)).exp();
+            if band < 0.02 {
+                continue;
+            }
+
+            let noise = fbm(x as f32 / 40.0, y as f32 / 40.0);
+            let intensity = band * noise * config.milky_way_intensity;
+            if intensity < 0.01 {
+                continue;
+            }
+            // Slightly warm core, bluish edges
+            let warmth = band;
+            pixels.push((
+                x,
+                y,
+                srgb_to_linear_rgb(Vector3::new(
+                    intensity * (0.8 + 0.2 * warmth),
+                    intensity * 0.85,
+                    intensity * (1.0 - 0.2 * warmth),
+                )),
+            ));
+        }
+    }
+    pixels
+}
+
+/// Simple LCG, same constants as the original star generator
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        self.0
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// Hash-based value noise in [0, 1] on an integer lattice
+fn lattice(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(374761393) ^ (y as u32).wrapping_mul(668265263);
+    h = (h ^ (h >> 13)).wrapping_mul(1274126177);
+    (h ^ (h >> 16)) as f32 / u32::MAX as f32
+}
+
+fn value_noise(x: f32, y: f32) -> f32 {
+    let x0 = x.floor() as i32;
+    let y0 = y.floor() as i32;
+    let fx = x - x0 as f32;
+    let fy = y - y0 as f32;
+    let sx = fx * fx * (3.0 - 2.0 * fx);
+    let sy = fy * fy * (3.0 - 2.0 * fy);
+
+    let top = lattice(x0, y0) + (lattice(x0 + 1, y0) - lattice(x0, y0)) * sx;
+    let bottom = lattice(x0, y0 + 1) + (lattice(x0 + 1, y0 + 1) - lattice(x0, y0 + 1)) * sx;
+    top + (bottom - top) * sy
+}
+
+/// Four octaves of value noise, normalized to [0, 1]
+fn fbm(x: f32, y: f32) -> f32 {
+    let mut sum = 0.0;
+    let mut amplitude = 0.5;
+    let mut frequency = 1.0;
+    for _ in 0..4 {
+        sum += value_noise(x * frequency, y * frequency) * amplitude;
+        amplitude *= 0.5;
+        frequency *= 2.0;
+    }
+    sum / 0.9375
+}
@@ -0,0 +1,80 @@
+use raylib::prelude::*;
+use crate::render_stats::RenderStats;
+use crate::screen_texture::ScreenTexture;
+use std::time::Duration;
+
+/// Panel de estadísticas del pipeline (acción `DebugStats`): triángulos enviados, descartados y
+/// recortados, fragmentos generados, sombreados y rechazados por profundidad, el tiempo de cada
+/// etapa, los objetos más caros del frame y la subida de la textura del framebuffer
+pub struct StatsOverlay {
+    pub visible: bool,
+    pub max_objects: usize, // Objetos listados (los más caros primero)
+}
+
+impl StatsOverlay {
+    pub fn new() -> Self {
+        StatsOverlay { visible: false, max_objects: 8 }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Dibuja el panel pegado al borde derecho de una ventana de `width` píxeles, desde `y`
+    pub fn draw(&self, d: &mut RaylibDrawHandle, stats: &RenderStats, screen_texture: &ScreenTexture, width: i32, y: i32) {
+        if !self.visible {
+            return;
+        }
+
+        let ms = |time: Duration| time.as_secs_f32() * 1000.0;
+        let gpu = if stats.gpu_fragments > 0 { format!(" ({} en GPU)", stats.gpu_fragments) } else { String::new() };
+        let mut lines = vec![
+            format!(
+                "Triángulos: {} enviados  {} descartados  {} recortados",
+                stats.triangles, stats.culled_triangles, stats.clipped_triangles
+            ),
+            format!(
+                "Fragmentos: {} generados  {} sombreados{}  {} tapados",
+                stats.fragments, stats.shaded_fragments, gpu, stats.depth_rejected_fragments
+            ),
+            format!(
+                "Etapas (ms): vertex {:.2}  raster {:.2}  fragment {:.2}",
+                ms(stats.vertex_time),
+                ms(stats.raster_time),
+                ms(stats.fragment_time)
+            ),
+            format!(
+                "Subida de textura: {:.2} ms ({} px)  hilo {:.2} ms  blit {:.2} ms",
+                ms(screen_texture.upload_time()),
+                screen_texture.uploaded_pixels(),
+                ms(screen_texture.encode_time()),
+                ms(stats.blit_time)
+            ),
+            "Objetos (ms):".to_string(),
+        ];
+        let mut objects: Vec<&(&str, Duration)> = stats.objects.iter().collect();
+        objects.sort_by_key(|(_, time)| std::cmp::Reverse(*time));
+        for (name, time) in objects.into_iter().take(self.max_objects) {
+            lines.push(format!("  {:<18} {:6.2}", name, ms(*time)));
+        }
+
+        let font_size = 10;
+        let line_height = 13;
+        let panel_width = lines.iter().map(|line| measure_text(line, font_size)).max().unwrap_or(0) + 20;
+        let panel_height = 28 + lines.len() as i32 * line_height;
+        let x = width - panel_width - 10;
+        let accent = Color::new(120, 200, 255, 255);
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 190));
+        d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+        d.draw_text("PIPELINE", x + 10, y + 8, 12, accent);
+        for (i, line) in lines.iter().enumerate() {
+            d.draw_text(line, x + 10, y + 24 + i as i32 * line_height, font_size, Color::new(200, 210, 220, 255));
+        }
+    }
+}
+
+impl Default for StatsOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
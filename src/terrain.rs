@@ -0,0 +1,205 @@
+use raylib::prelude::*;
+use crate::matrix::inverse_rotate_vector;
+use crate::obj::Obj;
+use crate::scene::Planet;
+use crate::shaders::{rocky_terrain_noise, PlanetType};
+use crate::vertex::Vertex;
+use std::f32::consts::PI;
+
+/// Caras del cubo que se proyecta sobre la esfera: normal y los ejes u y v de cada cara
+const CUBE_FACES: [[[f32; 3]; 3]; 6] = [
+    [[1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]],
+    [[-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]],
+    [[0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+    [[0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]],
+    [[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+    [[0.0, 0.0, -1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+];
+
+/// Trozo de una cara del cubo: esquina (u, v) y lado en coordenadas de la cara ([-1, 1])
+#[derive(Clone, Copy, Debug)]
+struct Patch {
+    face: usize,
+    u: f32,
+    v: f32,
+    size: f32,
+}
+
+/// Relieve de cerca de los planetas rocosos: cuando la nave se acerca, la esfera del planeta se
+/// sustituye por un cubo proyectado sobre ella cuyas caras se dividen en un árbol cuaternario
+/// (más fino cuanto más cerca del observador) y cuyos vértices se desplazan con el mismo ruido
+/// que pinta el shader (`rocky_terrain_noise`), así que las montañas son geometría de verdad
+pub struct SurfaceTerrain {
+    pub approach_radii: f32, // Distancia al centro (en radios del planeta) desde la que se dibuja el relieve
+    pub relief: f32,         // Altura entre el valle más hondo y el pico más alto (en radios)
+    pub max_depth: u32,      // Niveles del árbol cuaternario bajo cada cara del cubo
+    pub patch_segments: u32, // Celdas por lado de cada parche
+    pub split_distance: f32, // Un parche se divide si el observador está a menos de este múltiplo de su lado
+}
+
+impl SurfaceTerrain {
+    pub fn new() -> Self {
+        SurfaceTerrain {
+            approach_radii: 3.0,
+            relief: 0.08,
+            max_depth: 6,
+            patch_segments: 8,
+            split_distance: 1.5,
+        }
+    }
+
+    /// Si `planet` se dibuja con relieve con el observador en `point` (espacio de mundo): solo los
+    /// planetas rocosos con el shader de su tipo, y solo de cerca
+    pub fn engages(&self, planet: &Planet, point: Vector3) -> bool {
+        if !matches!(planet.planet_type, PlanetType::Rocky) || planet.shader.is_some() {
+            return false;
+        }
+        let position = planet.position();
+        let (dx, dy, dz) = (point.x - position.x, point.y - position.y, point.z - position.z);
+        (dx * dx + dy * dy + dz * dz).sqrt() < self.approach_radii * planet.scale
+    }
+
+    /// Radio del terreno (la esfera unitaria es 1) en la dirección `direction` en el instante `time`
+    pub fn height(&self, direction: Vector3, time: f32) -> f32 {
+        1.0 + self.relief * (rocky_terrain_noise(direction, time) - 0.5)
+    }
+
+    /// Malla de relieve de `planet` vista desde `point` (espacio de mundo) tras `hours` horas
+    /// simuladas; en espacio de objeto, para dibujarla en lugar de la esfera unitaria
+    pub fn build_for(&self, planet: &Planet, point: Vector3, hours: f64, time: f32) -> Obj {
+        let position = planet.position();
+        let local = inverse_rotate_vector(
+            Vector3::new(point.x - position.x, point.y - position.y, point.z - position.z),
+            planet.rotation(hours),
+        );
+        let scale = planet.scale.max(0.0001);
+        self.build(Vector3::new(local.x / scale, local.y / scale, local.z / scale), time)
+    }
+
+    /// Malla de relieve con el observador en `viewer` (espacio de objeto de la esfera unitaria)
+    /// Los parches vecinos de distinto nivel no comparten vértices: cada uno lleva un faldón que
+    /// baja bajo el valle más hondo y tapa las grietas entre ellos
+    pub fn build(&self, viewer: Vector3, time: f32) -> Obj {
+        let mut patches = Vec::new();
+        for face in 0..CUBE_FACES.len() {
+            self.subdivide(Patch { face, u: -1.0, v: -1.0, size: 2.0 }, 0, viewer, &mut patches);
+        }
+
+        let mut mesh = Obj { vertices: Vec::new(), indices: Vec::new() };
+        for patch in patches {
+            self.push_patch(&mut mesh, patch, time);
+        }
+        mesh
+    }
+
+    fn subdivide(&self, patch: Patch, depth: u32, viewer: Vector3, patches: &mut Vec<Patch>) {
+        let half = patch.size * 0.5;
+        let center = cube_to_sphere(patch.face, patch.u + half, patch.v + half);
+        let (dx, dy, dz) = (viewer.x - center.x, viewer.y - center.y, viewer.z - center.z);
+        let distance = (dx * dx + dy * dy + dz * dz).sqrt();
+        if depth >= self.max_depth || distance >= self.split_distance * patch.size {
+            patches.push(patch);
+            return;
+        }
+        for (du, dv) in [(0.0, 0.0), (half, 0.0), (0.0, half), (half, half)] {
+            let child = Patch { face: patch.face, u: patch.u + du, v: patch.v + dv, size: half };
+            self.subdivide(child, depth + 1, viewer, patches);
+        }
+    }
+
+    fn push_patch(&self, mesh: &mut Obj, patch: Patch, time: f32) {
+        let segments = self.patch_segments.max(1);
+        let step = patch.size / segments as f32;
+        let base = mesh.vertices.len() as u32;
+        let surface = |u: f32, v: f32| {
+            let direction = cube_to_sphere(patch.face, u, v);
+            let height = self.height(direction, time);
+            Vector3::new(direction.x * height, direction.y * height, direction.z * height)
+        };
+
+        for i in 0..=segments {
+            for j in 0..=segments {
+                let (u, v) = (patch.u + step * j as f32, patch.v + step * i as f32);
+                let position = surface(u, v);
+
+                // Normal por diferencias centrales sobre la superficie desplazada
+                let e = step * 0.5;
+                let (du0, du1, dv0, dv1) = (surface(u - e, v), surface(u + e, v), surface(u, v - e), surface(u, v + e));
+                let tangent_u = Vector3::new(du1.x - du0.x, du1.y - du0.y, du1.z - du0.z);
+                let tangent_v = Vector3::new(dv1.x - dv0.x, dv1.y - dv0.y, dv1.z - dv0.z);
+                let mut normal = normalize(Vector3::new(
+                    tangent_u.y * tangent_v.z - tangent_u.z * tangent_v.y,
+                    tangent_u.z * tangent_v.x - tangent_u.x * tangent_v.z,
+                    tangent_u.x * tangent_v.y - tangent_u.y * tangent_v.x,
+                ));
+                if normal.x * position.x + normal.y * position.y + normal.z * position.z < 0.0 {
+                    normal = Vector3::new(-normal.x, -normal.y, -normal.z);
+                }
+                mesh.vertices.push(Vertex::new(position, normal, spherical_tex_coords(position)));
+            }
+        }
+
+        let row = segments + 1;
+        for i in 0..segments {
+            for j in 0..segments {
+                let first = base + i * row + j;
+                let third = first + row;
+                mesh.indices.extend_from_slice(&[first, first + 1, third]);
+                mesh.indices.extend_from_slice(&[first + 1, third + 1, third]);
+            }
+        }
+
+        // Faldones: cada borde se repite por debajo del terreno más bajo
+        let edges: [Vec<u32>; 4] = [
+            (0..row).map(|j| base + j).collect(),
+            (0..row).map(|j| base + segments * row + j).collect(),
+            (0..row).map(|i| base + i * row).collect(),
+            (0..row).map(|i| base + i * row + segments).collect(),
+        ];
+        let depth = 1.0 - self.relief;
+        for edge in edges {
+            let skirt = mesh.vertices.len() as u32;
+            for &index in &edge {
+                let top = &mesh.vertices[index as usize];
+                let (normal, tex_coords) = (top.normal, top.tex_coords);
+                let p = normalize(top.position);
+                let position = Vector3::new(p.x * depth, p.y * depth, p.z * depth);
+                mesh.vertices.push(Vertex::new(position, normal, tex_coords));
+            }
+            for k in 0..segments as usize {
+                let (a, b) = (edge[k], edge[k + 1]);
+                let (a_low, b_low) = (skirt + k as u32, skirt + k as u32 + 1);
+                mesh.indices.extend_from_slice(&[a, b, a_low]);
+                mesh.indices.extend_from_slice(&[b, b_low, a_low]);
+            }
+        }
+    }
+}
+
+impl Default for SurfaceTerrain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Punto (u, v) de la cara `face` del cubo proyectado sobre la esfera unitaria
+fn cube_to_sphere(face: usize, u: f32, v: f32) -> Vector3 {
+    let [n, a, b] = CUBE_FACES[face];
+    normalize(Vector3::new(n[0] + a[0] * u + b[0] * v, n[1] + a[1] * u + b[1] * v, n[2] + a[2] * u + b[2] * v))
+}
+
+fn normalize(v: Vector3) -> Vector3 {
+    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+    if length > 0.0 {
+        Vector3::new(v.x / length, v.y / length, v.z / length)
+    } else {
+        v
+    }
+}
+
+/// Coordenadas de textura con la misma longitud y latitud que `Obj::generate_sphere`
+fn spherical_tex_coords(p: Vector3) -> Vector2 {
+    let p = normalize(p);
+    let longitude = p.z.atan2(p.x).rem_euclid(2.0 * PI);
+    Vector2::new(longitude / (2.0 * PI), p.y.clamp(-1.0, 1.0).acos() / PI)
+}
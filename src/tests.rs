@@ -0,0 +1,28 @@
+//! Pruebas de los módulos del juego. Son privados del binario, así que sus pruebas viven aquí
+//! (una por subsistema, como las de la biblioteca en `tests/`) y no en pruebas de integración
+
+use std::fs;
+use std::path::PathBuf;
+
+mod assets;
+mod camera_path;
+mod console;
+mod daylight;
+mod god_rays;
+mod hangar;
+mod quality;
+mod replay;
+mod ring_particles;
+mod sas;
+mod scripting;
+mod secondary_view;
+mod terrain;
+mod tour;
+mod trajectory;
+
+/// Ruta de un archivo temporal de las pruebas (el binario no tiene `CARGO_TARGET_TMPDIR`)
+fn temp_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(env!("CARGO_PKG_NAME"));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
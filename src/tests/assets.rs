@@ -0,0 +1,61 @@
+//! Pruebas del gestor de recursos: carga en segundo plano, caché y recarga en caliente
+
+use crate::assets::{AssetEvent, AssetManager, AssetState};
+use crate::obj::LoadOptions;
+use computer_graphics_v3::Obj;
+use super::temp_path;
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+#[test]
+fn asset_manager_loads_in_the_background_and_reloads_changed_files() {
+    let dir = temp_path("asset_manager");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("triangle.obj");
+    let path_str = path.to_str().unwrap();
+    fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+    let _ = fs::remove_file(dir.join("missing.obj"));
+
+    let mut assets = AssetManager::new(2, Obj::generate_sphere(1.0, 8));
+    let placeholder = assets.mesh_or_placeholder(path_str);
+    assets.request_mesh(path_str, &LoadOptions::default());
+    assets.request_mesh(dir.join("missing.obj").to_str().unwrap(), &LoadOptions::default());
+    assert_eq!(assets.state(path_str), Some(&AssetState::Loading));
+    assert_eq!(assets.progress(), (0, 2));
+
+    // Hasta que un hilo termina se dibuja la malla provisional
+    let wait_for = |assets: &mut AssetManager, wanted: &dyn Fn(&AssetEvent) -> bool| {
+        let start = Instant::now();
+        let mut events = Vec::new();
+        while !events.iter().any(wanted) {
+            assert!(start.elapsed().as_secs() < 10, "la carga no terminó: {:?}", events);
+            events.extend(assets.update());
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        events
+    };
+    wait_for(&mut assets, &|event| matches!(event, AssetEvent::Loaded(key) if key == path_str));
+    if assets.failures().is_empty() {
+        wait_for(&mut assets, &|event| matches!(event, AssetEvent::Failed(..)));
+    }
+    let mesh = assets.mesh(path_str).expect("cargada");
+    assert_eq!(mesh.indices.len(), 3);
+    assert!(Arc::ptr_eq(&assets.mesh_or_placeholder(path_str), &mesh));
+    assert!(!Arc::ptr_eq(&mesh, &placeholder));
+    assert_eq!(assets.progress(), (2, 2));
+    assert_eq!(assets.failures().len(), 1, "el archivo que falta no se oculta");
+
+    // Pedirla otra vez sale de la caché
+    assets.request_mesh(path_str, &LoadOptions::default());
+    assert!(Arc::ptr_eq(&assets.mesh(path_str).unwrap(), &mesh));
+
+    // Al cambiar el archivo se recarga y, mientras tanto, se sigue viendo la versión anterior
+    fs::write(&path, "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 3\nf 2 4 3\n").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(10);
+    fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+    assets.poll_changes();
+    assert_eq!(assets.mesh(path_str).unwrap().indices.len(), 3);
+    wait_for(&mut assets, &|event| matches!(event, AssetEvent::Reloaded(key) if key == path_str));
+    assert_eq!(assets.mesh(path_str).unwrap().indices.len(), 6);
+}
@@ -0,0 +1,32 @@
+//! Pruebas de las trayectorias de cámara guardadas (`CameraPath::save`/`CameraPath::parse`)
+
+use crate::camera_path::CameraPath;
+use super::temp_path;
+use raylib::prelude::*;
+
+#[test]
+fn camera_path_round_trips_and_rejects_malformed_lines() {
+    let mut camera_path = CameraPath::new();
+    camera_path.add_keyframe(Vector3::new(0.1, 2.0, -3.5), Vector3::zero(), 0.0);
+    camera_path.add_keyframe(Vector3::new(10.0, 0.0, 1.0 / 3.0), Vector3::new(1.0, 2.0, 3.0), 2.5);
+    camera_path.add_keyframe(Vector3::new(-4.0, 1.0e-3, 7.25), Vector3::new(0.0, 1.0, 0.0), 1.0);
+
+    let path = temp_path("round_trip.path");
+    camera_path.save(path.to_str().unwrap()).unwrap();
+    let loaded = CameraPath::load(path.to_str().unwrap()).unwrap();
+    assert_eq!(loaded.keyframes.len(), 3);
+    for (a, b) in loaded.keyframes.iter().zip(&camera_path.keyframes) {
+        assert_eq!((a.time, a.position, a.target), (b.time, b.position, b.target));
+    }
+    // Se reordenan por tiempo al cargar, también si el archivo está desordenado
+    let shuffled = CameraPath::parse("# vuelo\n\n2 0 0 0 0 0 0\n1 0 0 0 0 0 0\n").unwrap();
+    assert_eq!(shuffled.keyframes.iter().map(|k| k.time).collect::<Vec<_>>(), vec![1.0, 2.0]);
+
+    let malformed = [("0 1 2 3 4 5\n", "línea 1"), ("0 0 0 0 0 0 0\n1 0 0 x 0 0 0\n", "línea 2")];
+    for (contents, line) in malformed {
+        let Err(error) = CameraPath::parse(contents) else {
+            panic!("'{}' debería ser un error", contents.trim());
+        };
+        assert!(error.to_string().contains(line), "{}: {}", contents.trim(), error);
+    }
+}
@@ -0,0 +1,22 @@
+//! Pruebas de las órdenes de la consola (`parse_command`)
+
+use crate::console::{parse_command, Command, SpawnKind};
+use raylib::prelude::*;
+
+#[test]
+fn console_parses_commands() {
+    assert_eq!(parse_command("warp 10 5 -30"), Ok(Command::Warp(Vector3::new(10.0, 5.0, -30.0))));
+    assert_eq!(parse_command("  TimeScale   50 "), Ok(Command::TimeScale(50.0)));
+    assert_eq!(parse_command("spawn asteroid 100"), Ok(Command::Spawn { kind: SpawnKind::Asteroid, count: 100 }));
+    assert_eq!(parse_command("spawn asteroid"), Ok(Command::Spawn { kind: SpawnKind::Asteroid, count: 1 }));
+    assert_eq!(parse_command("track Estación Marte"), Ok(Command::Track("Estación Marte".to_string())));
+    // Por nombre, el nivel más alto con esa calidad de shaders; también en español y por índice
+    assert_eq!(parse_command("quality low"), Ok(Command::Quality(0)));
+    assert_eq!(parse_command("quality media"), Ok(Command::Quality(2)));
+    assert_eq!(parse_command("quality ultra"), Ok(Command::Quality(4)));
+    assert_eq!(parse_command("quality 3"), Ok(Command::Quality(3)));
+
+    for bad in ["", "warp 1 2", "warp 1 2 x", "timescale 0", "spawn ship 3", "spawn asteroid 0", "track", "quality 9", "jump"] {
+        assert!(parse_command(bad).is_err(), "'{}' no debería ser válida", bad);
+    }
+}
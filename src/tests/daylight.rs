@@ -0,0 +1,40 @@
+//! Pruebas del modo de día y noche (terminador, altura del sol y hora local)
+
+use crate::daylight::DaylightView;
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn daylight_view_finds_the_terminator_and_the_next_sunset() {
+    let scene = Scene::parse("[planet]\nname = Dia\norbital_radius = 20\norbital_speed = 0\nscale = 2\nrotation_period = 24\n").unwrap();
+    let planet = &scene.planets[0];
+    let mut daylight = DaylightView::new();
+    assert!(daylight.readout(&scene, 0.0, Vector3::zero(), 1.0).is_none());
+
+    // El terminador está a 0° de altura del sol, con una mitad amaneciendo y la otra anocheciendo
+    let terminator = daylight.elevation_circle(planet, 0.0);
+    for &point in &terminator {
+        assert!(DaylightView::sun_elevation(planet, point).abs() < 0.01);
+    }
+    let dawn = terminator[..terminator.len() - 1].iter().filter(|&&point| DaylightView::is_dawn(planet, 0.0, point)).count();
+    assert!((dawn as i32 - daylight.segments as i32 / 2).abs() <= 2, "{} de {} puntos amaneciendo", dawn, daylight.segments);
+
+    // Sobre el punto subsolar es mediodía y el sol se pone un cuarto de día después
+    let center = planet.position();
+    let sun = DaylightView::sun_direction(planet);
+    let noon = Vector3::new(center.x + sun.x * 3.0, center.y + sun.y * 3.0, center.z + sun.z * 3.0);
+    assert!((DaylightView::sun_elevation(planet, noon) - 90.0).abs() < 0.01);
+    assert!((planet.local_time_of_day(0.0, noon) - 12.0).abs() < 0.01);
+    let (wait, sunrise) = DaylightView::next_terminator_crossing(planet, 0.0, noon).expect("el sol se pone");
+    assert!(!sunrise && (wait - 6.0).abs() < 0.01, "atardecer en {} h", wait);
+
+    // En la cara de noche lo siguiente es el amanecer
+    let midnight = Vector3::new(center.x - sun.x * 3.0, center.y - sun.y * 3.0, center.z - sun.z * 3.0);
+    let (wait, sunrise) = DaylightView::next_terminator_crossing(planet, 0.0, midnight).expect("el sol sale");
+    assert!(sunrise && (wait - 6.0).abs() < 0.01, "amanecer en {} h", wait);
+
+    assert_eq!(daylight.toggle(Some(0)), Some(0));
+    let text = daylight.readout(&scene, 0.0, noon, 2.0).unwrap();
+    assert!(text.contains("Dia") && text.contains("12:00") && text.contains("atardecer") && text.contains("3 s"), "{}", text);
+    assert_eq!(daylight.toggle(Some(0)), None);
+}
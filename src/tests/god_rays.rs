@@ -0,0 +1,48 @@
+//! Pruebas de los rayos de luz del sol y de su corona
+
+use crate::corona::SunCorona;
+use computer_graphics_v3::Framebuffer;
+use raylib::prelude::*;
+
+#[test]
+fn god_rays_leave_shadow_shafts_behind_occluders() {
+    // Un cuerpo tapa la mitad izquierda del disco del sol: los rayos salen solo por la derecha
+    let mut framebuffer = Framebuffer::new(96, 64);
+    framebuffer.set_dithering(false);
+    framebuffer.set_background_color(Vector3::zero());
+    framebuffer.clear();
+    let (center, radius) = (Vector2::new(48.0, 32.0), 10.0);
+    for y in 0..64 {
+        for x in 0..48 {
+            framebuffer.point(x, y, Vector3::zero(), -0.5);
+        }
+    }
+    framebuffer.god_rays_pass(center, radius, 0.0, Vector3::new(1.0, 0.9, 0.7), 24, 0.97);
+
+    let brightness = |x: usize, y: usize| {
+        let pixels = framebuffer.to_rgb8();
+        let index = (y * 96 + x) * 3;
+        pixels[index] as u32 + pixels[index + 1] as u32 + pixels[index + 2] as u32
+    };
+    assert!(brightness(70, 32) > 0, "los rayos deberían salir del disco visible");
+    assert!(brightness(70, 32) > brightness(26, 32) * 4, "{} frente a {}", brightness(70, 32), brightness(26, 32));
+
+    // Sin nada visible del disco no hay rayos
+    let mut covered = Framebuffer::new(96, 64);
+    covered.set_background_color(Vector3::zero());
+    covered.clear();
+    let before = covered.to_rgb8();
+    for y in 0..64 {
+        for x in 0..96 {
+            covered.point(x, y, Vector3::zero(), -0.5);
+        }
+    }
+    covered.god_rays_pass(center, radius, 0.0, Vector3::new(1.0, 0.9, 0.7), 24, 0.97);
+    assert_eq!(covered.to_rgb8(), before);
+
+    // La corona cae desde el borde del sol y se apaga en el del billboard
+    let corona = SunCorona::new();
+    assert_eq!(corona.corona_profile(0.5, 0.0, 0.0), 0.0);
+    assert!(corona.corona_profile(1.2, 0.0, 0.0) > corona.corona_profile(2.5, 0.0, 0.0));
+    assert_eq!(corona.corona_profile(corona.corona_scale, 0.0, 0.0), 0.0);
+}
@@ -0,0 +1,26 @@
+//! Pruebas del hangar (`HangarConfig::parse`, ver `assets/ships.cfg`)
+
+use crate::hangar::{Hangar, HangarConfig};
+
+#[test]
+fn hangar_cycles_through_the_configured_ships() {
+    let config = HangarConfig::load("assets/ships.cfg").unwrap();
+    assert!(config.ships.len() > 1);
+    assert_eq!(config.ships[0].path, "assets/models/Untitled.obj");
+    for ship in &config.ships {
+        assert!(std::path::Path::new(&ship.path).exists(), "falta {}", ship.path);
+    }
+
+    let config = HangarConfig::parse("[ship]\nname = A\nmodel = a.obj\n[ship]\nname = B\nmodel = b.obj\nscale = 2\nengine_offset = 0.7\n").unwrap();
+    assert_eq!((config.ships[1].scale, config.ships[1].engine_offset, config.ships[1].engine_size), (2.0, 0.7, 0.35));
+    let mut hangar = Hangar::new(config);
+    assert_eq!(hangar.active().name, "A");
+    assert_eq!(hangar.cycle().name, "B");
+    assert_eq!(hangar.cycle().name, "A");
+
+    // Errores con el número de línea
+    assert!(HangarConfig::parse("name = A\n").is_err());
+    assert!(HangarConfig::parse("[ship]\nname = A\n").unwrap_err().to_string().contains("línea 1"));
+    assert!(HangarConfig::parse("[ship]\nname = A\nmodel = a.gltf\n").is_err());
+    assert!(HangarConfig::parse("# vacío\n").is_err());
+}
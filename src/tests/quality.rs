@@ -0,0 +1,28 @@
+//! Pruebas del archivo de calidad adaptativa (`QualityConfig::parse`, ver `assets/quality.cfg`)
+
+use crate::quality::{QualityConfig, QUALITY_LEVELS};
+
+#[test]
+fn quality_config_parses_and_rejects_malformed_lines() {
+    assert_eq!(QualityConfig::parse("# vacío\n").unwrap(), QualityConfig::default());
+    let config = QualityConfig::parse("adaptive = false\ntarget_fps = 30 # consola\nmin_level = 1\nmax_level = 3\n").unwrap();
+    assert_eq!((config.adaptive, config.target_fps, config.min_level, config.max_level), (false, 30.0, 1, 3));
+    assert_eq!(QualityConfig::parse("target_fps = 0\n").unwrap().target_fps, 1.0);
+    QualityConfig::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/quality.cfg")).unwrap();
+
+    let top = QUALITY_LEVELS.len() - 1;
+    let malformed = [
+        ("adaptive\n".to_string(), "línea 1"),
+        ("adaptive = sí\n".to_string(), "línea 1"),
+        ("\nmax_level = {}\n".replace("{}", &(top + 1).to_string()), "línea 2"),
+        ("min_level = -1\n".to_string(), "línea 1"),
+        ("target_fps = 60\nvsync = true\n".to_string(), "línea 2"),
+        ("min_level = 3\nmax_level = 1\n".to_string(), "min_level"),
+    ];
+    for (contents, expected) in malformed {
+        let Err(error) = QualityConfig::parse(&contents) else {
+            panic!("'{}' debería ser un error", contents.trim());
+        };
+        assert!(error.to_string().contains(expected), "{}: {}", contents.trim(), error);
+    }
+}
@@ -0,0 +1,74 @@
+//! Pruebas del formato de las repeticiones (`Replay::save`/`Replay::parse`)
+
+use crate::flight_model::FlightConfig;
+use crate::input_map::Action;
+use crate::input_state::InputState;
+use crate::replay::Replay;
+use super::temp_path;
+use raylib::prelude::*;
+use std::fs;
+
+#[test]
+fn replay_reads_bits_in_the_recorded_action_order() {
+    // Grabada con solo tres acciones y en otro orden: bit 0 = fire, 1 = una que ya no existe, 2 = forward
+    let contents = "\
+actions = fire, old_action, forward
+0.016 0 0 0 1 7 1
+";
+    let replay = Replay::parse(contents).expect("repetición válida");
+    let input = &replay.frames[0].input;
+    assert!(input.is_down(Action::Fire) && input.is_pressed(Action::Fire));
+    assert!(input.is_down(Action::Forward) && !input.is_pressed(Action::Forward));
+    let mut others = Action::ALL.iter().filter(|&&action| action != Action::Fire && action != Action::Forward);
+    assert!(others.all(|&action| !input.is_down(action) && !input.is_pressed(action)));
+
+    // Lo que se guarda lleva el orden actual en la cabecera y se vuelve a leer igual
+    let mut recorded = Replay::new(None, false, Some(7), 3, FlightConfig::default());
+    let mut input = InputState::new();
+    input.press(Action::Controls); // La última de `Action::ALL`
+    input.hold(Action::PitchUp);
+    recorded.record(0.02, &input);
+    let path = temp_path("action_order.replay");
+    recorded.save(path.to_str().unwrap()).unwrap();
+    let saved = fs::read_to_string(&path).unwrap();
+    assert!(saved.lines().any(|line| line.starts_with("actions = pitch_up, pitch_down,")));
+    assert_eq!(Replay::parse(&saved).unwrap().frames[0].input, input);
+}
+
+#[test]
+fn replay_round_trips_and_rejects_malformed_lines() {
+    let flight = FlightConfig { gravity: 0.25, ..FlightConfig::default() };
+    let mut recorded = Replay::new(Some(String::from("real")), true, None, 42, flight);
+    let mut input = InputState::new();
+    input.hold(Action::Forward);
+    input.press(Action::Fire);
+    input.mouse_delta = Vector2::new(1.5, -0.25);
+    input.mouse_wheel = -1.0;
+    input.any_pressed = true;
+    recorded.record(1.0 / 60.0, &input);
+    recorded.record(0.1, &InputState::new());
+
+    let path = temp_path("round_trip.replay");
+    recorded.save(path.to_str().unwrap()).unwrap();
+    let loaded = Replay::load(path.to_str().unwrap()).unwrap();
+    assert_eq!(loaded.preset.as_deref(), Some("real"));
+    assert_eq!((loaded.pluto, loaded.seed, loaded.new_system_seed), (true, None, 42));
+    assert_eq!(loaded.flight, flight);
+    assert_eq!(loaded.frames, recorded.frames);
+    assert_eq!(loaded.duration(), recorded.duration());
+
+    // Cada error indica la línea
+    let malformed = [
+        ("pluto = quizá\n", "línea 1"),
+        ("seed = -3\n", "línea 1"),
+        ("# cabecera\n0.016 0 0 0 1 0\n", "línea 2"),
+        ("0.016 0 0 0 1 zz 0\n", "línea 1"),
+        ("0.016 x 0 0 1 0 0\n", "línea 1"),
+    ];
+    for (contents, line) in malformed {
+        let Err(error) = Replay::parse(contents) else {
+            panic!("'{}' debería ser un error", contents.trim());
+        };
+        assert!(error.to_string().contains(line), "{}: {}", contents.trim(), error);
+    }
+}
@@ -0,0 +1,46 @@
+//! Pruebas de las rocas de los anillos vistas de cerca
+
+use crate::ring_particles::{RingParticles, RingRock};
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn ring_particles_fill_the_annulus_near_the_camera() {
+    let scene = Scene::parse(
+        "[planet]\nname = Anillado\ntype = gas_giant\norbital_radius = 30\norbital_speed = 0\nscale = 2\n\
+         [rings]\ninner_radius = 4\nouter_radius = 6\n[gap]\nradius = 5\nwidth = 0.6\n",
+    )
+    .unwrap();
+    let (planet, particles) = (&scene.planets[0], RingParticles::new());
+    let center = planet.position();
+    let rings = planet.rings.as_ref().unwrap();
+
+    // De lejos solo el disco
+    let far = Vector3::new(center.x, center.y + 20.0, center.z);
+    assert_eq!(particles.blend(planet, far, 0.0), 0.0);
+    assert!(particles.rocks(0, planet, far, 0.0).is_empty());
+
+    // Dentro del anillo, miles de rocas dentro de la corona y ninguna en la división
+    let camera = Vector3::new(center.x + 4.5, center.y + 0.1, center.z);
+    assert_eq!(particles.blend(planet, camera, 0.0), 1.0);
+    let rocks = particles.rocks(0, planet, camera, 0.0);
+    assert!(rocks.len() > 1000, "solo {} rocas", rocks.len());
+    for rock in &rocks {
+        let (x, y, z) = (rock.position.x - center.x, rock.position.y - center.y, rock.position.z - center.z);
+        let radial = (x * x + z * z).sqrt();
+        assert!(radial >= rings.inner_radius - 0.001 && radial <= rings.outer_radius + 0.001);
+        assert!((radial - 5.0).abs() > 0.2, "roca en la división a {}", radial);
+        assert!(y.abs() <= particles.thickness && rock.scale > 0.0 && rock.scale <= particles.rock_size);
+    }
+
+    // Al moverse la cámara las rocas cercanas siguen en el mismo sitio
+    let moved = particles.rocks(0, planet, Vector3::new(camera.x, camera.y, camera.z + 0.05), 0.0);
+    let closest = rocks
+        .iter()
+        .min_by(|a, b| {
+            let d = |rock: &&RingRock| (rock.position.x - camera.x).powi(2) + (rock.position.z - camera.z).powi(2);
+            d(a).total_cmp(&d(b))
+        })
+        .unwrap();
+    assert!(moved.iter().any(|rock| rock.position == closest.position));
+}
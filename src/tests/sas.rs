@@ -0,0 +1,50 @@
+//! Pruebas del piloto automático de orientación (SAS)
+
+use crate::camera::Camera;
+use crate::flight_model::{FlightMode, FlightModel};
+use crate::input_state::InputState;
+use crate::sas::{Sas, SasMode};
+use crate::scene::BodyRef;
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn sas_holds_retrograde_and_matches_target_velocity() {
+    let scene = Scene::parse("[planet]\nname = Blanco\norbital_radius = 20\norbital_speed = 0.3\n").unwrap();
+    let mut sas = Sas::new();
+    sas.target = Some(BodyRef::Planet(0));
+    let target_velocity = sas.relative_velocity(&scene, 0.0, Vector3::zero());
+    let target_speed = (target_velocity.x.powi(2) + target_velocity.z.powi(2)).sqrt();
+    assert!((target_speed - 6.0).abs() < 0.01, "el objetivo va a {} u/s", target_speed);
+
+    let input = InputState::new();
+    let mut camera = Camera::new(Vector3::new(0.0, 0.0, 30.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let mut flight = FlightModel::default();
+    flight.mode = FlightMode::Newtonian;
+    flight.velocity = Vector3::new(4.0, 0.0, 0.0);
+    let step = 1.0 / 120.0;
+
+    // Retrógrado: la proa acaba en contra de la velocidad relativa
+    assert_eq!(sas.toggle(SasMode::Retrograde), Some(SasMode::Retrograde));
+    for _ in 0..600 {
+        let relative = sas.relative_velocity(&scene, 0.0, flight.velocity);
+        assert!(sas.update(&mut camera, &input, relative, step).is_none());
+    }
+    let relative = sas.relative_velocity(&scene, 0.0, flight.velocity);
+    let speed = (relative.x.powi(2) + relative.y.powi(2) + relative.z.powi(2)).sqrt();
+    let forward = camera.forward();
+    let alignment = -(forward.x * relative.x + forward.y * relative.y + forward.z * relative.z) / speed;
+    assert!(alignment > 0.99, "proa a {} del retrógrado", alignment.acos());
+
+    // Igualar velocidad: el empuje lleva la velocidad relativa a cero sin pasarse
+    sas.toggle(SasMode::MatchVelocity);
+    for _ in 0..1200 {
+        let relative = sas.relative_velocity(&scene, 0.0, flight.velocity);
+        let command = sas.update(&mut camera, &input, relative, step).expect("igualar pide empuje");
+        flight.update(command, camera.forward(), true, step);
+    }
+    let relative = sas.relative_velocity(&scene, 0.0, flight.velocity);
+    assert!((relative.x.powi(2) + relative.y.powi(2) + relative.z.powi(2)).sqrt() < 0.05);
+
+    assert_eq!(sas.toggle(SasMode::MatchVelocity), None);
+}
@@ -0,0 +1,37 @@
+//! Pruebas de los guiones de las escenas (solo con la característica `scripting`)
+
+#![cfg(feature = "scripting")]
+
+use crate::console::Command;
+use crate::scripting::{ScriptContext, ScriptHost};
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+use std::path::PathBuf;
+
+#[test]
+fn scene_script_runs_example_mission() {
+    let scene = Scene::parse("[planet]\nname = Glacies\norbital_radius = 10\n[script]\npath = scripts/mision.rhai\n").unwrap();
+    assert_eq!(scene.scripts, vec!["scripts/mision.rhai".to_string()]);
+
+    let mut host = ScriptHost::new();
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets").join("scenes").join(&scene.scripts[0]);
+    host.load(&path).unwrap();
+    assert_eq!(host.take_messages(), vec!["Misión: vuela hasta Glacies".to_string()]);
+
+    let glacies = Vector3::new(30.0, 0.0, 0.0);
+    let atalaya = Vector3::new(-20.0, 0.0, 5.0);
+    let context = |ship: Vector3| ScriptContext {
+        time: 42.0,
+        ship,
+        bodies: vec![("Glacies".to_string(), glacies), ("Atalaya".to_string(), atalaya)],
+        ..ScriptContext::default()
+    };
+    // Lejos de todo no pasa nada; los sucesos llegan a `on_event`
+    assert!(host.update(context(Vector3::zero()), 0.01, &[("impact", "Neon".to_string())]).is_empty());
+    assert_eq!(host.take_messages(), vec!["Impacto en Neon".to_string()]);
+    assert!(host.update(context(glacies), 0.01, &[]).is_empty());
+    assert_eq!(host.take_messages(), vec!["Has llegado a Glacies. Siguiente: Atalaya".to_string()]);
+    // Al terminar, la misión pide a la cámara que siga a Glacies (una orden como las de la consola)
+    assert_eq!(host.update(context(atalaya), 0.01, &[]), vec![Command::Track("Glacies".to_string())]);
+    assert_eq!(host.take_messages(), vec!["Misión cumplida en 42 s (1 impactos durante el viaje)".to_string()]);
+}
@@ -0,0 +1,86 @@
+//! Pruebas de la vista secundaria: su render con otra cámara en un framebuffer propio y la
+//! composición encima de la vista principal
+
+use computer_graphics_v3::fragment::Fragment;
+use crate::matrix::{create_model_matrix, create_view_matrix, create_viewport_matrix, Projection};
+use crate::secondary_view::{InsetKind, SecondaryView};
+use computer_graphics_v3::{Camera, FnShader, Framebuffer, Light, Material, Obj, Renderer, Uniforms};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+/// Dibuja una esfera de color plano en `center` vista desde `camera`
+fn draw_sphere(renderer: &Renderer, framebuffer: &mut Framebuffer, camera: &Camera, projection: Matrix, center: Vector3, color: Vector3) {
+    let view = create_view_matrix(camera.eye, camera.target, camera.up);
+    let viewport = create_viewport_matrix(0.0, 0.0, framebuffer.width as f32, framebuffer.height as f32);
+    let model = create_model_matrix(center, 1.0, Vector3::zero());
+    let uniforms = Uniforms::new(model, view, projection, viewport, 0.0, camera.eye, Vector3::new(0.0, 0.0, 1.0));
+    let sphere = Obj::generate_sphere(1.0, 16);
+    let shader = FnShader::new(Material::matte(color), move |_: &Fragment, _: &Uniforms| color);
+    renderer.draw_indexed_mesh(framebuffer, &uniforms, &sphere.vertices, &sphere.indices, &shader);
+}
+
+/// Color del píxel (x, y) en RGB de 8 bits
+fn pixel(framebuffer: &Framebuffer, x: u32, y: u32) -> [u8; 3] {
+    let index = ((y * framebuffer.width + x) * 3) as usize;
+    let rgb = framebuffer.to_rgb8();
+    [rgb[index], rgb[index + 1], rgb[index + 2]]
+}
+
+#[test]
+fn rear_view_renders_behind_the_ship_into_its_own_framebuffer() {
+    // Una esfera roja delante de la nave y una verde detrás: la vista principal ve la roja y el
+    // retrovisor, con su propia cámara y su propio framebuffer, la verde
+    let (main_width, main_height) = (96, 64);
+    let renderer = Renderer::new(Light::new(Vector3::new(0.0, 10.0, 0.0)));
+    let (ahead, behind) = (Vector3::new(0.0, 0.0, 10.0), Vector3::new(0.0, 0.0, -10.0));
+    let (red, green) = (Vector3::new(0.9, 0.1, 0.1), Vector3::new(0.1, 0.9, 0.1));
+    let ship = Vector3::zero();
+    let heading = Vector3::new(0.0, 0.0, 1.0);
+
+    let mut main = Framebuffer::new(main_width, main_height);
+    main.set_dithering(false);
+    main.set_background_color(Vector3::zero());
+    main.clear();
+    let main_camera = Camera::new(Vector3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 0.5, 1.0), Vector3::new(0.0, 1.0, 0.0));
+    let aspect = main_width as f32 / main_height as f32;
+    let projection = Projection::Perspective { fov_y: PI / 3.0, aspect, near: 0.1, far: 100.0 }.matrix();
+    for (center, color) in [(ahead, red), (behind, green)] {
+        draw_sphere(&renderer, &mut main, &main_camera, projection, center, color);
+    }
+
+    let mut view = SecondaryView::new();
+    view.framebuffer.set_dithering(false);
+    assert_eq!(view.cycle(), Some(InsetKind::RearView));
+    let (x, y) = view.prepare(main_width, main_height);
+    let (inset_width, inset_height) = (view.framebuffer.width, view.framebuffer.height);
+    assert_eq!(view.layout(main_width, main_height), (x, y, inset_width, inset_height));
+    assert!(inset_width < main_width && inset_height < main_height);
+    let rear_camera = view.rear_camera(ship, heading, 0.5);
+    let inset_projection = view.projection(100.0).matrix();
+    for (center, color) in [(ahead, red), (behind, green)] {
+        draw_sphere(&renderer, &mut view.framebuffer, &rear_camera, inset_projection, center, color);
+    }
+
+    let [r, g, _] = pixel(&main, main_width / 2, main_height / 2);
+    assert!(r.saturating_sub(g) > 100, "la vista principal debería ver la esfera roja: {:?}", [r, g]);
+    let [r, g, _] = pixel(&view.framebuffer, inset_width / 2, inset_height / 2);
+    assert!(g.saturating_sub(r) > 100, "el retrovisor debería ver la esfera verde: {:?}", [r, g]);
+
+    // Compuesta, la vista queda arriba al centro y el resto de la imagen no cambia
+    main.composite(&view.framebuffer, x, y);
+    let [r, g, _] = pixel(&main, x as u32 + inset_width / 2, y as u32 + inset_height / 2);
+    assert!(g.saturating_sub(r) > 100, "centro del retrovisor compuesto: {:?}", [r, g]);
+    let [r, g, _] = pixel(&main, main_width / 2, main_height / 2);
+    assert!(r.saturating_sub(g) > 100, "centro de la vista principal tras componer: {:?}", [r, g]);
+
+    // La cámara del planeta orbita a `orbit_distance` radios mirando al centro, y el ciclo
+    // termina con la vista oculta
+    assert_eq!(view.cycle(), Some(InsetKind::PlanetCam));
+    let center = Vector3::new(20.0, 0.0, 5.0);
+    let planet_camera = view.planet_camera(center, 2.0, 3.0);
+    let offset = planet_camera.eye - center;
+    let distance = (offset.x * offset.x + offset.y * offset.y + offset.z * offset.z).sqrt();
+    assert!((distance - 2.0 * view.orbit_distance).abs() < 1e-3);
+    assert_eq!(planet_camera.target, center);
+    assert_eq!(view.cycle(), None);
+}
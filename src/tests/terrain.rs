@@ -0,0 +1,51 @@
+//! Pruebas del relieve de la superficie de los planetas rocosos
+
+use crate::scene::Planet;
+use crate::terrain::SurfaceTerrain;
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn surface_terrain_refines_near_the_viewer_and_follows_the_shader_noise() {
+    let terrain = SurfaceTerrain::new();
+    let scene = Scene::parse("[planet]\nname = Roca\norbital_radius = 20\nscale = 2\n[planet]\nname = Gas\ntype = gas_giant\norbital_radius = 40\n").unwrap();
+    let (rocky, gas) = (&scene.planets[0], &scene.planets[1]);
+    let near = |planet: &Planet| {
+        let p = planet.position();
+        Vector3::new(p.x + planet.scale * 1.2, p.y, p.z)
+    };
+    assert!(terrain.engages(rocky, near(rocky)));
+    assert!(!terrain.engages(gas, near(gas)), "solo los planetas rocosos tienen relieve");
+    assert!(!terrain.engages(rocky, Vector3::zero()));
+
+    // Observador justo sobre +X: allí los parches son mucho más finos que en la cara opuesta
+    let mesh = terrain.build(Vector3::new(1.05, 0.0, 0.0), 0.0);
+    let around = |direction: Vector3| {
+        mesh.vertices
+            .iter()
+            .filter(|vertex| {
+                let p = vertex.position;
+                let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt();
+                (p.x * direction.x + p.y * direction.y + p.z * direction.z) / length > 0.99
+            })
+            .count()
+    };
+    let (front, back) = (around(Vector3::new(1.0, 0.0, 0.0)), around(Vector3::new(-1.0, 0.0, 0.0)));
+    assert!(front > back * 10, "{} vértices cerca del observador y {} en la cara opuesta", front, back);
+
+    // Cada vértice está a la altura del ruido del shader (o en el faldón bajo el terreno), y la
+    // altura varía: son montañas, no una esfera
+    let (mut lowest, mut highest) = (f32::MAX, f32::MIN);
+    for vertex in &mesh.vertices {
+        let p = vertex.position;
+        let length = (p.x * p.x + p.y * p.y + p.z * p.z).sqrt();
+        if (length - (1.0 - terrain.relief)).abs() < 1.0e-4 {
+            continue;
+        }
+        assert!((length - terrain.height(p, 0.0)).abs() < 1.0e-4, "vértice a {} en lugar de {}", length, terrain.height(p, 0.0));
+        lowest = lowest.min(length);
+        highest = highest.max(length);
+    }
+    assert!(highest - lowest > terrain.relief * 0.3, "relieve de solo {}", highest - lowest);
+    assert!(highest < 1.0 + terrain.relief && lowest > 1.0 - terrain.relief);
+}
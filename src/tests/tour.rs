@@ -0,0 +1,49 @@
+//! Pruebas de la visita guiada
+
+use crate::scene::BodyRef;
+use crate::tour::{Tour, TourConfig};
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn tour_visits_every_body_with_its_facts() {
+    let scene = Scene::parse(
+        "[planet]\nname = Ferrum\norbital_radius = 12\nscale = 1.5\nfact = Tiene ciudades.\nfact = Su día dura 24 horas.\n\
+         [planet]\nname = Jovis\norbital_radius = 18\n\
+         [moon]\nname = Selene\nparent = Ferrum\norbital_radius = 2.5\nfact = Ilumina las noches.\n",
+    )
+    .unwrap();
+    assert_eq!(scene.facts(BodyRef::Moon(0)).collect::<Vec<_>>(), vec!["Ilumina las noches."]);
+
+    let config = TourConfig::parse("dwell = 2\nflight_time = 1\n").unwrap();
+    let mut tour = Tour::new(config);
+    assert!(tour.start(&scene, 0.0, Vector3::new(0.0, 40.0, 60.0), Vector3::zero()));
+
+    // Cada planeta seguido de sus lunas; la ficha solo aparece al llegar
+    let step = 1.0 / 120.0;
+    let mut time = 0.0;
+    let mut visited = Vec::new();
+    let mut previous_eye = Vector3::new(0.0, 40.0, 60.0);
+    while let Some((eye, target)) = tour.update(&scene, time) {
+        // El vuelo es continuo: la cámara no salta entre dos pasos
+        let jump = ((eye.x - previous_eye.x).powi(2) + (eye.y - previous_eye.y).powi(2) + (eye.z - previous_eye.z).powi(2)).sqrt();
+        assert!(jump < 5.0, "salto de {} en t = {}", jump, time);
+        previous_eye = eye;
+        if let Some(card) = tour.card(&scene)
+            && visited.last() != Some(&card.title)
+        {
+            let body = tour.current_stop().unwrap();
+            let position = scene.body_position(body, time).unwrap();
+            assert!(((target.x - position.x).powi(2) + (target.z - position.z).powi(2)).sqrt() < 0.01);
+            visited.push(card.title.clone());
+            if card.title == "Ferrum" {
+                assert_eq!(card.subtitle, "Planeta rocoso");
+                assert_eq!(card.facts, vec!["Tiene ciudades.", "Su día dura 24 horas."]);
+            }
+        }
+        time += step;
+        assert!(time < 20.0, "la visita no termina");
+    }
+    assert_eq!(visited, vec!["Ferrum", "Selene", "Jovis"]);
+    assert!(!tour.is_active());
+}
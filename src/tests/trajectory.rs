@@ -0,0 +1,36 @@
+//! Pruebas de la predicción de trayectorias con la gravedad de la escena
+
+use crate::scene::BodyRef;
+use crate::trajectory::{Trajectory, PREDICTION_STEP};
+use computer_graphics_v3::Scene;
+use raylib::prelude::*;
+
+#[test]
+fn trajectory_prediction_orbits_and_impacts() {
+    // Un planeta quieto lejos del sol: con la velocidad de una órbita circular la nave lo rodea,
+    // y parada cae sobre él
+    let scene = Scene::parse("[planet]\nname = Ancla\norbital_radius = 150\norbital_speed = 0\nscale = 2\n").unwrap();
+    let planet = scene.planets[0].position();
+    let (gravity, radius) = (1.0, 5.0);
+    let start = Vector3::new(planet.x + radius, planet.y, planet.z);
+    let orbital_speed = (gravity * 8.0 / radius).sqrt();
+    let velocity = Vector3::new(0.0, 0.0, orbital_speed);
+
+    let orbit = Trajectory::predict(&scene, 3.0, 0.0, start, velocity, gravity, 20.0);
+    assert!(orbit.impact.is_none());
+    assert_eq!(orbit.points.len(), (20.0 / PREDICTION_STEP).ceil() as usize + 1);
+    for point in &orbit.points {
+        let distance = ((point.x - planet.x).powi(2) + (point.y - planet.y).powi(2) + (point.z - planet.z).powi(2)).sqrt();
+        assert!((distance - radius).abs() < 0.5, "la órbita se aleja a {}", distance);
+    }
+
+    let fall = Trajectory::predict(&scene, 3.0, 0.0, start, Vector3::zero(), gravity, 20.0);
+    let (body, seconds) = fall.impact.expect("la nave parada debería caer sobre el planeta");
+    assert_eq!(body, Some(BodyRef::Planet(0)));
+    assert!(seconds > 1.0 && seconds < 10.0, "impacto a los {} s", seconds);
+
+    // Sin gravedad la nave sigue en línea recta
+    let straight = Trajectory::predict(&scene, 3.0, 0.0, start, velocity, 0.0, 2.0);
+    let end = straight.points.last().unwrap();
+    assert!((end.z - (start.z + orbital_speed * 2.0)).abs() < 0.01 && (end.x - start.x).abs() < 0.0001);
+}
@@ -0,0 +1,365 @@
+use raylib::prelude::*;
+use crate::camera_path::CameraPath;
+use crate::scene::{BodyRef, Scene};
+use crate::shaders::PlanetType;
+use crate::units::{format_days, format_distance, orbital_period_days};
+use std::fs;
+use std::io;
+
+/// Tiempos y encuadre de la visita guiada (ver `assets/tour.cfg`)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TourConfig {
+    pub dwell: f32,       // Segundos en cada parada
+    pub flight_time: f32, // Segundos de vuelo entre dos paradas
+    pub distance: f32,    // Distancia de la cámara al cuerpo, en radios del cuerpo (más 2 unidades)
+}
+
+impl TourConfig {
+    pub fn new() -> Self {
+        TourConfig { dwell: 8.0, flight_time: 5.0, distance: 3.5 }
+    }
+
+    /// Cargar la configuración de un archivo de líneas `clave = valor` (`#` inicia un comentario)
+    /// Las claves que no aparecen conservan su valor por defecto
+    pub fn load(path: &str) -> io::Result<Self> {
+        TourConfig::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(contents: &str) -> io::Result<Self> {
+        let mut config = TourConfig::new();
+
+        for (index, raw_line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| config_error(line_number, "se esperaba 'clave = valor'"))?;
+            let value: f32 = value
+                .trim()
+                .parse()
+                .map_err(|e| config_error(line_number, &format!("{}", e)))?;
+            match key.trim() {
+                "dwell" => config.dwell = value.max(0.5),
+                "flight_time" => config.flight_time = value.max(0.5),
+                "distance" => config.distance = value.max(1.2),
+                other => return Err(config_error(line_number, &format!("clave desconocida '{}'", other))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+impl Default for TourConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ficha de un cuerpo: nombre, qué es, datos de la escena y sus curiosidades
+#[derive(Clone, Debug, PartialEq)]
+pub struct TourCard {
+    pub title: String,
+    pub subtitle: String,
+    pub lines: Vec<String>,
+    pub facts: Vec<String>,
+}
+
+/// Ficha del cuerpo `body` a partir de la escena (None si ya no existe)
+pub fn body_card(scene: &Scene, body: BodyRef) -> Option<TourCard> {
+    let parent_name = |parent: usize| scene.planets.get(parent).map_or("?", |planet| planet.name.as_str());
+    let (title, subtitle, lines) = match body {
+        BodyRef::Planet(idx) => {
+            let planet = scene.planets.get(idx)?;
+            let kind = match planet.planet_type {
+                PlanetType::GasGiant => "Gigante gaseoso",
+                PlanetType::Ice => "Planeta helado",
+                PlanetType::Volcanic => "Planeta volcánico",
+                PlanetType::SciFi => "Planeta artificial",
+                _ => "Planeta rocoso",
+            };
+            let moons = scene.moons.iter().filter(|moon| moon.parent == idx).count();
+            let mut lines = vec![
+                format!("Radio: {:.1} u", planet.scale),
+                format!("Órbita: {} del sol", format_distance(planet.orbital_radius)),
+                format!("Año: {}", format_days(orbital_period_days(planet.orbital_speed))),
+                format!("Día: {:.1} h{}", planet.rotation_period, if planet.retrograde { " (retrógrado)" } else { "" }),
+                format!("Inclinación del eje: {:.1}°", planet.axial_tilt.to_degrees()),
+            ];
+            if moons > 0 {
+                lines.push(format!("Lunas: {}", moons));
+            }
+            if planet.rings.is_some() {
+                lines.push("Tiene anillos".to_string());
+            }
+            (planet.name.clone(), kind.to_string(), lines)
+        }
+        BodyRef::Moon(idx) => {
+            let moon = scene.moons.get(idx)?;
+            let lines = vec![
+                format!("Radio: {:.1} u", moon.scale),
+                format!("Órbita: {} de {}", format_distance(moon.orbital_radius), parent_name(moon.parent)),
+                format!("Inclinación de la órbita: {:.1}°", moon.inclination.to_degrees()),
+            ];
+            (moon.name.clone(), format!("Luna de {}", parent_name(moon.parent)), lines)
+        }
+        BodyRef::Station(idx) => {
+            let station = scene.stations.get(idx)?;
+            let lines = vec![
+                format!("Radio del anillo: {:.1} u", station.scale),
+                format!("Órbita: {} de {}", format_distance(station.orbital_radius), parent_name(station.parent)),
+            ];
+            (station.name.clone(), format!("Estación en órbita de {}", parent_name(station.parent)), lines)
+        }
+        BodyRef::BlackHole(idx) => {
+            let hole = scene.black_holes.get(idx)?;
+            let lines = vec![
+                format!("Horizonte de sucesos: {:.1} u", hole.radius),
+                format!("Disco de acreción: {:.1}-{:.1} u", hole.disk_inner, hole.disk_outer),
+                format!("Borde interno del disco: {:.0} K", hole.disk_temperature),
+            ];
+            (hole.name.clone(), "Agujero negro".to_string(), lines)
+        }
+    };
+    Some(TourCard { title, subtitle, lines, facts: scene.facts(body).map(str::to_string).collect() })
+}
+
+/// Fase de la parada actual
+enum Phase {
+    /// Volando hacia la parada: trayectoria desde la pose de partida hasta el encuadre del
+    /// cuerpo en `from` (donde estaba al salir); el desplazamiento del cuerpo durante el vuelo se
+    /// suma poco a poco para llegar justo a su encuadre
+    Flying { path: CameraPath, start: f32, from: Vector3 },
+    /// Mirando el cuerpo con su ficha en pantalla
+    Dwelling { start: f32 },
+}
+
+/// Visita guiada: la cámara vuela de cuerpo en cuerpo (cada planeta seguido de sus lunas y
+/// estaciones, y al final los agujeros negros), se queda `dwell` segundos mirando cada uno con
+/// su ficha y pasa al siguiente. Los vuelos son trayectorias de cámara como las de K/'
+pub struct Tour {
+    pub config: TourConfig,
+    stops: Vec<BodyRef>,
+    current: usize,       // Índice de la parada actual en `stops`
+    phase: Option<Phase>, // None = visita inactiva
+}
+
+impl Tour {
+    pub fn new(config: TourConfig) -> Self {
+        Tour { config, stops: Vec::new(), current: 0, phase: None }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.phase.is_some()
+    }
+
+    /// Cuerpo de la parada actual (None si la visita no está activa)
+    pub fn current_stop(&self) -> Option<BodyRef> {
+        self.phase.as_ref().and_then(|_| self.stops.get(self.current).copied())
+    }
+
+    /// Empezar la visita desde la pose actual de la cámara; false si la escena no tiene cuerpos
+    pub fn start(&mut self, scene: &Scene, time: f32, eye: Vector3, target: Vector3) -> bool {
+        self.stops = (0..scene.planets.len())
+            .flat_map(|planet| {
+                let moons = (0..scene.moons.len()).filter(move |&idx| scene.moons[idx].parent == planet).map(BodyRef::Moon);
+                let stations = (0..scene.stations.len()).filter(move |&idx| scene.stations[idx].parent == planet).map(BodyRef::Station);
+                std::iter::once(BodyRef::Planet(planet)).chain(moons).chain(stations)
+            })
+            .chain((0..scene.black_holes.len()).map(BodyRef::BlackHole))
+            .collect();
+        self.current = 0;
+        self.phase = None;
+        if self.stops.is_empty() {
+            return false;
+        }
+        self.begin_flight(scene, time, eye, target);
+        self.is_active()
+    }
+
+    pub fn stop(&mut self) {
+        self.phase = None;
+    }
+
+    /// Avanzar la visita al instante `time`; devuelve la pose de la cámara (ojo, objetivo), o
+    /// None si no está activa. Al terminar la última parada la visita se desactiva sola
+    pub fn update(&mut self, scene: &Scene, time: f32) -> Option<(Vector3, Vector3)> {
+        let body = self.current_stop()?;
+        let Some(position) = scene.body_position(body, time) else {
+            // El cuerpo ya no existe (otra escena)
+            self.stop();
+            return None;
+        };
+        let radius = scene.body_radius(body).unwrap_or(1.0);
+
+        match self.phase.as_ref()? {
+            Phase::Flying { path, start, from } => {
+                let progress = ((time - start) / self.config.flight_time).clamp(0.0, 1.0);
+                let eased = progress * progress * (3.0 - 2.0 * progress);
+                let (eye, target) = path.sample(eased * path.duration())?;
+                let drift = Vector3::new((position.x - from.x) * eased, (position.y - from.y) * eased, (position.z - from.z) * eased);
+                let pose = (
+                    Vector3::new(eye.x + drift.x, eye.y + drift.y, eye.z + drift.z),
+                    Vector3::new(target.x + drift.x, target.y + drift.y, target.z + drift.z),
+                );
+                if progress >= 1.0 {
+                    self.phase = Some(Phase::Dwelling { start: time });
+                }
+                Some(pose)
+            }
+            Phase::Dwelling { start } => {
+                let pose = (self.viewpoint(position, radius), position);
+                if time - start >= self.config.dwell {
+                    self.current += 1;
+                    if self.current < self.stops.len() {
+                        self.begin_flight(scene, time, pose.0, pose.1);
+                    } else {
+                        self.stop();
+                    }
+                }
+                Some(pose)
+            }
+        }
+    }
+
+    /// Ficha de la parada actual mientras se mira el cuerpo (no durante el vuelo)
+    pub fn card(&self, scene: &Scene) -> Option<TourCard> {
+        match self.phase {
+            Some(Phase::Dwelling { .. }) => body_card(scene, self.current_stop()?),
+            _ => None,
+        }
+    }
+
+    /// Dibuja la ficha de la parada (o el destino del vuelo) abajo a la izquierda
+    pub fn draw(&self, d: &mut RaylibDrawHandle, scene: &Scene, time: f32, height: i32) {
+        let Some(body) = self.current_stop() else {
+            return;
+        };
+        let accent = Color::new(140, 200, 255, 255);
+        let text_color = Color::new(220, 220, 220, 255);
+        let header = format!("VISITA GUIADA  {}/{}", self.current + 1, self.stops.len());
+        let x = 10;
+
+        let Some(card) = self.card(scene) else {
+            // En vuelo: solo el destino
+            let destination = format!("Rumbo a {}", scene.body_name(body).unwrap_or("?"));
+            let y = height - 90;
+            d.draw_rectangle(x, y, 300, 46, Color::new(0, 0, 0, 170));
+            d.draw_text(&header, x + 10, y + 8, 10, accent);
+            d.draw_text(&destination, x + 10, y + 22, 16, text_color);
+            return;
+        };
+
+        let panel_width = 340;
+        // Cada curiosidad empieza con un guion y sus líneas siguientes van sangradas
+        let facts: Vec<(String, i32)> = card
+            .facts
+            .iter()
+            .flat_map(|fact| wrap_text(&format!("- {}", fact), 12, panel_width - 30).into_iter().enumerate())
+            .map(|(idx, line)| (line, if idx == 0 { 0 } else { 10 }))
+            .collect();
+        let panel_height = 64 + card.lines.len() as i32 * 14 + if facts.is_empty() { 0 } else { 8 + facts.len() as i32 * 14 } + 14;
+        let y = height - panel_height - 44;
+        d.draw_rectangle(x, y, panel_width, panel_height, Color::new(0, 0, 0, 170));
+        d.draw_rectangle_lines(x, y, panel_width, panel_height, accent);
+        d.draw_text(&header, x + 10, y + 8, 10, accent);
+        d.draw_text(&card.title, x + 10, y + 22, 20, Color::WHITE);
+        d.draw_text(&card.subtitle, x + 10, y + 44, 12, accent);
+
+        let mut row = y + 64;
+        for line in &card.lines {
+            d.draw_text(line, x + 10, row, 12, text_color);
+            row += 14;
+        }
+        if !facts.is_empty() {
+            row += 8;
+            for (line, indent) in &facts {
+                d.draw_text(line, x + 10 + indent, row, 12, Color::new(255, 220, 150, 255));
+                row += 14;
+            }
+        }
+
+        // Tiempo que queda en la parada
+        if let Some(Phase::Dwelling { start }) = self.phase {
+            let remaining = (1.0 - (time - start) / self.config.dwell).clamp(0.0, 1.0);
+            d.draw_rectangle(x + 10, y + panel_height - 10, ((panel_width - 20) as f32 * remaining) as i32, 3, accent);
+        }
+    }
+
+    /// Encuadre de un cuerpo en `position`: entre el cuerpo y el sol, algo de lado y por encima,
+    /// para ver la cara iluminada con el terminador
+    fn viewpoint(&self, position: Vector3, radius: f32) -> Vector3 {
+        let length = (position.x * position.x + position.y * position.y + position.z * position.z).sqrt();
+        let toward_sun = if length > 0.001 {
+            Vector3::new(-position.x / length, -position.y / length, -position.z / length)
+        } else {
+            Vector3::new(0.0, 0.0, 1.0)
+        };
+        let side = Vector3::new(toward_sun.z, 0.0, -toward_sun.x);
+        let direction = Vector3::new(
+            toward_sun.x * 0.75 + side.x * 0.5,
+            toward_sun.y * 0.75 + 0.35,
+            toward_sun.z * 0.75 + side.z * 0.5,
+        );
+        let norm = (direction.x * direction.x + direction.y * direction.y + direction.z * direction.z).sqrt().max(0.0001);
+        let distance = radius * self.config.distance + 2.0;
+        Vector3::new(
+            position.x + direction.x / norm * distance,
+            position.y + direction.y / norm * distance,
+            position.z + direction.z / norm * distance,
+        )
+    }
+
+    /// Trayectoria desde la pose actual hasta el encuadre de la parada actual, con un punto
+    /// intermedio elevado para no atravesar lo que haya entre medias
+    fn begin_flight(&mut self, scene: &Scene, time: f32, eye: Vector3, target: Vector3) {
+        let body = self.stops[self.current];
+        let Some(from) = scene.body_position(body, time) else {
+            self.phase = None;
+            return;
+        };
+        let end = self.viewpoint(from, scene.body_radius(body).unwrap_or(1.0));
+        let (dx, dy, dz) = (end.x - eye.x, end.y - eye.y, end.z - eye.z);
+        let lift = (dx * dx + dy * dy + dz * dz).sqrt() * 0.2;
+        let middle = Vector3::new((eye.x + end.x) * 0.5, (eye.y + end.y) * 0.5 + lift, (eye.z + end.z) * 0.5);
+        let middle_target = Vector3::new((target.x + from.x) * 0.5, (target.y + from.y) * 0.5, (target.z + from.z) * 0.5);
+
+        let mut path = CameraPath::new();
+        path.add_keyframe(eye, target, 0.0);
+        path.add_keyframe(middle, middle_target, 0.5);
+        path.add_keyframe(end, from, 1.0);
+        self.phase = Some(Phase::Flying { path, start: time, from });
+    }
+}
+
+impl Default for Tour {
+    fn default() -> Self {
+        Self::new(TourConfig::default())
+    }
+}
+
+/// Partir `text` en líneas de como mucho `max_width` píxeles con la fuente de raylib
+fn wrap_text(text: &str, font_size: i32, max_width: i32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if !line.is_empty() && measure_text(&candidate, font_size) > max_width {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+fn config_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("línea {}: {}", line, message))
+}
@@ -0,0 +1,123 @@
+use raylib::prelude::*;
+use crate::framebuffer::Framebuffer;
+use crate::renderer::Renderer;
+use crate::scene::{BodyRef, Scene};
+use crate::uniforms::Uniforms;
+
+/// Paso de integración de la predicción en segundos (más largo que el de la simulación: la línea
+/// se recalcula en cada frame y solo tiene que ser fiel a la escala de la pantalla)
+pub const PREDICTION_STEP: f32 = 1.0 / 30.0;
+/// Densidad de los agujeros negros relativa a la de la estrella y los planetas
+const BLACK_HOLE_DENSITY: f32 = 10.0;
+
+/// Cuerpo que atrae a la nave; la masa es la de una esfera de su radio (todos con la misma
+/// densidad salvo los agujeros negros)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GravitySource {
+    pub body: Option<BodyRef>, // None = la estrella central
+    pub position: Vector3,
+    pub radius: f32,
+    pub mass: f32,
+}
+
+/// Fuentes de gravedad de la escena `ahead` segundos después de `time`: la estrella (en el
+/// origen, de radio `sun_radius`), los planetas, las lunas y los agujeros negros. Las estaciones
+/// son demasiado pequeñas para contar
+pub fn gravity_sources(scene: &Scene, sun_radius: f32, time: f32, ahead: f32) -> Vec<GravitySource> {
+    let mut sources = vec![GravitySource { body: None, position: Vector3::zero(), radius: sun_radius, mass: sun_radius.powi(3) }];
+    for (idx, planet) in scene.planets.iter().enumerate() {
+        let position = planet.position_after(ahead);
+        sources.push(GravitySource { body: Some(BodyRef::Planet(idx)), position, radius: planet.scale, mass: planet.scale.powi(3) });
+    }
+    for (idx, moon) in scene.moons.iter().enumerate() {
+        let parent = scene.planets[moon.parent].position_after(ahead);
+        let offset = moon.offset(time + ahead);
+        let position = Vector3::new(parent.x + offset.x, parent.y + offset.y, parent.z + offset.z);
+        sources.push(GravitySource { body: Some(BodyRef::Moon(idx)), position, radius: moon.scale, mass: moon.scale.powi(3) });
+    }
+    for (idx, hole) in scene.black_holes.iter().enumerate() {
+        let mass = BLACK_HOLE_DENSITY * hole.radius.powi(3);
+        sources.push(GravitySource { body: Some(BodyRef::BlackHole(idx)), position: hole.position, radius: hole.radius, mass });
+    }
+    sources
+}
+
+/// Aceleración de la gravedad en `point` con la constante `strength` (0 = sin gravedad)
+/// Dentro de un cuerpo la distancia se limita a su radio para que la aceleración no se dispare
+pub fn gravity_at(sources: &[GravitySource], point: Vector3, strength: f32) -> Vector3 {
+    let mut acceleration = Vector3::zero();
+    for source in sources {
+        let delta = Vector3::new(source.position.x - point.x, source.position.y - point.y, source.position.z - point.z);
+        let distance = (delta.x * delta.x + delta.y * delta.y + delta.z * delta.z).sqrt();
+        if distance < 0.0001 {
+            continue;
+        }
+        let clamped = distance.max(source.radius);
+        let pull = strength * source.mass / (clamped * clamped * distance);
+        acceleration = Vector3::new(acceleration.x + delta.x * pull, acceleration.y + delta.y * pull, acceleration.z + delta.z * pull);
+    }
+    acceleration
+}
+
+/// Camino previsto de la nave si deja de empujar: los puntos cada `PREDICTION_STEP` segundos y,
+/// si choca con un cuerpo antes del final, con cuál y cuándo
+#[derive(Clone, Debug, Default)]
+pub struct Trajectory {
+    pub points: Vec<Vector3>,
+    pub impact: Option<(Option<BodyRef>, f32)>, // (cuerpo, segundos hasta el choque)
+}
+
+impl Trajectory {
+    /// Integrar la trayectoria desde `position` con `velocity` durante `seconds` segundos a partir
+    /// del instante `time`, con la misma integración que el modelo de vuelo newtoniano
+    pub fn predict(scene: &Scene, sun_radius: f32, time: f32, position: Vector3, velocity: Vector3, strength: f32, seconds: f32) -> Self {
+        let steps = (seconds / PREDICTION_STEP).ceil().max(0.0) as usize;
+        let mut points = Vec::with_capacity(steps + 1);
+        let (mut position, mut velocity) = (position, velocity);
+        points.push(position);
+
+        for step in 0..steps {
+            let ahead = step as f32 * PREDICTION_STEP;
+            let sources = gravity_sources(scene, sun_radius, time, ahead);
+            let hit = sources.iter().find(|source| {
+                let (dx, dy, dz) = (position.x - source.position.x, position.y - source.position.y, position.z - source.position.z);
+                dx * dx + dy * dy + dz * dz < source.radius * source.radius
+            });
+            if let Some(source) = hit {
+                return Trajectory { points, impact: Some((source.body, ahead)) };
+            }
+
+            let acceleration = gravity_at(&sources, position, strength);
+            velocity = Vector3::new(
+                velocity.x + acceleration.x * PREDICTION_STEP,
+                velocity.y + acceleration.y * PREDICTION_STEP,
+                velocity.z + acceleration.z * PREDICTION_STEP,
+            );
+            position = Vector3::new(
+                position.x + velocity.x * PREDICTION_STEP,
+                position.y + velocity.y * PREDICTION_STEP,
+                position.z + velocity.z * PREDICTION_STEP,
+            );
+            points.push(position);
+        }
+        Trajectory { points, impact: None }
+    }
+
+    /// Dibuja el camino como una línea 3D que se apaga hacia el final; si acaba en un choque,
+    /// una cruz roja marca el punto
+    pub fn render(&self, renderer: &Renderer, framebuffer: &mut Framebuffer, uniforms: &Uniforms, color: Vector3) {
+        let segments = self.points.len().saturating_sub(1);
+        for (idx, pair) in self.points.windows(2).enumerate() {
+            let fade = 1.0 - idx as f32 / segments as f32;
+            let faded = Vector3::new(color.x * fade, color.y * fade, color.z * fade);
+            renderer.draw_line_3d(framebuffer, uniforms, pair[0], pair[1], faded);
+        }
+        if let (Some(_), Some(&p)) = (self.impact, self.points.last()) {
+            let red = Vector3::new(1.0, 0.15, 0.1);
+            let size = 0.5;
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x - size, p.y, p.z), Vector3::new(p.x + size, p.y, p.z), red);
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x, p.y - size, p.z), Vector3::new(p.x, p.y + size, p.z), red);
+            renderer.draw_line_3d(framebuffer, uniforms, Vector3::new(p.x, p.y, p.z - size), Vector3::new(p.x, p.y, p.z + size), red);
+        }
+    }
+}
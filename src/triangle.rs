@@ -1,133 +1,441 @@
+use crate::color::srgb_to_linear_rgb;
 use crate::fragment::Fragment;
+use crate::framebuffer::ScissorRect;
 use crate::vertex::Vertex;
 use crate::light::Light;
-use raylib::prelude::Vector3;
+use crate::material::Material;
+use crate::matrix::{clip_to_screen, logarithmic_depth};
+use raylib::prelude::{Matrix, Vector2, Vector3, Vector4};
 
-/// Calculate barycentric coordinates for a point P with respect to triangle (A, B, C)
-/// Returns (w1, w2, w3) where P = w1*A + w2*B + w3*C
-/// If the point is outside the triangle, one or more weights will be negative
-fn barycentric_coordinates(p_x: f32, p_y: f32, a: &Vertex, b: &Vertex, c: &Vertex) -> (f32, f32, f32) {
-    let a_x = a.transformed_position.x;
-    let a_y = a.transformed_position.y;
-    let b_x = b.transformed_position.x;
-    let b_y = b.transformed_position.y;
-    let c_x = c.transformed_position.x;
-    let c_y = c.transformed_position.y;
+/// Subpixel precision of the fixed-point screen coordinates (1/16 of a pixel)
+const SUBPIXEL_BITS: i64 = 4;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
 
-    // Calculate the area of the parallelogram formed by vectors AB and AC
-    let denom = (b_y - c_y) * (a_x - c_x) + (c_x - b_x) * (a_y - c_y);
+/// Guard band: triangles with a vertex farther than this many pixels from the origin (or with
+/// a non-finite coordinate, e.g. from a vertex at the camera plane) are dropped instead of
+/// rasterized. Keeps the fixed-point edge functions far from overflowing.
+pub const GUARD_BAND: f32 = 1.0e7;
 
-    // Avoid division by zero for degenerate triangles
-    if denom.abs() < 1e-10 {
-        return (-1.0, -1.0, -1.0);
+/// Side in pixels of the square tiles the triangle's bounding box is walked in.
+/// Tiles are independent of each other, so they can be filled in parallel.
+pub const TILE_SIZE: i32 = 8;
+
+fn to_fixed(v: f32) -> i64 {
+    (v * SUBPIXEL_ONE as f32).round() as i64
+}
+
+/// Integer edge function of the directed edge a -> b, evaluated at pixel centers.
+/// Positive on the interior side for triangles with positive area. Because it is exact,
+/// two triangles sharing an edge see exactly opposite values along it, and the top-left
+/// rule hands each pixel center lying on that edge to exactly one of them: no cracks, no overlap.
+#[derive(Clone, Copy)]
+struct Edge {
+    step_x: i64, // Change per pixel to the right
+    step_y: i64, // Change per pixel down
+    origin: i64, // Value at the center of pixel (0, 0), including the fill-rule bias
+    bias: i64,
+}
+
+impl Edge {
+    fn new(a: (i64, i64), b: (i64, i64)) -> Self {
+        let dx = b.0 - a.0;
+        let dy = b.1 - a.1;
+        // Top-left rule (screen Y grows downward): pixels exactly on a top edge (horizontal,
+        // going right) or a left edge (going up) are inside; on any other edge they are not
+        let is_top_left = (dy == 0 && dx > 0) || dy < 0;
+        let bias = if is_top_left { 0 } else { -1 };
+        let center = SUBPIXEL_ONE / 2;
+        Edge {
+            step_x: -dy * SUBPIXEL_ONE,
+            step_y: dx * SUBPIXEL_ONE,
+            origin: dx * (center - a.1) - dy * (center - a.0) + bias,
+            bias,
+        }
     }
 
-    // Calculate barycentric coordinates
-    let w1 = ((b_y - c_y) * (p_x - c_x) + (c_x - b_x) * (p_y - c_y)) / denom;
-    let w2 = ((c_y - a_y) * (p_x - c_x) + (a_x - c_x) * (p_y - c_y)) / denom;
-    let w3 = 1.0 - w1 - w2;
+    fn at(&self, x: i32, y: i32) -> i64 {
+        self.origin + self.step_x * x as i64 + self.step_y * y as i64
+    }
+}
 
-    (w1, w2, w3)
+/// Converts screen-space barycentric weights to perspective-correct ones using each vertex's clip w
+fn perspective_correct(w1: f32, w2: f32, w3: f32, a: &Vertex, b: &Vertex, c: &Vertex) -> (f32, f32, f32) {
+    let q1 = w1 / a.clip_position.w.max(1e-6);
+    let q2 = w2 / b.clip_position.w.max(1e-6);
+    let q3 = w3 / c.clip_position.w.max(1e-6);
+    let sum = q1 + q2 + q3;
+    if sum > 0.0 {
+        (q1 / sum, q2 / sum, q3 / sum)
+    } else {
+        (w1, w2, w3)
+    }
 }
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, light: &Light) -> Vec<Fragment> {
-    let mut fragments = Vec::new();
-
-    // === DEMO: Uncomment to show RGB color interpolation ===
-    // Assign RGB colors to the three vertices for interpolation demonstration
-    // let color1 = Vector3::new(1.0, 0.0, 0.0); // Red
-    // let color2 = Vector3::new(0.0, 0.0, 1.0); // Blue
-    // let color3 = Vector3::new(0.0, 1.0, 0.0); // Green
-
-    // Base gray color for all vertices
-    let base_color = Vector3::new(0.5, 0.5, 0.5);
-
-    // Get the bounding box of the triangle
-    let min_x = v1.transformed_position.x.min(v2.transformed_position.x).min(v3.transformed_position.x).floor() as i32;
-    let max_x = v1.transformed_position.x.max(v2.transformed_position.x).max(v3.transformed_position.x).ceil() as i32;
-    let min_y = v1.transformed_position.y.min(v2.transformed_position.y).min(v3.transformed_position.y).floor() as i32;
-    let max_y = v1.transformed_position.y.max(v2.transformed_position.y).max(v3.transformed_position.y).ceil() as i32;
-
-    // Iterate over each pixel in the bounding box
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            let p_x = x as f32 + 0.5; // Sample at pixel center
-            let p_y = y as f32 + 0.5;
-
-            // Calculate barycentric coordinates
-            let (w1, w2, w3) = barycentric_coordinates(p_x, p_y, v1, v2, v3);
-
-            // Check if point is inside the triangle
-            if w1 >= 0.0 && w2 >= 0.0 && w3 >= 0.0 {
-                // === DEMO: Uncomment to show RGB color interpolation ===
-                // Interpolate color using barycentric coordinates
-                // let interpolated_color = Vector3::new(
-                //     w1 * color1.x + w2 * color2.x + w3 * color3.x,
-                //     w1 * color1.y + w2 * color2.y + w3 * color3.y,
-                //     w1 * color1.z + w2 * color2.z + w3 * color3.z,
-                // );
-
-                // Interpolate normals using barycentric coordinates
-                let interpolated_normal = Vector3::new(
-                    w1 * v1.normal.x + w2 * v2.normal.x + w3 * v3.normal.x,
-                    w1 * v1.normal.y + w2 * v2.normal.y + w3 * v3.normal.y,
-                    w1 * v1.normal.z + w2 * v2.normal.z + w3 * v3.normal.z,
-                );
-
-                // Normalize the interpolated normal
-                let normal_length = (interpolated_normal.x * interpolated_normal.x
-                                   + interpolated_normal.y * interpolated_normal.y
-                                   + interpolated_normal.z * interpolated_normal.z).sqrt();
-                let mut normalized_normal = interpolated_normal;
-                if normal_length > 0.0 {
-                    normalized_normal.x /= normal_length;
-                    normalized_normal.y /= normal_length;
-                    normalized_normal.z /= normal_length;
-                }
+/// Everything needed to turn a covered pixel into a lit fragment
+struct Shading<'a> {
+    v1: &'a Vertex,
+    v2: &'a Vertex,
+    v3: &'a Vertex,
+    light: &'a Light,
+    material: &'a Material,
+    camera_position: Vector3,
+    base_color: Vector3,
+    far: f32,
+}
 
-                // Interpolate position in world space for this fragment
-                let world_pos = Vector3::new(
-                    w1 * v1.position.x + w2 * v2.position.x + w3 * v3.position.x,
-                    w1 * v1.position.y + w2 * v2.position.y + w3 * v3.position.y,
-                    w1 * v1.position.z + w2 * v2.position.z + w3 * v3.position.z,
-                );
-
-                // Light direction (from surface to light) for this fragment
-                let mut light_dir = Vector3::new(
-                    light.position.x - world_pos.x,
-                    light.position.y - world_pos.y,
-                    light.position.z - world_pos.z,
-                );
-
-                // Normalize light direction
-                let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
-                if light_length > 0.0 {
-                    light_dir.x /= light_length;
-                    light_dir.y /= light_length;
-                    light_dir.z /= light_length;
-                }
+impl Shading<'_> {
+    /// Builds the fragment for pixel (x, y) from its screen-space barycentric weights
+    fn fragment(&self, x: i32, y: i32, w1: f32, w2: f32, w3: f32) -> Fragment {
+        let p_x = x as f32 + 0.5; // Sample at pixel center
+        let p_y = y as f32 + 0.5;
 
-                // Calculate per-fragment lighting intensity using interpolated normal (Lambertian shading)
-                let intensity = (normalized_normal.x * light_dir.x
-                               + normalized_normal.y * light_dir.y
-                               + normalized_normal.z * light_dir.z).max(0.0);
+        // Screen-space weights are not linear in 3D under perspective: weight each vertex
+        // by 1/w and renormalize to interpolate 3D attributes correctly
+        let (p1, p2, p3) = perspective_correct(w1, w2, w3, self.v1, self.v2, self.v3);
 
-                // Apply shading to base color
-                let shaded_color = Vector3::new(
-                    base_color.x * intensity,
-                    base_color.y * intensity,
-                    base_color.z * intensity,
-                );
+        // Interpolate world-space normals
+        let interpolated_normal = Vector3::new(
+            p1 * self.v1.transformed_normal.x + p2 * self.v2.transformed_normal.x + p3 * self.v3.transformed_normal.x,
+            p1 * self.v1.transformed_normal.y + p2 * self.v2.transformed_normal.y + p3 * self.v3.transformed_normal.y,
+            p1 * self.v1.transformed_normal.z + p2 * self.v2.transformed_normal.z + p3 * self.v3.transformed_normal.z,
+        );
 
-                // Interpolate depth using barycentric coordinates
-                let depth = w1 * v1.transformed_position.z
-                          + w2 * v2.transformed_position.z
-                          + w3 * v3.transformed_position.z;
+        // Normalize the interpolated normal
+        let normal_length = (interpolated_normal.x * interpolated_normal.x
+                           + interpolated_normal.y * interpolated_normal.y
+                           + interpolated_normal.z * interpolated_normal.z).sqrt();
+        let mut normalized_normal = interpolated_normal;
+        if normal_length > 0.0 {
+            normalized_normal.x /= normal_length;
+            normalized_normal.y /= normal_length;
+            normalized_normal.z /= normal_length;
+        }
 
-                fragments.push(Fragment::new_with_world_pos(p_x, p_y, shaded_color, depth, world_pos));
-            }
+        // Interpolate position in world space (lighting) and object space (surface patterns)
+        let world_pos = Vector3::new(
+            p1 * self.v1.world_position.x + p2 * self.v2.world_position.x + p3 * self.v3.world_position.x,
+            p1 * self.v1.world_position.y + p2 * self.v2.world_position.y + p3 * self.v3.world_position.y,
+            p1 * self.v1.world_position.z + p2 * self.v2.world_position.z + p3 * self.v3.world_position.z,
+        );
+        let object_pos = Vector3::new(
+            p1 * self.v1.position.x + p2 * self.v2.position.x + p3 * self.v3.position.x,
+            p1 * self.v1.position.y + p2 * self.v2.position.y + p3 * self.v3.position.y,
+            p1 * self.v1.position.z + p2 * self.v2.position.z + p3 * self.v3.position.z,
+        );
+
+        // Light direction (from surface to light) for this fragment
+        let mut light_dir = Vector3::new(
+            self.light.position.x - world_pos.x,
+            self.light.position.y - world_pos.y,
+            self.light.position.z - world_pos.z,
+        );
+
+        // Normalize light direction
+        let light_length = (light_dir.x * light_dir.x + light_dir.y * light_dir.y + light_dir.z * light_dir.z).sqrt();
+        if light_length > 0.0 {
+            light_dir.x /= light_length;
+            light_dir.y /= light_length;
+            light_dir.z /= light_length;
+        }
+
+        // Calculate per-fragment lighting intensity using interpolated normal (Lambertian shading)
+        let intensity = (normalized_normal.x * light_dir.x
+                       + normalized_normal.y * light_dir.y
+                       + normalized_normal.z * light_dir.z).max(0.0);
+
+        // Baked ambient occlusion darkens creases and cavities of the mesh
+        let occlusion = p1 * self.v1.occlusion + p2 * self.v2.occlusion + p3 * self.v3.occlusion;
+
+        // Apply shading to base color
+        let shaded_color = Vector3::new(
+            self.base_color.x * intensity * occlusion * self.light.color.x,
+            self.base_color.y * intensity * occlusion * self.light.color.y,
+            self.base_color.z * intensity * occlusion * self.light.color.z,
+        );
+
+        // View direction (from surface to camera) for the specular term
+        let mut view_dir = Vector3::new(
+            self.camera_position.x - world_pos.x,
+            self.camera_position.y - world_pos.y,
+            self.camera_position.z - world_pos.z,
+        );
+        let view_length = (view_dir.x * view_dir.x + view_dir.y * view_dir.y + view_dir.z * view_dir.z).sqrt();
+        if view_length > 0.0 {
+            view_dir.x /= view_length;
+            view_dir.y /= view_length;
+            view_dir.z /= view_length;
+        }
+
+        // Blinn-Phong: half vector between light and view directions
+        let mut half_dir = Vector3::new(
+            light_dir.x + view_dir.x,
+            light_dir.y + view_dir.y,
+            light_dir.z + view_dir.z,
+        );
+        let half_length = (half_dir.x * half_dir.x + half_dir.y * half_dir.y + half_dir.z * half_dir.z).sqrt();
+        if half_length > 0.0 {
+            half_dir.x /= half_length;
+            half_dir.y /= half_length;
+            half_dir.z /= half_length;
+        }
+
+        // Only surfaces facing the light receive a highlight
+        let specular_intensity = if intensity > 0.0 {
+            (normalized_normal.x * half_dir.x
+           + normalized_normal.y * half_dir.y
+           + normalized_normal.z * half_dir.z).max(0.0).powf(self.material.shininess)
+        } else {
+            0.0
+        };
+
+        let specular_color = Vector3::new(
+            self.material.specular_color.x * self.light.color.x * specular_intensity,
+            self.material.specular_color.y * self.light.color.y * specular_intensity,
+            self.material.specular_color.z * self.light.color.z * specular_intensity,
+        );
+
+        // Logarithmic depth per fragment: log2(1 + d) is not linear in screen space, so the
+        // view distance is interpolated perspective-correctly like the other attributes and
+        // only then converted (interpolating the vertices' depth would bend it inside large triangles)
+        let distance = p1 * self.v1.view_distance + p2 * self.v2.view_distance + p3 * self.v3.view_distance;
+        let depth = logarithmic_depth(distance, self.far);
+
+        Fragment {
+            position: Vector2::new(p_x, p_y),
+            color: shaded_color,
+            depth,
+            world_position: world_pos,
+            object_position: object_pos,
+            normal: normalized_normal,
+            specular: specular_color,
         }
     }
+}
 
-    fragments
+/// Whether a vertex is finite and inside the guard band
+fn in_guard_band(v: &Vertex) -> bool {
+    let p = v.transformed_position;
+    p.x.is_finite() && p.y.is_finite() && p.x.abs() <= GUARD_BAND && p.y.abs() <= GUARD_BAND
+}
+
+/// How a screen-space triangle relates to the writable area, decided from its vertices alone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Coverage {
+    /// Produces no fragments: degenerate, entirely behind the near plane, outside the guard band
+    /// or entirely outside the scissor rectangle
+    Culled,
+    /// Crosses the near plane (split by `clip_near` before rasterizing) or is partially outside
+    /// the scissor rectangle (rasterization is clamped to it)
+    Clipped,
+    /// Entirely inside the scissor rectangle
+    Inside,
+}
+
+/// Classifies a triangle the same way `clip_near` and `triangle` bound it, without rasterizing it.
+/// `near` is the view distance of the near plane.
+/// Used for pipeline statistics and to skip culled triangles early.
+pub fn coverage(v1: &Vertex, v2: &Vertex, v3: &Vertex, near: f32, scissor: &ScissorRect) -> Coverage {
+    let behind = [v1, v2, v3].iter().filter(|v| v.view_distance < near).count();
+    if scissor.is_empty() || behind == 3 {
+        return Coverage::Culled;
+    }
+    if behind > 0 {
+        // The screen positions of the vertices behind the camera are mirrored by the
+        // perspective divide, so they say nothing about the visible part
+        return Coverage::Clipped;
+    }
+    if !(in_guard_band(v1) && in_guard_band(v2) && in_guard_band(v3)) {
+        return Coverage::Culled;
+    }
+
+    let fixed = |v: &Vertex| (to_fixed(v.transformed_position.x), to_fixed(v.transformed_position.y));
+    let (a, b, c) = (fixed(v1), fixed(v2), fixed(v3));
+    if (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0) == 0 {
+        return Coverage::Culled;
+    }
+
+    let (p1, p2, p3) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
+    let min_x = p1.x.min(p2.x).min(p3.x).floor() as i32;
+    let max_x = p1.x.max(p2.x).max(p3.x).ceil() as i32;
+    let min_y = p1.y.min(p2.y).min(p3.y).floor() as i32;
+    let max_y = p1.y.max(p2.y).max(p3.y).ceil() as i32;
+    let (right, bottom) = (scissor.x + scissor.width - 1, scissor.y + scissor.height - 1);
+    if max_x < scissor.x || min_x > right || max_y < scissor.y || min_y > bottom {
+        Coverage::Culled
+    } else if min_x >= scissor.x && max_x <= right && min_y >= scissor.y && max_y <= bottom {
+        Coverage::Inside
+    } else {
+        Coverage::Clipped
+    }
+}
+
+/// Vertex on the edge a -> b at `t`: every attribute is affine in 3D along the edge, clip-space
+/// position included, so they are interpolated linearly and the screen position is projected again
+fn lerp_vertex(a: &Vertex, b: &Vertex, t: f32, projection: &Matrix, viewport: &Matrix) -> Vertex {
+    let lerp3 = |p: Vector3, q: Vector3| Vector3::new(p.x + (q.x - p.x) * t, p.y + (q.y - p.y) * t, p.z + (q.z - p.z) * t);
+    let (p, q) = (a.clip_position, b.clip_position);
+    let clip_position = Vector4::new(p.x + (q.x - p.x) * t, p.y + (q.y - p.y) * t, p.z + (q.z - p.z) * t, p.w + (q.w - p.w) * t);
+    Vertex {
+        position: lerp3(a.position, b.position),
+        normal: lerp3(a.normal, b.normal),
+        tex_coords: Vector2::new(a.tex_coords.x + (b.tex_coords.x - a.tex_coords.x) * t, a.tex_coords.y + (b.tex_coords.y - a.tex_coords.y) * t),
+        color: lerp3(a.color, b.color),
+        transformed_position: clip_to_screen(&clip_position, projection, viewport),
+        transformed_normal: lerp3(a.transformed_normal, b.transformed_normal),
+        world_position: lerp3(a.world_position, b.world_position),
+        clip_position,
+        view_distance: a.view_distance + (b.view_distance - a.view_distance) * t,
+        occlusion: a.occlusion + (b.occlusion - a.occlusion) * t,
+    }
+}
+
+/// Clips a triangle against the near plane (view distance `near`) in homogeneous space, before
+/// the perspective divide, and hands what is left to `emit`: the triangle itself if it is
+/// entirely in front of the plane, nothing if it is entirely behind, or the one or two
+/// triangles of the visible part if it crosses it. Without this, the vertices behind the
+/// camera would be divided by a negative w and project mirrored across the screen.
+/// `projection` and `viewport` place the new vertices on the screen like the vertex shader.
+pub fn clip_near(vertices: [&Vertex; 3], near: f32, projection: &Matrix, viewport: &Matrix, mut emit: impl FnMut([&Vertex; 3])) {
+    let inside = vertices.map(|v| v.view_distance >= near);
+    if inside == [true; 3] {
+        emit(vertices);
+        return;
+    }
+
+    // Sutherland-Hodgman against a single plane: a triangle becomes a polygon of up to four
+    // vertices, fanned out from the first one. The view distance is linear in clip space
+    let mut polygon: [Vertex; 4] = Default::default();
+    let mut count = 0;
+    for i in 0..3 {
+        let (a, b) = (vertices[i], vertices[(i + 1) % 3]);
+        if inside[i] {
+            polygon[count] = a.clone();
+            count += 1;
+        }
+        if inside[i] != inside[(i + 1) % 3] {
+            let t = (near - a.view_distance) / (b.view_distance - a.view_distance);
+            polygon[count] = lerp_vertex(a, b, t, projection, viewport);
+            count += 1;
+        }
+    }
+    for i in 1..count.saturating_sub(1) {
+        emit([&polygon[0], &polygon[i], &polygon[i + 1]]);
+    }
+}
+
+/// Rasterizes a triangle with fixed-point edge functions, walking its bounding box (clamped to
+/// the `scissor` rectangle) in `TILE_SIZE` x `TILE_SIZE` tiles, so off-screen parts of a
+/// triangle never generate fragments. Tiles entirely outside one edge are skipped, and tiles
+/// entirely inside all three edges skip the per-pixel coverage test.
+/// Both windings are accepted (no back-face culling).
+/// The vertices must be in front of the near plane: triangles that cross it go through `clip_near` first.
+/// `far` is the far plane of the projection, for the logarithmic depth of each fragment.
+/// Each fragment is handed to `emit` as soon as its pixel is found to be covered, so the caller
+/// can shade and write it right away and no fragment list is ever built, however much of the
+/// screen the triangle fills.
+pub fn triangle(
+    vertices: [&Vertex; 3],
+    light: &Light,
+    material: &Material,
+    camera_position: Vector3,
+    far: f32,
+    scissor: &ScissorRect,
+    mut emit: impl FnMut(&Fragment),
+) {
+    let [v1, v2, v3] = vertices;
+    if scissor.is_empty() {
+        return;
+    }
+
+    if !(in_guard_band(v1) && in_guard_band(v2) && in_guard_band(v3)) {
+        return;
+    }
+
+    let fixed = |v: &Vertex| (to_fixed(v.transformed_position.x), to_fixed(v.transformed_position.y));
+    let (a, mut b, mut c) = (fixed(v1), fixed(v2), fixed(v3));
+    let (mut v2, mut v3) = (v2, v3);
+
+    // Twice the signed area; flip the winding so the interior is always on the positive side
+    let mut area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+    if area == 0 {
+        return; // Degenerate triangle
+    }
+    if area < 0 {
+        std::mem::swap(&mut b, &mut c);
+        std::mem::swap(&mut v2, &mut v3);
+        area = -area;
+    }
+
+    // Each edge function is the (unnormalized) weight of the opposite vertex
+    let edges = [Edge::new(b, c), Edge::new(c, a), Edge::new(a, b)];
+    let inv_area = 1.0 / area as f32;
+
+    // Base color comes from the material (gray by default); materials are authored
+    // in sRGB, lighting is computed in linear space
+    let shading = Shading {
+        v1,
+        v2,
+        v3,
+        light,
+        material,
+        camera_position,
+        base_color: srgb_to_linear_rgb(material.diffuse_color),
+        far,
+    };
+
+    // Get the bounding box of the triangle, clamped to the scissor rectangle
+    let min_x = (v1.transformed_position.x.min(v2.transformed_position.x).min(v3.transformed_position.x).floor() as i32).max(scissor.x);
+    let max_x = (v1.transformed_position.x.max(v2.transformed_position.x).max(v3.transformed_position.x).ceil() as i32).min(scissor.x + scissor.width - 1);
+    let min_y = (v1.transformed_position.y.min(v2.transformed_position.y).min(v3.transformed_position.y).floor() as i32).max(scissor.y);
+    let max_y = (v1.transformed_position.y.max(v2.transformed_position.y).max(v3.transformed_position.y).ceil() as i32).min(scissor.y + scissor.height - 1);
+    if min_x > max_x || min_y > max_y {
+        return; // Entirely outside the scissor rectangle
+    }
+
+    // Walk the bounding box tile by tile (tiles aligned to the TILE_SIZE grid)
+    let first_tile_x = min_x.div_euclid(TILE_SIZE) * TILE_SIZE;
+    let first_tile_y = min_y.div_euclid(TILE_SIZE) * TILE_SIZE;
+    for tile_y in (first_tile_y..=max_y).step_by(TILE_SIZE as usize) {
+        for tile_x in (first_tile_x..=max_x).step_by(TILE_SIZE as usize) {
+            let x0 = tile_x.max(min_x);
+            let y0 = tile_y.max(min_y);
+            let x1 = (tile_x + TILE_SIZE - 1).min(max_x);
+            let y1 = (tile_y + TILE_SIZE - 1).min(max_y);
+
+            // Edge functions are linear, so over the tile they peak at one of its corners
+            let mut fully_inside = true;
+            let mut fully_outside = false;
+            for edge in &edges {
+                let corners = [edge.at(x0, y0), edge.at(x1, y0), edge.at(x0, y1), edge.at(x1, y1)];
+                let max = corners.iter().copied().max().unwrap_or(-1);
+                let min = corners.iter().copied().min().unwrap_or(-1);
+                if max < 0 {
+                    fully_outside = true;
+                    break;
+                }
+                if min < 0 {
+                    fully_inside = false;
+                }
+            }
+            if fully_outside {
+                continue;
+            }
+
+            for y in y0..=y1 {
+                let mut e = [edges[0].at(x0, y), edges[1].at(x0, y), edges[2].at(x0, y)];
+                for x in x0..=x1 {
+                    // Check if the pixel center is inside the triangle
+                    if fully_inside || (e[0] >= 0 && e[1] >= 0 && e[2] >= 0) {
+                        let w1 = (e[0] - edges[0].bias) as f32 * inv_area;
+                        let w2 = (e[1] - edges[1].bias) as f32 * inv_area;
+                        let w3 = 1.0 - w1 - w2;
+                        emit(&shading.fragment(x, y, w1, w2, w3));
+                    }
+                    for (value, edge) in e.iter_mut().zip(edges.iter()) {
+                        *value += edge.step_x;
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,71 @@
+use raylib::prelude::*;
+use crate::matrix::camera_relative_mvp;
+use crate::scene::{Atmosphere, Crater, Eruption, Storm};
+use crate::shaders::RingUniforms;
+
+/// Luz de relleno reflejada por un cuerpo cercano (la luz cenicienta que un planeta devuelve
+/// sobre sus lunas)
+#[derive(Clone, Copy, Debug)]
+pub struct FillLight {
+    pub direction: Vector3, // Dirección hacia el cuerpo que la refleja, en espacio de objeto (normalizada)
+    pub color: Vector3,     // Color lineal ya multiplicado por su fuerza (1 = luz directa del sol)
+}
+
+pub struct Uniforms {
+    pub model_matrix: Matrix,
+    pub view_matrix: Matrix,
+    pub projection_matrix: Matrix,
+    pub viewport_matrix: Matrix,
+    pub mvp_matrix: Matrix,       // projection * view * model, combinada una vez por objeto y frame
+    pub time: f32,
+    pub camera_position: Vector3, // Posición de la cámara (para iluminación especular)
+    pub rings: Option<RingUniforms>, // Anillos: transparencia y sombras mutuas (None si no aplica)
+    pub craters: Vec<Crater>,     // Cráteres de impacto del cuerpo (vacío si no tiene)
+    pub eruptions: Vec<Eruption>, // Erupciones volcánicas en curso (vacío si no tiene)
+    pub storms: Vec<Storm>,       // Tormentas de la atmósfera, ya desplazadas a la hora actual
+    pub atmosphere: Option<Atmosphere>, // Dispersión atmosférica del cuerpo (None si no tiene aire)
+    pub fill_light: Option<FillLight>,  // Luz reflejada por el planeta padre (solo lunas)
+    pub sun_direction: Vector3,   // Dirección hacia el sol en espacio de objeto (normalizada)
+}
+
+impl Uniforms {
+    /// Crear los uniforms de un objeto precalculando la matriz MVP combinada
+    /// Así cada vértice pasa por una sola multiplicación 4x4 en lugar de tres antes de la división
+    /// de perspectiva; la matriz de modelo se conserva aparte para quien necesite el espacio de mundo
+    /// La MVP se compone relativa a la cámara (`view_matrix` debe mirar desde `camera_position`):
+    /// lejos del origen los objetos no tiemblan aunque las posiciones del mundo sean enormes
+    pub fn new(
+        model_matrix: Matrix,
+        view_matrix: Matrix,
+        projection_matrix: Matrix,
+        viewport_matrix: Matrix,
+        time: f32,
+        camera_position: Vector3,
+        sun_direction: Vector3,
+    ) -> Self {
+        let mvp_matrix = camera_relative_mvp(&projection_matrix, &view_matrix, &model_matrix, camera_position);
+        Uniforms {
+            model_matrix,
+            view_matrix,
+            projection_matrix,
+            viewport_matrix,
+            mvp_matrix,
+            time,
+            camera_position,
+            rings: None,
+            craters: Vec::new(),
+            eruptions: Vec::new(),
+            storms: Vec::new(),
+            atmosphere: None,
+            fill_light: None,
+            sun_direction,
+        }
+    }
+
+    /// Cambiar el objeto que se dibuja con estos uniforms (misma cámara), recombinando la MVP
+    pub fn set_model(&mut self, model_matrix: Matrix, sun_direction: Vector3) {
+        self.mvp_matrix = camera_relative_mvp(&self.projection_matrix, &self.view_matrix, &model_matrix, self.camera_position);
+        self.model_matrix = model_matrix;
+        self.sun_direction = sun_direction;
+    }
+}
@@ -0,0 +1,158 @@
+use std::f32::consts::PI;
+
+/// Unidades de la escena por unidad astronómica (las órbitas son proporcionales a las reales)
+pub const AU_UNITS: f32 = 15.0;
+/// Kilómetros en una unidad astronómica
+pub const KM_PER_AU: f64 = 149_597_870.7;
+/// Segundos reales que dura un año terrestre de las órbitas (los demás periodos son proporcionales)
+pub const YEAR_SECONDS: f32 = 120.0;
+/// Días terrestres en un año
+pub const DAYS_PER_YEAR: f32 = 365.25;
+/// Radio medio de la Tierra en km
+pub const EARTH_RADIUS_KM: f32 = 6371.0;
+/// Escala de la Tierra; los demás radios se comprimen con la raíz cuadrada de su tamaño real
+/// para que Júpiter no sea más grande que el sol ni Mercurio invisible
+pub const EARTH_SCALE: f32 = 0.8;
+
+/// Distancia de la escena en unidades astronómicas
+pub fn units_to_au(units: f32) -> f32 {
+    units / AU_UNITS
+}
+
+pub fn au_to_units(au: f32) -> f32 {
+    au * AU_UNITS
+}
+
+/// Distancia de la escena en km (f64: las distancias del sistema solar pierden precisión en f32)
+pub fn units_to_km(units: f32) -> f64 {
+    units_to_au(units) as f64 * KM_PER_AU
+}
+
+pub fn km_to_units(km: f64) -> f32 {
+    au_to_units((km / KM_PER_AU) as f32)
+}
+
+/// Escala de la escena para un cuerpo de `earth_radii` radios terrestres
+pub fn radius_scale(earth_radii: f32) -> f32 {
+    EARTH_SCALE * earth_radii.max(0.0).sqrt()
+}
+
+/// Escala de la escena para un cuerpo de `radius_km` km de radio (comprimida como `radius_scale`)
+pub fn radius_km_to_scale(radius_km: f32) -> f32 {
+    radius_scale(radius_km / EARTH_RADIUS_KM)
+}
+
+/// Velocidad angular (rad/s reales) de una órbita de `days` días terrestres
+pub fn orbital_speed_for_period(days: f32) -> f32 {
+    2.0 * PI / (days / DAYS_PER_YEAR * YEAR_SECONDS)
+}
+
+/// Días terrestres que dura una órbita de `speed` rad/s reales (la inversa de la anterior)
+pub fn orbital_period_days(speed: f32) -> f32 {
+    orbit_seconds_to_days(2.0 * PI / speed.abs().max(1.0e-6))
+}
+
+/// Días simulados que pasan en las órbitas durante `seconds` segundos reales
+pub fn orbit_seconds_to_days(seconds: f32) -> f32 {
+    seconds / YEAR_SECONDS * DAYS_PER_YEAR
+}
+
+/// Días simulados del reloj en `hours` horas simuladas
+pub fn hours_to_days(hours: f64) -> f64 {
+    hours / 24.0
+}
+
+/// Unidad de una distancia del archivo de escena
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceUnit {
+    Scene, // Unidades de la escena (sin sufijo o `u`)
+    Au,    // Unidades astronómicas (`au` o `ua`)
+    Km,    // Kilómetros
+}
+
+impl DistanceUnit {
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix.to_ascii_lowercase().as_str() {
+            "" | "u" => Some(DistanceUnit::Scene),
+            "au" | "ua" => Some(DistanceUnit::Au),
+            "km" => Some(DistanceUnit::Km),
+            _ => None,
+        }
+    }
+
+    /// `value` en esta unidad pasado a unidades de la escena
+    pub fn to_units(self, value: f64) -> f32 {
+        match self {
+            DistanceUnit::Scene => value as f32,
+            DistanceUnit::Au => au_to_units(value as f32),
+            DistanceUnit::Km => km_to_units(value),
+        }
+    }
+}
+
+/// Unidad de una duración del archivo de escena
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeUnit {
+    Hours, // `h`
+    Days,  // `d`
+    Years, // `y` (años terrestres)
+}
+
+impl TimeUnit {
+    pub fn from_suffix(suffix: &str) -> Option<Self> {
+        match suffix.to_ascii_lowercase().as_str() {
+            "h" => Some(TimeUnit::Hours),
+            "d" => Some(TimeUnit::Days),
+            "y" => Some(TimeUnit::Years),
+            _ => None,
+        }
+    }
+
+    /// `value` en esta unidad pasado a días
+    pub fn to_days(self, value: f64) -> f64 {
+        match self {
+            TimeUnit::Hours => value / 24.0,
+            TimeUnit::Days => value,
+            TimeUnit::Years => value * DAYS_PER_YEAR as f64,
+        }
+    }
+}
+
+/// Separar una cantidad como `1.52 AU` o `3389.5km` en el número y el sufijo de la unidad
+/// (vacío si no lleva). None si no empieza por un número
+pub fn split_quantity(value: &str) -> Option<(f64, &str)> {
+    let value = value.trim();
+    // La `e` de la notación científica (`1.5e8`) es parte del número
+    let exponent = |index: usize| value[index + 1..].starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+');
+    let split = value
+        .char_indices()
+        .find(|&(index, c)| c.is_alphabetic() && !(matches!(c, 'e' | 'E') && exponent(index)))
+        .map_or(value.len(), |(index, _)| index);
+    let number = value[..split].trim().parse::<f64>().ok().filter(|number| number.is_finite())?;
+    Some((number, value[split..].trim()))
+}
+
+/// Distancia de la escena para el HUD: en UA a partir de 0.1 UA y en km por debajo
+pub fn format_distance(units: f32) -> String {
+    let au = units_to_au(units);
+    if au.abs() >= 0.1 {
+        return format!("{:.2} UA", au);
+    }
+    let km = units_to_km(units);
+    if km.abs() >= 1.0e6 {
+        format!("{:.1} millones de km", km / 1.0e6)
+    } else {
+        format!("{:.0} km", km)
+    }
+}
+
+/// Duración en días simulados para el HUD: en horas si no llega a un día
+pub fn format_days(days: f32) -> String {
+    if days.abs() < 1.0 {
+        format!("{:.0} h", days * 24.0)
+    } else if days.abs() < 10.0 {
+        format!("{:.1} días", days)
+    } else {
+        format!("{:.0} días", days)
+    }
+}
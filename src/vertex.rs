@@ -1,6 +1,4 @@
-#![allow(dead_code)]
-
-use raylib::math::{Vector2, Vector3};
+use raylib::math::{Vector2, Vector3, Vector4};
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -9,7 +7,11 @@ pub struct Vertex {
   pub tex_coords: Vector2,
   pub color: Vector3,
   pub transformed_position: Vector3,
-  pub transformed_normal: Vector3,
+  pub transformed_normal: Vector3, // World-space normal
+  pub world_position: Vector3,     // Position after the model transform
+  pub clip_position: Vector4,      // Clip-space position, for near-plane clipping; its w (view distance, 1 under an orthographic projection) makes interpolation perspective-correct
+  pub view_distance: f32,          // Distance in front of the camera, interpolated per fragment for the logarithmic depth
+  pub occlusion: f32,              // Baked ambient occlusion (1 = fully open, see `Obj::bake_ambient_occlusion`)
 }
 
 impl Vertex {
@@ -21,6 +23,10 @@ impl Vertex {
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: position,
       transformed_normal: normal,
+      world_position: position,
+      clip_position: Vector4::new(0.0, 0.0, 0.0, 1.0),
+      view_distance: 1.0,
+      occlusion: 1.0,
     }
   }
 
@@ -32,6 +38,10 @@ impl Vertex {
       color,
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 0.0, 0.0),
+      world_position: position,
+      clip_position: Vector4::new(0.0, 0.0, 0.0, 1.0),
+      view_distance: 1.0,
+      occlusion: 1.0,
     }
   }
 
@@ -50,6 +60,10 @@ impl Default for Vertex {
       color: Vector3::new(0.0, 0.0, 0.0), // Black
       transformed_position: Vector3::new(0.0, 0.0, 0.0),
       transformed_normal: Vector3::new(0.0, 1.0, 0.0),
+      world_position: Vector3::new(0.0, 0.0, 0.0),
+      clip_position: Vector4::new(0.0, 0.0, 0.0, 1.0),
+      view_distance: 1.0,
+      occlusion: 1.0,
     }
   }
 }
\ No newline at end of file
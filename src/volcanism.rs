@@ -0,0 +1,104 @@
+use raylib::prelude::*;
+use crate::scene::Scene;
+use crate::shaders::{lava_factor, PlanetType};
+use std::f32::consts::PI;
+
+/// Sucesos de la actividad volcánica que el juego puede atender
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VolcanicEvent {
+    /// El planeta `planet` entra en erupción en `center` (dirección en su espacio de objeto)
+    Eruption { planet: usize, center: Vector3 },
+}
+
+/// Generador de erupciones de los planetas volcánicos: cada planeta de tipo `Volcanic` entra en
+/// erupción a intervalos aleatorios (proceso de Poisson, como la actividad solar) en un punto
+/// elegido con el mismo ruido que dibuja la lava: de varias candidatas se queda con la que más
+/// lava tiene en ese momento
+/// `update` solo devuelve los sucesos; el juego registra la erupción en el planeta
+/// (`Planet::add_eruption`) y lanza la eyecta
+pub struct VolcanicActivity {
+    pub enabled: bool,
+    pub mean_interval: f32,  // Segundos medios entre erupciones de un mismo planeta
+    pub duration: f32,       // Segundos que dura cada erupción
+    pub candidates: usize,   // Puntos al azar entre los que se elige la boca
+    next_eruption: Vec<f32>, // Próxima erupción de cada planeta (por índice; NaN = sin programar)
+    rng_state: u64,
+}
+
+impl VolcanicActivity {
+    pub fn new(mean_interval: f32, duration: f32) -> Self {
+        VolcanicActivity {
+            enabled: true,
+            mean_interval,
+            duration,
+            candidates: 12,
+            next_eruption: Vec::new(),
+            rng_state: 0x1A7A_F10E,
+        }
+    }
+
+    /// Olvida la programación (nueva escena): cada planeta vuelve a esperar un intervalo
+    /// aleatorio a partir de la primera actualización
+    pub fn reset(&mut self) {
+        self.next_eruption.clear();
+    }
+
+    /// Provoca una erupción inmediata en el planeta `planet` en `time`
+    pub fn trigger(&mut self, planet: usize, time: f32) -> VolcanicEvent {
+        // Varias direcciones uniformes sobre la esfera; gana la que cae sobre más lava
+        let mut center = Vector3::new(0.0, 1.0, 0.0);
+        let mut best = f32::MIN;
+        for _ in 0..self.candidates.max(1) {
+            let y = self.random() * 2.0 - 1.0;
+            let azimuth = self.random() * 2.0 * PI;
+            let ring = (1.0 - y * y).max(0.0).sqrt();
+            let direction = Vector3::new(ring * azimuth.cos(), y, ring * azimuth.sin());
+            let lava = lava_factor(direction, time);
+            if lava > best {
+                best = lava;
+                center = direction;
+            }
+        }
+        VolcanicEvent::Eruption { planet, center }
+    }
+
+    /// Avanza la actividad hasta `time` y devuelve las erupciones que empiezan, en orden
+    pub fn update(&mut self, time: f32, scene: &Scene) -> Vec<VolcanicEvent> {
+        let mut events = Vec::new();
+        if !self.enabled {
+            return events;
+        }
+
+        self.next_eruption.resize(scene.planets.len(), f32::NAN);
+        for (idx, planet) in scene.planets.iter().enumerate() {
+            if !matches!(planet.planet_type, PlanetType::Volcanic) {
+                continue;
+            }
+            if self.next_eruption[idx].is_nan() {
+                self.next_eruption[idx] = time + self.random_interval();
+            }
+            if time >= self.next_eruption[idx] {
+                events.push(self.trigger(idx, time));
+                self.next_eruption[idx] = time + self.duration + self.random_interval();
+            }
+        }
+        events
+    }
+
+    /// Valor pseudoaleatorio en [0, 1) (LCG, como la actividad solar)
+    fn random(&mut self) -> f32 {
+        self.rng_state = self.rng_state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.rng_state >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Espera hasta la próxima erupción: distribución exponencial de media `mean_interval`
+    fn random_interval(&mut self) -> f32 {
+        -(1.0 - self.random()).ln() * self.mean_interval.max(0.1)
+    }
+}
+
+impl Default for VolcanicActivity {
+    fn default() -> Self {
+        Self::new(25.0, 8.0)
+    }
+}
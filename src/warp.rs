@@ -0,0 +1,88 @@
+use raylib::prelude::*;
+
+// Estructura para el sistema de teletransporte (warping) - ahora sobre la nave y la cámara
+pub struct WarpSystem {
+    pub is_warping: bool,           // Si está en proceso de warp
+    pub warp_progress: f32,          // Progreso del warp (0.0 a 1.0)
+    pub warp_duration: f32,          // Duración total del warp en segundos
+    warp_start_time: f32,            // Tiempo cuando comenzó el warp
+    target_ship_position: Vector3,        // Posición objetivo de la nave
+    start_ship_position: Vector3,         // Posición inicial de la nave
+    target_camera_position: Vector3,      // Posición objetivo de la cámara
+    start_camera_position: Vector3,        // Posición inicial de la cámara
+}
+
+impl WarpSystem {
+    pub fn new() -> Self {
+        WarpSystem {
+            is_warping: false,
+            warp_progress: 0.0,
+            warp_duration: 1.0, // 1 segundo de animación
+            warp_start_time: 0.0,
+            target_ship_position: Vector3::zero(),
+            start_ship_position: Vector3::zero(),
+            target_camera_position: Vector3::zero(),
+            start_camera_position: Vector3::zero(),
+        }
+    }
+
+    pub fn start_warp(&mut self, current_time: f32, start_ship_pos: Vector3, target_ship_pos: Vector3, start_camera_pos: Vector3, target_camera_pos: Vector3) {
+        self.is_warping = true;
+        self.warp_progress = 0.0;
+        self.warp_start_time = current_time;
+        self.start_ship_position = start_ship_pos;
+        self.target_ship_position = target_ship_pos;
+        self.start_camera_position = start_camera_pos;
+        self.target_camera_position = target_camera_pos;
+    }
+
+    pub fn update(&mut self, current_time: f32) -> bool {
+        if !self.is_warping {
+            return false;
+        }
+
+        let elapsed = current_time - self.warp_start_time;
+        self.warp_progress = (elapsed / self.warp_duration).min(1.0);
+
+        if self.warp_progress >= 1.0 {
+            self.is_warping = false;
+            self.warp_progress = 1.0;
+            return true; // Warp completado
+        }
+        false
+    }
+
+    pub fn get_current_ship_position(&self) -> Vector3 {
+        if !self.is_warping {
+            return self.target_ship_position;
+        }
+        // Interpolación suave con easing (ease-in-out)
+        let t = self.warp_progress;
+        let eased_t = t * t * (3.0 - 2.0 * t); // Smoothstep
+        Vector3::new(
+            self.start_ship_position.x + (self.target_ship_position.x - self.start_ship_position.x) * eased_t,
+            self.start_ship_position.y + (self.target_ship_position.y - self.start_ship_position.y) * eased_t,
+            self.start_ship_position.z + (self.target_ship_position.z - self.start_ship_position.z) * eased_t,
+        )
+    }
+
+    pub fn get_current_camera_position(&self) -> Vector3 {
+        if !self.is_warping {
+            return self.target_camera_position;
+        }
+        // Interpolación suave con easing (ease-in-out)
+        let t = self.warp_progress;
+        let eased_t = t * t * (3.0 - 2.0 * t); // Smoothstep
+        Vector3::new(
+            self.start_camera_position.x + (self.target_camera_position.x - self.start_camera_position.x) * eased_t,
+            self.start_camera_position.y + (self.target_camera_position.y - self.start_camera_position.y) * eased_t,
+            self.start_camera_position.z + (self.target_camera_position.z - self.start_camera_position.z) * eased_t,
+        )
+    }
+}
+
+impl Default for WarpSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
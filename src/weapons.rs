@@ -0,0 +1,138 @@
+use raylib::prelude::*;
+use crate::asteroids::AsteroidBelt;
+
+/// Proyectil disparado por la nave
+#[derive(Clone, Copy, Debug)]
+pub struct Projectile {
+    pub position: Vector3,
+    pub velocity: Vector3,
+    pub age: f32, // Segundos desde el disparo
+}
+
+/// Sucesos del combate que el juego puede atender
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeaponEvent {
+    /// Un proyectil destruye el asteroide `id` en `position`; `size` es su radio
+    AsteroidDestroyed { id: u32, position: Vector3, size: f32 },
+}
+
+/// Cañón de la nave: dispara proyectiles en línea recta, los integra y comprueba si alcanzan
+/// algún asteroide del cinturón. Igual que el cinturón, `update` solo devuelve sucesos: el juego
+/// dibuja la explosión
+pub struct Weapons {
+    pub muzzle_speed: f32,   // Velocidad del proyectil respecto a la nave (unidades por segundo)
+    pub lifetime: f32,       // Segundos hasta que el proyectil se pierde
+    pub cooldown: f32,       // Segundos mínimos entre disparos
+    pub radius: f32,         // Radio de colisión (y de dibujo) de cada proyectil
+    pub max_projectiles: usize,
+    projectiles: Vec<Projectile>,
+    last_shot: f32,
+}
+
+impl Weapons {
+    pub fn new() -> Self {
+        Weapons {
+            muzzle_speed: 90.0,
+            lifetime: 2.5,
+            cooldown: 0.15,
+            radius: 0.15,
+            max_projectiles: 64,
+            projectiles: Vec::new(),
+            last_shot: f32::NEG_INFINITY,
+        }
+    }
+
+    pub fn projectiles(&self) -> &[Projectile] {
+        &self.projectiles
+    }
+
+    /// Dispara desde `origin` a lo largo de `direction` (normalizada), sumando la velocidad de la
+    /// nave. Devuelve false si el cañón aún se está enfriando o hay demasiados proyectiles en vuelo
+    pub fn fire(&mut self, time: f32, origin: Vector3, direction: Vector3, ship_velocity: Vector3) -> bool {
+        if time - self.last_shot < self.cooldown || self.projectiles.len() >= self.max_projectiles {
+            return false;
+        }
+        self.last_shot = time;
+        self.projectiles.push(Projectile {
+            position: origin,
+            velocity: Vector3::new(
+                ship_velocity.x + direction.x * self.muzzle_speed,
+                ship_velocity.y + direction.y * self.muzzle_speed,
+                ship_velocity.z + direction.z * self.muzzle_speed,
+            ),
+            age: 0.0,
+        });
+        true
+    }
+
+    /// Avanza los proyectiles y destruye los asteroides alcanzados
+    /// Se prueba el segmento recorrido en el frame contra las rocas cercanas (las del índice
+    /// espacial del cinturón), para que los proyectiles rápidos no atraviesen las pequeñas entre
+    /// dos frames
+    pub fn update(&mut self, delta_time: f32, mut belt: Option<&mut AsteroidBelt>) -> Vec<WeaponEvent> {
+        let mut events = Vec::new();
+
+        self.projectiles.retain_mut(|projectile| {
+            let start = projectile.position;
+            let step = Vector3::new(
+                projectile.velocity.x * delta_time,
+                projectile.velocity.y * delta_time,
+                projectile.velocity.z * delta_time,
+            );
+            projectile.position = Vector3::new(start.x + step.x, start.y + step.y, start.z + step.z);
+            projectile.age += delta_time;
+
+            if let Some(belt) = belt.as_deref_mut() {
+                let hit = belt
+                    .index()
+                    .along_segment(start, projectile.position, self.radius)
+                    .into_iter()
+                    .filter_map(|entry| {
+                        let asteroid = &belt.asteroids()[entry.item];
+                        segment_sphere_hit(start, step, asteroid.position, asteroid.scale + self.radius)
+                            .map(|t| (t, asteroid.id, asteroid.position, asteroid.scale))
+                    })
+                    .min_by(|a, b| a.0.total_cmp(&b.0));
+                if let Some((_, id, position, size)) = hit {
+                    belt.destroy(id);
+                    events.push(WeaponEvent::AsteroidDestroyed { id, position, size });
+                    return false;
+                }
+            }
+            projectile.age < self.lifetime
+        });
+
+        events
+    }
+
+    /// Elimina todos los proyectiles en vuelo (p. ej. al regenerar el sistema)
+    pub fn clear(&mut self) {
+        self.projectiles.clear();
+    }
+}
+
+impl Default for Weapons {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fracción t en [0, 1] del segmento `start` -> `start + step` donde entra en la esfera, o None
+fn segment_sphere_hit(start: Vector3, step: Vector3, center: Vector3, radius: f32) -> Option<f32> {
+    let offset = Vector3::new(start.x - center.x, start.y - center.y, start.z - center.z);
+    let c = offset.x * offset.x + offset.y * offset.y + offset.z * offset.z - radius * radius;
+    if c <= 0.0 {
+        return Some(0.0); // Ya estaba dentro al empezar el frame
+    }
+    let a = step.x * step.x + step.y * step.y + step.z * step.z;
+    if a <= 0.0 {
+        return None;
+    }
+    let b = offset.x * step.x + offset.y * step.y + offset.z * step.z;
+    let discriminant = b * b - a * c;
+    if b >= 0.0 || discriminant < 0.0 {
+        return None;
+    }
+    let t = (-b - discriminant.sqrt()) / a;
+    (t <= 1.0).then_some(t)
+}
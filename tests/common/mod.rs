@@ -0,0 +1,55 @@
+//! Escena de prueba compartida por las pruebas que dibujan en un framebuffer sin ventana
+
+use computer_graphics_v3::fragment::Fragment;
+use computer_graphics_v3::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use computer_graphics_v3::vertex::Vertex;
+use computer_graphics_v3::{FnShader, Framebuffer, Light, Material, Renderer, Uniforms};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+pub const WIDTH: u32 = 64;
+pub const HEIGHT: u32 = 48;
+
+/// Escena de prueba: framebuffer, renderer y uniforms de una cámara fija en (0, 0, 3) mirando
+/// al origen, con la matriz de modelo identidad
+pub struct TestScene {
+    pub framebuffer: Framebuffer,
+    pub renderer: Renderer,
+    pub uniforms: Uniforms,
+}
+
+impl TestScene {
+    pub fn new(light_position: Vector3) -> Self {
+        let eye = Vector3::new(0.0, 0.0, 3.0);
+        let view = create_view_matrix(eye, Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+        let projection = create_projection_matrix(PI / 3.0, WIDTH as f32 / HEIGHT as f32, 0.1, 100.0);
+        let viewport = create_viewport_matrix(0.0, 0.0, WIDTH as f32, HEIGHT as f32);
+        let uniforms = Uniforms::new(Matrix::identity(), view, projection, viewport, 0.0, eye, Vector3::new(0.0, 0.0, 1.0));
+
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        // Sin tramado: la referencia no depende del patrón de Bayer
+        framebuffer.set_dithering(false);
+        framebuffer.set_background_color(Vector3::new(0.02, 0.02, 0.05));
+        framebuffer.clear();
+
+        TestScene { framebuffer, renderer: Renderer::new(Light::new(light_position)), uniforms }
+    }
+
+    /// Dibuja una malla indexada con un material mate del color dado (difuso más especular)
+    pub fn draw(&mut self, vertices: &[Vertex], indices: &[u32], material: Material) {
+        let shader = FnShader::new(material, |fragment: &Fragment, _: &Uniforms| {
+            Vector3::new(
+                fragment.color.x + fragment.specular.x,
+                fragment.color.y + fragment.specular.y,
+                fragment.color.z + fragment.specular.z,
+            )
+        });
+        self.renderer.draw_indexed_mesh(&mut self.framebuffer, &self.uniforms, vertices, indices, &shader);
+    }
+}
+
+/// Triángulo mirando a la cámara (+Z) con los vértices dados
+pub fn facing_triangle(a: Vector3, b: Vector3, c: Vector3) -> Vec<Vertex> {
+    let normal = Vector3::new(0.0, 0.0, 1.0);
+    [a, b, c].into_iter().map(|position| Vertex::new(position, normal, Vector2::zero())).collect()
+}
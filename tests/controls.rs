@@ -0,0 +1,52 @@
+//! Pruebas del archivo de controles (`InputMap::parse`/`InputMap::save`, ver `assets/controls.cfg`)
+
+use computer_graphics_v3::input_map::{Action, Binding, InputMap};
+use raylib::prelude::*;
+use std::path::PathBuf;
+
+#[test]
+fn controls_file_parses_bindings_and_round_trips() {
+    let map = InputMap::parse(
+        "# comentario\n\
+         forward = W, UP   # dos teclas\n\
+         fire = mouse_left\n\
+         pitch_up = KEY_I\n\
+         minimap =\n",
+    )
+    .unwrap();
+    assert_eq!(map.bindings(Action::Forward), &[Binding::Key(KeyboardKey::KEY_W), Binding::Key(KeyboardKey::KEY_UP)]);
+    assert_eq!(map.bindings(Action::Fire), &[Binding::Mouse(MouseButton::MOUSE_BUTTON_LEFT)]);
+    assert_eq!(map.bindings(Action::PitchUp), &[Binding::Key(KeyboardKey::KEY_I)]);
+    assert!(map.bindings(Action::Minimap).is_empty());
+    // Las acciones que no aparecen conservan la asignación por defecto
+    assert_eq!(map.bindings(Action::Tour), Action::Tour.default_bindings().as_slice());
+
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("controls.cfg");
+    map.save(path.to_str().unwrap()).unwrap();
+    let loaded = InputMap::load(path.to_str().unwrap()).unwrap();
+    for action in Action::ALL {
+        assert_eq!(loaded.bindings(action), map.bindings(action), "{}", action.name());
+    }
+
+    // El archivo que se distribuye tiene todas las acciones
+    let shipped = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/controls.cfg")).unwrap();
+    InputMap::parse(&shipped).unwrap();
+    for action in Action::ALL {
+        assert!(shipped.lines().any(|line| line.split('=').next().unwrap().trim() == action.name()), "falta {}", action.name());
+    }
+}
+
+#[test]
+fn controls_file_rejects_malformed_lines() {
+    let malformed = [
+        ("forward W\n", "línea 1"),
+        ("\nwarp_drive = F1\n", "línea 2"),
+        ("forward = W\nfire = LASER\n", "línea 2"),
+    ];
+    for (contents, line) in malformed {
+        let Err(error) = InputMap::parse(contents) else {
+            panic!("'{}' debería ser un error", contents.trim());
+        };
+        assert!(error.to_string().contains(line), "{}: {}", contents.trim(), error);
+    }
+}
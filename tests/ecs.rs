@@ -0,0 +1,111 @@
+//! Pruebas de los sistemas del mundo de entidades (`ecs`)
+
+use computer_graphics_v3::ecs::{self, orbit_system, render_system, spin_system, Mesh, Orbit, Pass, Pool, Renderable, Spin, Transform, World};
+use computer_graphics_v3::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use computer_graphics_v3::{Framebuffer, Light, Obj, PlanetType, Renderer, Scene, Uniforms};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+#[test]
+fn world_systems_place_and_draw_entities() {
+    // Una luna con acoplamiento de marea alrededor de un planeta en órbita: los sistemas la
+    // colocan y orientan como `Moon` y la dibujan igual que un draw_indexed_mesh directo
+    let planet = Scene::solar_system().planets.remove(0);
+    let moon = Scene::solar_system().moons.remove(0);
+    let sphere = Arc::new(Obj::generate_sphere(1.0, 12));
+    let time = 7.5;
+
+    let mut world = World::new();
+    let planet_entity = world.spawn();
+    world.orbits.insert(planet_entity, planet.orbit());
+    world.spins.insert(planet_entity, planet.spin());
+    let moon_entity = world.spawn_renderable(
+        Transform::at(Vector3::zero(), moon.scale),
+        Renderable { bounds: Some(moon.scale), ..Renderable::new(Mesh::Single(sphere.clone()), "Luna", Pass::Opaque) },
+        ecs::Material::Shader(Arc::new(PlanetType::Moon)),
+    );
+    world.orbits.insert(moon_entity, Orbit { parent: Some(planet_entity), ..moon.orbit() });
+    world.spins.insert(moon_entity, Spin::TidalLock);
+    orbit_system(&mut world, time);
+    spin_system(&mut world, time, 3.0);
+
+    let planet_transform = world.transforms.get(planet_entity).unwrap();
+    let moon_transform = *world.transforms.get(moon_entity).unwrap();
+    let expected = planet.position_after(time);
+    assert_eq!((planet_transform.position.x, planet_transform.position.z), (expected.x, expected.z));
+    let offset = moon.offset(time);
+    let expected = Vector3::new(expected.x + offset.x, expected.y + offset.y, expected.z + offset.z);
+    assert_eq!((moon_transform.position.x, moon_transform.position.y, moon_transform.position.z), (expected.x, expected.y, expected.z));
+    assert_eq!(moon_transform.model_matrix(), moon.model_matrix(expected, time));
+
+    // Cámara a 3 radios de la luna, del lado del sol para que se vea iluminada
+    let (position, radius) = (moon_transform.position, moon.scale);
+    let eye = Vector3::new(position.x * 0.9, position.y * 0.9 + radius, position.z * 0.9);
+    let view = Uniforms::new(
+        Matrix::identity(),
+        create_view_matrix(eye, position, Vector3::new(0.0, 1.0, 0.0)),
+        create_projection_matrix(PI / 3.0, WIDTH as f32 / HEIGHT as f32, 0.01, 1000.0),
+        create_viewport_matrix(0.0, 0.0, WIDTH as f32, HEIGHT as f32),
+        time,
+        eye,
+        Vector3::zero(),
+    );
+    let new_framebuffer = || {
+        let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+        framebuffer.set_dithering(false);
+        framebuffer.clear();
+        framebuffer
+    };
+    let renderer = Renderer::new(Light::new(Vector3::zero()));
+    let mut drawn = new_framebuffer();
+    render_system(&mut world, &renderer, &mut drawn, &view, Pass::Opaque, 0);
+    render_system(&mut world, &renderer, &mut drawn, &view, Pass::Translucent, 0);
+    assert_eq!(world.debug_objects(0).len(), 1);
+
+    let mut direct = new_framebuffer();
+    let uniforms = Uniforms::new(
+        moon.model_matrix(position, time),
+        view.view_matrix,
+        view.projection_matrix,
+        view.viewport_matrix,
+        time,
+        eye,
+        moon_transform.sun_direction(),
+    );
+    renderer.draw_indexed_mesh(&mut direct, &uniforms, &sphere.vertices, &sphere.indices, &PlanetType::Moon);
+    assert_eq!(drawn.to_rgb8(), direct.to_rgb8());
+    assert_ne!(drawn.to_rgb8(), new_framebuffer().to_rgb8());
+}
+
+#[test]
+fn pool_reuses_entities_and_hides_the_rest() {
+    let sphere = Arc::new(Obj::generate_sphere(1.0, 6));
+    let mut world = World::new();
+    let mut pool = Pool::new();
+    let mut spawn = |world: &mut World| {
+        world.spawn_renderable(
+            Transform::at(Vector3::zero(), 1.0),
+            Renderable::new(Mesh::Single(sphere.clone()), "Rocas", Pass::Opaque),
+            ecs::Material::Shader(Arc::new(PlanetType::Moon)),
+        )
+    };
+    pool.resize(&mut world, 3, &mut spawn);
+    let first = pool.active().to_vec();
+    assert_eq!(world.len(), 3);
+
+    // Menos entidades: las que sobran se ocultan, no se borran
+    pool.resize(&mut world, 1, &mut spawn);
+    assert_eq!(pool.active(), &first[..1]);
+    let visible: Vec<bool> = first.iter().map(|&entity| world.renderables.get(entity).unwrap().visible).collect();
+    assert_eq!(visible, [true, false, false]);
+
+    // Más: primero vuelven las ocultas y solo se crean las que faltan
+    pool.resize(&mut world, 4, &mut spawn);
+    assert_eq!(&pool.active()[..3], &first[..]);
+    assert_eq!(world.len(), 4);
+    assert!(pool.active().iter().all(|&entity| world.renderables.get(entity).unwrap().visible));
+}
@@ -0,0 +1,44 @@
+//! Pruebas del sombreado en la GPU (solo con la característica `gpu`)
+
+#![cfg(feature = "gpu")]
+
+use computer_graphics_v3::gpu_shading::GpuShading;
+use computer_graphics_v3::matrix::{create_projection_matrix, create_view_matrix, create_viewport_matrix};
+use computer_graphics_v3::{Framebuffer, Light, Obj, PlanetType, Renderer, Uniforms};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const WIDTH: u32 = 64;
+const HEIGHT: u32 = 48;
+
+/// Esfera rocosa iluminada de lado, vista desde (0, 0, 3), con o sin sombreado en la GPU
+fn render_sphere(gpu: Option<GpuShading>) -> (Vec<u8>, u64) {
+    let eye = Vector3::new(0.0, 0.0, 3.0);
+    let view = create_view_matrix(eye, Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let projection = create_projection_matrix(PI / 3.0, WIDTH as f32 / HEIGHT as f32, 0.1, 100.0);
+    let viewport = create_viewport_matrix(0.0, 0.0, WIDTH as f32, HEIGHT as f32);
+    let uniforms = Uniforms::new(Matrix::identity(), view, projection, viewport, 2.0, eye, Vector3::new(0.0, 0.0, 1.0));
+
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.set_dithering(false);
+    framebuffer.set_background_color(Vector3::new(0.02, 0.02, 0.05));
+    framebuffer.clear();
+    let mut renderer = Renderer::new(Light::new(Vector3::new(-4.0, 3.0, 4.0)));
+    renderer.set_gpu_shading(gpu);
+    let sphere = Obj::generate_sphere(1.0, 24);
+    renderer.draw_indexed_mesh(&mut framebuffer, &uniforms, &sphere.vertices, &sphere.indices, &PlanetType::Rocky);
+    (framebuffer.to_rgb8(), renderer.take_stats().gpu_fragments)
+}
+
+#[test]
+fn gpu_shading_matches_cpu() {
+    // El color base calculado en la GPU tiene que dar la misma imagen que los shaders de la CPU
+    // Sin un adaptador compatible (CI sin GPU ni llvmpipe) no hay nada que comparar
+    let Ok(gpu) = GpuShading::new() else {
+        return;
+    };
+    let (cpu, _) = render_sphere(None);
+    let (gpu, gpu_fragments) = render_sphere(Some(gpu));
+    assert!(gpu_fragments > 0);
+    assert!(cpu.iter().zip(&gpu).all(|(a, b)| a.abs_diff(*b) <= 1));
+}
@@ -0,0 +1,88 @@
+//! Pruebas del vuelo sin ventana: la cámara solo lee `InputState`, así que una entrada
+//! construida a mano la mueve igual que el teclado
+
+use computer_graphics_v3::camera::{lerp_angle, Camera, CameraMode};
+use computer_graphics_v3::input_map::Action;
+use computer_graphics_v3::input_state::InputState;
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+const STEP: f32 = 1.0 / 120.0;
+
+fn dot(a: Vector3, b: Vector3) -> f32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+#[test]
+fn synthetic_input_flies_the_camera_headless() {
+    let mut camera = Camera::new(Vector3::new(0.0, 0.0, 30.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    assert_eq!(camera.mode, CameraMode::Chase);
+
+    // Dos pulsaciones de cambio de modo en frames distintos se acumulan para el siguiente paso,
+    // pero un paso solo ve una pulsación y los siguientes del mismo frame solo las mantenidas
+    let mut pending = InputState::new();
+    let mut frame = InputState::new();
+    frame.press(Action::CameraMode);
+    pending.accumulate(&frame);
+    pending.accumulate(&InputState::new());
+    camera.process_input(&pending, STEP);
+    camera.process_input(&pending.held(), STEP);
+    assert_eq!(camera.mode, CameraMode::Cockpit);
+    let mut press = InputState::new();
+    press.press(Action::CameraMode);
+    camera.process_input(&press, STEP);
+    assert_eq!(camera.mode, CameraMode::FreeFly);
+
+    // Un segundo con `Forward` mantenida: la inercia acelera y la cámara avanza hacia donde mira
+    let start = camera.eye;
+    let forward = camera.forward();
+    let mut input = InputState::new();
+    input.hold(Action::Forward);
+    for _ in 0..120 {
+        camera.process_input(&input, STEP);
+    }
+    let moved = camera.eye - start;
+    let along = dot(moved, forward);
+    assert!(along > 10.0 && along < 18.0, "avanzó {} (máximo 18 u/s)", along);
+    assert!((dot(moved, moved) - along * along).abs() < 1e-3, "se desvió de la dirección de la cámara: {:?}", moved);
+
+    // Al soltar sigue por inercia un poco y se detiene
+    let released = camera.eye;
+    let idle = InputState::new();
+    for _ in 0..240 {
+        camera.process_input(&idle, STEP);
+    }
+    let coast = dot(camera.eye - released, forward);
+    assert!(coast > 0.0 && coast < 3.0, "deriva tras soltar: {}", coast);
+    let stopped = camera.eye;
+    camera.process_input(&idle, STEP);
+    assert!(dot(camera.eye - stopped, camera.eye - stopped) < 1e-6);
+
+    // Girar a la izquierda cambia el yaw y la vista sigue apuntando delante de la cámara
+    let yaw = camera.yaw;
+    let mut turn = InputState::new();
+    turn.hold(Action::YawLeft);
+    for _ in 0..60 {
+        camera.process_input(&turn, STEP);
+    }
+    assert!(camera.yaw > yaw + 0.1, "yaw {} -> {}", yaw, camera.yaw);
+    let view = camera.target - camera.eye;
+    assert!(dot(view, camera.forward()) > 0.99);
+}
+
+#[test]
+fn interpolated_yaw_takes_the_short_way_across_the_seam() {
+    // Entre dos pasos el yaw cruza de PI a -PI: el frame intermedio sigue mirando hacia atrás
+    // (yaw = ±PI) en vez de dar media vuelta hasta yaw = 0
+    let mut previous = Camera::new(Vector3::new(0.0, 0.0, 30.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    previous.yaw = PI - 0.1;
+    let mut current = previous.clone();
+    current.yaw = -PI + 0.1;
+    let halfway = current.interpolated(&previous, 0.5);
+    assert!((halfway.yaw.cos() + 1.0).abs() < 1e-4, "yaw intermedio {}", halfway.yaw);
+    assert!((current.interpolated(&previous, 0.25).yaw - (PI - 0.05)).abs() < 1e-4);
+    assert!((current.interpolated(&previous, 1.0).yaw.sin() - current.yaw.sin()).abs() < 1e-4);
+
+    assert!((lerp_angle(-PI + 0.1, PI - 0.1, 0.5).cos() + 1.0).abs() < 1e-4);
+    assert!((lerp_angle(0.2, 0.6, 0.5) - 0.4).abs() < 1e-6);
+}
@@ -0,0 +1,358 @@
+//! Pruebas del rasterizador con imágenes de referencia (golden images)
+//!
+//! Cada prueba dibuja una escena pequeña y determinista en un framebuffer sin abrir ninguna
+//! ventana, la lee de vuelta con `Framebuffer::to_rgb8` y la compara con `tests/golden/<nombre>.ppm`
+//! con una tolerancia por canal. Si la imagen no coincide, la obtenida se guarda junto a los
+//! temporales de cargo para poder compararlas a ojo.
+//!
+//! Tras un cambio intencionado del render, las referencias se regeneran con
+//! `UPDATE_GOLDEN=1 cargo test --test rasterizer` (y se revisan antes de subirlas).
+
+mod common;
+
+use common::{facing_triangle, TestScene, HEIGHT, WIDTH};
+use computer_graphics_v3::fragment::Fragment;
+use computer_graphics_v3::framebuffer::ScissorRect;
+use computer_graphics_v3::matrix::{create_model_matrix, create_projection_matrix, create_view_matrix, create_viewport_matrix, project_to_screen};
+use computer_graphics_v3::triangle::GUARD_BAND;
+use computer_graphics_v3::vertex::Vertex;
+use computer_graphics_v3::{FnShader, Framebuffer, Material, Obj, Scene, Uniforms};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::PathBuf;
+
+const TOLERANCE: u8 = 8;           // Diferencia máxima por canal que no cuenta como distinta
+const MAX_MISMATCH: f32 = 0.01;    // Fracción de píxeles distintos que se admite
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}.ppm", name))
+}
+
+/// PPM binario (P6) de 8 bits
+fn write_ppm(path: &PathBuf, width: u32, height: u32, pixels: &[u8]) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    data.extend_from_slice(pixels);
+    fs::write(path, data).unwrap();
+}
+
+/// Lee un PPM escrito por `write_ppm`: (ancho, alto, píxeles RGB)
+fn read_ppm(path: &PathBuf) -> Option<(u32, u32, Vec<u8>)> {
+    let data = fs::read(path).ok()?;
+    // Cabecera: cuatro campos separados por espacios en blanco ("P6", ancho, alto, 255)
+    let mut fields = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    while fields.len() < 4 && offset < data.len() {
+        let whitespace = data[offset].is_ascii_whitespace();
+        match (start, whitespace) {
+            (None, false) => start = Some(offset),
+            (Some(s), true) => {
+                fields.push(String::from_utf8_lossy(&data[s..offset]).into_owned());
+                start = None;
+            }
+            _ => {}
+        }
+        offset += 1;
+    }
+    if fields.len() < 4 || fields[0] != "P6" || fields[3] != "255" {
+        return None;
+    }
+    let width = fields[1].parse().ok()?;
+    let height = fields[2].parse().ok()?;
+    Some((width, height, data[offset..].to_vec()))
+}
+
+/// Compara el framebuffer con su referencia; con `UPDATE_GOLDEN` la reescribe
+fn assert_matches_golden(name: &str, framebuffer: &Framebuffer) {
+    let actual = framebuffer.to_rgb8();
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        write_ppm(&path, framebuffer.width, framebuffer.height, &actual);
+        eprintln!("Referencia escrita en {}", path.display());
+        return;
+    }
+    // Una referencia que falta es un error: se genera a propósito, no al pasar las pruebas
+    assert!(
+        path.exists(),
+        "{}: falta la referencia {} (genérala con UPDATE_GOLDEN=1 cargo test --test rasterizer)",
+        name,
+        path.display()
+    );
+
+    let (width, height, expected) = read_ppm(&path).unwrap_or_else(|| panic!("{} no es un PPM válido", path.display()));
+    assert_eq!((width, height), (framebuffer.width, framebuffer.height), "{}: el tamaño no coincide", name);
+    assert_eq!(expected.len(), actual.len(), "{}: faltan píxeles en la referencia", name);
+
+    let mismatched = expected
+        .chunks_exact(3)
+        .zip(actual.chunks_exact(3))
+        .filter(|(e, a)| e.iter().zip(a.iter()).any(|(e, a)| e.abs_diff(*a) > TOLERANCE))
+        .count();
+    let fraction = mismatched as f32 / (width * height) as f32;
+    if fraction > MAX_MISMATCH {
+        let actual_path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{}.actual.ppm", name));
+        write_ppm(&actual_path, width, height, &actual);
+        panic!(
+            "{}: {} píxeles distintos ({:.1}%, se admite {:.1}%); imagen obtenida en {}",
+            name,
+            mismatched,
+            fraction * 100.0,
+            MAX_MISMATCH * 100.0,
+            actual_path.display()
+        );
+    }
+}
+
+#[test]
+fn single_triangle() {
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    let vertices = facing_triangle(Vector3::new(-1.0, -0.8, 0.0), Vector3::new(1.0, -0.8, 0.0), Vector3::new(0.0, 0.9, 0.0));
+    scene.draw(&vertices, &[0, 1, 2], Material::matte(Vector3::new(0.9, 0.4, 0.1)));
+    assert_matches_golden("single_triangle", &scene.framebuffer);
+}
+
+#[test]
+fn lit_sphere() {
+    let mut scene = TestScene::new(Vector3::new(-4.0, 3.0, 4.0));
+    let sphere = Obj::generate_sphere(1.0, 24);
+    let material = Material::new(Vector3::new(0.2, 0.5, 0.9), Vector3::new(0.6, 0.6, 0.6), 32.0);
+    scene.draw(&sphere.vertices, &sphere.indices, material);
+    assert_matches_golden("lit_sphere", &scene.framebuffer);
+}
+
+#[test]
+fn depth_overlap() {
+    // Dos triángulos que se cruzan en profundidad: cada uno debe tapar al otro en la mitad
+    // que tiene más cerca de la cámara, sin importar el orden de dibujo
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    let tilted_left = facing_triangle(Vector3::new(-1.2, -0.8, 0.6), Vector3::new(1.2, -0.8, -0.6), Vector3::new(0.0, 0.9, 0.0));
+    let tilted_right = facing_triangle(Vector3::new(-1.2, 0.8, -0.6), Vector3::new(0.0, -0.9, 0.0), Vector3::new(1.2, 0.8, 0.6));
+    scene.draw(&tilted_left, &[0, 1, 2], Material::matte(Vector3::new(0.9, 0.1, 0.1)));
+    scene.draw(&tilted_right, &[0, 1, 2], Material::matte(Vector3::new(0.1, 0.8, 0.2)));
+    assert_matches_golden("depth_overlap", &scene.framebuffer);
+
+    // El mismo resultado dibujándolos al revés
+    let mut reversed = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    reversed.draw(&tilted_right, &[0, 1, 2], Material::matte(Vector3::new(0.1, 0.8, 0.2)));
+    reversed.draw(&tilted_left, &[0, 1, 2], Material::matte(Vector3::new(0.9, 0.1, 0.1)));
+    assert_matches_golden("depth_overlap", &reversed.framebuffer);
+}
+
+#[test]
+fn specular_uses_world_space_positions() {
+    // El triángulo está centrado en x = -5 en su espacio de objeto y la matriz de modelo lo lleva
+    // al origen, justo delante de la cámara y de la luz: el brillo especular tiene que estar en
+    // el centro de la imagen. Con la dirección de vista calculada desde la posición de objeto, el
+    // vector medio se inclinaría hacia +X y el centro quedaría casi negro
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 3.0));
+    let model = create_model_matrix(Vector3::new(5.0, 0.0, 0.0), 1.0, Vector3::zero());
+    let u = &scene.uniforms;
+    scene.uniforms = Uniforms::new(model, u.view_matrix, u.projection_matrix, u.viewport_matrix, 0.0, u.camera_position, u.sun_direction);
+    let vertices = facing_triangle(Vector3::new(-6.0, -0.8, 0.0), Vector3::new(-4.0, -0.8, 0.0), Vector3::new(-5.0, 0.9, 0.0));
+    let material = Material::new(Vector3::zero(), Vector3::new(1.0, 1.0, 1.0), 64.0);
+    let shader = FnShader::new(material, |fragment: &Fragment, _: &Uniforms| fragment.specular);
+    scene.renderer.draw_indexed_mesh(&mut scene.framebuffer, &scene.uniforms, &vertices, &[0, 1, 2], &shader);
+
+    let pixels = scene.framebuffer.to_rgb8();
+    let center = ((HEIGHT / 2 * WIDTH + WIDTH / 2) * 3) as usize;
+    assert!(pixels[center] > 200, "brillo especular en el centro: {}", pixels[center]);
+}
+
+/// Uniforms de una cámara en `eye` mirando al origen con el far plane `far`
+fn camera_uniforms(eye: Vector3, far: f32) -> Uniforms {
+    let view = create_view_matrix(eye, Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let projection = create_projection_matrix(PI / 3.0, WIDTH as f32 / HEIGHT as f32, 0.1, far);
+    let viewport = create_viewport_matrix(0.0, 0.0, WIDTH as f32, HEIGHT as f32);
+    Uniforms::new(Matrix::identity(), view, projection, viewport, 0.0, eye, Vector3::new(0.0, 0.0, 1.0))
+}
+
+/// Dibuja una malla de un color plano, sin iluminación
+fn draw_flat(scene: &mut TestScene, vertices: &[Vertex], indices: &[u32], color: Vector3) {
+    let shader = FnShader::new(Material::matte(color), move |_: &Fragment, _: &Uniforms| color);
+    scene.renderer.draw_indexed_mesh(&mut scene.framebuffer, &scene.uniforms, vertices, indices, &shader);
+}
+
+/// Color RGB de 8 bits del píxel bajo el punto `world`
+fn pixel_at(scene: &TestScene, world: Vector3) -> [u8; 3] {
+    let u = &scene.uniforms;
+    let screen = project_to_screen(world, &u.view_matrix, &u.projection_matrix, &u.viewport_matrix).unwrap();
+    let index = ((screen.y as u32 * WIDTH + screen.x as u32) * 3) as usize;
+    let pixels = scene.framebuffer.to_rgb8();
+    [pixels[index], pixels[index + 1], pixels[index + 2]]
+}
+
+#[test]
+fn logarithmic_depth_orders_a_large_ring_across_a_sphere() {
+    // Un anillo de dos triángulos enormes (de justo delante de la cámara hasta 200 unidades)
+    // atraviesa una esfera por el ecuador, con el far plane lejos como el del sistema solar.
+    // La profundidad logarítmica no es lineal en pantalla: interpolada desde los vértices, el
+    // anillo quedaría detrás de la mitad inferior de la esfera que tiene detrás
+    let (ring_color, sphere_color) = (Vector3::new(0.9, 0.1, 0.1), Vector3::new(0.1, 0.1, 0.9));
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let ring: Vec<Vertex> = [(-30.0, 4.0), (30.0, 4.0), (30.0, -200.0), (-30.0, -200.0)]
+        .into_iter()
+        .map(|(x, z)| Vertex::new(Vector3::new(x, 0.0, z), up, Vector2::zero()))
+        .collect();
+    let sphere = Obj::generate_sphere(1.0, 32);
+    let is_ring = |[r, _, b]: [u8; 3]| r > 150 && b < 100;
+    let is_sphere = |[r, _, b]: [u8; 3]| b > 150 && r < 100;
+
+    for ring_first in [false, true] {
+        let mut scene = TestScene::new(Vector3::new(0.0, 10.0, 10.0));
+        scene.uniforms = camera_uniforms(Vector3::new(0.0, 1.5, 5.0), 5000.0);
+        for draw_ring in [ring_first, !ring_first] {
+            if draw_ring {
+                draw_flat(&mut scene, &ring, &[0, 1, 2, 0, 2, 3], ring_color);
+            } else {
+                draw_flat(&mut scene, &sphere.vertices, &sphere.indices, sphere_color);
+            }
+        }
+
+        // Encima del anillo la esfera tapa el anillo que pasa por detrás
+        let above = pixel_at(&scene, Vector3::new(0.0, 0.6, 0.8));
+        assert!(is_sphere(above), "sobre el ecuador debería verse la esfera: {:?}", above);
+        // Delante de la esfera el anillo tapa su mitad inferior
+        let front = pixel_at(&scene, Vector3::new(0.0, 0.0, 1.5));
+        assert!(is_ring(front), "delante de la esfera debería verse el anillo: {:?}", front);
+        // Lejos del planeta solo queda el anillo
+        let far = pixel_at(&scene, Vector3::new(6.0, 0.0, -20.0));
+        assert!(is_ring(far), "anillo lejano: {:?}", far);
+    }
+}
+
+#[test]
+fn dynamic_far_plane_keeps_distant_surfaces_apart() {
+    // El far plane cubre el sistema visto desde la cámara y nunca baja del mínimo
+    let scene = Scene::parse("[planet]\nname = Lejano\norbital_radius = 2000\nscale = 4\n").unwrap();
+    let far = scene.far_plane(Vector3::new(0.0, 0.0, 3000.0), 300.0);
+    assert!(far >= 5004.0, "far plane {}", far);
+    assert_eq!(Scene::parse("").unwrap().far_plane(Vector3::zero(), 300.0), 300.0);
+
+    // Con ese far, dos caras a 0.05 unidades a 2000 de la cámara siguen ordenadas en ambos órdenes
+    let (front_color, back_color) = (Vector3::new(0.9, 0.1, 0.1), Vector3::new(0.1, 0.1, 0.9));
+    let quad = |z: f32| facing_triangle(Vector3::new(-900.0, -900.0, z), Vector3::new(900.0, -900.0, z), Vector3::new(0.0, 900.0, z));
+    for front_first in [false, true] {
+        let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+        scene.uniforms = camera_uniforms(Vector3::new(0.0, 0.0, 2000.0), far);
+        let faces = [(quad(0.05), front_color), (quad(0.0), back_color)];
+        let order = if front_first { [0, 1] } else { [1, 0] };
+        for i in order {
+            draw_flat(&mut scene, &faces[i].0, &[0, 1, 2], faces[i].1);
+        }
+        let [r, _, b] = pixel_at(&scene, Vector3::zero());
+        assert!(r > 150 && b < 100, "la cara de delante debería tapar la de detrás: {:?}", [r, b]);
+    }
+}
+
+/// Píxeles del framebuffer distintos del fondo (el color de la esquina superior izquierda)
+fn covered_pixels(framebuffer: &Framebuffer) -> Vec<(u32, u32)> {
+    let pixels = framebuffer.to_rgb8();
+    let background = [pixels[0], pixels[1], pixels[2]];
+    (0..HEIGHT)
+        .flat_map(|y| (0..WIDTH).map(move |x| (x, y)))
+        .filter(|&(x, y)| {
+            let index = ((y * WIDTH + x) * 3) as usize;
+            pixels[index..index + 3] != background
+        })
+        .collect()
+}
+
+#[test]
+fn triangle_crossing_the_near_plane_is_clipped() {
+    // Un suelo bajo la cámara (en (0, 0, 3)) con un vértice detrás de ella: recortado en espacio
+    // de recorte solo se ve la parte de delante, en la mitad inferior de la imagen. Sin recortar,
+    // el vértice de detrás se proyecta reflejado y el triángulo cruza la pantalla hacia arriba
+    let up = Vector3::new(0.0, 1.0, 0.0);
+    let floor: Vec<Vertex> = [Vector3::new(-1.0, -0.5, 0.0), Vector3::new(1.0, -0.5, 0.0), Vector3::new(0.0, -0.5, 10.0)]
+        .into_iter()
+        .map(|position| Vertex::new(position, up, Vector2::zero()))
+        .collect();
+    let mut scene = TestScene::new(Vector3::new(0.0, 5.0, 3.0));
+    draw_flat(&mut scene, &floor, &[0, 1, 2], Vector3::new(0.9, 0.8, 0.2));
+
+    let stats = scene.renderer.take_stats();
+    assert_eq!((stats.triangles, stats.clipped_triangles, stats.culled_triangles), (1, 1, 0));
+    let covered = covered_pixels(&scene.framebuffer);
+    assert!(covered.contains(&(WIDTH / 2, HEIGHT - 1)), "el suelo debería llegar al borde inferior");
+    for &(x, y) in &covered {
+        assert!(y >= HEIGHT / 2, "píxel ({}, {}) por encima del horizonte", x, y);
+        // Nada de lo recortado queda más cerca que el plano cercano
+        assert!(scene.framebuffer.depth_at(x as i32, y as i32) > -1.0, "profundidad en ({}, {})", x, y);
+    }
+
+    // Del todo detrás de la cámara no se rasteriza
+    let mut scene = TestScene::new(Vector3::new(0.0, 5.0, 3.0));
+    let behind = facing_triangle(Vector3::new(-1.0, -0.5, 4.0), Vector3::new(1.0, -0.5, 4.0), Vector3::new(0.0, 0.5, 4.0));
+    draw_flat(&mut scene, &behind, &[0, 1, 2], Vector3::new(0.9, 0.8, 0.2));
+    let stats = scene.renderer.take_stats();
+    assert_eq!((stats.culled_triangles, stats.fragments), (1, 0));
+    assert!(covered_pixels(&scene.framebuffer).is_empty());
+}
+
+#[test]
+fn scissor_rect_and_guard_band_limit_rasterization() {
+    // Con un rectángulo de recorte, un triángulo que llena la pantalla solo escribe dentro de él
+    let big = facing_triangle(Vector3::new(-5.0, -5.0, 0.0), Vector3::new(5.0, -5.0, 0.0), Vector3::new(0.0, 5.0, 0.0));
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    let rect = ScissorRect::new(10, 8, 20, 12);
+    scene.framebuffer.set_scissor(Some(rect));
+    draw_flat(&mut scene, &big, &[0, 1, 2], Vector3::new(0.2, 0.8, 0.4));
+    let stats = scene.renderer.take_stats();
+    assert_eq!((stats.clipped_triangles, stats.fragments), (1, 20 * 12));
+    let covered = covered_pixels(&scene.framebuffer);
+    assert_eq!(covered.len(), 20 * 12);
+    assert!(covered.iter().all(|&(x, y)| rect.contains(x as i32, y as i32)));
+
+    // Uno fuera del rectángulo se descarta sin generar fragmentos, igual que uno vacío
+    let small = facing_triangle(Vector3::new(0.8, 0.5, 0.0), Vector3::new(1.2, 0.5, 0.0), Vector3::new(1.0, 0.9, 0.0));
+    draw_flat(&mut scene, &small, &[0, 1, 2], Vector3::new(0.2, 0.8, 0.4));
+    scene.framebuffer.set_scissor(Some(ScissorRect::new(0, 0, 0, 0)));
+    draw_flat(&mut scene, &big, &[0, 1, 2], Vector3::new(0.2, 0.8, 0.4));
+    let stats = scene.renderer.take_stats();
+    assert_eq!((stats.triangles, stats.culled_triangles, stats.fragments), (2, 2, 0));
+
+    // Un vértice más allá de la banda de guarda (casi en el plano de la cámara, fuera de la
+    // vista) descarta el triángulo en lugar de desbordar las funciones de arista
+    scene.framebuffer.set_scissor(None);
+    let edge = GUARD_BAND * 10.0;
+    let mut wild = facing_triangle(Vector3::new(-0.5, -0.4, 0.0), Vector3::new(0.5, -0.4, 0.0), Vector3::new(0.0, 0.5, 0.0));
+    wild[2].position = Vector3::new(edge, 0.0, 2.5);
+    draw_flat(&mut scene, &wild, &[0, 1, 2], Vector3::new(0.2, 0.8, 0.4));
+    let stats = scene.renderer.take_stats();
+    assert_eq!((stats.triangles, stats.culled_triangles, stats.fragments), (1, 1, 0));
+}
+
+#[test]
+fn composite_covers_inset_area() {
+    // Una vista secundaria renderizada en su propio framebuffer se copia encima de la principal,
+    // por delante de todo lo dibujado, y se recorta en los bordes
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    let vertices = facing_triangle(Vector3::new(-1.5, -1.2, 0.0), Vector3::new(1.5, -1.2, 0.0), Vector3::new(0.0, 1.2, 0.0));
+    scene.draw(&vertices, &[0, 1, 2], Material::matte(Vector3::new(0.9, 0.4, 0.1)));
+    let before = scene.framebuffer.to_rgb8();
+
+    let mut inset = Framebuffer::new(16, 12);
+    inset.set_dithering(false);
+    inset.set_background_color(Vector3::new(0.2, 0.6, 0.3));
+    inset.clear();
+    let (x, y) = (56, 40); // Solo cabe una esquina de 8 x 8 píxeles
+    scene.framebuffer.composite(&inset, x, y);
+
+    let after = scene.framebuffer.to_rgb8();
+    let inset_pixel = &inset.to_rgb8()[..3];
+    for py in 0..HEIGHT as i32 {
+        for px in 0..WIDTH as i32 {
+            let index = (py as usize * WIDTH as usize + px as usize) * 3;
+            if px >= x && py >= y {
+                assert_eq!(&after[index..index + 3], inset_pixel, "píxel ({}, {})", px, py);
+                assert_eq!(scene.framebuffer.depth_at(px, py), -1.0);
+            } else {
+                assert_eq!(&after[index..index + 3], &before[index..index + 3], "píxel ({}, {})", px, py);
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Pruebas de los archivos de escena (`Scene::load`/`Scene::parse`) y de sus unidades reales
+
+use computer_graphics_v3::units::{format_distance, orbital_period_days, units_to_au, AU_UNITS};
+use computer_graphics_v3::Scene;
+
+#[test]
+fn scene_file_loads_and_rejects_malformed_sections() {
+    // La escena por defecto: 5 planetas, 4 lunas, una estación y un agujero negro
+    let scene = Scene::load(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/scenes/solar_system.scene")).unwrap();
+    assert_eq!(
+        (scene.planets.len(), scene.moons.len(), scene.stations.len(), scene.black_holes.len()),
+        (5, 4, 1, 1)
+    );
+
+    let moon = Scene::parse("[planet]\nname = A\norbital_radius = 10\n[moon]\nname = B\nparent = A\norbital_radius = 2\n").unwrap();
+    assert_eq!(moon.moons[0].name, "B");
+
+    let malformed = [
+        ("name = suelta\n", "línea 1"),
+        ("[planet]\nname = A\norbital_radius = 5\n[cometa]\n", "línea 4"),
+        ("[planet]\nname A\n", "línea 2"),
+        ("[rings]\ninner_radius = 1\n", "línea 1"),
+        ("[planet]\nname = A\norbital_radius = 5\nscale = grande\n", "línea 4"),
+        ("[nebula]\ncolor = 1, 0\n", "línea 2"),
+        ("[star]\nclass = Z\n", "línea 2"),
+        ("[planet]\nname = A\norbital_radius = 5\ntype = cometa\n", "línea 4"),
+        ("[moon]\nname = B\nparent = Nadie\n", "línea 1"),
+    ];
+    for (contents, line) in malformed {
+        let Err(error) = Scene::parse(contents) else {
+            panic!("'{}' debería ser un error", contents.replace('\n', " "));
+        };
+        assert!(error.to_string().contains(line), "{}: {}", contents.replace('\n', " "), error);
+    }
+}
+
+#[test]
+fn scene_distances_accept_real_units() {
+    let scene = Scene::parse(
+        "[planet]\nname = Marte\norbital_radius = 1.524 au\norbital_period = 1.881 y\nscale = 3389.5 km\nrotation_period = 1.026 d\n\
+         [planet]\nname = Tierra\norbital_radius = 1.496e8 km\norbital_period = 365.25\n\
+         [planet]\nname = Antigua\norbital_radius = 12\norbital_speed = 0.5\nscale = 1.5\n",
+    )
+    .unwrap();
+    let close = |a: f32, b: f32| (a - b).abs() < 0.01 * b.abs().max(1.0);
+    let (mars, earth, old) = (&scene.planets[0], &scene.planets[1], &scene.planets[2]);
+    assert!(close(mars.orbital_radius, 1.524 * AU_UNITS));
+    assert!(close(earth.orbital_radius, AU_UNITS));
+    assert!(close(orbital_period_days(mars.orbital_speed), 687.0));
+    assert!(close(orbital_period_days(earth.orbital_speed), 365.25));
+    assert!(close(mars.rotation_period, 24.62));
+    // El radio real se comprime como en el sistema solar real: Marte mide 0.53 radios terrestres
+    assert!(close(mars.scale, 0.8 * 0.532f32.sqrt()));
+    // Sin unidades los valores siguen en unidades de la escena
+    assert_eq!((old.orbital_radius, old.orbital_speed, old.scale), (12.0, 0.5, 1.5));
+
+    assert_eq!(format_distance(mars.orbital_radius), "1.52 UA");
+    assert_eq!(format_distance(AU_UNITS * 0.01), "1.5 millones de km");
+    let real = Scene::preset("real", false).unwrap();
+    let tierra = real.planets.iter().find(|planet| planet.name == "Tierra").unwrap();
+    assert!(close(units_to_au(tierra.orbital_radius), 1.0));
+    assert!(close(orbital_period_days(tierra.orbital_speed), 365.25));
+
+    let Err(error) = Scene::parse("[planet]\norbital_radius = 3 parsecs\n") else {
+        panic!("una unidad desconocida debería ser un error");
+    };
+    assert!(error.to_string().contains("línea 2"), "{}", error);
+}
@@ -0,0 +1,59 @@
+//! Pruebas de la codificación de la pantalla: la zona cambiada y su copia para el hilo de la
+//! ventana
+
+mod common;
+
+use common::{facing_triangle, TestScene, HEIGHT, WIDTH};
+use computer_graphics_v3::Material;
+use raylib::prelude::*;
+
+#[test]
+fn changed_region_matches_readback() {
+    // Lo que recibiría la textura de la ventana (la zona cambiada, en RGBA) debe ser lo mismo
+    // que se lee de vuelta sin ventana
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    scene.framebuffer.take_changed_region();
+    let vertices = facing_triangle(Vector3::new(-0.5, -0.4, 0.0), Vector3::new(0.5, -0.4, 0.0), Vector3::new(0.0, 0.5, 0.0));
+    scene.draw(&vertices, &[0, 1, 2], Material::matte(Vector3::new(0.3, 0.7, 0.9)));
+
+    let region = scene.framebuffer.take_changed_region();
+    assert!(!region.is_empty() && region.width() < WIDTH as i32 && region.height() < HEIGHT as i32);
+    let mut rgba = vec![0; (region.width() * region.height()) as usize * 4];
+    assert_eq!(scene.framebuffer.encode_region(&region, &mut rgba), (region.width() * region.height()) as usize);
+
+    let rgb = scene.framebuffer.to_rgb8();
+    for (i, pixel) in rgba.chunks_exact(4).enumerate() {
+        let x = region.min_x as usize + i % region.width() as usize;
+        let y = region.min_y as usize + i / region.width() as usize;
+        let index = (y * WIDTH as usize + x) * 3;
+        assert_eq!(&pixel[..3], &rgb[index..index + 3], "píxel ({}, {})", x, y);
+    }
+    // Al limpiar, la zona borrada se presenta una vez más para quitar el triángulo de la pantalla
+    scene.framebuffer.clear();
+    assert_eq!(scene.framebuffer.take_changed_region(), region);
+    assert!(scene.framebuffer.take_changed_region().is_empty());
+}
+
+#[test]
+fn copied_region_encodes_like_framebuffer() {
+    // El hilo de la ventana codifica una copia de la zona con la configuración del momento de
+    // copiarla: debe dar los mismos bytes aunque el framebuffer cambie de exposición después
+    let mut scene = TestScene::new(Vector3::new(0.0, 0.0, 5.0));
+    scene.framebuffer.set_exposure(1.5);
+    let vertices = facing_triangle(Vector3::new(-0.5, -0.4, 0.0), Vector3::new(0.5, -0.4, 0.0), Vector3::new(0.0, 0.5, 0.0));
+    scene.draw(&vertices, &[0, 1, 2], Material::matte(Vector3::new(0.3, 0.7, 0.9)));
+
+    let region = scene.framebuffer.take_changed_region();
+    let pixels = (region.width() * region.height()) as usize;
+    let mut expected = vec![0; pixels * 4];
+    scene.framebuffer.encode_region(&region, &mut expected);
+
+    let mut colors = Vec::new();
+    scene.framebuffer.copy_region(&region, &mut colors);
+    let encoder = scene.framebuffer.pixel_encoder();
+    scene.framebuffer.set_exposure(0.5);
+    let mut rgba = vec![0; pixels * 4];
+    assert_eq!(colors.len(), pixels);
+    assert_eq!(encoder.encode_region(&region, &colors, &mut rgba), pixels);
+    assert_eq!(rgba, expected);
+}
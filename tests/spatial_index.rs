@@ -0,0 +1,71 @@
+//! Pruebas de la rejilla de búsqueda espacial y de la pirámide de visión
+
+use computer_graphics_v3::matrix::{create_projection_matrix, create_view_matrix};
+use computer_graphics_v3::spatial_index::{Frustum, SpatialIndex};
+use raylib::prelude::*;
+use std::f32::consts::PI;
+
+#[test]
+fn spatial_index_matches_brute_force() {
+    // La rejilla solo descarta candidatos: cada consulta debe dar lo mismo que probar todas las
+    // esferas una a una, incluidas las grandes que no caben en una celda
+    let mut state = 12345u64;
+    let mut random = || {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (state >> 40) as f32 / (1u64 << 24) as f32
+    };
+    let mut index = SpatialIndex::new(2.0);
+    let mut spheres = Vec::new();
+    for id in 0..300usize {
+        let center = Vector3::new(random() * 40.0 - 20.0, random() * 6.0 - 3.0, random() * 40.0 - 20.0);
+        let radius = if id % 50 == 0 { 3.0 + random() * 2.0 } else { 0.05 + random() * 0.4 };
+        index.insert(id, center, radius);
+        spheres.push((center, radius));
+    }
+    let distance = |a: Vector3, b: Vector3| ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt();
+    let sorted = |mut ids: Vec<usize>| {
+        ids.sort_unstable();
+        ids
+    };
+
+    for _ in 0..50 {
+        let point = Vector3::new(random() * 50.0 - 25.0, random() * 10.0 - 5.0, random() * 50.0 - 25.0);
+        let (nearest, nearest_distance) = index.nearest(point, |id| id % 3 != 0).unwrap();
+        let expected = (0..spheres.len())
+            .filter(|id| id % 3 != 0)
+            .map(|id| distance(spheres[id].0, point) - spheres[id].1)
+            .fold(f32::INFINITY, f32::min);
+        assert_eq!(nearest_distance, expected);
+        assert_ne!(nearest.item % 3, 0);
+
+        let radius = random() * 4.0;
+        let overlapping = sorted(index.overlapping(point, radius).iter().map(|entry| entry.item).collect());
+        let expected: Vec<usize> = (0..spheres.len()).filter(|&id| distance(spheres[id].0, point) <= spheres[id].1 + radius).collect();
+        assert_eq!(overlapping, expected);
+
+        let end = Vector3::new(point.x + random() * 8.0 - 4.0, point.y, point.z + random() * 8.0 - 4.0);
+        let along = sorted(index.along_segment(point, end, 0.1).iter().map(|entry| entry.item).collect());
+        let expected: Vec<usize> = (0..spheres.len())
+            .filter(|&id| {
+                (0..=200).any(|step| {
+                    let t = step as f32 / 200.0;
+                    let sample = Vector3::new(point.x + (end.x - point.x) * t, point.y, point.z + (end.z - point.z) * t);
+                    distance(spheres[id].0, sample) <= spheres[id].1 + 0.1 - 0.01
+                })
+            })
+            .collect();
+        assert!(expected.iter().all(|id| along.contains(id)), "{:?} no contiene {:?}", along, expected);
+    }
+
+    // Pirámide de visión mirando a -Z desde z = 10: lo de delante entra, lo de detrás no
+    let view = create_view_matrix(Vector3::new(0.0, 0.0, 10.0), Vector3::zero(), Vector3::new(0.0, 1.0, 0.0));
+    let projection = create_projection_matrix(PI / 3.0, 1.0, 0.1, 100.0);
+    let frustum = Frustum::new(&view, &projection);
+    assert!(frustum.intersects_sphere(Vector3::zero(), 0.5));
+    assert!(!frustum.intersects_sphere(Vector3::new(0.0, 0.0, 12.0), 0.5));
+    assert!(!frustum.intersects_sphere(Vector3::new(30.0, 0.0, 0.0), 0.5));
+    assert!(frustum.intersects_sphere(Vector3::new(7.0, 0.0, 0.0), 1.5));
+    let visible = sorted(index.in_frustum(&frustum).iter().map(|entry| entry.item).collect());
+    let expected: Vec<usize> = (0..spheres.len()).filter(|&id| frustum.intersects_sphere(spheres[id].0, spheres[id].1)).collect();
+    assert_eq!(visible, expected);
+}